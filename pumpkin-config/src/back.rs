@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct BackConfig {
+    /// Can Players use `/back` to return to where they were before teleporting?
+    pub on_teleport: bool,
+    /// Can Players use `/back` to return to where they died?
+    pub on_death: bool,
+}
+
+impl Default for BackConfig {
+    fn default() -> Self {
+        Self {
+            on_teleport: true,
+            on_death: true,
+        }
+    }
+}
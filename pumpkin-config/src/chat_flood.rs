@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+/// Stops Players from flooding the chat
+pub struct ChatFloodConfig {
+    /// Is flood protection enabled?
+    pub enabled: bool,
+    /// How many messages a Player may send within `interval_secs`
+    pub max_messages: usize,
+    /// The time window for `max_messages` in seconds
+    pub interval_secs: u64,
+    /// How often a Player may send the same message in a row
+    pub max_repeats: u32,
+    /// Kick Players who flood the chat, otherwise only their message is dropped
+    pub kick: bool,
+}
+
+impl ChatFloodConfig {
+    /// Returns why the limits can't be used, it can be changed while the server runs so this doesn't panic
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if self.max_messages == 0 || self.max_repeats == 0 {
+            return Err("Chat flood protection must allow at least 1 message");
+        }
+        Ok(())
+    }
+}
+
+impl Default for ChatFloodConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_messages: 10,
+            interval_secs: 10,
+            max_repeats: 3,
+            kick: true,
+        }
+    }
+}
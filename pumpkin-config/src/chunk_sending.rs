@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct ChunkSendingConfig {
+    /// How many chunks a Player is sent per tick at most, the others wait for the next ticks nearest first
+    pub max_chunks_per_tick: u32,
+}
+
+impl Default for ChunkSendingConfig {
+    fn default() -> Self {
+        Self {
+            max_chunks_per_tick: 16,
+        }
+    }
+}
+
+impl ChunkSendingConfig {
+    pub fn validate(&self) {
+        assert!(
+            self.max_chunks_per_tick >= 1,
+            "At least 1 chunk has to be sent per tick"
+        );
+    }
+}
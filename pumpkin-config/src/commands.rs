@@ -1,14 +1,57 @@
+use std::{collections::HashMap, time::Duration};
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize)]
 pub struct CommandsConfig {
     /// Are commands from the Console accepted ?
     pub use_console: bool,
+    /// Seconds a Player has to wait before using the same command again, 0 disables cooldowns
+    pub default_cooldown_secs: u64,
+    /// Cooldowns in seconds for specific commands, these override the default
+    pub cooldowns: HashMap<String, u64>,
+    /// Are options changed with `/config set` also written to features.toml? Otherwise they are reset on restart
+    pub persist_config_changes: bool,
     // TODO: commands...
 }
 
+impl CommandsConfig {
+    /// The cooldown of the command, using its primary name
+    pub fn cooldown(&self, command: &str) -> Duration {
+        Duration::from_secs(
+            self.cooldowns
+                .get(command)
+                .copied()
+                .unwrap_or(self.default_cooldown_secs),
+        )
+    }
+}
+
 impl Default for CommandsConfig {
     fn default() -> Self {
-        Self { use_console: true }
+        Self {
+            use_console: true,
+            default_cooldown_secs: 0,
+            cooldowns: HashMap::new(),
+            persist_config_changes: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::CommandsConfig;
+
+    #[test]
+    fn test_cooldown_override() {
+        let mut config = CommandsConfig {
+            default_cooldown_secs: 2,
+            ..Default::default()
+        };
+        config.cooldowns.insert("tp".to_string(), 10);
+        assert_eq!(config.cooldown("tp"), Duration::from_secs(10));
+        assert_eq!(config.cooldown("weather"), Duration::from_secs(2));
     }
 }
@@ -14,7 +14,8 @@ pub struct CompressionConfig {
 pub struct CompressionInfo {
     /// The compression threshold used when compression is enabled
     pub threshold: u32,
-    /// A value between 0..9
+    /// A value between 0..=9
+    /// 0 = No compression, only the packet framing of compressed packets is used.
     /// 1 = Optimize for the best speed of encoding.
     /// 9 = Optimize for the size of data being encoded.
     pub level: u32,
@@ -29,6 +30,15 @@ impl Default for CompressionInfo {
     }
 }
 
+impl CompressionConfig {
+    pub fn validate(&self) {
+        assert!(
+            self.compression_info.level <= 9,
+            "Compression level must be between 0 and 9"
+        );
+    }
+}
+
 impl Default for CompressionConfig {
     fn default() -> Self {
         Self {
@@ -37,3 +47,21 @@ impl Default for CompressionConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::CompressionConfig;
+
+    #[test]
+    fn test_default_is_valid() {
+        CompressionConfig::default().validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "Compression level must be between 0 and 9")]
+    fn test_level_out_of_range() {
+        let mut config = CompressionConfig::default();
+        config.compression_info.level = 10;
+        config.validate();
+    }
+}
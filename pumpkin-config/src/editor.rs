@@ -0,0 +1,342 @@
+use std::{
+    fmt, fs,
+    io::ErrorKind,
+    path::Path,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use serde::Serialize;
+use toml::{Table, Value};
+
+use crate::{AdvancedConfiguration, LoadConfiguration};
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigKeyError {
+    /// No option with this key exists
+    Unknown(String),
+    /// The key names a whole section instead of a single option
+    NotAnOption(String),
+    /// The option is only read on startup
+    NotReloadable(String),
+    /// The value can't be parsed as the type of the option
+    WrongType { key: String, expected: &'static str },
+    /// The value has the right type but is outside of the option's limits
+    Invalid { key: String, reason: &'static str },
+    /// The changed configuration could not be written
+    Save(String),
+}
+
+impl fmt::Display for ConfigKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unknown(key) => write!(f, "Unknown config option {}", key),
+            Self::NotAnOption(key) => write!(
+                f,
+                "{} is a section or list, only single options can be changed",
+                key
+            ),
+            Self::NotReloadable(key) => write!(
+                f,
+                "{} can't be changed while the server runs, edit features.toml and restart the server instead",
+                key
+            ),
+            Self::WrongType { key, expected } => write!(f, "{} has to be {}", key, expected),
+            Self::Invalid { key, reason } => write!(f, "Invalid value for {}: {}", key, reason),
+            Self::Save(err) => write!(f, "Couldn't save the config: {}", err),
+        }
+    }
+}
+
+fn to_table(config: &impl Serialize) -> Table {
+    Table::try_from(config).expect("The configuration can be represented as TOML")
+}
+
+fn lookup<'a>(table: &'a Table, key: &str) -> Result<&'a Value, ConfigKeyError> {
+    let mut parts = key.split('.');
+    let first = parts.next().unwrap_or_default();
+    let mut value = table
+        .get(first)
+        .ok_or_else(|| ConfigKeyError::Unknown(key.to_string()))?;
+    for part in parts {
+        value = value
+            .get(part)
+            .ok_or_else(|| ConfigKeyError::Unknown(key.to_string()))?;
+    }
+    Ok(value)
+}
+
+fn lookup_mut<'a>(table: &'a mut Table, key: &str) -> Result<&'a mut Value, ConfigKeyError> {
+    let mut parts = key.split('.');
+    let first = parts.next().unwrap_or_default();
+    let mut value = table
+        .get_mut(first)
+        .ok_or_else(|| ConfigKeyError::Unknown(key.to_string()))?;
+    for part in parts {
+        value = value
+            .get_mut(part)
+            .ok_or_else(|| ConfigKeyError::Unknown(key.to_string()))?;
+    }
+    Ok(value)
+}
+
+/// Looks up an option by its dotted key, e.g. `pvp.enabled`
+pub fn get_option(config: &impl Serialize, key: &str) -> Result<Value, ConfigKeyError> {
+    let value = lookup(&to_table(config), key)?.clone();
+    match value {
+        Value::Table(_) | Value::Array(_) => Err(ConfigKeyError::NotAnOption(key.to_string())),
+        value => Ok(value),
+    }
+}
+
+/// Parses `raw` as the type the option currently has and replaces it
+pub fn set_option(table: &mut Table, key: &str, raw: &str) -> Result<(), ConfigKeyError> {
+    let value = lookup_mut(table, key)?;
+    let wrong_type = |expected| ConfigKeyError::WrongType {
+        key: key.to_string(),
+        expected,
+    };
+    *value = match value {
+        Value::Boolean(_) => Value::Boolean(raw.parse().map_err(|_| wrong_type("true or false"))?),
+        Value::Integer(_) => Value::Integer(raw.parse().map_err(|_| wrong_type("a whole number"))?),
+        Value::Float(_) => Value::Float(raw.parse().map_err(|_| wrong_type("a number"))?),
+        Value::String(_) => Value::String(raw.to_string()),
+        Value::Datetime(_) | Value::Table(_) | Value::Array(_) => {
+            return Err(ConfigKeyError::NotAnOption(key.to_string()))
+        }
+    };
+    Ok(())
+}
+
+/// The sections of `features.toml` which can be changed while the server runs, their options are read
+/// from `LIVE_CONFIG` every time they are used. Every other option is only read once, e.g. when binding a socket
+pub const RELOADABLE_SECTIONS: [&str; 7] = [
+    "pvp",
+    "sleeping",
+    "movement",
+    "chat_flood",
+    "homes",
+    "teleport_requests",
+    "messages",
+];
+
+impl AdvancedConfiguration {
+    /// Validates the sections of `RELOADABLE_SECTIONS` which have limits, without panicking
+    pub(crate) fn validate_reloadable(&self) -> Result<(), &'static str> {
+        self.movement.validate()?;
+        self.chat_flood.validate()
+    }
+}
+
+/// Holds the current configuration, changing it swaps in a new one so readers never wait for a writer
+pub struct LiveConfig {
+    current: RwLock<Arc<AdvancedConfiguration>>,
+}
+
+impl LiveConfig {
+    pub fn new(config: &AdvancedConfiguration) -> Self {
+        let copy = to_table(config)
+            .try_into()
+            .expect("The configuration can be read back from TOML");
+        Self {
+            current: RwLock::new(Arc::new(copy)),
+        }
+    }
+
+    /// The current configuration, it does not see changes made after it was loaded
+    pub fn load(&self) -> Arc<AdvancedConfiguration> {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Changes a reloadable option, the server uses the new value right away.
+    ///
+    /// With `persist` the option is also changed in `features.toml`, returns the new value
+    pub fn set(&self, key: &str, raw: &str, persist: bool) -> Result<Value, ConfigKeyError> {
+        let path = persist.then(AdvancedConfiguration::get_path);
+        self.set_with_path(key, raw, path)
+    }
+
+    fn set_with_path(
+        &self,
+        key: &str,
+        raw: &str,
+        path: Option<&Path>,
+    ) -> Result<Value, ConfigKeyError> {
+        // Held until the new configuration is stored, so concurrent changes don't undo each other
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+        let mut table = to_table(&**current);
+        lookup(&table, key)?;
+        let section = key.split('.').next().unwrap_or_default();
+        if !RELOADABLE_SECTIONS.contains(&section) {
+            return Err(ConfigKeyError::NotReloadable(key.to_string()));
+        }
+        set_option(&mut table, key, raw)?;
+        let value = lookup(&table, key)?.clone();
+        // Catches values the type can't hold, e.g. an unknown enum variant or a negative size
+        let changed: AdvancedConfiguration =
+            table.try_into().map_err(|_| ConfigKeyError::WrongType {
+                key: key.to_string(),
+                expected: "a valid value",
+            })?;
+        changed
+            .validate_reloadable()
+            .map_err(|reason| ConfigKeyError::Invalid {
+                key: key.to_string(),
+                reason,
+            })?;
+        if let Some(path) = path {
+            persist_option(path, key, value.clone(), &changed)?;
+        }
+        *current = Arc::new(changed);
+        Ok(value)
+    }
+}
+
+/// Changes a single option in the config file on disk, other options in it are kept as they are.
+///
+/// `fallback` is written when there is no config file yet
+fn persist_option(
+    path: &Path,
+    key: &str,
+    value: Value,
+    fallback: &AdvancedConfiguration,
+) -> Result<(), ConfigKeyError> {
+    let save_error = |err: &dyn fmt::Display| ConfigKeyError::Save(err.to_string());
+    let mut table = match fs::read_to_string(path) {
+        Ok(content) => content.parse::<Table>().map_err(|err| save_error(&err))?,
+        Err(err) if err.kind() == ErrorKind::NotFound => to_table(fallback),
+        Err(err) => return Err(save_error(&err)),
+    };
+    let mut parts = key.rsplit('.');
+    let name = parts.next().unwrap_or_default();
+    let mut section = &mut table;
+    for part in parts.rev() {
+        section = section
+            .entry(part)
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| ConfigKeyError::Save(format!("{} is not a section", part)))?;
+    }
+    section.insert(name.to_string(), value);
+    let content = toml::to_string(&table).map_err(|err| save_error(&err))?;
+    fs::write(path, content).map_err(|err| save_error(&err))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use toml::{Table, Value};
+
+    use super::{get_option, set_option, to_table, ConfigKeyError, LiveConfig};
+    use crate::AdvancedConfiguration;
+
+    #[test]
+    fn test_get() {
+        let config = AdvancedConfiguration::default();
+        assert_eq!(get_option(&config, "pvp.enabled"), Ok(Value::Boolean(true)));
+        assert_eq!(
+            get_option(&config, "pvp.missing"),
+            Err(ConfigKeyError::Unknown("pvp.missing".to_string()))
+        );
+        assert_eq!(
+            get_option(&config, "pvp"),
+            Err(ConfigKeyError::NotAnOption("pvp".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_set() {
+        let mut table = to_table(&AdvancedConfiguration::default());
+        set_option(&mut table, "pvp.enabled", "false").unwrap();
+        set_option(&mut table, "sleeping.players_sleeping_percentage", "50").unwrap();
+        let config: AdvancedConfiguration = table.try_into().unwrap();
+        assert!(!config.pvp.enabled);
+        assert_eq!(config.sleeping.players_sleeping_percentage, 50);
+    }
+
+    #[test]
+    fn test_set_wrong_type() {
+        let mut table = to_table(&AdvancedConfiguration::default());
+        assert_eq!(
+            set_option(&mut table, "pvp.enabled", "maybe"),
+            Err(ConfigKeyError::WrongType {
+                key: "pvp.enabled".to_string(),
+                expected: "true or false"
+            })
+        );
+        assert_eq!(get_option(&table, "pvp.enabled"), Ok(Value::Boolean(true)));
+    }
+
+    #[test]
+    fn test_live_set() {
+        let live = LiveConfig::new(&AdvancedConfiguration::default());
+        let before = live.load();
+        assert_eq!(
+            live.set_with_path("pvp.enabled", "false", None),
+            Ok(Value::Boolean(false))
+        );
+        assert!(!live.load().pvp.enabled);
+        assert!(before.pvp.enabled);
+    }
+
+    #[test]
+    fn test_live_set_rejected() {
+        let live = LiveConfig::new(&AdvancedConfiguration::default());
+        assert_eq!(
+            live.set_with_path("socket.read_buffer_size", "0", None),
+            Err(ConfigKeyError::NotReloadable(
+                "socket.read_buffer_size".to_string()
+            ))
+        );
+        assert_eq!(
+            live.set_with_path("socket.missing", "0", None),
+            Err(ConfigKeyError::Unknown("socket.missing".to_string()))
+        );
+        assert!(matches!(
+            live.set_with_path("movement.max_speed", "-1", None),
+            Err(ConfigKeyError::Invalid { .. })
+        ));
+        assert!(matches!(
+            live.set_with_path("homes.max_homes", "-1", None),
+            Err(ConfigKeyError::WrongType { .. })
+        ));
+        assert_eq!(live.load().movement.max_speed, 10.0);
+        assert_eq!(live.load().homes.max_homes, 3);
+    }
+
+    #[test]
+    fn test_persist_edits_file() {
+        let path = env::temp_dir().join("pumpkin-features-test.toml");
+        let mut on_disk = to_table(&AdvancedConfiguration::default());
+        set_option(&mut on_disk, "messages.server_name", "Edited on disk").unwrap();
+        fs::write(&path, toml::to_string(&on_disk).unwrap()).unwrap();
+
+        let live = LiveConfig::new(&AdvancedConfiguration::default());
+        live.set_with_path("pvp.enabled", "false", Some(&path))
+            .unwrap();
+        live.set_with_path("sleeping.players_sleeping_percentage", "50", Some(&path))
+            .unwrap();
+        assert!(live
+            .set_with_path("movement.max_speed", "0", Some(&path))
+            .is_err());
+
+        let saved: Table = fs::read_to_string(&path).unwrap().parse().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(get_option(&saved, "pvp.enabled"), Ok(Value::Boolean(false)));
+        assert_eq!(
+            get_option(&saved, "sleeping.players_sleeping_percentage"),
+            Ok(Value::Integer(50))
+        );
+        assert_eq!(
+            get_option(&saved, "movement.max_speed"),
+            Ok(Value::Float(10.0))
+        );
+        assert_eq!(
+            get_option(&saved, "messages.server_name"),
+            Ok(Value::String("Edited on disk".to_string()))
+        );
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+/// The RSA key sizes clients can handle during login
+pub const SUPPORTED_KEY_SIZES: [usize; 2] = [1024, 2048];
+
+#[derive(Deserialize, Serialize)]
+pub struct EncryptionConfig {
+    /// The size of the RSA key used for the login encryption in bits, 1024 like vanilla or 2048.
+    /// The key is generated once on startup
+    pub key_size: usize,
+}
+
+impl EncryptionConfig {
+    pub fn validate(&self) {
+        assert!(
+            SUPPORTED_KEY_SIZES.contains(&self.key_size),
+            "The encryption key size must be one of {:?}",
+            SUPPORTED_KEY_SIZES
+        );
+    }
+}
+
+impl Default for EncryptionConfig {
+    fn default() -> Self {
+        Self { key_size: 1024 }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EncryptionConfig;
+
+    #[test]
+    fn test_default_is_valid() {
+        EncryptionConfig::default().validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "The encryption key size must be one of")]
+    fn test_unsupported_key_size() {
+        EncryptionConfig { key_size: 512 }.validate();
+    }
+}
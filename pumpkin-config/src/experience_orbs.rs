@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct ExperienceOrbsConfig {
+    /// How many chunks away Players see experience orbs, capped by `entity_tracking_range`
+    pub tracking_range: u8,
+    /// Orbs dropped this many blocks away from another one are added to it instead, so there are less entities
+    pub merge_radius: f64,
+}
+
+impl Default for ExperienceOrbsConfig {
+    fn default() -> Self {
+        Self {
+            tracking_range: 6,
+            merge_radius: 0.5,
+        }
+    }
+}
+
+impl ExperienceOrbsConfig {
+    pub fn validate(&self) {
+        assert!(
+            self.merge_radius >= 0.0,
+            "Experience orb merge radius must not be negative"
+        );
+    }
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use crate::messages::fill_template;
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct KitItem {
+    /// The item, e.g. `minecraft:bread`
+    pub item: String,
+    pub count: u8,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct FirstJoinConfig {
+    /// The message broadcast when a Player joins for the first time, `{player}` is replaced with their name.
+    /// Empty disables it
+    pub welcome: String,
+    /// The items Players get when they join for the first time
+    pub starter_kit: Vec<KitItem>,
+}
+
+impl FirstJoinConfig {
+    /// Returns the welcome message for the Player, None if it is disabled
+    pub fn welcome_message(&self, player: &str) -> Option<String> {
+        (!self.welcome.is_empty()).then(|| fill_template(&self.welcome, player))
+    }
+}
+
+impl Default for FirstJoinConfig {
+    fn default() -> Self {
+        Self {
+            welcome: "Welcome {player} to the server!".to_string(),
+            starter_kit: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FirstJoinConfig;
+
+    #[test]
+    fn test_welcome_message() {
+        let mut config = FirstJoinConfig::default();
+        assert_eq!(
+            config.welcome_message("Alex").as_deref(),
+            Some("Welcome Alex to the server!")
+        );
+        config.welcome.clear();
+        assert!(config.welcome_message("Alex").is_none());
+    }
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Default)]
+/// Checks the address Clients say they connected to, which blocks direct IP connections and spoofed hosts
+pub struct HandshakeConfig {
+    /// The hostnames Clients may connect with, e.g. `mc.example.com`. Empty allows every address
+    ///
+    /// Behind a proxy this has to include the address the proxy connects to this server with
+    pub allowed_hosts: Vec<String>,
+    /// The protocol version Clients need to log in, 0 uses the one this server implements
+    ///
+    /// Only change this when something in front of the server translates between versions
+    pub protocol_version: u32,
+    /// The Minecraft version Clients with another protocol version are told to use, empty uses the one this server implements
+    pub minecraft_version: String,
+}
+
+impl HandshakeConfig {
+    /// Checks if the `server_address` of a handshake is allowed
+    pub fn allows(&self, server_address: &str) -> bool {
+        if self.allowed_hosts.is_empty() {
+            return true;
+        }
+        let host = declared_host(server_address);
+        self.allowed_hosts
+            .iter()
+            .any(|allowed| allowed.trim_end_matches('.').eq_ignore_ascii_case(host))
+    }
+}
+
+/// The host the Client connected to, without what proxies and mod loaders add to it.
+///
+/// BungeeCord forwarding appends `\0ip\0uuid\0properties` and Forge appends `\0FML\0`,
+/// SRV records may also leave a trailing dot
+fn declared_host(server_address: &str) -> &str {
+    let host = server_address.split('\0').next().unwrap_or_default();
+    host.trim_end_matches('.')
+}
+
+#[cfg(test)]
+mod test {
+    use super::HandshakeConfig;
+
+    fn config() -> HandshakeConfig {
+        HandshakeConfig {
+            allowed_hosts: vec!["mc.example.com".to_string(), "play.example.com".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_allowed_host() {
+        let config = config();
+        assert!(config.allows("mc.example.com"));
+        assert!(config.allows("Play.Example.com."));
+        assert!(config.allows("mc.example.com\0FML3\0"));
+        assert!(config.allows("mc.example.com\0127.0.0.1\0uuid\0[]"));
+    }
+
+    #[test]
+    fn test_disallowed_host() {
+        let config = config();
+        assert!(!config.allows("203.0.113.7"));
+        assert!(!config.allows("evil.example.com"));
+        assert!(!config.allows("mc.example.com.evil.net"));
+        assert!(!config.allows(""));
+    }
+
+    #[test]
+    fn test_empty_allows_everything() {
+        assert!(HandshakeConfig::default().allows("203.0.113.7"));
+    }
+}
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum HardcoreDeathAction {
+    /// Players can only watch the world after dying, like in vanilla
+    Spectator,
+    /// Players get banned once they leave the death screen
+    Ban,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct HardcoreConfig {
+    /// What happens to Players who die while `hardcore` is enabled
+    pub death_action: HardcoreDeathAction,
+}
+
+impl Default for HardcoreConfig {
+    fn default() -> Self {
+        Self {
+            death_action: HardcoreDeathAction::Spectator,
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct HomesConfig {
+    /// How many homes each Player can set with `/sethome`
+    pub max_homes: usize,
+}
+
+impl Default for HomesConfig {
+    fn default() -> Self {
+        Self { max_homes: 3 }
+    }
+}
@@ -0,0 +1,45 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone)]
+/// A JSON API over HTTP for dashboards, with the status of the Server and running commands
+pub struct HttpApiConfig {
+    /// Is the HTTP API enabled?
+    pub enabled: bool,
+    /// The network address and port the API listens on.
+    pub address: SocketAddr,
+    /// Requests have to send this as `Authorization: Bearer <token>`
+    pub token: String,
+    /// How many requests one IP may make within `interval_secs`
+    pub max_requests: usize,
+    /// The time window for `max_requests` in seconds
+    pub interval_secs: u64,
+}
+
+impl HttpApiConfig {
+    pub fn validate(&self) {
+        if self.enabled {
+            assert!(
+                !self.token.is_empty(),
+                "The HTTP API needs a token when it is enabled"
+            );
+            assert!(
+                self.max_requests >= 1,
+                "The HTTP API needs to allow at least 1 request"
+            );
+        }
+    }
+}
+
+impl Default for HttpApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: SocketAddr::new(Ipv4Addr::new(127, 0, 0, 1).into(), 25580),
+            token: "".to_string(),
+            max_requests: 60,
+            interval_secs: 60,
+        }
+    }
+}
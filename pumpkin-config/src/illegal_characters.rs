@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum IllegalCharacterAction {
+    /// Kick the Player, like vanilla does
+    Kick,
+    /// Drop the whole message
+    Drop,
+    /// Remove the illegal characters and formatting codes, then send the rest
+    Sanitize,
+}
+
+#[derive(Deserialize, Serialize)]
+/// Rejects characters which can crash clients, like control characters or broken section sign (`§`) formatting
+pub struct IllegalCharactersConfig {
+    /// Are chat messages and names checked?
+    pub enabled: bool,
+    /// What happens to chat messages with control characters or section signs
+    pub chat_action: IllegalCharacterAction,
+    /// In online mode names must match the vanilla `[A-Za-z0-9_]{3,16}`, otherwise the login is rejected.
+    /// Offline mode only rejects control characters, as proxies may use other names
+    pub strict_names: bool,
+}
+
+impl Default for IllegalCharactersConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            chat_action: IllegalCharacterAction::Kick,
+            strict_names: true,
+        }
+    }
+}
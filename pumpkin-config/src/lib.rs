@@ -9,20 +9,81 @@ use std::{
     sync::LazyLock,
 };
 
+use editor::LiveConfig;
+
 pub mod auth;
+pub mod editor;
 pub mod proxy;
 pub mod resource_pack;
 
 pub use auth::AuthenticationConfig;
+pub use back::BackConfig;
+pub use chat_flood::ChatFloodConfig;
+pub use chunk_sending::ChunkSendingConfig;
 pub use commands::CommandsConfig;
 pub use compression::CompressionConfig;
+pub use encryption::EncryptionConfig;
+pub use experience_orbs::ExperienceOrbsConfig;
+pub use first_join::{FirstJoinConfig, KitItem};
+pub use handshake::HandshakeConfig;
+pub use hardcore::{HardcoreConfig, HardcoreDeathAction};
+pub use homes::HomesConfig;
+pub use http_api::HttpApiConfig;
+pub use illegal_characters::{IllegalCharacterAction, IllegalCharactersConfig};
+pub use login_throttle::LoginThrottleConfig;
+pub use messages::MessagesConfig;
+pub use metrics::MetricsConfig;
+pub use mob_spawning::MobSpawningConfig;
+pub use movement::MovementConfig;
+pub use network_queue::{NetworkQueueConfig, QueueFullPolicy};
+pub use op_bypass::{BypassLimit, OpBypassConfig};
+pub use packet_capture::PacketCaptureConfig;
 pub use pvp::PVPConfig;
-pub use rcon::RCONConfig;
+pub use query::QueryConfig;
+pub use rcon::{RCONConfig, RCONTlsConfig};
+pub use server_links::{ServerLink, ServerLinksConfig};
+pub use sleeping::SleepingConfig;
+pub use socket::SocketConfig;
+pub use status_sample::{StatusSampleConfig, StatusSampleMode};
+pub use teleport_requests::TeleportRequestConfig;
+pub use threads::ThreadsConfig;
+pub use tick_rate::TickRateConfig;
+pub use whitelist::WhitelistConfig;
+pub use world_gen::{FlatLayer, WorldGenConfig, WorldType};
 
+mod back;
+mod chat_flood;
+mod chunk_sending;
 mod commands;
 pub mod compression;
+pub mod encryption;
+mod experience_orbs;
+mod first_join;
+mod handshake;
+mod hardcore;
+mod homes;
+mod http_api;
+mod illegal_characters;
+mod login_throttle;
+mod messages;
+mod metrics;
+mod mob_spawning;
+mod movement;
+mod network_queue;
+mod op_bypass;
+mod packet_capture;
 mod pvp;
+mod query;
 mod rcon;
+mod server_links;
+mod sleeping;
+mod socket;
+mod status_sample;
+mod teleport_requests;
+mod threads;
+mod tick_rate;
+mod whitelist;
+mod world_gen;
 
 use proxy::ProxyConfig;
 use resource_pack::ResourcePackConfig;
@@ -30,6 +91,9 @@ use resource_pack::ResourcePackConfig;
 pub static ADVANCED_CONFIG: LazyLock<AdvancedConfiguration> =
     LazyLock::new(AdvancedConfiguration::load);
 
+/// The configuration as it is right now, the options of `editor::RELOADABLE_SECTIONS` can be changed with `/config set`
+pub static LIVE_CONFIG: LazyLock<LiveConfig> = LazyLock::new(|| LiveConfig::new(&ADVANCED_CONFIG));
+
 pub static BASIC_CONFIG: LazyLock<BasicConfiguration> = LazyLock::new(BasicConfiguration::load);
 
 /// The idea is that Pumpkin should very customizable.
@@ -40,12 +104,41 @@ pub static BASIC_CONFIG: LazyLock<BasicConfiguration> = LazyLock::new(BasicConfi
 #[derive(Deserialize, Serialize, Default)]
 pub struct AdvancedConfiguration {
     pub proxy: ProxyConfig,
+    pub socket: SocketConfig,
+    pub threads: ThreadsConfig,
+    pub tick_rate: TickRateConfig,
+    pub world_gen: WorldGenConfig,
+    pub handshake: HandshakeConfig,
     pub authentication: AuthenticationConfig,
+    pub encryption: EncryptionConfig,
+    pub whitelist: WhitelistConfig,
     pub packet_compression: CompressionConfig,
     pub resource_pack: ResourcePackConfig,
+    pub server_links: ServerLinksConfig,
     pub commands: CommandsConfig,
+    pub op_bypass: OpBypassConfig,
     pub rcon: RCONConfig,
+    pub query: QueryConfig,
     pub pvp: PVPConfig,
+    pub sleeping: SleepingConfig,
+    pub hardcore: HardcoreConfig,
+    pub movement: MovementConfig,
+    pub messages: MessagesConfig,
+    pub first_join: FirstJoinConfig,
+    pub chat_flood: ChatFloodConfig,
+    pub illegal_characters: IllegalCharactersConfig,
+    pub login_throttle: LoginThrottleConfig,
+    pub homes: HomesConfig,
+    pub teleport_requests: TeleportRequestConfig,
+    pub back: BackConfig,
+    pub metrics: MetricsConfig,
+    pub mob_spawning: MobSpawningConfig,
+    pub http_api: HttpApiConfig,
+    pub chunk_sending: ChunkSendingConfig,
+    pub experience_orbs: ExperienceOrbsConfig,
+    pub packet_capture: PacketCaptureConfig,
+    pub status_sample: StatusSampleConfig,
+    pub network_queue: NetworkQueueConfig,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -56,16 +149,26 @@ pub struct BasicConfiguration {
     pub seed: String,
     /// The maximum number of players allowed on the server.
     pub max_players: u32,
+    /// Only Operators can break and place blocks this many blocks around the world spawn, 0 disables it.
+    pub spawn_protection: u32,
     /// The maximum view distance for players.
     pub view_distance: u8,
     /// The maximum simulated view distance.
     pub simulation_distance: u8,
+    /// How far away in chunks Players see other entities, 0 uses the view distance.
+    pub entity_tracking_range: u8,
+    /// How many ticks the moves of entities are collected before they are sent to Players as one update.
+    pub entity_update_interval: u8,
+    /// How many entities, not counting Players, a chunk can hold before spawning more is refused, 0 disables the limit.
+    pub max_entities_per_chunk: u32,
     /// The default game difficulty.
     pub default_difficulty: Difficulty,
     /// Whether the Nether dimension is enabled.
     pub allow_nether: bool,
     /// Whether the server is in hardcore mode.
     pub hardcore: bool,
+    /// Whether Players respawn right away instead of seeing the death screen.
+    pub immediate_respawn: bool,
     /// Whether online mode is enabled. Requires valid Minecraft accounts.
     pub online_mode: bool,
     /// Whether packet encryption is enabled. Required when online mode is enabled.
@@ -74,6 +177,10 @@ pub struct BasicConfiguration {
     pub motd: String,
     /// The default game mode for players.
     pub default_gamemode: GameMode,
+    /// Whether Players get the default game mode on every join instead of only on their first one.
+    pub force_gamemode: bool,
+    /// How often the worlds and players are saved in seconds, 0 disables autosaving.
+    pub autosave_interval_secs: u64,
 }
 
 impl Default for BasicConfiguration {
@@ -82,15 +189,32 @@ impl Default for BasicConfiguration {
             server_address: SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 25565),
             seed: "".to_string(),
             max_players: 100000,
+            spawn_protection: 16,
             view_distance: 10,
             simulation_distance: 10,
+            entity_tracking_range: 0,
+            entity_update_interval: 2,
+            max_entities_per_chunk: 256,
             default_difficulty: Difficulty::Normal,
             allow_nether: true,
             hardcore: false,
+            immediate_respawn: false,
             online_mode: true,
             encryption: true,
             motd: "A Blazing fast Pumpkin Server!".to_string(),
             default_gamemode: GameMode::Survival,
+            force_gamemode: false,
+            autosave_interval_secs: 300,
+        }
+    }
+}
+
+impl BasicConfiguration {
+    /// The entity tracking range in chunks, which is never more than the view distance
+    pub fn entity_tracking_range(&self) -> u8 {
+        match self.entity_tracking_range {
+            0 => self.view_distance,
+            range => range.min(self.view_distance),
         }
     }
 }
@@ -141,7 +265,25 @@ impl LoadConfiguration for AdvancedConfiguration {
     }
 
     fn validate(&self) {
-        self.resource_pack.validate()
+        self.resource_pack.validate();
+        self.packet_compression.validate();
+        self.socket.validate();
+        self.encryption.validate();
+        self.threads.validate();
+        self.world_gen.validate();
+        self.tick_rate.validate();
+        self.op_bypass.validate();
+        self.rcon.validate();
+        self.login_throttle.validate();
+        self.mob_spawning.validate();
+        self.http_api.validate();
+        self.chunk_sending.validate();
+        self.experience_orbs.validate();
+        self.packet_capture.validate();
+        self.network_queue.validate();
+        if let Err(err) = self.validate_reloadable() {
+            panic!("{}", err)
+        }
     }
 }
 
@@ -156,6 +298,10 @@ impl LoadConfiguration for BasicConfiguration {
             self.view_distance <= 32,
             "View distance must be less than 32"
         );
+        assert!(
+            self.entity_update_interval >= 1,
+            "Entity update interval must be at least 1 tick"
+        );
         if self.online_mode {
             assert!(
                 self.encryption,
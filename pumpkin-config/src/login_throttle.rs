@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+/// Slows down repeated logins to the same account, whichever IP they come from
+pub struct LoginThrottleConfig {
+    /// Is login throttling enabled?
+    pub enabled: bool,
+    /// How many logins a name may start within `interval_secs`
+    pub max_attempts: usize,
+    /// The time window for `max_attempts` in seconds
+    pub interval_secs: u64,
+}
+
+impl LoginThrottleConfig {
+    pub fn validate(&self) {
+        if self.enabled {
+            assert!(
+                self.max_attempts >= 1,
+                "Login throttling needs at least 1 attempt"
+            );
+        }
+    }
+}
+
+impl Default for LoginThrottleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_attempts: 3,
+            interval_secs: 10,
+        }
+    }
+}
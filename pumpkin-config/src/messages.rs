@@ -0,0 +1,100 @@
+use pumpkin_core::text::color::translate_color_codes;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct MessagesConfig {
+    /// Should everyone be told when a Player joins or leaves?
+    pub join_leave: bool,
+    /// The message broadcast when a Player joins, `{player}` is replaced with their name
+    pub join: String,
+    /// The message broadcast when a Player leaves, `{player}` is replaced with their name
+    pub leave: String,
+    /// The name of the server, used for `{server}` in the kick message
+    pub server_name: String,
+    /// The screen kicked Players see, `{reason}` is replaced with the reason and `{server}` with `server_name`.
+    ///
+    /// Color codes like `&c` can be used, in the reason too
+    pub kick: String,
+}
+
+impl MessagesConfig {
+    /// Returns the join message for the Player, None if join messages are disabled
+    pub fn join_message(&self, player: &str) -> Option<String> {
+        self.join_leave.then(|| fill_template(&self.join, player))
+    }
+
+    /// Returns the leave message for the Player, None if leave messages are disabled
+    pub fn leave_message(&self, player: &str) -> Option<String> {
+        self.join_leave.then(|| fill_template(&self.leave, player))
+    }
+
+    /// Fills the kick message in with the reason
+    pub fn kick_message(&self, reason: &str) -> String {
+        translate_color_codes(
+            &self
+                .kick
+                .replace("{server}", &self.server_name)
+                .replace("{reason}", reason),
+        )
+    }
+}
+
+pub(crate) fn fill_template(template: &str, player: &str) -> String {
+    template.replace("{player}", player)
+}
+
+impl Default for MessagesConfig {
+    fn default() -> Self {
+        Self {
+            join_leave: true,
+            join: "{player} joined the game".to_string(),
+            leave: "{player} left the game".to_string(),
+            server_name: "Pumpkin".to_string(),
+            kick: "{reason}".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MessagesConfig;
+
+    #[test]
+    fn test_default_messages() {
+        let config = MessagesConfig::default();
+        assert_eq!(
+            config.join_message("Steve").as_deref(),
+            Some("Steve joined the game")
+        );
+        assert_eq!(
+            config.leave_message("Steve").as_deref(),
+            Some("Steve left the game")
+        );
+    }
+
+    #[test]
+    fn test_custom_and_disabled_messages() {
+        let mut config = MessagesConfig {
+            join: "Welcome {player}! ({player})".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.join_message("Alex").as_deref(),
+            Some("Welcome Alex! (Alex)")
+        );
+        config.join_leave = false;
+        assert!(config.join_message("Alex").is_none());
+        assert!(config.leave_message("Alex").is_none());
+    }
+
+    #[test]
+    fn test_kick_message() {
+        let mut config = MessagesConfig::default();
+        assert_eq!(
+            config.kick_message("The server is full"),
+            "The server is full"
+        );
+        config.kick = "&6{server}&r\n{reason}".to_string();
+        assert_eq!(config.kick_message("&cBanned"), "§6Pumpkin§r\n§cBanned");
+    }
+}
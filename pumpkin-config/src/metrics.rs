@@ -0,0 +1,20 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct MetricsConfig {
+    /// Is the Prometheus metrics endpoint enabled?
+    pub enabled: bool,
+    /// The network address and port where metrics can be scraped over HTTP.
+    pub address: SocketAddr,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 9225),
+        }
+    }
+}
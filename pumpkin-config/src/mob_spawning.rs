@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct MobSpawningConfig {
+    /// How many mobs may be within the simulation distance of a Player before no more spawn around them
+    pub mob_cap_per_player: u32,
+    /// How many ticks pass between two spawn cycles, like vanilla's passive mobs every 400 ticks
+    pub spawn_interval_ticks: u32,
+}
+
+impl Default for MobSpawningConfig {
+    fn default() -> Self {
+        Self {
+            mob_cap_per_player: 10,
+            spawn_interval_ticks: 400,
+        }
+    }
+}
+
+impl MobSpawningConfig {
+    pub fn validate(&self) {
+        assert!(
+            self.spawn_interval_ticks >= 1,
+            "Mob spawn interval must be at least 1 tick"
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+/// Checks if Players move faster than the client would allow
+pub struct MovementConfig {
+    /// Are moves checked at all?
+    pub enabled: bool,
+    /// How many blocks a Player may move with a single packet
+    pub max_speed: f64,
+    /// How many blocks a flying Player may move with a single packet
+    pub max_flying_speed: f64,
+    /// How many blocks a Player gliding with an elytra may move with a single packet
+    pub max_elytra_speed: f64,
+}
+
+impl MovementConfig {
+    /// Returns why the limits can't be used, it can be changed while the server runs so this doesn't panic
+    pub fn validate(&self) -> Result<(), &'static str> {
+        let speeds = [self.max_speed, self.max_flying_speed, self.max_elytra_speed];
+        if speeds.iter().all(|speed| speed.is_finite() && *speed > 0.0) {
+            Ok(())
+        } else {
+            Err("Movement speeds must be positive numbers")
+        }
+    }
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        // Like vanilla, which allows squared distances of 100 and 300 for elytras
+        Self {
+            enabled: true,
+            max_speed: 10.0,
+            max_flying_speed: 10.0,
+            max_elytra_speed: 300_f64.sqrt(),
+        }
+    }
+}
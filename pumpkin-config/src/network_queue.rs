@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum QueueFullPolicy {
+    /// Disconnects the Client as soon as its queue is full
+    Disconnect,
+    /// Drops low priority packets like particles, the Client is only disconnected once normal packets don't fit anymore
+    DropLowPriority,
+}
+
+/// Packets which can't be written to a Client right away wait in its queue until the connection is writable again
+#[derive(Deserialize, Serialize)]
+pub struct NetworkQueueConfig {
+    /// How many bytes may wait for a single Client, critical packets like disconnects are always queued
+    pub max_bytes: usize,
+    /// What happens to packets sent while the queue is full
+    pub policy: QueueFullPolicy,
+}
+
+impl Default for NetworkQueueConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 8 * 1024 * 1024,
+            policy: QueueFullPolicy::DropLowPriority,
+        }
+    }
+}
+
+impl NetworkQueueConfig {
+    pub fn validate(&self) {
+        assert!(
+            self.max_bytes >= 64 * 1024,
+            "The network queue has to hold at least 64KiB"
+        );
+    }
+}
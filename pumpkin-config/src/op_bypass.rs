@@ -0,0 +1,100 @@
+use serde::{Deserialize, Serialize};
+
+/// A limit which Operators with a high enough permission level are not affected by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BypassLimit {
+    /// Breaking and placing blocks near the world spawn
+    SpawnProtection,
+    /// Joining when the server is full
+    MaxPlayers,
+    /// Waiting before using the same command again
+    CommandCooldowns,
+    /// Sending too many or repeated chat messages
+    ChatFlood,
+}
+
+#[derive(Deserialize, Serialize)]
+/// The permission level an Operator needs to bypass each limit, a level above 4 means nobody bypasses it
+pub struct OpBypassConfig {
+    pub spawn_protection: u8,
+    /// Operators with `bypassesPlayerLimit` in `ops.json` can always join
+    pub max_players: u8,
+    pub command_cooldowns: u8,
+    pub chat_flood: u8,
+}
+
+impl OpBypassConfig {
+    /// The permission level needed to bypass the limit
+    pub const fn required_level(&self, limit: BypassLimit) -> u8 {
+        match limit {
+            BypassLimit::SpawnProtection => self.spawn_protection,
+            BypassLimit::MaxPlayers => self.max_players,
+            BypassLimit::CommandCooldowns => self.command_cooldowns,
+            BypassLimit::ChatFlood => self.chat_flood,
+        }
+    }
+
+    /// Checks if a Player with the permission level is not affected by the limit
+    pub const fn can_bypass(&self, limit: BypassLimit, permission_lvl: u8) -> bool {
+        permission_lvl >= self.required_level(limit)
+    }
+
+    pub fn validate(&self) {
+        for level in [
+            self.spawn_protection,
+            self.max_players,
+            self.command_cooldowns,
+            self.chat_flood,
+        ] {
+            // Every Player has level 0, so it would disable the limit
+            assert!(level >= 1, "Op bypass levels must be at least 1");
+        }
+    }
+}
+
+impl Default for OpBypassConfig {
+    fn default() -> Self {
+        Self {
+            spawn_protection: 1,
+            max_players: 4,
+            command_cooldowns: 2,
+            chat_flood: 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BypassLimit, OpBypassConfig};
+
+    const LIMITS: [BypassLimit; 4] = [
+        BypassLimit::SpawnProtection,
+        BypassLimit::MaxPlayers,
+        BypassLimit::CommandCooldowns,
+        BypassLimit::ChatFlood,
+    ];
+
+    #[test]
+    fn test_levels() {
+        let config = OpBypassConfig::default();
+        config.validate();
+        for limit in LIMITS {
+            assert!(config.can_bypass(limit, 4), "{:?}", limit);
+            assert!(!config.can_bypass(limit, 0), "{:?}", limit);
+        }
+    }
+
+    #[test]
+    fn test_per_limit_level() {
+        let config = OpBypassConfig {
+            command_cooldowns: 3,
+            chat_flood: 5,
+            ..Default::default()
+        };
+        assert!(config.can_bypass(BypassLimit::CommandCooldowns, 3));
+        assert!(!config.can_bypass(BypassLimit::CommandCooldowns, 2));
+        // Nobody bypasses a level above 4
+        assert!(!config.can_bypass(BypassLimit::ChatFlood, 4));
+        assert!(config.can_bypass(BypassLimit::SpawnProtection, 1));
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/// Debug only, logs every decoded packet so protocol issues can be reproduced
+#[derive(Deserialize, Serialize)]
+pub struct PacketCaptureConfig {
+    /// Is every inbound and outbound packet written into the capture file?
+    pub enabled: bool,
+    /// The file the packets are appended to, inbound packets keep their payload so they can be replayed
+    pub path: String,
+}
+
+impl Default for PacketCaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: "packet_capture.log".to_string(),
+        }
+    }
+}
+
+impl PacketCaptureConfig {
+    pub fn validate(&self) {
+        assert!(
+            !self.enabled || !self.path.is_empty(),
+            "The packet capture needs a file to write into"
+        );
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize, Serialize)]
 pub struct PVPConfig {
-    /// Is PVP enabled ?
+    /// Is PVP enabled ? Can be overridden per world
     pub enabled: bool,
     /// Do we want to have the Red hurt animation & fov bobbing
     pub hurt_animation: bool,
@@ -0,0 +1,20 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct QueryConfig {
+    /// Is the UDP Query protocol enabled?
+    pub enabled: bool,
+    /// The network address and UDP port where query requests are answered.
+    pub address: SocketAddr,
+}
+
+impl Default for QueryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 25565),
+        }
+    }
+}
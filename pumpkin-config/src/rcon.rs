@@ -13,6 +13,30 @@ pub struct RCONConfig {
     /// The maximum number of concurrent RCON connections allowed.
     /// If 0 there is no limit
     pub max_connections: u32,
+    /// Encrypts RCON connections, clients then have to connect with TLS
+    pub tls: RCONTlsConfig,
+    /// The permission level of commands run over RCON, e.g. 3 keeps RCON from using `/stop`
+    pub permission_level: i32,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct RCONTlsConfig {
+    /// Is TLS Enabled? Without it the password and commands are sent in plaintext
+    pub enabled: bool,
+    /// PEM file with the certificate chain, the server certificate first
+    pub cert_path: String,
+    /// PEM file with the private key of the certificate
+    pub key_path: String,
+}
+
+impl Default for RCONTlsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cert_path: "rcon_cert.pem".to_string(),
+            key_path: "rcon_key.pem".to_string(),
+        }
+    }
 }
 
 impl Default for RCONConfig {
@@ -22,6 +46,17 @@ impl Default for RCONConfig {
             address: SocketAddr::new(Ipv4Addr::new(0, 0, 0, 0).into(), 25575),
             password: "".to_string(),
             max_connections: 0,
+            tls: RCONTlsConfig::default(),
+            permission_level: 4,
         }
     }
 }
+
+impl RCONConfig {
+    pub fn validate(&self) {
+        assert!(
+            (0..=4).contains(&self.permission_level),
+            "RCON permission level must be between 0 and 4"
+        );
+    }
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+/// Links shown to 1.21+ clients in the pause menu
+pub struct ServerLinksConfig {
+    pub enabled: bool,
+    pub links: Vec<ServerLink>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ServerLink {
+    /// Either a vanilla label like `website` or `bug_report`, which the client translates, or custom text
+    pub label: String,
+    /// Must be a http or https URL, invalid links are not sent
+    pub url: String,
+}
+
+impl Default for ServerLinksConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            links: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct SleepingConfig {
+    /// How many of the Players in a world have to sleep to skip the night, in percent.
+    /// Above 100 the night is never skipped. Can be overridden per world
+    pub players_sleeping_percentage: u8,
+}
+
+impl Default for SleepingConfig {
+    fn default() -> Self {
+        Self {
+            players_sleeping_percentage: 100,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+/// TCP options for the listener and the connections of Players
+pub struct SocketConfig {
+    /// The size of the send buffer (`SO_SNDBUF`) in bytes, 0 keeps the OS default
+    pub send_buffer_size: usize,
+    /// The size of the receive buffer (`SO_RCVBUF`) in bytes, 0 keeps the OS default
+    pub receive_buffer_size: usize,
+    /// Should the OS send keepalive probes (`SO_KEEPALIVE`) on idle connections?
+    pub keepalive: bool,
+    /// How many connections may wait to be accepted
+    pub backlog: i32,
+    /// How many bytes are read from a connection at once
+    pub read_buffer_size: usize,
+    /// The read buffer grows up to this size in bytes when reads keep filling it
+    pub max_read_buffer_size: usize,
+}
+
+impl SocketConfig {
+    pub fn validate(&self) {
+        assert!(
+            self.read_buffer_size > 0,
+            "The read buffer size must be at least 1 byte"
+        );
+        assert!(
+            self.max_read_buffer_size >= self.read_buffer_size,
+            "The maximum read buffer size must not be smaller than the read buffer size"
+        );
+    }
+}
+
+impl Default for SocketConfig {
+    fn default() -> Self {
+        Self {
+            send_buffer_size: 0,
+            receive_buffer_size: 0,
+            keepalive: false,
+            backlog: 1024,
+            read_buffer_size: 4096,
+            max_read_buffer_size: 64 * 1024,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SocketConfig;
+
+    #[test]
+    fn test_default_is_valid() {
+        SocketConfig::default().validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "The read buffer size must be at least 1 byte")]
+    fn test_empty_read_buffer() {
+        let mut config = SocketConfig::default();
+        config.read_buffer_size = 0;
+        config.validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "The maximum read buffer size must not be smaller")]
+    fn test_max_below_size() {
+        let mut config = SocketConfig::default();
+        config.max_read_buffer_size = config.read_buffer_size - 1;
+        config.validate();
+    }
+}
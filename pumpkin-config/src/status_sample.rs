@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusSampleMode {
+    /// Shows the Players who are online, like vanilla
+    Real,
+    /// Shows the names from `custom` instead of the online Players
+    Custom,
+    /// Hides the Player count and sample, clients show `???` instead
+    Hidden,
+}
+
+/// The Players shown when hovering over the Player count in the server list
+#[derive(Deserialize, Serialize)]
+pub struct StatusSampleConfig {
+    pub mode: StatusSampleMode,
+    /// How many online Players are shown at most in `real` mode, vanilla shows 12
+    pub max_players: usize,
+    /// The lines shown in `custom` mode
+    pub custom: Vec<String>,
+    /// An extra line shown below the sample, nothing is added when empty
+    pub hover_line: String,
+}
+
+impl Default for StatusSampleConfig {
+    fn default() -> Self {
+        Self {
+            mode: StatusSampleMode::Real,
+            max_players: 12,
+            custom: Vec::new(),
+            hover_line: String::new(),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+pub struct TeleportRequestConfig {
+    /// Seconds until a `/tpa` request can no longer be accepted
+    pub expiry_secs: u64,
+}
+
+impl Default for TeleportRequestConfig {
+    fn default() -> Self {
+        Self { expiry_secs: 120 }
+    }
+}
@@ -0,0 +1,61 @@
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+/// How many threads the Server uses, both default to the number of CPUs
+pub struct ThreadsConfig {
+    /// The worker threads of the tokio runtime which handles networking and ticking
+    pub tokio_worker_threads: usize,
+    /// The threads of the global rayon pool which reads and generates chunks
+    pub rayon_threads: usize,
+}
+
+impl ThreadsConfig {
+    pub fn validate(&self) {
+        assert!(
+            self.tokio_worker_threads > 0,
+            "There must be at least 1 tokio worker thread"
+        );
+        assert!(
+            self.rayon_threads > 0,
+            "There must be at least 1 rayon thread"
+        );
+    }
+}
+
+impl Default for ThreadsConfig {
+    fn default() -> Self {
+        let cpus = thread::available_parallelism().map_or(1, |cpus| cpus.get());
+        Self {
+            tokio_worker_threads: cpus,
+            rayon_threads: cpus,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ThreadsConfig;
+
+    #[test]
+    fn test_default_is_valid() {
+        ThreadsConfig::default().validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "There must be at least 1 tokio worker thread")]
+    fn test_zero_tokio_threads() {
+        let mut config = ThreadsConfig::default();
+        config.tokio_worker_threads = 0;
+        config.validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "There must be at least 1 rayon thread")]
+    fn test_zero_rayon_threads() {
+        let mut config = ThreadsConfig::default();
+        config.rayon_threads = 0;
+        config.validate();
+    }
+}
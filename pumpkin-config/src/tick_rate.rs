@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+/// How many times per second the worlds tick, e.g. to let a lobby tick slower than the game worlds
+pub struct TickRateConfig {
+    /// The rate of every world which has no rate of its own
+    pub default: f32,
+    /// Rates for specific worlds, keyed by their dimension like `minecraft:the_nether`
+    pub worlds: HashMap<String, f32>,
+}
+
+impl TickRateConfig {
+    /// The tick rate of the world
+    pub fn rate(&self, world: &str) -> f32 {
+        self.worlds.get(world).copied().unwrap_or(self.default)
+    }
+
+    pub fn validate(&self) {
+        for rate in self.worlds.values().chain([&self.default]) {
+            assert!(
+                (1.0..=10_000.0).contains(rate),
+                "Tick rates must be between 1 and 10000"
+            );
+        }
+    }
+}
+
+impl Default for TickRateConfig {
+    fn default() -> Self {
+        Self {
+            default: 20.0,
+            worlds: HashMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TickRateConfig;
+
+    #[test]
+    fn test_world_rate() {
+        let mut config = TickRateConfig::default();
+        config.validate();
+        config.worlds.insert("minecraft:the_end".to_string(), 5.0);
+        assert_eq!(config.rate("minecraft:the_end"), 5.0);
+        assert_eq!(config.rate("minecraft:overworld"), 20.0);
+    }
+}
@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Serialize)]
+/// Only Players in `whitelist.json` may join
+pub struct WhitelistConfig {
+    /// Is the whitelist enabled on startup? It can be changed with `/whitelist on|off`
+    pub enabled: bool,
+    /// Kick online Players who are not whitelisted when the whitelist is enabled or reloaded
+    pub enforce: bool,
+}
+
+impl Default for WhitelistConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            enforce: true,
+        }
+    }
+}
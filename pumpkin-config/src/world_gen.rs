@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+/// The height of the world, layers of flat worlds have to fit into it
+const WORLD_HEIGHT: u32 = 384;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum WorldType {
+    /// The regular terrain generator
+    #[default]
+    Default,
+    /// Layers of blocks, configured by `flat_layers`
+    Flat,
+    /// Nothing but air, except for a small platform to spawn on
+    Void,
+}
+
+#[derive(Deserialize, Serialize, Clone, PartialEq, Eq, Debug)]
+pub struct FlatLayer {
+    /// The block the layer consists of, e.g. `minecraft:stone`
+    pub block: String,
+    /// How many blocks thick the layer is
+    pub height: u16,
+}
+
+impl FlatLayer {
+    pub fn new(block: &str, height: u16) -> Self {
+        Self {
+            block: block.to_string(),
+            height,
+        }
+    }
+}
+
+/// Only affects chunks which are not generated yet, existing chunks are always loaded from the world folder
+#[derive(Deserialize, Serialize)]
+pub struct WorldGenConfig {
+    pub world_type: WorldType,
+    /// The layers of flat worlds, from the bottom of the world upwards
+    pub flat_layers: Vec<FlatLayer>,
+}
+
+impl WorldGenConfig {
+    pub fn validate(&self) {
+        assert!(
+            self.flat_layers.iter().all(|layer| layer.height > 0),
+            "Flat layers must be at least 1 block thick"
+        );
+        assert!(
+            self.flat_layers
+                .iter()
+                .map(|layer| layer.height as u32)
+                .sum::<u32>()
+                <= WORLD_HEIGHT,
+            "Flat layers must not be higher than the world"
+        );
+    }
+}
+
+impl Default for WorldGenConfig {
+    fn default() -> Self {
+        Self {
+            world_type: WorldType::Default,
+            flat_layers: vec![
+                FlatLayer::new("minecraft:bedrock", 1),
+                FlatLayer::new("minecraft:dirt", 2),
+                FlatLayer::new("minecraft:grass_block", 1),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{FlatLayer, WorldGenConfig};
+
+    #[test]
+    fn test_default_is_valid() {
+        WorldGenConfig::default().validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "Flat layers must be at least 1 block thick")]
+    fn test_empty_layer() {
+        let mut config = WorldGenConfig::default();
+        config
+            .flat_layers
+            .push(FlatLayer::new("minecraft:stone", 0));
+        config.validate();
+    }
+
+    #[test]
+    #[should_panic(expected = "Flat layers must not be higher than the world")]
+    fn test_too_high() {
+        let mut config = WorldGenConfig::default();
+        config
+            .flat_layers
+            .push(FlatLayer::new("minecraft:stone", 384));
+        config.validate();
+    }
+}
@@ -0,0 +1,42 @@
+use std::str::FromStr;
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseDifficultyError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, FromPrimitive, ToPrimitive)]
+pub enum Difficulty {
+    Peaceful,
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl FromStr for Difficulty {
+    type Err = ParseDifficultyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "peaceful" => Ok(Self::Peaceful),
+            "easy" => Ok(Self::Easy),
+            "normal" => Ok(Self::Normal),
+            "hard" => Ok(Self::Hard),
+            _ => Err(ParseDifficultyError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Difficulty;
+
+    #[test]
+    fn test_parse_difficulty() {
+        assert_eq!("peaceful".parse(), Ok(Difficulty::Peaceful));
+        assert_eq!("hard".parse(), Ok(Difficulty::Hard));
+        assert!("impossible".parse::<Difficulty>().is_err());
+        assert_eq!(Difficulty::Normal as u8, 2);
+    }
+}
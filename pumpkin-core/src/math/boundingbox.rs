@@ -1,5 +1,6 @@
 use super::{position::WorldPosition, vector3::Vector3};
 
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct BoundingBox {
     pub min_x: f64,
     pub min_y: f64,
@@ -33,10 +34,166 @@ impl BoundingBox {
         }
     }
 
+    /// Creates the box of an Entity standing at `position`, Entities are centered on the x and z axis
+    pub fn from_entity_pos(position: Vector3<f64>, width: f64, height: f64) -> Self {
+        let half_width = width / 2.0;
+        Self {
+            min_x: position.x - half_width,
+            min_y: position.y,
+            min_z: position.z - half_width,
+            max_x: position.x + half_width,
+            max_y: position.y + height,
+            max_z: position.z + half_width,
+        }
+    }
+
     pub fn squared_magnitude(&self, pos: Vector3<f64>) -> f64 {
         let d = f64::max(f64::max(self.min_x - pos.x, pos.x - self.max_x), 0.0);
         let e = f64::max(f64::max(self.min_y - pos.y, pos.y - self.max_y), 0.0);
         let f = f64::max(f64::max(self.min_z - pos.z, pos.z - self.max_z), 0.0);
         super::squared_magnitude(d, e, f)
     }
+
+    /// The box grown by the amounts on both sides of each axis
+    pub fn expand(&self, x: f64, y: f64, z: f64) -> Self {
+        Self {
+            min_x: self.min_x - x,
+            min_y: self.min_y - y,
+            min_z: self.min_z - z,
+            max_x: self.max_x + x,
+            max_y: self.max_y + y,
+            max_z: self.max_z + z,
+        }
+    }
+
+    /// Whether the boxes overlap, boxes which only touch don't
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.min_x < other.max_x
+            && self.max_x > other.min_x
+            && self.min_y < other.max_y
+            && self.max_y > other.min_y
+            && self.min_z < other.max_z
+            && self.max_z > other.min_z
+    }
+
+    /// Whether the point is inside the box, points on its faces count as inside
+    pub fn contains(&self, point: Vector3<f64>) -> bool {
+        (self.min_x..=self.max_x).contains(&point.x)
+            && (self.min_y..=self.max_y).contains(&point.y)
+            && (self.min_z..=self.max_z).contains(&point.z)
+    }
+
+    /// Where a ray starting at `origin` enters the box, as a multiple of `direction`.
+    ///
+    /// Returns `None` if the ray misses the box or only hits it further than `max_distance` times `direction`.
+    /// A ray starting inside the box hits it at 0
+    pub fn ray_intersection(
+        &self,
+        origin: Vector3<f64>,
+        direction: Vector3<f64>,
+        max_distance: f64,
+    ) -> Option<f64> {
+        let axes = [
+            (origin.x, direction.x, self.min_x, self.max_x),
+            (origin.y, direction.y, self.min_y, self.max_y),
+            (origin.z, direction.z, self.min_z, self.max_z),
+        ];
+        let (mut enter, mut exit) = (0.0, max_distance);
+        for (origin, direction, min, max) in axes {
+            if direction == 0.0 {
+                // Parallel to these faces, so the ray has to run between them
+                if origin < min || origin > max {
+                    return None;
+                }
+                continue;
+            }
+            let (near, far) = if direction > 0.0 {
+                ((min - origin) / direction, (max - origin) / direction)
+            } else {
+                ((max - origin) / direction, (min - origin) / direction)
+            };
+            enter = f64::max(enter, near);
+            exit = f64::min(exit, far);
+            if enter > exit {
+                return None;
+            }
+        }
+        Some(enter)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::math::vector3::Vector3;
+
+    use super::BoundingBox;
+
+    #[test]
+    fn test_entity_reach() {
+        let target = BoundingBox::from_entity_pos(Vector3::new(0.0, 64.0, 0.0), 0.6, 1.8);
+        // Standing right next to the target
+        let eye = Vector3::new(1.0, 65.62, 0.0);
+        assert!(target.squared_magnitude(eye) < 3.0 * 3.0);
+        // 10 blocks away is too far for an attack
+        let eye = Vector3::new(10.3, 65.62, 0.0);
+        assert!(target.squared_magnitude(eye) > 4.0 * 4.0);
+        // Inside the box
+        let eye = Vector3::new(0.0, 65.0, 0.0);
+        assert_eq!(target.squared_magnitude(eye), 0.0);
+    }
+
+    #[test]
+    fn test_contains() {
+        let target = BoundingBox::from_entity_pos(Vector3::new(0.0, 64.0, 0.0), 0.6, 1.8);
+        assert!(target.contains(Vector3::new(0.0, 65.0, 0.0)));
+        assert!(target.contains(Vector3::new(0.3, 64.0, -0.3)));
+        assert!(!target.contains(Vector3::new(0.0, 65.9, 0.0)));
+        assert!(!target.contains(Vector3::new(0.31, 65.0, 0.0)));
+    }
+
+    #[test]
+    fn test_intersects() {
+        let target = BoundingBox::from_entity_pos(Vector3::new(0.0, 64.0, 0.0), 0.6, 1.8);
+        let item = BoundingBox::from_entity_pos(Vector3::new(1.0, 64.0, 0.0), 0.25, 0.25);
+        assert!(!target.intersects(&item));
+        assert!(target.expand(1.0, 0.5, 1.0).intersects(&item));
+        // Touching isn't enough
+        let touching = BoundingBox::from_entity_pos(Vector3::new(0.0, 65.8, 0.0), 0.6, 1.8);
+        assert!(!target.intersects(&touching));
+    }
+
+    #[test]
+    fn test_ray_intersection() {
+        let target = BoundingBox::from_entity_pos(Vector3::new(0.0, 64.0, 0.0), 0.6, 1.8);
+        let eye = Vector3::new(-3.0, 65.62, 0.0);
+        // Looking straight at the target hits its face 2.7 blocks away
+        let hit = target.ray_intersection(eye, Vector3::new(1.0, 0.0, 0.0), 3.0);
+        assert!((hit.unwrap() - 2.7).abs() < 1.0E-9);
+        // Too short to reach it
+        assert_eq!(
+            target.ray_intersection(eye, Vector3::new(1.0, 0.0, 0.0), 2.0),
+            None
+        );
+        // Looking away or past it
+        assert_eq!(
+            target.ray_intersection(eye, Vector3::new(-1.0, 0.0, 0.0), 10.0),
+            None
+        );
+        assert_eq!(
+            target.ray_intersection(eye, Vector3::new(1.0, 0.0, 1.0).normalize(), 10.0),
+            None
+        );
+        // Looking down at its feet
+        let hit = target.ray_intersection(eye, Vector3::new(1.0, -0.5, 0.0), 10.0);
+        assert!((hit.unwrap() - 2.7).abs() < 1.0E-9);
+        // From inside
+        assert_eq!(
+            target.ray_intersection(
+                Vector3::new(0.0, 65.0, 0.0),
+                Vector3::new(0.0, 1.0, 0.0),
+                1.0
+            ),
+            Some(0.0)
+        );
+    }
 }
@@ -2,19 +2,25 @@ use serde::{Deserialize, Serialize};
 
 use super::vector3::Vector3;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 /// Aka Block Position
 pub struct WorldPosition(pub Vector3<i32>);
 
+impl WorldPosition {
+    /// Packs the position into a single long, like the protocol sends it
+    pub fn as_long(&self) -> i64 {
+        ((self.0.x as i64 & 0x3FFFFFF) << 38)
+            | ((self.0.z as i64 & 0x3FFFFFF) << 12)
+            | (self.0.y as i64 & 0xFFF)
+    }
+}
+
 impl Serialize for WorldPosition {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
-        let long = ((self.0.x as i64 & 0x3FFFFFF) << 38)
-            | ((self.0.z as i64 & 0x3FFFFFF) << 12)
-            | (self.0.y as i64 & 0xFFF);
-        serializer.serialize_i64(long)
+        serializer.serialize_i64(self.as_long())
     }
 }
 
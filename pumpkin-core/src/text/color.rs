@@ -61,3 +61,33 @@ pub enum NamedColor {
     Yellow,
     White,
 }
+
+/// Replaces `&` color and format codes like `&c` with the `§` codes the client renders
+pub fn translate_color_codes(text: &str) -> String {
+    let mut translated = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match chars.peek() {
+            Some(code)
+                if c == '&' && "0123456789abcdefklmnor".contains(code.to_ascii_lowercase()) =>
+            {
+                translated.push('§');
+            }
+            _ => translated.push(c),
+        }
+    }
+    translated
+}
+
+#[cfg(test)]
+mod test {
+    use super::translate_color_codes;
+
+    #[test]
+    fn test_translate_color_codes() {
+        assert_eq!(translate_color_codes("&cBanned &lnow"), "§cBanned §lnow");
+        assert_eq!(translate_color_codes("&C&R"), "§C§R");
+        // Anything which isn't a code stays as it is
+        assert_eq!(translate_color_codes("Rock & Roll &z&"), "Rock & Roll &z&");
+    }
+}
@@ -140,25 +140,32 @@ impl<'a> TextComponent<'a> {
     }
 
     pub fn encode(&self) -> Vec<u8> {
-        // TODO: Somehow fix this ugly mess
-        #[derive(serde::Serialize)]
-        #[serde(rename_all = "camelCase")]
-        struct TempStruct<'a> {
-            #[serde(flatten)]
-            text: &'a TextContent<'a>,
-            #[serde(flatten)]
-            style: &'a Style<'a>,
-        }
-        let astruct = TempStruct {
+        fastnbt::to_bytes_with_opts(&self.flattened(), SerOpts::network_nbt()).unwrap()
+    }
+
+    /// The JSON form of the component, which some packets like the Login disconnect use instead of NBT
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(&self.flattened()).unwrap()
+    }
+
+    fn flattened(&self) -> FlatTextComponent<'_> {
+        FlatTextComponent {
             text: &self.content,
             style: &self.style,
-        };
-        // dbg!(&serde_json::to_string(&astruct));
-
-        fastnbt::to_bytes_with_opts(&astruct, SerOpts::network_nbt()).unwrap()
+        }
     }
 }
 
+// TODO: Somehow fix this ugly mess
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FlatTextComponent<'a> {
+    #[serde(flatten)]
+    text: &'a TextContent<'a>,
+    #[serde(flatten)]
+    style: &'a Style<'a>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum TextContent<'a> {
@@ -180,3 +187,25 @@ pub enum TextContent<'a> {
     /// https://minecraft.fandom.com/wiki/Controls#Configurable_controls
     Keybind { keybind: Cow<'a, str> },
 }
+
+#[cfg(test)]
+mod test {
+    use super::{color::NamedColor, TextComponent, TextContent};
+
+    #[test]
+    fn test_json_round_trip() {
+        let json = TextComponent::text("You are banned").to_json();
+        // Clients reject unset fields sent as null
+        assert_eq!(json, r#"{"text":"You are banned"}"#);
+
+        let json = TextComponent::text("Kicked")
+            .color_named(NamedColor::Red)
+            .to_json();
+        let component: TextComponent = serde_json::from_str(&json).unwrap();
+        match component.content {
+            TextContent::Text { text } => assert_eq!(text, "Kicked"),
+            content => panic!("Unexpected content {:?}", content),
+        }
+        assert!(component.style.color.is_some());
+    }
+}
@@ -1,4 +1,4 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
 use super::{
     click::ClickEvent,
@@ -9,24 +9,45 @@ use super::{
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 pub struct Style<'a> {
     /// Changes the color to render the content
-    pub color: Option<Color>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<Color>,
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bool_or_byte",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub bold: Option<u8>,
     /// Whether to render the content in italic.
     /// Keep in mind that booleans are represented as bytes in nbt
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bool_or_byte",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub italic: Option<u8>,
     /// Whether to render the content in underlined.
     /// Keep in mind that booleans are represented as bytes in nbt
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bool_or_byte",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub underlined: Option<u8>,
     /// Whether to render the content in strikethrough.
     /// Keep in mind that booleans are represented as bytes in nbt
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bool_or_byte",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub strikethrough: Option<u8>,
     /// Whether to render the content in obfuscated.
     /// Keep in mind that booleans are represented as bytes in nbt
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        deserialize_with = "deserialize_bool_or_byte",
+        skip_serializing_if = "Option::is_none"
+    )]
     pub obfuscated: Option<u8>,
     /// When the text is shift-clicked by a player, this string is inserted in their chat input. It does not overwrite any existing text the player was writing. This only works in chat messages
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -98,3 +119,22 @@ impl<'a> Style<'a> {
         self
     }
 }
+
+/// Booleans are bytes in NBT, but JSON text components use `true` and `false`
+fn deserialize_bool_or_byte<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u8>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrByte {
+        Bool(bool),
+        Byte(u8),
+    }
+
+    Ok(
+        Option::<BoolOrByte>::deserialize(deserializer)?.map(|value| match value {
+            BoolOrByte::Bool(value) => u8::from(value),
+            BoolOrByte::Byte(value) => value,
+        }),
+    )
+}
@@ -0,0 +1,257 @@
+use std::{collections::HashMap, str::FromStr};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseEffectTypeError;
+
+/// All status effects, the discriminant is the id in the `minecraft:mob_effect` registry
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(i32)]
+pub enum EffectType {
+    Speed = 0,
+    Slowness,
+    Haste,
+    MiningFatigue,
+    Strength,
+    InstantHealth,
+    InstantDamage,
+    JumpBoost,
+    Nausea,
+    Regeneration,
+    Resistance,
+    FireResistance,
+    WaterBreathing,
+    Invisibility,
+    Blindness,
+    NightVision,
+    Hunger,
+    Weakness,
+    Poison,
+    Wither,
+    HealthBoost,
+    Absorption,
+    Saturation,
+    Glowing,
+    Levitation,
+    Luck,
+    Unluck,
+    SlowFalling,
+    ConduitPower,
+    DolphinsGrace,
+    BadOmen,
+    HeroOfTheVillage,
+    Darkness,
+    TrialOmen,
+    RaidOmen,
+    WindCharged,
+    Weaving,
+    Oozing,
+    Infested,
+}
+
+impl FromStr for EffectType {
+    type Err = ParseEffectTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("minecraft:").unwrap_or(s) {
+            "speed" => Ok(Self::Speed),
+            "slowness" => Ok(Self::Slowness),
+            "haste" => Ok(Self::Haste),
+            "mining_fatigue" => Ok(Self::MiningFatigue),
+            "strength" => Ok(Self::Strength),
+            "instant_health" => Ok(Self::InstantHealth),
+            "instant_damage" => Ok(Self::InstantDamage),
+            "jump_boost" => Ok(Self::JumpBoost),
+            "nausea" => Ok(Self::Nausea),
+            "regeneration" => Ok(Self::Regeneration),
+            "resistance" => Ok(Self::Resistance),
+            "fire_resistance" => Ok(Self::FireResistance),
+            "water_breathing" => Ok(Self::WaterBreathing),
+            "invisibility" => Ok(Self::Invisibility),
+            "blindness" => Ok(Self::Blindness),
+            "night_vision" => Ok(Self::NightVision),
+            "hunger" => Ok(Self::Hunger),
+            "weakness" => Ok(Self::Weakness),
+            "poison" => Ok(Self::Poison),
+            "wither" => Ok(Self::Wither),
+            "health_boost" => Ok(Self::HealthBoost),
+            "absorption" => Ok(Self::Absorption),
+            "saturation" => Ok(Self::Saturation),
+            "glowing" => Ok(Self::Glowing),
+            "levitation" => Ok(Self::Levitation),
+            "luck" => Ok(Self::Luck),
+            "unluck" => Ok(Self::Unluck),
+            "slow_falling" => Ok(Self::SlowFalling),
+            "conduit_power" => Ok(Self::ConduitPower),
+            "dolphins_grace" => Ok(Self::DolphinsGrace),
+            "bad_omen" => Ok(Self::BadOmen),
+            "hero_of_the_village" => Ok(Self::HeroOfTheVillage),
+            "darkness" => Ok(Self::Darkness),
+            "trial_omen" => Ok(Self::TrialOmen),
+            "raid_omen" => Ok(Self::RaidOmen),
+            "wind_charged" => Ok(Self::WindCharged),
+            "weaving" => Ok(Self::Weaving),
+            "oozing" => Ok(Self::Oozing),
+            "infested" => Ok(Self::Infested),
+            _ => Err(ParseEffectTypeError),
+        }
+    }
+}
+
+/// A duration of -1 means the effect never runs out
+pub const INFINITE_DURATION: i32 = -1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StatusEffect {
+    pub effect_type: EffectType,
+    /// The remaining duration in ticks
+    pub duration: i32,
+    /// The level of the effect, starting at 0 for level I
+    pub amplifier: u8,
+    /// Ambient effects come from beacons or conduits and have less visible particles
+    pub ambient: bool,
+    pub show_particles: bool,
+    pub show_icon: bool,
+}
+
+impl StatusEffect {
+    pub const fn new(effect_type: EffectType, duration: i32, amplifier: u8) -> Self {
+        Self {
+            effect_type,
+            duration,
+            amplifier,
+            ambient: false,
+            show_particles: true,
+            show_icon: true,
+        }
+    }
+
+    pub const fn is_infinite(&self) -> bool {
+        self.duration == INFINITE_DURATION
+    }
+
+    /// The flags byte used in the Entity Effect packet
+    pub const fn flags(&self) -> i8 {
+        let mut flags = 0;
+        if self.ambient {
+            flags |= 0x01;
+        }
+        if self.show_particles {
+            flags |= 0x02;
+        }
+        if self.show_icon {
+            flags |= 0x04;
+        }
+        flags
+    }
+}
+
+/// The active status effects of a living entity, each effect type can only be active once
+#[derive(Default)]
+pub struct StatusEffects {
+    effects: HashMap<EffectType, StatusEffect>,
+}
+
+impl StatusEffects {
+    /// Adds the effect, replacing and returning an already active effect of the same type
+    pub fn add(&mut self, effect: StatusEffect) -> Option<StatusEffect> {
+        self.effects.insert(effect.effect_type, effect)
+    }
+
+    pub fn remove(&mut self, effect_type: EffectType) -> Option<StatusEffect> {
+        self.effects.remove(&effect_type)
+    }
+
+    /// Removes all effects and returns the types which were active
+    pub fn clear(&mut self) -> Vec<EffectType> {
+        self.effects
+            .drain()
+            .map(|(effect_type, _)| effect_type)
+            .collect()
+    }
+
+    pub fn get(&self, effect_type: EffectType) -> Option<&StatusEffect> {
+        self.effects.get(&effect_type)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &StatusEffect> {
+        self.effects.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    /// Counts down the duration of every effect by one tick.
+    ///
+    /// Effects which ran out are removed and their types returned, so the client can be told
+    pub fn tick(&mut self) -> Vec<EffectType> {
+        let mut expired = Vec::new();
+        self.effects.retain(|effect_type, effect| {
+            if effect.is_infinite() {
+                return true;
+            }
+            effect.duration -= 1;
+            if effect.duration <= 0 {
+                expired.push(*effect_type);
+                false
+            } else {
+                true
+            }
+        });
+        expired
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EffectType, StatusEffect, StatusEffects, INFINITE_DURATION};
+
+    #[test]
+    fn test_tick_to_expiry() {
+        let mut effects = StatusEffects::default();
+        effects.add(StatusEffect::new(EffectType::Speed, 2, 0));
+        effects.add(StatusEffect::new(EffectType::Poison, 3, 1));
+
+        assert!(effects.tick().is_empty());
+        assert_eq!(effects.get(EffectType::Speed).unwrap().duration, 1);
+
+        assert_eq!(effects.tick(), vec![EffectType::Speed]);
+        assert!(effects.get(EffectType::Speed).is_none());
+
+        assert_eq!(effects.tick(), vec![EffectType::Poison]);
+        assert!(effects.is_empty());
+    }
+
+    #[test]
+    fn test_infinite_never_expires() {
+        let mut effects = StatusEffects::default();
+        effects.add(StatusEffect::new(
+            EffectType::NightVision,
+            INFINITE_DURATION,
+            0,
+        ));
+        for _ in 0..100 {
+            assert!(effects.tick().is_empty());
+        }
+        assert!(effects.get(EffectType::NightVision).is_some());
+    }
+
+    #[test]
+    fn test_add_replaces() {
+        let mut effects = StatusEffects::default();
+        assert!(effects
+            .add(StatusEffect::new(EffectType::Speed, 100, 0))
+            .is_none());
+        let old = effects.add(StatusEffect::new(EffectType::Speed, 200, 1));
+        assert_eq!(old.unwrap().amplifier, 0);
+        assert_eq!(effects.clear(), vec![EffectType::Speed]);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("speed".parse(), Ok(EffectType::Speed));
+        assert_eq!("minecraft:jump_boost".parse(), Ok(EffectType::JumpBoost));
+        assert!("flying".parse::<EffectType>().is_err());
+        assert_eq!(EffectType::Infested as i32, 38);
+    }
+}
@@ -1,7 +1,87 @@
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ParseEntityTypeError;
+
 // TODO
-#[derive(Clone)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(i32)]
 pub enum EntityType {
+    Boat = 10,
+    ExperienceOrb = 38,
+    Item = 58,
+    Pig = 77,
     Zombie = 124,
     Player = 128,
 }
+
+impl EntityType {
+    /// Players can only join and items and experience only be dropped, an item entity without an item would be nothing
+    pub const fn is_summonable(&self) -> bool {
+        !matches!(self, Self::Player | Self::Item | Self::ExperienceOrb)
+    }
+
+    /// The width and height of the bounding box while standing
+    pub const fn dimensions(&self) -> (f32, f32) {
+        match self {
+            Self::Boat => (1.375, 0.5625),
+            Self::ExperienceOrb => (0.5, 0.5),
+            Self::Item => (0.25, 0.25),
+            Self::Pig => (0.9, 0.9),
+            Self::Zombie => (0.6, 1.95),
+            Self::Player => (0.6, 1.8),
+        }
+    }
+
+    /// Mobs count towards the mob cap, Players, items and vehicles don't
+    pub const fn is_mob(&self) -> bool {
+        matches!(self, Self::Pig | Self::Zombie)
+    }
+
+    /// The height of the eyes above the feet while standing
+    pub const fn standing_eye_height(&self) -> f32 {
+        match self {
+            Self::Boat => 0.5625,
+            Self::ExperienceOrb => 0.425,
+            Self::Item => 0.2125,
+            Self::Pig => 0.765,
+            Self::Zombie => 1.74,
+            Self::Player => 1.62,
+        }
+    }
+}
+
+impl FromStr for EntityType {
+    type Err = ParseEntityTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix("minecraft:").unwrap_or(s) {
+            "boat" => Ok(Self::Boat),
+            "experience_orb" => Ok(Self::ExperienceOrb),
+            "item" => Ok(Self::Item),
+            "pig" => Ok(Self::Pig),
+            "zombie" => Ok(Self::Zombie),
+            "player" => Ok(Self::Player),
+            _ => Err(ParseEntityTypeError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EntityType;
+
+    #[test]
+    fn test_parse_entity_type() {
+        assert_eq!("zombie".parse(), Ok(EntityType::Zombie));
+        assert_eq!("minecraft:boat".parse(), Ok(EntityType::Boat));
+        assert!("creeper".parse::<EntityType>().is_err());
+        assert!("minecraft:".parse::<EntityType>().is_err());
+        assert!(!EntityType::Player.is_summonable());
+        assert!(!EntityType::Item.is_summonable());
+        assert!(!EntityType::ExperienceOrb.is_summonable());
+        assert_eq!("minecraft:pig".parse(), Ok(EntityType::Pig));
+        assert!(EntityType::Pig.is_mob());
+        assert!(!EntityType::Boat.is_mob());
+    }
+}
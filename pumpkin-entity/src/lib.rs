@@ -1,3 +1,4 @@
+pub mod effect;
 pub mod entity_type;
 pub mod pose;
 
@@ -73,10 +73,7 @@ impl DragHandler {
             Err(InventoryError::MultiplePlayersDragging)?
         }
         let mut slots = container.all_slots();
-        let slots_cloned = slots
-            .iter()
-            .map(|stack| stack.map(|item| item.to_owned()))
-            .collect_vec();
+        let slots_cloned = slots.iter().map(|stack| (**stack).clone()).collect_vec();
         let Some(carried_item) = maybe_carried_item else {
             return Ok(());
         };
@@ -85,11 +82,11 @@ impl DragHandler {
             // Checked in any function that uses this function.
             MouseDragType::Middle => {
                 for slot in &drag.slots {
-                    *slots[*slot] = *maybe_carried_item;
+                    *slots[*slot] = maybe_carried_item.clone();
                 }
             }
             MouseDragType::Right => {
-                let mut single_item = *carried_item;
+                let mut single_item = carried_item.clone();
                 single_item.item_count = 1;
 
                 let changing_slots =
@@ -105,7 +102,7 @@ impl DragHandler {
                                 carried_item.item_count += 1;
                             }
                         } else {
-                            *slots[slot] = Some(single_item)
+                            *slots[slot] = Some(single_item.clone())
                         }
                     }
                 });
@@ -123,9 +120,9 @@ impl DragHandler {
                 let amount_of_slots = changing_slots.clone().count();
                 let (amount_per_slot, remainder) =
                     (carried_item.item_count as usize).div_rem_euclid(&amount_of_slots);
-                let mut item_in_each_slot = *carried_item;
+                let mut item_in_each_slot = carried_item.clone();
                 item_in_each_slot.item_count = amount_per_slot as u8;
-                changing_slots.for_each(|slot| *slots[slot] = Some(item_in_each_slot));
+                changing_slots.for_each(|slot| *slots[slot] = Some(item_in_each_slot.clone()));
 
                 if remainder > 0 {
                     carried_item.item_count = remainder as u8;
@@ -99,7 +99,7 @@ pub fn handle_item_take(
     let Some(item) = item_slot else {
         return;
     };
-    let mut new_item = *item;
+    let mut new_item = item.clone();
 
     match mouse_click {
         MouseClick::Left => {
@@ -124,9 +124,7 @@ pub fn handle_item_change(
             if current.item_id == carried.item_id {
                 combine_stacks(carried_slot, current, mouse_click);
             } else if mouse_click == MouseClick::Left {
-                let carried = *carried;
-                *carried_slot = Some(current.to_owned());
-                *current_slot = Some(carried.to_owned());
+                std::mem::swap(carried_slot, current_slot);
             }
         }
         // Put held stack into empty slot
@@ -137,7 +135,7 @@ pub fn handle_item_change(
             }
             MouseClick::Right => {
                 carried.item_count -= 1;
-                let mut new = *carried;
+                let mut new = carried.clone();
                 new.item_count = 1;
                 *current_slot = Some(new);
             }
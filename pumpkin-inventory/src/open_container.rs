@@ -52,7 +52,7 @@ struct Chest([Option<ItemStack>; 27]);
 
 impl Chest {
     pub fn new() -> Self {
-        Self([None; 27])
+        Self([const { None }; 27])
     }
 }
 impl Container for Chest {
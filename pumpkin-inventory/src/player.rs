@@ -27,10 +27,10 @@ impl Default for PlayerInventory {
 impl PlayerInventory {
     pub fn new() -> Self {
         Self {
-            crafting: [None; 4],
+            crafting: [const { None }; 4],
             crafting_output: None,
-            items: [None; 36],
-            armor: [None; 4],
+            items: [const { None }; 36],
+            armor: [const { None }; 4],
             offhand: None,
             // TODO: What when player spawns in with an different index ?
             selected: 0,
@@ -106,6 +106,24 @@ impl PlayerInventory {
         self.selected = slot;
     }
 
+    /// Puts the item into the first free slot, the hotbar comes first. Returns false if the inventory is full
+    pub fn add_item(&mut self, item: ItemStack) -> bool {
+        // The hotbar is at the end of `items`
+        let (main, hotbar) = self.items.split_at_mut(27);
+        match hotbar.iter_mut().chain(main).find(|slot| slot.is_none()) {
+            Some(slot) => {
+                *slot = Some(item);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The slot of the held item, see `get_slot`
+    pub fn held_slot(&self) -> usize {
+        self.selected + 36
+    }
+
     pub fn held_item(&self) -> Option<&ItemStack> {
         debug_assert!((0..9).contains(&self.selected));
         self.items[self.selected + 36 - 9].as_ref()
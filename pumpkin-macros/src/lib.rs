@@ -1,5 +1,6 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::parse::Parser;
 
 extern crate proc_macro;
 #[proc_macro_attribute]
@@ -10,13 +11,24 @@ pub fn packet(input: TokenStream, item: TokenStream) -> TokenStream {
 
     let (impl_generics, ty_generics, _) = ast.generics.split_for_impl();
 
-    let input: proc_macro2::TokenStream = input.into();
+    // The packet id, optionally followed by the priority of the packet
+    let args = syn::punctuated::Punctuated::<syn::Expr, syn::Token![,]>::parse_terminated
+        .parse(input)
+        .unwrap();
+    let mut args = args.into_iter();
+    let id = args.next().expect("The packet id is missing");
+    let priority = args.next().map(|priority| {
+        quote! {
+            const PRIORITY: crate::bytebuf::packet_id::PacketPriority = #priority;
+        }
+    });
     let item: proc_macro2::TokenStream = item.into();
 
     let gen = quote! {
         #item
         impl #impl_generics crate::bytebuf::packet_id::Packet for #name #ty_generics {
-            const PACKET_ID: i32 = #input;
+            const PACKET_ID: i32 = #id;
+            #priority
         }
     };
 
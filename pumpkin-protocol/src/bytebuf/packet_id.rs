@@ -74,8 +74,20 @@ impl<'de> Deserialize<'de> for VarInt {
     }
 }
 
+/// How important it is that a Clientbound packet reaches the Client, decides which packets are dropped when its network queue is full
+#[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
+pub enum PacketPriority {
+    /// Only cosmetic, e.g. particles and animations
+    Low,
+    Normal,
+    /// Is always sent, e.g. keep alives and disconnects
+    Critical,
+}
+
 pub trait Packet {
     const PACKET_ID: VarIntType;
+    /// Set with `#[packet(id, PacketPriority::Low)]`
+    const PRIORITY: PacketPriority = PacketPriority::Normal;
 }
 
 impl<P> ClientPacket for P
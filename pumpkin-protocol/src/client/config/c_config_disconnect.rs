@@ -1,13 +1,17 @@
+use pumpkin_core::text::TextComponent;
 use pumpkin_macros::packet;
+use serde::Serialize;
 
-#[derive(serde::Serialize)]
-#[packet(0x02)]
+use crate::bytebuf::packet_id::PacketPriority;
+
+#[derive(Serialize)]
+#[packet(0x02, PacketPriority::Critical)]
 pub struct CConfigDisconnect<'a> {
-    reason: &'a str,
+    reason: &'a TextComponent<'a>,
 }
 
 impl<'a> CConfigDisconnect<'a> {
-    pub fn new(reason: &'a str) -> Self {
+    pub fn new(reason: &'a TextComponent<'a>) -> Self {
         Self { reason }
     }
 }
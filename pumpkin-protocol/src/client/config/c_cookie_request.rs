@@ -7,3 +7,9 @@ use crate::Identifier;
 pub struct CCookieRequest {
     key: Identifier,
 }
+
+impl CCookieRequest {
+    pub fn new(key: Identifier) -> Self {
+        Self { key }
+    }
+}
@@ -16,9 +16,9 @@ impl<'a> CKnownPacks<'a> {
 impl<'a> ClientPacket for CKnownPacks<'a> {
     fn write(&self, bytebuf: &mut ByteBuffer) {
         bytebuf.put_list::<KnownPack>(self.known_packs, |p, v| {
-            p.put_string(v.namespace);
-            p.put_string(v.id);
-            p.put_string(v.version);
+            p.put_string(&v.namespace);
+            p.put_string(&v.id);
+            p.put_string(&v.version);
         });
     }
 }
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use pumpkin_macros::packet;
 
 use crate::{bytebuf::ByteBuffer, ClientPacket};
@@ -18,7 +20,7 @@ impl<'a> CRegistryData<'a> {
 }
 
 pub struct RegistryEntry<'a> {
-    pub entry_id: &'a str,
+    pub entry_id: Cow<'a, str>,
     pub data: Vec<u8>,
 }
 
@@ -26,7 +28,7 @@ impl<'a> ClientPacket for CRegistryData<'a> {
     fn write(&self, bytebuf: &mut ByteBuffer) {
         bytebuf.put_string(self.registry_id);
         bytebuf.put_list::<RegistryEntry>(self.entries, |p, v| {
-            p.put_string(v.entry_id);
+            p.put_string(&v.entry_id);
             p.put_bool(!v.data.is_empty());
             p.put_slice(&v.data);
         });
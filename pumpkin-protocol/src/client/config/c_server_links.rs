@@ -0,0 +1,126 @@
+use pumpkin_core::text::TextComponent;
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket};
+
+/// The labels the client translates itself
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BuiltInLinkLabel {
+    BugReport,
+    CommunityGuidelines,
+    Support,
+    Status,
+    Feedback,
+    Community,
+    Website,
+    Forums,
+    News,
+    Announcements,
+}
+
+impl BuiltInLinkLabel {
+    /// Parses the label like it is called in vanilla, e.g. `bug_report`
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "bug_report" => Self::BugReport,
+            "community_guidelines" => Self::CommunityGuidelines,
+            "support" => Self::Support,
+            "status" => Self::Status,
+            "feedback" => Self::Feedback,
+            "community" => Self::Community,
+            "website" => Self::Website,
+            "forums" => Self::Forums,
+            "news" => Self::News,
+            "announcements" => Self::Announcements,
+            _ => return None,
+        })
+    }
+}
+
+pub enum LinkLabel<'a> {
+    BuiltIn(BuiltInLinkLabel),
+    Custom(TextComponent<'a>),
+}
+
+pub struct Link<'a> {
+    pub label: LinkLabel<'a>,
+    pub url: &'a str,
+}
+
+/// Links shown in the pause menu, like the rules or the website of the server
+#[packet(0x10)]
+pub struct CServerLinks<'a> {
+    links: &'a [Link<'a>],
+}
+
+impl<'a> CServerLinks<'a> {
+    pub fn new(links: &'a [Link<'a>]) -> Self {
+        Self { links }
+    }
+}
+
+impl<'a> ClientPacket for CServerLinks<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_list::<Link>(self.links, |p, link| {
+            match &link.label {
+                LinkLabel::BuiltIn(label) => {
+                    p.put_bool(true);
+                    p.put_var_int(&(*label as i32).into());
+                }
+                LinkLabel::Custom(text) => {
+                    p.put_bool(false);
+                    p.put_slice(&text.encode());
+                }
+            }
+            p.put_string(link.url);
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::text::TextComponent;
+
+    use crate::{bytebuf::ByteBuffer, ClientPacket};
+
+    use super::{BuiltInLinkLabel, CServerLinks, Link, LinkLabel};
+
+    #[test]
+    fn test_built_in_label() {
+        assert_eq!(
+            BuiltInLinkLabel::from_name("website"),
+            Some(BuiltInLinkLabel::Website)
+        );
+        assert_eq!(BuiltInLinkLabel::from_name("Website"), None);
+
+        let links = [Link {
+            label: LinkLabel::BuiltIn(BuiltInLinkLabel::Website),
+            url: "https://example.com",
+        }];
+        let mut bytebuf = ByteBuffer::empty();
+        CServerLinks::new(&links).write(&mut bytebuf);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 1);
+        assert!(bytebuf.get_bool().unwrap());
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 6);
+        assert_eq!(bytebuf.get_string().unwrap(), "https://example.com");
+        assert!(bytebuf.buf().is_empty());
+    }
+
+    #[test]
+    fn test_custom_label() {
+        let text = TextComponent::text("Rules");
+        let encoded = text.encode();
+        let links = [Link {
+            label: LinkLabel::Custom(text),
+            url: "https://example.com/rules",
+        }];
+        let mut bytebuf = ByteBuffer::empty();
+        CServerLinks::new(&links).write(&mut bytebuf);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 1);
+        assert!(!bytebuf.get_bool().unwrap());
+        let mut label = vec![0; encoded.len()];
+        bytebuf.copy_to_slice(&mut label).unwrap();
+        assert_eq!(label, encoded);
+        assert_eq!(bytebuf.get_string().unwrap(), "https://example.com/rules");
+    }
+}
@@ -1,8 +1,10 @@
 use pumpkin_macros::packet;
 use serde::Serialize;
 
+use crate::bytebuf::packet_id::PacketPriority;
+
 #[derive(Serialize)]
-#[packet(0x00)]
+#[packet(0x00, PacketPriority::Critical)]
 pub struct CLoginDisconnect<'a> {
     json_reason: &'a str,
 }
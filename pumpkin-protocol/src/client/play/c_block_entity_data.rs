@@ -0,0 +1,60 @@
+use pumpkin_macros::packet;
+use pumpkin_world::block::block_entity::BlockEntity;
+
+use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+/// Updates the data of a block entity, e.g. the text of a sign
+#[packet(0x07)]
+pub struct CBlockEntityData<'a> {
+    block_entity: &'a BlockEntity,
+    block_entity_type: VarInt,
+}
+
+impl<'a> CBlockEntityData<'a> {
+    /// Returns `None` if the client doesn't know the type of the block entity
+    pub fn new(block_entity: &'a BlockEntity) -> Option<Self> {
+        let block_entity_type = block_entity.type_id()?;
+        Some(Self {
+            block_entity,
+            block_entity_type: VarInt(block_entity_type as i32),
+        })
+    }
+}
+
+impl<'a> ClientPacket for CBlockEntityData<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        bytebuf.put_i64(self.block_entity.position.as_long());
+        bytebuf.put_var_int(&self.block_entity_type);
+        bytebuf.put_slice(&network_nbt(self.block_entity));
+    }
+}
+
+/// The data of a block entity like the client expects it, without its id and position
+pub(crate) fn network_nbt(block_entity: &BlockEntity) -> Vec<u8> {
+    fastnbt::to_bytes_with_opts(&block_entity.data, fastnbt::SerOpts::network_nbt()).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+    use pumpkin_world::block::block_entity::BlockEntity;
+
+    use crate::{bytebuf::ByteBuffer, ClientPacket};
+
+    use super::{network_nbt, CBlockEntityData};
+
+    #[test]
+    fn test_write_sign() {
+        let position = WorldPosition(Vector3::new(5, -10, -7));
+        let sign = BlockEntity::sign("minecraft:oak_wall_sign", position).unwrap();
+        let mut bytebuf = ByteBuffer::empty();
+        CBlockEntityData::new(&sign).unwrap().write(&mut bytebuf);
+
+        assert_eq!(bytebuf.get_i64().unwrap(), position.as_long());
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 7);
+        assert_eq!(bytebuf.buf().as_ref(), network_nbt(&sign).as_slice());
+
+        let unknown = BlockEntity::new("minecraft:not_a_block_entity", position);
+        assert!(CBlockEntityData::new(&unknown).is_none());
+    }
+}
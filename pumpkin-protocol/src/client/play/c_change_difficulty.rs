@@ -13,3 +13,19 @@ impl CChangeDifficulty {
         Self { difficulty, locked }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{bytebuf::ByteBuffer, ClientPacket};
+
+    use super::CChangeDifficulty;
+
+    #[test]
+    fn test_change_difficulty_fields() {
+        let mut bytebuf = ByteBuffer::empty();
+        CChangeDifficulty::new(3, true).write(&mut bytebuf);
+        assert_eq!(bytebuf.get_u8().unwrap(), 3);
+        assert!(bytebuf.get_bool().unwrap());
+        assert!(bytebuf.buf().is_empty());
+    }
+}
@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 
+use super::c_block_entity_data::network_nbt;
+
 use crate::{bytebuf::ByteBuffer, BitSet, ClientPacket, VarInt};
 use itertools::Itertools;
+use pumpkin_core::math::position::WorldPosition;
 use pumpkin_macros::packet;
 use pumpkin_world::{chunk::ChunkData, DIRECT_PALETTE_BITS};
 
@@ -15,14 +18,15 @@ impl<'a> ClientPacket for CChunkData<'a> {
         // Chunk Z
         buf.put_i32(self.0.position.z);
 
+        let blocks = self.0.blocks.read();
         let heightmap_nbt =
-            fastnbt::to_bytes_with_opts(&self.0.blocks.heightmap, fastnbt::SerOpts::network_nbt())
+            fastnbt::to_bytes_with_opts(&blocks.heightmap, fastnbt::SerOpts::network_nbt())
                 .unwrap();
         // Heightmaps
         buf.put_slice(&heightmap_nbt);
 
         let mut data_buf = ByteBuffer::empty();
-        self.0.blocks.iter_subchunks().for_each(|chunk| {
+        blocks.iter_subchunks().for_each(|chunk| {
             let block_count = chunk.iter().filter(|block| !block.is_air()).count() as i16;
             // Block count
             data_buf.put_i16(block_count);
@@ -107,8 +111,24 @@ impl<'a> ClientPacket for CChunkData<'a> {
         // Data
         buf.put_slice(data_buf.buf());
 
-        // TODO: block entities
-        buf.put_var_int(&VarInt(0));
+        // Block entities the client doesn't know would make it disconnect
+        let block_entities = self.0.block_entities.read();
+        let block_entities = block_entities
+            .iter()
+            .filter_map(|entity| Some((entity, entity.type_id()?)))
+            .collect_vec();
+        buf.put_var_int(&VarInt(block_entities.len() as i32));
+        for (entity, type_id) in block_entities {
+            let WorldPosition(position) = entity.position;
+            // Packed XZ
+            buf.put_u8(((position.x.rem_euclid(16) as u8) << 4) | position.z.rem_euclid(16) as u8);
+            // Y
+            buf.put_i16(position.y as i16);
+            // Type
+            buf.put_var_int(&VarInt(type_id as i32));
+            // Data
+            buf.put_slice(&network_nbt(entity));
+        }
 
         // TODO
         buf.put_bit_set(&BitSet(VarInt(1), &[0]));
@@ -124,3 +144,56 @@ impl<'a> ClientPacket for CChunkData<'a> {
         buf.put_var_int(&VarInt(0));
     }
 }
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::{position::WorldPosition, vector2::Vector2, vector3::Vector3};
+    use pumpkin_world::{
+        block::block_entity::BlockEntity,
+        chunk::{ChunkBlocks, ChunkData},
+    };
+
+    use crate::{
+        bytebuf::ByteBuffer, client::play::c_block_entity_data::network_nbt, ClientPacket,
+    };
+
+    use super::CChunkData;
+
+    #[test]
+    fn test_block_entities() {
+        let chunk = ChunkData::new(ChunkBlocks::default(), Vector2::new(-2, 3));
+        let mut sign = BlockEntity::sign(
+            "minecraft:oak_sign",
+            WorldPosition(Vector3::new(-29, -60, 55)),
+        )
+        .unwrap();
+        sign.set_sign_text(true, &["Welcome".to_string()]);
+        chunk.set_block_entity(sign.clone());
+        chunk.set_block_entity(BlockEntity::new(
+            "minecraft:not_a_block_entity",
+            WorldPosition(Vector3::new(-30, 0, 50)),
+        ));
+
+        let mut bytebuf = ByteBuffer::empty();
+        CChunkData(&chunk).write(&mut bytebuf);
+        assert_eq!(bytebuf.get_i32().unwrap(), -2);
+        assert_eq!(bytebuf.get_i32().unwrap(), 3);
+        let heightmap = fastnbt::to_bytes_with_opts(
+            &chunk.blocks.read().heightmap,
+            fastnbt::SerOpts::network_nbt(),
+        )
+        .unwrap();
+        bytebuf.copy_to_bytes(heightmap.len()).unwrap();
+        let size = bytebuf.get_var_int().unwrap().0;
+        bytebuf.copy_to_bytes(size as usize).unwrap();
+
+        // The unknown block entity is skipped
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 1);
+        // x = 3, z = 7 inside of the chunk
+        assert_eq!(bytebuf.get_u8().unwrap(), 0x37);
+        assert_eq!(bytebuf.get_i16().unwrap(), -60);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 7);
+        let nbt = network_nbt(&sign);
+        assert_eq!(&bytebuf.buf()[..nbt.len()], nbt.as_slice());
+    }
+}
@@ -0,0 +1,15 @@
+use pumpkin_macros::packet;
+
+use crate::Identifier;
+
+#[derive(serde::Serialize)]
+#[packet(0x16)]
+pub struct CCookieRequestPlay {
+    key: Identifier,
+}
+
+impl CCookieRequestPlay {
+    pub fn new(key: Identifier) -> Self {
+        Self { key }
+    }
+}
@@ -1,10 +1,10 @@
 use pumpkin_macros::packet;
 use serde::Serialize;
 
-use crate::VarInt;
+use crate::{bytebuf::packet_id::PacketPriority, VarInt};
 
 #[derive(Serialize)]
-#[packet(0x03)]
+#[packet(0x03, PacketPriority::Low)]
 pub struct CEntityAnimation {
     entity_id: VarInt,
     /// See `Animation`
@@ -20,11 +20,31 @@ impl CEntityAnimation {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Animation {
-    SwingMainArm,
-    LeaveBed,
-    SwingOffhand,
-    CriticalEffect,
-    MagicCriticaleffect,
+    SwingMainArm = 0,
+    // 1 was used for the damage animation, which is now `CHurtAnimation`
+    LeaveBed = 2,
+    SwingOffhand = 3,
+    CriticalEffect = 4,
+    MagicCriticaleffect = 5,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{bytebuf::ByteBuffer, ClientPacket};
+
+    use super::{Animation, CEntityAnimation};
+
+    #[test]
+    fn test_swing_animation_ids() {
+        let mut bytebuf = ByteBuffer::empty();
+        CEntityAnimation::new(7.into(), Animation::SwingOffhand as u8).write(&mut bytebuf);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 7);
+        assert_eq!(bytebuf.get_u8().unwrap(), 3);
+
+        assert_eq!(Animation::SwingMainArm as u8, 0);
+        assert_eq!(Animation::LeaveBed as u8, 2);
+    }
 }
@@ -18,6 +18,7 @@ impl CGameEvent {
     }
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum GameEvent {
     NoRespawnBlockAvailable,
@@ -1,10 +1,10 @@
 use pumpkin_macros::packet;
 use serde::Serialize;
 
-use crate::VarInt;
+use crate::{bytebuf::packet_id::PacketPriority, VarInt};
 
 #[derive(Serialize)]
-#[packet(0x24)]
+#[packet(0x24, PacketPriority::Low)]
 pub struct CHurtAnimation<'a> {
     entity_id: &'a VarInt,
     yaw: f32,
@@ -1,7 +1,9 @@
 use pumpkin_macros::packet;
 use serde::Serialize;
 
-#[packet(0x26)]
+use crate::bytebuf::packet_id::PacketPriority;
+
+#[packet(0x26, PacketPriority::Critical)]
 #[derive(Serialize)]
 pub struct CKeepAlive {
     pub keep_alive_id: i64,
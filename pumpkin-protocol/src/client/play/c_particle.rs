@@ -1,10 +1,10 @@
 use pumpkin_macros::packet;
 use serde::Serialize;
 
-use crate::VarInt;
+use crate::{bytebuf::packet_id::PacketPriority, VarInt};
 
 #[derive(Serialize)]
-#[packet(0x29)]
+#[packet(0x29, PacketPriority::Low)]
 pub struct CParticle<'a> {
     /// If true, particle distance increases from 256 to 65536.
     long_distance: bool,
@@ -0,0 +1,27 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Plays the animation of an item flying to the entity which picked it up, the item entity has to be removed separately
+#[derive(Serialize)]
+#[packet(0x6F)]
+pub struct CPickupItem {
+    collected_entity_id: VarInt,
+    collector_entity_id: VarInt,
+    pickup_item_count: VarInt,
+}
+
+impl CPickupItem {
+    pub fn new(
+        collected_entity_id: VarInt,
+        collector_entity_id: VarInt,
+        pickup_item_count: VarInt,
+    ) -> Self {
+        Self {
+            collected_entity_id,
+            collector_entity_id,
+            pickup_item_count,
+        }
+    }
+}
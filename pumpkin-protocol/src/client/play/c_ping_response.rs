@@ -1,6 +1,7 @@
 use pumpkin_macros::packet;
 use serde::Serialize;
 
+/// Echoes the payload of `SPlayPingRequest`
 #[derive(Serialize)]
 #[packet(0x36)]
 pub struct CPingResponse {
@@ -12,3 +13,23 @@ impl CPingResponse {
         Self { payload }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{bytebuf::ByteBuffer, server::play::SPlayPingRequest, ClientPacket, ServerPacket};
+
+    use super::CPingResponse;
+
+    #[test]
+    fn test_payload_is_echoed() {
+        let payload = -0x1234_5678_9ABC_DEF0;
+        let mut request = ByteBuffer::empty();
+        request.put_i64(payload);
+        let request = SPlayPingRequest::read(&mut request).unwrap();
+
+        let mut response = ByteBuffer::empty();
+        CPingResponse::new(request.payload).write(&mut response);
+        assert_eq!(response.get_i64().unwrap(), payload);
+        assert!(response.buf().is_empty());
+    }
+}
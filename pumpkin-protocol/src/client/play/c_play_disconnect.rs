@@ -2,8 +2,10 @@ use pumpkin_core::text::TextComponent;
 use pumpkin_macros::packet;
 use serde::Serialize;
 
+use crate::bytebuf::packet_id::PacketPriority;
+
 #[derive(Serialize)]
-#[packet(0x1D)]
+#[packet(0x1D, PacketPriority::Critical)]
 pub struct CPlayDisconnect<'a> {
     reason: &'a TextComponent<'a>,
 }
@@ -0,0 +1,20 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+#[derive(Serialize)]
+#[packet(0x43)]
+pub struct CRemoveMobEffect {
+    entity_id: VarInt,
+    effect_id: VarInt,
+}
+
+impl CRemoveMobEffect {
+    pub fn new(entity_id: VarInt, effect_id: VarInt) -> Self {
+        Self {
+            entity_id,
+            effect_id,
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Keep the attributes (e.g. max health) of the Player after respawning
+pub const KEEP_ATTRIBUTES: u8 = 0x01;
+/// Keep the entity metadata (e.g. skin parts) of the Player after respawning
+pub const KEEP_METADATA: u8 = 0x02;
+
+/// Makes the client recreate its world, used for respawning and changing dimensions.
+///
+/// The client forgets every chunk and entity, so they have to be sent again
+#[derive(Serialize)]
+#[packet(0x47)]
+pub struct CRespawn<'a> {
+    dimension_type: VarInt,
+    dimension_name: &'a str,
+    hashed_seed: i64,
+    game_mode: u8,
+    previous_gamemode: i8,
+    debug: bool,
+    is_flat: bool,
+    death_location: Option<(&'a str, WorldPosition)>,
+    portal_cooldown: VarInt,
+    data_kept: u8,
+}
+
+impl<'a> CRespawn<'a> {
+    #[expect(clippy::too_many_arguments)]
+    pub fn new(
+        dimension_type: VarInt,
+        dimension_name: &'a str,
+        hashed_seed: i64,
+        game_mode: u8,
+        previous_gamemode: i8,
+        debug: bool,
+        is_flat: bool,
+        death_location: Option<(&'a str, WorldPosition)>,
+        portal_cooldown: VarInt,
+        data_kept: u8,
+    ) -> Self {
+        Self {
+            dimension_type,
+            dimension_name,
+            hashed_seed,
+            game_mode,
+            previous_gamemode,
+            debug,
+            is_flat,
+            death_location,
+            portal_cooldown,
+            data_kept,
+        }
+    }
+}
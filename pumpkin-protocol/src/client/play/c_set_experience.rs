@@ -0,0 +1,23 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+#[derive(Serialize)]
+#[packet(0x5C)]
+pub struct CSetExperience {
+    /// How full the experience bar is, between 0 and 1
+    experience_bar: f32,
+    level: VarInt,
+    total_experience: VarInt,
+}
+
+impl CSetExperience {
+    pub fn new(experience_bar: f32, level: VarInt, total_experience: VarInt) -> Self {
+        Self {
+            experience_bar,
+            level,
+            total_experience,
+        }
+    }
+}
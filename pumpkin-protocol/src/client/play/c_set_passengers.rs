@@ -0,0 +1,48 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Replaces the passengers of a vehicle, passengers missing from the list dismount
+#[derive(Serialize)]
+#[packet(0x5F)]
+pub struct CSetPassengers<'a> {
+    vehicle_id: VarInt,
+    count: VarInt,
+    passenger_ids: &'a [VarInt],
+}
+
+impl<'a> CSetPassengers<'a> {
+    pub fn new(vehicle_id: VarInt, passenger_ids: &'a [VarInt]) -> Self {
+        Self {
+            vehicle_id,
+            count: VarInt(passenger_ids.len() as i32),
+            passenger_ids,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{bytebuf::ByteBuffer, ClientPacket, VarInt};
+
+    use super::CSetPassengers;
+
+    #[test]
+    fn test_set_passengers_fields() {
+        let mut bytebuf = ByteBuffer::empty();
+        CSetPassengers::new(VarInt(7), &[VarInt(2), VarInt(300)]).write(&mut bytebuf);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 7);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 2);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 2);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 300);
+        assert!(bytebuf.buf().is_empty());
+
+        // Without passengers everyone dismounts
+        let mut bytebuf = ByteBuffer::empty();
+        CSetPassengers::new(VarInt(7), &[]).write(&mut bytebuf);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 7);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0);
+        assert!(bytebuf.buf().is_empty());
+    }
+}
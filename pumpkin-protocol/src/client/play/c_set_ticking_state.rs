@@ -0,0 +1,19 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+/// Tells the client how fast its World ticks, so it predicts movement and the time at the same rate
+#[derive(Serialize)]
+#[packet(0x71)]
+pub struct CSetTickingState {
+    tick_rate: f32,
+    is_frozen: bool,
+}
+
+impl CSetTickingState {
+    pub fn new(tick_rate: f32, is_frozen: bool) -> Self {
+        Self {
+            tick_rate,
+            is_frozen,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+/// Experience orbs aren't spawned with `CSpawnEntity`, the count only decides how big the orb looks
+#[derive(Serialize)]
+#[packet(0x02)]
+pub struct CSpawnExperienceOrb {
+    entity_id: VarInt,
+    x: f64,
+    y: f64,
+    z: f64,
+    count: i16,
+}
+
+impl CSpawnExperienceOrb {
+    pub fn new(entity_id: VarInt, x: f64, y: f64, z: f64, count: i16) -> Self {
+        Self {
+            entity_id,
+            x,
+            y,
+            z,
+            count,
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::ByteBuffer, cookie::write_cookie, ClientPacket};
+
+/// Asks the client to store a cookie, which is kept across server transfers
+#[packet(0x6B)]
+pub struct CStoreCookiePlay<'a> {
+    key: &'a str,
+    payload: &'a [u8],
+}
+
+impl<'a> CStoreCookiePlay<'a> {
+    pub fn new(key: &'a str, payload: &'a [u8]) -> Self {
+        Self { key, payload }
+    }
+}
+
+impl<'a> ClientPacket for CStoreCookiePlay<'a> {
+    fn write(&self, bytebuf: &mut ByteBuffer) {
+        write_cookie(bytebuf, self.key, self.payload);
+    }
+}
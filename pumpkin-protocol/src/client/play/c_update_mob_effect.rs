@@ -0,0 +1,55 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+use crate::VarInt;
+
+#[derive(Serialize)]
+#[packet(0x76)]
+pub struct CUpdateMobEffect {
+    entity_id: VarInt,
+    effect_id: VarInt,
+    amplifier: VarInt,
+    duration: VarInt,
+    flags: i8,
+}
+
+impl CUpdateMobEffect {
+    pub fn new(
+        entity_id: VarInt,
+        effect_id: VarInt,
+        amplifier: VarInt,
+        duration: VarInt,
+        flags: i8,
+    ) -> Self {
+        Self {
+            entity_id,
+            effect_id,
+            amplifier,
+            duration,
+            flags,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{bytebuf::ByteBuffer, client::play::CRemoveMobEffect, ClientPacket};
+
+    use super::CUpdateMobEffect;
+
+    #[test]
+    fn test_add_remove_effect() {
+        let mut bytebuf = ByteBuffer::empty();
+        CUpdateMobEffect::new(5.into(), 18.into(), 1.into(), 600.into(), 0x06).write(&mut bytebuf);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 5);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 18);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 1);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 600);
+        assert_eq!(bytebuf.get_i8().unwrap(), 0x06);
+
+        CRemoveMobEffect::new(5.into(), 18.into()).write(&mut bytebuf);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 5);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 18);
+        assert!(bytebuf.buf().is_empty());
+    }
+}
@@ -0,0 +1,19 @@
+use pumpkin_macros::packet;
+use serde::Serialize;
+
+/// Syncs the time with the client, a negative time of day stops the client's daylight cycle
+#[derive(Serialize)]
+#[packet(0x64)]
+pub struct CUpdateTime {
+    world_age: i64,
+    time_of_day: i64,
+}
+
+impl CUpdateTime {
+    pub fn new(world_age: i64, time_of_day: i64) -> Self {
+        Self {
+            world_age,
+            time_of_day,
+        }
+    }
+}
@@ -1,11 +1,13 @@
 mod c_acknowledge_block;
 mod c_actionbar;
 mod c_block_destroy_stage;
+mod c_block_entity_data;
 mod c_block_update;
 mod c_center_chunk;
 mod c_change_difficulty;
 mod c_chunk_data;
 mod c_close_container;
+mod c_cookie_request;
 mod c_disguised_chat_message;
 mod c_entity_animation;
 mod c_entity_metadata;
@@ -17,7 +19,9 @@ mod c_hurt_animation;
 mod c_keep_alive;
 mod c_login;
 mod c_open_screen;
+mod c_open_sign_editor;
 mod c_particle;
+mod c_pickup_item;
 mod c_ping_response;
 mod c_play_disconnect;
 mod c_player_abilities;
@@ -25,12 +29,20 @@ mod c_player_chat_message;
 mod c_player_info_update;
 mod c_player_remove;
 mod c_remove_entities;
+mod c_remove_mob_effect;
+mod c_respawn;
 mod c_set_container_content;
 mod c_set_container_property;
 mod c_set_container_slot;
+mod c_set_experience;
+mod c_set_health;
 mod c_set_held_item;
+mod c_set_passengers;
+mod c_set_ticking_state;
 mod c_set_title;
+mod c_spawn_experience_orb;
 mod c_spawn_player;
+mod c_store_cookie;
 mod c_subtitle;
 mod c_sync_player_position;
 mod c_system_chat_message;
@@ -39,17 +51,21 @@ mod c_unload_chunk;
 mod c_update_entity_pos;
 mod c_update_entity_pos_rot;
 mod c_update_entity_rot;
+mod c_update_mob_effect;
+mod c_update_time;
 mod c_worldevent;
 mod player_action;
 
 pub use c_acknowledge_block::*;
 pub use c_actionbar::*;
 pub use c_block_destroy_stage::*;
+pub use c_block_entity_data::*;
 pub use c_block_update::*;
 pub use c_center_chunk::*;
 pub use c_change_difficulty::*;
 pub use c_chunk_data::*;
 pub use c_close_container::*;
+pub use c_cookie_request::*;
 pub use c_disguised_chat_message::*;
 pub use c_entity_animation::*;
 pub use c_entity_metadata::*;
@@ -61,7 +77,9 @@ pub use c_hurt_animation::*;
 pub use c_keep_alive::*;
 pub use c_login::*;
 pub use c_open_screen::*;
+pub use c_open_sign_editor::*;
 pub use c_particle::*;
+pub use c_pickup_item::*;
 pub use c_ping_response::*;
 pub use c_play_disconnect::*;
 pub use c_player_abilities::*;
@@ -69,12 +87,20 @@ pub use c_player_chat_message::*;
 pub use c_player_info_update::*;
 pub use c_player_remove::*;
 pub use c_remove_entities::*;
+pub use c_remove_mob_effect::*;
+pub use c_respawn::*;
 pub use c_set_container_content::*;
 pub use c_set_container_property::*;
 pub use c_set_container_slot::*;
+pub use c_set_experience::*;
+pub use c_set_health::*;
 pub use c_set_held_item::*;
+pub use c_set_passengers::*;
+pub use c_set_ticking_state::*;
 pub use c_set_title::*;
+pub use c_spawn_experience_orb::*;
 pub use c_spawn_player::*;
+pub use c_store_cookie::*;
 pub use c_subtitle::*;
 pub use c_sync_player_position::*;
 pub use c_system_chat_message::*;
@@ -83,5 +109,7 @@ pub use c_unload_chunk::*;
 pub use c_update_entity_pos::*;
 pub use c_update_entity_pos_rot::*;
 pub use c_update_entity_rot::*;
+pub use c_update_mob_effect::*;
+pub use c_update_time::*;
 pub use c_worldevent::*;
 pub use player_action::*;
@@ -0,0 +1,36 @@
+use bytes::Bytes;
+
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    Identifier,
+};
+
+/// Clients don't store or send cookies larger than this, in bytes
+pub const MAX_COOKIE_SIZE: usize = 5120;
+
+/// Writes the body of a Store Cookie packet, the same in the config and play state
+pub(crate) fn write_cookie(bytebuf: &mut ByteBuffer, key: &str, payload: &[u8]) {
+    bytebuf.put_string(key);
+    bytebuf.put_var_int(&(payload.len() as i32).into());
+    bytebuf.put_slice(payload);
+}
+
+/// Reads the body of a Cookie Response packet, the same in the config and play state
+pub(crate) fn read_cookie_response(
+    bytebuf: &mut ByteBuffer,
+) -> Result<(Identifier, Option<Bytes>), DeserializerError> {
+    let key = bytebuf.get_string()?;
+    let payload = if bytebuf.get_bool()? {
+        let length = bytebuf.get_var_int()?.0;
+        if length < 0 || length as usize > MAX_COOKIE_SIZE {
+            return Err(DeserializerError::Message(format!(
+                "cookie length {} is not in 0..={}",
+                length, MAX_COOKIE_SIZE
+            )));
+        }
+        Some(bytebuf.copy_to_bytes(length as usize)?)
+    } else {
+        None
+    };
+    Ok((key, payload))
+}
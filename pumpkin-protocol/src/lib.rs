@@ -1,15 +1,20 @@
 use bytebuf::{packet_id::Packet, ByteBuffer, DeserializerError};
 use bytes::Buf;
 use serde::{Deserialize, Serialize};
-use std::io::{self, Write};
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
 use thiserror::Error;
 
 pub mod bytebuf;
 pub mod client;
+pub mod cookie;
 pub mod packet_decoder;
 pub mod packet_encoder;
 pub mod server;
 pub mod slot;
+pub mod version;
 
 /// To current Minecraft protocol
 /// Don't forget to change this when porting
@@ -144,6 +149,8 @@ pub enum PacketError {
     FailedFinish,
     #[error("failed to write encoded packet to connection")]
     ConnectionWrite,
+    #[error("the client does not read its packets fast enough")]
+    QueueFull,
     #[error("packet exceeds maximum length")]
     TooLong,
     #[error("packet length is out of bounds")]
@@ -193,7 +200,8 @@ pub trait ServerPacket: Packet + Sized {
 pub struct StatusResponse {
     /// The version on which the Server is running. Optional
     pub version: Option<Version>,
-    /// Information about currently connected Players. Optional
+    /// Information about currently connected Players. Optional, clients show `???` without it
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub players: Option<Players>,
     /// The description displayed also called MOTD (Message of the day). Optional
     pub description: String,
@@ -239,8 +247,9 @@ pub struct Property {
     pub signature: Option<String>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct KnownPack<'a> {
-    pub namespace: &'a str,
-    pub id: &'a str,
-    pub version: &'a str,
+    pub namespace: Cow<'a, str>,
+    pub id: Cow<'a, str>,
+    pub version: Cow<'a, str>,
 }
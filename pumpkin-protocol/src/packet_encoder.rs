@@ -146,3 +146,66 @@ impl PacketEncoder {
         self.buf.split()
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::io::Read;
+
+    use bytes::BytesMut;
+    use flate2::{bufread::ZlibEncoder, Compression};
+    use pumpkin_config::compression::CompressionInfo;
+    use pumpkin_macros::packet;
+    use serde::Serialize;
+
+    use super::PacketEncoder;
+    use crate::VarInt;
+
+    #[derive(Serialize)]
+    #[packet(0x7F)]
+    struct TestPacket {
+        text: String,
+    }
+
+    fn encode(compression: Option<CompressionInfo>) -> BytesMut {
+        let mut encoder = PacketEncoder::default();
+        encoder.set_compression(compression);
+        encoder
+            .append_packet(&TestPacket {
+                text: "pumpkin ".repeat(100),
+            })
+            .unwrap();
+        encoder.take()
+    }
+
+    fn compression(level: u32) -> Option<CompressionInfo> {
+        Some(CompressionInfo {
+            threshold: 256,
+            level,
+        })
+    }
+
+    #[test]
+    fn test_compression_level_is_applied() {
+        let uncompressed = encode(None);
+        let mut data = &uncompressed[..];
+        VarInt::decode(&mut data).unwrap();
+
+        for level in [0, 4, 9] {
+            let packet = encode(compression(level));
+            let mut r = &packet[..];
+            VarInt::decode(&mut r).unwrap();
+            assert_eq!(VarInt::decode(&mut r).unwrap().0 as usize, data.len());
+
+            let mut expected = Vec::new();
+            ZlibEncoder::new(data, Compression::new(level))
+                .read_to_end(&mut expected)
+                .unwrap();
+            assert_eq!(r, &expected[..]);
+        }
+    }
+
+    #[test]
+    fn test_level_0_is_larger_than_level_9() {
+        assert!(encode(compression(0)).len() > encode(compression(9)).len());
+    }
+}
@@ -1,9 +1,11 @@
 mod s_acknowledge_finish_config;
 mod s_client_information;
+mod s_cookie_response;
 mod s_known_packs;
 mod s_plugin_message;
 
 pub use s_acknowledge_finish_config::*;
 pub use s_client_information::*;
+pub use s_cookie_response::*;
 pub use s_known_packs::*;
 pub use s_plugin_message::*;
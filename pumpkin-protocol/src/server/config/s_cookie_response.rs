@@ -0,0 +1,67 @@
+use bytes::Bytes;
+use pumpkin_macros::packet;
+
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    cookie::read_cookie_response,
+    Identifier, ServerPacket,
+};
+
+/// The answer to a cookie request, the payload is None when the client has no such cookie
+#[packet(0x01)]
+pub struct SCookieResponse {
+    pub key: Identifier,
+    pub payload: Option<Bytes>,
+}
+
+impl ServerPacket for SCookieResponse {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        let (key, payload) = read_cookie_response(bytebuf)?;
+        Ok(Self { key, payload })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        bytebuf::ByteBuffer, client::config::CStoreCookie, cookie::MAX_COOKIE_SIZE, ClientPacket,
+        ServerPacket,
+    };
+
+    use super::SCookieResponse;
+
+    #[test]
+    fn test_store_and_response_round_trip() {
+        let mut stored = ByteBuffer::empty();
+        CStoreCookie::new("pumpkin:session", &[1, 2, 3]).write(&mut stored);
+
+        // The client answers with the same key and payload, plus whether it has the cookie
+        let mut response = ByteBuffer::empty();
+        response.put_string(&stored.get_string().unwrap());
+        response.put_bool(true);
+        response.put_slice(&stored.get_slice());
+
+        let packet = SCookieResponse::read(&mut response).unwrap();
+        assert_eq!(packet.key, "pumpkin:session");
+        assert_eq!(packet.payload.unwrap().as_ref(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_response_without_cookie() {
+        let mut bytebuf = ByteBuffer::empty();
+        bytebuf.put_string("pumpkin:session");
+        bytebuf.put_bool(false);
+        let packet = SCookieResponse::read(&mut bytebuf).unwrap();
+        assert!(packet.payload.is_none());
+    }
+
+    #[test]
+    fn test_oversized_response() {
+        let mut bytebuf = ByteBuffer::empty();
+        bytebuf.put_string("pumpkin:session");
+        bytebuf.put_bool(true);
+        bytebuf.put_var_int(&(MAX_COOKIE_SIZE as i32 + 1).into());
+        bytebuf.put_slice(&vec![0; MAX_COOKIE_SIZE + 1]);
+        assert!(SCookieResponse::read(&mut bytebuf).is_err());
+    }
+}
@@ -1,10 +1,65 @@
 use pumpkin_macros::packet;
 
-use crate::VarInt;
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    KnownPack, ServerPacket,
+};
+
+/// Vanilla clients never send more
+const MAX_KNOWN_PACKS: i32 = 64;
 
-#[derive(serde::Deserialize)]
 #[packet(0x07)]
 pub struct SKnownPacks {
-    pub known_pack_count: VarInt,
-    // known_packs: &'a [KnownPack]
+    /// The packs the client has of those the server sent in `CKnownPacks`
+    pub known_packs: Vec<KnownPack<'static>>,
+}
+
+impl ServerPacket for SKnownPacks {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        let count = bytebuf.get_var_int()?.0;
+        if !(0..=MAX_KNOWN_PACKS).contains(&count) {
+            return Err(DeserializerError::Message(format!(
+                "{} known packs are too many",
+                count
+            )));
+        }
+        let mut known_packs = Vec::with_capacity(count as usize);
+        for _ in 0..count {
+            known_packs.push(KnownPack {
+                namespace: bytebuf.get_string()?.into(),
+                id: bytebuf.get_string()?.into(),
+                version: bytebuf.get_string()?.into(),
+            });
+        }
+        Ok(Self { known_packs })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{bytebuf::ByteBuffer, KnownPack, ServerPacket, VarInt};
+
+    use super::SKnownPacks;
+
+    #[test]
+    fn test_read_known_packs() {
+        let mut bytebuf = ByteBuffer::empty();
+        bytebuf.put_var_int(&VarInt(1));
+        bytebuf.put_string("minecraft");
+        bytebuf.put_string("core");
+        bytebuf.put_string("1.21");
+        let packet = SKnownPacks::read(&mut bytebuf).unwrap();
+        assert_eq!(
+            packet.known_packs,
+            vec![KnownPack {
+                namespace: "minecraft".into(),
+                id: "core".into(),
+                version: "1.21".into(),
+            }]
+        );
+
+        let mut bytebuf = ByteBuffer::empty();
+        bytebuf.put_var_int(&VarInt(100_000));
+        assert!(SKnownPacks::read(&mut bytebuf).is_err());
+    }
 }
@@ -18,7 +18,7 @@ impl ServerPacket for SEncryptionResponse {
         let shared_secret_length = bytebuf.get_var_int()?;
         let shared_secret = bytebuf.copy_to_bytes(shared_secret_length.0 as usize)?;
         let verify_token_length = bytebuf.get_var_int()?;
-        let verify_token = bytebuf.copy_to_bytes(shared_secret_length.0 as usize)?;
+        let verify_token = bytebuf.copy_to_bytes(verify_token_length.0 as usize)?;
         Ok(Self {
             shared_secret_length,
             shared_secret: shared_secret.to_vec(),
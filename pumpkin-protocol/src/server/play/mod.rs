@@ -1,41 +1,57 @@
+mod s_change_difficulty;
 mod s_chat_command;
 mod s_chat_message;
 mod s_click_container;
+mod s_client_command;
 mod s_client_information;
 mod s_close_container;
 mod s_confirm_teleport;
+mod s_cookie_response;
 mod s_interact;
 mod s_keep_alive;
+mod s_message_acknowledgement;
+mod s_move_vehicle;
 mod s_ping_request;
 mod s_player_action;
 mod s_player_command;
 mod s_player_ground;
+mod s_player_input;
 mod s_player_position;
 mod s_player_position_rotation;
 mod s_player_rotation;
+mod s_player_session;
 mod s_set_creative_slot;
 mod s_set_held_item;
 mod s_swing_arm;
+mod s_update_sign;
 mod s_use_item;
 mod s_use_item_on;
 
+pub use s_change_difficulty::*;
 pub use s_chat_command::*;
 pub use s_chat_message::*;
 pub use s_click_container::*;
+pub use s_client_command::*;
 pub use s_client_information::*;
 pub use s_close_container::*;
 pub use s_confirm_teleport::*;
+pub use s_cookie_response::*;
 pub use s_interact::*;
 pub use s_keep_alive::*;
+pub use s_message_acknowledgement::*;
+pub use s_move_vehicle::*;
 pub use s_ping_request::*;
 pub use s_player_action::*;
 pub use s_player_command::*;
 pub use s_player_ground::*;
+pub use s_player_input::*;
 pub use s_player_position::*;
 pub use s_player_position_rotation::*;
 pub use s_player_rotation::*;
+pub use s_player_session::*;
 pub use s_set_creative_slot::*;
 pub use s_set_held_item::*;
 pub use s_swing_arm::*;
+pub use s_update_sign::*;
 pub use s_use_item::*;
 pub use s_use_item_on::*;
@@ -0,0 +1,9 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+/// Sent when the Player changes the difficulty in the options menu, only possible for operators
+#[packet(0x02)]
+#[derive(Deserialize)]
+pub struct SChangeDifficulty {
+    pub difficulty: u8,
+}
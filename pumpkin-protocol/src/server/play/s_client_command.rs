@@ -0,0 +1,24 @@
+use num_derive::FromPrimitive;
+use pumpkin_macros::packet;
+
+use crate::{bytebuf::DeserializerError, ServerPacket, VarInt};
+
+/// Sent when the Player clicks respawn on the death screen or opens the statistics menu
+#[packet(0x09)]
+pub struct SClientCommand {
+    pub action_id: VarInt,
+}
+
+#[derive(FromPrimitive, Debug, PartialEq, Eq)]
+pub enum ClientCommandAction {
+    PerformRespawn = 0,
+    RequestStats,
+}
+
+impl ServerPacket for SClientCommand {
+    fn read(bytebuf: &mut crate::bytebuf::ByteBuffer) -> Result<Self, DeserializerError> {
+        Ok(Self {
+            action_id: bytebuf.get_var_int()?,
+        })
+    }
+}
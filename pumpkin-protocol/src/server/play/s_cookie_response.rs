@@ -0,0 +1,22 @@
+use bytes::Bytes;
+use pumpkin_macros::packet;
+
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    cookie::read_cookie_response,
+    Identifier, ServerPacket,
+};
+
+/// The answer to a cookie request, the payload is None when the client has no such cookie
+#[packet(0x11)]
+pub struct SCookieResponsePlay {
+    pub key: Identifier,
+    pub payload: Option<Bytes>,
+}
+
+impl ServerPacket for SCookieResponsePlay {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        let (key, payload) = read_cookie_response(bytebuf)?;
+        Ok(Self { key, payload })
+    }
+}
@@ -0,0 +1,26 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+use crate::VarInt;
+
+/// Tells the Server how many chat messages the Client has seen since the last acknowledgement
+#[packet(0x03)]
+#[derive(Deserialize)]
+pub struct SMessageAcknowledgement {
+    pub message_count: VarInt,
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{bytebuf::ByteBuffer, ServerPacket};
+
+    use super::SMessageAcknowledgement;
+
+    #[test]
+    fn test_read_message_acknowledgement() {
+        let mut bytebuf = ByteBuffer::empty();
+        bytebuf.put_var_int(&300.into());
+        let packet = SMessageAcknowledgement::read(&mut bytebuf).unwrap();
+        assert_eq!(packet.message_count.0, 300);
+    }
+}
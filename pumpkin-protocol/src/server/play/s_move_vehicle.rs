@@ -0,0 +1,12 @@
+use pumpkin_macros::packet;
+
+/// Sent by the Player steering a vehicle, like a boat
+#[derive(serde::Deserialize)]
+#[packet(0x1E)]
+pub struct SMoveVehicle {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub yaw: f32,
+    pub pitch: f32,
+}
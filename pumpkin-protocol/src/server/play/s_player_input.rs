@@ -0,0 +1,20 @@
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+/// The movement keys of a Player riding a vehicle
+#[packet(0x26)]
+#[derive(Deserialize)]
+pub struct SPlayerInput {
+    pub sideways: f32,
+    pub forward: f32,
+    pub flags: u8,
+}
+
+impl SPlayerInput {
+    pub const JUMP: u8 = 0x01;
+    pub const UNMOUNT: u8 = 0x02;
+
+    pub fn unmount(&self) -> bool {
+        self.flags & Self::UNMOUNT != 0
+    }
+}
@@ -0,0 +1,75 @@
+use bytes::Bytes;
+use pumpkin_macros::packet;
+
+use crate::{
+    bytebuf::{ByteBuffer, DeserializerError},
+    ServerPacket,
+};
+
+/// Sent by 1.19+ clients after joining to start a signed chat session
+#[packet(0x07)]
+pub struct SPlayerSession {
+    pub session_id: uuid::Uuid,
+    pub expires_at: i64,
+    pub public_key: Bytes,
+    pub key_signature: Bytes,
+}
+
+const MAX_PUBLIC_KEY_LENGTH: usize = 512;
+const MAX_KEY_SIGNATURE_LENGTH: usize = 4096;
+
+fn get_byte_array(bytebuf: &mut ByteBuffer, max_length: usize) -> Result<Bytes, DeserializerError> {
+    let length = bytebuf.get_var_int()?.0;
+    if length < 0 || length as usize > max_length {
+        return Err(DeserializerError::Message(format!(
+            "byte array length {} is not in 0..={}",
+            length, max_length
+        )));
+    }
+    bytebuf.copy_to_bytes(length as usize)
+}
+
+impl ServerPacket for SPlayerSession {
+    fn read(bytebuf: &mut ByteBuffer) -> Result<Self, DeserializerError> {
+        Ok(Self {
+            session_id: bytebuf.get_uuid()?,
+            expires_at: bytebuf.get_i64()?,
+            public_key: get_byte_array(bytebuf, MAX_PUBLIC_KEY_LENGTH)?,
+            key_signature: get_byte_array(bytebuf, MAX_KEY_SIGNATURE_LENGTH)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{bytebuf::ByteBuffer, ServerPacket};
+
+    use super::SPlayerSession;
+
+    #[test]
+    fn test_read_player_session() {
+        let session_id = uuid::Uuid::new_v4();
+        let mut bytebuf = ByteBuffer::empty();
+        bytebuf.put_uuid(&session_id);
+        bytebuf.put_i64(1_700_000_000_000);
+        bytebuf.put_var_int(&3.into());
+        bytebuf.put_slice(&[1, 2, 3]);
+        bytebuf.put_var_int(&2.into());
+        bytebuf.put_slice(&[4, 5]);
+
+        let packet = SPlayerSession::read(&mut bytebuf).unwrap();
+        assert_eq!(packet.session_id, session_id);
+        assert_eq!(packet.expires_at, 1_700_000_000_000);
+        assert_eq!(packet.public_key.as_ref(), &[1, 2, 3]);
+        assert_eq!(packet.key_signature.as_ref(), &[4, 5]);
+    }
+
+    #[test]
+    fn test_read_player_session_oversized_key() {
+        let mut bytebuf = ByteBuffer::empty();
+        bytebuf.put_uuid(&uuid::Uuid::new_v4());
+        bytebuf.put_i64(0);
+        bytebuf.put_var_int(&1000.into());
+        assert!(SPlayerSession::read(&mut bytebuf).is_err());
+    }
+}
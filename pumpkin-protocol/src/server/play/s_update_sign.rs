@@ -0,0 +1,12 @@
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_macros::packet;
+use serde::Deserialize;
+
+/// Sent when the Player is done editing a sign
+#[derive(Deserialize)]
+#[packet(0x35)]
+pub struct SUpdateSign {
+    pub location: WorldPosition,
+    pub is_front_text: bool,
+    pub lines: [String; 4],
+}
@@ -1,19 +1,142 @@
 use crate::VarInt;
-use pumpkin_world::item::ItemStack;
+use pumpkin_core::text::TextComponent;
+use pumpkin_world::item::{ItemComponents, ItemStack};
 use serde::ser::SerializeSeq;
 use serde::{
     de::{self, SeqAccess},
     Deserialize, Serialize, Serializer,
 };
 
+/// The ids of the data components in the `minecraft:data_component_type` registry
+const CUSTOM_NAME_COMPONENT: i32 = 5;
+const LORE_COMPONENT: i32 = 7;
+const ENCHANTMENTS_COMPONENT: i32 = 9;
+
+/// The client rejects more lore lines than this
+const MAX_LORE_LINES: i32 = 256;
+
+// NBT tag ids, used to read text components
+const TAG_END: u8 = 0;
+const TAG_BYTE: u8 = 1;
+const TAG_SHORT: u8 = 2;
+const TAG_INT: u8 = 3;
+const TAG_LONG: u8 = 4;
+const TAG_FLOAT: u8 = 5;
+const TAG_DOUBLE: u8 = 6;
+const TAG_BYTE_ARRAY: u8 = 7;
+const TAG_STRING: u8 = 8;
+const TAG_LIST: u8 = 9;
+const TAG_COMPOUND: u8 = 10;
+const TAG_INT_ARRAY: u8 = 11;
+const TAG_LONG_ARRAY: u8 = 12;
+
+/// Nested compounds and lists deeper than this are rejected, like vanilla does
+const MAX_NBT_DEPTH: usize = 512;
+
 #[derive(Debug, Clone)]
 pub struct Slot {
     item_count: VarInt,
     item_id: Option<VarInt>,
-    num_components_to_add: Option<VarInt>,
-    num_components_to_remove: Option<VarInt>,
-    components_to_add: Option<Vec<(VarInt, ())>>, // The second type depends on the varint
-    components_to_remove: Option<Vec<VarInt>>,
+    components: ItemComponents,
+}
+
+fn next<'de, T: Deserialize<'de>, A: SeqAccess<'de>>(seq: &mut A) -> Result<T, A::Error> {
+    seq.next_element::<T>()?
+        .ok_or(de::Error::custom("Unexpected end of slot"))
+}
+
+fn read_length<'de, A: SeqAccess<'de>>(seq: &mut A, max: i32) -> Result<i32, A::Error> {
+    let length = next::<VarInt, _>(seq)?.0;
+    if !(0..=max).contains(&length) {
+        return Err(de::Error::custom("Invalid slot component length"));
+    }
+    Ok(length)
+}
+
+fn read_nbt_string<'de, A: SeqAccess<'de>>(seq: &mut A) -> Result<String, A::Error> {
+    let length = next::<u16, _>(seq)?;
+    let mut bytes = Vec::with_capacity(length as usize);
+    for _ in 0..length {
+        bytes.push(next::<u8, _>(seq)?);
+    }
+    // Java uses modified UTF-8, which only differs for null and supplementary characters
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn skip_nbt_array<'de, A: SeqAccess<'de>>(
+    seq: &mut A,
+    element_size: usize,
+) -> Result<(), A::Error> {
+    let length = next::<i32, _>(seq)?;
+    if length < 0 {
+        return Err(de::Error::custom("Negative NBT array length"));
+    }
+    for _ in 0..length as usize * element_size {
+        next::<u8, _>(seq)?;
+    }
+    Ok(())
+}
+
+fn skip_nbt_payload<'de, A: SeqAccess<'de>>(
+    seq: &mut A,
+    tag: u8,
+    depth: usize,
+) -> Result<(), A::Error> {
+    if depth > MAX_NBT_DEPTH {
+        return Err(de::Error::custom("NBT is nested too deep"));
+    }
+    match tag {
+        TAG_BYTE => next::<u8, _>(seq).map(|_| ()),
+        TAG_SHORT => next::<i16, _>(seq).map(|_| ()),
+        TAG_INT => next::<i32, _>(seq).map(|_| ()),
+        TAG_LONG => next::<i64, _>(seq).map(|_| ()),
+        TAG_FLOAT => next::<f32, _>(seq).map(|_| ()),
+        TAG_DOUBLE => next::<f64, _>(seq).map(|_| ()),
+        TAG_BYTE_ARRAY => skip_nbt_array(seq, 1),
+        TAG_STRING => read_nbt_string(seq).map(|_| ()),
+        TAG_LIST => {
+            let element_tag = next::<u8, _>(seq)?;
+            let length = next::<i32, _>(seq)?;
+            for _ in 0..length.max(0) {
+                skip_nbt_payload(seq, element_tag, depth + 1)?;
+            }
+            Ok(())
+        }
+        TAG_COMPOUND => loop {
+            let tag = next::<u8, _>(seq)?;
+            if tag == TAG_END {
+                return Ok(());
+            }
+            read_nbt_string(seq)?;
+            skip_nbt_payload(seq, tag, depth + 1)?;
+        },
+        TAG_INT_ARRAY => skip_nbt_array(seq, 4),
+        TAG_LONG_ARRAY => skip_nbt_array(seq, 8),
+        _ => Err(de::Error::custom("Invalid NBT tag")),
+    }
+}
+
+/// Reads a text component sent as network NBT, only the plain text is kept
+fn read_text<'de, A: SeqAccess<'de>>(seq: &mut A) -> Result<String, A::Error> {
+    match next::<u8, _>(seq)? {
+        TAG_STRING => read_nbt_string(seq),
+        TAG_COMPOUND => {
+            let mut text = String::new();
+            loop {
+                let tag = next::<u8, _>(seq)?;
+                if tag == TAG_END {
+                    return Ok(text);
+                }
+                let name = read_nbt_string(seq)?;
+                if tag == TAG_STRING && name == "text" {
+                    text = read_nbt_string(seq)?;
+                } else {
+                    skip_nbt_payload(seq, tag, 1)?;
+                }
+            }
+        }
+        _ => Err(de::Error::custom("Invalid text component")),
+    }
 }
 
 impl<'de> Deserialize<'de> for Slot {
@@ -33,41 +156,53 @@ impl<'de> Deserialize<'de> for Slot {
             where
                 A: SeqAccess<'de>,
             {
-                let item_count = seq
-                    .next_element::<VarInt>()?
-                    .ok_or(de::Error::custom("Failed to decode VarInt"))?;
+                let item_count = next::<VarInt, _>(&mut seq)?;
                 if item_count.0 == 0 {
-                    return Ok(Slot {
-                        item_count: 0.into(),
-                        item_id: None,
-                        num_components_to_add: None,
-                        num_components_to_remove: None,
-                        components_to_add: None,
-                        components_to_remove: None,
-                    });
+                    return Ok(Slot::empty());
                 }
-                let item_id = seq
-                    .next_element::<VarInt>()?
-                    .ok_or(de::Error::custom("Failed to decode VarInt"))?;
-                let num_components_to_add = seq
-                    .next_element::<VarInt>()?
-                    .ok_or(de::Error::custom("Failed to decode VarInt"))?;
-                let num_components_to_remove = seq
-                    .next_element::<VarInt>()?
-                    .ok_or(de::Error::custom("Failed to decode VarInt"))?;
-                if num_components_to_add.0 != 0 || num_components_to_remove.0 != 0 {
+                let item_id = next::<VarInt, _>(&mut seq)?;
+                let num_components_to_add = next::<VarInt, _>(&mut seq)?;
+                let num_components_to_remove = next::<VarInt, _>(&mut seq)?;
+                if num_components_to_remove.0 != 0 {
                     return Err(de::Error::custom(
-                        "Slot components are currently unsupported",
+                        "Removing slot components is currently unsupported",
                     ));
                 }
 
+                let mut components = ItemComponents::default();
+                for _ in 0..num_components_to_add.0 {
+                    match next::<VarInt, _>(&mut seq)?.0 {
+                        CUSTOM_NAME_COMPONENT => {
+                            components.custom_name = Some(read_text(&mut seq)?);
+                        }
+                        LORE_COMPONENT => {
+                            let lines = read_length(&mut seq, MAX_LORE_LINES)?;
+                            components.lore = (0..lines)
+                                .map(|_| read_text(&mut seq))
+                                .collect::<Result<_, _>>()?;
+                        }
+                        ENCHANTMENTS_COMPONENT => {
+                            let count = read_length(&mut seq, i32::MAX)?;
+                            for _ in 0..count {
+                                let id = next::<VarInt, _>(&mut seq)?.0;
+                                let level = next::<VarInt, _>(&mut seq)?.0;
+                                components.enchantments.push((id, level));
+                            }
+                            // Show in tooltip
+                            next::<bool, _>(&mut seq)?;
+                        }
+                        _ => {
+                            return Err(de::Error::custom(
+                                "Slot component is currently unsupported",
+                            ))
+                        }
+                    }
+                }
+
                 Ok(Slot {
                     item_count,
                     item_id: Some(item_id),
-                    num_components_to_add: Some(num_components_to_add),
-                    num_components_to_remove: Some(num_components_to_remove),
-                    components_to_add: None,
-                    components_to_remove: None,
+                    components,
                 })
             }
         }
@@ -81,59 +216,60 @@ impl Serialize for Slot {
     where
         S: Serializer,
     {
-        if self.item_count == 0.into() {
+        let Some(item_id) = &self.item_id else {
+            let mut s = serializer.serialize_seq(Some(1))?;
+            s.serialize_element(&VarInt(0))?;
+            return s.end();
+        };
+        if self.item_count.0 == 0 {
             let mut s = serializer.serialize_seq(Some(1))?;
             s.serialize_element(&self.item_count)?;
-            s.end()
-        } else {
-            match (&self.num_components_to_add, &self.num_components_to_remove) {
-                (Some(to_add), Some(to_remove)) => {
-                    let mut s = serializer.serialize_seq(Some(6))?;
-                    s.serialize_element(&self.item_count)?;
-                    s.serialize_element(self.item_id.as_ref().unwrap())?;
-                    s.serialize_element(to_add)?;
-                    s.serialize_element(to_remove)?;
-                    s.serialize_element(self.components_to_add.as_ref().unwrap())?;
-                    s.serialize_element(self.components_to_remove.as_ref().unwrap())?;
-                    s.end()
-                }
-                (None, Some(to_remove)) => {
-                    let mut s = serializer.serialize_seq(Some(5))?;
-                    s.serialize_element(&self.item_count)?;
-                    s.serialize_element(self.item_id.as_ref().unwrap())?;
-                    s.serialize_element(&VarInt(0))?;
-                    s.serialize_element(to_remove)?;
-                    s.serialize_element(self.components_to_remove.as_ref().unwrap())?;
-                    s.end()
-                }
-                (Some(to_add), None) => {
-                    let mut s = serializer.serialize_seq(Some(5))?;
-                    s.serialize_element(&self.item_count)?;
-                    s.serialize_element(self.item_id.as_ref().unwrap())?;
-                    s.serialize_element(to_add)?;
-                    s.serialize_element(&VarInt(0))?;
-                    s.serialize_element(self.components_to_add.as_ref().unwrap())?;
-                    s.end()
-                }
-                (None, None) => {
-                    let mut s = serializer.serialize_seq(Some(4))?;
-                    s.serialize_element(&self.item_count)?;
-                    s.serialize_element(&self.item_id.as_ref().unwrap())?;
-                    s.serialize_element(&VarInt(0))?;
-                    s.serialize_element(&VarInt(0))?;
-                    s.end()
-                }
+            return s.end();
+        }
+
+        let components = &self.components;
+        let num_components_to_add = components.custom_name.is_some() as i32
+            + !components.lore.is_empty() as i32
+            + !components.enchantments.is_empty() as i32;
+
+        let mut s = serializer.serialize_seq(None)?;
+        s.serialize_element(&self.item_count)?;
+        s.serialize_element(item_id)?;
+        s.serialize_element(&VarInt(num_components_to_add))?;
+        // Components to remove
+        s.serialize_element(&VarInt(0))?;
+        if let Some(custom_name) = &components.custom_name {
+            s.serialize_element(&VarInt(CUSTOM_NAME_COMPONENT))?;
+            s.serialize_element(&TextComponent::text(custom_name))?;
+        }
+        if !components.lore.is_empty() {
+            s.serialize_element(&VarInt(LORE_COMPONENT))?;
+            s.serialize_element(&VarInt(components.lore.len() as i32))?;
+            for line in &components.lore {
+                s.serialize_element(&TextComponent::text(line))?;
+            }
+        }
+        if !components.enchantments.is_empty() {
+            s.serialize_element(&VarInt(ENCHANTMENTS_COMPONENT))?;
+            s.serialize_element(&VarInt(components.enchantments.len() as i32))?;
+            for (id, level) in &components.enchantments {
+                s.serialize_element(&VarInt(*id))?;
+                s.serialize_element(&VarInt(*level))?;
             }
+            // Show in tooltip
+            s.serialize_element(&true)?;
         }
+        s.end()
     }
 }
 
 impl Slot {
     pub fn to_item(self) -> Option<ItemStack> {
-        let item_id = self.item_id?.0.try_into().unwrap();
+        let item_id = self.item_id?.0.try_into().ok()?;
         Some(ItemStack {
             item_id,
-            item_count: self.item_count.0.try_into().unwrap(),
+            item_count: self.item_count.0.try_into().ok()?,
+            components: self.components,
         })
     }
 
@@ -141,10 +277,7 @@ impl Slot {
         Slot {
             item_count: VarInt(0),
             item_id: None,
-            num_components_to_add: None,
-            num_components_to_remove: None,
-            components_to_add: None,
-            components_to_remove: None,
+            components: ItemComponents::new(),
         }
     }
 }
@@ -154,11 +287,7 @@ impl From<&ItemStack> for Slot {
         Slot {
             item_count: item.item_count.into(),
             item_id: Some(item.item_id.into()),
-            // TODO: add these
-            num_components_to_add: None,
-            num_components_to_remove: None,
-            components_to_add: None,
-            components_to_remove: None,
+            components: item.components.clone(),
         }
     }
 }
@@ -168,3 +297,57 @@ impl From<Option<&ItemStack>> for Slot {
         item.map(Slot::from).unwrap_or(Slot::empty())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use pumpkin_world::item::ItemStack;
+
+    use crate::{
+        bytebuf::ByteBuffer, client::play::CSetContainerSlot, server::play::SSetCreativeSlot,
+        ClientPacket, ServerPacket,
+    };
+
+    use super::Slot;
+
+    /// Writes the slot like the server would and reads it back like a client sent it
+    fn round_trip(slot: &Slot) -> Slot {
+        let mut bytebuf = ByteBuffer::empty();
+        CSetContainerSlot::new(0, 1, 36, slot).write(&mut bytebuf);
+        // Window id and state id, the rest is laid out like a creative slot action
+        bytebuf.get_i8().unwrap();
+        bytebuf.get_var_int().unwrap();
+        let packet = SSetCreativeSlot::read(&mut bytebuf).unwrap();
+        assert_eq!(packet.slot, 36);
+        assert!(bytebuf.buf().is_empty());
+        packet.clicked_item
+    }
+
+    #[test]
+    fn test_enchanted_named_item() {
+        let mut item = ItemStack::new(1, 842);
+        item.components.custom_name = Some("Excalibur".to_string());
+        item.components.lore = vec!["Pulled out of a stone".to_string(), "Sharp".to_string()];
+        item.components.enchantments = vec![(13, 5), (22, 3)];
+
+        let read = round_trip(&Slot::from(&item)).to_item().unwrap();
+        assert_eq!(read.item_id, 842);
+        assert_eq!(read.item_count, 1);
+        assert_eq!(read.components, item.components);
+    }
+
+    #[test]
+    fn test_plain_and_empty_slots() {
+        let item = ItemStack::new(64, 1);
+        let slot = Slot::from(&item);
+        let mut bytebuf = ByteBuffer::empty();
+        CSetContainerSlot::new(0, 1, 36, &slot).write(&mut bytebuf);
+        // No components to add or remove
+        assert_eq!(&bytebuf.buf()[bytebuf.buf().len() - 2..], &[0, 0]);
+
+        let read = round_trip(&slot).to_item().unwrap();
+        assert!(read.components.is_empty());
+        assert_eq!(read.item_count, 64);
+
+        assert!(round_trip(&Slot::empty()).to_item().is_none());
+    }
+}
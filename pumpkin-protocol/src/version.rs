@@ -0,0 +1,164 @@
+use crate::{
+    bytebuf::packet_id::Packet,
+    server::{
+        handshake::SHandShake,
+        login::{SEncryptionResponse, SLoginAcknowledged, SLoginPluginResponse, SLoginStart},
+        status::{SStatusPingRequest, SStatusRequest},
+    },
+    ConnectionState, CURRENT_MC_PROTOCOL,
+};
+
+/// A protocol version Clients can connect with, every version may use other packet ids
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ProtocolVersion {
+    /// 1.20.5 and 1.20.6
+    V1_20_5,
+    /// 1.21 and 1.21.1
+    #[default]
+    V1_21,
+}
+
+impl ProtocolVersion {
+    /// The version the packets of this crate are written for
+    pub const CURRENT: Self = Self::V1_21;
+
+    /// The version of a protocol number, `None` if we have no packet ids for it
+    pub fn from_protocol(protocol: i32) -> Option<Self> {
+        match protocol {
+            766 => Some(Self::V1_20_5),
+            767 => Some(Self::V1_21),
+            _ => None,
+        }
+    }
+
+    pub fn protocol(self) -> u32 {
+        match self {
+            Self::V1_20_5 => 766,
+            Self::V1_21 => CURRENT_MC_PROTOCOL,
+        }
+    }
+
+    /// The packet ids this version uses
+    pub fn packet_ids(self) -> &'static PacketIds {
+        match self {
+            Self::V1_20_5 => &V1_20_5_IDS,
+            Self::V1_21 => &V1_21_IDS,
+        }
+    }
+}
+
+/// Maps the ids a version uses for server bound packets to the ids of `ProtocolVersion::CURRENT`,
+/// which packets are matched against.
+///
+/// Only the Handshake, Status and Login states have tables yet, the later states are only understood in the current version
+pub struct PacketIds {
+    pub version: ProtocolVersion,
+    /// Pairs of the id in this version and the current one
+    pub handshake: &'static [(i32, i32)],
+    pub status: &'static [(i32, i32)],
+    pub login: &'static [(i32, i32)],
+}
+
+impl PacketIds {
+    /// The current id of a packet the Client sent in `state`, `None` if this version has no such packet
+    pub fn server_packet_id(&self, state: ConnectionState, id: i32) -> Option<i32> {
+        let table = match state {
+            ConnectionState::HandShake => self.handshake,
+            ConnectionState::Status => self.status,
+            ConnectionState::Login | ConnectionState::Transfer => self.login,
+            ConnectionState::Config | ConnectionState::Play => {
+                return (self.version == ProtocolVersion::CURRENT).then_some(id);
+            }
+        };
+        table
+            .iter()
+            .find(|(theirs, _)| *theirs == id)
+            .map(|(_, current)| *current)
+    }
+}
+
+const HANDSHAKE_IDS: &[(i32, i32)] = &[(0x00, SHandShake::PACKET_ID)];
+
+const STATUS_IDS: &[(i32, i32)] = &[
+    (0x00, SStatusRequest::PACKET_ID),
+    (0x01, SStatusPingRequest::PACKET_ID),
+];
+
+const LOGIN_IDS: &[(i32, i32)] = &[
+    (0x00, SLoginStart::PACKET_ID),
+    (0x01, SEncryptionResponse::PACKET_ID),
+    (0x02, SLoginPluginResponse::PACKET_ID),
+    (0x03, SLoginAcknowledged::PACKET_ID),
+];
+
+// These states did not change between 1.20.5 and 1.21
+static V1_20_5_IDS: PacketIds = PacketIds {
+    version: ProtocolVersion::V1_20_5,
+    handshake: HANDSHAKE_IDS,
+    status: STATUS_IDS,
+    login: LOGIN_IDS,
+};
+
+static V1_21_IDS: PacketIds = PacketIds {
+    version: ProtocolVersion::V1_21,
+    handshake: HANDSHAKE_IDS,
+    status: STATUS_IDS,
+    login: LOGIN_IDS,
+};
+
+#[cfg(test)]
+mod test {
+    use bytes::BytesMut;
+
+    use super::ProtocolVersion;
+    use crate::{
+        bytebuf::{packet_id::Packet, ByteBuffer},
+        server::{login::SLoginStart, status::SStatusRequest},
+        ConnectionState, ServerPacket, CURRENT_MC_PROTOCOL,
+    };
+
+    #[test]
+    fn test_versions() {
+        assert_eq!(
+            ProtocolVersion::CURRENT.protocol(),
+            CURRENT_MC_PROTOCOL,
+            "Don't forget to add the new version"
+        );
+        for version in [ProtocolVersion::V1_20_5, ProtocolVersion::V1_21] {
+            assert_eq!(
+                ProtocolVersion::from_protocol(version.protocol() as i32),
+                Some(version)
+            );
+            assert_eq!(version.packet_ids().version, version);
+        }
+        assert_eq!(ProtocolVersion::from_protocol(47), None);
+    }
+
+    #[test]
+    fn test_later_states_need_current_version() {
+        let old = ProtocolVersion::V1_20_5.packet_ids();
+        let current = ProtocolVersion::CURRENT.packet_ids();
+        assert_eq!(old.server_packet_id(ConnectionState::Play, 0x12), None);
+        assert_eq!(
+            current.server_packet_id(ConnectionState::Play, 0x12),
+            Some(0x12)
+        );
+        assert_eq!(
+            old.server_packet_id(ConnectionState::Login, 0x00),
+            Some(SLoginStart::PACKET_ID)
+        );
+        assert_eq!(old.server_packet_id(ConnectionState::Login, 0x7f), None);
+    }
+
+    #[test]
+    fn test_status_request_on_every_version() {
+        for version in [ProtocolVersion::V1_20_5, ProtocolVersion::V1_21] {
+            let id = version
+                .packet_ids()
+                .server_packet_id(ConnectionState::Status, 0x00);
+            assert_eq!(id, Some(SStatusRequest::PACKET_ID), "{:?}", version);
+            let mut bytebuf = ByteBuffer::new(BytesMut::new());
+            assert!(SStatusRequest::read(&mut bytebuf).is_ok());
+        }
+    }
+}
@@ -1,7 +1,9 @@
 use pumpkin_protocol::VarInt;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize)]
+use crate::byte_bool;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BiomeCodec {
     name: String,
     id: i32,
@@ -18,16 +20,17 @@ impl Default for BiomeCodec {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Biome {
-    has_precipitation: i8,
+    #[serde(deserialize_with = "byte_bool")]
+    has_precipitation: u8,
     temperature: f32,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature_modifier: Option<String>,
     downfall: f32,
     effects: BiomeEffects,
 }
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct BiomeEffects {
     fog_color: i32,
     water_color: i32,
@@ -51,20 +54,21 @@ struct BiomeEffects {
     music: Option<Music>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Particle {
     options: ParticleOptions,
     probability: f32,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct ParticleOptions {
+    #[serde(rename = "type")]
     typee: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     value: Option<VarInt>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct MoodSound {
     block_search_extent: i32,
     offset: f64,
@@ -72,18 +76,19 @@ struct MoodSound {
     tick_delay: i32,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct AdditionsSound {
     sound: String,
     tick_chance: f64,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Music {
     sound: String,
     min_delay: i32,
     max_delay: i32,
-    replace_current_music: i8,
+    #[serde(deserialize_with = "byte_bool")]
+    replace_current_music: u8,
 }
 
 // 1.20.6 default https://gist.github.com/WinX64/ab8c7a8df797c273b32d3a3b66522906
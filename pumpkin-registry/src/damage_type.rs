@@ -67,7 +67,7 @@ pub(super) fn entries() -> Vec<RegistryEntry<'static>> {
     let items: Vec<_> = NAMES
         .iter()
         .map(|name| RegistryEntry {
-            entry_id: name,
+            entry_id: (*name).into(),
             data: fastnbt::to_bytes_with_opts(
                 &DamageType {
                     exhaustion: 0.1,
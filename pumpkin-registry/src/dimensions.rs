@@ -1,15 +1,21 @@
 use serde::{Deserialize, Serialize};
 
+use crate::byte_bool;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Dimension {
     ambient_light: f32,
+    #[serde(deserialize_with = "byte_bool")]
     bed_works: u8,
     coordinate_scale: f64,
     effects: DimensionEffects,
     #[serde(skip_serializing_if = "Option::is_none")]
     fixed_time: Option<i64>,
+    #[serde(deserialize_with = "byte_bool")]
     has_ceiling: u8,
+    #[serde(deserialize_with = "byte_bool")]
     has_raids: u8,
+    #[serde(deserialize_with = "byte_bool")]
     has_skylight: u8,
     height: i32,
     infiniburn: String,
@@ -17,9 +23,13 @@ pub struct Dimension {
     min_y: i32,
     monster_spawn_block_light_limit: i32,
     monster_spawn_light_level: MonsterSpawnLightLevel,
+    #[serde(deserialize_with = "byte_bool")]
     natural: u8,
+    #[serde(deserialize_with = "byte_bool")]
     piglin_safe: u8,
+    #[serde(deserialize_with = "byte_bool")]
     respawn_anchor_works: u8,
+    #[serde(deserialize_with = "byte_bool")]
     ultrawarm: u8,
 }
 
@@ -67,7 +77,7 @@ pub enum MonsterSpawnLightLevel {
 }
 
 #[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize)]
-#[serde(tag = "type", content = "value")]
+#[serde(tag = "type")]
 pub enum MonsterSpawnLightLevelTagged {
     #[serde(rename = "minecraft:uniform")]
     Uniform {
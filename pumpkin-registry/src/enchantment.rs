@@ -0,0 +1,289 @@
+use fastnbt::SerOpts;
+use pumpkin_protocol::client::config::RegistryEntry;
+use serde::Serialize;
+
+/// The items an enchantment can be put on, like vanilla's `enchantable` item tags
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnchantmentTarget {
+    Armor,
+    HeadArmor,
+    LegArmor,
+    FootArmor,
+    Sword,
+    /// Swords and axes
+    SharpWeapon,
+    FireAspect,
+    /// Tools and shears
+    Mining,
+    /// Only the tools which drop something different
+    MiningLoot,
+    Bow,
+    Crossbow,
+    Trident,
+    FishingRod,
+    Mace,
+    /// Everything which can break
+    Durability,
+    /// Everything which can be worn
+    Equippable,
+    Vanishing,
+}
+
+fn is_armor(name: &str) -> bool {
+    ["_helmet", "_chestplate", "_leggings", "_boots"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+fn is_tool(name: &str) -> bool {
+    ["_pickaxe", "_axe", "_shovel", "_hoe"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+fn is_head(name: &str) -> bool {
+    name.ends_with("_head") || name.ends_with("_skull")
+}
+
+fn is_damageable(name: &str) -> bool {
+    is_armor(name)
+        || is_tool(name)
+        || name.ends_with("_sword")
+        || matches!(
+            name,
+            "bow"
+                | "crossbow"
+                | "trident"
+                | "fishing_rod"
+                | "shears"
+                | "flint_and_steel"
+                | "shield"
+                | "elytra"
+                | "carrot_on_a_stick"
+                | "warped_fungus_on_a_stick"
+                | "brush"
+                | "mace"
+        )
+}
+
+impl EnchantmentTarget {
+    /// Whether the item, e.g. `minecraft:diamond_sword`, can get the enchantment
+    pub fn supports(self, item: &str) -> bool {
+        let name = item.strip_prefix("minecraft:").unwrap_or(item);
+        match self {
+            Self::Armor => is_armor(name),
+            Self::HeadArmor => name.ends_with("_helmet"),
+            Self::LegArmor => name.ends_with("_leggings"),
+            Self::FootArmor => name.ends_with("_boots"),
+            Self::Sword => name.ends_with("_sword"),
+            Self::SharpWeapon => name.ends_with("_sword") || name.ends_with("_axe"),
+            Self::FireAspect => name.ends_with("_sword") || name == "mace",
+            Self::Mining => is_tool(name) || name == "shears",
+            Self::MiningLoot => is_tool(name),
+            Self::Bow => name == "bow",
+            Self::Crossbow => name == "crossbow",
+            Self::Trident => name == "trident",
+            Self::FishingRod => name == "fishing_rod",
+            Self::Mace => name == "mace",
+            Self::Durability => is_damageable(name),
+            Self::Equippable => {
+                is_armor(name) || is_head(name) || matches!(name, "elytra" | "carved_pumpkin")
+            }
+            Self::Vanishing => {
+                is_damageable(name)
+                    || is_head(name)
+                    || matches!(name, "compass" | "recovery_compass" | "carved_pumpkin")
+            }
+        }
+    }
+
+    fn tag(self) -> &'static str {
+        match self {
+            Self::Armor => "#minecraft:enchantable/armor",
+            Self::HeadArmor => "#minecraft:enchantable/head_armor",
+            Self::LegArmor => "#minecraft:enchantable/leg_armor",
+            Self::FootArmor => "#minecraft:enchantable/foot_armor",
+            Self::Sword => "#minecraft:enchantable/sword",
+            Self::SharpWeapon => "#minecraft:enchantable/sharp_weapon",
+            Self::FireAspect => "#minecraft:enchantable/fire_aspect",
+            Self::Mining => "#minecraft:enchantable/mining",
+            Self::MiningLoot => "#minecraft:enchantable/mining_loot",
+            Self::Bow => "#minecraft:enchantable/bow",
+            Self::Crossbow => "#minecraft:enchantable/crossbow",
+            Self::Trident => "#minecraft:enchantable/trident",
+            Self::FishingRod => "#minecraft:enchantable/fishing",
+            Self::Mace => "#minecraft:enchantable/mace",
+            Self::Durability => "#minecraft:enchantable/durability",
+            Self::Equippable => "#minecraft:enchantable/equippable",
+            Self::Vanishing => "#minecraft:enchantable/vanishing",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Enchantment {
+    pub name: &'static str,
+    pub max_level: i32,
+    pub target: EnchantmentTarget,
+    /// The enchantments which can't be on the same item
+    pub exclusive_with: &'static [&'static str],
+}
+
+impl Enchantment {
+    /// Like vanilla, an enchantment is also not compatible with itself
+    pub fn is_compatible_with(&self, other: &Enchantment) -> bool {
+        self.name != other.name
+            && !self.exclusive_with.contains(&other.name)
+            && !other.exclusive_with.contains(&self.name)
+    }
+}
+
+const ARMOR_SET: &[&str] = &[
+    "protection",
+    "blast_protection",
+    "fire_protection",
+    "projectile_protection",
+];
+const BOOTS_SET: &[&str] = &["depth_strider", "frost_walker"];
+const BOW_SET: &[&str] = &["infinity", "mending"];
+const CROSSBOW_SET: &[&str] = &["multishot", "piercing"];
+const DAMAGE_SET: &[&str] = &[
+    "sharpness",
+    "smite",
+    "bane_of_arthropods",
+    "impaling",
+    "density",
+    "breach",
+];
+const MINING_SET: &[&str] = &["fortune", "silk_touch"];
+
+const fn enchantment(
+    name: &'static str,
+    max_level: i32,
+    target: EnchantmentTarget,
+    exclusive_with: &'static [&'static str],
+) -> Enchantment {
+    Enchantment {
+        name,
+        max_level,
+        target,
+        exclusive_with,
+    }
+}
+
+/// Sorted by name like vanilla's registry, the index is the registry id
+pub const ENCHANTMENTS: [Enchantment; 42] = {
+    use EnchantmentTarget::*;
+    [
+        enchantment("aqua_affinity", 1, HeadArmor, &[]),
+        enchantment("bane_of_arthropods", 5, SharpWeapon, DAMAGE_SET),
+        enchantment("binding_curse", 1, Equippable, &[]),
+        enchantment("blast_protection", 4, Armor, ARMOR_SET),
+        enchantment("breach", 4, Mace, DAMAGE_SET),
+        enchantment("channeling", 1, Trident, &["riptide"]),
+        enchantment("density", 5, Mace, DAMAGE_SET),
+        enchantment("depth_strider", 3, FootArmor, BOOTS_SET),
+        enchantment("efficiency", 5, Mining, &[]),
+        enchantment("feather_falling", 4, FootArmor, &[]),
+        enchantment("fire_aspect", 2, FireAspect, &[]),
+        enchantment("fire_protection", 4, Armor, ARMOR_SET),
+        enchantment("flame", 1, Bow, &[]),
+        enchantment("fortune", 3, MiningLoot, MINING_SET),
+        enchantment("frost_walker", 2, FootArmor, BOOTS_SET),
+        enchantment("impaling", 5, Trident, DAMAGE_SET),
+        enchantment("infinity", 1, Bow, BOW_SET),
+        enchantment("knockback", 2, Sword, &[]),
+        enchantment("looting", 3, Sword, &[]),
+        enchantment("loyalty", 3, Trident, &["riptide"]),
+        enchantment("luck_of_the_sea", 3, FishingRod, &[]),
+        enchantment("lure", 3, FishingRod, &[]),
+        enchantment("mending", 1, Durability, BOW_SET),
+        enchantment("multishot", 1, Crossbow, CROSSBOW_SET),
+        enchantment("piercing", 4, Crossbow, CROSSBOW_SET),
+        enchantment("power", 5, Bow, &[]),
+        enchantment("projectile_protection", 4, Armor, ARMOR_SET),
+        enchantment("protection", 4, Armor, ARMOR_SET),
+        enchantment("punch", 2, Bow, &[]),
+        enchantment("quick_charge", 3, Crossbow, &[]),
+        enchantment("respiration", 3, HeadArmor, &[]),
+        enchantment("riptide", 3, Trident, &["channeling", "loyalty"]),
+        enchantment("sharpness", 5, SharpWeapon, DAMAGE_SET),
+        enchantment("silk_touch", 1, MiningLoot, MINING_SET),
+        enchantment("smite", 5, SharpWeapon, DAMAGE_SET),
+        enchantment("soul_speed", 3, FootArmor, &[]),
+        enchantment("sweeping_edge", 3, Sword, &[]),
+        enchantment("swift_sneak", 3, LegArmor, &[]),
+        enchantment("thorns", 3, Armor, &[]),
+        enchantment("unbreaking", 3, Durability, &[]),
+        enchantment("vanishing_curse", 1, Vanishing, &[]),
+        enchantment("wind_burst", 3, Mace, &[]),
+    ]
+};
+
+/// The enchantment and its registry id, the `minecraft:` namespace can be left out
+pub fn find_enchantment(name: &str) -> Option<(i32, &'static Enchantment)> {
+    let name = name.strip_prefix("minecraft:").unwrap_or(name);
+    ENCHANTMENTS
+        .iter()
+        .position(|enchantment| enchantment.name == name)
+        .map(|id| (id as i32, &ENCHANTMENTS[id]))
+}
+
+pub fn enchantment_by_id(id: i32) -> Option<&'static Enchantment> {
+    usize::try_from(id).ok().and_then(|id| ENCHANTMENTS.get(id))
+}
+
+#[derive(Serialize)]
+struct Description {
+    translate: String,
+}
+
+#[derive(Serialize)]
+struct Cost {
+    base: i32,
+    per_level_above_first: i32,
+}
+
+#[derive(Serialize)]
+struct EnchantmentData {
+    description: Description,
+    supported_items: &'static str,
+    weight: i32,
+    max_level: i32,
+    min_cost: Cost,
+    max_cost: Cost,
+    anvil_cost: i32,
+    slots: Vec<&'static str>,
+}
+
+pub(super) fn entries() -> Vec<RegistryEntry<'static>> {
+    ENCHANTMENTS
+        .iter()
+        .map(|enchantment| RegistryEntry {
+            entry_id: format!("minecraft:{}", enchantment.name).into(),
+            // Only the fields the client needs, the effects are not sent
+            data: fastnbt::to_bytes_with_opts(
+                &EnchantmentData {
+                    description: Description {
+                        translate: format!("enchantment.minecraft.{}", enchantment.name),
+                    },
+                    supported_items: enchantment.target.tag(),
+                    weight: 1,
+                    max_level: enchantment.max_level,
+                    min_cost: Cost {
+                        base: 1,
+                        per_level_above_first: 10,
+                    },
+                    max_cost: Cost {
+                        base: 51,
+                        per_level_above_first: 10,
+                    },
+                    anvil_cost: 1,
+                    slots: vec!["any"],
+                },
+                SerOpts::network_nbt(),
+            )
+            .unwrap(),
+        })
+        .collect()
+}
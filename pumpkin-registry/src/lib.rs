@@ -1,21 +1,44 @@
+use std::{borrow::Cow, collections::HashSet, fs, io, path::Path};
+
 use biomes::Biome;
 use chat_type::ChatType;
 use dimensions::Dimension;
 use fastnbt::SerOpts;
 use paint::Painting;
-use pumpkin_protocol::client::config::RegistryEntry;
+use pumpkin_protocol::{client::config::RegistryEntry, KnownPack};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 use wolf::WolfVariant;
 
 mod biomes;
 mod chat_type;
 mod damage_type;
 mod dimensions;
+pub mod enchantment;
 mod paint;
 mod wolf;
 
+/// Where custom registry entries are loaded from, laid out like the `data` folder of a datapack,
+/// e.g. `registries/example/dimension_type/tall.json` becomes `example:tall`
+pub const REGISTRY_FOLDER: &str = "registries";
+
+/// The registries whose entries can be loaded from JSON, with the folder their entries are in
+const LOADABLE_REGISTRIES: [(&str, &str); 2] = [
+    ("minecraft:dimension_type", "dimension_type"),
+    ("minecraft:worldgen/biome", "worldgen/biome"),
+];
+
+/// The data pack with vanilla's registry entries, clients which have it don't need their data
+pub const CORE_PACK: KnownPack<'static> = KnownPack {
+    namespace: Cow::Borrowed("minecraft"),
+    id: Cow::Borrowed("core"),
+    version: Cow::Borrowed("1.21"),
+};
+
 pub struct Registry {
     pub registry_id: String,
     pub registry_entries: Vec<RegistryEntry<'static>>,
+    /// Ids of the entries which are in `CORE_PACK` as they are
+    pub core_entries: HashSet<Cow<'static, str>>,
 }
 
 impl Registry {
@@ -24,56 +47,72 @@ impl Registry {
         let dimensions = Registry {
             registry_id: "minecraft:dimension_type".to_string(),
             registry_entries: vec![RegistryEntry {
-                entry_id: "minecraft:overworld",
+                entry_id: "minecraft:overworld".into(),
                 data: fastnbt::to_bytes_with_opts(&Dimension::default(), SerOpts::network_nbt())
                     .unwrap(),
             }],
-        };
+            core_entries: HashSet::new(),
+        }
+        .in_core_pack();
         let biomes = Registry {
             registry_id: "minecraft:worldgen/biome".to_string(),
             registry_entries: vec![
                 RegistryEntry {
-                    entry_id: "minecraft:plains",
+                    entry_id: "minecraft:plains".into(),
                     data: fastnbt::to_bytes_with_opts(&Biome::default(), SerOpts::network_nbt())
                         .unwrap(),
                 },
                 RegistryEntry {
-                    entry_id: "minecraft:snowy_taiga",
+                    entry_id: "minecraft:snowy_taiga".into(),
                     data: fastnbt::to_bytes_with_opts(&Biome::default(), SerOpts::network_nbt())
                         .unwrap(),
                 },
             ],
-        };
+            core_entries: HashSet::new(),
+        }
+        .in_core_pack();
         let wolf_variants = Registry {
             registry_id: "minecraft:wolf_variant".to_string(),
             registry_entries: vec![RegistryEntry {
-                entry_id: "minecraft:wolf_variant",
+                entry_id: "minecraft:wolf_variant".into(),
                 data: fastnbt::to_bytes_with_opts(&WolfVariant::default(), SerOpts::network_nbt())
                     .unwrap(),
             }],
+            core_entries: HashSet::new(),
         };
 
         let chat_types = Registry {
             registry_id: "minecraft:chat_type".to_string(),
             registry_entries: vec![RegistryEntry {
-                entry_id: "minecraft:chat",
+                entry_id: "minecraft:chat".into(),
                 data: fastnbt::to_bytes_with_opts(&ChatType::default(), SerOpts::network_nbt())
                     .unwrap(),
             }],
-        };
+            core_entries: HashSet::new(),
+        }
+        .in_core_pack();
 
         let damage_types = Registry {
             registry_id: "minecraft:damage_type".to_string(),
             registry_entries: damage_type::entries(),
-        };
+            core_entries: HashSet::new(),
+        }
+        .in_core_pack();
         let paintings = Registry {
             registry_id: "minecraft:painting_variant".to_string(),
             registry_entries: vec![RegistryEntry {
-                entry_id: "minecraft:painting_variant",
+                entry_id: "minecraft:painting_variant".into(),
                 data: fastnbt::to_bytes_with_opts(&Painting::default(), SerOpts::network_nbt())
                     .unwrap(),
             }],
+            core_entries: HashSet::new(),
         };
+        let enchantments = Registry {
+            registry_id: "minecraft:enchantment".to_string(),
+            registry_entries: enchantment::entries(),
+            core_entries: HashSet::new(),
+        }
+        .in_core_pack();
         vec![
             dimensions,
             damage_types,
@@ -81,6 +120,303 @@ impl Registry {
             wolf_variants,
             paintings,
             chat_types,
+            enchantments,
         ]
     }
+
+    /// Marks every entry as one of vanilla's, only for entries with vanilla ids
+    fn in_core_pack(mut self) -> Self {
+        self.core_entries = self
+            .registry_entries
+            .iter()
+            .map(|entry| entry.entry_id.clone())
+            .collect();
+        self
+    }
+
+    /// The entries to send to a client which has `client_packs`, entries of those packs are sent without their data
+    pub fn entries_for(&self, client_packs: &[KnownPack]) -> Vec<RegistryEntry<'static>> {
+        let has_core = client_packs.contains(&CORE_PACK);
+        self.registry_entries
+            .iter()
+            .map(|entry| RegistryEntry {
+                entry_id: entry.entry_id.clone(),
+                data: if has_core && self.core_entries.contains(&entry.entry_id) {
+                    Vec::new()
+                } else {
+                    entry.data.clone()
+                },
+            })
+            .collect()
+    }
+
+    /// The built in registries with the entries found in `folder` added.
+    ///
+    /// Entries which already exist are replaced, new ones are appended so the ids of the built in ones don't change
+    pub fn load(folder: &Path) -> Vec<Self> {
+        let mut registries = Self::get_static();
+        if !folder.is_dir() {
+            return registries;
+        }
+        for registry in &mut registries {
+            let Some((_, path)) = LOADABLE_REGISTRIES
+                .iter()
+                .find(|(id, _)| *id == registry.registry_id)
+            else {
+                continue;
+            };
+            let loaded = match registry.registry_id.as_str() {
+                "minecraft:dimension_type" => load_entries::<Dimension>(folder, path),
+                _ => load_entries::<Biome>(folder, path),
+            };
+            match loaded {
+                Ok(entries) => registry.add_entries(entries),
+                Err(err) => log::error!(
+                    "Couldn't load the {} registry: {}",
+                    registry.registry_id,
+                    err
+                ),
+            }
+        }
+        registries
+    }
+
+    fn add_entries(&mut self, entries: Vec<RegistryEntry<'static>>) {
+        for entry in entries {
+            match self
+                .registry_entries
+                .iter_mut()
+                .find(|existing| existing.entry_id == entry.entry_id)
+            {
+                Some(existing) => {
+                    // The client's copy is outdated now
+                    self.core_entries.remove(&entry.entry_id);
+                    *existing = entry;
+                }
+                None => self.registry_entries.push(entry),
+            }
+        }
+    }
+}
+
+/// Reads `<folder>/<namespace>/<path>/<name>.json` of every namespace, sorted by their id
+fn load_entries<T: DeserializeOwned + Serialize>(
+    folder: &Path,
+    path: &str,
+) -> io::Result<Vec<RegistryEntry<'static>>> {
+    let mut entries = Vec::new();
+    for namespace in fs::read_dir(folder)? {
+        let namespace = namespace?;
+        let registry_folder = namespace.path().join(path);
+        if !registry_folder.is_dir() {
+            continue;
+        }
+        for file in fs::read_dir(registry_folder)? {
+            let file = file?.path();
+            if file.extension() != Some("json".as_ref()) {
+                continue;
+            }
+            let name = file.file_stem().unwrap_or_default().to_string_lossy();
+            let id = format!("{}:{}", namespace.file_name().to_string_lossy(), name);
+            let value: T = serde_json::from_str(&fs::read_to_string(&file)?).map_err(|err| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", id, err))
+            })?;
+            let data = fastnbt::to_bytes_with_opts(&value, SerOpts::network_nbt())
+                .map_err(io::Error::other)?;
+            entries.push(RegistryEntry {
+                entry_id: id.into(),
+                data,
+            });
+        }
+    }
+    entries.sort_by(|a, b| a.entry_id.cmp(&b.entry_id));
+    Ok(entries)
+}
+
+/// Vanilla uses booleans in JSON where the network NBT has bytes
+fn byte_bool<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum ByteBool {
+        Bool(bool),
+        Byte(u8),
+    }
+    Ok(match ByteBool::deserialize(deserializer)? {
+        ByteBool::Bool(value) => u8::from(value),
+        ByteBool::Byte(value) => value,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::Path};
+
+    use pumpkin_protocol::{
+        bytebuf::ByteBuffer,
+        client::config::{CRegistryData, RegistryEntry},
+        ClientPacket,
+    };
+
+    use pumpkin_protocol::KnownPack;
+
+    use super::{Registry, CORE_PACK};
+
+    /// The registry id and the number of entries a `CRegistryData` packet contains
+    fn serialized(registry: &Registry) -> (String, i32) {
+        let mut bytebuf = ByteBuffer::empty();
+        CRegistryData::new(&registry.registry_id, &registry.registry_entries).write(&mut bytebuf);
+        (
+            bytebuf.get_string().unwrap(),
+            bytebuf.get_var_int().unwrap().0,
+        )
+    }
+
+    fn counts(registries: &[Registry]) -> Vec<(String, i32)> {
+        registries
+            .iter()
+            .filter(|registry| {
+                registry.registry_id == "minecraft:dimension_type"
+                    || registry.registry_id == "minecraft:worldgen/biome"
+            })
+            .map(serialized)
+            .collect()
+    }
+
+    #[test]
+    fn test_static_registries() {
+        let registries = Registry::load(Path::new("this folder does not exist"));
+        assert_eq!(
+            counts(&registries),
+            vec![
+                ("minecraft:dimension_type".to_string(), 1),
+                ("minecraft:worldgen/biome".to_string(), 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_registries() {
+        let folder = env::temp_dir().join("pumpkin-registries-test");
+        let _ = fs::remove_dir_all(&folder);
+        let dimensions = folder.join("example/dimension_type");
+        let biomes = folder.join("example/worldgen/biome");
+        fs::create_dir_all(&dimensions).unwrap();
+        fs::create_dir_all(&biomes).unwrap();
+        fs::create_dir_all(folder.join("minecraft/worldgen/biome")).unwrap();
+        fs::write(
+            dimensions.join("tall.json"),
+            r##"{
+                "ambient_light": 0.0, "bed_works": true, "coordinate_scale": 1.0,
+                "effects": "minecraft:overworld", "has_ceiling": false, "has_raids": true,
+                "has_skylight": true, "height": 1024, "infiniburn": "#minecraft:infiniburn_overworld",
+                "logical_height": 1024, "min_y": -512, "monster_spawn_block_light_limit": 0,
+                "monster_spawn_light_level": {"type": "minecraft:uniform", "min_inclusive": 0, "max_inclusive": 7},
+                "natural": true, "piglin_safe": false, "respawn_anchor_works": false, "ultrawarm": false
+            }"##,
+        )
+        .unwrap();
+        let biome = r#"{
+            "has_precipitation": true, "temperature": 0.8, "downfall": 0.4,
+            "effects": {"fog_color": 12638463, "water_color": 4159204, "water_fog_color": 329011, "sky_color": 7907327},
+            "spawners": {}
+        }"#;
+        fs::write(biomes.join("meadow.json"), biome).unwrap();
+        fs::write(biomes.join("swamp.json"), biome).unwrap();
+        fs::write(biomes.join("notes.txt"), "not a biome").unwrap();
+        // Replaces the built in plains
+        fs::write(folder.join("minecraft/worldgen/biome/plains.json"), biome).unwrap();
+
+        let registries = Registry::load(&folder);
+        assert_eq!(
+            counts(&registries),
+            vec![
+                ("minecraft:dimension_type".to_string(), 2),
+                ("minecraft:worldgen/biome".to_string(), 4)
+            ]
+        );
+        let biomes = registries
+            .iter()
+            .find(|registry| registry.registry_id == "minecraft:worldgen/biome")
+            .unwrap();
+        let ids: Vec<_> = biomes
+            .registry_entries
+            .iter()
+            .map(|entry| &*entry.entry_id)
+            .collect();
+        assert_eq!(
+            ids,
+            vec![
+                "minecraft:plains",
+                "minecraft:snowy_taiga",
+                "example:meadow",
+                "example:swamp"
+            ]
+        );
+        fs::remove_dir_all(folder).unwrap();
+    }
+
+    /// How many entries of each registry are sent with their data
+    fn sent_data(registries: &[Registry], client_packs: &[KnownPack]) -> Vec<(String, usize)> {
+        registries
+            .iter()
+            .map(|registry| {
+                let with_data = registry
+                    .entries_for(client_packs)
+                    .iter()
+                    .filter(|entry| !entry.data.is_empty())
+                    .count();
+                (registry.registry_id.clone(), with_data)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_known_packs() {
+        let registries = Registry::get_static();
+        // Without the core pack everything has to be sent
+        let all = sent_data(&registries, &[]);
+        for (registry, (_, count)) in registries.iter().zip(&all) {
+            assert_eq!(registry.registry_entries.len(), *count);
+        }
+        let older_core = KnownPack {
+            version: "1.20.6".into(),
+            ..CORE_PACK
+        };
+        assert_eq!(sent_data(&registries, &[older_core]), all);
+
+        // Only our placeholders which aren't vanilla's are sent
+        let with_core = sent_data(&registries, &[CORE_PACK]);
+        assert_eq!(
+            with_core,
+            vec![
+                ("minecraft:dimension_type".to_string(), 0),
+                ("minecraft:damage_type".to_string(), 0),
+                ("minecraft:worldgen/biome".to_string(), 0),
+                ("minecraft:wolf_variant".to_string(), 1),
+                ("minecraft:painting_variant".to_string(), 1),
+                ("minecraft:chat_type".to_string(), 0),
+                ("minecraft:enchantment".to_string(), 0),
+            ]
+        );
+        // Every entry is still listed, the client needs their ids
+        for registry in &registries {
+            assert_eq!(
+                registry.entries_for(&[CORE_PACK]).len(),
+                registry.registry_entries.len()
+            );
+        }
+    }
+
+    #[test]
+    fn test_overridden_entries_are_sent() {
+        let mut biomes = Registry::get_static().remove(2);
+        assert_eq!(biomes.registry_id, "minecraft:worldgen/biome");
+        biomes.add_entries(vec![RegistryEntry {
+            entry_id: "minecraft:plains".into(),
+            data: vec![1, 2, 3],
+        }]);
+        let entries = biomes.entries_for(&[CORE_PACK]);
+        assert_eq!(entries[0].data, vec![1, 2, 3]);
+        assert!(entries[1].data.is_empty());
+    }
 }
@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use fastnbt::Value;
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    text::TextComponent,
+};
+
+use crate::global_registry;
+
+pub const BLOCK_ENTITY_TYPE_REGISTRY: &str = "minecraft:block_entity_type";
+
+/// How many lines of text fit on each side of a sign
+pub const SIGN_LINES: usize = 4;
+
+/// Data a block state can't hold, like the text of a sign or the items in a chest.
+///
+/// The data is kept as NBT, so block entities we don't understand yet survive loading and saving
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockEntity {
+    /// e.g. minecraft:sign
+    pub id: String,
+    pub position: WorldPosition,
+    /// Everything besides the id and the position, in the format vanilla stores it
+    pub data: HashMap<String, Value>,
+}
+
+impl BlockEntity {
+    pub fn new(id: impl Into<String>, position: WorldPosition) -> Self {
+        Self {
+            id: id.into(),
+            position,
+            data: HashMap::new(),
+        }
+    }
+
+    /// An empty sign, if the block is a sign
+    pub fn sign(block_name: &str, position: WorldPosition) -> Option<Self> {
+        let id = sign_type(block_name)?;
+        let mut sign = Self::new(id, position);
+        sign.data
+            .insert("front_text".to_string(), empty_sign_text());
+        sign.data.insert("back_text".to_string(), empty_sign_text());
+        sign.data.insert("is_waxed".to_string(), Value::Byte(0));
+        Some(sign)
+    }
+
+    /// The id of the block entity type in the protocol, `None` if the type is unknown
+    pub fn type_id(&self) -> Option<u32> {
+        global_registry::find_protocol_id(BLOCK_ENTITY_TYPE_REGISTRY, &self.id)
+    }
+
+    pub fn is_sign(&self) -> bool {
+        self.id == "minecraft:sign" || self.id == "minecraft:hanging_sign"
+    }
+
+    /// Waxed signs can't be edited anymore
+    pub fn is_waxed(&self) -> bool {
+        matches!(self.data.get("is_waxed"), Some(Value::Byte(waxed)) if *waxed != 0)
+    }
+
+    /// The lines on one side of a sign as JSON text components
+    pub fn sign_messages(&self, front: bool) -> Vec<&str> {
+        let side = if front { "front_text" } else { "back_text" };
+        let Some(Value::Compound(text)) = self.data.get(side) else {
+            return Vec::new();
+        };
+        let Some(Value::List(messages)) = text.get("messages") else {
+            return Vec::new();
+        };
+        messages
+            .iter()
+            .filter_map(|message| match message {
+                Value::String(message) => Some(message.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Replaces the text on one side of a sign, keeping its color and glow.
+    ///
+    /// Returns `false` if this is no sign or the sign is waxed
+    pub fn set_sign_text(&mut self, front: bool, lines: &[String]) -> bool {
+        if !self.is_sign() || self.is_waxed() {
+            return false;
+        }
+        let side = if front { "front_text" } else { "back_text" };
+        let text = self
+            .data
+            .entry(side.to_string())
+            .or_insert_with(empty_sign_text);
+        if !matches!(text, Value::Compound(_)) {
+            *text = empty_sign_text();
+        }
+        let Value::Compound(text) = text else {
+            unreachable!()
+        };
+        let messages = (0..SIGN_LINES)
+            .map(|i| {
+                let line = lines.get(i).map(String::as_str).unwrap_or_default();
+                Value::String(TextComponent::text(line).to_json())
+            })
+            .collect();
+        text.insert("messages".to_string(), Value::List(messages));
+        true
+    }
+
+    /// Reads an entry of the `block_entities` list in a saved chunk
+    pub fn from_nbt(nbt: Value) -> Option<Self> {
+        let Value::Compound(mut data) = nbt else {
+            return None;
+        };
+        let Some(Value::String(id)) = data.remove("id") else {
+            return None;
+        };
+        let mut coordinate = |name| match data.remove(name) {
+            Some(Value::Int(value)) => Some(value),
+            _ => None,
+        };
+        let position = Vector3::new(coordinate("x")?, coordinate("y")?, coordinate("z")?);
+        data.remove("keepPacked");
+        Some(Self {
+            id,
+            position: WorldPosition(position),
+            data,
+        })
+    }
+
+    /// The entry of the `block_entities` list in a saved chunk
+    pub fn to_nbt(&self) -> Value {
+        let mut nbt = self.data.clone();
+        nbt.insert("id".to_string(), Value::String(self.id.clone()));
+        nbt.insert("x".to_string(), Value::Int(self.position.0.x));
+        nbt.insert("y".to_string(), Value::Int(self.position.0.y));
+        nbt.insert("z".to_string(), Value::Int(self.position.0.z));
+        nbt.insert("keepPacked".to_string(), Value::Byte(0));
+        Value::Compound(nbt)
+    }
+}
+
+/// The block entity type of a sign block, e.g. minecraft:sign for minecraft:oak_wall_sign
+pub fn sign_type(block_name: &str) -> Option<&'static str> {
+    if block_name.ends_with("_hanging_sign") {
+        Some("minecraft:hanging_sign")
+    } else if block_name.ends_with("_sign") {
+        Some("minecraft:sign")
+    } else {
+        None
+    }
+}
+
+fn empty_sign_text() -> Value {
+    let empty = Value::String(TextComponent::text("").to_json());
+    Value::Compound(HashMap::from([
+        ("messages".to_string(), Value::List(vec![empty; SIGN_LINES])),
+        ("color".to_string(), Value::String("black".to_string())),
+        ("has_glowing_text".to_string(), Value::Byte(0)),
+    ]))
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+
+    use super::{sign_type, BlockEntity};
+
+    fn at() -> WorldPosition {
+        WorldPosition(Vector3::new(-3, 70, 18))
+    }
+
+    #[test]
+    fn test_sign_types() {
+        assert_eq!(sign_type("minecraft:oak_sign"), Some("minecraft:sign"));
+        assert_eq!(sign_type("minecraft:oak_wall_sign"), Some("minecraft:sign"));
+        assert_eq!(
+            sign_type("minecraft:birch_wall_hanging_sign"),
+            Some("minecraft:hanging_sign")
+        );
+        assert_eq!(sign_type("minecraft:chest"), None);
+    }
+
+    #[test]
+    fn test_nbt_round_trip() {
+        let mut sign = BlockEntity::sign("minecraft:oak_sign", at()).unwrap();
+        assert!(sign.set_sign_text(true, &["Hello".to_string()]));
+        assert_eq!(BlockEntity::from_nbt(sign.to_nbt()), Some(sign.clone()));
+        assert_eq!(sign.type_id(), Some(7));
+    }
+
+    #[test]
+    fn test_edit_sign() {
+        let mut sign = BlockEntity::sign("minecraft:oak_sign", at()).unwrap();
+        assert!(sign.set_sign_text(false, &["a".to_string(), "b".to_string()]));
+        assert_eq!(
+            sign.sign_messages(false),
+            vec![
+                r#"{"text":"a"}"#,
+                r#"{"text":"b"}"#,
+                r#"{"text":""}"#,
+                r#"{"text":""}"#
+            ]
+        );
+        assert_eq!(sign.sign_messages(true)[0], r#"{"text":""}"#);
+
+        sign.data
+            .insert("is_waxed".to_string(), fastnbt::Value::Byte(1));
+        assert!(!sign.set_sign_text(true, &["c".to_string()]));
+        assert!(!BlockEntity::new("minecraft:chest", at()).set_sign_text(true, &[]));
+    }
+}
@@ -9,6 +9,25 @@ pub static BLOCKS: LazyLock<HashMap<String, RegistryBlockType>> = LazyLock::new(
         .expect("Could not parse block.json registry.")
 });
 
+/// Maps every state id back to the identifier of its Block and the state itself
+static BLOCK_STATES: LazyLock<HashMap<BlockId, (&'static str, &'static RegistryBlockState)>> =
+    LazyLock::new(|| {
+        BLOCKS
+            .iter()
+            .flat_map(|(registry_id, block)| {
+                block
+                    .states
+                    .iter()
+                    .map(move |state| (state.id, (registry_id.as_str(), state)))
+            })
+            .collect()
+    });
+
+/// Finds the identifier of the Block and the state a state id belongs to
+pub fn find_block_state(id: BlockId) -> Option<(&'static str, &'static RegistryBlockState)> {
+    BLOCK_STATES.get(&id).copied()
+}
+
 pumpkin_macros::blocks_enum!();
 pumpkin_macros::block_categories_enum!();
 
@@ -1,11 +1,12 @@
 use num_derive::FromPrimitive;
 
+pub mod block_entity;
 mod block_registry;
 pub mod block_state;
 
 use pumpkin_core::math::vector3::Vector3;
 
-pub use block_registry::{Block, BlockCategory, BlockId};
+pub use block_registry::{find_block_state, Block, BlockCategory, BlockId};
 pub use block_state::BlockState;
 
 #[derive(FromPrimitive)]
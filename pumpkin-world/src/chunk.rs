@@ -1,25 +1,40 @@
 use std::cmp::max;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::Index;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use fastnbt::LongArray;
-use pumpkin_core::math::vector2::Vector2;
+use fastnbt::{LongArray, Value};
+use parking_lot::RwLock;
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    block::{BlockId, BlockState},
+    block::{block_entity::BlockEntity, find_block_state, BlockId, BlockState},
     coordinates::{ChunkRelativeBlockCoordinates, Height},
     level::{ChunkNotGeneratedError, WorldError},
-    WORLD_HEIGHT,
+    WORLD_HEIGHT, WORLD_LOWEST_Y,
 };
 
 const CHUNK_AREA: usize = 16 * 16;
 const SUBCHUNK_VOLUME: usize = CHUNK_AREA * 16;
 const CHUNK_VOLUME: usize = CHUNK_AREA * WORLD_HEIGHT;
 
+/// The data version of Minecraft 1.21.1, written into saved chunks
+const DATA_VERSION: i32 = 3955;
+/// The y coordinate of the lowest subchunk
+const LOWEST_SECTION_Y: i32 = WORLD_LOWEST_Y as i32 / 16;
+
 pub struct ChunkData {
-    pub blocks: ChunkBlocks,
+    pub blocks: RwLock<ChunkBlocks>,
     pub position: Vector2<i32>,
+    /// Signs, chests and every other block with extra data in this chunk
+    pub block_entities: RwLock<Vec<BlockEntity>>,
+    /// Whether the chunk changed since it was last saved
+    dirty: AtomicBool,
+    /// The NBT the chunk was read from, without the blocks and block entities which are kept above.
+    ///
+    /// Biomes, light, ticks, structures and everything else Pumpkin doesn't handle yet are written back from it
+    saved_nbt: Option<HashMap<String, Value>>,
 }
 
 pub struct ChunkBlocks {
@@ -33,15 +48,17 @@ pub struct ChunkBlocks {
     pub heightmap: ChunkHeightmaps,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "PascalCase")]
 struct PaletteEntry {
     name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
     properties: Option<HashMap<String, String>>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 struct ChunkSectionBlockStates {
+    #[serde(skip_serializing_if = "Option::is_none")]
     data: Option<LongArray>,
     palette: Vec<PaletteEntry>,
 }
@@ -53,24 +70,35 @@ pub struct ChunkHeightmaps {
     world_surface: LongArray,
 }
 
-#[derive(Deserialize, Debug)]
-#[expect(dead_code)]
+#[derive(Deserialize, Serialize, Debug)]
 struct ChunkSection {
     #[serde(rename = "Y")]
-    y: i32,
+    y: i8,
+    #[serde(skip_serializing_if = "Option::is_none")]
     block_states: Option<ChunkSectionBlockStates>,
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Serialize, Debug)]
 #[serde(rename_all = "PascalCase")]
 struct ChunkNbt {
-    #[expect(dead_code)]
-    data_version: usize,
+    data_version: i32,
+
+    #[serde(rename = "xPos")]
+    x_pos: i32,
+    #[serde(rename = "zPos")]
+    z_pos: i32,
+    #[serde(rename = "yPos")]
+    y_pos: i32,
+
+    status: String,
 
     #[serde(rename = "sections")]
     sections: Vec<ChunkSection>,
 
     heightmaps: ChunkHeightmaps,
+
+    #[serde(rename = "block_entities", default)]
+    block_entities: Vec<Value>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Eq)]
@@ -115,6 +143,54 @@ impl Default for ChunkHeightmaps {
     }
 }
 
+impl ChunkSectionBlockStates {
+    /// Builds the palette and packs the indices into longs like vanilla does,
+    /// a subchunk made of a single block only has a palette
+    fn from_blocks(blocks: &[BlockId; SUBCHUNK_VOLUME]) -> Result<Self, WorldError> {
+        let mut palette = Vec::new();
+        let mut palette_indices = HashMap::new();
+        let indices = blocks
+            .iter()
+            .map(|block| {
+                *palette_indices.entry(*block).or_insert_with(|| {
+                    palette.push(*block);
+                    palette.len() - 1
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let data = if palette.len() == 1 {
+            None
+        } else {
+            let block_bit_size = max(4, 64 - (palette.len() as i64 - 1).leading_zeros());
+            let blocks_in_pallete = (64 / block_bit_size) as usize;
+            let longs = indices
+                .chunks(blocks_in_pallete)
+                .map(|indices| {
+                    indices.iter().enumerate().fold(0u64, |long, (i, index)| {
+                        long | (*index as u64) << (i as u32 * block_bit_size)
+                    }) as i64
+                })
+                .collect();
+            Some(LongArray::new(longs))
+        };
+
+        let palette = palette
+            .into_iter()
+            .map(|block| {
+                let (name, state) =
+                    find_block_state(block).ok_or(WorldError::BlockStateIdNotFound)?;
+                Ok(PaletteEntry {
+                    name: name.to_string(),
+                    properties: (!state.properties.is_empty()).then(|| state.properties.clone()),
+                })
+            })
+            .collect::<Result<Vec<_>, WorldError>>()?;
+
+        Ok(Self { data, palette })
+    }
+}
+
 impl Default for ChunkBlocks {
     fn default() -> Self {
         Self {
@@ -166,6 +242,13 @@ impl ChunkBlocks {
             .map(|subchunk| subchunk.try_into().unwrap())
     }
 
+    /// How many of the 16 blocks high subchunks contain anything besides air
+    pub fn non_empty_sections(&self) -> usize {
+        self.iter_subchunks()
+            .filter(|subchunk| subchunk.iter().any(|block| !block.is_air()))
+            .count()
+    }
+
     fn convert_index(index: ChunkRelativeBlockCoordinates) -> usize {
         // % works for negative numbers as intended.
         index.y.get_absolute() as usize * CHUNK_AREA + *index.z as usize * 16 + *index.x as usize
@@ -188,6 +271,68 @@ impl Index<ChunkRelativeBlockCoordinates> for ChunkBlocks {
 }
 
 impl ChunkData {
+    pub fn new(blocks: ChunkBlocks, position: Vector2<i32>) -> Self {
+        Self {
+            blocks: RwLock::new(blocks),
+            position,
+            block_entities: RwLock::new(Vec::new()),
+            dirty: AtomicBool::new(false),
+            saved_nbt: None,
+        }
+    }
+
+    /// Sets the given block in the chunk and marks it dirty, returning the old block.
+    ///
+    /// The block entity of the old block is removed
+    pub fn set_block(&self, position: ChunkRelativeBlockCoordinates, block: BlockId) -> BlockId {
+        let (x, y, z) = (*position.x as i32, *position.y as i32, *position.z as i32);
+        let old = self.blocks.write().set_block(position, block);
+        if old != block {
+            self.block_entities.write().retain(|entity| {
+                let at = entity.position.0;
+                (at.x.rem_euclid(16), at.y, at.z.rem_euclid(16)) != (x, y, z)
+            });
+            self.mark_dirty();
+        }
+        old
+    }
+
+    /// Gets a copy of the block entity at the given position
+    pub fn get_block_entity(&self, position: WorldPosition) -> Option<BlockEntity> {
+        self.block_entities
+            .read()
+            .iter()
+            .find(|entity| entity.position == position)
+            .cloned()
+    }
+
+    /// Adds or replaces a block entity and marks the chunk dirty
+    pub fn set_block_entity(&self, block_entity: BlockEntity) {
+        let mut block_entities = self.block_entities.write();
+        match block_entities
+            .iter_mut()
+            .find(|entity| entity.position == block_entity.position)
+        {
+            Some(entity) => *entity = block_entity,
+            None => block_entities.push(block_entity),
+        }
+        self.mark_dirty();
+    }
+
+    /// Marks the chunk as changed, so it gets saved again
+    pub fn mark_dirty(&self) {
+        self.dirty.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.load(Ordering::Relaxed)
+    }
+
+    /// Clears the dirty flag and returns whether it was set
+    pub fn take_dirty(&self) -> bool {
+        self.dirty.swap(false, Ordering::Relaxed)
+    }
+
     pub fn from_bytes(chunk_data: Vec<u8>, at: Vector2<i32>) -> Result<Self, WorldError> {
         if fastnbt::from_bytes::<ChunkStatus>(&chunk_data).expect("Failed reading chunk status.")
             != ChunkStatus::Full
@@ -197,10 +342,21 @@ impl ChunkData {
             ));
         }
 
+        let mut saved_nbt = fastnbt::from_bytes::<HashMap<String, Value>>(&chunk_data)
+            .map_err(|err| WorldError::ErrorDeserializingChunk(err.to_string()))?;
         let chunk_data = match fastnbt::from_bytes::<ChunkNbt>(chunk_data.as_slice()) {
             Ok(v) => v,
             Err(err) => return Err(WorldError::ErrorDeserializingChunk(err.to_string())),
         };
+        // These are written again from the parsed chunk, no need to keep them twice
+        saved_nbt.remove("block_entities");
+        if let Some(Value::List(sections)) = saved_nbt.get_mut("sections") {
+            for section in sections {
+                if let Value::Compound(section) = section {
+                    section.remove("block_states");
+                }
+            }
+        }
 
         // this needs to be boxed, otherwise it will cause a stack-overflow
         let mut blocks = ChunkBlocks::empty_with_heightmap(chunk_data.heightmaps);
@@ -225,7 +381,22 @@ impl ChunkData {
 
             let block_data = match block_states.data {
                 None => {
-                    // We skipped placing an empty subchunk.
+                    // The whole subchunk is made of the only block in the palette
+                    if let Some(block) = palette
+                        .first()
+                        .filter(|block| **block != BlockId::default())
+                    {
+                        for index in block_index..block_index + SUBCHUNK_VOLUME {
+                            blocks.set_block_no_heightmap_update(
+                                ChunkRelativeBlockCoordinates {
+                                    z: ((index % CHUNK_AREA) / 16).into(),
+                                    y: Height::from_absolute((index / CHUNK_AREA) as u16),
+                                    x: (index % 16).into(),
+                                },
+                                *block,
+                            );
+                        }
+                    }
                     // We need to increase the y coordinate of the next subchunk being placed.
                     block_index += SUBCHUNK_VOLUME;
                     continue;
@@ -271,9 +442,252 @@ impl ChunkData {
             }
         }
 
-        Ok(ChunkData {
-            blocks,
-            position: at,
-        })
+        let mut chunk = ChunkData::new(blocks, at);
+        chunk.saved_nbt = Some(saved_nbt);
+        *chunk.block_entities.write() = chunk_data
+            .block_entities
+            .into_iter()
+            .filter_map(BlockEntity::from_nbt)
+            .collect();
+        Ok(chunk)
+    }
+
+    /// Serializes the chunk into the NBT vanilla stores in region files.
+    ///
+    /// A chunk which was read from a region file keeps every tag it was read with, only the changed ones are replaced
+    pub fn to_bytes(&self) -> Result<Vec<u8>, WorldError> {
+        let blocks = self.blocks.read();
+        let sections = blocks
+            .iter_subchunks()
+            .enumerate()
+            .map(|(i, subchunk)| {
+                Ok(ChunkSection {
+                    y: (LOWEST_SECTION_Y + i as i32) as i8,
+                    block_states: Some(ChunkSectionBlockStates::from_blocks(subchunk)?),
+                })
+            })
+            .collect::<Result<Vec<_>, WorldError>>()?;
+
+        let chunk_nbt = ChunkNbt {
+            data_version: DATA_VERSION,
+            x_pos: self.position.x,
+            z_pos: self.position.z,
+            y_pos: LOWEST_SECTION_Y,
+            status: "minecraft:full".to_string(),
+            sections,
+            heightmaps: blocks.heightmap.clone(),
+            block_entities: self
+                .block_entities
+                .read()
+                .iter()
+                .map(BlockEntity::to_nbt)
+                .collect(),
+        };
+        let serialize_error =
+            |err: fastnbt::error::Error| WorldError::ErrorSerializingChunk(err.to_string());
+        let bytes = fastnbt::to_bytes(&chunk_nbt).map_err(serialize_error)?;
+        let Some(saved_nbt) = &self.saved_nbt else {
+            return Ok(bytes);
+        };
+        let changed =
+            fastnbt::from_bytes::<HashMap<String, Value>>(&bytes).map_err(serialize_error)?;
+        let mut nbt = saved_nbt.clone();
+        merge_chunk_nbt(&mut nbt, changed);
+        fastnbt::to_bytes(&nbt).map_err(serialize_error)
+    }
+}
+
+/// Writes the tags of `changed` over the saved NBT of a chunk.
+///
+/// Compounds like the heightmaps are merged, so the ones Pumpkin doesn't calculate are kept.
+/// Sections are merged by their y coordinate, keeping their biomes and light
+fn merge_chunk_nbt(saved: &mut HashMap<String, Value>, changed: HashMap<String, Value>) {
+    for (key, value) in changed {
+        let merged = match (key.as_str(), saved.remove(&key), value) {
+            ("sections", Some(Value::List(old)), Value::List(new)) => {
+                Value::List(merge_sections(old, new))
+            }
+            (_, Some(Value::Compound(mut old)), Value::Compound(new)) => {
+                old.extend(new);
+                Value::Compound(old)
+            }
+            (_, _, value) => value,
+        };
+        saved.insert(key, merged);
+    }
+}
+
+fn merge_sections(old: Vec<Value>, new: Vec<Value>) -> Vec<Value> {
+    let with_y = |section: Value| {
+        let Value::Compound(section) = section else {
+            return None;
+        };
+        let y = match section.get("Y") {
+            Some(Value::Byte(y)) => *y,
+            _ => return None,
+        };
+        Some((y, section))
+    };
+    // Vanilla also keeps light in the sections right above and below the world, these stay untouched
+    let mut sections = old
+        .into_iter()
+        .filter_map(with_y)
+        .collect::<BTreeMap<_, _>>();
+    for (y, section) in new.into_iter().filter_map(with_y) {
+        sections.entry(y).or_default().extend(section);
+    }
+    sections.into_values().map(Value::Compound).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use fastnbt::{ByteArray, LongArray, Value};
+    use pumpkin_core::math::{position::WorldPosition, vector2::Vector2, vector3::Vector3};
+
+    use crate::{block::BlockId, coordinates::ChunkRelativeBlockCoordinates};
+
+    use super::ChunkData;
+
+    fn compound<const N: usize>(entries: [(&str, Value); N]) -> Value {
+        Value::Compound(HashMap::from(
+            entries.map(|(key, value)| (key.to_string(), value)),
+        ))
+    }
+
+    fn light() -> Value {
+        Value::ByteArray(ByteArray::new(vec![15; 2048]))
+    }
+
+    /// A chunk like vanilla saves it, with a stone bottom section
+    fn vanilla_chunk() -> HashMap<String, Value> {
+        let heightmap = || Value::LongArray(LongArray::new(vec![0; 37]));
+        let Value::Compound(nbt) = compound([
+            ("DataVersion", Value::Int(3953)),
+            ("xPos", Value::Int(2)),
+            ("zPos", Value::Int(-1)),
+            ("yPos", Value::Int(-4)),
+            ("Status", Value::String("minecraft:full".to_string())),
+            ("isLightOn", Value::Byte(1)),
+            ("InhabitedTime", Value::Long(1200)),
+            (
+                "sections",
+                Value::List(vec![
+                    // Only light below the world
+                    compound([("Y", Value::Byte(-5)), ("SkyLight", light())]),
+                    compound([
+                        ("Y", Value::Byte(-4)),
+                        (
+                            "block_states",
+                            compound([(
+                                "palette",
+                                Value::List(vec![compound([(
+                                    "Name",
+                                    Value::String("minecraft:stone".to_string()),
+                                )])]),
+                            )]),
+                        ),
+                        (
+                            "biomes",
+                            compound([(
+                                "palette",
+                                Value::List(vec![Value::String("minecraft:plains".to_string())]),
+                            )]),
+                        ),
+                        ("BlockLight", light()),
+                        ("SkyLight", light()),
+                    ]),
+                ]),
+            ),
+            (
+                "Heightmaps",
+                compound([
+                    ("MOTION_BLOCKING", heightmap()),
+                    ("WORLD_SURFACE", heightmap()),
+                    ("OCEAN_FLOOR", heightmap()),
+                ]),
+            ),
+            ("block_ticks", Value::List(vec![])),
+            ("PostProcessing", Value::List(vec![Value::List(vec![])])),
+            (
+                "structures",
+                compound([("References", compound([])), ("starts", compound([]))]),
+            ),
+        ]) else {
+            unreachable!()
+        };
+        nbt
+    }
+
+    fn section(nbt: &HashMap<String, Value>, y: i8) -> &HashMap<String, Value> {
+        let Some(Value::List(sections)) = nbt.get("sections") else {
+            panic!("The chunk has no sections");
+        };
+        sections
+            .iter()
+            .find_map(|section| match section {
+                Value::Compound(section) if section.get("Y") == Some(&Value::Byte(y)) => {
+                    Some(section)
+                }
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_saving_keeps_vanilla_tags() {
+        let vanilla = vanilla_chunk();
+        let chunk =
+            ChunkData::from_bytes(fastnbt::to_bytes(&vanilla).unwrap(), Vector2::new(2, -1))
+                .unwrap();
+        let (_, relative) = ChunkRelativeBlockCoordinates::from_world_position(WorldPosition(
+            Vector3::new(33, -60, -10),
+        ))
+        .unwrap();
+        assert_ne!(chunk.blocks.read().get_block(relative), BlockId::default());
+        chunk.set_block(relative, BlockId::default());
+
+        let saved =
+            fastnbt::from_bytes::<HashMap<String, Value>>(&chunk.to_bytes().unwrap()).unwrap();
+        for tag in [
+            "isLightOn",
+            "InhabitedTime",
+            "block_ticks",
+            "PostProcessing",
+            "structures",
+        ] {
+            assert_eq!(saved.get(tag), vanilla.get(tag), "{} was lost", tag);
+        }
+        let Some(Value::Compound(heightmaps)) = saved.get("Heightmaps") else {
+            panic!("The heightmaps were lost");
+        };
+        assert!(heightmaps.contains_key("OCEAN_FLOOR"));
+
+        assert_eq!(
+            section(&saved, -5).get("SkyLight"),
+            section(&vanilla, -5).get("SkyLight")
+        );
+        let bottom = section(&saved, -4);
+        for tag in ["biomes", "BlockLight", "SkyLight"] {
+            assert_eq!(
+                bottom.get(tag),
+                section(&vanilla, -4).get(tag),
+                "{} was lost",
+                tag
+            );
+        }
+        // The changed block is saved
+        let Some(Value::Compound(block_states)) = bottom.get("block_states") else {
+            panic!("The blocks were not saved");
+        };
+        let Some(Value::List(palette)) = block_states.get("palette") else {
+            panic!("The blocks were not saved");
+        };
+        assert_eq!(palette.len(), 2);
+
+        // And read back the same way
+        let read = ChunkData::from_bytes(chunk.to_bytes().unwrap(), Vector2::new(2, -1)).unwrap();
+        assert_eq!(read.blocks.read().get_block(relative), BlockId::default());
     }
 }
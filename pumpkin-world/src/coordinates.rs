@@ -2,7 +2,7 @@ use std::ops::Deref;
 
 use derive_more::derive::{AsMut, AsRef, Display, Into};
 use num_traits::{PrimInt, Signed, Unsigned};
-use pumpkin_core::math::vector2::Vector2;
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2};
 use serde::{Deserialize, Serialize};
 
 use crate::{WORLD_LOWEST_Y, WORLD_MAX_Y};
@@ -99,6 +99,23 @@ pub struct ChunkRelativeBlockCoordinates {
 }
 
 impl ChunkRelativeBlockCoordinates {
+    /// Splits a world position into the chunk it is in and its coordinates inside of that chunk.
+    ///
+    /// Returns `None` if the position is above or below the world
+    pub fn from_world_position(position: WorldPosition) -> Option<(Vector2<i32>, Self)> {
+        let WorldPosition(position) = position;
+        if position.y < WORLD_LOWEST_Y as i32 || position.y >= WORLD_MAX_Y as i32 {
+            return None;
+        }
+        let chunk = Vector2::new(position.x.div_euclid(16), position.z.div_euclid(16));
+        let relative = Self {
+            x: (position.x.rem_euclid(16) as u8).into(),
+            y: position.y.into(),
+            z: (position.z.rem_euclid(16) as u8).into(),
+        };
+        Some((chunk, relative))
+    }
+
     pub fn with_chunk_coordinates(self, chunk_coordinates: Vector2<i32>) -> BlockCoordinates {
         BlockCoordinates {
             x: *self.x as i32 + chunk_coordinates.x * 16,
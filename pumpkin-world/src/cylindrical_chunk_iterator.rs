@@ -14,12 +14,13 @@ impl Cylindrical {
         }
     }
 
-    #[allow(unused_variables)]
+    /// Calls `newly_included` for every chunk only in the new cylinder and `just_removed` for
+    /// every chunk only in the old one. With `ignore` the old cylinder is treated as empty
     pub fn for_each_changed_chunk(
         old_cylindrical: Cylindrical,
         new_cylindrical: Cylindrical,
         mut newly_included: impl FnMut(Vector2<i32>),
-        just_removed: impl FnMut(Vector2<i32>),
+        mut just_removed: impl FnMut(Vector2<i32>),
         ignore: bool,
     ) {
         let min_x = old_cylindrical.get_left().min(new_cylindrical.get_left());
@@ -31,26 +32,16 @@ impl Cylindrical {
 
         for x in min_x..=max_x {
             for z in min_z..=max_z {
-                // TODO
-                // let old_is_within = if ignore {
-                //     false
-                // } else {
-                //     old_cylindrical.is_within_distance(x, z)
-                // };
-                // let new_is_within = if ignore {
-                //     true
-                // } else {
-                //     new_cylindrical.is_within_distance(x, z)
-                // };
-
-                // if old_is_within != new_is_within {
-                //     if new_is_within {
-                newly_included(Vector2::new(x, z));
-                //     } else {
-                //         dbg!("aa");
-                //         just_removed(Vector2::new(x, z));
-                //     }
-                // }
+                let old_is_within = !ignore && old_cylindrical.is_within_distance(x, z);
+                let new_is_within = new_cylindrical.is_within_distance(x, z);
+
+                if old_is_within != new_is_within {
+                    if new_is_within {
+                        newly_included(Vector2::new(x, z));
+                    } else {
+                        just_removed(Vector2::new(x, z));
+                    }
+                }
             }
         }
     }
@@ -71,13 +62,107 @@ impl Cylindrical {
         self.center.z + self.view_distance + 1
     }
 
-    #[allow(dead_code)]
-    fn is_within_distance(&self, x: i32, z: i32) -> bool {
-        let max_dist_squared = self.view_distance * self.view_distance;
-        let max_dist = self.view_distance as i64;
-        let dist_x = (x - self.center.x).abs().max(0) - (1);
-        let dist_z = (z - self.center.z).abs().max(0) - (1);
-        let dist_squared = dist_x.pow(2) + (max_dist.min(dist_z as i64) as i32).pow(2);
-        dist_squared < max_dist_squared
+    /// Same as vanilla, the chunks right next to the center are always included
+    pub fn is_within_distance(&self, x: i32, z: i32) -> bool {
+        let dist_x = ((x - self.center.x).abs() - 1).max(0) as i64;
+        let dist_z = ((z - self.center.z).abs() - 1).max(0) as i64;
+        let view_distance = self.view_distance as i64;
+        dist_x * dist_x + dist_z * dist_z < view_distance * view_distance
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::vector2::Vector2;
+
+    use super::Cylindrical;
+
+    fn changed(
+        old: Cylindrical,
+        new: Cylindrical,
+        ignore: bool,
+    ) -> (Vec<Vector2<i32>>, Vec<Vector2<i32>>) {
+        let mut loaded = Vec::new();
+        let mut unloaded = Vec::new();
+        Cylindrical::for_each_changed_chunk(
+            old,
+            new,
+            |pos| loaded.push(pos),
+            |pos| unloaded.push(pos),
+            ignore,
+        );
+        (loaded, unloaded)
+    }
+
+    #[test]
+    fn test_within_distance() {
+        let cylindrical = Cylindrical::new(Vector2::new(0, 0), 2);
+        assert!(cylindrical.is_within_distance(0, 0));
+        assert!(cylindrical.is_within_distance(2, 2));
+        assert!(cylindrical.is_within_distance(-2, 0));
+        assert!(!cylindrical.is_within_distance(3, 3));
+        assert!(!cylindrical.is_within_distance(4, 0));
+    }
+
+    #[test]
+    fn test_same_cylinder_changes_nothing() {
+        let center = Vector2::new(5, -3);
+        let (loaded, unloaded) = changed(
+            Cylindrical::new(center, 4),
+            Cylindrical::new(center, 4),
+            false,
+        );
+        assert!(loaded.is_empty());
+        assert!(unloaded.is_empty());
+    }
+
+    #[test]
+    fn test_ignore_loads_everything() {
+        let center = Vector2::new(0, 0);
+        let (loaded, unloaded) = changed(
+            Cylindrical::new(center, 4),
+            Cylindrical::new(center, 4),
+            true,
+        );
+        let cylindrical = Cylindrical::new(center, 4);
+        assert!(unloaded.is_empty());
+        assert!(loaded.contains(&center));
+        assert!(loaded
+            .iter()
+            .all(|pos| cylindrical.is_within_distance(pos.x, pos.z)));
+    }
+
+    #[test]
+    fn test_shrink_and_grow() {
+        let center = Vector2::new(0, 0);
+        let (loaded, unloaded) = changed(
+            Cylindrical::new(center, 8),
+            Cylindrical::new(center, 4),
+            false,
+        );
+        assert!(loaded.is_empty());
+        assert!(unloaded.contains(&Vector2::new(8, 0)));
+        assert!(!unloaded.contains(&Vector2::new(2, 0)));
+
+        let (loaded, unloaded) = changed(
+            Cylindrical::new(center, 4),
+            Cylindrical::new(center, 8),
+            false,
+        );
+        assert!(unloaded.is_empty());
+        assert!(loaded.contains(&Vector2::new(8, 0)));
+        assert!(!loaded.contains(&Vector2::new(2, 0)));
+    }
+
+    #[test]
+    fn test_move() {
+        let (loaded, unloaded) = changed(
+            Cylindrical::new(Vector2::new(0, 0), 4),
+            Cylindrical::new(Vector2::new(1, 0), 4),
+            false,
+        );
+        assert!(loaded.iter().all(|pos| pos.x > 0));
+        assert!(unloaded.iter().all(|pos| pos.x < 0));
+        assert_eq!(loaded.len(), unloaded.len());
     }
 }
@@ -1,15 +1,29 @@
 use std::path::PathBuf;
 
+use serde::{Deserialize, Serialize};
+
 use crate::level::Level;
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub enum Dimension {
+    #[serde(rename = "minecraft:overworld")]
     OverWorld,
+    #[serde(rename = "minecraft:the_nether")]
     Nether,
+    #[serde(rename = "minecraft:the_end")]
     End,
 }
 
 impl Dimension {
+    /// The identifier of the dimension type, e.g. `minecraft:overworld`
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Dimension::OverWorld => "minecraft:overworld",
+            Dimension::Nether => "minecraft:the_nether",
+            Dimension::End => "minecraft:the_end",
+        }
+    }
+
     pub fn into_level(&self, mut base_directory: PathBuf) -> Level {
         match self {
             Dimension::OverWorld => {}
@@ -29,6 +29,10 @@ impl ItemStack {
         .contains(&self.item_id)
     }
 
+    pub fn is_elytra(&self) -> bool {
+        self.item_id == 773
+    }
+
     pub fn is_leggings(&self) -> bool {
         [
             // Leather
@@ -33,7 +33,7 @@ pub fn get_item_element(item_id: &str) -> &ItemComponents {
     &ITEMS.get(item_id).expect("Item not found").components
 }
 
-#[expect(dead_code)]
-pub fn get_item_protocol_id(item_id: &str) -> u32 {
-    global_registry::get_protocol_id(ITEM_REGISTRY, item_id)
+/// The protocol id of an item like `minecraft:stone`, `None` if there is no such item
+pub fn get_item_protocol_id(item_id: &str) -> Option<u32> {
+    global_registry::find_protocol_id(ITEM_REGISTRY, item_id)
 }
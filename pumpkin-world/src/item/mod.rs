@@ -1,6 +1,6 @@
 mod item_categories;
 mod item_registry;
-pub use item_registry::ITEMS;
+pub use item_registry::{get_item_protocol_id, ITEMS};
 #[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 /// Item Rarity
@@ -11,12 +11,49 @@ pub enum Rarity {
     Epic,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub struct ItemStack {
     pub item_count: u8,
     // This ID is the numerical protocol ID, not the usual minecraft::block ID.
     pub item_id: u32,
-    // TODO: Add Item Components
+    pub components: ItemComponents,
+}
+
+impl ItemStack {
+    pub const fn new(item_count: u8, item_id: u32) -> Self {
+        Self {
+            item_count,
+            item_id,
+            components: ItemComponents::new(),
+        }
+    }
+}
+
+/// The data components of an item which differ from the defaults of its type
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemComponents {
+    /// The name given to the item, e.g. with an anvil. Only the plain text is kept
+    pub custom_name: Option<String>,
+    /// Extra lines shown in the tooltip
+    pub lore: Vec<String>,
+    /// The enchantment registry ids with their levels
+    pub enchantments: Vec<(i32, i32)>,
+    // TODO: Add more Item Components
+}
+
+impl ItemComponents {
+    pub const fn new() -> Self {
+        Self {
+            custom_name: None,
+            lore: Vec::new(),
+            enchantments: Vec::new(),
+        }
+    }
+
+    /// Empty components are sent as "no data"
+    pub fn is_empty(&self) -> bool {
+        self.custom_name.is_none() && self.lore.is_empty() && self.enchantments.is_empty()
+    }
 }
 
 impl PartialEq for ItemStack {
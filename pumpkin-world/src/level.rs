@@ -1,21 +1,26 @@
 use std::{
     collections::HashMap,
-    fs::OpenOptions,
-    io::{Read, Seek},
-    path::PathBuf,
+    fs::{self, File, OpenOptions},
+    io::{Read, Seek, Write},
+    path::{Path, PathBuf},
     sync::Arc,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
-use flate2::{bufread::ZlibDecoder, read::GzDecoder};
+use flate2::{bufread::ZlibDecoder, read::GzDecoder, write::ZlibEncoder};
 use itertools::Itertools;
 use parking_lot::Mutex;
-use pumpkin_core::math::vector2::Vector2;
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::math::{position::WorldPosition, vector2::Vector2};
 use rayon::prelude::*;
 use thiserror::Error;
 use tokio::sync::mpsc;
 
 use crate::{
-    chunk::ChunkData,
+    block::BlockId,
+    chunk::{ChunkBlocks, ChunkData},
+    coordinates::ChunkRelativeBlockCoordinates,
     world_gen::{get_world_gen, Seed, WorldGenerator},
 };
 
@@ -26,20 +31,36 @@ use crate::{
 /// - **Chunk Loading:** Efficiently loads chunks from disk (Anvil format).
 /// - **Chunk Caching:** Stores accessed chunks in memory for faster access.
 /// - **Chunk Generation:** Generates new chunks on-demand using a specified `WorldGenerator`.
+/// - **Chunk Saving:** Writes changed chunks back into Anvil region files.
 ///
 /// For more details on world generation, refer to the `WorldGenerator` module.
 pub struct Level {
-    save_file: Option<SaveFile>,
+    save_file: SaveFile,
     loaded_chunks: Arc<Mutex<HashMap<Vector2<i32>, Arc<ChunkData>>>>,
+    /// Changed chunks which were unloaded but are still being written, fetching one of them takes it back
+    unloading_chunks: Mutex<HashMap<Vector2<i32>, Arc<ChunkData>>>,
+    /// Held while region files are written, so saving and unloading never rewrite the same region at once
+    region_lock: Mutex<()>,
     world_gen: Box<dyn WorldGenerator>,
 }
 
 struct SaveFile {
-    #[expect(dead_code)]
     root_folder: PathBuf,
     region_folder: PathBuf,
 }
 
+/// Chunks are stored in sectors of 4KiB inside of a region file
+const SECTOR_SIZE: usize = 4096;
+/// The location and timestamp tables, one sector each
+const REGION_HEADER_SIZE: usize = SECTOR_SIZE * 2;
+const REGION_CHUNKS: usize = 32 * 32;
+const ZLIB_COMPRESSION: u8 = 2;
+
+/// How often reading a chunk is tried before a placeholder is sent instead
+const FETCH_ATTEMPTS: u32 = 3;
+/// The delay before the first retry, it doubles with every retry
+const FETCH_RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
 #[derive(Error, Debug)]
 pub enum WorldError {
     // using ErrorKind instead of Error, beacuse the function read_chunks and read_region_chunks is designed to return an error on a per-chunk basis, while std::io::Error does not implement Copy or Clone
@@ -59,6 +80,21 @@ pub enum WorldError {
     BlockStateIdNotFound,
     #[error("The block is not inside of the chunk")]
     BlockOutsideChunk,
+    #[error("The chunk is not loaded")]
+    ChunkNotLoaded,
+    #[error("Error serializing chunk: {0}")]
+    ErrorSerializingChunk(String),
+    #[error("The chunk is too large to be saved")]
+    ChunkTooLarge,
+}
+
+impl WorldError {
+    /// Whether trying again may work, like when the region file is being written at the same time.
+    ///
+    /// Broken chunk data stays broken
+    pub fn is_transient(&self) -> bool {
+        matches!(self, Self::IoError(_))
+    }
 }
 
 #[derive(Error, Debug)]
@@ -105,37 +141,57 @@ impl Compression {
 
 impl Level {
     pub fn from_root_folder(root_folder: PathBuf) -> Self {
-        let world_gen = get_world_gen(Seed(0)); // TODO Read Seed from config.
-
-        if root_folder.exists() {
-            let region_folder = root_folder.join("region");
-            assert!(
-                region_folder.exists(),
-                "World region folder does not exist, despite there being a root folder."
-            );
+        let world_gen = get_world_gen(Seed(0), &ADVANCED_CONFIG.world_gen); // TODO Read Seed from config.
 
-            Self {
-                world_gen,
-                save_file: Some(SaveFile {
-                    root_folder,
-                    region_folder,
-                }),
-                loaded_chunks: Arc::new(Mutex::new(HashMap::new())),
-            }
-        } else {
+        if !root_folder.exists() {
             log::warn!(
                 "Pumpkin currently only supports Superflat World generation. Use a vanilla ./world folder to play in a normal world."
             );
-
-            Self {
-                world_gen,
-                save_file: None,
-                loaded_chunks: Arc::new(Mutex::new(HashMap::new())),
-            }
+        }
+        // The folders get created once something is saved
+        let region_folder = root_folder.join("region");
+        Self {
+            world_gen,
+            save_file: SaveFile {
+                root_folder,
+                region_folder,
+            },
+            loaded_chunks: Arc::new(Mutex::new(HashMap::new())),
+            unloading_chunks: Mutex::new(HashMap::new()),
+            region_lock: Mutex::new(()),
         }
     }
 
-    pub fn get_block() {}
+    /// The folder the level is saved in
+    pub fn root_folder(&self) -> &Path {
+        &self.save_file.root_folder
+    }
+
+    /// Gets a chunk if it is kept in memory
+    pub fn get_loaded_chunk(&self, at: Vector2<i32>) -> Option<Arc<ChunkData>> {
+        self.loaded_chunks.lock().get(&at).cloned()
+    }
+
+    /// The amount of chunks currently kept in memory
+    pub fn loaded_chunk_count(&self) -> usize {
+        self.loaded_chunks.lock().len()
+    }
+
+    /// Gets the block at the given position.
+    ///
+    /// The chunk has to be loaded already
+    pub fn get_block(&self, position: WorldPosition) -> Result<BlockId, WorldError> {
+        let (at, relative) = ChunkRelativeBlockCoordinates::from_world_position(position)
+            .ok_or(WorldError::BlockOutsideChunk)?;
+        let chunk = self
+            .loaded_chunks
+            .lock()
+            .get(&at)
+            .cloned()
+            .ok_or(WorldError::ChunkNotLoaded)?;
+        let block = chunk.blocks.read().get_block(relative);
+        Ok(block)
+    }
 
     /// Reads/Generates many chunks in a world
     /// MUST be called from a tokio runtime thread
@@ -151,55 +207,336 @@ impl Level {
             if is_alive {
                 return;
             }
-            let mut loaded_chunks = self.loaded_chunks.lock();
-            let channel = channel.clone();
+            let chunk = self.fetch_chunk(*at, FETCH_RETRY_BACKOFF, |at| {
+                Self::read_chunk(&self.save_file, at)
+            });
+            // The receiver may have stopped waiting, then nobody needs the chunk anymore
+            let _ = channel.blocking_send(Ok(chunk));
+        })
+    }
 
-            // Check if chunks is already loaded
-            if loaded_chunks.contains_key(at) {
-                channel
-                    .blocking_send(Ok(loaded_chunks.get(at).unwrap().clone()))
-                    .expect("Failed sending ChunkData.");
-                return;
+    /// Gets a loaded chunk, or reads it with `read` and generates it if it was never generated.
+    ///
+    /// Transient errors are retried with a growing delay starting at `backoff`. If the chunk still can't be read,
+    /// an empty placeholder is returned so the client isn't left waiting. It isn't kept, so it never overwrites the saved chunk
+    fn fetch_chunk(
+        &self,
+        at: Vector2<i32>,
+        backoff: Duration,
+        mut read: impl FnMut(Vector2<i32>) -> Result<ChunkData, WorldError>,
+    ) -> Arc<ChunkData> {
+        {
+            let mut loaded_chunks = self.loaded_chunks.lock();
+            if let Some(chunk) = loaded_chunks.get(&at) {
+                return chunk.clone();
+            }
+            // Its region file may not contain the latest changes yet
+            if let Some(chunk) = self.unloading_chunks.lock().get(&at) {
+                loaded_chunks.insert(at, chunk.clone());
+                return chunk.clone();
             }
-            let at = *at;
-            let data = match &self.save_file {
-                Some(save_file) => {
-                    match Self::read_chunk(save_file, at) {
-                        Err(WorldError::ChunkNotGenerated(_)) => {
-                            // This chunk was not generated yet.
-                            Ok(self.world_gen.generate_chunk(at))
-                        }
-                        // TODO this doesn't warn the user about the error. fix.
-                        result => result,
-                    }
+        }
+        let data = match Self::with_retries(at, backoff, &mut read) {
+            Err(WorldError::ChunkNotGenerated(_)) => {
+                // This chunk was not generated yet.
+                // It is only saved once it changes, until then generating it again gives the same chunk
+                self.world_gen.generate_chunk(at)
+            }
+            Ok(chunk) => chunk,
+            Err(err) => {
+                log::error!(
+                    "Couldn't load chunk {} {}, sending an empty one instead: {}",
+                    at.x,
+                    at.z,
+                    err
+                );
+                return Arc::new(ChunkData::new(ChunkBlocks::default(), at));
+            }
+        };
+        // Another Player may have loaded it in the meantime
+        self.loaded_chunks
+            .lock()
+            .entry(at)
+            .or_insert_with(|| Arc::new(data))
+            .clone()
+    }
+
+    /// Calls `read` up to `FETCH_ATTEMPTS` times while it fails with a transient error, doubling the delay every time
+    fn with_retries(
+        at: Vector2<i32>,
+        mut backoff: Duration,
+        read: &mut impl FnMut(Vector2<i32>) -> Result<ChunkData, WorldError>,
+    ) -> Result<ChunkData, WorldError> {
+        let mut attempt = 1;
+        loop {
+            match read(at) {
+                Err(err) if err.is_transient() && attempt < FETCH_ATTEMPTS => {
+                    log::warn!(
+                        "Couldn't load chunk {} {} (attempt {}/{}), retrying: {}",
+                        at.x,
+                        at.z,
+                        attempt,
+                        FETCH_ATTEMPTS,
+                        err
+                    );
+                    thread::sleep(backoff);
+                    backoff *= 2;
+                    attempt += 1;
                 }
-                None => {
-                    // There is no savefile yet -> generate the chunks
-                    Ok(self.world_gen.generate_chunk(at))
+                result => return result,
+            }
+        }
+    }
+
+    /// Sets the block at the given position and marks its chunk dirty, returning the old block.
+    ///
+    /// The chunk has to be loaded already
+    pub fn set_block(
+        &self,
+        position: WorldPosition,
+        block: BlockId,
+    ) -> Result<BlockId, WorldError> {
+        let (at, relative) = ChunkRelativeBlockCoordinates::from_world_position(position)
+            .ok_or(WorldError::BlockOutsideChunk)?;
+        let chunk = self
+            .loaded_chunks
+            .lock()
+            .get(&at)
+            .cloned()
+            .ok_or(WorldError::ChunkNotLoaded)?;
+        Ok(chunk.set_block(relative, block))
+    }
+
+    /// Marks a loaded chunk as changed, e.g. when an entity inside of it changed
+    pub fn mark_chunk_dirty(&self, at: Vector2<i32>) {
+        if let Some(chunk) = self.loaded_chunks.lock().get(&at) {
+            chunk.mark_dirty();
+        }
+    }
+
+    /// Returns every loaded chunk which changed since it was last saved and clears their dirty flag.
+    ///
+    /// Callers have to mark a chunk dirty again if saving it fails
+    pub fn take_dirty_chunks(&self) -> Vec<Arc<ChunkData>> {
+        self.loaded_chunks
+            .lock()
+            .values()
+            .filter(|chunk| chunk.take_dirty())
+            .cloned()
+            .collect_vec()
+    }
+
+    /// Saves every loaded chunk which changed since it was last saved.
+    ///
+    /// Returns how many chunks were written
+    pub fn save_chunks(&self) -> Result<usize, WorldError> {
+        self.save_dirty_chunks(|region, chunks| Self::write_region(&self.save_file, region, chunks))
+    }
+
+    /// Writes the dirty chunks of every region at once, so each region file only gets rewritten once.
+    ///
+    /// Only taking the dirty chunks locks the loaded chunks, they can be used while the chunks are written
+    fn save_dirty_chunks(
+        &self,
+        mut write: impl FnMut(Vector2<i32>, &[Arc<ChunkData>]) -> Result<(), WorldError>,
+    ) -> Result<usize, WorldError> {
+        let _region_lock = self.region_lock.lock();
+        let mut saved = 0;
+        let mut result = Ok(());
+        let regions = self
+            .take_dirty_chunks()
+            .into_iter()
+            .into_group_map_by(|chunk| Self::region_of(chunk.position));
+        for (region, chunks) in regions {
+            match write(region, &chunks) {
+                Ok(()) => saved += chunks.len(),
+                Err(err) => {
+                    // Try again next time
+                    chunks.iter().for_each(|chunk| chunk.mark_dirty());
+                    result = Err(err);
                 }
             }
-            .unwrap();
-            let data = Arc::new(data);
-            channel
-                .blocking_send(Ok(data.clone()))
-                .expect("Failed sending ChunkData.");
-            loaded_chunks.insert(at, data);
+        }
+        result.map(|()| saved)
+    }
+
+    /// Removes the chunks from memory, saving the ones which changed first.
+    ///
+    /// Chunks for which `is_watched` returns true are kept, it is called while no chunk can be loaded.
+    /// Chunks which could not be saved stay loaded, so their changes are not lost
+    pub fn unload_chunks(
+        &self,
+        chunks: &[Vector2<i32>],
+        is_watched: impl Fn(Vector2<i32>) -> bool,
+    ) -> Result<(), WorldError> {
+        self.unload_chunks_with(chunks, is_watched, |region, chunks| {
+            Self::write_region(&self.save_file, region, chunks)
         })
     }
 
+    fn unload_chunks_with(
+        &self,
+        chunks: &[Vector2<i32>],
+        is_watched: impl Fn(Vector2<i32>) -> bool,
+        mut write: impl FnMut(Vector2<i32>, &[Arc<ChunkData>]) -> Result<(), WorldError>,
+    ) -> Result<(), WorldError> {
+        let _region_lock = self.region_lock.lock();
+        let regions = {
+            let mut loaded_chunks = self.loaded_chunks.lock();
+            let mut unloading_chunks = self.unloading_chunks.lock();
+            chunks
+                .iter()
+                .filter(|at| !is_watched(**at))
+                .filter_map(|at| loaded_chunks.remove(at))
+                .filter(|chunk| chunk.take_dirty())
+                .inspect(|chunk| {
+                    unloading_chunks.insert(chunk.position, chunk.clone());
+                })
+                .into_group_map_by(|chunk| Self::region_of(chunk.position))
+        };
+        let mut result = Ok(());
+        for (region, chunks) in regions {
+            let written = write(region, &chunks);
+            let mut loaded_chunks = self.loaded_chunks.lock();
+            let mut unloading_chunks = self.unloading_chunks.lock();
+            for chunk in chunks {
+                unloading_chunks.remove(&chunk.position);
+                if written.is_err() {
+                    chunk.mark_dirty();
+                    // It may have been fetched again while it was written
+                    loaded_chunks.entry(chunk.position).or_insert(chunk);
+                }
+            }
+            if let Err(err) = written {
+                result = Err(err);
+            }
+        }
+        result
+    }
+
+    /// The region a chunk is saved in, each region contains 32x32 chunks
+    fn region_of(at: Vector2<i32>) -> Vector2<i32> {
+        Vector2::new(at.x.div_euclid(32), at.z.div_euclid(32))
+    }
+
+    /// The index of a chunk in the tables of its region file
+    fn region_index(at: Vector2<i32>) -> usize {
+        (at.x.rem_euclid(32) + at.z.rem_euclid(32) * 32) as usize
+    }
+
+    fn region_file(save_file: &SaveFile, region: Vector2<i32>) -> PathBuf {
+        save_file
+            .region_folder
+            .join(format!("r.{}.{}.mca", region.x, region.z))
+    }
+
+    /// Writes the chunks into their region file, keeping the other chunks of the region.
+    ///
+    /// The region file is rebuilt with all chunks packed one after another
+    /// and then atomically replaces the old file, so a crash never leaves a half written region behind
+    fn write_region(
+        save_file: &SaveFile,
+        region: Vector2<i32>,
+        chunks: &[Arc<ChunkData>],
+    ) -> Result<(), WorldError> {
+        let io_error = |err: std::io::Error| WorldError::IoError(err.kind());
+
+        let mut payloads = HashMap::new();
+        for chunk in chunks {
+            payloads.insert(
+                Self::region_index(chunk.position),
+                Self::chunk_payload(chunk)?,
+            );
+        }
+
+        fs::create_dir_all(&save_file.region_folder).map_err(io_error)?;
+        let path = Self::region_file(save_file, region);
+        let old_region = match fs::read(&path) {
+            Ok(old_region) => old_region,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(io_error(err)),
+        };
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs() as u32)
+            .to_be_bytes();
+
+        let mut region_data = vec![0; REGION_HEADER_SIZE];
+        for index in 0..REGION_CHUNKS {
+            let (payload, timestamp) = match payloads.get(&index) {
+                Some(payload) => (payload.as_slice(), timestamp),
+                None => match Self::old_chunk_payload(&old_region, index) {
+                    Some(old) => old,
+                    None => continue,
+                },
+            };
+
+            let sector_offset = region_data.len() / SECTOR_SIZE;
+            let sector_count = payload.len().div_ceil(SECTOR_SIZE);
+            if sector_count > u8::MAX as usize {
+                // TODO: vanilla stores these chunks in external .mcc files
+                return Err(WorldError::ChunkTooLarge);
+            }
+            region_data.extend_from_slice(payload);
+            region_data.resize((sector_offset + sector_count) * SECTOR_SIZE, 0);
+
+            let location = (((sector_offset as u32) << 8) | sector_count as u32).to_be_bytes();
+            region_data[index * 4..index * 4 + 4].copy_from_slice(&location);
+            region_data[SECTOR_SIZE + index * 4..SECTOR_SIZE + index * 4 + 4]
+                .copy_from_slice(&timestamp);
+        }
+
+        let temp_path = path.with_extension("mca.tmp");
+        let mut temp_file = File::create(&temp_path).map_err(io_error)?;
+        temp_file.write_all(&region_data).map_err(io_error)?;
+        temp_file.sync_all().map_err(io_error)?;
+        fs::rename(&temp_path, &path).map_err(io_error)
+    }
+
+    /// The length, compression scheme and the compressed NBT of a chunk, like they are stored in a region file
+    fn chunk_payload(chunk: &ChunkData) -> Result<Vec<u8>, WorldError> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(&chunk.to_bytes()?)
+            .and_then(|()| encoder.finish())
+            .map(|compressed| {
+                // The length includes the compression scheme byte
+                let mut payload = ((compressed.len() + 1) as u32).to_be_bytes().to_vec();
+                payload.push(ZLIB_COMPRESSION);
+                payload.extend_from_slice(&compressed);
+                payload
+            })
+            .map_err(|err| WorldError::Compression(CompressionError::ZlibError(err)))
+    }
+
+    /// The sectors and timestamp of a chunk in an existing region file, `None` if it is missing or invalid
+    fn old_chunk_payload(old_region: &[u8], index: usize) -> Option<(&[u8], [u8; 4])> {
+        let location = old_region.get(index * 4..index * 4 + 4)?;
+        let sector_offset = u32::from_be_bytes([0, location[0], location[1], location[2]]) as usize;
+        let sector_count = location[3] as usize;
+        let start = sector_offset * SECTOR_SIZE;
+        if sector_count == 0 || start < REGION_HEADER_SIZE || start >= old_region.len() {
+            return None;
+        }
+        let end = (start + sector_count * SECTOR_SIZE).min(old_region.len());
+        let timestamp = old_region
+            .get(SECTOR_SIZE + index * 4..SECTOR_SIZE + index * 4 + 4)?
+            .try_into()
+            .ok()?;
+        Some((&old_region[start..end], timestamp))
+    }
+
     fn read_chunk(save_file: &SaveFile, at: Vector2<i32>) -> Result<ChunkData, WorldError> {
-        let region = (
-            ((at.x as f32) / 32.0).floor() as i32,
-            ((at.z as f32) / 32.0).floor() as i32,
-        );
+        let chunk_data = Self::read_chunk_bytes(save_file, at)?;
+        ChunkData::from_bytes(chunk_data, at)
+    }
 
+    /// Reads the decompressed NBT of a chunk from its region file
+    fn read_chunk_bytes(save_file: &SaveFile, at: Vector2<i32>) -> Result<Vec<u8>, WorldError> {
         let mut region_file = OpenOptions::new()
             .read(true)
-            .open(
-                save_file
-                    .region_folder
-                    .join(format!("r.{}.{}.mca", region.0, region.1)),
-            )
+            .open(Self::region_file(save_file, Self::region_of(at)))
             .map_err(|err| match err.kind() {
                 std::io::ErrorKind::NotFound => {
                     WorldError::ChunkNotGenerated(ChunkNotGeneratedError::RegionFileMissing)
@@ -218,15 +555,12 @@ impl Level {
             .read_exact(&mut timestamp_table)
             .map_err(|err| WorldError::IoError(err.kind()))?;
 
-        let modulus = |a: i32, b: i32| ((a % b) + b) % b;
-        let chunk_x = modulus(at.x, 32) as u32;
-        let chunk_z = modulus(at.z, 32) as u32;
-        let table_entry = (chunk_x + chunk_z * 32) * 4;
+        let table_entry = Self::region_index(at) * 4;
 
         let mut offset = vec![0u8];
-        offset.extend_from_slice(&location_table[table_entry as usize..table_entry as usize + 3]);
-        let offset = u32::from_be_bytes(offset.try_into().unwrap()) as u64 * 4096;
-        let size = location_table[table_entry as usize + 3] as usize * 4096;
+        offset.extend_from_slice(&location_table[table_entry..table_entry + 3]);
+        let offset = u32::from_be_bytes(offset.try_into().unwrap()) as u64 * SECTOR_SIZE as u64;
+        let size = location_table[table_entry + 3] as usize * SECTOR_SIZE;
 
         if offset == 0 && size == 0 {
             return Err(WorldError::ChunkNotGenerated(
@@ -256,10 +590,7 @@ impl Level {
 
         // size includes the compression scheme byte, so we need to subtract 1
         let chunk_data = file_buf.drain(0..size as usize - 1).collect_vec();
-        let decompressed_chunk =
-            Self::decompress_data(compression, chunk_data).map_err(WorldError::Compression)?;
-
-        ChunkData::from_bytes(decompressed_chunk, at)
+        Self::decompress_data(compression, chunk_data).map_err(WorldError::Compression)
     }
 
     fn decompress_data(
@@ -286,3 +617,314 @@ impl Level {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, io::ErrorKind, path::PathBuf, sync::Arc, time::Duration};
+
+    use pumpkin_core::math::{position::WorldPosition, vector2::Vector2, vector3::Vector3};
+
+    use crate::{
+        block::BlockId,
+        chunk::{ChunkBlocks, ChunkData},
+        coordinates::ChunkRelativeBlockCoordinates,
+        level::WorldError,
+        WORLD_LOWEST_Y,
+    };
+
+    use super::{ChunkNotGeneratedError, Level, SaveFile, FETCH_ATTEMPTS, SECTOR_SIZE};
+
+    fn level_with_chunks(chunks: &[(i32, bool)]) -> Level {
+        let level = Level::from_root_folder(env::temp_dir().join("pumpkin-level-test"));
+        for (x, dirty) in chunks {
+            let chunk = ChunkData::new(ChunkBlocks::default(), Vector2::new(*x, 0));
+            if *dirty {
+                chunk.mark_dirty();
+            }
+            level
+                .loaded_chunks
+                .lock()
+                .insert(Vector2::new(*x, 0), Arc::new(chunk));
+        }
+        level
+    }
+
+    #[test]
+    fn test_save_only_dirty_chunks() {
+        let level = level_with_chunks(&[(0, true), (1, false), (2, true)]);
+        let mut written = Vec::new();
+        let saved = level
+            .save_dirty_chunks(|_, chunks| {
+                written.extend(chunks.iter().map(|chunk| chunk.position.x));
+                Ok(())
+            })
+            .unwrap();
+        written.sort();
+        assert_eq!(saved, 2);
+        assert_eq!(written, vec![0, 2]);
+        assert!(level
+            .loaded_chunks
+            .lock()
+            .values()
+            .all(|chunk| !chunk.is_dirty()));
+
+        // Nothing changed, so nothing is written again
+        assert_eq!(level.save_dirty_chunks(|_, _| unreachable!()).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_failed_save_stays_dirty() {
+        let level = level_with_chunks(&[(0, true)]);
+        assert!(level
+            .save_dirty_chunks(|_, _| Err(WorldError::ChunkTooLarge))
+            .is_err());
+        assert!(level.loaded_chunks.lock()[&Vector2::new(0, 0)].is_dirty());
+    }
+
+    #[test]
+    fn test_set_block_marks_dirty() {
+        let level = level_with_chunks(&[(-1, false), (0, false)]);
+        let stone = BlockId { data: 1 };
+        let position = WorldPosition(Vector3::new(-3, 70, 5));
+
+        assert_eq!(
+            level.set_block(position, stone).unwrap(),
+            BlockId::default()
+        );
+        assert!(level.loaded_chunks.lock()[&Vector2::new(-1, 0)].is_dirty());
+        assert!(!level.loaded_chunks.lock()[&Vector2::new(0, 0)].is_dirty());
+        assert_eq!(level.get_block(position).unwrap(), stone);
+
+        // Saving clears the dirty flag and the block is kept
+        let dirty = level.take_dirty_chunks();
+        assert_eq!(dirty.len(), 1);
+        assert_eq!(dirty[0].position, Vector2::new(-1, 0));
+        assert!(level.take_dirty_chunks().is_empty());
+        assert_eq!(level.set_block(position, stone).unwrap(), stone);
+        // Setting the same block again is not a change
+        assert!(level.take_dirty_chunks().is_empty());
+
+        assert!(matches!(
+            level.set_block(WorldPosition(Vector3::new(40, 70, 0)), stone),
+            Err(WorldError::ChunkNotLoaded)
+        ));
+        assert!(matches!(
+            level.set_block(WorldPosition(Vector3::new(0, 320, 0)), stone),
+            Err(WorldError::BlockOutsideChunk)
+        ));
+        assert!(matches!(
+            level.get_block(WorldPosition(Vector3::new(40, 70, 0))),
+            Err(WorldError::ChunkNotLoaded)
+        ));
+    }
+
+    #[test]
+    fn test_unload_saves_dirty_chunks() {
+        let level = level_with_chunks(&[(0, true), (1, false), (2, true), (3, true)]);
+        let mut written = Vec::new();
+        level
+            .unload_chunks_with(
+                &[Vector2::new(0, 0), Vector2::new(1, 0), Vector2::new(3, 0)],
+                |at| at.x == 3,
+                |_, chunks| {
+                    written.extend(chunks.iter().map(|chunk| chunk.position.x));
+                    Ok(())
+                },
+            )
+            .unwrap();
+        assert_eq!(written, vec![0]);
+        // Watched chunks stay loaded
+        assert_eq!(level.loaded_chunks.lock().len(), 2);
+        assert!(level.loaded_chunks.lock()[&Vector2::new(3, 0)].is_dirty());
+
+        // A chunk which failed to save stays loaded
+        assert!(level
+            .unload_chunks_with(
+                &[Vector2::new(2, 0)],
+                |_| false,
+                |_, _| Err(WorldError::ChunkTooLarge)
+            )
+            .is_err());
+        assert!(level.loaded_chunks.lock()[&Vector2::new(2, 0)].is_dirty());
+        assert!(level.unloading_chunks.lock().is_empty());
+    }
+
+    #[test]
+    fn test_fetch_while_unloading() {
+        let level = level_with_chunks(&[(0, true)]);
+        let at = Vector2::new(0, 0);
+        let unloaded = level.get_loaded_chunk(at).unwrap();
+        level
+            .unload_chunks_with(
+                &[at],
+                |_| false,
+                |_, _| {
+                    // The region file doesn't have the changes yet, so the chunk must not be read from it
+                    let fetched = level.fetch_chunk(at, Duration::ZERO, |_| {
+                        panic!("The chunk is still being written")
+                    });
+                    assert!(Arc::ptr_eq(&fetched, &unloaded));
+                    Ok(())
+                },
+            )
+            .unwrap();
+        assert!(Arc::ptr_eq(&level.get_loaded_chunk(at).unwrap(), &unloaded));
+        assert!(level.unloading_chunks.lock().is_empty());
+    }
+
+    fn empty_save_file(name: &str) -> SaveFile {
+        let root_folder = env::temp_dir().join(format!("pumpkin-region-test-{}", name));
+        let _ = fs::remove_dir_all(&root_folder);
+        SaveFile {
+            region_folder: root_folder.join("region"),
+            root_folder,
+        }
+    }
+
+    /// A chunk with a different block in every column
+    fn noisy_chunk(at: Vector2<i32>, seed: u32) -> Arc<ChunkData> {
+        let chunk = ChunkData::new(ChunkBlocks::default(), at);
+        let mut state = seed;
+        for x in 0..16 {
+            for z in 0..16 {
+                for y in WORLD_LOWEST_Y as i32..WORLD_LOWEST_Y as i32 + 48 {
+                    state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                    let block = BlockId {
+                        data: (state >> 16) as u16 % 2000,
+                    };
+                    let position = WorldPosition(Vector3::new(at.x * 16 + x, y, at.z * 16 + z));
+                    let (_, relative) =
+                        ChunkRelativeBlockCoordinates::from_world_position(position).unwrap();
+                    chunk.set_block(relative, block);
+                }
+            }
+        }
+        Arc::new(chunk)
+    }
+
+    fn region_file_of(save_file: &SaveFile, at: Vector2<i32>) -> PathBuf {
+        Level::region_file(save_file, Level::region_of(at))
+    }
+
+    #[test]
+    fn test_write_and_read_back() {
+        let save_file = empty_save_file("read-back");
+        let at = Vector2::new(-3, 40);
+        let chunk = ChunkData::new(ChunkBlocks::default(), at);
+        let stone = BlockId { data: 1 };
+        let position = WorldPosition(Vector3::new(-45, 10, 650));
+        let (_, relative) = ChunkRelativeBlockCoordinates::from_world_position(position).unwrap();
+        chunk.set_block(relative, stone);
+        let chunk = Arc::new(chunk);
+
+        Level::write_region(&save_file, Level::region_of(at), &[chunk.clone()]).unwrap();
+
+        // The stored NBT is exactly what was serialized
+        let nbt = Level::read_chunk_bytes(&save_file, at).unwrap();
+        assert_eq!(nbt, chunk.to_bytes().unwrap());
+
+        let read = Level::read_chunk(&save_file, at).unwrap();
+        assert_eq!(read.position, at);
+        assert_eq!(read.blocks.read().get_block(relative), stone);
+        // Saving it again keeps the same NBT, the order of the tags may differ
+        let nbt_value = |nbt: &[u8]| fastnbt::from_bytes::<fastnbt::Value>(nbt).unwrap();
+        assert_eq!(nbt_value(&read.to_bytes().unwrap()), nbt_value(&nbt));
+        assert!(!region_file_of(&save_file, at)
+            .with_extension("mca.tmp")
+            .exists());
+    }
+
+    #[test]
+    fn test_multi_sector_chunks() {
+        let save_file = empty_save_file("multi-sector");
+        let first = noisy_chunk(Vector2::new(0, 0), 1);
+        let second = noisy_chunk(Vector2::new(1, 0), 2);
+        let region = Level::region_of(first.position);
+
+        Level::write_region(&save_file, region, &[first.clone(), second.clone()]).unwrap();
+        let region_data = fs::read(region_file_of(&save_file, first.position)).unwrap();
+        // The first chunk needs more than one sector
+        assert!(region_data[3] > 1);
+        assert_eq!(region_data.len() % SECTOR_SIZE, 0);
+
+        // Rewriting one chunk keeps the other one
+        let changed = noisy_chunk(Vector2::new(0, 0), 3);
+        Level::write_region(&save_file, region, &[changed.clone()]).unwrap();
+        assert_eq!(
+            Level::read_chunk_bytes(&save_file, changed.position).unwrap(),
+            changed.to_bytes().unwrap()
+        );
+        assert_eq!(
+            Level::read_chunk_bytes(&save_file, second.position).unwrap(),
+            second.to_bytes().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_fetch_retries_transient_errors() {
+        let level = level_with_chunks(&[]);
+        let at = Vector2::new(3, -2);
+        let stone = BlockId { data: 1 };
+        let (_, relative) = ChunkRelativeBlockCoordinates::from_world_position(WorldPosition(
+            Vector3::new(49, 10, -30),
+        ))
+        .unwrap();
+        let mut attempts = 0;
+        let chunk = level.fetch_chunk(at, Duration::ZERO, |at| {
+            attempts += 1;
+            if attempts == 1 {
+                return Err(WorldError::IoError(ErrorKind::Interrupted));
+            }
+            let chunk = ChunkData::new(ChunkBlocks::default(), at);
+            chunk.set_block(relative, stone);
+            Ok(chunk)
+        });
+        assert_eq!(attempts, 2);
+        assert_eq!(chunk.blocks.read().get_block(relative), stone);
+        assert!(level.get_loaded_chunk(at).is_some());
+    }
+
+    #[test]
+    fn test_generated_chunks_are_clean() {
+        let level = level_with_chunks(&[]);
+        let at = Vector2::new(5, 7);
+        let chunk = level.fetch_chunk(at, Duration::ZERO, |_| {
+            Err(WorldError::ChunkNotGenerated(
+                ChunkNotGeneratedError::RegionFileMissing,
+            ))
+        });
+        assert!(level.get_loaded_chunk(at).is_some());
+        // Saving right after generating must not write the whole world
+        assert!(!chunk.is_dirty());
+        assert!(level.take_dirty_chunks().is_empty());
+    }
+
+    #[test]
+    fn test_fetch_sends_placeholder() {
+        let level = level_with_chunks(&[]);
+        let at = Vector2::new(3, -2);
+
+        // Broken data isn't read again
+        let mut attempts = 0;
+        let chunk = level.fetch_chunk(at, Duration::ZERO, |_| {
+            attempts += 1;
+            Err(WorldError::RegionIsInvalid)
+        });
+        assert_eq!(attempts, 1);
+        assert_eq!(chunk.position, at);
+        assert_eq!(chunk.blocks.read().non_empty_sections(), 0);
+        // The placeholder must not replace the saved chunk
+        assert!(level.get_loaded_chunk(at).is_none());
+        assert!(!chunk.is_dirty());
+
+        // Transient errors are only retried a few times
+        let mut attempts = 0;
+        let chunk = level.fetch_chunk(at, Duration::ZERO, |_| {
+            attempts += 1;
+            Err(WorldError::IoError(ErrorKind::TimedOut))
+        });
+        assert_eq!(attempts, FETCH_ATTEMPTS);
+        assert_eq!(chunk.blocks.read().non_empty_sections(), 0);
+        assert!(level.get_loaded_chunk(at).is_none());
+    }
+}
@@ -74,10 +74,7 @@ impl<B: BiomeGenerator, T: PerlinTerrainGenerator> WorldGenerator for GenericGen
             }
         }
 
-        ChunkData {
-            blocks,
-            position: at,
-        }
+        ChunkData::new(blocks, at)
     }
 }
 
@@ -1,52 +1,52 @@
+use pumpkin_config::FlatLayer;
 use pumpkin_core::math::vector2::Vector2;
-use pumpkin_macros::block;
 
 use crate::{
-    biome::Biome,
-    block::block_state::BlockState,
-    coordinates::{BlockCoordinates, XZBlockCoordinates},
-    world_gen::{
-        generator::{BiomeGenerator, GeneratorInit, TerrainGenerator},
-        generic_generator::GenericGenerator,
-        Seed,
-    },
+    block::{block_state::BlockState, BlockId},
+    chunk::{ChunkBlocks, ChunkData},
+    coordinates::ChunkRelativeBlockCoordinates,
+    world_gen::generator::WorldGenerator,
+    WORLD_LOWEST_Y,
 };
 
-#[expect(dead_code)]
-pub type SuperflatGenerator = GenericGenerator<SuperflatBiomeGenerator, SuperflatTerrainGenerator>;
-
-pub(crate) struct SuperflatBiomeGenerator {}
-
-impl GeneratorInit for SuperflatBiomeGenerator {
-    fn new(_: Seed) -> Self {
-        Self {}
-    }
+/// Stacks the configured layers on top of each other, starting at the bottom of the world
+pub struct SuperflatGenerator {
+    /// One block per height, from `WORLD_LOWEST_Y` upwards
+    layers: Vec<BlockId>,
 }
 
-impl BiomeGenerator for SuperflatBiomeGenerator {
-    // TODO make generic over Biome and allow changing the Biome in the config.
-    fn generate_biome(&self, _: XZBlockCoordinates) -> Biome {
-        Biome::Plains
-    }
-}
-
-pub(crate) struct SuperflatTerrainGenerator {}
-
-impl GeneratorInit for SuperflatTerrainGenerator {
-    fn new(_: Seed) -> Self {
-        Self {}
+impl SuperflatGenerator {
+    pub fn new(layers: &[FlatLayer]) -> Self {
+        let layers = layers
+            .iter()
+            .flat_map(|layer| {
+                let block = BlockState::new(&layer.block, None).unwrap_or_else(|_| {
+                    panic!("Unknown block {} in the flat world layers", layer.block)
+                });
+                std::iter::repeat(BlockId::from(block)).take(layer.height as usize)
+            })
+            .collect();
+        Self { layers }
     }
 }
 
-impl TerrainGenerator for SuperflatTerrainGenerator {
-    fn prepare_chunk(&self, _at: &Vector2<i32>) {}
-    // TODO allow specifying which blocks should be at which height in the config.
-    fn generate_block(&self, at: BlockCoordinates, _: Biome) -> BlockState {
-        match *at.y {
-            -64 => block!("minecraft:bedrock"),
-            -63..=-62 => block!("minecraft:dirt"),
-            -61 => block!("minecraft:grass_block"),
-            _ => BlockState::AIR,
+impl WorldGenerator for SuperflatGenerator {
+    fn generate_chunk(&self, at: Vector2<i32>) -> ChunkData {
+        let mut blocks = ChunkBlocks::default();
+        for (y, block) in self.layers.iter().enumerate() {
+            for x in 0..16u8 {
+                for z in 0..16u8 {
+                    blocks.set_block(
+                        ChunkRelativeBlockCoordinates {
+                            x: x.into(),
+                            y: (WORLD_LOWEST_Y + y as i16).into(),
+                            z: z.into(),
+                        },
+                        *block,
+                    );
+                }
+            }
         }
+        ChunkData::new(blocks, at)
     }
 }
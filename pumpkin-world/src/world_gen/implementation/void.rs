@@ -0,0 +1,38 @@
+use pumpkin_core::math::vector2::Vector2;
+use pumpkin_macros::block;
+
+use crate::{
+    block::BlockId,
+    chunk::{ChunkBlocks, ChunkData},
+    coordinates::ChunkRelativeBlockCoordinates,
+    world_gen::generator::WorldGenerator,
+};
+
+/// The chunk containing the world spawn, which gets a platform so players don't fall into the void
+const SPAWN_CHUNK: Vector2<i32> = Vector2 { x: 0, z: 0 };
+const SPAWN_PLATFORM_Y: i16 = 63;
+
+/// Generates nothing but air, except for a stone platform covering the spawn chunk
+pub struct VoidGenerator;
+
+impl WorldGenerator for VoidGenerator {
+    fn generate_chunk(&self, at: Vector2<i32>) -> ChunkData {
+        let mut blocks = ChunkBlocks::default();
+        if at == SPAWN_CHUNK {
+            let stone = BlockId::from(block!("minecraft:stone"));
+            for x in 0..16u8 {
+                for z in 0..16u8 {
+                    blocks.set_block(
+                        ChunkRelativeBlockCoordinates {
+                            x: x.into(),
+                            y: SPAWN_PLATFORM_Y.into(),
+                            z: z.into(),
+                        },
+                        stone,
+                    );
+                }
+            }
+        }
+        ChunkData::new(blocks, at)
+    }
+}
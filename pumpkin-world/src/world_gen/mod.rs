@@ -5,12 +5,104 @@ mod noise;
 mod seed;
 
 pub use generator::WorldGenerator;
-use implementation::overworld::biome::plains::PlainsGenerator;
+use implementation::{
+    overworld::biome::plains::PlainsGenerator, superflat::SuperflatGenerator, void::VoidGenerator,
+};
+use pumpkin_config::{WorldGenConfig, WorldType};
 pub use seed::Seed;
 
 use generator::GeneratorInit;
 
-pub fn get_world_gen(seed: Seed) -> Box<dyn WorldGenerator> {
-    // TODO decide which WorldGenerator to pick based on config.
-    Box::new(PlainsGenerator::new(seed))
+pub fn get_world_gen(seed: Seed, config: &WorldGenConfig) -> Box<dyn WorldGenerator> {
+    match config.world_type {
+        WorldType::Default => Box::new(PlainsGenerator::new(seed)),
+        WorldType::Flat => Box::new(SuperflatGenerator::new(&config.flat_layers)),
+        WorldType::Void => Box::new(VoidGenerator),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::{FlatLayer, WorldGenConfig, WorldType};
+    use pumpkin_core::math::vector2::Vector2;
+
+    use super::{get_world_gen, Seed};
+    use crate::{
+        block::{block_state::BlockState, BlockId},
+        coordinates::ChunkRelativeBlockCoordinates,
+        WORLD_LOWEST_Y, WORLD_MAX_Y,
+    };
+
+    fn block(name: &str) -> BlockId {
+        BlockState::new(name, None).unwrap().into()
+    }
+
+    fn column(config: &WorldGenConfig, at: Vector2<i32>, x: u8, z: u8) -> Vec<BlockId> {
+        let chunk = get_world_gen(Seed(0), config).generate_chunk(at);
+        let blocks = chunk.blocks.read();
+        (WORLD_LOWEST_Y..WORLD_MAX_Y)
+            .map(|y| {
+                blocks.get_block(ChunkRelativeBlockCoordinates {
+                    x: x.into(),
+                    y: y.into(),
+                    z: z.into(),
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn void_chunks_are_empty() {
+        let config = WorldGenConfig {
+            world_type: WorldType::Void,
+            ..Default::default()
+        };
+        for at in [Vector2::new(1, 0), Vector2::new(-3, 7)] {
+            for (x, z) in [(0, 0), (7, 12), (15, 15)] {
+                assert!(column(&config, at, x, z).iter().all(BlockId::is_air));
+            }
+        }
+    }
+
+    #[test]
+    fn void_spawn_platform() {
+        let config = WorldGenConfig {
+            world_type: WorldType::Void,
+            ..Default::default()
+        };
+        let column = column(&config, Vector2::new(0, 0), 10, 10);
+        let solid: Vec<_> = column
+            .iter()
+            .enumerate()
+            .filter(|(_, block)| !block.is_air())
+            .collect();
+        assert_eq!(solid.len(), 1);
+        assert_eq!(*solid[0].1, block("minecraft:stone"));
+    }
+
+    #[test]
+    fn flat_layers() {
+        let config = WorldGenConfig {
+            world_type: WorldType::Flat,
+            flat_layers: vec![
+                FlatLayer::new("minecraft:bedrock", 1),
+                FlatLayer::new("minecraft:stone", 3),
+                FlatLayer::new("minecraft:sand", 2),
+            ],
+        };
+        let expected = [
+            "minecraft:bedrock",
+            "minecraft:stone",
+            "minecraft:stone",
+            "minecraft:stone",
+            "minecraft:sand",
+            "minecraft:sand",
+        ]
+        .map(block);
+        for at in [Vector2::new(0, 0), Vector2::new(-5, 2)] {
+            let column = column(&config, at, 3, 9);
+            assert_eq!(column[..expected.len()], expected);
+            assert!(column[expected.len()..].iter().all(BlockId::is_air));
+        }
+    }
 }
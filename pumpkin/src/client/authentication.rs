@@ -29,6 +29,8 @@ pub struct Texture {
     metadata: Option<HashMap<String, String>>,
 }
 
+const HAS_JOINED_URL: &str = "https://sessionserver.mojang.com/session/minecraft/hasJoined";
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct GameProfile {
     pub id: Uuid,
@@ -59,11 +61,12 @@ pub async fn authenticate(
 ) -> Result<GameProfile, AuthError> {
     assert!(ADVANCED_CONFIG.authentication.enabled);
     assert!(server.auth_client.is_some());
-    let address = if ADVANCED_CONFIG.authentication.prevent_proxy_connections {
-        format!("https://sessionserver.mojang.com/session/minecraft/hasJoined?username={username}&serverId={server_hash}&ip={ip}")
-    } else {
-        format!("https://sessionserver.mojang.com/session/minecraft/hasJoined?username={username}&serverId={server_hash}")
-    };
+    // The session server only answers with the profile if the Player joined from the same IP
+    let ip = ADVANCED_CONFIG
+        .authentication
+        .prevent_proxy_connections
+        .then_some(ip);
+    let address = has_joined_url(username, server_hash, ip);
     let auth_client = server
         .auth_client
         .as_ref()
@@ -74,13 +77,47 @@ pub async fn authenticate(
         .send()
         .await
         .map_err(|_| AuthError::FailedResponse)?;
-    match response.status() {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|_| AuthError::FailedResponse)?;
+    parse_has_joined(status, &body, ip.is_some())
+}
+
+pub fn has_joined_url(username: &str, server_hash: &str, ip: Option<&IpAddr>) -> Url {
+    let mut params = vec![
+        ("username", username.to_string()),
+        ("serverId", server_hash.to_string()),
+    ];
+    if let Some(ip) = ip {
+        params.push(("ip", ip.to_string()));
+    }
+    Url::parse_with_params(HAS_JOINED_URL, params).expect("The session server URL is valid")
+}
+
+/// The session server answers with no content or `null` if the Player didn't join
+pub fn parse_has_joined(
+    status: StatusCode,
+    body: &str,
+    ip_checked: bool,
+) -> Result<GameProfile, AuthError> {
+    let unverified = if ip_checked {
+        AuthError::UnverifiedAddress
+    } else {
+        AuthError::UnverifiedUsername
+    };
+    match status {
         StatusCode::OK => {}
-        StatusCode::NO_CONTENT => Err(AuthError::UnverifiedUsername)?,
-        other => Err(AuthError::UnknownStatusCode(other))?,
+        StatusCode::NO_CONTENT => return Err(unverified),
+        other => return Err(AuthError::UnknownStatusCode(other)),
+    }
+    if body.trim().is_empty() {
+        return Err(unverified);
     }
-    let profile: GameProfile = response.json().await.map_err(|_| AuthError::FailedParse)?;
-    Ok(profile)
+    let profile: Option<GameProfile> =
+        serde_json::from_str(body).map_err(|_| AuthError::FailedParse)?;
+    profile.ok_or(unverified)
 }
 
 pub fn unpack_textures(property: &Property, config: &TextureConfig) -> Result<(), TextureError> {
@@ -126,6 +163,8 @@ pub enum AuthError {
     FailedResponse,
     #[error("Failed to verify username")]
     UnverifiedUsername,
+    #[error("Failed to verify username, you have to join from the same IP address as the one you logged in with")]
+    UnverifiedAddress,
     #[error("Failed to parse JSON into Game Profile")]
     FailedParse,
     #[error("Unknown Status Code")]
@@ -145,3 +184,78 @@ pub enum TextureError {
     #[error("Failed to parse JSON from player texture: {0}")]
     JSONError(String),
 }
+
+#[cfg(test)]
+mod test {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use reqwest::StatusCode;
+
+    use super::{has_joined_url, parse_has_joined, AuthError};
+
+    const PROFILE: &str =
+        r#"{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch","properties":[]}"#;
+
+    #[test]
+    fn test_url_without_ip() {
+        assert_eq!(
+            has_joined_url("Notch", "-4f2a", None).as_str(),
+            "https://sessionserver.mojang.com/session/minecraft/hasJoined?username=Notch&serverId=-4f2a"
+        );
+    }
+
+    #[test]
+    fn test_url_with_ip() {
+        let ip = IpAddr::V4(Ipv4Addr::new(203, 0, 113, 7));
+        assert_eq!(
+            has_joined_url("Notch", "-4f2a", Some(&ip)).as_str(),
+            "https://sessionserver.mojang.com/session/minecraft/hasJoined?username=Notch&serverId=-4f2a&ip=203.0.113.7"
+        );
+        let ip = IpAddr::V6(Ipv6Addr::LOCALHOST);
+        assert!(has_joined_url("Notch", "-4f2a", Some(&ip))
+            .as_str()
+            .ends_with("&ip=%3A%3A1"));
+    }
+
+    #[test]
+    fn test_joined() {
+        let profile = parse_has_joined(StatusCode::OK, PROFILE, true).unwrap();
+        assert_eq!(profile.name, "Notch");
+    }
+
+    #[test]
+    fn test_not_joined() {
+        assert!(matches!(
+            parse_has_joined(StatusCode::NO_CONTENT, "", false),
+            Err(AuthError::UnverifiedUsername)
+        ));
+        assert!(matches!(
+            parse_has_joined(StatusCode::OK, "null", false),
+            Err(AuthError::UnverifiedUsername)
+        ));
+    }
+
+    #[test]
+    fn test_ip_mismatch_is_rejected() {
+        assert!(matches!(
+            parse_has_joined(StatusCode::NO_CONTENT, "", true),
+            Err(AuthError::UnverifiedAddress)
+        ));
+        assert!(matches!(
+            parse_has_joined(StatusCode::OK, "null", true),
+            Err(AuthError::UnverifiedAddress)
+        ));
+    }
+
+    #[test]
+    fn test_invalid_response() {
+        assert!(matches!(
+            parse_has_joined(StatusCode::OK, "{", false),
+            Err(AuthError::FailedParse)
+        ));
+        assert!(matches!(
+            parse_has_joined(StatusCode::INTERNAL_SERVER_ERROR, "", false),
+            Err(AuthError::UnknownStatusCode(_))
+        ));
+    }
+}
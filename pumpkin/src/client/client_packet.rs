@@ -1,11 +1,11 @@
-use std::sync::Arc;
+use std::{cmp::Ordering, sync::Arc, time::Instant};
 
 use num_traits::FromPrimitive;
-use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_config::{HandshakeConfig, ADVANCED_CONFIG, BASIC_CONFIG};
 use pumpkin_core::text::TextComponent;
 use pumpkin_protocol::{
     client::{
-        config::{CConfigAddResourcePack, CFinishConfig, CKnownPacks, CRegistryData},
+        config::{CConfigAddResourcePack, CFinishConfig, CKnownPacks, CRegistryData, CServerLinks},
         login::{CLoginSuccess, CSetCompression},
         status::CPingResponse,
     },
@@ -15,18 +15,38 @@ use pumpkin_protocol::{
         login::{SEncryptionResponse, SLoginAcknowledged, SLoginPluginResponse, SLoginStart},
         status::{SStatusPingRequest, SStatusRequest},
     },
-    ConnectionState, KnownPack, CURRENT_MC_PROTOCOL,
+    ConnectionState,
 };
+use pumpkin_registry::CORE_PACK;
 use uuid::Uuid;
 
 use crate::{
-    client::authentication::{self, GameProfile},
+    client::{
+        authentication::{self, GameProfile},
+        disconnect::DisconnectReason,
+    },
     entity::player::{ChatMode, Hand},
     proxy::velocity::velocity_login,
-    server::{Server, CURRENT_MC_VERSION},
+    server::{bans::format_duration, server_links::server_links, supported_version, Server},
 };
 
-use super::{authentication::unpack_textures, Client, PlayerConfig};
+use super::{
+    authentication::unpack_textures, illegal_characters::is_valid_player_name, Client, PlayerConfig,
+};
+
+/// Why a Client which sent `protocol` in its handshake can't log in, `None` if it can
+fn protocol_mismatch(protocol: i32, config: &HandshakeConfig) -> Option<DisconnectReason> {
+    let (supported, name) = supported_version(config);
+    match protocol.cmp(&(supported as i32)) {
+        Ordering::Less => Some(DisconnectReason::Outdated(Some(format!(
+            "Outdated client! Please use {name}"
+        )))),
+        Ordering::Equal => None,
+        Ordering::Greater => Some(DisconnectReason::Outdated(Some(format!(
+            "Outdated server! I'm still on {name}"
+        )))),
+    }
+}
 
 /// Processes incoming Packets from the Client to the Server
 /// Implements the `Client` Packets
@@ -40,22 +60,36 @@ impl Client {
             .store(version, std::sync::atomic::Ordering::Relaxed);
 
         self.connection_state.store(handshake.next_state);
+        if !ADVANCED_CONFIG.handshake.allows(&handshake.server_address) {
+            log::debug!(
+                "{} connected with the disallowed address {:?}",
+                self.address.lock(),
+                handshake.server_address
+            );
+            // The Status state has no disconnect packet
+            if self.connection_state.load() == ConnectionState::Status {
+                self.close();
+            } else {
+                self.kick(DisconnectReason::ProtocolError(Some(
+                    "Please connect using the server's address".to_string(),
+                )));
+            }
+            return;
+        }
         if self.connection_state.load() != ConnectionState::Status {
-            let protocol = version;
-            match protocol.cmp(&(CURRENT_MC_PROTOCOL as i32)) {
-                std::cmp::Ordering::Less => {
-                    self.kick(&format!("Client outdated ({protocol}), Server uses Minecraft {CURRENT_MC_VERSION}, Protocol {CURRENT_MC_PROTOCOL}"));
-                }
-                std::cmp::Ordering::Equal => {}
-                std::cmp::Ordering::Greater => {
-                    self.kick(&format!("Server outdated, Server uses Minecraft {CURRENT_MC_VERSION}, Protocol {CURRENT_MC_PROTOCOL}"));
-                }
+            if let Some(reason) = protocol_mismatch(version, &ADVANCED_CONFIG.handshake) {
+                log::debug!(
+                    "{} tried to log in with protocol version {}",
+                    self.address.lock(),
+                    version
+                );
+                self.kick(reason);
             }
         }
     }
 
     pub fn handle_status_request(&self, server: &Arc<Server>, _status_request: SStatusRequest) {
-        self.send_packet(&server.get_status());
+        server.send_status(self);
     }
 
     pub fn handle_ping_request(&self, _server: &Arc<Server>, ping_request: SStatusPingRequest) {
@@ -64,18 +98,27 @@ impl Client {
         self.close();
     }
 
-    fn is_valid_player_name(name: &str) -> bool {
-        name.len() <= 16
-            && name
-                .chars()
-                .all(|c| c > 32_u8 as char && c < 127_u8 as char)
-    }
-
     pub fn handle_login_start(&self, server: &Arc<Server>, login_start: SLoginStart) {
         log::debug!("login start, State {:?}", self.connection_state);
 
-        if !Self::is_valid_player_name(&login_start.name) {
-            self.kick("Invalid characters in username");
+        let config = &ADVANCED_CONFIG.illegal_characters;
+        let strict = config.enabled && config.strict_names && BASIC_CONFIG.online_mode;
+        if !is_valid_player_name(&login_start.name, strict) {
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid characters in username".to_string(),
+            )));
+            return;
+        }
+        let throttled = server.login_throttle.lock().attempt(
+            &login_start.name,
+            Instant::now(),
+            &ADVANCED_CONFIG.login_throttle,
+        );
+        if let Err(wait) = throttled {
+            self.kick(DisconnectReason::Throttled(Some(format!(
+                "You are logging in too fast, try again in {}",
+                format_duration(wait.as_secs().max(1))
+            ))));
             return;
         }
         // default game profile, when no online mode
@@ -97,6 +140,7 @@ impl Client {
 
         // TODO: check config for encryption
         let verify_token: [u8; 4] = rand::random();
+        self.verify_token.store(Some(verify_token));
         self.send_packet(&server.encryption_request(&verify_token, BASIC_CONFIG.online_mode));
     }
 
@@ -105,10 +149,27 @@ impl Client {
         server: &Arc<Server>,
         encryption_response: SEncryptionResponse,
     ) {
-        let shared_secret = server.decrypt(&encryption_response.shared_secret).unwrap();
+        // Only a client holding the private key's counterpart can send the token back
+        let Some(verify_token) = self.verify_token.take() else {
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Unexpected encryption response".to_string(),
+            )));
+            return;
+        };
+        if let Err(e) = server.verify_token(&verify_token, &encryption_response.verify_token) {
+            self.kick(DisconnectReason::ProtocolError(Some(e.to_string())));
+            return;
+        }
+        let shared_secret = match server.decrypt(&encryption_response.shared_secret) {
+            Ok(shared_secret) => shared_secret,
+            Err(e) => {
+                self.kick(DisconnectReason::ProtocolError(Some(e.to_string())));
+                return;
+            }
+        };
 
         self.set_encryption(Some(&shared_secret))
-            .unwrap_or_else(|e| self.kick(&e.to_string()));
+            .unwrap_or_else(|e| self.kick(DisconnectReason::ProtocolError(Some(e.to_string()))));
 
         let mut gameprofile = self.gameprofile.lock();
 
@@ -133,7 +194,9 @@ impl Client {
                             .allow_banned_players
                         {
                             if !actions.is_empty() {
-                                self.kick("Your account can't join");
+                                self.kick(DisconnectReason::Banned(Some(
+                                    "Your account can't join".to_string(),
+                                )));
                             }
                         } else {
                             for allowed in &ADVANCED_CONFIG
@@ -142,19 +205,22 @@ impl Client {
                                 .allowed_actions
                             {
                                 if !actions.contains(allowed) {
-                                    self.kick("Your account can't join");
+                                    self.kick(DisconnectReason::Banned(Some(
+                                        "Your account can't join".to_string(),
+                                    )));
                                 }
                             }
                         }
                     }
                     *gameprofile = Some(profile);
                 }
-                Err(e) => self.kick(&e.to_string()),
+                Err(e) => self.kick(DisconnectReason::Unverified(Some(e.to_string()))),
             }
         }
         for property in &gameprofile.as_ref().unwrap().properties {
-            unpack_textures(property, &ADVANCED_CONFIG.authentication.textures)
-                .unwrap_or_else(|e| self.kick(&e.to_string()));
+            unpack_textures(property, &ADVANCED_CONFIG.authentication.textures).unwrap_or_else(
+                |e| self.kick(DisconnectReason::ProtocolError(Some(e.to_string()))),
+            );
         }
 
         // enable compression
@@ -165,10 +231,26 @@ impl Client {
         }
 
         if let Some(profile) = gameprofile.as_ref() {
+            let ip = self.address.lock().ip();
+            if let Some(message) = server.ban_message(profile, &ip) {
+                self.kick(DisconnectReason::Banned(Some(message)));
+                return;
+            }
+            if !server.is_whitelisted(profile) {
+                self.kick(DisconnectReason::NotWhitelisted);
+                return;
+            }
+            if !server.take_player_slot(self, profile) {
+                self.kick(DisconnectReason::ServerFull);
+                return;
+            }
+            server.login_throttle.lock().clear(&profile.name);
             let packet = CLoginSuccess::new(&profile.id, &profile.name, &profile.properties, false);
             self.send_packet(&packet);
         } else {
-            self.kick("game profile is none");
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "game profile is none".to_string(),
+            )));
         }
     }
 
@@ -207,12 +289,13 @@ impl Client {
             self.send_packet(&resource_pack);
         }
 
+        if ADVANCED_CONFIG.server_links.enabled {
+            let links = server_links(&ADVANCED_CONFIG.server_links);
+            self.send_packet(&CServerLinks::new(&links));
+        }
+
         // known data packs
-        self.send_packet(&CKnownPacks::new(&[KnownPack {
-            namespace: "minecraft",
-            id: "core",
-            version: "1.21",
-        }]));
+        self.send_packet(&CKnownPacks::new(&[CORE_PACK]));
         dbg!("login acknowledged");
     }
     pub fn handle_client_information_config(
@@ -236,7 +319,9 @@ impl Client {
                 server_listing: client_information.server_listing,
             });
         } else {
-            self.kick("Invalid hand or chat type")
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid hand or chat type".to_string(),
+            )))
         }
     }
 
@@ -247,17 +332,16 @@ impl Client {
             dbg!("got a client brand");
             match String::from_utf8(plugin_message.data) {
                 Ok(brand) => *self.brand.lock() = Some(brand),
-                Err(e) => self.kick(&e.to_string()),
+                Err(e) => self.kick(DisconnectReason::ProtocolError(Some(e.to_string()))),
             }
         }
     }
 
-    pub fn handle_known_packs(&self, server: &Arc<Server>, _config_acknowledged: SKnownPacks) {
+    pub fn handle_known_packs(&self, server: &Arc<Server>, known_packs: SKnownPacks) {
+        // Clients don't need the data of entries in packs they already have
         for registry in &server.cached_registry {
-            self.send_packet(&CRegistryData::new(
-                &registry.registry_id,
-                &registry.registry_entries,
-            ));
+            let entries = registry.entries_for(&known_packs.known_packs);
+            self.send_packet(&CRegistryData::new(&registry.registry_id, &entries));
         }
 
         // We are done with configuring
@@ -276,3 +360,47 @@ impl Client {
             .store(true, std::sync::atomic::Ordering::Relaxed);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::HandshakeConfig;
+    use pumpkin_protocol::CURRENT_MC_PROTOCOL;
+
+    use super::protocol_mismatch;
+    use crate::{client::disconnect::DisconnectReason, server::CURRENT_MC_VERSION};
+
+    #[test]
+    fn test_protocol_mismatch() {
+        let config = HandshakeConfig::default();
+        let current = CURRENT_MC_PROTOCOL as i32;
+        assert_eq!(protocol_mismatch(current, &config), None);
+        assert_eq!(
+            protocol_mismatch(current - 1, &config),
+            Some(DisconnectReason::Outdated(Some(format!(
+                "Outdated client! Please use {CURRENT_MC_VERSION}"
+            ))))
+        );
+        assert_eq!(
+            protocol_mismatch(current + 1, &config),
+            Some(DisconnectReason::Outdated(Some(format!(
+                "Outdated server! I'm still on {CURRENT_MC_VERSION}"
+            ))))
+        );
+    }
+
+    #[test]
+    fn test_configured_version() {
+        let config = HandshakeConfig {
+            protocol_version: 766,
+            minecraft_version: "1.20.6".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(protocol_mismatch(766, &config), None);
+        assert_eq!(
+            protocol_mismatch(CURRENT_MC_PROTOCOL as i32, &config),
+            Some(DisconnectReason::Outdated(Some(
+                "Outdated server! I'm still on 1.20.6".to_string()
+            )))
+        );
+    }
+}
@@ -66,7 +66,7 @@ impl Player {
 
         let carried_item = self
             .carried_item
-            .load()
+            .lock()
             .as_ref()
             .map_or_else(Slot::empty, |item| item.into());
 
@@ -211,10 +211,8 @@ impl Player {
 
         match slot {
             container_click::Slot::Normal(slot) => {
-                let mut carried_item = self.carried_item.load();
-                let res = container.handle_item_change(&mut carried_item, slot, mouse_click);
-                self.carried_item.store(carried_item);
-                res
+                let mut carried_item = self.carried_item.lock();
+                container.handle_item_change(&mut carried_item, slot, mouse_click)
             }
             container_click::Slot::OutsideInventory => Ok(()),
         }
@@ -256,7 +254,7 @@ impl Player {
                         slots.skip(36).rev().find_map(find_condition)
                     };
                     if let Some(slot) = slots {
-                        let mut item_slot = container.all_slots()[slot].map(|i| i.to_owned());
+                        let mut item_slot = container.all_slots()[slot].clone();
                         container.handle_item_change(&mut item_slot, slot, MouseClick::Left)?;
                         *container.all_slots()[slot] = item_slot;
                     }
@@ -297,7 +295,7 @@ impl Player {
         let mut inventory = self.inventory.lock();
         let mut container = OptionallyCombinedContainer::new(&mut inventory, opened_container);
         if let Some(Some(item)) = container.all_slots().get_mut(slot) {
-            self.carried_item.store(Some(item.to_owned()));
+            *self.carried_item.lock() = Some(item.clone());
         }
         Ok(())
     }
@@ -314,10 +312,9 @@ impl Player {
         let Some(item) = slots.get_mut(slot) else {
             return Ok(());
         };
-        let Some(mut carried_item) = **item else {
+        let Some(mut carried_item) = item.take() else {
             return Ok(());
         };
-        **item = None;
 
         for slot in slots.iter_mut().filter_map(|slot| slot.as_mut()) {
             if slot.item_id == carried_item.item_id {
@@ -336,7 +333,7 @@ impl Player {
                 }
             }
         }
-        self.carried_item.store(Some(carried_item));
+        *self.carried_item.lock() = Some(carried_item);
         Ok(())
     }
 
@@ -364,15 +361,8 @@ impl Player {
                 let mut inventory = self.inventory.lock();
                 let mut container =
                     OptionallyCombinedContainer::new(&mut inventory, opened_container);
-                let mut carried_item = self.carried_item.load();
-                let res = drag_handler.apply_drag(
-                    &mut carried_item,
-                    &mut container,
-                    &container_id,
-                    player_id,
-                );
-                self.carried_item.store(carried_item);
-                res
+                let mut carried_item = self.carried_item.lock();
+                drag_handler.apply_drag(&mut carried_item, &mut container, &container_id, player_id)
             }
         }
     }
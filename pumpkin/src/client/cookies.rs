@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use pumpkin_protocol::{
+    client::{
+        config::{CCookieRequest, CStoreCookie},
+        play::{CCookieRequestPlay, CStoreCookiePlay},
+    },
+    cookie::MAX_COOKIE_SIZE,
+    ConnectionState,
+};
+use thiserror::Error;
+
+use super::Client;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum CookieError {
+    #[error("cookie {0} is larger than {} bytes", MAX_COOKIE_SIZE)]
+    TooLarge(String),
+}
+
+/// The cookies of a Client which the Server knows about, either stored by the Server or
+/// received in a cookie response
+#[derive(Default)]
+pub struct CookieJar {
+    cookies: HashMap<String, Bytes>,
+}
+
+impl CookieJar {
+    #[allow(dead_code)]
+    pub fn get(&self, key: &str) -> Option<&Bytes> {
+        self.cookies.get(key)
+    }
+
+    /// Remembers a cookie the Server wants to store, oversized cookies are rejected
+    #[allow(dead_code)]
+    pub fn set(&mut self, key: &str, payload: Bytes) -> Result<(), CookieError> {
+        if payload.len() > MAX_COOKIE_SIZE {
+            return Err(CookieError::TooLarge(key.to_string()));
+        }
+        self.cookies.insert(key.to_string(), payload);
+        Ok(())
+    }
+
+    /// Updates a cookie after the client answered a request, None means the client has no such cookie
+    pub fn received(&mut self, key: String, payload: Option<Bytes>) {
+        match payload {
+            Some(payload) => {
+                self.cookies.insert(key, payload);
+            }
+            None => {
+                self.cookies.remove(&key);
+            }
+        }
+    }
+}
+
+// Nothing in the Server uses cookies yet, these are meant for plugins
+#[allow(dead_code)]
+impl Client {
+    /// Returns the cookie if it was stored by the Server or received from the client
+    pub fn cookie(&self, key: &str) -> Option<Bytes> {
+        self.cookies.lock().get(key).cloned()
+    }
+
+    /// Stores a cookie on the client, only possible in the config and play state
+    pub fn store_cookie(&self, key: &str, payload: Bytes) -> Result<(), CookieError> {
+        self.cookies.lock().set(key, payload.clone())?;
+        match self.connection_state.load() {
+            ConnectionState::Config => self.send_packet(&CStoreCookie::new(key, &payload)),
+            ConnectionState::Play => self.send_packet(&CStoreCookiePlay::new(key, &payload)),
+            state => log::warn!("Can't store cookie {} in the {:?} state", key, state),
+        }
+        Ok(())
+    }
+
+    /// Asks the client for a cookie, the response updates [`Client::cookie`]
+    pub fn request_cookie(&self, key: &str) {
+        match self.connection_state.load() {
+            ConnectionState::Config => self.send_packet(&CCookieRequest::new(key.to_string())),
+            ConnectionState::Play => self.send_packet(&CCookieRequestPlay::new(key.to_string())),
+            state => log::warn!("Can't request cookie {} in the {:?} state", key, state),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::Bytes;
+    use pumpkin_protocol::cookie::MAX_COOKIE_SIZE;
+
+    use super::{CookieError, CookieJar};
+
+    #[test]
+    fn test_store_and_receive() {
+        let mut jar = CookieJar::default();
+        jar.set("pumpkin:session", Bytes::from_static(b"abc"))
+            .unwrap();
+        assert_eq!(jar.get("pumpkin:session").unwrap().as_ref(), b"abc");
+
+        // The client knows best, so a response replaces what was stored
+        jar.received(
+            "pumpkin:session".to_string(),
+            Some(Bytes::from_static(b"xyz")),
+        );
+        assert_eq!(jar.get("pumpkin:session").unwrap().as_ref(), b"xyz");
+        jar.received("pumpkin:session".to_string(), None);
+        assert!(jar.get("pumpkin:session").is_none());
+    }
+
+    #[test]
+    fn test_size_limit() {
+        let mut jar = CookieJar::default();
+        assert!(jar
+            .set("pumpkin:max", Bytes::from(vec![0; MAX_COOKIE_SIZE]))
+            .is_ok());
+        assert_eq!(
+            jar.set("pumpkin:big", Bytes::from(vec![0; MAX_COOKIE_SIZE + 1])),
+            Err(CookieError::TooLarge("pumpkin:big".to_string()))
+        );
+        assert!(jar.get("pumpkin:big").is_none());
+    }
+}
@@ -0,0 +1,302 @@
+use pumpkin_config::MessagesConfig;
+use pumpkin_core::text::{Text, TextComponent, TextContent};
+
+use crate::server::CURRENT_MC_VERSION;
+
+/// Why a Client is disconnected, so logs and metrics can tell disconnects apart.
+///
+/// The detail replaces the default message, which clients translate into their language
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// The client didn't answer keep alives or stopped reading what it is sent
+    Timeout,
+    /// An operator kicked the Player
+    Kicked(Option<String>),
+    ServerFull,
+    Banned(Option<String>),
+    NotWhitelisted,
+    /// The client sent something it shouldn't have
+    ProtocolError(Option<String>),
+    ServerClosing,
+    /// Too many chat messages or commands
+    Spam,
+    /// The client uses another protocol version
+    Outdated(Option<String>),
+    /// The client couldn't prove who it is, e.g. through the proxy
+    Unverified(Option<String>),
+    /// The same account logged in too often
+    Throttled(Option<String>),
+    /// Control characters or section signs in chat
+    IllegalCharacters,
+}
+
+impl DisconnectReason {
+    /// A short name for logs and metrics
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::Kicked(_) => "kicked",
+            Self::ServerFull => "server_full",
+            Self::Banned(_) => "banned",
+            Self::NotWhitelisted => "not_whitelisted",
+            Self::ProtocolError(_) => "protocol_error",
+            Self::ServerClosing => "server_closing",
+            Self::Spam => "spam",
+            Self::Outdated(_) => "outdated",
+            Self::Unverified(_) => "unverified",
+            Self::Throttled(_) => "throttled",
+            Self::IllegalCharacters => "illegal_characters",
+        }
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Kicked(detail)
+            | Self::Banned(detail)
+            | Self::ProtocolError(detail)
+            | Self::Outdated(detail)
+            | Self::Unverified(detail)
+            | Self::Throttled(detail) => detail.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The key of the vanilla message in the client's language files
+    pub fn translation_key(&self) -> &'static str {
+        match self {
+            Self::Timeout => "disconnect.timeout",
+            Self::Kicked(_) => "multiplayer.disconnect.kicked",
+            Self::ServerFull => "multiplayer.disconnect.server_full",
+            Self::Banned(_) => "multiplayer.disconnect.banned",
+            Self::NotWhitelisted => "multiplayer.disconnect.not_whitelisted",
+            Self::ProtocolError(_) => "disconnect.packetError",
+            Self::ServerClosing => "multiplayer.disconnect.server_shutdown",
+            Self::Spam => "disconnect.spam",
+            Self::Outdated(_) => "multiplayer.disconnect.outdated_client",
+            Self::Unverified(_) => "multiplayer.disconnect.unverified_username",
+            // Vanilla has no key for throttling
+            Self::Throttled(_) => "disconnect.loginFailed",
+            Self::IllegalCharacters => "multiplayer.disconnect.illegal_characters",
+        }
+    }
+
+    /// The English message of `translation_key`
+    pub fn default_message(&self) -> String {
+        match self {
+            Self::Timeout => "Timed out".to_string(),
+            Self::Kicked(_) => "Kicked by an operator".to_string(),
+            Self::ServerFull => "Server is full!".to_string(),
+            Self::Banned(_) => "You are banned from this server".to_string(),
+            Self::NotWhitelisted => "You are not white-listed on this server!".to_string(),
+            Self::ProtocolError(_) => "Network Protocol Error".to_string(),
+            Self::ServerClosing => "Server closed".to_string(),
+            Self::Spam => "Kicked for spamming".to_string(),
+            Self::Outdated(_) => format!("Incompatible client! Please use {CURRENT_MC_VERSION}"),
+            Self::Unverified(_) => "Failed to verify username!".to_string(),
+            Self::Throttled(_) => "Failed to log in".to_string(),
+            Self::IllegalCharacters => "Illegal characters in chat".to_string(),
+        }
+    }
+
+    /// The message the client shows, filled into the `messages.kick` template.
+    ///
+    /// The template can only hold plain text, so the default message is only translated when the template is unchanged
+    pub fn message(&self, config: &MessagesConfig) -> TextComponent<'static> {
+        if let Some(detail) = self.detail() {
+            return owned_text(config.kick_message(detail));
+        }
+        if config.kick != MessagesConfig::default().kick {
+            return owned_text(config.kick_message(&self.default_message()));
+        }
+        let with = match self {
+            Self::Outdated(_) => vec![Text(Box::new(TextComponent::text(CURRENT_MC_VERSION)))],
+            _ => Vec::new(),
+        };
+        TextComponent {
+            content: TextContent::Translate {
+                translate: self.translation_key().into(),
+                with,
+            },
+            style: Default::default(),
+        }
+    }
+}
+
+/// Either a `DisconnectReason` or a message which is shown as it is
+#[derive(Debug, Clone)]
+pub enum DisconnectMessage<'a> {
+    Reason(DisconnectReason),
+    Custom(TextComponent<'a>),
+}
+
+impl<'a> DisconnectMessage<'a> {
+    pub fn category(&self) -> &'static str {
+        match self {
+            Self::Reason(reason) => reason.category(),
+            Self::Custom(_) => "custom",
+        }
+    }
+
+    pub fn message(&self, config: &MessagesConfig) -> TextComponent<'a> {
+        match self {
+            Self::Reason(reason) => reason.message(config),
+            Self::Custom(message) => message.clone(),
+        }
+    }
+}
+
+impl<'a> From<DisconnectReason> for DisconnectMessage<'a> {
+    fn from(reason: DisconnectReason) -> Self {
+        Self::Reason(reason)
+    }
+}
+
+impl<'a> From<TextComponent<'a>> for DisconnectMessage<'a> {
+    fn from(message: TextComponent<'a>) -> Self {
+        Self::Custom(message)
+    }
+}
+
+fn owned_text(text: String) -> TextComponent<'static> {
+    TextComponent {
+        content: TextContent::Text { text: text.into() },
+        style: Default::default(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::MessagesConfig;
+    use pumpkin_core::text::{TextComponent, TextContent};
+
+    use super::{DisconnectMessage, DisconnectReason};
+    use crate::server::CURRENT_MC_VERSION;
+
+    fn all_reasons() -> [DisconnectReason; 12] {
+        [
+            DisconnectReason::Timeout,
+            DisconnectReason::Kicked(None),
+            DisconnectReason::ServerFull,
+            DisconnectReason::Banned(None),
+            DisconnectReason::NotWhitelisted,
+            DisconnectReason::ProtocolError(None),
+            DisconnectReason::ServerClosing,
+            DisconnectReason::Spam,
+            DisconnectReason::Outdated(None),
+            DisconnectReason::Unverified(None),
+            DisconnectReason::Throttled(None),
+            DisconnectReason::IllegalCharacters,
+        ]
+    }
+
+    fn text(message: &TextComponent) -> String {
+        match &message.content {
+            TextContent::Text { text } => text.to_string(),
+            TextContent::Translate { translate, .. } => translate.to_string(),
+            _ => panic!("unexpected content {:?}", message.content),
+        }
+    }
+
+    #[test]
+    fn test_default_messages() {
+        let config = MessagesConfig::default();
+        let expected = [
+            ("timeout", "disconnect.timeout", "Timed out"),
+            (
+                "kicked",
+                "multiplayer.disconnect.kicked",
+                "Kicked by an operator",
+            ),
+            (
+                "server_full",
+                "multiplayer.disconnect.server_full",
+                "Server is full!",
+            ),
+            (
+                "banned",
+                "multiplayer.disconnect.banned",
+                "You are banned from this server",
+            ),
+            (
+                "not_whitelisted",
+                "multiplayer.disconnect.not_whitelisted",
+                "You are not white-listed on this server!",
+            ),
+            (
+                "protocol_error",
+                "disconnect.packetError",
+                "Network Protocol Error",
+            ),
+            (
+                "server_closing",
+                "multiplayer.disconnect.server_shutdown",
+                "Server closed",
+            ),
+            ("spam", "disconnect.spam", "Kicked for spamming"),
+            (
+                "outdated",
+                "multiplayer.disconnect.outdated_client",
+                "Incompatible client! Please use ",
+            ),
+            (
+                "unverified",
+                "multiplayer.disconnect.unverified_username",
+                "Failed to verify username!",
+            ),
+            ("throttled", "disconnect.loginFailed", "Failed to log in"),
+            (
+                "illegal_characters",
+                "multiplayer.disconnect.illegal_characters",
+                "Illegal characters in chat",
+            ),
+        ];
+        for (reason, (category, key, english)) in all_reasons().iter().zip(expected) {
+            assert_eq!(reason.category(), category);
+            assert_eq!(reason.translation_key(), key);
+            assert!(
+                reason.default_message().starts_with(english),
+                "{:?}",
+                reason
+            );
+            // Clients translate the message themselves
+            assert_eq!(text(&reason.message(&config)), key);
+        }
+
+        let TextContent::Translate { with, .. } =
+            DisconnectReason::Outdated(None).message(&config).content
+        else {
+            panic!("The default message is translated");
+        };
+        assert_eq!(text(&with[0].0), CURRENT_MC_VERSION);
+    }
+
+    #[test]
+    fn test_details_and_templates() {
+        let mut config = MessagesConfig::default();
+        let banned = DisconnectReason::Banned(Some("Griefing".to_string()));
+        assert_eq!(text(&banned.message(&config)), "Griefing");
+
+        // The template can't hold translated text
+        config.kick = "[{server}] {reason}".to_string();
+        assert_eq!(text(&banned.message(&config)), "[Pumpkin] Griefing");
+        assert_eq!(
+            text(&DisconnectReason::ServerFull.message(&config)),
+            "[Pumpkin] Server is full!"
+        );
+    }
+
+    #[test]
+    fn test_custom_message() {
+        let config = MessagesConfig {
+            kick: "[{server}] {reason}".to_string(),
+            ..Default::default()
+        };
+        let message = DisconnectMessage::from(TextComponent::text("Bye"));
+        assert_eq!(message.category(), "custom");
+        // Custom messages are shown as they are
+        assert_eq!(text(&message.message(&config)), "Bye");
+
+        let message = DisconnectMessage::from(DisconnectReason::Spam);
+        assert_eq!(message.category(), "spam");
+    }
+}
@@ -0,0 +1,100 @@
+use pumpkin_config::IllegalCharacterAction;
+
+/// Like vanilla, section signs, control characters and DEL are not allowed in chat
+pub fn is_allowed_chat_character(c: char) -> bool {
+    c != '§' && c >= ' ' && c != '\u{7f}'
+}
+
+/// Whether the name matches the vanilla `[A-Za-z0-9_]{3,16}`
+pub fn is_vanilla_name(name: &str) -> bool {
+    (3..=16).contains(&name.len()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Whether a Player may log in with the name, strict names have to be vanilla ones
+pub fn is_valid_player_name(name: &str, strict: bool) -> bool {
+    if strict {
+        return is_vanilla_name(name);
+    }
+    name.len() <= 16
+        && name
+            .chars()
+            .all(|c| c > 32_u8 as char && c < 127_u8 as char)
+}
+
+/// Checks a chat message for illegal characters, `Err` tells what to do instead of sending it
+pub fn filter_chat(
+    message: String,
+    action: IllegalCharacterAction,
+) -> Result<String, IllegalCharacterAction> {
+    if message.chars().all(is_allowed_chat_character) {
+        return Ok(message);
+    }
+    if action != IllegalCharacterAction::Sanitize {
+        return Err(action);
+    }
+    let mut sanitized = String::with_capacity(message.len());
+    let mut chars = message.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            // Also drop the formatting code
+            chars.next();
+        } else if is_allowed_chat_character(c) {
+            sanitized.push(c);
+        }
+    }
+    if sanitized.trim().is_empty() {
+        return Err(IllegalCharacterAction::Drop);
+    }
+    Ok(sanitized)
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::IllegalCharacterAction;
+
+    use super::{filter_chat, is_valid_player_name};
+
+    #[test]
+    fn test_names() {
+        assert!(is_valid_player_name("Steve_123", true));
+        assert!(!is_valid_player_name("St", true));
+        assert!(!is_valid_player_name("Steve.Bedrock", true));
+        assert!(!is_valid_player_name("Ste\u{0}ve", true));
+        assert!(!is_valid_player_name("§cSteve", true));
+        assert!(!is_valid_player_name("SeventeenLetters1", true));
+
+        // Offline mode only rejects what can't be displayed
+        assert!(is_valid_player_name(".Steve", false));
+        assert!(!is_valid_player_name("Ste\u{0}ve", false));
+        assert!(!is_valid_player_name("Ste ve", false));
+        assert!(!is_valid_player_name("§cSteve", false));
+    }
+
+    #[test]
+    fn test_chat() {
+        assert_eq!(
+            filter_chat("Hello world!".to_string(), IllegalCharacterAction::Kick),
+            Ok("Hello world!".to_string())
+        );
+        assert_eq!(
+            filter_chat("§kHello".to_string(), IllegalCharacterAction::Kick),
+            Err(IllegalCharacterAction::Kick)
+        );
+        assert_eq!(
+            filter_chat("Hello\n".to_string(), IllegalCharacterAction::Drop),
+            Err(IllegalCharacterAction::Drop)
+        );
+        assert_eq!(
+            filter_chat(
+                "§kHello§\u{7f}".to_string(),
+                IllegalCharacterAction::Sanitize
+            ),
+            Ok("Hello".to_string())
+        );
+        // Nothing is left to send
+        assert_eq!(
+            filter_chat("\u{1b}§c".to_string(), IllegalCharacterAction::Sanitize),
+            Err(IllegalCharacterAction::Drop)
+        );
+    }
+}
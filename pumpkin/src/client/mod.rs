@@ -1,43 +1,65 @@
 use std::{
-    io::{self, Write},
+    collections::VecDeque,
+    io,
     net::SocketAddr,
     sync::{
-        atomic::{AtomicBool, AtomicI32},
+        atomic::{AtomicBool, AtomicI32, Ordering},
         Arc,
     },
+    time::Duration,
 };
 
 use crate::{
     entity::player::{ChatMode, Hand},
-    server::Server,
+    server::{metrics::METRICS, socket, Server},
 };
 
 use authentication::GameProfile;
+use bytes::Bytes;
+use cookies::CookieJar;
 use crossbeam::atomic::AtomicCell;
-use mio::{event::Event, net::TcpStream, Token};
+use disconnect::{DisconnectMessage, DisconnectReason};
+use mio::{
+    event::Event,
+    net::{TcpListener, TcpStream},
+    Token,
+};
+use packet_capture::{CapturedPacket, PacketCapture, PACKET_CAPTURE};
 use parking_lot::Mutex;
-use pumpkin_config::compression::CompressionInfo;
+use pumpkin_config::{compression::CompressionInfo, ADVANCED_CONFIG, LIVE_CONFIG};
+use pumpkin_core::text::TextComponent;
 use pumpkin_protocol::{
-    bytebuf::{packet_id::Packet, DeserializerError},
-    client::{config::CConfigDisconnect, login::CLoginDisconnect},
+    bytebuf::{packet_id::Packet, ByteBuffer, DeserializerError},
+    client::{config::CConfigDisconnect, login::CLoginDisconnect, play::CPlayDisconnect},
     packet_decoder::PacketDecoder,
     packet_encoder::PacketEncoder,
     server::{
-        config::{SAcknowledgeFinishConfig, SClientInformationConfig, SKnownPacks, SPluginMessage},
+        config::{
+            SAcknowledgeFinishConfig, SClientInformationConfig, SCookieResponse, SKnownPacks,
+            SPluginMessage,
+        },
         handshake::SHandShake,
         login::{SEncryptionResponse, SLoginAcknowledged, SLoginPluginResponse, SLoginStart},
         status::{SStatusPingRequest, SStatusRequest},
     },
+    version::ProtocolVersion,
     ClientPacket, ConnectionState, PacketError, RawPacket, ServerPacket,
 };
+use read_buffer::ReadBuffer;
+use write_queue::{Admission, WriteQueue};
 
-use std::io::Read;
 use thiserror::Error;
 
 pub mod authentication;
 mod client_packet;
 mod container;
+pub mod cookies;
+pub mod disconnect;
+pub mod illegal_characters;
+pub mod packet_capture;
 pub mod player_packet;
+mod read_buffer;
+mod write_queue;
 
 /// Represents a player's configuration settings.
 ///
@@ -96,6 +118,8 @@ pub struct Client {
     pub connection_state: AtomicCell<ConnectionState>,
     /// Whether encryption is enabled for the connection.
     pub encryption: AtomicBool,
+    /// The token sent in the encryption request, the client has to send it back encrypted.
+    pub verify_token: AtomicCell<Option<[u8; 4]>>,
     /// Indicates if the client connection is closed.
     pub closed: AtomicBool,
     /// A unique token identifying the client.
@@ -108,15 +132,27 @@ pub struct Client {
     enc: Arc<Mutex<PacketEncoder>>,
     /// The packet decoder for incoming packets.
     dec: Arc<Mutex<PacketDecoder>>,
+    /// The buffer incoming data is read into before it gets decoded.
+    read_buffer: Mutex<ReadBuffer>,
     /// A queue of raw packets received from the client, waiting to be processed.
-    pub client_packets_queue: Arc<Mutex<Vec<RawPacket>>>,
+    pub client_packets_queue: Arc<Mutex<VecDeque<RawPacket>>>,
 
     /// Indicates whether the client should be converted into a player.
     pub make_player: AtomicBool,
+    /// Whether the client took one of the `max_players` slots, it is given back when they leave
+    pub holds_player_slot: AtomicBool,
     /// Sends each keep alive packet that the server receives for a player to here, which gets picked up in a tokio task
     pub keep_alive_sender: Arc<tokio::sync::mpsc::Sender<i64>>,
     /// Stores the last time it was confirmed that the client is alive
     pub last_alive_received: AtomicCell<std::time::Instant>,
+    /// How long the client took to answer the last keep alive
+    pub latency: AtomicCell<Duration>,
+    /// The cookies the Server stored on the client or received from it
+    cookies: Mutex<CookieJar>,
+    /// Where every packet is logged to when debugging with `packet_capture`
+    capture: Option<Arc<PacketCapture>>,
+    /// Encoded packets which could not be written yet because the connection was not writable
+    write_queue: Mutex<WriteQueue>,
 }
 
 impl Client {
@@ -137,19 +173,91 @@ impl Client {
             connection: Arc::new(Mutex::new(connection)),
             enc: Arc::new(Mutex::new(PacketEncoder::default())),
             dec: Arc::new(Mutex::new(PacketDecoder::default())),
+            read_buffer: Mutex::new(ReadBuffer::from_config(&ADVANCED_CONFIG.socket)),
             encryption: AtomicBool::new(false),
+            verify_token: AtomicCell::new(None),
             closed: AtomicBool::new(false),
-            client_packets_queue: Arc::new(Mutex::new(Vec::new())),
+            client_packets_queue: Arc::new(Mutex::new(VecDeque::new())),
             make_player: AtomicBool::new(false),
+            holds_player_slot: AtomicBool::new(false),
             keep_alive_sender,
             last_alive_received: AtomicCell::new(std::time::Instant::now()),
+            latency: AtomicCell::new(Duration::ZERO),
+            cookies: Mutex::new(CookieJar::default()),
+            capture: PACKET_CAPTURE.clone(),
+            write_queue: Mutex::new(WriteQueue::from_config(&ADVANCED_CONFIG.network_queue)),
         }
     }
 
+    /// Accepts the next connection of the listener, the Client's address is the one of the peer.
+    ///
+    /// Returns None if no connection is waiting, the token is only taken for an accepted connection
+    pub fn accept(
+        listener: &TcpListener,
+        token: impl FnOnce() -> Token,
+        keep_alive_sender: Arc<tokio::sync::mpsc::Sender<i64>>,
+    ) -> io::Result<Option<Self>> {
+        let (connection, address) = match listener.accept() {
+            Ok(accepted) => accepted,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        socket::configure_connection(&connection, &ADVANCED_CONFIG.socket);
+        Ok(Some(Self::new(
+            token(),
+            connection,
+            address,
+            keep_alive_sender,
+        )))
+    }
+
+    pub fn handle_cookie_response(&self, key: String, payload: Option<Bytes>) {
+        self.cookies.lock().received(key, payload);
+    }
+
     /// Adds a Incoming packet to the queue
     pub fn add_packet(&self, packet: RawPacket) {
         let mut client_packets_queue = self.client_packets_queue.lock();
-        client_packets_queue.push(packet);
+        client_packets_queue.push_back(packet);
+    }
+
+    /// Takes the next Incoming packet out of the queue, the queue is not locked while the packet gets handled
+    pub fn next_packet(&self) -> Option<RawPacket> {
+        let mut packet = self.client_packets_queue.lock().pop_front()?;
+        if let Some(capture) = &self.capture {
+            capture.record(&CapturedPacket::inbound(
+                self.token.0,
+                self.connection_state.load(),
+                &mut packet,
+            ));
+        }
+        Some(packet)
+    }
+
+    /// Queues the inbound packets of a capture as if the Client sent them again, returns how many were queued
+    pub fn replay<'a>(&self, packets: impl IntoIterator<Item = &'a CapturedPacket>) -> usize {
+        let mut queued = 0;
+        for packet in packets
+            .into_iter()
+            .filter_map(CapturedPacket::to_raw_packet)
+        {
+            self.add_packet(packet);
+            queued += 1;
+        }
+        queued
+    }
+
+    fn capture_outbound<P: ClientPacket>(&self, packet: &P) {
+        if let Some(capture) = &self.capture {
+            let mut payload = ByteBuffer::empty();
+            packet.write(&mut payload);
+            capture.record(&CapturedPacket::outbound(
+                self.token.0,
+                self.connection_state.load(),
+                P::PACKET_ID,
+                payload.buf().len(),
+            ));
+        }
     }
 
     /// Sets the Packet encryption
@@ -181,35 +289,56 @@ impl Client {
     /// Send a Clientbound Packet to the Client
     pub fn send_packet<P: ClientPacket>(&self, packet: &P) {
         // assert!(!self.closed);
-        let mut enc = self.enc.lock();
-        enc.append_packet(packet)
-            .unwrap_or_else(|e| self.kick(&e.to_string()));
-        self.connection
-            .lock()
-            .write_all(&enc.take())
-            .map_err(|_| PacketError::ConnectionWrite)
-            .unwrap_or_else(|e| self.kick(&e.to_string()));
+        if let Err(err) = self.try_send_packet(packet) {
+            match err {
+                PacketError::QueueFull => self.kick(DisconnectReason::Timeout),
+                err => self.kick(DisconnectReason::ProtocolError(Some(err.to_string()))),
+            }
+        }
     }
 
+    /// Encodes the packet and writes as much as the connection takes, the rest waits in the write queue.
+    ///
+    /// Low priority packets are dropped while the queue is full
     pub fn try_send_packet<P: ClientPacket>(&self, packet: &P) -> Result<(), PacketError> {
         // assert!(!self.closed);
+        let mut write_queue = self.write_queue.lock();
+        match write_queue.admit(P::PRIORITY) {
+            Admission::Send => {}
+            Admission::Drop => return Ok(()),
+            Admission::Disconnect => return Err(PacketError::QueueFull),
+        }
+        self.capture_outbound(packet);
 
         let mut enc = self.enc.lock();
         enc.append_packet(packet)?;
-        self.connection
-            .lock()
-            .write_all(&enc.take())
+        let bytes = enc.take();
+        METRICS
+            .bytes_sent
+            .fetch_add(bytes.len() as u64, Ordering::Relaxed);
+        write_queue.push(bytes);
+        write_queue
+            .flush(&mut *self.connection.lock())
             .map_err(|_| PacketError::ConnectionWrite)?;
         Ok(())
     }
 
+    /// Writes the queued packets once the connection is writable again
+    pub fn flush_write_queue(&self) {
+        let result = self.write_queue.lock().flush(&mut *self.connection.lock());
+        if let Err(err) = result {
+            log::debug!("Couldn't write to {}: {}", self.address.lock(), err);
+            self.close();
+        }
+    }
+
     /// Processes all packets send by the client
     pub async fn process_packets(&self, server: &Arc<Server>) {
-        while let Some(mut packet) = self.client_packets_queue.lock().pop() {
+        while let Some(mut packet) = self.next_packet() {
             let _ = self.handle_packet(server, &mut packet).await.map_err(|e| {
                 let text = format!("Error while reading incoming packet {}", e);
                 log::error!("{}", text);
-                self.kick(&text)
+                self.kick(DisconnectReason::ProtocolError(Some(text)))
             });
         }
     }
@@ -221,22 +350,27 @@ impl Client {
         packet: &mut RawPacket,
     ) -> Result<(), DeserializerError> {
         // TODO: handle each packet's Error instead of calling .unwrap()
+        let state = self.connection_state.load();
+        // Before the handshake the version is unknown, versions we have no ids for get the current ones
+        let version = ProtocolVersion::from_protocol(self.protocol_version.load(Ordering::Relaxed))
+            .unwrap_or_default();
+        let Some(id) = version.packet_ids().server_packet_id(state, packet.id.0) else {
+            skip_unknown_packet(state, packet);
+            return Ok(());
+        };
         let bytebuf = &mut packet.bytebuf;
-        match self.connection_state.load() {
-            pumpkin_protocol::ConnectionState::HandShake => match packet.id.0 {
+        match state {
+            pumpkin_protocol::ConnectionState::HandShake => match id {
                 SHandShake::PACKET_ID => {
                     self.handle_handshake(server, SHandShake::read(bytebuf)?);
                     Ok(())
                 }
                 _ => {
-                    log::error!(
-                        "Failed to handle packet id {} while in Handshake state",
-                        packet.id.0
-                    );
+                    skip_unknown_packet(self.connection_state.load(), packet);
                     Ok(())
                 }
             },
-            pumpkin_protocol::ConnectionState::Status => match packet.id.0 {
+            pumpkin_protocol::ConnectionState::Status => match id {
                 SStatusRequest::PACKET_ID => {
                     self.handle_status_request(server, SStatusRequest::read(bytebuf)?);
                     Ok(())
@@ -246,16 +380,13 @@ impl Client {
                     Ok(())
                 }
                 _ => {
-                    log::error!(
-                        "Failed to handle packet id {} while in Status state",
-                        packet.id.0
-                    );
+                    skip_unknown_packet(self.connection_state.load(), packet);
                     Ok(())
                 }
             },
             // TODO: Check config if transfer is enabled
             pumpkin_protocol::ConnectionState::Login
-            | pumpkin_protocol::ConnectionState::Transfer => match packet.id.0 {
+            | pumpkin_protocol::ConnectionState::Transfer => match id {
                 SLoginStart::PACKET_ID => {
                     self.handle_login_start(server, SLoginStart::read(bytebuf)?);
                     Ok(())
@@ -274,14 +405,11 @@ impl Client {
                     Ok(())
                 }
                 _ => {
-                    log::error!(
-                        "Failed to handle packet id {} while in Login state",
-                        packet.id.0
-                    );
+                    skip_unknown_packet(self.connection_state.load(), packet);
                     Ok(())
                 }
             },
-            pumpkin_protocol::ConnectionState::Config => match packet.id.0 {
+            pumpkin_protocol::ConnectionState::Config => match id {
                 SClientInformationConfig::PACKET_ID => {
                     self.handle_client_information_config(
                         server,
@@ -305,11 +433,13 @@ impl Client {
                     self.handle_known_packs(server, SKnownPacks::read(bytebuf)?);
                     Ok(())
                 }
+                SCookieResponse::PACKET_ID => {
+                    let response = SCookieResponse::read(bytebuf)?;
+                    self.handle_cookie_response(response.key, response.payload);
+                    Ok(())
+                }
                 _ => {
-                    log::error!(
-                        "Failed to handle packet id {} while in Config state",
-                        packet.id.0
-                    );
+                    skip_unknown_packet(self.connection_state.load(), packet);
                     Ok(())
                 }
             },
@@ -320,68 +450,88 @@ impl Client {
         }
     }
 
-    /// Reads the connection until our buffer of len 4096 is full, then decode
+    /// Reads everything the connection has available, then decode
     /// Close connection when an error occurs or when the Client closed the connection
     pub async fn poll(&self, event: &Event) {
+        if event.is_writable() {
+            self.flush_write_queue();
+        }
         if event.is_readable() {
-            let mut received_data = vec![0; 4096];
             let mut bytes_read = 0;
-            loop {
-                let connection = self.connection.clone();
-                let mut connection = connection.lock();
-                match connection.read(&mut received_data[bytes_read..]) {
-                    Ok(0) => {
-                        // Reading 0 bytes means the other side has closed the
-                        // connection or is done writing, then so are we.
-                        self.close();
-                        break;
-                    }
-                    Ok(n) => {
-                        bytes_read += n;
-                        received_data.extend(&vec![0; n]);
-                    }
-                    // Would block "errors" are the OS's way of saying that the
-                    // connection is not actually ready to perform this I/O operation.
-                    Err(ref err) if would_block(err) => break,
-                    Err(ref err) if interrupted(err) => continue,
-                    // Other errors we'll consider fatal.
-                    Err(_) => self.close(),
-                }
+            let result = {
+                let mut connection = self.connection.lock();
+                let mut dec = self.dec.lock();
+                self.read_buffer
+                    .lock()
+                    .read_available(&mut *connection, |data| {
+                        bytes_read += data.len();
+                        dec.queue_slice(data);
+                    })
+            };
+            match result {
+                Ok(false) => {}
+                // Other errors we'll consider fatal.
+                Ok(true) | Err(_) => self.close(),
             }
 
             if bytes_read != 0 {
-                let mut dec = self.dec.lock();
-                dec.queue_slice(&received_data[..bytes_read]);
-                match dec.decode() {
-                    Ok(packet) => {
-                        if let Some(packet) = packet {
-                            self.add_packet(packet);
-                        }
-                    }
-                    Err(err) => self.kick(&err.to_string()),
+                METRICS
+                    .bytes_received
+                    .fetch_add(bytes_read as u64, Ordering::Relaxed);
+                self.decode_packets();
+            }
+        }
+    }
+
+    /// Queues every complete packet which was received, a packet which did not fully arrive yet stays buffered.
+    ///
+    /// A packet which can't be decoded kicks the Client, as the following packets would be misread
+    fn decode_packets(&self) {
+        let mut dec = self.dec.lock();
+        loop {
+            match dec.decode() {
+                Ok(Some(packet)) => self.add_packet(packet),
+                Ok(None) => break,
+                Err(err) => {
+                    drop(dec);
+                    self.kick(DisconnectReason::ProtocolError(Some(err.to_string())));
+                    break;
                 }
-                dec.clear();
             }
         }
     }
 
-    /// Kicks the Client with a reason depending on the connection state
-    pub fn kick(&self, reason: &str) {
-        dbg!(reason);
-        match self.connection_state.load() {
+    /// Kicks the Client, the message of a reason is filled into the `messages.kick` template
+    pub fn kick<'a>(&self, message: impl Into<DisconnectMessage<'a>>) {
+        let message = message.into();
+        let category = message.category();
+        let text = message.message(&LIVE_CONFIG.load().messages);
+        METRICS.record_disconnect(category);
+        log::debug!(
+            "Disconnecting {} ({}): {}",
+            self.address.lock(),
+            category,
+            text.clone().to_pretty_console()
+        );
+        self.disconnect(&text);
+    }
+
+    /// Sends the disconnect packet of the connection state and closes the connection
+    pub fn disconnect(&self, reason: &TextComponent) {
+        let sent = match self.connection_state.load() {
+            // The Login disconnect is the only one which still uses JSON
             ConnectionState::Login => {
-                self.try_send_packet(&CLoginDisconnect::new(
-                    &serde_json::to_string_pretty(&reason).unwrap_or_else(|_| "".into()),
-                ))
-                .unwrap_or_else(|_| self.close());
-            }
-            ConnectionState::Config => {
-                self.try_send_packet(&CConfigDisconnect::new(reason))
-                    .unwrap_or_else(|_| self.close());
+                self.try_send_packet(&CLoginDisconnect::new(&reason.to_json()))
             }
-            _ => {
-                log::warn!("Can't kick in {:?} State", self.connection_state)
+            ConnectionState::Config => self.try_send_packet(&CConfigDisconnect::new(reason)),
+            ConnectionState::Play => self.try_send_packet(&CPlayDisconnect::new(reason)),
+            state => {
+                log::warn!("Can't kick in {:?} State", state);
+                Ok(())
             }
+        };
+        if let Err(err) = sent {
+            log::debug!("Couldn't send the disconnect packet: {}", err);
         }
         self.close()
     }
@@ -393,12 +543,25 @@ impl Client {
     }
 }
 
+/// Packets the Server does not handle are skipped, only packets which can't be read kick the Client.
+///
+/// Every packet is decoded into its own buffer, so skipping one never breaks the next one
+pub fn skip_unknown_packet(state: ConnectionState, packet: &RawPacket) {
+    log::debug!(
+        "Skipping unhandled packet id {:#04x} in {:?} state",
+        packet.id.0,
+        state
+    );
+}
+
 #[derive(Error, Debug)]
 pub enum EncryptionError {
     #[error("failed to decrypt shared secret")]
     FailedDecrypt,
     #[error("shared secret has the wrong length")]
     SharedWrongLength,
+    #[error("verify token does not match")]
+    VerifyTokenMismatch,
 }
 
 fn would_block(err: &io::Error) -> bool {
@@ -408,3 +571,206 @@ fn would_block(err: &io::Error) -> bool {
 pub fn interrupted(err: &io::Error) -> bool {
     err.kind() == io::ErrorKind::Interrupted
 }
+
+#[cfg(test)]
+mod test {
+    use std::{io::Read, sync::Arc, time::Duration};
+
+    use bytes::BytesMut;
+    use mio::Token;
+    use parking_lot::Mutex;
+    use pumpkin_core::text::{TextComponent, TextContent};
+    use pumpkin_protocol::{bytebuf::ByteBuffer, client::status::CPingResponse, ConnectionState};
+
+    use super::{
+        authentication::has_joined_url,
+        packet_capture::{read_capture, PacketCapture},
+        Client,
+    };
+    use crate::test_helpers::{accepted_client, client, connected_client};
+
+    /// Collects what a capture writes so the test can read it back
+    #[derive(Clone, Default)]
+    struct CaptureBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CaptureBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl CaptureBuffer {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8(self.0.lock().clone())
+                .unwrap()
+                .lines()
+                .map(String::from)
+                .collect()
+        }
+    }
+
+    /// Captures every packet of the Client into a buffer
+    fn start_capture(client: &mut Client) -> CaptureBuffer {
+        let buffer = CaptureBuffer::default();
+        client.capture = Some(Arc::new(PacketCapture::new(Box::new(buffer.clone()))));
+        buffer
+    }
+
+    /// Kicks the Client and returns the id and data of the packet it sent
+    fn kick_packet(state: ConnectionState, reason: &str) -> (i32, ByteBuffer) {
+        let (client, mut remote) = connected_client(Token(1), ConnectionState::HandShake);
+        client.connection_state.store(state);
+        client.kick(DisconnectReason::Kicked(Some(reason.to_string())));
+        assert!(client.closed.load(std::sync::atomic::Ordering::Relaxed));
+
+        remote
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut bytes = vec![0; 1024];
+        let read = remote.read(&mut bytes).unwrap();
+        let mut bytebuf = ByteBuffer::new(BytesMut::from(&bytes[..read]));
+        let length = bytebuf.get_var_int().unwrap().0 as usize;
+        assert_eq!(length, bytebuf.buf().len());
+        let id = bytebuf.get_var_int().unwrap().0;
+        (id, bytebuf)
+    }
+
+    fn queued_ids(client: &Client) -> Vec<i32> {
+        client
+            .client_packets_queue
+            .lock()
+            .iter()
+            .map(|packet| packet.id.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_unknown_packet_is_skipped() {
+        let client = client(Token(1), ConnectionState::HandShake);
+        // An unknown packet with one byte of data, followed by a Status Request in the same read
+        client.dec.lock().queue_slice(&[2, 0x7f, 0xAA, 1, 0x00]);
+        client.decode_packets();
+
+        assert_eq!(queued_ids(&client), vec![0x7f, 0x00]);
+        assert!(!client.closed.load(std::sync::atomic::Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_partial_packet_stays_buffered() {
+        let client = client(Token(1), ConnectionState::HandShake);
+        client.dec.lock().queue_slice(&[1, 0x00, 3, 0x01]);
+        client.decode_packets();
+        assert_eq!(queued_ids(&client), vec![0x00]);
+
+        client.dec.lock().queue_slice(&[0x05, 0x06]);
+        client.decode_packets();
+        assert_eq!(queued_ids(&client), vec![0x00, 0x01]);
+    }
+
+    #[test]
+    fn test_malformed_packet_kicks() {
+        let client = client(Token(1), ConnectionState::HandShake);
+        // The length does not fit into a VarInt
+        client
+            .dec
+            .lock()
+            .queue_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]);
+        client.decode_packets();
+        assert!(client.closed.load(std::sync::atomic::Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_login_kick_is_json_component() {
+        let (id, mut bytebuf) = kick_packet(ConnectionState::Login, "The server is full");
+        assert_eq!(id, 0x00);
+        let json = bytebuf.get_string().unwrap();
+        let component: TextComponent = serde_json::from_str(&json).unwrap();
+        match component.content {
+            TextContent::Text { text } => assert_eq!(text, "The server is full"),
+            content => panic!("Unexpected content {:?}", content),
+        }
+    }
+
+    #[test]
+    fn test_config_and_play_kicks_are_nbt_components() {
+        let expected = TextComponent::text("Invalid packet").encode();
+        for (state, packet_id) in [
+            (ConnectionState::Config, 0x02),
+            (ConnectionState::Play, 0x1D),
+        ] {
+            let (id, mut bytebuf) = kick_packet(state, "Invalid packet");
+            assert_eq!(id, packet_id, "{:?}", state);
+            assert_eq!(&bytebuf.buf()[..], &expected[..], "{:?}", state);
+        }
+    }
+
+    #[test]
+    fn test_capture_records_packets() {
+        let (mut client, _remote) = connected_client(Token(1), ConnectionState::HandShake);
+        let capture = start_capture(&mut client);
+        client.connection_state.store(ConnectionState::Status);
+        // A Ping Request with the payload 5
+        client
+            .dec
+            .lock()
+            .queue_slice(&[9, 0x01, 0, 0, 0, 0, 0, 0, 0, 5]);
+        client.decode_packets();
+        let packet = client.next_packet().unwrap();
+        assert_eq!(packet.id.0, 0x01);
+        client.send_packet(&CPingResponse::new(5));
+
+        assert_eq!(
+            capture.lines(),
+            vec!["1 in Status 0x01 8 0000000000000005", "1 out Status 0x01 8"]
+        );
+    }
+
+    #[test]
+    fn test_replay_reproduces_packets() {
+        let mut client = client(Token(1), ConnectionState::HandShake);
+        let capture = start_capture(&mut client);
+        // An unknown packet, a Handshake and a Login Start which arrived in one read
+        let mut stream = vec![2, 0x7f, 0xAA];
+        let handshake = [0x00, 0xff, 0x05, 0x00, 0x63, 0xdd, 0x02];
+        stream.push(handshake.len() as u8);
+        stream.extend_from_slice(&handshake);
+        stream.extend_from_slice(&[3, 0x00, 0x01, b'a']);
+        client.dec.lock().queue_slice(&stream);
+        client.decode_packets();
+        let mut handled = Vec::new();
+        while let Some(mut packet) = client.next_packet() {
+            handled.push((packet.id.0, packet.bytebuf.buf().to_vec()));
+        }
+        assert_eq!(handled.len(), 3);
+
+        let captured = read_capture(capture.lines().join("\n").as_bytes()).unwrap();
+        let mut replayed = client(Token(1), ConnectionState::HandShake);
+        let replay_capture = start_capture(&mut replayed);
+        assert_eq!(replayed.replay(&captured), 3);
+        let mut replayed_packets = Vec::new();
+        while let Some(mut packet) = replayed.next_packet() {
+            replayed_packets.push((packet.id.0, packet.bytebuf.buf().to_vec()));
+        }
+        assert_eq!(replayed_packets, handled);
+        // The replayed Client sees the exact same stream
+        assert_eq!(replay_capture.lines(), capture.lines());
+    }
+
+    #[test]
+    fn test_accept_keeps_peer_address() {
+        let (client, remote) = accepted_client(Token(1), [127, 0, 0, 2].into());
+        assert_eq!(*client.address.lock(), remote.local_addr().unwrap());
+
+        // The encryption response asks the session server with the IP of the peer, not of the Server
+        let ip = client.address.lock().ip();
+        let url = has_joined_url("Steve", "hash", Some(&ip));
+        assert!(url
+            .query_pairs()
+            .any(|(key, value)| key == "ip" && value == "127.0.0.2"));
+    }
+}
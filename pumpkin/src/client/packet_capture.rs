@@ -0,0 +1,248 @@
+use std::{
+    fmt::Write as _,
+    fs::OpenOptions,
+    io::{self, BufRead, Write},
+    sync::{Arc, LazyLock},
+};
+
+use parking_lot::Mutex;
+use pumpkin_config::{PacketCaptureConfig, ADVANCED_CONFIG};
+use pumpkin_protocol::{bytebuf::ByteBuffer, ConnectionState, RawPacket, VarInt};
+
+/// The capture every Client writes into, only opened when `packet_capture` is enabled so there is no overhead otherwise
+pub static PACKET_CAPTURE: LazyLock<Option<Arc<PacketCapture>>> =
+    LazyLock::new(|| PacketCapture::from_config(&ADVANCED_CONFIG.packet_capture).map(Arc::new));
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Direction {
+    Inbound,
+    Outbound,
+}
+
+/// One decoded packet of a capture.
+///
+/// A capture has one line per packet:
+/// `<connection> <in|out> <state> <id> <length> [payload]`,
+/// only inbound packets keep their payload (as hex) since only those can be replayed
+#[derive(Debug, PartialEq, Clone)]
+pub struct CapturedPacket {
+    /// The token of the connection the packet belongs to
+    pub connection: usize,
+    pub direction: Direction,
+    pub state: ConnectionState,
+    pub id: i32,
+    /// The length of the payload, without the packet id
+    pub length: usize,
+    pub payload: Option<Vec<u8>>,
+}
+
+impl CapturedPacket {
+    pub fn inbound(connection: usize, state: ConnectionState, packet: &mut RawPacket) -> Self {
+        let payload = packet.bytebuf.buf().to_vec();
+        Self {
+            connection,
+            direction: Direction::Inbound,
+            state,
+            id: packet.id.0,
+            length: payload.len(),
+            payload: Some(payload),
+        }
+    }
+
+    pub fn outbound(connection: usize, state: ConnectionState, id: i32, length: usize) -> Self {
+        Self {
+            connection,
+            direction: Direction::Outbound,
+            state,
+            id,
+            length,
+            payload: None,
+        }
+    }
+
+    /// The packet as it would have been queued by the decoder, None for outbound packets
+    pub fn to_raw_packet(&self) -> Option<RawPacket> {
+        if self.direction != Direction::Inbound {
+            return None;
+        }
+        let payload = self.payload.as_deref()?;
+        Some(RawPacket {
+            id: VarInt(self.id),
+            bytebuf: ByteBuffer::new(payload.into()),
+        })
+    }
+
+    pub fn to_line(&self) -> String {
+        let direction = match self.direction {
+            Direction::Inbound => "in",
+            Direction::Outbound => "out",
+        };
+        let mut line = format!(
+            "{} {} {:?} {:#04x} {}",
+            self.connection, direction, self.state, self.id, self.length
+        );
+        if let Some(payload) = &self.payload {
+            line.push(' ');
+            for byte in payload {
+                let _ = write!(line, "{:02x}", byte);
+            }
+        }
+        line
+    }
+
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut parts = line.split_whitespace();
+        let connection = parts.next()?.parse().ok()?;
+        let direction = match parts.next()? {
+            "in" => Direction::Inbound,
+            "out" => Direction::Outbound,
+            _ => return None,
+        };
+        let state = parse_state(parts.next()?)?;
+        let id = i32::from_str_radix(parts.next()?.strip_prefix("0x")?, 16).ok()?;
+        let length = parts.next()?.parse().ok()?;
+        let payload = match parts.next() {
+            Some(hex) => Some(parse_hex(hex)?),
+            // An empty payload has nothing to write
+            None if direction == Direction::Inbound => Some(Vec::new()),
+            None => None,
+        };
+        if payload
+            .as_ref()
+            .is_some_and(|payload| payload.len() != length)
+        {
+            return None;
+        }
+        Some(Self {
+            connection,
+            direction,
+            state,
+            id,
+            length,
+            payload,
+        })
+    }
+}
+
+fn parse_state(state: &str) -> Option<ConnectionState> {
+    Some(match state {
+        "HandShake" => ConnectionState::HandShake,
+        "Status" => ConnectionState::Status,
+        "Login" => ConnectionState::Login,
+        "Transfer" => ConnectionState::Transfer,
+        "Config" => ConnectionState::Config,
+        "Play" => ConnectionState::Play,
+        _ => return None,
+    })
+}
+
+fn parse_hex(hex: &str) -> Option<Vec<u8>> {
+    if hex.len() % 2 != 0 {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Writes every captured packet as one line
+pub struct PacketCapture {
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl PacketCapture {
+    pub fn new(out: Box<dyn Write + Send>) -> Self {
+        Self {
+            out: Mutex::new(out),
+        }
+    }
+
+    pub fn from_config(config: &PacketCaptureConfig) -> Option<Self> {
+        if !config.enabled {
+            return None;
+        }
+        match OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&config.path)
+        {
+            Ok(file) => {
+                log::warn!(
+                    "Capturing every packet into {}, this is slow and should only be used for debugging",
+                    config.path
+                );
+                Some(Self::new(Box::new(file)))
+            }
+            Err(err) => {
+                log::error!("Couldn't open the packet capture {}: {}", config.path, err);
+                None
+            }
+        }
+    }
+
+    pub fn record(&self, packet: &CapturedPacket) {
+        let mut out = self.out.lock();
+        if let Err(err) = writeln!(out, "{}", packet.to_line()) {
+            log::error!("Couldn't write into the packet capture: {}", err);
+        }
+    }
+}
+
+/// Reads a capture, lines which can't be parsed are skipped
+pub fn read_capture(reader: impl BufRead) -> io::Result<Vec<CapturedPacket>> {
+    let mut packets = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        match CapturedPacket::parse(&line) {
+            Some(packet) => packets.push(packet),
+            None => log::debug!("Skipping invalid capture line {:?}", line),
+        }
+    }
+    Ok(packets)
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_protocol::ConnectionState;
+
+    use super::{read_capture, CapturedPacket, Direction};
+
+    #[test]
+    fn test_lines_round_trip() {
+        let inbound = CapturedPacket {
+            connection: 3,
+            direction: Direction::Inbound,
+            state: ConnectionState::Play,
+            id: 0x1A,
+            length: 3,
+            payload: Some(vec![0x00, 0xff, 0x10]),
+        };
+        assert_eq!(inbound.to_line(), "3 in Play 0x1a 3 00ff10");
+        let outbound = CapturedPacket::outbound(3, ConnectionState::Config, 0x03, 120);
+        assert_eq!(outbound.to_line(), "3 out Config 0x03 120");
+
+        let capture = format!(
+            "{}\nnot a packet\n{}\n",
+            inbound.to_line(),
+            outbound.to_line()
+        );
+        assert_eq!(
+            read_capture(capture.as_bytes()).unwrap(),
+            vec![inbound, outbound]
+        );
+    }
+
+    #[test]
+    fn test_payload_has_to_match_the_length() {
+        assert!(CapturedPacket::parse("1 in Status 0x00 2 00").is_none());
+        assert!(CapturedPacket::parse("1 in Status 0x00 1 0").is_none());
+        assert_eq!(
+            CapturedPacket::parse("1 in Status 0x00 0")
+                .unwrap()
+                .payload
+                .unwrap(),
+            Vec::<u8>::new()
+        );
+    }
+}
@@ -1,41 +1,98 @@
-use std::{f32::consts::PI, sync::Arc};
+use std::{sync::Arc, time::Instant};
 
 use crate::{
+    client::{disconnect::DisconnectReason, illegal_characters::filter_chat},
     commands::CommandSender,
-    entity::player::{ChatMode, Hand, Player},
-    server::Server,
-    world::player_chunker,
+    entity::{
+        chat_flood::Flood,
+        experience_orb,
+        item::{self, ItemEntity},
+        knockback_direction,
+        player::{ChatMode, ChatSession, Hand, Player},
+        Entity,
+    },
+    events::{BlockPlace, Cancellable, PlayerChat},
+    server::{bans::BanInfo, mutes::unix_now, Server},
+    world::{
+        entity_tracker,
+        hardcore::{self, DeathOutcome},
+        mount, player_chunker,
+        respawn::{self, RespawnPoint},
+        sleep,
+    },
 };
 use num_traits::FromPrimitive;
-use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_config::{BypassLimit, IllegalCharacterAction, ADVANCED_CONFIG, LIVE_CONFIG};
 use pumpkin_core::{
     math::{position::WorldPosition, vector3::Vector3, wrap_degrees},
-    text::TextComponent,
-    GameMode,
+    text::{color::NamedColor, TextComponent},
+    Difficulty, GameMode,
 };
 use pumpkin_entity::EntityId;
-use pumpkin_inventory::{InventoryError, WindowType};
+use pumpkin_inventory::{player::PlayerInventory, InventoryError, WindowType};
 use pumpkin_protocol::server::play::{SCloseContainer, SSetPlayerGround, SUseItem};
 use pumpkin_protocol::{
     client::play::{
-        Animation, CAcknowledgeBlockChange, CBlockUpdate, CEntityAnimation, CEntityVelocity,
-        CHeadRot, CHurtAnimation, CPingResponse, CPlayerChatMessage, CUpdateEntityPos,
-        CUpdateEntityPosRot, CUpdateEntityRot, CWorldEvent, FilterType,
+        CAcknowledgeBlockChange, CBlockEntityData, CBlockUpdate, CEntityAnimation, CEntityVelocity,
+        CHeadRot, CHurtAnimation, COpenSignEditor, CPingResponse, CPlayerChatMessage,
+        CSetContainerSlot, CWorldEvent, FilterType,
     },
     server::play::{
-        Action, ActionType, SChatCommand, SChatMessage, SClientInformationPlay, SConfirmTeleport,
-        SInteract, SPlayPingRequest, SPlayerAction, SPlayerCommand, SPlayerPosition,
-        SPlayerPositionRotation, SPlayerRotation, SSetCreativeSlot, SSetHeldItem, SSwingArm,
-        SUseItemOn, Status,
+        Action, ActionType, ClientCommandAction, SChangeDifficulty, SChatCommand, SChatMessage,
+        SClientCommand, SClientInformationPlay, SConfirmTeleport, SInteract,
+        SMessageAcknowledgement, SMoveVehicle, SPlayPingRequest, SPlayerAction, SPlayerCommand,
+        SPlayerInput, SPlayerPosition, SPlayerPositionRotation, SPlayerRotation, SPlayerSession,
+        SSetCreativeSlot, SSetHeldItem, SSwingArm, SUpdateSign, SUseItemOn, Status,
     },
+    slot::Slot,
+    VarInt,
 };
-use pumpkin_world::block::{BlockFace, BlockState};
+use pumpkin_world::block::{block_entity::BlockEntity, BlockFace, BlockId, BlockState};
 use pumpkin_world::global_registry;
+use pumpkin_world::item::ItemStack;
 
 use super::PlayerConfig;
 
-fn modulus(a: f32, b: f32) -> f32 {
-    ((a % b) + b) % b
+/// The longest line of a sign vanilla accepts
+const MAX_SIGN_LINE_LENGTH: usize = 384;
+
+/// The damage a Player deals with their fist
+const BASE_ATTACK_DAMAGE: f32 = 1.0;
+const ATTACK_KNOCKBACK_STRENGTH: f64 = 1.0;
+
+/// The damage one Player deals to another, zero when PvP is disabled or the victim is protected
+fn player_attack_damage(
+    pvp_enabled: bool,
+    protect_creative: bool,
+    victim_gamemode: GameMode,
+) -> f32 {
+    if !pvp_enabled || (protect_creative && victim_gamemode == GameMode::Creative) {
+        return 0.0;
+    }
+    BASE_ATTACK_DAMAGE
+}
+
+/// Puts an item from the creative inventory into the Player's inventory.
+///
+/// Returns false when nothing changed, this happens when the item was dropped out of the inventory
+fn set_creative_slot(
+    inventory: &mut PlayerInventory,
+    gamemode: GameMode,
+    slot: i16,
+    item: Option<ItemStack>,
+) -> Result<bool, InventoryError> {
+    // Only creative players can create items out of nothing
+    if gamemode != GameMode::Creative {
+        return Err(InventoryError::PermissionError);
+    }
+    if slot == -1 {
+        // The caller drops the item into the world
+        return Ok(false);
+    }
+    let slot = usize::try_from(slot).map_err(|_| InventoryError::InvalidSlot)?;
+    // Creative players can put any item everywhere
+    inventory.set_slot(slot, item, true)?;
+    Ok(true)
 }
 
 /// Handles all Play Packets send by a real Player
@@ -54,12 +111,14 @@ impl Player {
 
                 *awaiting_teleport = None;
             } else {
-                self.kick(TextComponent::text("Wrong teleport id"))
+                self.kick(DisconnectReason::ProtocolError(Some(
+                    "Wrong teleport id".to_string(),
+                )))
             }
         } else {
-            self.kick(TextComponent::text(
-                "Send Teleport confirm, but we did not teleport",
-            ))
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Send Teleport confirm, but we did not teleport".to_string(),
+            )))
         }
     }
 
@@ -73,50 +132,35 @@ impl Player {
 
     pub async fn handle_position(&self, _server: &Arc<Server>, position: SPlayerPosition) {
         if position.x.is_nan() || position.feet_y.is_nan() || position.z.is_nan() {
-            self.kick(TextComponent::text("Invalid movement"));
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid movement".to_string(),
+            )));
             return;
         }
         let entity = &self.entity;
-        entity.set_pos(
+        // Passengers move with their vehicle, see `mount::move_passengers`
+        if entity.is_riding() {
+            return;
+        }
+        let to = Vector3::new(
             Self::clamp_horizontal(position.x),
             Self::clamp_vertical(position.feet_y),
             Self::clamp_horizontal(position.z),
         );
-        let pos = entity.pos.load();
-        self.last_position.store(pos);
-        let last_position = self.last_position.load();
+        if !self.validate_move(to) {
+            return;
+        }
+        entity.set_pos(to.x, to.y, to.z);
+        self.last_position.store(entity.pos.load());
         entity
             .on_ground
             .store(position.ground, std::sync::atomic::Ordering::Relaxed);
-        let entity_id = entity.entity_id;
-        let Vector3 { x, y, z } = pos;
-        let (lastx, lasty, lastz) = (last_position.x, last_position.y, last_position.z);
-        let world = &entity.world;
-
-        // let delta = Vector3::new(x - lastx, y - lasty, z - lastz);
-        // let velocity = self.velocity;
-
-        // // Player is falling down fast, we should account for that
-        // let max_speed = if self.fall_flying { 300.0 } else { 100.0 };
-
-        // teleport when more than 8 blocks (i guess 8 blocks)
-        // TODO: REPLACE * 2.0 by movement packets. see vanilla for details
-        // if delta.length_squared() - velocity.length_squared() > max_speed * 2.0 {
-        //     self.teleport(x, y, z, self.entity.yaw, self.entity.pitch);
-        //     return;
-        // }
-        // send new position to all other players
-        world.broadcast_packet_expect(
-            &[self.client.token],
-            &CUpdateEntityPos::new(
-                entity_id.into(),
-                x.mul_add(4096.0, -(lastx * 4096.0)) as i16,
-                y.mul_add(4096.0, -(lasty * 4096.0)) as i16,
-                z.mul_add(4096.0, -(lastz * 4096.0)) as i16,
-                position.ground,
-            ),
-        );
+        // The other players get the new position with the next batch, see `entity_tracker::broadcast_movement`
+        self.update_pose().await;
         player_chunker::update_position(entity, self).await;
+        self.move_passengers().await;
+        item::pickup_items(&entity.world, self);
+        experience_orb::pickup_experience(&entity.world, self);
     }
 
     pub async fn handle_position_rotation(
@@ -128,23 +172,32 @@ impl Player {
             || position_rotation.feet_y.is_nan()
             || position_rotation.z.is_nan()
         {
-            self.kick(TextComponent::text("Invalid movement"));
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid movement".to_string(),
+            )));
             return;
         }
         if position_rotation.yaw.is_infinite() || position_rotation.pitch.is_infinite() {
-            self.kick(TextComponent::text("Invalid rotation"));
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid rotation".to_string(),
+            )));
             return;
         }
         let entity = &self.entity;
+        if entity.is_riding() {
+            return;
+        }
 
-        entity.set_pos(
+        let to = Vector3::new(
             Self::clamp_horizontal(position_rotation.x),
             Self::clamp_vertical(position_rotation.feet_y),
             Self::clamp_horizontal(position_rotation.z),
         );
-        let pos = entity.pos.load();
-        self.last_position.store(pos);
-        let last_position = self.last_position.load();
+        if !self.validate_move(to) {
+            return;
+        }
+        entity.set_pos(to.x, to.y, to.z);
+        self.last_position.store(entity.pos.load());
         entity.on_ground.store(
             position_rotation.ground,
             std::sync::atomic::Ordering::Relaxed,
@@ -154,50 +207,20 @@ impl Player {
             wrap_degrees(position_rotation.pitch).clamp(-90.0, 90.0) % 360.0,
         );
 
-        let entity_id = entity.entity_id;
-        let Vector3 { x, y, z } = pos;
-        let (lastx, lasty, lastz) = (last_position.x, last_position.y, last_position.z);
-        let yaw = modulus(entity.yaw.load() * 256.0 / 360.0, 256.0);
-        let pitch = modulus(entity.pitch.load() * 256.0 / 360.0, 256.0);
-        // let head_yaw = (entity.head_yaw * 256.0 / 360.0).floor();
-        let world = &entity.world;
-
-        // let delta = Vector3::new(x - lastx, y - lasty, z - lastz);
-        // let velocity = self.velocity;
-
-        // // Player is falling down fast, we should account for that
-        // let max_speed = if self.fall_flying { 300.0 } else { 100.0 };
-
-        // // teleport when more than 8 blocks (i guess 8 blocks)
-        // // TODO: REPLACE * 2.0 by movement packets. see vanilla for details
-        // if delta.length_squared() - velocity.length_squared() > max_speed * 2.0 {
-        //     self.teleport(x, y, z, yaw, pitch);
-        //     return;
-        // }
-        // send new position to all other players
-
-        world.broadcast_packet_expect(
-            &[self.client.token],
-            &CUpdateEntityPosRot::new(
-                entity_id.into(),
-                x.mul_add(4096.0, -(lastx * 4096.0)) as i16,
-                y.mul_add(4096.0, -(lasty * 4096.0)) as i16,
-                z.mul_add(4096.0, -(lastz * 4096.0)) as i16,
-                yaw as u8,
-                pitch as u8,
-                position_rotation.ground,
-            ),
-        );
-        world.broadcast_packet_expect(
-            &[self.client.token],
-            &CHeadRot::new(entity_id.into(), yaw as u8),
-        );
+        // The head turns right away, the body follows with the next batch
+        self.broadcast_head_rotation();
+        self.update_pose().await;
         player_chunker::update_position(entity, self).await;
+        self.move_passengers().await;
+        item::pickup_items(&entity.world, self);
+        experience_orb::pickup_experience(&entity.world, self);
     }
 
     pub async fn handle_rotation(&self, _server: &Arc<Server>, rotation: SPlayerRotation) {
         if !rotation.yaw.is_finite() || !rotation.pitch.is_finite() {
-            self.kick(TextComponent::text("Invalid rotation"));
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid rotation".to_string(),
+            )));
             return;
         }
         let entity = &self.entity;
@@ -208,18 +231,80 @@ impl Player {
             wrap_degrees(rotation.yaw) % 360.0,
             wrap_degrees(rotation.pitch).clamp(-90.0, 90.0) % 360.0,
         );
-        // send new position to all other players
-        let entity_id = entity.entity_id;
-        let yaw = modulus(entity.yaw.load() * 256.0 / 360.0, 256.0);
-        let pitch = modulus(entity.pitch.load() * 256.0 / 360.0, 256.0);
-        // let head_yaw = modulus(entity.head_yaw * 256.0 / 360.0, 256.0);
+        self.broadcast_head_rotation();
+    }
 
-        let world = &entity.world;
-        let packet =
-            CUpdateEntityRot::new(entity_id.into(), yaw as u8, pitch as u8, rotation.ground);
-        world.broadcast_packet_expect(&[self.client.token], &packet);
-        let packet = CHeadRot::new(entity_id.into(), yaw as u8);
-        world.broadcast_packet_expect(&[self.client.token], &packet);
+    /// Takes the passengers along after the Player moved
+    async fn move_passengers(&self) {
+        for passenger in mount::move_passengers(&self.entity.world, self) {
+            player_chunker::update_position(&passenger.entity, &passenger).await;
+        }
+    }
+
+    pub fn handle_player_input(&self, _server: &Arc<Server>, input: SPlayerInput) {
+        if !input.sideways.is_finite() || !input.forward.is_finite() {
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid input".to_string(),
+            )));
+            return;
+        }
+        if input.unmount() {
+            mount::dismount(&self.entity.world, self);
+        }
+    }
+
+    pub async fn handle_move_vehicle(&self, _server: &Arc<Server>, move_vehicle: SMoveVehicle) {
+        if move_vehicle.x.is_nan()
+            || move_vehicle.y.is_nan()
+            || move_vehicle.z.is_nan()
+            || !move_vehicle.yaw.is_finite()
+            || !move_vehicle.pitch.is_finite()
+        {
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid movement".to_string(),
+            )));
+            return;
+        }
+        let world = &self.entity.world;
+        let Some(vehicle) = self
+            .entity
+            .vehicle
+            .load()
+            .and_then(|id| world.get_player_by_entityid(id))
+        else {
+            return;
+        };
+        // Only the first passenger of a boat-like vehicle steers it, Players move by themselves
+        let vehicle_entity = &vehicle.entity;
+        if !mount::is_steered_by_passenger(&vehicle_entity.entity_type)
+            || vehicle_entity.passengers.lock().first() != Some(&self.entity_id())
+        {
+            return;
+        }
+        vehicle_entity.set_pos(
+            Self::clamp_horizontal(move_vehicle.x),
+            Self::clamp_vertical(move_vehicle.y),
+            Self::clamp_horizontal(move_vehicle.z),
+        );
+        vehicle_entity.set_rotation(
+            wrap_degrees(move_vehicle.yaw) % 360.0,
+            wrap_degrees(move_vehicle.pitch).clamp(-90.0, 90.0) % 360.0,
+        );
+        for passenger in mount::move_passengers(world, &vehicle) {
+            player_chunker::update_position(&passenger.entity, &passenger).await;
+        }
+    }
+
+    /// Head rotations are not batched like the rest of the movement, so looking around stays smooth
+    fn broadcast_head_rotation(&self) {
+        let entity = &self.entity;
+        entity.world.broadcast_packet_expect(
+            &[self.client.token],
+            &CHeadRot::new(
+                entity.entity_id.into(),
+                entity_tracker::encode_angle(entity.yaw.load()),
+            ),
+        );
     }
 
     pub fn handle_chat_command(&self, server: &Arc<Server>, command: SChatCommand) {
@@ -227,6 +312,25 @@ impl Player {
         dispatcher.handle_command(&mut CommandSender::Player(self), server, &command.command);
     }
 
+    pub fn handle_change_difficulty(&self, server: &Arc<Server>, packet: SChangeDifficulty) {
+        let is_operator = server
+            .operators
+            .read()
+            .get(&self.gameprofile.id)
+            .is_some_and(|op| op.level >= 2);
+        if !is_operator {
+            return;
+        }
+        match Difficulty::from_u8(packet.difficulty) {
+            Some(difficulty) => {
+                self.entity.world.set_difficulty(difficulty, false);
+            }
+            None => self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid difficulty".to_string(),
+            ))),
+        }
+    }
+
     pub fn handle_player_ground(&self, _server: &Arc<Server>, ground: SSetPlayerGround) {
         self.entity
             .on_ground
@@ -251,7 +355,9 @@ impl Player {
                         entity.set_sneaking(false).await
                     }
                 }
-                pumpkin_protocol::server::play::Action::LeaveBed => todo!(),
+                pumpkin_protocol::server::play::Action::LeaveBed => {
+                    self.stop_sleeping();
+                }
                 pumpkin_protocol::server::play::Action::StartSprinting => {
                     if !entity.sprinting.load(std::sync::atomic::Ordering::Relaxed) {
                         entity.set_sprinting(true).await
@@ -276,49 +382,197 @@ impl Player {
                     }
                 } // TODO
             }
+            self.update_pose().await;
         } else {
-            self.kick(TextComponent::text("Invalid player command"))
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid player command".to_string(),
+            )))
         }
     }
 
+    pub async fn handle_client_command(&self, server: &Arc<Server>, command: SClientCommand) {
+        match ClientCommandAction::from_i32(command.action_id.0) {
+            Some(ClientCommandAction::PerformRespawn) => {
+                if self.entity.health.load() <= 0.0 && self.death_outcome() == DeathOutcome::Ban {
+                    self.ban_after_hardcore_death(server);
+                } else {
+                    // Players who died in hardcore mode come back as spectators
+                    self.respawn().await;
+                }
+            }
+            // TODO: statistics
+            Some(ClientCommandAction::RequestStats) => {}
+            None => self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid client command".to_string(),
+            ))),
+        }
+    }
+
+    fn ban_after_hardcore_death(&self, server: &Server) {
+        {
+            let mut bans = server.bans.write();
+            bans.ban_player(
+                Some(self.gameprofile.id),
+                &self.gameprofile.name,
+                BanInfo {
+                    created: unix_now(),
+                    source: "Server".to_string(),
+                    expires: None,
+                    reason: Some(hardcore::BAN_REASON.to_string()),
+                },
+            );
+            if let Err(err) = bans.save() {
+                log::error!("Couldn't save the ban list: {}", err);
+            }
+        }
+        server.kick_banned();
+    }
+
     pub async fn handle_swing_arm(&self, _server: &Arc<Server>, swing_arm: SSwingArm) {
         match Hand::from_i32(swing_arm.hand.0) {
-            Some(hand) => {
-                let animation = match hand {
-                    Hand::Main => Animation::SwingMainArm,
-                    Hand::Off => Animation::SwingOffhand,
-                };
-                let id = self.entity_id();
-                let world = &self.entity.world;
-                world.broadcast_packet_expect(
-                    &[self.client.token],
-                    &CEntityAnimation::new(id.into(), animation as u8),
-                )
-            }
+            Some(hand) => self.swing_arm(hand),
             None => {
-                self.kick(TextComponent::text("Invalid hand"));
+                self.kick(DisconnectReason::ProtocolError(Some(
+                    "Invalid hand".to_string(),
+                )));
             }
         };
     }
 
-    pub async fn handle_chat_message(&self, _server: &Arc<Server>, chat_message: SChatMessage) {
+    /// Shows everyone else the Player swinging the hand, the client already animated it for the Player
+    fn swing_arm(&self, hand: Hand) {
+        let animation = hand.swing_animation();
+        self.entity.world.broadcast_packet_expect(
+            &[self.client.token],
+            &CEntityAnimation::new(self.entity_id().into(), animation as u8),
+        );
+    }
+
+    /// Returns if the Player is muted and tells them so
+    pub fn is_muted(&self, server: &Server) -> bool {
+        let mutes = server.mutes.read();
+        let Some(mute) = mutes.get(&self.gameprofile.id, unix_now()) else {
+            return false;
+        };
+        let mut notice = "You are muted".to_string();
+        if let Some(reason) = &mute.reason {
+            notice.push_str(": ");
+            notice.push_str(reason);
+        }
+        self.send_system_message(TextComponent::text(&notice).color_named(NamedColor::Red));
+        true
+    }
+
+    /// Returns if the message may be sent, Players who flood the chat are kicked or their message is dropped
+    fn check_chat_flood(&self, message: &str) -> bool {
+        let live = LIVE_CONFIG.load();
+        let config = &live.chat_flood;
+        if !config.enabled || self.can_bypass(BypassLimit::ChatFlood) {
+            return true;
+        }
+        let Err(flood) = self
+            .chat_flood
+            .lock()
+            .check(message, Instant::now(), config)
+        else {
+            return true;
+        };
+        log::info!(
+            "{} is flooding the chat ({:?})",
+            self.gameprofile.name,
+            flood
+        );
+        if config.kick {
+            self.kick(DisconnectReason::Spam);
+        } else {
+            let notice = match flood {
+                Flood::TooFast => "You are sending messages too quickly",
+                Flood::Repeated => "Please don't repeat the same message",
+            };
+            self.send_system_message(TextComponent::text(notice).color_named(NamedColor::Red));
+        }
+        false
+    }
+
+    /// The message without illegal characters, `None` if it must not be sent
+    fn filter_illegal_characters(&self, message: String) -> Option<String> {
+        let config = &ADVANCED_CONFIG.illegal_characters;
+        if !config.enabled {
+            return Some(message);
+        }
+        match filter_chat(message, config.chat_action) {
+            Ok(message) => Some(message),
+            Err(IllegalCharacterAction::Kick) => {
+                self.kick(DisconnectReason::IllegalCharacters);
+                None
+            }
+            Err(_) => {
+                self.send_system_message(
+                    TextComponent::text("Your message contained illegal characters")
+                        .color_named(NamedColor::Red),
+                );
+                None
+            }
+        }
+    }
+
+    pub async fn handle_chat_message(&self, server: &Arc<Server>, chat_message: SChatMessage) {
         dbg!("got message");
 
         let message = chat_message.message;
         if message.len() > 256 {
-            self.kick(TextComponent::text("Oversized message"));
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Oversized message".to_string(),
+            )));
+            return;
+        }
+
+        if chat_message.message_count.0 < 0 {
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid message acknowledgement".to_string(),
+            )));
+            return;
+        }
+        self.acknowledged_messages.fetch_add(
+            chat_message.message_count.0,
+            std::sync::atomic::Ordering::Relaxed,
+        );
+
+        let original_len = message.len();
+        let Some(message) = self.filter_illegal_characters(message) else {
+            return;
+        };
+        // The signature doesn't match a sanitized message
+        let signature = chat_message
+            .signature
+            .filter(|_| message.len() == original_len);
+
+        if self.is_muted(server) {
+            return;
+        }
+        if !self.check_chat_flood(&message) {
             return;
         }
 
         // TODO: filter message & validation
         let gameprofile = &self.gameprofile;
 
+        let event = server.events.fire(PlayerChat {
+            profile: gameprofile.clone(),
+            message,
+            cancelled: false,
+        });
+        if event.is_cancelled() {
+            return;
+        }
+        let message = event.message;
+
         let entity = &self.entity;
         let world = &entity.world;
         world.broadcast_packet_all(&CPlayerChatMessage::new(
             gameprofile.id,
             1.into(),
-            chat_message.signature.as_deref(),
+            signature.as_deref(),
             &message,
             chat_message.timestamp,
             chat_message.salt,
@@ -341,7 +595,36 @@ impl Player {
         ) */
     }
 
-    pub fn handle_client_information_play(
+    pub fn handle_player_session(&self, _server: &Arc<Server>, session: SPlayerSession) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|time| time.as_millis() as i64)
+            .unwrap_or(0);
+        // TODO: verify the key signature against Mojang's public keys in online mode
+        let chat_session = chat_session(session, now);
+        if chat_session.is_none() {
+            log::debug!("{} sent an expired chat session", self.gameprofile.name);
+        }
+        *self.chat_session.lock() = chat_session;
+    }
+
+    pub fn handle_message_acknowledgement(
+        &self,
+        _server: &Arc<Server>,
+        acknowledgement: SMessageAcknowledgement,
+    ) {
+        let count = acknowledgement.message_count.0;
+        if count < 0 {
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid message acknowledgement".to_string(),
+            )));
+            return;
+        }
+        self.acknowledged_messages
+            .fetch_add(count, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    pub async fn handle_client_information_play(
         &self,
         _server: &Arc<Server>,
         client_information: SClientInformationPlay,
@@ -350,6 +633,7 @@ impl Player {
             Hand::from_i32(client_information.main_hand.into()),
             ChatMode::from_i32(client_information.chat_mode.into()),
         ) {
+            let old_view_distance = player_chunker::get_view_distance(self) as i32;
             *self.config.lock() = PlayerConfig {
                 locale: client_information.locale,
                 view_distance: client_information.view_distance,
@@ -360,8 +644,11 @@ impl Player {
                 text_filtering: client_information.text_filtering,
                 server_listing: client_information.server_listing,
             };
+            player_chunker::update_view_distance(self, old_view_distance).await;
         } else {
-            self.kick(TextComponent::text("Invalid hand or chat type"))
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid hand or chat type".to_string(),
+            )))
         }
     }
 
@@ -370,70 +657,118 @@ impl Player {
         let entity = &self.entity;
         if entity.sneaking.load(std::sync::atomic::Ordering::Relaxed) != sneaking {
             entity.set_sneaking(sneaking).await;
+            self.update_pose().await;
         }
-        match ActionType::from_i32(interact.typ.0) {
-            Some(action) => match action {
-                ActionType::Attack => {
-                    let entity_id = interact.entity_id;
-                    // TODO: do validation and stuff
-                    let config = &ADVANCED_CONFIG.pvp;
-                    if config.enabled {
-                        let world = &entity.world;
-                        let attacked_player = world.get_player_by_entityid(entity_id.0 as EntityId);
-                        if let Some(player) = attacked_player {
-                            let victem_entity = &player.entity;
-                            if config.protect_creative
-                                && player.gamemode.load() == GameMode::Creative
-                            {
-                                return;
-                            }
-                            if config.knockback {
-                                let yaw = entity.yaw.load();
-                                let strength = 1.0;
-                                let victem_velocity = victem_entity.velocity.load();
-                                let saved_velo = victem_velocity;
-                                victem_entity.knockback(
-                                    strength * 0.5,
-                                    (yaw * (PI / 180.0)).sin() as f64,
-                                    -(yaw * (PI / 180.0)).cos() as f64,
-                                );
-                                let packet = &CEntityVelocity::new(
-                                    &entity_id,
-                                    victem_velocity.x as f32,
-                                    victem_velocity.y as f32,
-                                    victem_velocity.z as f32,
-                                );
-                                let velocity = entity.velocity.load();
-                                victem_entity
-                                    .velocity
-                                    .store(velocity.multiply(0.6, 1.0, 0.6));
-
-                                victem_entity.velocity.store(saved_velo);
-                                player.client.send_packet(packet);
-                            }
-                            if config.hurt_animation {
-                                world.broadcast_packet_all(&CHurtAnimation::new(
-                                    &entity_id,
-                                    entity.yaw.load(),
-                                ))
-                            }
-                            if config.swing {}
-                        } else {
-                            self.kick(TextComponent::text("Interacted with invalid entity id"))
-                        }
-                    }
-                }
-                ActionType::Interact => {
-                    dbg!("todo");
-                }
-                ActionType::InteractAt => {
-                    dbg!("todo");
-                }
-            },
-            None => self.kick(TextComponent::text("Invalid action type")),
+        let Some(action) = ActionType::from_i32(interact.typ.0) else {
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid action type".to_string(),
+            )));
+            return;
+        };
+        self.interact_with_entity(interact.entity_id.0 as EntityId, action);
+    }
+
+    /// Attacks or uses the entity with the id, only ids which don't exist kick
+    fn interact_with_entity(&self, entity_id: EntityId, action: ActionType) {
+        let world = &self.entity.world;
+        if let Some(target) = world.get_player_by_entityid(entity_id) {
+            if !self.reaches_entity(&target.entity) {
+                return;
+            }
+            match action {
+                ActionType::Attack => self.attack(&target),
+                // Like in vanilla, using another Player does nothing
+                ActionType::Interact | ActionType::InteractAt => log::debug!(
+                    "{} interacted with {}",
+                    self.gameprofile.name,
+                    target.gameprofile.name
+                ),
+            }
+        } else if let Some(target) = world.get_entity(entity_id) {
+            if !self.reaches_entity(&target) {
+                return;
+            }
+            match action {
+                ActionType::Attack => self.attack_mob(&target),
+                ActionType::Interact | ActionType::InteractAt => log::debug!(
+                    "{} interacted with a {:?}",
+                    self.gameprofile.name,
+                    target.entity_type
+                ),
+            }
+        } else {
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Interacted with invalid entity id".to_string(),
+            )));
         }
     }
-    pub async fn handle_player_action(&self, _server: &Arc<Server>, player_action: SPlayerAction) {
+
+    /// Whether the entity is close enough to interact with, logs when it is not
+    fn reaches_entity(&self, target: &Entity) -> bool {
+        if self.can_interact_with_entity(target, 1.0) {
+            return true;
+        }
+        log::warn!(
+            "{} tried to interact with an entity out of reach",
+            self.gameprofile.name
+        );
+        false
+    }
+
+    /// Attacks another Player, dealing damage and knockback depending on the PvP config.
+    ///
+    /// When PvP is disabled the attack is silently cancelled
+    fn attack(&self, victim: &Player) {
+        let live = LIVE_CONFIG.load();
+        let config = &live.pvp;
+        let entity = &self.entity;
+        let damage = player_attack_damage(
+            entity.world.is_pvp_enabled(),
+            config.protect_creative,
+            victim.gamemode.load(),
+        );
+        if damage <= 0.0 || !victim.damage(damage) {
+            return;
+        }
+        let victim_entity = &victim.entity;
+        let victim_id = VarInt(victim.entity_id());
+        if config.knockback {
+            let (x, z) = knockback_direction(entity.yaw.load());
+            let saved_velocity = victim_entity.velocity.load();
+            victim_entity.knockback(ATTACK_KNOCKBACK_STRENGTH * 0.5, x, z);
+            let velocity = victim_entity.velocity.load();
+            victim.client.send_packet(&CEntityVelocity::new(
+                &victim_id,
+                velocity.x as f32,
+                velocity.y as f32,
+                velocity.z as f32,
+            ));
+            // The client moves itself, so we don't keep the velocity on the server
+            victim_entity.velocity.store(saved_velocity);
+        }
+        if config.hurt_animation {
+            entity
+                .world
+                .broadcast_packet_all(&CHurtAnimation::new(&victim_id, entity.yaw.load()))
+        }
+    }
+
+    /// Attacks a mob, unlike Players mobs can be attacked while PvP is disabled.
+    ///
+    /// Items, experience orbs and vehicles can't be hurt, so attacking them does nothing
+    fn attack_mob(&self, mob: &Entity) {
+        if !mob.damage(BASE_ATTACK_DAMAGE) {
+            return;
+        }
+        if LIVE_CONFIG.load().pvp.hurt_animation {
+            self.entity.world.broadcast_packet_all(&CHurtAnimation::new(
+                &VarInt(mob.entity_id),
+                self.entity.yaw.load(),
+            ));
+        }
+    }
+
+    pub async fn handle_player_action(&self, server: &Arc<Server>, player_action: SPlayerAction) {
         match Status::from_i32(player_action.status.0) {
             Some(status) => match status {
                 Status::StartedDigging => {
@@ -441,6 +776,10 @@ impl Player {
                         // TODO: maybe log?
                         return;
                     }
+                    if !self.can_build_at(&player_action.location) {
+                        self.deny_block_change(&player_action.location, player_action.sequence);
+                        return;
+                    }
                     // TODO: do validation
                     // TODO: Config
                     if self.gamemode.load() == GameMode::Creative {
@@ -452,6 +791,7 @@ impl Player {
                         world.broadcast_packet_all(&CWorldEvent::new(2001, &location, 11, false));
                         // AIR
                         world.broadcast_packet_all(&CBlockUpdate::new(&location, 0.into()));
+                        world.set_block(location, BlockId::default());
                     }
                 }
                 Status::CancelledDigging => {
@@ -469,6 +809,10 @@ impl Player {
                         // TODO: maybe log?
                         return;
                     }
+                    if !self.can_build_at(&location) {
+                        self.deny_block_change(&location, player_action.sequence);
+                        return;
+                    }
                     // Block break & block break sound
                     // TODO: currently this is always dirt replace it
                     let entity = &self.entity;
@@ -476,15 +820,16 @@ impl Player {
                     world.broadcast_packet_all(&CWorldEvent::new(2001, &location, 11, false));
                     // AIR
                     world.broadcast_packet_all(&CBlockUpdate::new(&location, 0.into()));
+                    world.set_block(location, BlockId::default());
                     // TODO: Send this every tick
                     self.client
                         .send_packet(&CAcknowledgeBlockChange::new(player_action.sequence));
                 }
                 Status::DropItemStack => {
-                    dbg!("todo");
+                    self.drop_held_item(server.new_entity_id(), true);
                 }
                 Status::DropItem => {
-                    dbg!("todo");
+                    self.drop_held_item(server.new_entity_id(), false);
                 }
                 Status::ShootArrowOrFinishEating => {
                     dbg!("todo");
@@ -493,16 +838,33 @@ impl Player {
                     dbg!("todo");
                 }
             },
-            None => self.kick(TextComponent::text("Invalid status")),
+            None => self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid status".to_string(),
+            ))),
         }
     }
 
+    /// Reverts a block the client changed in the spawn protection
+    fn deny_block_change(&self, location: &WorldPosition, sequence: VarInt) {
+        let block = self.entity.world.get_block(*location).unwrap_or_default();
+        self.client.send_packet(&CBlockUpdate::new(
+            location,
+            block.get_id_mojang_repr().into(),
+        ));
+        self.client
+            .send_packet(&CAcknowledgeBlockChange::new(sequence));
+        self.send_system_message(
+            TextComponent::text("You can't build near the spawn").color_named(NamedColor::Red),
+        );
+    }
+
+    /// Answers the ping of the debug screen, unlike keep alives the client measures the latency with it
     pub fn handle_play_ping_request(&self, _server: &Arc<Server>, request: SPlayPingRequest) {
         self.client
             .send_packet(&CPingResponse::new(request.payload));
     }
 
-    pub async fn handle_use_item_on(&self, _server: &Arc<Server>, use_item_on: SUseItemOn) {
+    pub async fn handle_use_item_on(&self, server: &Arc<Server>, use_item_on: SUseItemOn) {
         let location = use_item_on.location;
 
         if !self.can_interact_with_block_at(&location, 1.0) {
@@ -511,29 +873,108 @@ impl Player {
         }
 
         if let Some(face) = BlockFace::from_i32(use_item_on.face.0) {
+            let world = &self.entity.world;
+            let clicked = world.get_block(location);
+            // Sneaking Players place blocks against the bed instead
+            if !self
+                .entity
+                .sneaking
+                .load(std::sync::atomic::Ordering::Relaxed)
+                && clicked.is_some_and(respawn::is_respawn_block)
+            {
+                let bed = clicked.and_then(|block| sleep::bed_head(location, block));
+                let point = bed.unwrap_or(location).0;
+                self.set_respawn_point(Some(RespawnPoint {
+                    dimension: world.dimension,
+                    position: (point.x, point.y, point.z),
+                    yaw: self.entity.yaw.load(),
+                    forced: false,
+                }));
+                self.send_system_message(TextComponent::text("Respawn point set"));
+                // Clicking the bed again while sleeping does nothing
+                if let Some(bed) = bed.filter(|_| self.sleeping_in.load().is_none()) {
+                    let thundering = world.weather.lock().thundering;
+                    if world.time.lock().can_sleep(thundering) {
+                        self.start_sleeping(bed);
+                    } else {
+                        self.send_system_message(TextComponent::text(
+                            "You can sleep only at night or during thunderstorms",
+                        ));
+                    }
+                }
+                self.client
+                    .send_packet(&CAcknowledgeBlockChange::new(use_item_on.sequence));
+                return;
+            }
             if let Some(item) = self.inventory.lock().held_item() {
                 let minecraft_id = global_registry::find_minecraft_id(
                     global_registry::ITEM_REGISTRY,
                     item.item_id,
                 )
                 .expect("All item ids are in the global registry");
-                if let Ok(block_state_id) = BlockState::new(minecraft_id, None) {
-                    let entity = &self.entity;
-                    let world = &entity.world;
-                    world.broadcast_packet_all(&CBlockUpdate::new(
-                        &location,
-                        block_state_id.get_id_mojang_repr().into(),
-                    ));
-                    world.broadcast_packet_all(&CBlockUpdate::new(
-                        &WorldPosition(location.0 + face.to_offset()),
-                        block_state_id.get_id_mojang_repr().into(),
-                    ));
+                if let Ok(block_state) = BlockState::new(minecraft_id, None) {
+                    let placed_location = WorldPosition(location.0 + face.to_offset());
+                    if !self.can_build_at(&placed_location) {
+                        self.deny_block_change(&placed_location, use_item_on.sequence);
+                        return;
+                    }
+                    let event = server.events.fire(BlockPlace {
+                        profile: self.gameprofile.clone(),
+                        location: placed_location,
+                        block_state_id: block_state.get_id(),
+                        cancelled: false,
+                    });
+                    if !event.is_cancelled() {
+                        world.broadcast_packet_all(&CBlockUpdate::new(
+                            &location,
+                            block_state.get_id_mojang_repr().into(),
+                        ));
+                        world.broadcast_packet_all(&CBlockUpdate::new(
+                            &placed_location,
+                            block_state.get_id_mojang_repr().into(),
+                        ));
+                        world.set_block(placed_location, block_state.into());
+                        if let Some(sign) = BlockEntity::sign(minecraft_id, placed_location) {
+                            world.update_block_entity(sign);
+                            self.client
+                                .send_packet(&COpenSignEditor::new(&placed_location, true));
+                        }
+                    }
                 }
             }
             self.client
                 .send_packet(&CAcknowledgeBlockChange::new(use_item_on.sequence));
         } else {
-            self.kick(TextComponent::text("Invalid block face"))
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid block face".to_string(),
+            )))
+        }
+    }
+
+    pub fn handle_update_sign(&self, _server: &Arc<Server>, update_sign: SUpdateSign) {
+        let location = update_sign.location;
+        if update_sign
+            .lines
+            .iter()
+            .any(|line| line.chars().count() > MAX_SIGN_LINE_LENGTH)
+        {
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Sign line too long".to_string(),
+            )));
+            return;
+        }
+        let world = &self.entity.world;
+        if self.can_interact_with_block_at(&location, 1.0)
+            && self.can_build_at(&location)
+            && world.edit_sign(location, update_sign.is_front_text, &update_sign.lines)
+        {
+            return;
+        }
+        // The client already shows its text, so we have to revert it
+        if let Some(block_entity) = world.get_block_entity(location) {
+            if let Some(packet) = CBlockEntityData::new(&block_entity) {
+                self.client.send_packet(&packet);
+            }
         }
     }
 
@@ -545,22 +986,109 @@ impl Player {
     pub fn handle_set_held_item(&self, _server: &Arc<Server>, held: SSetHeldItem) {
         let slot = held.slot;
         if !(0..=8).contains(&slot) {
-            self.kick(TextComponent::text("Invalid held slot"))
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid held slot".to_string(),
+            )))
         }
         self.inventory.lock().set_selected(slot as usize);
     }
 
+    /// Throws the item from the Player's eyes where they look, they can't pick it up again right away.
+    ///
+    /// Returns `None` if the chunk already holds too many entities
+    pub fn drop_item(&self, entity_id: EntityId, item: ItemStack) -> Option<Arc<ItemEntity>> {
+        let entity = &self.entity;
+        let eyes = entity.eye_pos();
+        let dropped = item::drop_item(
+            &entity.world,
+            entity_id,
+            item,
+            Vector3::new(eyes.x, eyes.y - 0.3, eyes.z),
+            item::throw_velocity(entity.yaw.load(), entity.pitch.load()),
+            item::PLAYER_DROP_PICKUP_DELAY,
+        );
+        if dropped.is_none() {
+            log::debug!(
+                "{} dropped an item into a full chunk",
+                self.gameprofile.name
+            );
+        }
+        dropped
+    }
+
+    /// Drops one of the held items, or the whole stack. The held slot is sent again either way,
+    /// the client already took the items out
+    pub fn drop_held_item(
+        &self,
+        entity_id: EntityId,
+        whole_stack: bool,
+    ) -> Option<Arc<ItemEntity>> {
+        let held = self.inventory.lock().held_item().cloned();
+        let dropped = held
+            .filter(|_| self.gamemode.load() != GameMode::Spectator)
+            .and_then(|held| {
+                let count = if whole_stack { held.item_count } else { 1 };
+                self.drop_item(
+                    entity_id,
+                    ItemStack {
+                        item_count: count,
+                        ..held
+                    },
+                )
+            });
+        let mut inventory = self.inventory.lock();
+        let slot = inventory.held_slot();
+        if let Some(dropped) = &dropped {
+            let count = dropped.item.lock().item_count;
+            if let Ok(stack) = inventory.get_slot(slot) {
+                if let Some(item) = stack.as_mut().filter(|item| item.item_count > count) {
+                    item.item_count -= count;
+                } else {
+                    *stack = None;
+                }
+            }
+        }
+        let slot_data = Slot::from(inventory.held_item());
+        let state_id = inventory
+            .state_id
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.client.send_packet(&CSetContainerSlot::new(
+            0,
+            (state_id + 1) as i32,
+            slot,
+            &slot_data,
+        ));
+        dropped
+    }
+
     pub fn handle_set_creative_slot(
         &self,
-        _server: &Arc<Server>,
+        server: &Arc<Server>,
         packet: SSetCreativeSlot,
     ) -> Result<(), InventoryError> {
-        if self.gamemode.load() != GameMode::Creative {
-            return Err(InventoryError::PermissionError);
+        let slot = packet.slot;
+        let item = packet.clicked_item.to_item();
+        let slot_data = Slot::from(item.as_ref());
+        let dropped = item.clone().filter(|_| slot == -1);
+        let mut inventory = self.inventory.lock();
+        if !set_creative_slot(&mut inventory, self.gamemode.load(), slot, item)? {
+            drop(inventory);
+            if let Some(item) = dropped {
+                self.drop_item(server.new_entity_id(), item);
+            }
+            return Ok(());
         }
-        self.inventory
-            .lock()
-            .set_slot(packet.slot as usize, packet.clicked_item.to_item(), false)
+        // Tell the client the slot was accepted, so it stays in sync with us
+        let state_id = inventory
+            .state_id
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.client.send_packet(&CSetContainerSlot::new(
+            0,
+            (state_id + 1) as i32,
+            slot as usize,
+            &slot_data,
+        ));
+        Ok(())
     }
 
     // TODO:
@@ -581,8 +1109,281 @@ impl Player {
             self.open_container.store(None);
         }
         let Some(_window_type) = WindowType::from_u8(packet.window_id) else {
-            self.kick(TextComponent::text("Invalid window ID"));
+            self.kick(DisconnectReason::ProtocolError(Some(
+                "Invalid window ID".to_string(),
+            )));
             return;
         };
     }
 }
+
+/// The chat session the client started, an expired one is dropped so the Player chats unsigned like
+/// in offline mode instead of getting kicked, e.g. when their clock is off
+fn chat_session(session: SPlayerSession, now: i64) -> Option<ChatSession> {
+    (session.expires_at >= now).then(|| ChatSession {
+        session_id: session.session_id,
+        expires_at: session.expires_at,
+        public_key: session.public_key,
+        key_signature: session.key_signature,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::Read,
+        sync::{atomic::Ordering, Arc},
+        time::Duration,
+    };
+
+    use bytes::Bytes;
+    use mio::Token;
+    use pumpkin_core::{math::vector3::Vector3, GameMode};
+    use pumpkin_entity::entity_type::EntityType;
+    use pumpkin_inventory::{player::PlayerInventory, InventoryError};
+    use pumpkin_protocol::{
+        client::play::Animation,
+        server::play::{ActionType, SPlayerSession},
+        ConnectionState,
+    };
+    use pumpkin_world::item::ItemStack;
+    use uuid::Uuid;
+
+    use super::{chat_session, player_attack_damage, set_creative_slot, BASE_ATTACK_DAMAGE};
+    use crate::{
+        entity::{
+            player::{Hand, Player},
+            Entity,
+        },
+        test_helpers::{self, connected_client, player_of, read_packet, world},
+        world::game_rules::GameRule,
+    };
+
+    fn joined_player(gamemode: GameMode) -> Arc<Player> {
+        let player = test_helpers::joined_player(&world("drop"));
+        player.gamemode.store(gamemode);
+        player
+    }
+
+    /// A zombie next to the Player, like `/summon` spawns it
+    fn summon_zombie(player: &Player) -> Arc<Entity> {
+        let world = &player.entity.world;
+        let zombie = Arc::new(Entity::new(
+            10,
+            Uuid::new_v4(),
+            world.clone(),
+            EntityType::Zombie,
+        ));
+        zombie.set_pos(1.5, 64.0, 0.5);
+        assert!(world.spawn_entity(zombie.clone()));
+        zombie
+    }
+
+    #[test]
+    fn test_interact_with_mob() {
+        let player = joined_player(GameMode::Survival);
+        player.entity.set_pos(0.5, 64.0, 0.5);
+        summon_zombie(&player);
+        for action in [
+            ActionType::Attack,
+            ActionType::Interact,
+            ActionType::InteractAt,
+        ] {
+            player.interact_with_entity(10, action);
+        }
+        assert!(!player.client.closed.load(Ordering::Relaxed));
+
+        // Only ids which don't exist kick
+        player.interact_with_entity(11, ActionType::Attack);
+        assert!(player.client.closed.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_attack_mob_without_pvp() {
+        let player = joined_player(GameMode::Survival);
+        player.entity.set_pos(0.5, 64.0, 0.5);
+        let zombie = summon_zombie(&player);
+        let world = &player.entity.world;
+        assert!(world.game_rules.lock().set(GameRule::Pvp, "false"));
+
+        player.interact_with_entity(10, ActionType::Attack);
+        assert_eq!(zombie.health.load(), 20.0 - BASE_ATTACK_DAMAGE);
+        // until it dies
+        while zombie.health.load() > 0.0 {
+            player.interact_with_entity(10, ActionType::Attack);
+        }
+        assert!(world.get_entity(10).is_none());
+        assert!(!player.client.closed.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn test_swing_is_broadcast() {
+        let world = world("swing");
+        let (client, mut swinger_remote) = connected_client(Token(1), ConnectionState::Play);
+        let swinger = player_of(&world, client, 2, "Steve");
+        let (client, mut viewer_remote) = connected_client(Token(2), ConnectionState::Play);
+        let viewer = player_of(&world, client, 3, "Alex");
+        world.add_player(Token(1), swinger.clone());
+        world.add_player(Token(2), viewer);
+
+        swinger.swing_arm(Hand::Off);
+        swinger.swing_arm(Hand::Main);
+        for animation in [Animation::SwingOffhand, Animation::SwingMainArm] {
+            let (id, mut bytebuf) = read_packet(&mut viewer_remote);
+            assert_eq!(id, 0x03);
+            assert_eq!(bytebuf.get_var_int().unwrap().0, 2);
+            assert_eq!(bytebuf.get_u8().unwrap(), animation as u8);
+        }
+        // The swinger animated it already
+        swinger_remote
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .unwrap();
+        assert!(swinger_remote.read(&mut [0; 64]).is_err());
+    }
+
+    #[test]
+    fn test_pvp_disabled_deals_no_damage() {
+        assert_eq!(player_attack_damage(false, false, GameMode::Survival), 0.0);
+        assert_eq!(player_attack_damage(false, true, GameMode::Adventure), 0.0);
+    }
+
+    #[test]
+    fn test_pvp_enabled_deals_damage() {
+        assert_eq!(
+            player_attack_damage(true, true, GameMode::Survival),
+            BASE_ATTACK_DAMAGE
+        );
+        assert_eq!(
+            player_attack_damage(true, false, GameMode::Creative),
+            BASE_ATTACK_DAMAGE
+        );
+        assert_eq!(player_attack_damage(true, true, GameMode::Creative), 0.0);
+    }
+
+    #[test]
+    fn test_creative_set_slot() {
+        let mut inventory = PlayerInventory::new();
+        let item = ItemStack::new(64, 1);
+        assert!(
+            set_creative_slot(&mut inventory, GameMode::Creative, 36, Some(item.clone())).unwrap()
+        );
+        assert_eq!(
+            inventory.get_slot(36).unwrap().as_ref().unwrap().item_count,
+            64
+        );
+
+        assert!(set_creative_slot(&mut inventory, GameMode::Creative, 36, None).unwrap());
+        assert!(inventory.get_slot(36).unwrap().is_none());
+
+        assert!(
+            !set_creative_slot(&mut inventory, GameMode::Creative, -1, Some(item.clone())).unwrap()
+        );
+        assert!(matches!(
+            set_creative_slot(&mut inventory, GameMode::Creative, 46, Some(item)),
+            Err(InventoryError::InvalidSlot)
+        ));
+    }
+
+    #[test]
+    fn test_survival_set_slot_rejected() {
+        let mut inventory = PlayerInventory::new();
+        let item = ItemStack::new(64, 1);
+        assert!(matches!(
+            set_creative_slot(&mut inventory, GameMode::Survival, 36, Some(item)),
+            Err(InventoryError::PermissionError)
+        ));
+        assert!(inventory.get_slot(36).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_drop_held_item() {
+        let player = joined_player(GameMode::Survival);
+        player.entity.set_pos(0.5, 64.0, 0.5);
+        player.entity.set_rotation(0.0, 0.0);
+        player.inventory.lock().add_item(ItemStack::new(5, 1));
+
+        let dropped = player.drop_held_item(10, false).unwrap();
+        assert_eq!(*dropped.item.lock(), ItemStack::new(1, 1));
+        assert_eq!(
+            player.inventory.lock().held_item(),
+            Some(&ItemStack::new(4, 1))
+        );
+        // Thrown from below the eyes towards where the Player looks
+        let entity = &dropped.entity;
+        let pos = entity.pos.load();
+        assert_eq!((pos.x, pos.z), (0.5, 0.5));
+        assert!((pos.y - 65.32).abs() < 1.0E-6);
+        let velocity = entity.velocity.load();
+        assert!((velocity.z - 0.3).abs() < 1.0E-9);
+        assert!((velocity.y - 0.1).abs() < 1.0E-9);
+        assert!(player.entity.world.get_entity(10).is_some());
+    }
+
+    #[test]
+    fn test_drop_held_stack() {
+        let player = joined_player(GameMode::Survival);
+        player.inventory.lock().add_item(ItemStack::new(5, 1));
+
+        let dropped = player.drop_held_item(10, true).unwrap();
+        assert_eq!(dropped.item.lock().item_count, 5);
+        assert!(player.inventory.lock().held_item().is_none());
+        // Nothing left to drop
+        assert!(player.drop_held_item(11, true).is_none());
+        assert!(player.entity.world.get_entity(11).is_none());
+    }
+
+    #[test]
+    fn test_spectators_drop_nothing() {
+        let player = joined_player(GameMode::Spectator);
+        player.inventory.lock().add_item(ItemStack::new(5, 1));
+
+        assert!(player.drop_held_item(10, false).is_none());
+        assert_eq!(player.inventory.lock().held_item().unwrap().item_count, 5);
+    }
+
+    #[test]
+    fn test_moves_wait_for_teleport_confirm() {
+        let player = joined_player(GameMode::Survival);
+        player.teleport(0.5, 64.0, 0.5, 0.0, 0.0);
+
+        // Until the client confirms the teleport no move counts, not even a cheating one
+        assert!(!player.validate_move(Vector3::new(0.6, 64.0, 0.5)));
+        assert!(!player.validate_move(Vector3::new(500.0, 64.0, 0.5)));
+        let pos = player.entity.pos.load();
+        assert_eq!((pos.x, pos.z), (0.5, 0.5));
+        assert!(player.awaiting_teleport.lock().is_some());
+
+        // Like `handle_confirm_teleport`
+        *player.awaiting_teleport.lock() = None;
+        assert!(player.validate_move(Vector3::new(0.6, 64.0, 0.5)));
+        assert!(!player.validate_move(Vector3::new(500.0, 64.0, 0.5)));
+    }
+
+    #[test]
+    fn test_gliding_needs_an_elytra() {
+        let player = joined_player(GameMode::Survival);
+        player.entity.set_pos(0.5, 64.0, 0.5);
+        // The client claims to glide without wearing an elytra
+        player.entity.fall_flying.store(true, Ordering::Relaxed);
+        let to = Vector3::new(15.5, 64.0, 0.5);
+        assert!(!player.validate_move(to));
+        assert_eq!(player.entity.pos.load().x, 0.5);
+
+        *player.awaiting_teleport.lock() = None;
+        *player.inventory.lock().get_slot(6).unwrap() = Some(ItemStack::new(1, 773));
+        assert!(player.validate_move(to));
+    }
+
+    #[test]
+    fn test_expired_chat_session() {
+        let session = |expires_at| SPlayerSession {
+            session_id: Uuid::new_v4(),
+            expires_at,
+            public_key: Bytes::new(),
+            key_signature: Bytes::new(),
+        };
+        assert!(chat_session(session(1000), 999).is_some());
+        assert!(chat_session(session(1000), 1000).is_some());
+        assert!(chat_session(session(1000), 1001).is_none());
+    }
+}
@@ -0,0 +1,174 @@
+use std::io::{self, Read};
+
+use pumpkin_config::SocketConfig;
+
+use super::{interrupted, would_block};
+
+/// After this many reads in a row filled the whole buffer, it doubles in size
+const GROW_AFTER_FULL_READS: u8 = 2;
+
+/// The buffer data from a connection is read into before it is decoded.
+///
+/// Starts at the configured size and grows up to the configured maximum when reads keep filling it
+pub struct ReadBuffer {
+    buf: Vec<u8>,
+    max_size: usize,
+    full_reads: u8,
+}
+
+impl ReadBuffer {
+    pub fn new(size: usize, max_size: usize) -> Self {
+        Self {
+            buf: vec![0; size],
+            max_size: max_size.max(size),
+            full_reads: 0,
+        }
+    }
+
+    pub fn from_config(config: &SocketConfig) -> Self {
+        Self::new(config.read_buffer_size, config.max_read_buffer_size)
+    }
+
+    /// How many bytes a single read can return
+    pub fn size(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Reads until the reader would block, passing every chunk of received data to `received`.
+    ///
+    /// Incomplete packets are fine, the decoder keeps them until the rest arrives.
+    /// Returns true if the other side closed the connection
+    pub fn read_available<R: Read>(
+        &mut self,
+        reader: &mut R,
+        mut received: impl FnMut(&[u8]),
+    ) -> io::Result<bool> {
+        loop {
+            match reader.read(&mut self.buf) {
+                // Reading 0 bytes means the other side has closed the
+                // connection or is done writing, then so are we.
+                Ok(0) => return Ok(true),
+                Ok(n) => {
+                    received(&self.buf[..n]);
+                    self.record_read(n);
+                }
+                // Would block "errors" are the OS's way of saying that the
+                // connection is not actually ready to perform this I/O operation.
+                Err(ref err) if would_block(err) => return Ok(false),
+                Err(ref err) if interrupted(err) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    fn record_read(&mut self, n: usize) {
+        if n < self.buf.len() {
+            self.full_reads = 0;
+            return;
+        }
+        self.full_reads += 1;
+        if self.full_reads >= GROW_AFTER_FULL_READS && self.buf.len() < self.max_size {
+            let size = (self.buf.len() * 2).min(self.max_size);
+            self.buf.resize(size, 0);
+            self.full_reads = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, Read};
+
+    use super::ReadBuffer;
+
+    /// Hands out its data as fast as the buffer allows, then would block
+    struct CountingReader {
+        data: Vec<u8>,
+        position: usize,
+        reads: usize,
+        closed: bool,
+    }
+
+    impl CountingReader {
+        fn new(len: usize) -> Self {
+            Self {
+                data: (0..len).map(|i| i as u8).collect(),
+                position: 0,
+                reads: 0,
+                closed: false,
+            }
+        }
+    }
+
+    impl Read for CountingReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.reads += 1;
+            let remaining = &self.data[self.position..];
+            if remaining.is_empty() {
+                return if self.closed {
+                    Ok(0)
+                } else {
+                    Err(io::ErrorKind::WouldBlock.into())
+                };
+            }
+            let n = remaining.len().min(buf.len());
+            buf[..n].copy_from_slice(&remaining[..n]);
+            self.position += n;
+            Ok(n)
+        }
+    }
+
+    fn read_all(buffer: &mut ReadBuffer, reader: &mut CountingReader) -> Vec<u8> {
+        let mut received = Vec::new();
+        let closed = buffer
+            .read_available(reader, |data| received.extend_from_slice(data))
+            .unwrap();
+        assert_eq!(closed, reader.closed);
+        received
+    }
+
+    #[test]
+    fn large_buffer_needs_fewer_reads() {
+        let mut small_reader = CountingReader::new(256 * 1024);
+        let received = read_all(&mut ReadBuffer::new(4096, 4096), &mut small_reader);
+        assert_eq!(received, small_reader.data);
+
+        let mut large_reader = CountingReader::new(256 * 1024);
+        let received = read_all(
+            &mut ReadBuffer::new(64 * 1024, 64 * 1024),
+            &mut large_reader,
+        );
+        assert_eq!(received, large_reader.data);
+
+        // Every read fills the buffer, plus the one which would block
+        assert_eq!(small_reader.reads, 64 + 1);
+        assert_eq!(large_reader.reads, 4 + 1);
+    }
+
+    #[test]
+    fn grows_up_to_the_maximum() {
+        let mut buffer = ReadBuffer::new(4096, 16 * 1024);
+        let mut reader = CountingReader::new(256 * 1024);
+        let received = read_all(&mut buffer, &mut reader);
+        assert_eq!(received, reader.data);
+        assert_eq!(buffer.size(), 16 * 1024);
+        assert!(reader.reads < 64 + 1);
+    }
+
+    #[test]
+    fn small_reads_dont_grow() {
+        let mut buffer = ReadBuffer::new(4096, 16 * 1024);
+        for _ in 0..10 {
+            read_all(&mut buffer, &mut CountingReader::new(100));
+        }
+        assert_eq!(buffer.size(), 4096);
+    }
+
+    #[test]
+    fn reports_closed_connections() {
+        let mut reader = CountingReader::new(10);
+        reader.closed = true;
+        let received = read_all(&mut ReadBuffer::new(4096, 4096), &mut reader);
+        assert_eq!(received.len(), 10);
+    }
+}
@@ -0,0 +1,211 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+};
+
+use bytes::{Buf, BytesMut};
+use pumpkin_config::{NetworkQueueConfig, QueueFullPolicy};
+use pumpkin_protocol::bytebuf::packet_id::PacketPriority;
+
+use super::{interrupted, would_block};
+
+/// What happens to a packet which should be sent to the Client
+#[derive(Debug, PartialEq, Eq)]
+pub enum Admission {
+    Send,
+    /// The queue is full and the packet is not important enough
+    Drop,
+    /// The queue is full and the packet can't be left out, so the Client is too slow to stay
+    Disconnect,
+}
+
+/// The encoded packets which still have to be written to a Client, in the order they were sent
+pub struct WriteQueue {
+    packets: VecDeque<BytesMut>,
+    queued_bytes: usize,
+    max_bytes: usize,
+    policy: QueueFullPolicy,
+}
+
+impl WriteQueue {
+    pub fn new(max_bytes: usize, policy: QueueFullPolicy) -> Self {
+        Self {
+            packets: VecDeque::new(),
+            queued_bytes: 0,
+            max_bytes,
+            policy,
+        }
+    }
+
+    pub fn from_config(config: &NetworkQueueConfig) -> Self {
+        Self::new(config.max_bytes, config.policy)
+    }
+
+    /// Decides if a packet gets queued, this has to happen before it is encoded,
+    /// an encrypted packet can't be left out afterwards without breaking the ones after it
+    pub fn admit(&self, priority: PacketPriority) -> Admission {
+        if self.queued_bytes < self.max_bytes || priority == PacketPriority::Critical {
+            return Admission::Send;
+        }
+        match (self.policy, priority) {
+            (QueueFullPolicy::DropLowPriority, PacketPriority::Low) => Admission::Drop,
+            _ => Admission::Disconnect,
+        }
+    }
+
+    pub fn push(&mut self, packet: BytesMut) {
+        if packet.is_empty() {
+            return;
+        }
+        self.queued_bytes += packet.len();
+        self.packets.push_back(packet);
+    }
+
+    /// Writes until the writer would block, whatever is left is written once the connection is writable again.
+    ///
+    /// Returns true if everything was written
+    pub fn flush<W: Write>(&mut self, writer: &mut W) -> io::Result<bool> {
+        while let Some(packet) = self.packets.front_mut() {
+            match writer.write(packet) {
+                Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+                Ok(n) => {
+                    self.queued_bytes -= n;
+                    packet.advance(n);
+                    if packet.is_empty() {
+                        self.packets.pop_front();
+                    }
+                }
+                Err(ref err) if would_block(err) => return Ok(false),
+                Err(ref err) if interrupted(err) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, Write};
+
+    use bytes::BytesMut;
+    use pumpkin_config::QueueFullPolicy;
+    use pumpkin_protocol::{
+        bytebuf::packet_id::{Packet, PacketPriority},
+        client::play::{CKeepAlive, CParticle, CPlayDisconnect, CSystemChatMessage},
+    };
+
+    use super::{Admission, WriteQueue};
+
+    /// Takes up to `capacity` bytes, then would block until it is drained
+    #[derive(Default)]
+    struct SlowWriter {
+        written: Vec<u8>,
+        capacity: usize,
+    }
+
+    impl Write for SlowWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = buf.len().min(self.capacity - self.written.len());
+            if n == 0 {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            self.written.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Queues the packet if it is admitted, like `Client::send_packet`
+    fn send(queue: &mut WriteQueue, priority: PacketPriority, data: &[u8]) -> Admission {
+        let admission = queue.admit(priority);
+        if admission == Admission::Send {
+            queue.push(BytesMut::from(data));
+        }
+        admission
+    }
+
+    #[test]
+    fn test_packet_priorities() {
+        assert_eq!(CParticle::PRIORITY, PacketPriority::Low);
+        assert_eq!(CSystemChatMessage::PRIORITY, PacketPriority::Normal);
+        assert_eq!(CKeepAlive::PRIORITY, PacketPriority::Critical);
+        assert_eq!(CPlayDisconnect::PRIORITY, PacketPriority::Critical);
+    }
+
+    #[test]
+    fn test_partial_writes_keep_the_order() {
+        let mut queue = WriteQueue::new(1024, QueueFullPolicy::DropLowPriority);
+        let mut writer = SlowWriter {
+            capacity: 3,
+            ..Default::default()
+        };
+        queue.push(BytesMut::from(&[1, 2][..]));
+        queue.push(BytesMut::from(&[3, 4, 5][..]));
+        assert!(!queue.flush(&mut writer).unwrap());
+        assert_eq!(writer.written, vec![1, 2, 3]);
+
+        writer.capacity = 10;
+        assert!(queue.flush(&mut writer).unwrap());
+        assert_eq!(writer.written, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_full_queue_drops_low_priority() {
+        let mut queue = WriteQueue::new(4, QueueFullPolicy::DropLowPriority);
+        // The Client doesn't read anything
+        let mut writer = SlowWriter::default();
+        assert_eq!(
+            send(&mut queue, PacketPriority::Normal, &[1, 1, 1, 1]),
+            Admission::Send
+        );
+        assert!(!queue.flush(&mut writer).unwrap());
+
+        assert_eq!(send(&mut queue, PacketPriority::Low, &[2]), Admission::Drop);
+        assert_eq!(
+            send(&mut queue, PacketPriority::Critical, &[3, 3]),
+            Admission::Send
+        );
+        assert_eq!(send(&mut queue, PacketPriority::Low, &[4]), Admission::Drop);
+        assert_eq!(
+            send(&mut queue, PacketPriority::Normal, &[5]),
+            Admission::Disconnect
+        );
+
+        // The disconnect is still delivered once the Client reads again, the particles never are
+        writer.capacity = 100;
+        assert!(queue.flush(&mut writer).unwrap());
+        assert_eq!(writer.written, vec![1, 1, 1, 1, 3, 3]);
+    }
+
+    #[test]
+    fn test_full_queue_disconnects() {
+        let mut queue = WriteQueue::new(4, QueueFullPolicy::Disconnect);
+        assert_eq!(
+            send(&mut queue, PacketPriority::Low, &[1, 1, 1, 1]),
+            Admission::Send
+        );
+        assert_eq!(
+            send(&mut queue, PacketPriority::Low, &[2]),
+            Admission::Disconnect
+        );
+        assert_eq!(
+            send(&mut queue, PacketPriority::Normal, &[2]),
+            Admission::Disconnect
+        );
+        assert_eq!(
+            send(&mut queue, PacketPriority::Critical, &[3]),
+            Admission::Send
+        );
+
+        let mut writer = SlowWriter {
+            capacity: 100,
+            ..Default::default()
+        };
+        assert!(queue.flush(&mut writer).unwrap());
+        assert_eq!(writer.written, vec![1, 1, 1, 1, 3]);
+    }
+}
@@ -58,3 +58,10 @@ pub fn parse_arg_player<'a>(
         }
     }
 }
+
+/// Consumes the name of any Player, which doesn't have to be online
+pub fn consume_arg_player_name(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    let valid = s.len() <= 16 && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then(|| s.into())
+}
@@ -0,0 +1,66 @@
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{ConsumedArgs, RawArgs};
+use crate::commands::CommandSender;
+
+/// Consumes one block coordinate, either absolute (`12`) or relative to the sender (`~`, `~-3`)
+pub fn consume_arg_block_coordinate(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    parse_block_coordinate(s, 0).map(|_| s.into())
+}
+
+/// Resolves a coordinate, `origin` is the coordinate `~` stands for
+pub fn parse_block_coordinate(s: &str, origin: i32) -> Option<i32> {
+    match s.strip_prefix('~') {
+        Some("") => Some(origin),
+        Some(offset) => offset.parse::<i32>().ok()?.checked_add(origin),
+        None => s.parse().ok(),
+    }
+}
+
+/// Resolves the block position consumed as three coordinates, relative ones start at the sender.
+///
+/// The console has no position, so `~` stands for 0 there
+pub fn parse_arg_block_position(
+    src: &mut CommandSender,
+    arg_names: [&str; 3],
+    consumed_args: &ConsumedArgs,
+) -> Result<WorldPosition, InvalidTreeError> {
+    let origin = src.as_mut_player().map_or(Vector3::new(0, 0, 0), |player| {
+        player.entity.block_pos.load().0
+    });
+    let [x, y, z] = arg_names.map(|arg_name| {
+        consumed_args
+            .get(arg_name)
+            .ok_or(InvalidConsumptionError(None))
+    });
+    let (x, y, z) = (x?, y?, z?);
+    let coordinate = |s: &String, origin| {
+        parse_block_coordinate(s, origin).ok_or_else(|| InvalidConsumptionError(Some(s.clone())))
+    };
+    Ok(WorldPosition(Vector3::new(
+        coordinate(x, origin.x)?,
+        coordinate(y, origin.y)?,
+        coordinate(z, origin.z)?,
+    )))
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_block_coordinate;
+
+    #[test]
+    fn test_parse_block_coordinate() {
+        assert_eq!(parse_block_coordinate("12", 5), Some(12));
+        assert_eq!(parse_block_coordinate("-64", 5), Some(-64));
+        assert_eq!(parse_block_coordinate("~", 5), Some(5));
+        assert_eq!(parse_block_coordinate("~-3", 5), Some(2));
+        assert_eq!(parse_block_coordinate("~10", -5), Some(5));
+        assert_eq!(parse_block_coordinate("1.5", 5), None);
+        assert_eq!(parse_block_coordinate("~~", 5), None);
+        assert_eq!(parse_block_coordinate("abc", 5), None);
+        assert_eq!(parse_block_coordinate(&format!("~{}", i32::MAX), 1), None);
+    }
+}
@@ -0,0 +1,31 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::target_selector::TargetSelector;
+use crate::commands::tree::{ConsumedArgs, RawArgs};
+use crate::commands::CommandSender;
+use crate::entity::player::Player;
+use crate::server::Server;
+
+/// Consumes a player name or a target selector like `@a[distance=..10]`
+pub fn consume_arg_targets(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    TargetSelector::from_str(s).ok().map(|_| s.into())
+}
+
+/// Resolves the targets against the online players, which may be none
+pub fn parse_arg_targets(
+    src: &mut CommandSender,
+    server: &Arc<Server>,
+    arg_name: &str,
+    consumed_args: &ConsumedArgs,
+) -> Result<Vec<Arc<Player>>, InvalidTreeError> {
+    let s = consumed_args
+        .get(arg_name)
+        .ok_or(InvalidConsumptionError(None))?;
+    let selector =
+        TargetSelector::from_str(s).map_err(|_| InvalidConsumptionError(Some(s.into())))?;
+    Ok(selector.select_players(src, server))
+}
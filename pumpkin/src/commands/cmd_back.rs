@@ -0,0 +1,28 @@
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::CommandTree;
+
+const NAMES: [&str; 1] = ["back"];
+
+const DESCRIPTION: &str = "Teleports you to where you were before your last teleport or death.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).execute(&|sender, _, _| {
+        let Some(player) = sender.as_mut_player() else {
+            sender.send_message(TextComponent::text("Only players can go back"));
+            return Ok(());
+        };
+        let Some(location) = player.last_location.lock().get().cloned() else {
+            player.send_system_message(TextComponent::text("You have no location to return to"));
+            return Ok(());
+        };
+        // This records the current location, so using /back again returns here
+        let message = if location.teleport(player) {
+            "Returned to your previous location"
+        } else {
+            "Your previous location is in another dimension"
+        };
+        player.send_system_message(TextComponent::text(message));
+        Ok(())
+    })
+}
@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::arg_player::consume_arg_player_name;
+use crate::commands::cmd_mute::{consume_arg_duration, parse_duration};
+use crate::commands::cmd_say::sender_name;
+use crate::commands::cmd_tellraw::consume_arg_message;
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::server::bans::BanInfo;
+use crate::server::mutes::unix_now;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["ban"];
+
+const DESCRIPTION: &str = "Bans a player from the server.";
+
+const ARG_PLAYER: &str = "player";
+pub const ARG_DURATION: &str = "duration";
+pub const ARG_REASON: &str = "reason";
+
+/// Builds the ban from the optional duration and reason of the command
+pub fn parse_ban_info(
+    sender: &mut CommandSender,
+    args: &ConsumedArgs,
+) -> Result<BanInfo, InvalidTreeError> {
+    let created = unix_now();
+    let expires = match args.get(ARG_DURATION) {
+        Some(s) => Some(
+            parse_duration(s)
+                .ok_or_else(|| InvalidConsumptionError(Some(s.into())))?
+                .saturating_add(created),
+        ),
+        None => None,
+    };
+    Ok(BanInfo {
+        created,
+        source: sender_name(sender),
+        expires,
+        reason: args.get(ARG_REASON).cloned(),
+    })
+}
+
+fn ban(
+    sender: &mut CommandSender,
+    server: &Arc<Server>,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let name = args.get(ARG_PLAYER).ok_or(InvalidConsumptionError(None))?;
+    let info = parse_ban_info(sender, args)?;
+    // The UUID is only known for online Players, the others are matched by name
+    let (uuid, name) = match server.get_player_by_name(name) {
+        Some(player) => (Some(player.gameprofile.id), player.gameprofile.name.clone()),
+        None => (None, name.clone()),
+    };
+    {
+        let mut bans = server.bans.write();
+        bans.remove_expired(info.created);
+        bans.ban_player(uuid, &name, info);
+        if let Err(err) = bans.save() {
+            log::error!("Couldn't save the ban list: {}", err);
+        }
+    }
+    sender.send_message(TextComponent::text(&format!("Banned {}", name)));
+    server.kick_banned();
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 3).with_child(
+            argument(ARG_PLAYER, consume_arg_player_name)
+                .execute(&ban)
+                .with_child(
+                    argument(ARG_DURATION, consume_arg_duration)
+                        .execute(&ban)
+                        .with_child(argument(ARG_REASON, consume_arg_message).execute(&ban)),
+                )
+                .with_child(argument(ARG_REASON, consume_arg_message).execute(&ban)),
+        ),
+    )
+}
@@ -0,0 +1,136 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::cmd_ban::{parse_ban_info, ARG_DURATION, ARG_REASON};
+use crate::commands::cmd_mute::consume_arg_duration;
+use crate::commands::cmd_tellraw::consume_arg_message;
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::server::Server;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["ban-ip"];
+
+const DESCRIPTION: &str = "Bans an IP address from the server.";
+
+const ARG_TARGET: &str = "target";
+
+/// Consumes an IP address or the name of an online Player
+fn consume_arg_target(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+/// The IP itself or the one the online Player named `target` connected from
+fn target_ip(target: &str, worlds: &[Arc<World>]) -> Option<IpAddr> {
+    if let Ok(ip) = target.parse() {
+        return Some(ip);
+    }
+    worlds
+        .iter()
+        .find_map(|world| world.get_player_by_name(target))
+        .map(|player| player.client.address.lock().ip())
+}
+
+fn ban_ip(
+    sender: &mut CommandSender,
+    server: &Arc<Server>,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let target = args.get(ARG_TARGET).ok_or(InvalidConsumptionError(None))?;
+    let Some(ip) = target_ip(target, &server.worlds) else {
+        sender.send_message(
+            TextComponent::text("Invalid IP address or unknown player")
+                .color_named(NamedColor::Red),
+        );
+        return Ok(());
+    };
+    let info = parse_ban_info(sender, args)?;
+    {
+        let mut bans = server.bans.write();
+        bans.remove_expired(info.created);
+        bans.ban_ip(ip, info);
+        if let Err(err) = bans.save() {
+            log::error!("Couldn't save the ban list: {}", err);
+        }
+    }
+    let kicked = server.kick_banned();
+    sender.send_message(TextComponent::text(&format!(
+        "Banned IP {}, {} player(s) were kicked",
+        ip, kicked
+    )));
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 3).with_child(
+            argument(ARG_TARGET, consume_arg_target)
+                .execute(&ban_ip)
+                .with_child(
+                    argument(ARG_DURATION, consume_arg_duration)
+                        .execute(&ban_ip)
+                        .with_child(argument(ARG_REASON, consume_arg_message).execute(&ban_ip)),
+                )
+                .with_child(argument(ARG_REASON, consume_arg_message).execute(&ban_ip)),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::IpAddr;
+
+    use mio::Token;
+    use pumpkin_protocol::ConnectionState;
+
+    use super::target_ip;
+    use crate::{
+        server::bans::{BanInfo, BanList},
+        test_helpers::{accepted_client, player_of, world},
+    };
+
+    #[test]
+    fn test_ban_player_ip() {
+        let world = world("ban-ip");
+        let steve_ip: IpAddr = [127, 0, 0, 2].into();
+        let alex_ip: IpAddr = [127, 0, 0, 3].into();
+        let (steve_client, _steve_remote) = accepted_client(Token(1), steve_ip);
+        let (alex_client, _alex_remote) = accepted_client(Token(2), alex_ip);
+        for (token, client, entity_id, name) in
+            [(1, steve_client, 2, "Steve"), (2, alex_client, 3, "Alex")]
+        {
+            client.connection_state.store(ConnectionState::Play);
+            let player = player_of(&world, client, entity_id, name);
+            world.add_player(Token(token), player);
+        }
+
+        let ip = target_ip("Steve", &[world.clone()]).unwrap();
+        assert_eq!(ip, steve_ip);
+        let mut bans = BanList::default();
+        bans.ban_ip(
+            ip,
+            BanInfo {
+                created: 0,
+                source: "Server".to_string(),
+                expires: None,
+                reason: None,
+            },
+        );
+        let banned = |name: &str| {
+            let player = world.get_player_by_name(name).unwrap();
+            let ip = player.client.address.lock().ip();
+            bans.find_ban(&player.gameprofile.id, name, &ip, 0)
+                .is_some()
+        };
+        assert!(banned("Steve"));
+        assert!(!banned("Alex"));
+
+        assert_eq!(target_ip("10.0.0.1", &[]), Some([10, 0, 0, 1].into()));
+        assert_eq!(target_ip("Herobrine", &[world]), None);
+    }
+}
@@ -0,0 +1,123 @@
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::{literal, require};
+use crate::commands::CommandSender;
+use crate::server::bans::{format_timestamp, BanInfo, BanList};
+use crate::server::mutes::unix_now;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["banlist"];
+
+const DESCRIPTION: &str = "Lists the banned players and IP addresses.";
+
+/// One line per active ban, with who banned, why and until when
+fn ban_lines(bans: &BanList, players: bool, ips: bool, now: u64) -> Vec<String> {
+    let mut entries = Vec::new();
+    if players {
+        entries.extend(
+            bans.players()
+                .iter()
+                .filter(|ban| !ban.info.is_expired(now))
+                .map(|ban| (ban.name.clone(), &ban.info)),
+        );
+    }
+    if ips {
+        entries.extend(
+            bans.ips()
+                .iter()
+                .filter(|ban| !ban.info.is_expired(now))
+                .map(|ban| (ban.ip.to_string(), &ban.info)),
+        );
+    }
+    if entries.is_empty() {
+        return vec!["There are no bans".to_string()];
+    }
+
+    let mut lines = vec![format!("There are {} ban(s):", entries.len())];
+    lines.extend(
+        entries
+            .into_iter()
+            .map(|(name, info)| ban_line(&name, info)),
+    );
+    lines
+}
+
+fn ban_line(name: &str, info: &BanInfo) -> String {
+    let mut line = format!("{} was banned by {}", name, info.source);
+    if let Some(reason) = &info.reason {
+        line.push_str(&format!(": {}", reason));
+    }
+    if let Some(expires) = info.expires {
+        line.push_str(&format!(" (until {})", format_timestamp(expires)));
+    }
+    line
+}
+
+fn list(sender: &mut CommandSender, server: &Server, players: bool, ips: bool) {
+    let lines = ban_lines(&server.bans.read(), players, ips, unix_now());
+    for line in lines {
+        sender.send_message(TextComponent::text(&line));
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 3)
+            .execute(&|sender, server, _| {
+                list(sender, server, true, true);
+                Ok(())
+            })
+            .with_child(literal("players").execute(&|sender, server, _| {
+                list(sender, server, true, false);
+                Ok(())
+            }))
+            .with_child(literal("ips").execute(&|sender, server, _| {
+                list(sender, server, false, true);
+                Ok(())
+            })),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::ban_lines;
+    use crate::server::bans::{BanInfo, BanList};
+
+    fn info(expires: Option<u64>, reason: Option<&str>) -> BanInfo {
+        BanInfo {
+            created: 0,
+            source: "Admin".to_string(),
+            expires,
+            reason: reason.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_ban_lines() {
+        let mut bans = BanList::default();
+        assert_eq!(ban_lines(&bans, true, true, 0), vec!["There are no bans"]);
+
+        bans.ban_player(None, "Steve", info(None, Some("Griefing")));
+        bans.ban_player(None, "Alex", info(Some(100), None));
+        bans.ban_ip(
+            "10.0.0.1".parse().unwrap(),
+            info(Some(1_714_571_100), Some("Bots")),
+        );
+        assert_eq!(
+            ban_lines(&bans, true, true, 50),
+            vec![
+                "There are 3 ban(s):",
+                "Steve was banned by Admin: Griefing",
+                "Alex was banned by Admin (until 1970-01-01 00:01 UTC)",
+                "10.0.0.1 was banned by Admin: Bots (until 2024-05-01 13:45 UTC)",
+            ]
+        );
+        // Expired bans are left out
+        assert_eq!(
+            ban_lines(&bans, true, false, 100),
+            vec!["There are 1 ban(s):", "Steve was banned by Admin: Griefing"]
+        );
+        assert_eq!(ban_lines(&bans, false, true, 50).len(), 2);
+    }
+}
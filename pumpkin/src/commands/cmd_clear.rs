@@ -0,0 +1,278 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_inventory::player::PlayerInventory;
+use pumpkin_protocol::client::play::CSetContainerSlot;
+use pumpkin_protocol::slot::Slot;
+use pumpkin_world::item::get_item_protocol_id;
+
+use crate::commands::arg_targets::{consume_arg_targets, parse_arg_targets};
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::entity::player::Player;
+
+const NAMES: [&str; 1] = ["clear"];
+
+const DESCRIPTION: &str = "Clears items from player inventory.";
+
+const ARG_TARGETS: &str = "targets";
+const ARG_ITEM: &str = "item";
+const ARG_MAX_COUNT: &str = "maxCount";
+
+/// Item ids may leave out the `minecraft:` namespace
+fn item_protocol_id(s: &str) -> Option<u32> {
+    if s.contains(':') {
+        get_item_protocol_id(s)
+    } else {
+        get_item_protocol_id(&format!("minecraft:{s}"))
+    }
+}
+
+pub fn consume_arg_item(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    item_protocol_id(s).map(|_| s.into())
+}
+
+pub fn parse_arg_item(consumed_args: &ConsumedArgs) -> Result<u32, InvalidTreeError> {
+    let s = consumed_args
+        .get(ARG_ITEM)
+        .ok_or(InvalidConsumptionError(None))?;
+    item_protocol_id(s).ok_or_else(|| InvalidConsumptionError(Some(s.into())))
+}
+
+pub fn consume_arg_max_count(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<u32>().ok().map(|_| s.into())
+}
+
+/// Like vanilla a max count of 0 only counts the matching items, without a max count all of them are removed
+pub fn parse_arg_max_count(consumed_args: &ConsumedArgs) -> Result<Option<u32>, InvalidTreeError> {
+    let Some(s) = consumed_args.get(ARG_MAX_COUNT) else {
+        return Ok(None);
+    };
+    s.parse()
+        .map(Some)
+        .map_err(|_| InvalidConsumptionError(Some(s.into())))
+}
+
+/// Removes up to `max_count` items with the id, or any items when it's `None`.
+///
+/// Returns how many items matched, which are all removed unless `max_count` stopped earlier, and the changed slots
+fn clear_inventory(
+    inventory: &mut PlayerInventory,
+    item_id: Option<u32>,
+    max_count: Option<u32>,
+) -> (u32, Vec<usize>) {
+    let mut remaining = max_count.unwrap_or(u32::MAX);
+    let mut found = 0;
+    let mut changed = Vec::new();
+    for (slot, stack) in inventory.slots_mut().into_iter().enumerate() {
+        let Some(item) = stack.as_mut() else {
+            continue;
+        };
+        if item_id.is_some_and(|item_id| item_id != item.item_id) {
+            continue;
+        }
+        if max_count == Some(0) {
+            found += u32::from(item.item_count);
+            continue;
+        }
+        let removed = u32::from(item.item_count).min(remaining);
+        if removed == 0 {
+            break;
+        }
+        remaining -= removed;
+        found += removed;
+        if removed == u32::from(item.item_count) {
+            *stack = None;
+        } else {
+            // Can't underflow as less than the whole stack was removed
+            item.item_count -= removed as u8;
+        }
+        changed.push(slot);
+    }
+    (found, changed)
+}
+
+/// Clears the Player's inventory and tells their client about the changed slots
+fn clear_player(player: &Player, item_id: Option<u32>, max_count: Option<u32>) -> u32 {
+    let mut inventory = player.inventory.lock();
+    let (found, changed) = clear_inventory(&mut inventory, item_id, max_count);
+    for slot in changed {
+        let slot_data = Slot::from(inventory.slots()[slot]);
+        let state_id = inventory
+            .state_id
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        player.client.send_packet(&CSetContainerSlot::new(
+            0,
+            (state_id + 1) as i32,
+            slot,
+            &slot_data,
+        ));
+    }
+    found
+}
+
+/// The feedback for clearing `found` items from the target, an error if nothing matched
+fn report(found: u32, target: &str, only_count: bool) -> Result<String, String> {
+    match (found, only_count) {
+        (0, _) => Err(format!("No items were found on {}", target)),
+        (found, true) => Ok(format!("Found {} matching item(s) on {}", found, target)),
+        (found, false) => Ok(format!("Removed {} item(s) from {}", found, target)),
+    }
+}
+
+fn clear(
+    sender: &mut CommandSender,
+    targets: &[Arc<Player>],
+    item_id: Option<u32>,
+    max_count: Option<u32>,
+) {
+    let target = match targets {
+        [] => {
+            sender.send_message(
+                TextComponent::text("No player was found").color_named(NamedColor::Red),
+            );
+            return;
+        }
+        [player] => format!("player {}", player.gameprofile.name),
+        _ => format!("{} players", targets.len()),
+    };
+    let found = targets
+        .iter()
+        .map(|player| clear_player(player, item_id, max_count))
+        .sum();
+    match report(found, &target, max_count == Some(0)) {
+        Ok(message) => sender.send_message(TextComponent::text(&message)),
+        Err(message) => {
+            sender.send_message(TextComponent::text(&message).color_named(NamedColor::Red))
+        }
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .execute(&|sender, server, _| {
+                let Some(player) = sender
+                    .as_mut_player()
+                    .and_then(|player| server.get_player_by_uuid(&player.gameprofile.id))
+                else {
+                    sender.send_message(
+                        TextComponent::text("The console has to give a player")
+                            .color_named(NamedColor::Red),
+                    );
+                    return Ok(());
+                };
+                clear(sender, &[player], None, None);
+                Ok(())
+            })
+            .with_child(
+                argument(ARG_TARGETS, consume_arg_targets)
+                    .execute(&|sender, server, args| {
+                        let targets = parse_arg_targets(sender, server, ARG_TARGETS, args)?;
+                        clear(sender, &targets, None, None);
+                        Ok(())
+                    })
+                    .with_child(
+                        argument(ARG_ITEM, consume_arg_item)
+                            .execute(&|sender, server, args| {
+                                let targets = parse_arg_targets(sender, server, ARG_TARGETS, args)?;
+                                let item_id = parse_arg_item(args)?;
+                                clear(sender, &targets, Some(item_id), None);
+                                Ok(())
+                            })
+                            .with_child(argument(ARG_MAX_COUNT, consume_arg_max_count).execute(
+                                &|sender, server, args| {
+                                    let targets =
+                                        parse_arg_targets(sender, server, ARG_TARGETS, args)?;
+                                    let item_id = parse_arg_item(args)?;
+                                    let max_count = parse_arg_max_count(args)?;
+                                    clear(sender, &targets, Some(item_id), max_count);
+                                    Ok(())
+                                },
+                            )),
+                    ),
+            ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_inventory::player::PlayerInventory;
+    use pumpkin_world::item::ItemStack;
+
+    use super::{clear_inventory, item_protocol_id, report};
+
+    fn inventory() -> (PlayerInventory, u32, u32) {
+        let bread = item_protocol_id("bread").unwrap();
+        let stone = item_protocol_id("minecraft:stone").unwrap();
+        let mut inventory = PlayerInventory::new();
+        inventory.add_item(ItemStack::new(8, bread));
+        inventory.add_item(ItemStack::new(64, stone));
+        inventory.add_item(ItemStack::new(5, bread));
+        (inventory, bread, stone)
+    }
+
+    #[test]
+    fn test_clear_all() {
+        let (mut inventory, _, _) = inventory();
+        let (found, changed) = clear_inventory(&mut inventory, None, None);
+        assert_eq!(found, 77);
+        // The hotbar starts at slot 36
+        assert_eq!(changed, vec![36, 37, 38]);
+        assert!(inventory.slots().iter().all(Option::is_none));
+
+        let (found, changed) = clear_inventory(&mut inventory, None, None);
+        assert_eq!((found, changed.len()), (0, 0));
+    }
+
+    #[test]
+    fn test_clear_item() {
+        let (mut inventory, bread, stone) = inventory();
+        assert_eq!(clear_inventory(&mut inventory, Some(bread), None).0, 13);
+        assert!(inventory.get_slot(36).unwrap().is_none());
+        assert_eq!(
+            inventory.get_slot(37).unwrap().as_ref(),
+            Some(&ItemStack::new(64, stone))
+        );
+
+        // Only part of a stack
+        let (found, changed) = clear_inventory(&mut inventory, Some(stone), Some(10));
+        assert_eq!((found, changed), (10, vec![37]));
+        assert_eq!(
+            inventory.get_slot(37).unwrap().as_ref().unwrap().item_count,
+            54
+        );
+    }
+
+    #[test]
+    fn test_count_only() {
+        let (mut inventory, bread, _) = inventory();
+        let (found, changed) = clear_inventory(&mut inventory, Some(bread), Some(0));
+        assert_eq!((found, changed.len()), (13, 0));
+        assert_eq!(
+            inventory.get_slot(36).unwrap().as_ref().unwrap().item_count,
+            8
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        assert_eq!(
+            report(13, "player Steve", false),
+            Ok("Removed 13 item(s) from player Steve".to_string())
+        );
+        assert_eq!(
+            report(13, "2 players", true),
+            Ok("Found 13 matching item(s) on 2 players".to_string())
+        );
+        assert_eq!(
+            report(0, "player Steve", false),
+            Err("No items were found on player Steve".to_string())
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use pumpkin_config::{editor::get_option, ADVANCED_CONFIG, LIVE_CONFIG};
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::cmd_tellraw::consume_arg_message;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, RawArgs};
+use crate::commands::tree_builder::{argument, literal, require};
+use crate::commands::CommandSender;
+
+const NAMES: [&str; 1] = ["config"];
+
+const DESCRIPTION: &str = "Shows or changes options of features.toml while the server runs.";
+
+const ARG_KEY: &str = "key";
+const ARG_VALUE: &str = "value";
+
+fn consume_arg_key(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(str::to_string)
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 4)
+            .with_child(
+                literal("get").with_child(argument(ARG_KEY, consume_arg_key).execute(
+                    &|sender, _, args| {
+                        let key = args.get(ARG_KEY).ok_or(InvalidConsumptionError(None))?;
+                        match get_option(&*LIVE_CONFIG.load(), key) {
+                            Ok(value) => sender
+                                .send_message(TextComponent::text(&format!("{} = {}", key, value))),
+                            Err(err) => sender.send_message(
+                                TextComponent::text(&err.to_string()).color_named(NamedColor::Red),
+                            ),
+                        }
+                        Ok(())
+                    },
+                )),
+            )
+            .with_child(
+                literal("set").with_child(argument(ARG_KEY, consume_arg_key).with_child(
+                    argument(ARG_VALUE, consume_arg_message).execute(&|sender, _, args| {
+                        let key = args.get(ARG_KEY).ok_or(InvalidConsumptionError(None))?;
+                        let value = args.get(ARG_VALUE).ok_or(InvalidConsumptionError(None))?;
+                        let persist = ADVANCED_CONFIG.commands.persist_config_changes;
+                        match LIVE_CONFIG.set(key, value, persist) {
+                            Ok(value) if persist => sender.send_message(TextComponent::text(
+                                &format!("Set {} = {} and saved it to features.toml", key, value),
+                            )),
+                            Ok(value) => sender.send_message(TextComponent::text(&format!(
+                                "Set {} = {} until the server restarts",
+                                key, value
+                            ))),
+                            Err(err) => sender.send_message(
+                                TextComponent::text(&err.to_string()).color_named(NamedColor::Red),
+                            ),
+                        }
+                        Ok(())
+                    }),
+                )),
+            ),
+    )
+}
@@ -0,0 +1,189 @@
+use std::{fs::File, io::BufReader};
+
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::math::vector2::Vector2;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use pumpkin_protocol::ConnectionState;
+use pumpkin_world::chunk::ChunkData;
+
+use crate::client::packet_capture::{read_capture, Direction};
+use crate::commands::arg_position::{consume_arg_block_coordinate, parse_block_coordinate};
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, literal, require};
+use crate::commands::CommandSender;
+use crate::world::{chunk_packet_size, World};
+
+const NAMES: [&str; 1] = ["debug"];
+
+const DESCRIPTION: &str = "Shows internal state for debugging.";
+
+const ARG_X: &str = "x";
+const ARG_Z: &str = "z";
+const ARG_CONNECTION: &str = "connection";
+
+/// Describes a loaded chunk, one line per property
+fn describe_chunk(chunk: &ChunkData) -> Vec<String> {
+    let (sections, non_empty_sections) = {
+        let blocks = chunk.blocks.read();
+        (blocks.iter_subchunks().count(), blocks.non_empty_sections())
+    };
+    vec![
+        format!("Chunk {} {}: loaded", chunk.position.x, chunk.position.z),
+        format!("Sections: {} ({} not empty)", sections, non_empty_sections),
+        format!("Dirty: {}", chunk.is_dirty()),
+        format!("Packet size: {}B", chunk_packet_size(chunk)),
+    ]
+}
+
+fn report_chunk(sender: &mut CommandSender, world: &World, at: Vector2<i32>) {
+    match world.get_loaded_chunk(at) {
+        Some(chunk) => {
+            for line in describe_chunk(&chunk) {
+                sender.send_message(TextComponent::text(&line));
+            }
+        }
+        None => sender.send_message(
+            TextComponent::text(&format!("Chunk {} {}: not loaded", at.x, at.z))
+                .color_named(NamedColor::Red),
+        ),
+    }
+}
+
+/// Chunk coordinates, `~` stands for the chunk the sender is in
+fn parse_chunk_position(
+    sender: &mut CommandSender,
+    args: &ConsumedArgs,
+) -> Result<Vector2<i32>, InvalidTreeError> {
+    let origin = sender
+        .as_mut_player()
+        .map_or(Vector2::new(0, 0), |player| player.entity.chunk_pos.load());
+    let coordinate = |name, origin| {
+        let s: &String = args.get(name).ok_or(InvalidConsumptionError(None))?;
+        parse_block_coordinate(s, origin).ok_or_else(|| InvalidConsumptionError(Some(s.clone())))
+    };
+    Ok(Vector2::new(
+        coordinate(ARG_X, origin.x)?,
+        coordinate(ARG_Z, origin.z)?,
+    ))
+}
+
+fn consume_arg_connection(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<usize>().ok().map(|_| s.into())
+}
+
+/// Replays the inbound Play packets one connection sent in the packet capture as if the sender sent them
+fn replay_capture(sender: &mut CommandSender, connection: usize) {
+    let path = &ADVANCED_CONFIG.packet_capture.path;
+    let captured = match File::open(path).and_then(|file| read_capture(BufReader::new(file))) {
+        Ok(captured) => captured,
+        Err(err) => {
+            sender.send_message(
+                TextComponent::text(&format!(
+                    "Couldn't read the packet capture {}: {}",
+                    path, err
+                ))
+                .color_named(NamedColor::Red),
+            );
+            return;
+        }
+    };
+    let Some(player) = sender.as_mut_player() else {
+        return;
+    };
+    let replayed = player.client.replay(captured.iter().filter(|packet| {
+        packet.connection == connection
+            && packet.direction == Direction::Inbound
+            && packet.state == ConnectionState::Play
+    }));
+    sender.send_message(TextComponent::text(&format!(
+        "Replaying {} packets of connection {}",
+        replayed, connection
+    )));
+}
+
+fn parse_arg_connection(args: &ConsumedArgs) -> Result<usize, InvalidTreeError> {
+    let s: &String = args
+        .get(ARG_CONNECTION)
+        .ok_or(InvalidConsumptionError(None))?;
+    s.parse()
+        .map_err(|_| InvalidConsumptionError(Some(s.clone())))
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .with_child(
+                literal("chunk")
+                    .with_child(
+                        require(&|sender| sender.is_player()).execute(&|sender, _, _| {
+                            let player = sender.as_mut_player().unwrap();
+                            let at = player.entity.chunk_pos.load();
+                            let world = player.entity.world.clone();
+                            report_chunk(sender, &world, at);
+                            Ok(())
+                        }),
+                    )
+                    .with_child(argument(ARG_X, consume_arg_block_coordinate).with_child(
+                        argument(ARG_Z, consume_arg_block_coordinate).execute(
+                            &|sender, server, args| {
+                                let at = parse_chunk_position(sender, args)?;
+                                let world = match sender.as_mut_player() {
+                                    Some(player) => player.entity.world.clone(),
+                                    None => server.worlds[0].clone(),
+                                };
+                                report_chunk(sender, &world, at);
+                                Ok(())
+                            },
+                        ),
+                    )),
+            )
+            // Replaying injects packets, so it needs the highest level
+            .with_child(literal("replay").with_child(
+                require(&|sender| sender.permission_lvl() >= 4 && sender.is_player()).with_child(
+                    argument(ARG_CONNECTION, consume_arg_connection).execute(&|sender, _, args| {
+                        let connection = parse_arg_connection(args)?;
+                        replay_capture(sender, connection);
+                        Ok(())
+                    }),
+                ),
+            )),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::vector2::Vector2;
+    use pumpkin_world::{
+        block::BlockState,
+        chunk::{ChunkBlocks, ChunkData},
+        coordinates::ChunkRelativeBlockCoordinates,
+    };
+
+    use super::describe_chunk;
+
+    #[test]
+    fn reports_sections() {
+        let stone = BlockState::new("minecraft:stone", None).unwrap().into();
+        let mut blocks = ChunkBlocks::default();
+        // Two blocks in the lowest section, one in the section from y 64 to 79
+        for (x, y) in [(0, -64), (5, -60), (3, 70)] {
+            blocks.set_block(
+                ChunkRelativeBlockCoordinates {
+                    x: (x as u8).into(),
+                    y: (y as i16).into(),
+                    z: 0u8.into(),
+                },
+                stone,
+            );
+        }
+        let chunk = ChunkData::new(blocks, Vector2::new(3, -2));
+
+        let lines = describe_chunk(&chunk);
+        assert_eq!(lines[0], "Chunk 3 -2: loaded");
+        assert_eq!(lines[1], "Sections: 24 (2 not empty)");
+        assert_eq!(lines[2], "Dirty: false");
+    }
+}
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::cmd_home::{consume_arg_home, parse_arg_home, ARG_HOME};
+use crate::commands::tree::{CommandTree, ConsumedArgs};
+use crate::commands::tree_builder::argument;
+use crate::commands::CommandSender;
+use crate::server::Server;
+use crate::world::homes::Homes;
+
+const NAMES: [&str; 1] = ["delhome"];
+
+const DESCRIPTION: &str = "Deletes one of your homes.";
+
+fn delete_home(sender: &mut CommandSender, server: &Arc<Server>, args: &ConsumedArgs) {
+    let Some(player) = sender.as_mut_player() else {
+        sender.send_message(TextComponent::text("Only players can have homes"));
+        return;
+    };
+    let name = parse_arg_home(args);
+    let world_folder = Homes::folder(server);
+    let uuid = &player.gameprofile.id;
+    let result = Homes::load(&world_folder, uuid).and_then(|mut homes| {
+        let removed = homes.remove(name).is_some();
+        if removed {
+            homes.save(&world_folder, uuid)?;
+        }
+        Ok(removed)
+    });
+    let message = match result {
+        Ok(true) => format!("Deleted home {}", name),
+        Ok(false) => format!("There is no home called {}", name),
+        Err(err) => {
+            log::error!(
+                "Couldn't save homes of {}: {}",
+                player.gameprofile.name,
+                err
+            );
+            "Couldn't delete your home".to_string()
+        }
+    };
+    player.send_system_message(TextComponent::text(&message));
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .execute(&|sender, server, args| {
+            delete_home(sender, server, args);
+            Ok(())
+        })
+        .with_child(
+            argument(ARG_HOME, consume_arg_home).execute(&|sender, server, args| {
+                delete_home(sender, server, args);
+                Ok(())
+            }),
+        )
+}
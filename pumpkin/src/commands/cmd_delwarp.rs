@@ -0,0 +1,32 @@
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::cmd_warp::{consume_arg_warp, parse_arg_warp, ARG_WARP};
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::{argument, require};
+
+const NAMES: [&str; 1] = ["delwarp"];
+
+const DESCRIPTION: &str = "Deletes a warp.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_WARP, consume_arg_warp).execute(&|sender, server, args| {
+                let name = parse_arg_warp(args)?;
+                let mut warps = server.warps.write();
+                if warps.remove(name).is_none() {
+                    sender.send_message(TextComponent::text(&format!(
+                        "There is no warp called {}",
+                        name
+                    )));
+                    return Ok(());
+                }
+                if let Err(err) = warps.save() {
+                    log::error!("Couldn't save warps: {}", err);
+                }
+                sender.send_message(TextComponent::text(&format!("Deleted warp {}", name)));
+                Ok(())
+            }),
+        ),
+    )
+}
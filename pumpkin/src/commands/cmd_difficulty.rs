@@ -0,0 +1,68 @@
+use std::str::FromStr;
+
+use pumpkin_core::text::TextComponent;
+use pumpkin_core::Difficulty;
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+
+const NAMES: [&str; 1] = ["difficulty"];
+
+const DESCRIPTION: &str = "Sets the difficulty level.";
+
+const ARG_DIFFICULTY: &str = "difficulty";
+
+pub fn consume_arg_difficulty(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    Difficulty::from_str(s).ok().map(|_| s.into())
+}
+
+pub fn parse_arg_difficulty(consumed_args: &ConsumedArgs) -> Result<Difficulty, InvalidTreeError> {
+    let s = consumed_args
+        .get(ARG_DIFFICULTY)
+        .ok_or(InvalidConsumptionError(None))?;
+    Difficulty::from_str(s).map_err(|_| InvalidConsumptionError(Some(s.into())))
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .execute(&|sender, server, _| {
+            let world = match sender.as_mut_player() {
+                Some(player) => player.entity.world.clone(),
+                None => server.worlds[0].clone(),
+            };
+            let difficulty = world.difficulty.lock().difficulty;
+            sender.send_message(TextComponent::text(&format!(
+                "The difficulty is {:?}",
+                difficulty
+            )));
+            Ok(())
+        })
+        .with_child(require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_DIFFICULTY, consume_arg_difficulty).execute(&|sender, server, args| {
+                let difficulty = parse_arg_difficulty(args)?;
+
+                let world = match sender.as_mut_player() {
+                    Some(player) => player.entity.world.clone(),
+                    None => server.worlds[0].clone(),
+                };
+                if world.difficulty.lock().difficulty == difficulty {
+                    sender.send_message(TextComponent::text(&format!(
+                        "The difficulty did not change; it is already set to {:?}",
+                        difficulty
+                    )));
+                    return Ok(());
+                }
+                // Commands may always change the difficulty, even when it is locked
+                world.set_difficulty(difficulty, true);
+                sender.send_message(TextComponent::text(&format!(
+                    "The difficulty has been set to {:?}",
+                    difficulty
+                )));
+                Ok(())
+            }),
+        ))
+}
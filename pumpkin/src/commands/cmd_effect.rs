@@ -0,0 +1,162 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::TextComponent;
+use pumpkin_entity::effect::{EffectType, StatusEffect, INFINITE_DURATION};
+
+use crate::commands::arg_player::{consume_arg_player, parse_arg_player};
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, literal, require};
+use crate::commands::CommandSender;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["effect"];
+
+const DESCRIPTION: &str = "Add or remove status effects.";
+
+const ARG_TARGET: &str = "target";
+const ARG_EFFECT: &str = "effect";
+const ARG_SECONDS: &str = "seconds";
+const ARG_AMPLIFIER: &str = "amplifier";
+
+/// Vanilla applies an effect for 30 seconds when no duration is given
+const DEFAULT_SECONDS: i32 = 30;
+const MAX_SECONDS: i32 = 1_000_000;
+
+pub fn consume_arg_effect(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<EffectType>().ok().map(|_| s.into())
+}
+
+pub fn parse_arg_effect(consumed_args: &ConsumedArgs) -> Result<EffectType, InvalidTreeError> {
+    let s = consumed_args
+        .get(ARG_EFFECT)
+        .ok_or(InvalidConsumptionError(None))?;
+    s.parse()
+        .map_err(|_| InvalidConsumptionError(Some(s.into())))
+}
+
+pub fn consume_arg_seconds(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    if s == "infinite" {
+        return Some(s.into());
+    }
+    match s.parse::<i32>() {
+        Ok(seconds) if (1..=MAX_SECONDS).contains(&seconds) => Some(s.into()),
+        _ => None,
+    }
+}
+
+/// Returns the duration in ticks
+pub fn parse_arg_seconds(consumed_args: &ConsumedArgs) -> Result<i32, InvalidTreeError> {
+    let Some(s) = consumed_args.get(ARG_SECONDS) else {
+        return Ok(DEFAULT_SECONDS * 20);
+    };
+    if s == "infinite" {
+        return Ok(INFINITE_DURATION);
+    }
+    s.parse::<i32>()
+        .map(|seconds| seconds * 20)
+        .map_err(|_| InvalidConsumptionError(Some(s.into())))
+}
+
+pub fn consume_arg_amplifier(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<u8>().ok().map(|_| s.into())
+}
+
+pub fn parse_arg_amplifier(consumed_args: &ConsumedArgs) -> Result<u8, InvalidTreeError> {
+    let Some(s) = consumed_args.get(ARG_AMPLIFIER) else {
+        return Ok(0);
+    };
+    s.parse()
+        .map_err(|_| InvalidConsumptionError(Some(s.into())))
+}
+
+fn give(
+    sender: &mut CommandSender,
+    server: &Arc<Server>,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let effect_type = parse_arg_effect(args)?;
+    let duration = parse_arg_seconds(args)?;
+    let amplifier = parse_arg_amplifier(args)?;
+    let target = parse_arg_player(sender, server, ARG_TARGET, args)?;
+
+    target.add_effect(StatusEffect::new(effect_type, duration, amplifier));
+    let message = format!(
+        "Applied effect {:?} to {}",
+        effect_type, target.gameprofile.name
+    );
+
+    sender.send_message(TextComponent::text(&message));
+    Ok(())
+}
+
+fn clear(
+    sender: &mut CommandSender,
+    server: &Arc<Server>,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let effect_type = args
+        .contains_key(ARG_EFFECT)
+        .then(|| parse_arg_effect(args))
+        .transpose()?;
+    let target = parse_arg_player(sender, server, ARG_TARGET, args)?;
+
+    let message = match effect_type {
+        Some(effect_type) => {
+            if target.remove_effect(effect_type) {
+                format!(
+                    "Removed effect {:?} from {}",
+                    effect_type, target.gameprofile.name
+                )
+            } else {
+                format!(
+                    "{} does not have the effect {:?}",
+                    target.gameprofile.name, effect_type
+                )
+            }
+        }
+        None => {
+            let removed = target.clear_effects();
+            format!(
+                "Removed {} effect(s) from {}",
+                removed, target.gameprofile.name
+            )
+        }
+    };
+
+    sender.send_message(TextComponent::text(&message));
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .with_child(
+                literal("give").with_child(
+                    argument(ARG_TARGET, consume_arg_player).with_child(
+                        argument(ARG_EFFECT, consume_arg_effect)
+                            .execute(&give)
+                            .with_child(
+                                argument(ARG_SECONDS, consume_arg_seconds)
+                                    .execute(&give)
+                                    .with_child(
+                                        argument(ARG_AMPLIFIER, consume_arg_amplifier)
+                                            .execute(&give),
+                                    ),
+                            ),
+                    ),
+                ),
+            )
+            .with_child(
+                literal("clear").with_child(
+                    argument(ARG_TARGET, consume_arg_player)
+                        .execute(&clear)
+                        .with_child(argument(ARG_EFFECT, consume_arg_effect).execute(&clear)),
+                ),
+            ),
+    )
+}
@@ -0,0 +1,244 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+
+use pumpkin_core::text::TextComponent;
+use pumpkin_protocol::client::play::CSetContainerSlot;
+use pumpkin_protocol::slot::Slot;
+use pumpkin_registry::enchantment::{enchantment_by_id, find_enchantment, Enchantment};
+use pumpkin_world::global_registry::{self, ITEM_REGISTRY};
+use pumpkin_world::item::ItemComponents;
+
+use crate::commands::arg_player::{consume_arg_player, parse_arg_player};
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["enchant"];
+
+const DESCRIPTION: &str = "Adds an enchantment to a player's held item.";
+
+const ARG_TARGET: &str = "target";
+const ARG_ENCHANTMENT: &str = "enchantment";
+const ARG_LEVEL: &str = "level";
+
+pub fn consume_arg_enchantment(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    find_enchantment(s).map(|_| s.into())
+}
+
+pub fn parse_arg_enchantment(
+    consumed_args: &ConsumedArgs,
+) -> Result<(i32, &'static Enchantment), InvalidTreeError> {
+    let s = consumed_args
+        .get(ARG_ENCHANTMENT)
+        .ok_or(InvalidConsumptionError(None))?;
+    find_enchantment(s).ok_or_else(|| InvalidConsumptionError(Some(s.into())))
+}
+
+pub fn consume_arg_level(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<i32>().ok().map(|_| s.into())
+}
+
+pub fn parse_arg_level(consumed_args: &ConsumedArgs) -> Result<i32, InvalidTreeError> {
+    let Some(s) = consumed_args.get(ARG_LEVEL) else {
+        return Ok(1);
+    };
+    s.parse()
+        .map_err(|_| InvalidConsumptionError(Some(s.into())))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum EnchantError {
+    /// The item can't get the enchantment at all
+    Unsupported,
+    /// The item already has this or a conflicting enchantment
+    Incompatible(&'static str),
+}
+
+/// Adds the enchantment to the components of the item named `item_name`.
+///
+/// The level is clamped into `1..=max_level`, returns the level which was applied
+fn add_enchantment(
+    components: &mut ItemComponents,
+    item_name: &str,
+    id: i32,
+    enchantment: &Enchantment,
+    level: i32,
+) -> Result<i32, EnchantError> {
+    if !enchantment.target.supports(item_name) {
+        return Err(EnchantError::Unsupported);
+    }
+    if let Some(existing) = components
+        .enchantments
+        .iter()
+        .filter_map(|(id, _)| enchantment_by_id(*id))
+        .find(|existing| !existing.is_compatible_with(enchantment))
+    {
+        return Err(EnchantError::Incompatible(existing.name));
+    }
+    let level = level.clamp(1, enchantment.max_level);
+    components.enchantments.push((id, level));
+    Ok(level)
+}
+
+fn enchant(
+    sender: &mut CommandSender,
+    server: &Arc<Server>,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let (id, enchantment) = parse_arg_enchantment(args)?;
+    let level = parse_arg_level(args)?;
+    let target = parse_arg_player(sender, server, ARG_TARGET, args)?;
+    let name = &target.gameprofile.name;
+
+    let mut inventory = target.inventory.lock();
+    let slot = inventory.held_slot();
+    let result = match inventory.get_slot(slot) {
+        Ok(Some(item)) => {
+            let item_name = global_registry::find_minecraft_id(ITEM_REGISTRY, item.item_id)
+                .expect("All item ids are in the global registry");
+            Some(
+                add_enchantment(&mut item.components, item_name, id, enchantment, level)
+                    .map(|level| (item_name, level)),
+            )
+        }
+        _ => None,
+    };
+
+    let message = match result {
+        Some(Ok((item_name, level))) => {
+            let slot_data = Slot::from(inventory.held_item());
+            let state_id = inventory.state_id.fetch_add(1, Ordering::Relaxed);
+            target.client.send_packet(&CSetContainerSlot::new(
+                0,
+                (state_id + 1) as i32,
+                slot,
+                &slot_data,
+            ));
+            format!(
+                "Applied enchantment {} {} to {}'s {}",
+                enchantment.name, level, name, item_name
+            )
+        }
+        Some(Err(EnchantError::Unsupported)) => format!(
+            "{}'s item does not support the enchantment {}",
+            name, enchantment.name
+        ),
+        Some(Err(EnchantError::Incompatible(existing))) => format!(
+            "{} can't be combined with {} on {}'s item",
+            enchantment.name, existing, name
+        ),
+        None => format!("{} is not holding any item", name),
+    };
+    drop(inventory);
+
+    sender.send_message(TextComponent::text(&message));
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_TARGET, consume_arg_player).with_child(
+                argument(ARG_ENCHANTMENT, consume_arg_enchantment)
+                    .execute(&enchant)
+                    .with_child(argument(ARG_LEVEL, consume_arg_level).execute(&enchant)),
+            ),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_registry::enchantment::find_enchantment;
+    use pumpkin_world::item::ItemComponents;
+
+    use super::{add_enchantment, EnchantError};
+
+    fn enchant(
+        components: &mut ItemComponents,
+        item_name: &str,
+        enchantment: &str,
+        level: i32,
+    ) -> Result<i32, EnchantError> {
+        let (id, enchantment) = find_enchantment(enchantment).unwrap();
+        add_enchantment(components, item_name, id, enchantment, level)
+    }
+
+    #[test]
+    fn test_add_enchantment() {
+        let mut components = ItemComponents::default();
+        assert_eq!(
+            enchant(&mut components, "minecraft:diamond_sword", "sharpness", 3),
+            Ok(3)
+        );
+        assert_eq!(
+            enchant(&mut components, "minecraft:diamond_sword", "unbreaking", 2),
+            Ok(2)
+        );
+        let (sharpness, _) = find_enchantment("minecraft:sharpness").unwrap();
+        let (unbreaking, _) = find_enchantment("unbreaking").unwrap();
+        assert_eq!(
+            components.enchantments,
+            vec![(sharpness, 3), (unbreaking, 2)]
+        );
+    }
+
+    #[test]
+    fn test_inapplicable_enchantment() {
+        let mut components = ItemComponents::default();
+        assert_eq!(
+            enchant(&mut components, "minecraft:bow", "sharpness", 1),
+            Err(EnchantError::Unsupported)
+        );
+        assert_eq!(
+            enchant(&mut components, "minecraft:diamond_boots", "respiration", 1),
+            Err(EnchantError::Unsupported)
+        );
+
+        assert_eq!(
+            enchant(&mut components, "minecraft:iron_axe", "smite", 1),
+            Ok(1)
+        );
+        assert_eq!(
+            enchant(&mut components, "minecraft:iron_axe", "sharpness", 1),
+            Err(EnchantError::Incompatible("smite"))
+        );
+        assert_eq!(
+            enchant(&mut components, "minecraft:iron_axe", "smite", 2),
+            Err(EnchantError::Incompatible("smite"))
+        );
+        assert_eq!(components.enchantments.len(), 1);
+    }
+
+    #[test]
+    fn test_level_is_clamped() {
+        let mut components = ItemComponents::default();
+        assert_eq!(
+            enchant(&mut components, "minecraft:diamond_sword", "sharpness", 10),
+            Ok(5)
+        );
+        assert_eq!(
+            enchant(
+                &mut components,
+                "minecraft:diamond_pickaxe",
+                "efficiency",
+                0
+            ),
+            Ok(1)
+        );
+        assert_eq!(
+            enchant(
+                &mut components,
+                "minecraft:diamond_pickaxe",
+                "silk_touch",
+                255
+            ),
+            Ok(1)
+        );
+    }
+}
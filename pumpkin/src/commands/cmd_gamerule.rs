@@ -0,0 +1,111 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::world::game_rules::GameRule;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["gamerule"];
+
+const DESCRIPTION: &str = "Sets or queries a game rule value.";
+
+const ARG_RULE: &str = "rule";
+const ARG_VALUE: &str = "value";
+
+pub fn consume_arg_rule(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    GameRule::from_name(s).map(|_| s.into())
+}
+
+pub fn parse_arg_rule(consumed_args: &ConsumedArgs) -> Result<GameRule, InvalidTreeError> {
+    let s = consumed_args
+        .get(ARG_RULE)
+        .ok_or(InvalidConsumptionError(None))?;
+    GameRule::from_name(s).ok_or_else(|| InvalidConsumptionError(Some(s.into())))
+}
+
+/// Whether the value fits is up to the rule, so any value is consumed
+pub fn consume_arg_value(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+/// Changes the rule in the worlds, returns the message for the sender
+fn set_rule(worlds: &[Arc<World>], rule: GameRule, value: &str) -> String {
+    let valid = worlds.iter().all(|world| world.set_game_rule(rule, value));
+    if valid {
+        format!("Gamerule {} is now set to: {}", rule.name(), value)
+    } else {
+        format!("Invalid value {} for gamerule {}", value, rule.name())
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_RULE, consume_arg_rule)
+                .execute(&|sender, server, args| {
+                    let rule = parse_arg_rule(args)?;
+                    let world = match sender.as_mut_player() {
+                        Some(player) => player.entity.world.clone(),
+                        None => server.worlds[0].clone(),
+                    };
+                    let value = world.game_rules.lock().get(rule);
+                    sender.send_message(TextComponent::text(&format!(
+                        "Gamerule {} is currently set to: {}",
+                        rule.name(),
+                        value
+                    )));
+                    Ok(())
+                })
+                .with_child(argument(ARG_VALUE, consume_arg_value).execute(
+                    &|sender, server, args| {
+                        let rule = parse_arg_rule(args)?;
+                        let value = args.get(ARG_VALUE).ok_or(InvalidConsumptionError(None))?;
+
+                        // Players change the rules of their own world, the console changes them everywhere
+                        let worlds = match sender.as_mut_player() {
+                            Some(player) => vec![player.entity.world.clone()],
+                            None => server.worlds.clone(),
+                        };
+                        let message = set_rule(&worlds, rule, value);
+                        sender.send_message(TextComponent::text(&message));
+                        Ok(())
+                    },
+                )),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::{consume_arg_rule, set_rule};
+    use crate::{commands::CommandSender, test_helpers::world, world::game_rules::GameRule};
+
+    #[test]
+    fn test_players_sleeping_percentage() {
+        let mut args = vec!["playersSleepingPercentage"];
+        assert_eq!(
+            consume_arg_rule(&CommandSender::Console, &mut args).as_deref(),
+            Some("playersSleepingPercentage")
+        );
+
+        let world = world("gamerule-sleeping");
+        let worlds = [world.clone()];
+        let rule = GameRule::PlayersSleepingPercentage;
+        assert_eq!(
+            set_rule(&worlds, rule, "25"),
+            "Gamerule playersSleepingPercentage is now set to: 25"
+        );
+        assert_eq!(world.players_sleeping_percentage(), 25);
+        assert_eq!(
+            set_rule(&worlds, rule, "150"),
+            "Invalid value 150 for gamerule playersSleepingPercentage"
+        );
+        assert_eq!(world.players_sleeping_percentage(), 25);
+    }
+}
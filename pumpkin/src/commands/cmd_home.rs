@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::argument;
+use crate::commands::CommandSender;
+use crate::server::Server;
+use crate::world::homes::{Homes, DEFAULT_HOME};
+use crate::world::location::is_valid_name;
+
+const NAMES: [&str; 1] = ["home"];
+
+const DESCRIPTION: &str = "Teleports you to one of your homes.";
+
+pub const ARG_HOME: &str = "name";
+
+pub fn consume_arg_home(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    is_valid_name(s).then(|| s.into())
+}
+
+/// Returns the given home name or the default one
+pub fn parse_arg_home<'a>(consumed_args: &'a ConsumedArgs) -> &'a str {
+    consumed_args
+        .get(ARG_HOME)
+        .map_or(DEFAULT_HOME, String::as_str)
+}
+
+fn teleport_home(sender: &mut CommandSender, server: &Arc<Server>, args: &ConsumedArgs) {
+    let Some(player) = sender.as_mut_player() else {
+        sender.send_message(TextComponent::text("Only players can have homes"));
+        return;
+    };
+    let name = parse_arg_home(args);
+    let homes = match Homes::load(&Homes::folder(server), &player.gameprofile.id) {
+        Ok(homes) => homes,
+        Err(err) => {
+            log::error!(
+                "Couldn't load homes of {}: {}",
+                player.gameprofile.name,
+                err
+            );
+            player.send_system_message(TextComponent::text("Couldn't load your homes"));
+            return;
+        }
+    };
+    let Some(home) = homes.get(name) else {
+        let names = homes.names().collect::<Vec<_>>();
+        let message = if names.is_empty() {
+            "You don't have any homes, set one with /sethome".to_string()
+        } else {
+            format!(
+                "There is no home called {}, your homes are: {}",
+                name,
+                names.join(", ")
+            )
+        };
+        player.send_system_message(TextComponent::text(&message));
+        return;
+    };
+    if !home.teleport(player) {
+        player.send_system_message(TextComponent::text(&format!(
+            "Home {} is in another dimension",
+            name
+        )));
+        return;
+    }
+    player.send_system_message(TextComponent::text(&format!("Teleported to home {}", name)));
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .execute(&|sender, server, args| {
+            teleport_home(sender, server, args);
+            Ok(())
+        })
+        .with_child(
+            argument(ARG_HOME, consume_arg_home).execute(&|sender, server, args| {
+                teleport_home(sender, server, args);
+                Ok(())
+            }),
+        )
+}
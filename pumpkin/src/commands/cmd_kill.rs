@@ -1,28 +1,32 @@
-use crate::commands::arg_player::{consume_arg_player, parse_arg_player};
+use crate::commands::arg_targets::{consume_arg_targets, parse_arg_targets};
 use crate::commands::tree::CommandTree;
-use crate::commands::tree::RawArgs;
 use crate::commands::tree_builder::argument;
-use crate::commands::CommandSender;
 use pumpkin_core::text::{color::NamedColor, TextComponent};
 
 const NAMES: [&str; 1] = ["kill"];
-const DESCRIPTION: &str = "Kills a target player.";
+const DESCRIPTION: &str = "Kills the target players.";
 
 const ARG_TARGET: &str = "target";
 
-pub fn consume_arg_target(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
-    consume_arg_player(_src, args)
-}
-
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
-        argument(ARG_TARGET, consume_arg_target).execute(&|sender, server, args| {
-            let target = parse_arg_player(sender, server, ARG_TARGET, args)?;
-            target.entity.kill();
+        argument(ARG_TARGET, consume_arg_targets).execute(&|sender, server, args| {
+            let targets = parse_arg_targets(sender, server, ARG_TARGET, args)?;
+            for target in &targets {
+                target.entity.kill();
+            }
 
-            sender.send_message(
-                TextComponent::text("Player has been killed.").color_named(NamedColor::Blue),
-            );
+            let message = match targets.len() {
+                0 => {
+                    sender.send_message(
+                        TextComponent::text("No player was found").color_named(NamedColor::Red),
+                    );
+                    return Ok(());
+                }
+                1 => "Player has been killed.".to_string(),
+                n => format!("{} players have been killed.", n),
+            };
+            sender.send_message(TextComponent::text(&message).color_named(NamedColor::Blue));
 
             Ok(())
         }),
@@ -0,0 +1,32 @@
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::cmd_say::sender_name;
+use crate::commands::cmd_tellraw::consume_arg_message;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::argument;
+
+const NAMES: [&str; 1] = ["me"];
+
+const DESCRIPTION: &str = "Describes an action you are doing.";
+
+const ARG_ACTION: &str = "action";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        argument(ARG_ACTION, consume_arg_message).execute(&|sender, server, args| {
+            let action = args.get(ARG_ACTION).ok_or(InvalidConsumptionError(None))?;
+            if let Some(player) = sender.as_mut_player() {
+                if player.is_muted(server) {
+                    return Ok(());
+                }
+            }
+            let text = format!("* {} {}", sender_name(sender), action);
+            log::info!("{}", text);
+            for player in server.get_all_players() {
+                player.send_system_message(TextComponent::text(&text));
+            }
+            Ok(())
+        }),
+    )
+}
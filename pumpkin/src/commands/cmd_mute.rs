@@ -0,0 +1,122 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::arg_targets::{consume_arg_targets, parse_arg_targets};
+use crate::commands::cmd_tellraw::consume_arg_message;
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::server::mutes::{unix_now, Mute};
+use crate::server::Server;
+
+const NAMES: [&str; 1] = ["mute"];
+
+const DESCRIPTION: &str = "Stops players from chatting.";
+
+const ARG_TARGETS: &str = "targets";
+const ARG_DURATION: &str = "duration";
+const ARG_REASON: &str = "reason";
+
+/// Parses a duration in seconds, either plain or with a `s`, `m`, `h` or `d` suffix
+pub fn parse_duration(s: &str) -> Option<u64> {
+    let (number, seconds_per_unit) = match s.chars().last()? {
+        's' => (&s[..s.len() - 1], 1),
+        'm' => (&s[..s.len() - 1], 60),
+        'h' => (&s[..s.len() - 1], 60 * 60),
+        'd' => (&s[..s.len() - 1], 24 * 60 * 60),
+        _ => (s, 1),
+    };
+    let number = number.parse::<u64>().ok()?;
+    if number == 0 {
+        return None;
+    }
+    number.checked_mul(seconds_per_unit)
+}
+
+pub fn consume_arg_duration(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    parse_duration(s).map(|_| s.into())
+}
+
+fn mute(
+    sender: &mut CommandSender,
+    server: &Arc<Server>,
+    args: &ConsumedArgs,
+) -> Result<(), InvalidTreeError> {
+    let now = unix_now();
+    let expires = match args.get(ARG_DURATION) {
+        Some(s) => Some(
+            parse_duration(s)
+                .ok_or_else(|| InvalidConsumptionError(Some(s.into())))?
+                .saturating_add(now),
+        ),
+        None => None,
+    };
+    let reason = args.get(ARG_REASON).cloned();
+
+    let players = parse_arg_targets(sender, server, ARG_TARGETS, args)?;
+    if players.is_empty() {
+        sender
+            .send_message(TextComponent::text("No player was found").color_named(NamedColor::Red));
+        return Ok(());
+    }
+
+    let mut mutes = server.mutes.write();
+    mutes.remove_expired(now);
+    for player in &players {
+        let name = &player.gameprofile.name;
+        mutes.mute(
+            player.gameprofile.id,
+            Mute {
+                name: name.clone(),
+                expires,
+                reason: reason.clone(),
+            },
+        );
+        let mut notice = "You have been muted".to_string();
+        if let Some(reason) = &reason {
+            notice.push_str(": ");
+            notice.push_str(reason);
+        }
+        player.send_system_message(TextComponent::text(&notice).color_named(NamedColor::Red));
+        sender.send_message(TextComponent::text(&format!("Muted {}", name)));
+    }
+    if let Err(err) = mutes.save() {
+        log::error!("Couldn't save mutes: {}", err);
+    }
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_TARGETS, consume_arg_targets)
+                .execute(&mute)
+                .with_child(
+                    argument(ARG_DURATION, consume_arg_duration)
+                        .execute(&mute)
+                        .with_child(argument(ARG_REASON, consume_arg_message).execute(&mute)),
+                ),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_duration;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30"), Some(30));
+        assert_eq!(parse_duration("30s"), Some(30));
+        assert_eq!(parse_duration("10m"), Some(600));
+        assert_eq!(parse_duration("2h"), Some(7200));
+        assert_eq!(parse_duration("1d"), Some(86400));
+        assert_eq!(parse_duration("0m"), None);
+        assert_eq!(parse_duration("-5s"), None);
+        assert_eq!(parse_duration("spam"), None);
+    }
+}
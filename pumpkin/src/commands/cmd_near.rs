@@ -0,0 +1,224 @@
+use std::sync::Arc;
+
+use pumpkin_core::{
+    math::vector3::Vector3,
+    text::{color::NamedColor, TextComponent},
+};
+use pumpkin_entity::EntityId;
+
+use crate::commands::arg_position::{consume_arg_block_coordinate, parse_arg_block_position};
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::server::Server;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["near"];
+
+const DESCRIPTION: &str = "Lists the players nearby.";
+
+const ARG_RADIUS: &str = "radius";
+const ARG_X: &str = "x";
+const ARG_Y: &str = "y";
+const ARG_Z: &str = "z";
+
+/// The radius in blocks when a Player doesn't give one
+const DEFAULT_RADIUS: u32 = 200;
+
+fn consume_arg_radius(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<u32>().ok().map(|_| s.into())
+}
+
+fn parse_arg_radius(consumed_args: &ConsumedArgs) -> Result<u32, InvalidTreeError> {
+    let s = consumed_args
+        .get(ARG_RADIUS)
+        .ok_or(InvalidConsumptionError(None))?;
+    s.parse()
+        .map_err(|_| InvalidConsumptionError(Some(s.into())))
+}
+
+/// The distance between two positions in blocks
+fn distance(from: Vector3<f64>, to: Vector3<f64>) -> f64 {
+    to.sub(&from).length()
+}
+
+/// The named positions within `radius` blocks of `origin` with their distance, nearest first.
+///
+/// A radius of 0 includes every position
+fn players_near<'a>(
+    origin: Vector3<f64>,
+    radius: u32,
+    players: &[(&'a str, Vector3<f64>)],
+) -> Vec<(&'a str, f64)> {
+    let mut near: Vec<_> = players
+        .iter()
+        .map(|(name, position)| (*name, distance(origin, *position)))
+        .filter(|(_, distance)| radius == 0 || *distance <= f64::from(radius))
+        .collect();
+    near.sort_by(|a, b| a.1.total_cmp(&b.1));
+    near
+}
+
+fn near_message(radius: u32, near: &[(&str, f64)]) -> String {
+    let range = match radius {
+        0 => "in this world".to_string(),
+        radius => format!("within {} blocks", radius),
+    };
+    if near.is_empty() {
+        return format!("There are no players {}", range);
+    }
+    let players: Vec<_> = near
+        .iter()
+        .map(|(name, distance)| format!("{} ({:.1} blocks)", name, distance))
+        .collect();
+    format!("Players {}: {}", range, players.join(", "))
+}
+
+/// Lists the Players in the World around `origin`, leaving out the sender themselves
+fn list_near(
+    sender: &mut CommandSender,
+    world: &World,
+    origin: Vector3<f64>,
+    radius: u32,
+    except: Option<EntityId>,
+) {
+    let players: Vec<_> = world
+        .current_players
+        .lock()
+        .values()
+        .filter(|player| Some(player.entity_id()) != except)
+        .map(|player| (player.gameprofile.name.clone(), player.entity.pos.load()))
+        .collect();
+    let players: Vec<_> = players
+        .iter()
+        .map(|(name, position)| (name.as_str(), *position))
+        .collect();
+    let near = players_near(origin, radius, &players);
+    sender.send_message(TextComponent::text(&near_message(radius, &near)));
+}
+
+/// Lists the Players around the sender, the console has to give a position
+fn near_sender(sender: &mut CommandSender, radius: u32) {
+    let Some((world, origin, entity_id)) = sender.as_mut_player().map(|player| {
+        (
+            player.entity.world.clone(),
+            player.entity.pos.load(),
+            player.entity_id(),
+        )
+    }) else {
+        sender.send_message(
+            TextComponent::text("The console has to give a position").color_named(NamedColor::Red),
+        );
+        return;
+    };
+    list_near(sender, &world, origin, radius, Some(entity_id));
+}
+
+/// The World the sender is in, the console looks at the default one
+fn sender_world(sender: &mut CommandSender, server: &Server) -> Arc<World> {
+    sender.as_mut_player().map_or_else(
+        || server.worlds[0].clone(),
+        |player| player.entity.world.clone(),
+    )
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .execute(&|sender, _, _| {
+                near_sender(sender, DEFAULT_RADIUS);
+                Ok(())
+            })
+            .with_child(
+                argument(ARG_RADIUS, consume_arg_radius)
+                    .execute(&|sender, _, args| {
+                        let radius = parse_arg_radius(args)?;
+                        near_sender(sender, radius);
+                        Ok(())
+                    })
+                    .with_child(argument(ARG_X, consume_arg_block_coordinate).with_child(
+                        argument(ARG_Y, consume_arg_block_coordinate).with_child(
+                            argument(ARG_Z, consume_arg_block_coordinate).execute(
+                                &|sender, server, args| {
+                                    let radius = parse_arg_radius(args)?;
+                                    let block = parse_arg_block_position(
+                                        sender,
+                                        [ARG_X, ARG_Y, ARG_Z],
+                                        args,
+                                    )?
+                                    .0;
+                                    let origin = Vector3::new(
+                                        f64::from(block.x) + 0.5,
+                                        f64::from(block.y),
+                                        f64::from(block.z) + 0.5,
+                                    );
+                                    let except =
+                                        sender.as_mut_player().map(|player| player.entity_id());
+                                    let world = sender_world(sender, server);
+                                    list_near(sender, &world, origin, radius, except);
+                                    Ok(())
+                                },
+                            ),
+                        ),
+                    )),
+            ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::vector3::Vector3;
+
+    use super::{distance, near_message, players_near};
+
+    fn players() -> Vec<(&'static str, Vector3<f64>)> {
+        vec![
+            ("Steve", Vector3::new(30.0, 64.0, 40.0)),
+            ("Alex", Vector3::new(3.0, 68.0, 0.0)),
+            ("Notch", Vector3::new(-500.0, 64.0, 0.0)),
+        ]
+    }
+
+    #[test]
+    fn test_distance() {
+        let origin = Vector3::new(0.0, 64.0, 0.0);
+        assert_eq!(distance(origin, origin), 0.0);
+        assert_eq!(distance(origin, Vector3::new(3.0, 68.0, 0.0)), 5.0);
+        assert_eq!(distance(Vector3::new(3.0, 68.0, 0.0), origin), 5.0);
+        assert_eq!(distance(origin, Vector3::new(-30.0, 64.0, -40.0)), 50.0);
+    }
+
+    #[test]
+    fn test_radius() {
+        let origin = Vector3::new(0.0, 64.0, 0.0);
+        let players = players();
+        assert_eq!(
+            players_near(origin, 50, &players),
+            vec![("Alex", 5.0), ("Steve", 50.0)]
+        );
+        assert_eq!(players_near(origin, 10, &players), vec![("Alex", 5.0)]);
+        assert!(players_near(origin, 4, &players).is_empty());
+        // Everyone, nearest first
+        let names: Vec<_> = players_near(origin, 0, &players)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, ["Alex", "Steve", "Notch"]);
+    }
+
+    #[test]
+    fn test_near_message() {
+        assert_eq!(
+            near_message(50, &[("Alex", 5.0), ("Steve", 49.96)]),
+            "Players within 50 blocks: Alex (5.0 blocks), Steve (50.0 blocks)"
+        );
+        assert_eq!(near_message(0, &[]), "There are no players in this world");
+        assert_eq!(
+            near_message(10, &[]),
+            "There are no players within 10 blocks"
+        );
+    }
+}
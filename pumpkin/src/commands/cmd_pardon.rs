@@ -0,0 +1,35 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::arg_player::consume_arg_player_name;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::{argument, require};
+
+const NAMES: [&str; 1] = ["pardon"];
+
+const DESCRIPTION: &str = "Removes the ban of a player.";
+
+const ARG_PLAYER: &str = "player";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 3).with_child(
+            argument(ARG_PLAYER, consume_arg_player_name).execute(&|sender, server, args| {
+                let name = args.get(ARG_PLAYER).ok_or(InvalidConsumptionError(None))?;
+                let mut bans = server.bans.write();
+                if !bans.pardon_player(name) {
+                    sender.send_message(
+                        TextComponent::text(&format!("{} is not banned", name))
+                            .color_named(NamedColor::Red),
+                    );
+                    return Ok(());
+                }
+                if let Err(err) = bans.save() {
+                    log::error!("Couldn't save the ban list: {}", err);
+                }
+                sender.send_message(TextComponent::text(&format!("Unbanned {}", name)));
+                Ok(())
+            }),
+        ),
+    )
+}
@@ -0,0 +1,45 @@
+use std::net::IpAddr;
+
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+
+const NAMES: [&str; 1] = ["pardon-ip"];
+
+const DESCRIPTION: &str = "Removes the ban of an IP address.";
+
+const ARG_IP: &str = "ip";
+
+fn consume_arg_ip(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<IpAddr>().ok().map(|_| s.into())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 3).with_child(
+            argument(ARG_IP, consume_arg_ip).execute(&|sender, server, args| {
+                let s = args.get(ARG_IP).ok_or(InvalidConsumptionError(None))?;
+                let ip = s
+                    .parse::<IpAddr>()
+                    .map_err(|_| InvalidConsumptionError(Some(s.into())))?;
+                let mut bans = server.bans.write();
+                if !bans.pardon_ip(&ip) {
+                    sender.send_message(
+                        TextComponent::text(&format!("{} is not banned", ip))
+                            .color_named(NamedColor::Red),
+                    );
+                    return Ok(());
+                }
+                if let Err(err) = bans.save() {
+                    log::error!("Couldn't save the ban list: {}", err);
+                }
+                sender.send_message(TextComponent::text(&format!("Unbanned IP {}", ip)));
+                Ok(())
+            }),
+        ),
+    )
+}
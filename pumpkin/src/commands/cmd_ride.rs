@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::arg_targets::{consume_arg_targets, parse_arg_targets};
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::tree::{CommandTree, ConsumedArgs};
+use crate::commands::tree_builder::{argument, literal, require};
+use crate::commands::CommandSender;
+use crate::entity::player::Player;
+use crate::server::Server;
+use crate::world::mount;
+
+const NAMES: [&str; 1] = ["ride"];
+
+const DESCRIPTION: &str = "Lets a player ride another player or dismount.";
+
+const ARG_TARGET: &str = "target";
+
+const ARG_VEHICLE: &str = "vehicle";
+
+/// The one player the argument selects, `None` after telling the sender why there isn't one
+fn single_player(
+    sender: &mut CommandSender,
+    server: &Arc<Server>,
+    arg_name: &str,
+    args: &ConsumedArgs,
+) -> Result<Option<Arc<Player>>, InvalidTreeError> {
+    let mut players = parse_arg_targets(sender, server, arg_name, args)?;
+    if players.len() != 1 {
+        sender.send_message(
+            TextComponent::text("Exactly one player has to be selected")
+                .color_named(NamedColor::Red),
+        );
+        return Ok(None);
+    }
+    Ok(players.pop())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_TARGET, consume_arg_targets)
+                .with_child(literal("mount").with_child(
+                    argument(ARG_VEHICLE, consume_arg_targets).execute(&|sender, server, args| {
+                        let Some(target) = single_player(sender, server, ARG_TARGET, args)? else {
+                            return Ok(());
+                        };
+                        let Some(vehicle) = single_player(sender, server, ARG_VEHICLE, args)?
+                        else {
+                            return Ok(());
+                        };
+                        if target.entity.vehicle.load() == Some(vehicle.entity_id()) {
+                            sender.send_message(
+                                TextComponent::text(&format!(
+                                    "{} is already riding {}",
+                                    target.gameprofile.name, vehicle.gameprofile.name
+                                ))
+                                .color_named(NamedColor::Red),
+                            );
+                            return Ok(());
+                        }
+                        let world = &target.entity.world;
+                        if !Arc::ptr_eq(world, &vehicle.entity.world)
+                            || !mount::mount(world, &target, &vehicle)
+                        {
+                            sender.send_message(
+                                TextComponent::text(
+                                    "Can't mount a player on itself or any of its passengers",
+                                )
+                                .color_named(NamedColor::Red),
+                            );
+                            return Ok(());
+                        }
+                        sender.send_message(TextComponent::text(&format!(
+                            "{} started riding {}",
+                            target.gameprofile.name, vehicle.gameprofile.name
+                        )));
+                        Ok(())
+                    }),
+                ))
+                .with_child(literal("dismount").execute(&|sender, server, args| {
+                    let Some(target) = single_player(sender, server, ARG_TARGET, args)? else {
+                        return Ok(());
+                    };
+                    if !mount::dismount(&target.entity.world, &target) {
+                        sender.send_message(
+                            TextComponent::text(&format!(
+                                "{} is not riding any vehicle",
+                                target.gameprofile.name
+                            ))
+                            .color_named(NamedColor::Red),
+                        );
+                        return Ok(());
+                    }
+                    sender.send_message(TextComponent::text(&format!(
+                        "{} stopped riding",
+                        target.gameprofile.name
+                    )));
+                    Ok(())
+                })),
+        ),
+    )
+}
@@ -0,0 +1,25 @@
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::require;
+
+const NAMES: [&str; 1] = ["save-all"];
+
+const DESCRIPTION: &str = "Saves the worlds and players now.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 4).execute(&|sender, server, _| {
+            sender.send_message(TextComponent::text(
+                "Saving the game (this may take a moment!)",
+            ));
+            // Saves even while autosaving is paused with `/save-off`
+            let saved = server.save();
+            sender.send_message(TextComponent::text(&format!(
+                "Saved the game ({} chunks)",
+                saved
+            )));
+            Ok(())
+        }),
+    )
+}
@@ -0,0 +1,23 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::require;
+
+const NAMES: [&str; 1] = ["save-on"];
+
+const DESCRIPTION: &str = "Resumes autosaving after /save-off.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 4).execute(&|sender, server, _| {
+            if server.autosave.set_enabled(true) {
+                sender.send_message(TextComponent::text("Automatic saving is now enabled"));
+            } else {
+                sender.send_message(
+                    TextComponent::text("Saving is already turned on").color_named(NamedColor::Red),
+                );
+            }
+            Ok(())
+        }),
+    )
+}
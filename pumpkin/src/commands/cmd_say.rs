@@ -0,0 +1,42 @@
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::cmd_tellraw::consume_arg_message;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+
+const NAMES: [&str; 1] = ["say"];
+
+const DESCRIPTION: &str = "Broadcasts a message to all players.";
+
+const ARG_MESSAGE: &str = "message";
+
+/// The name shown in front of messages of the sender
+pub fn sender_name(sender: &mut CommandSender) -> String {
+    match sender.as_mut_player() {
+        Some(player) => player.gameprofile.name.clone(),
+        None => "Server".to_string(),
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_MESSAGE, consume_arg_message).execute(&|sender, server, args| {
+                let message = args.get(ARG_MESSAGE).ok_or(InvalidConsumptionError(None))?;
+                if let Some(player) = sender.as_mut_player() {
+                    if player.is_muted(server) {
+                        return Ok(());
+                    }
+                }
+                let text = format!("[{}] {}", sender_name(sender), message);
+                log::info!("{}", text);
+                for player in server.get_all_players() {
+                    player.send_system_message(TextComponent::text(&text));
+                }
+                Ok(())
+            }),
+        ),
+    )
+}
@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use uuid::Uuid;
+
+use crate::commands::arg_player::consume_arg_player_name;
+use crate::commands::cmd_uuid::find_player;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::argument;
+use crate::server::bans::{format_duration, format_timestamp};
+use crate::server::mutes::unix_now;
+use crate::world::player_data::PlayerData;
+
+const NAMES: [&str; 1] = ["seen"];
+
+const DESCRIPTION: &str = "Shows when a player was last online.";
+
+const ARG_PLAYER: &str = "player";
+
+/// Describes when an offline Player was last seen according to their saved data
+fn last_seen_message(world_folder: &Path, name: &str, uuid: &Uuid, now: u64) -> String {
+    match PlayerData::load(world_folder, uuid) {
+        Ok(Some(data)) => match data.login_times.last_seen {
+            Some(last_seen) => format!(
+                "{} was last seen {} ({} ago)",
+                name,
+                format_timestamp(last_seen),
+                format_duration(now.saturating_sub(last_seen))
+            ),
+            None => format!("{} was seen before, but not since when", name),
+        },
+        Ok(None) => format!("{} never played on this world", name),
+        Err(err) => {
+            log::error!("Couldn't load the player data of {}: {}", name, err);
+            format!("Couldn't load the player data of {}", name)
+        }
+    }
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        argument(ARG_PLAYER, consume_arg_player_name).execute(&|sender, server, args| {
+            let name = args.get(ARG_PLAYER).ok_or(InvalidConsumptionError(None))?;
+            if let Some(player) = server.get_player_by_name(name) {
+                sender.send_message(TextComponent::text(&format!(
+                    "{} is online now",
+                    player.gameprofile.name
+                )));
+                return Ok(());
+            }
+            let found = find_player(&server.worlds, &server.name_cache.read(), name);
+            let Some((name, uuid)) = found else {
+                sender.send_message(
+                    TextComponent::text(&format!("Unknown player {}", name))
+                        .color_named(NamedColor::Red),
+                );
+                return Ok(());
+            };
+            // Player data is kept in the default world
+            let world_folder = server.worlds[0].level.root_folder();
+            let message = last_seen_message(world_folder, &name, &uuid, unix_now());
+            sender.send_message(TextComponent::text(&message));
+            Ok(())
+        }),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::env;
+
+    use pumpkin_core::GameMode;
+    use uuid::Uuid;
+
+    use super::last_seen_message;
+    use crate::world::player_data::{LoginTimes, PlayerData};
+
+    fn data(last_seen: Option<u64>) -> PlayerData {
+        PlayerData {
+            position: (0.0, 64.0, 0.0),
+            yaw: 0.0,
+            pitch: 0.0,
+            gamemode: GameMode::Survival,
+            health: 20.0,
+            food: 20,
+            food_saturation: 5.0,
+            respawn_point: None,
+            login_times: LoginTimes {
+                last_seen,
+                ..Default::default()
+            },
+            vanished: false,
+            experience: 0,
+        }
+    }
+
+    #[test]
+    fn test_last_seen_from_saved_data() {
+        let world_folder = env::temp_dir().join(format!("pumpkin-seen-{}", Uuid::new_v4()));
+        let uuid = Uuid::new_v4();
+        assert_eq!(
+            last_seen_message(&world_folder, "Steve", &uuid, 0),
+            "Steve never played on this world"
+        );
+
+        data(Some(1_714_571_100))
+            .save(&world_folder, &uuid)
+            .unwrap();
+        assert_eq!(
+            last_seen_message(&world_folder, "Steve", &uuid, 1_714_571_100 + 3 * 3600 + 60),
+            "Steve was last seen 2024-05-01 13:45 UTC (3h 1m ago)"
+        );
+
+        // Saved before the time was recorded
+        data(None).save(&world_folder, &uuid).unwrap();
+        assert_eq!(
+            last_seen_message(&world_folder, "Steve", &uuid, 0),
+            "Steve was seen before, but not since when"
+        );
+    }
+}
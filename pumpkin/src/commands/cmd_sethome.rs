@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use pumpkin_config::LIVE_CONFIG;
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::cmd_home::{consume_arg_home, parse_arg_home, ARG_HOME};
+use crate::commands::tree::{CommandTree, ConsumedArgs};
+use crate::commands::tree_builder::argument;
+use crate::commands::CommandSender;
+use crate::server::Server;
+use crate::world::homes::{Homes, TooManyHomes};
+use crate::world::location::Location;
+
+const NAMES: [&str; 1] = ["sethome"];
+
+const DESCRIPTION: &str = "Saves your current location as a home.";
+
+fn set_home(sender: &mut CommandSender, server: &Arc<Server>, args: &ConsumedArgs) {
+    let Some(player) = sender.as_mut_player() else {
+        sender.send_message(TextComponent::text("Only players can have homes"));
+        return;
+    };
+    let name = parse_arg_home(args);
+    let world_folder = Homes::folder(server);
+    let uuid = &player.gameprofile.id;
+    let max_homes = LIVE_CONFIG.load().homes.max_homes;
+    let result = Homes::load(&world_folder, uuid).and_then(|mut homes| {
+        let result = homes.set(name, Location::from_player(player), max_homes);
+        if result.is_ok() {
+            homes.save(&world_folder, uuid)?;
+        }
+        Ok(result)
+    });
+    let message = match result {
+        Ok(Ok(_)) => format!("Home {} set", name),
+        Ok(Err(TooManyHomes)) => format!("You can't have more than {} homes", max_homes),
+        Err(err) => {
+            log::error!(
+                "Couldn't save homes of {}: {}",
+                player.gameprofile.name,
+                err
+            );
+            "Couldn't save your home".to_string()
+        }
+    };
+    player.send_system_message(TextComponent::text(&message));
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION)
+        .execute(&|sender, server, args| {
+            set_home(sender, server, args);
+            Ok(())
+        })
+        .with_child(
+            argument(ARG_HOME, consume_arg_home).execute(&|sender, server, args| {
+                set_home(sender, server, args);
+                Ok(())
+            }),
+        )
+}
@@ -0,0 +1,35 @@
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::cmd_warp::{consume_arg_warp, parse_arg_warp, ARG_WARP};
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::{argument, require};
+use crate::world::location::Location;
+
+const NAMES: [&str; 1] = ["setwarp"];
+
+const DESCRIPTION: &str = "Saves your current location as a warp.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_WARP, consume_arg_warp).execute(&|sender, server, args| {
+                let name = parse_arg_warp(args)?;
+                let Some(player) = sender.as_mut_player() else {
+                    sender.send_message(TextComponent::text("Only players can set warps"));
+                    return Ok(());
+                };
+                let mut warps = server.warps.write();
+                warps.set(name, Location::from_player(player));
+                let message = match warps.save() {
+                    Ok(()) => format!("Warp {} set", name),
+                    Err(err) => {
+                        log::error!("Couldn't save warps: {}", err);
+                        format!("Warp {} set, but it couldn't be saved", name)
+                    }
+                };
+                player.send_system_message(TextComponent::text(&message));
+                Ok(())
+            }),
+        ),
+    )
+}
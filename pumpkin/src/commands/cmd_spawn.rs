@@ -0,0 +1,25 @@
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::CommandTree;
+
+const NAMES: [&str; 1] = ["spawn"];
+
+const DESCRIPTION: &str = "Teleports you to the world spawn.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).execute(&|sender, _, _| {
+        let Some(player) = sender.as_mut_player() else {
+            sender.send_message(TextComponent::text("Only players can go to the spawn"));
+            return Ok(());
+        };
+        let world = player.entity.world.clone();
+        // This records the current location, so /back returns here
+        let message = if world.teleport_to_spawn(player) {
+            "Teleported to the spawn"
+        } else {
+            "The spawn is in another dimension"
+        };
+        player.send_system_message(TextComponent::text(message));
+        Ok(())
+    })
+}
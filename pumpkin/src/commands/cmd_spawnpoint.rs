@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use pumpkin_core::{
+    math::{position::WorldPosition, vector3::Vector3},
+    text::{color::NamedColor, TextComponent},
+};
+
+use crate::commands::arg_position::{consume_arg_block_coordinate, parse_arg_block_position};
+use crate::commands::arg_targets::{consume_arg_targets, parse_arg_targets};
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::entity::player::Player;
+use crate::world::respawn::RespawnPoint;
+
+const NAMES: [&str; 1] = ["spawnpoint"];
+
+const DESCRIPTION: &str = "Sets where players respawn.";
+
+const ARG_TARGETS: &str = "targets";
+const ARG_X: &str = "x";
+const ARG_Y: &str = "y";
+const ARG_Z: &str = "z";
+
+/// Sets a forced respawn point, so it works without a bed
+fn set_spawn_point(
+    sender: &mut CommandSender,
+    players: &[Arc<Player>],
+    position: WorldPosition,
+    yaw: f32,
+) {
+    if players.is_empty() {
+        sender
+            .send_message(TextComponent::text("No player was found").color_named(NamedColor::Red));
+        return;
+    }
+    let Vector3 { x, y, z } = position.0;
+    for player in players {
+        player.set_respawn_point(Some(RespawnPoint {
+            dimension: player.entity.world.dimension,
+            position: (x, y, z),
+            yaw,
+            forced: true,
+        }));
+    }
+    let target = match players {
+        [player] => player.gameprofile.name.clone(),
+        _ => format!("{} players", players.len()),
+    };
+    sender.send_message(TextComponent::text(&format!(
+        "Set spawn point to {}, {}, {} for {}",
+        x, y, z, target
+    )));
+}
+
+/// Where the sender stands, the console has to give a position
+fn sender_position(sender: &mut CommandSender) -> Option<(WorldPosition, f32)> {
+    let position = sender
+        .as_mut_player()
+        .map(|player| (player.entity.block_pos.load(), player.entity.yaw.load()));
+    if position.is_none() {
+        sender.send_message(
+            TextComponent::text("The console has to give a position").color_named(NamedColor::Red),
+        );
+    }
+    position
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .execute(&|sender, server, _| {
+                let Some((position, yaw)) = sender_position(sender) else {
+                    return Ok(());
+                };
+                let Some(player) = sender
+                    .as_mut_player()
+                    .and_then(|player| server.get_player_by_uuid(&player.gameprofile.id))
+                else {
+                    return Ok(());
+                };
+                set_spawn_point(sender, &[player], position, yaw);
+                Ok(())
+            })
+            .with_child(
+                argument(ARG_TARGETS, consume_arg_targets)
+                    .execute(&|sender, server, args| {
+                        let players = parse_arg_targets(sender, server, ARG_TARGETS, args)?;
+                        let Some((position, yaw)) = sender_position(sender) else {
+                            return Ok(());
+                        };
+                        set_spawn_point(sender, &players, position, yaw);
+                        Ok(())
+                    })
+                    .with_child(argument(ARG_X, consume_arg_block_coordinate).with_child(
+                        argument(ARG_Y, consume_arg_block_coordinate).with_child(
+                            argument(ARG_Z, consume_arg_block_coordinate).execute(
+                                &|sender, server, args| {
+                                    let players =
+                                        parse_arg_targets(sender, server, ARG_TARGETS, args)?;
+                                    let position = parse_arg_block_position(
+                                        sender,
+                                        [ARG_X, ARG_Y, ARG_Z],
+                                        args,
+                                    )?;
+                                    set_spawn_point(sender, &players, position, 0.0);
+                                    Ok(())
+                                },
+                            ),
+                        ),
+                    )),
+            ),
+    )
+}
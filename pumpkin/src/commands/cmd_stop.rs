@@ -1,6 +1,7 @@
 use pumpkin_core::text::color::NamedColor;
 use pumpkin_core::text::TextComponent;
 
+use crate::client::disconnect::DisconnectReason;
 use crate::commands::tree::CommandTree;
 use crate::commands::tree_builder::require;
 
@@ -10,9 +11,12 @@ const DESCRIPTION: &str = "Stop the server.";
 
 pub fn init_command_tree<'a>() -> CommandTree<'a> {
     CommandTree::new(NAMES, DESCRIPTION).with_child(
-        require(&|sender| sender.permission_lvl() >= 4).execute(&|sender, _, _args| {
+        require(&|sender| sender.permission_lvl() >= 4).execute(&|sender, server, _args| {
             sender
                 .send_message(TextComponent::text("Stopping Server").color_named(NamedColor::Red));
+            for player in server.get_all_players() {
+                player.kick(DisconnectReason::ServerClosing);
+            }
             std::process::exit(0)
         }),
     )
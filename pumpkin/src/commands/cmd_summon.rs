@@ -0,0 +1,217 @@
+use std::sync::Arc;
+
+use pumpkin_core::{
+    math::vector3::Vector3,
+    text::{color::NamedColor, TextComponent},
+};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use uuid::Uuid;
+
+use crate::commands::arg_position::{consume_arg_block_coordinate, parse_arg_block_position};
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::entity::Entity;
+use crate::server::Server;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["summon"];
+
+const DESCRIPTION: &str = "Summons an entity.";
+
+const ARG_ENTITY: &str = "entity";
+const ARG_X: &str = "x";
+const ARG_Y: &str = "y";
+const ARG_Z: &str = "z";
+
+pub fn consume_arg_entity_type(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<EntityType>().ok().map(|_| s.into())
+}
+
+pub fn parse_arg_entity_type(consumed_args: &ConsumedArgs) -> Result<EntityType, InvalidTreeError> {
+    let s = consumed_args
+        .get(ARG_ENTITY)
+        .ok_or(InvalidConsumptionError(None))?;
+    s.parse()
+        .map_err(|_| InvalidConsumptionError(Some(s.into())))
+}
+
+/// Creates the entity at the position and spawns it for the Players around it.
+///
+/// Returns `None` if the chunk already holds too many entities
+pub fn summon(
+    world: &Arc<World>,
+    entity_id: EntityId,
+    entity_type: EntityType,
+    position: Vector3<f64>,
+) -> Option<Arc<Entity>> {
+    let entity = Arc::new(Entity::new(
+        entity_id,
+        Uuid::new_v4(),
+        world.clone(),
+        entity_type,
+    ));
+    entity.set_pos(position.x, position.y, position.z);
+    world.spawn_entity(entity.clone()).then_some(entity)
+}
+
+fn summon_at(
+    sender: &mut CommandSender,
+    server: &Server,
+    entity_type: EntityType,
+    world: Arc<World>,
+    position: Vector3<f64>,
+) {
+    if !entity_type.is_summonable() {
+        sender.send_message(
+            TextComponent::text(&format!("{:?} can't be summoned", entity_type))
+                .color_named(NamedColor::Red),
+        );
+        return;
+    }
+    if summon(&world, server.new_entity_id(), entity_type, position).is_none() {
+        sender.send_message(
+            TextComponent::text("There are too many entities in this chunk")
+                .color_named(NamedColor::Red),
+        );
+        return;
+    }
+    sender.send_message(TextComponent::text(&format!(
+        "Summoned new {:?}",
+        entity_type
+    )));
+}
+
+/// The World entities get summoned in, the console summons in the default one
+fn sender_world(sender: &mut CommandSender, server: &Server) -> Arc<World> {
+    sender.as_mut_player().map_or_else(
+        || server.worlds[0].clone(),
+        |player| player.entity.world.clone(),
+    )
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_ENTITY, consume_arg_entity_type)
+                .execute(&|sender, server, args| {
+                    let entity_type = parse_arg_entity_type(args)?;
+                    let Some(position) = sender
+                        .as_mut_player()
+                        .map(|player| player.entity.pos.load())
+                    else {
+                        sender.send_message(
+                            TextComponent::text("The console has to give a position")
+                                .color_named(NamedColor::Red),
+                        );
+                        return Ok(());
+                    };
+                    let world = sender_world(sender, server);
+                    summon_at(sender, server, entity_type, world, position);
+                    Ok(())
+                })
+                .with_child(argument(ARG_X, consume_arg_block_coordinate).with_child(
+                    argument(ARG_Y, consume_arg_block_coordinate).with_child(
+                        argument(ARG_Z, consume_arg_block_coordinate).execute(
+                            &|sender, server, args| {
+                                let entity_type = parse_arg_entity_type(args)?;
+                                let block =
+                                    parse_arg_block_position(sender, [ARG_X, ARG_Y, ARG_Z], args)?
+                                        .0;
+                                // Entities are summoned at the center of the block
+                                let position = Vector3::new(
+                                    f64::from(block.x) + 0.5,
+                                    f64::from(block.y),
+                                    f64::from(block.z) + 0.5,
+                                );
+                                let world = sender_world(sender, server);
+                                summon_at(sender, server, entity_type, world, position);
+                                Ok(())
+                            },
+                        ),
+                    ),
+                )),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::{io::Read, net::TcpStream, sync::Arc, time::Duration};
+
+    use bytes::BytesMut;
+    use mio::Token;
+    use pumpkin_core::math::vector3::Vector3;
+    use pumpkin_entity::entity_type::EntityType;
+    use pumpkin_protocol::{bytebuf::ByteBuffer, ConnectionState};
+
+    use super::summon;
+    use crate::{
+        entity::player::Player,
+        test_helpers::{connected_client, player_of, world},
+        world::World,
+    };
+
+    /// A Player in the World and the other end of their connection
+    fn joined_player(world: &Arc<World>) -> (Arc<Player>, TcpStream) {
+        let (client, remote) = connected_client(Token(1), ConnectionState::Play);
+        let player = player_of(world, client, 2, "Steve");
+        world.add_player(Token(1), player.clone());
+        (player, remote)
+    }
+
+    #[test]
+    fn test_summon() {
+        let world = world("summon");
+        let (player, mut remote) = joined_player(&world);
+
+        let zombie = summon(
+            &world,
+            10,
+            EntityType::Zombie,
+            Vector3::new(3.5, 64.0, -2.5),
+        )
+        .unwrap();
+        assert!(world
+            .get_entity(10)
+            .is_some_and(|entity| Arc::ptr_eq(&entity, &zombie)));
+        assert!(player.tracked_entities.lock().contains(&10));
+
+        // The Player nearby gets the spawn with the type of the entity
+        remote
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut bytes = vec![0; 1024];
+        let read = remote.read(&mut bytes).unwrap();
+        let mut bytebuf = ByteBuffer::new(BytesMut::from(&bytes[..read]));
+        bytebuf.get_var_int().unwrap();
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0x01);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 10);
+        assert_eq!(bytebuf.get_uuid().unwrap(), zombie.uuid);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, EntityType::Zombie as i32);
+        assert_eq!(bytebuf.get_f64().unwrap(), 3.5);
+
+        zombie.remove();
+        assert!(world.get_entity(10).is_none());
+        assert!(!player.tracked_entities.lock().contains(&10));
+    }
+
+    #[test]
+    fn test_summon_out_of_range() {
+        let world = world("summon");
+        let (player, _remote) = joined_player(&world);
+
+        let zombie = summon(
+            &world,
+            10,
+            EntityType::Zombie,
+            Vector3::new(10_000.0, 64.0, 0.0),
+        );
+        assert!(zombie.is_some());
+        assert!(world.get_entity(10).is_some());
+        assert!(!player.tracked_entities.lock().contains(&10));
+    }
+}
@@ -0,0 +1,117 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::commands::arg_targets::{consume_arg_targets, parse_arg_targets};
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+
+const NAMES: [&str; 1] = ["tellraw"];
+
+const DESCRIPTION: &str = "Sends a JSON text component to players.";
+
+const ARG_TARGETS: &str = "targets";
+const ARG_MESSAGE: &str = "message";
+
+/// The JSON may contain spaces, so this takes the rest of the command
+pub fn consume_arg_message(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let mut parts = Vec::new();
+    while let Some(part) = args.pop() {
+        parts.push(part);
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    Some(parts.join(" "))
+}
+
+/// Parses a raw JSON text component, a plain JSON string is a text component too
+fn parse_text(json: &str) -> Result<TextComponent<'static>, String> {
+    let value: Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+    match value {
+        Value::String(text) => TextComponent::deserialize(serde_json::json!({ "text": text })),
+        Value::Object(_) => TextComponent::deserialize(value),
+        _ => return Err("Expected a string or an object".to_string()),
+    }
+    .map_err(|err| err.to_string())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_TARGETS, consume_arg_targets).with_child(
+                argument(ARG_MESSAGE, consume_arg_message).execute(&|sender, server, args| {
+                    let message = args.get(ARG_MESSAGE).ok_or(InvalidConsumptionError(None))?;
+
+                    let text = match parse_text(message) {
+                        Ok(text) => text,
+                        Err(err) => {
+                            sender.send_message(
+                                TextComponent::text(&format!("Invalid chat component: {}", err))
+                                    .color_named(NamedColor::Red),
+                            );
+                            return Ok(());
+                        }
+                    };
+
+                    let players = parse_arg_targets(sender, server, ARG_TARGETS, args)?;
+                    if players.is_empty() {
+                        sender.send_message(
+                            TextComponent::text("No player was found").color_named(NamedColor::Red),
+                        );
+                        return Ok(());
+                    }
+                    for player in players {
+                        player.send_system_message(text.clone());
+                    }
+                    Ok(())
+                }),
+            ),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::text::{color::Color, TextContent};
+
+    use super::{consume_arg_message, parse_text};
+    use crate::commands::CommandSender;
+
+    #[test]
+    fn test_message_takes_the_rest() {
+        // Raw args are reversed
+        let mut args = vec!["\"hi\"}", "{\"text\":"];
+        assert_eq!(
+            consume_arg_message(&CommandSender::Console, &mut args).as_deref(),
+            Some("{\"text\": \"hi\"}")
+        );
+        assert!(args.is_empty());
+        assert!(consume_arg_message(&CommandSender::Console, &mut args).is_none());
+    }
+
+    #[test]
+    fn test_parse_valid() {
+        let text = parse_text(r#"{"text":"Hello","color":"red","bold":true}"#).unwrap();
+        assert!(matches!(text.content, TextContent::Text { ref text } if text == "Hello"));
+        assert!(matches!(text.style.color, Some(Color::Named(_))));
+        assert_eq!(text.style.bold, Some(1));
+
+        let text = parse_text(r#""plain""#).unwrap();
+        assert!(matches!(text.content, TextContent::Text { ref text } if text == "plain"));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse_text(r#"{"text":"Hello""#)
+            .unwrap_err()
+            .contains("EOF while parsing"));
+        assert_eq!(
+            parse_text("42").unwrap_err(),
+            "Expected a string or an object"
+        );
+        assert!(parse_text(r#"{"color":"red"}"#).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+use std::borrow::Cow;
+use std::time::{Duration, Instant};
+
+use pumpkin_config::LIVE_CONFIG;
+use pumpkin_core::text::{click::ClickEvent, color::NamedColor, TextComponent};
+
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, RawArgs};
+use crate::commands::tree_builder::argument;
+use crate::commands::CommandSender;
+
+const NAMES: [&str; 1] = ["tpa"];
+
+const DESCRIPTION: &str = "Asks another player to teleport to them.";
+
+const ARG_TARGET: &str = "player";
+
+/// The target is looked up when the command runs, it may be any online player
+fn consume_arg_name(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    args.pop().map(Into::into)
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(argument(ARG_TARGET, consume_arg_name).execute(
+        &|sender, server, args| {
+            let name = args.get(ARG_TARGET).ok_or(InvalidConsumptionError(None))?;
+            let Some(player) = sender.as_mut_player() else {
+                sender.send_message(TextComponent::text("Only players can teleport"));
+                return Ok(());
+            };
+            let Some(target) = server.get_player_by_name(name) else {
+                player.send_system_message(TextComponent::text(&format!(
+                    "No player called {} is online",
+                    name
+                )));
+                return Ok(());
+            };
+            if target.gameprofile.id == player.gameprofile.id {
+                player.send_system_message(TextComponent::text("You can't teleport to yourself"));
+                return Ok(());
+            }
+
+            let expiry = Duration::from_secs(LIVE_CONFIG.load().teleport_requests.expiry_secs);
+            server.teleport_requests.lock().request(
+                player.gameprofile.id,
+                target.gameprofile.id,
+                Instant::now() + expiry,
+            );
+
+            target.send_system_message(TextComponent::text(&format!(
+                "{} has requested to teleport to you",
+                player.gameprofile.name
+            )));
+            target.send_system_message(
+                TextComponent::text("Click here or type /tpaccept to accept")
+                    .color_named(NamedColor::Green)
+                    .click_event(ClickEvent::RunCommand(Cow::Borrowed("/tpaccept"))),
+            );
+            target.send_system_message(
+                TextComponent::text("Click here or type /tpdeny to deny")
+                    .color_named(NamedColor::Red)
+                    .click_event(ClickEvent::RunCommand(Cow::Borrowed("/tpdeny"))),
+            );
+            player.send_system_message(TextComponent::text(&format!(
+                "Sent a teleport request to {}, it expires in {} seconds",
+                target.gameprofile.name,
+                expiry.as_secs()
+            )));
+            Ok(())
+        },
+    ))
+}
@@ -0,0 +1,53 @@
+use std::time::Instant;
+
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::CommandTree;
+use crate::world::location::Location;
+
+const NAMES: [&str; 1] = ["tpaccept"];
+
+const DESCRIPTION: &str = "Accepts the newest teleport request.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).execute(&|sender, server, _| {
+        let Some(player) = sender.as_mut_player() else {
+            sender.send_message(TextComponent::text(
+                "Only players can accept teleport requests",
+            ));
+            return Ok(());
+        };
+        let request = server
+            .teleport_requests
+            .lock()
+            .take(&player.gameprofile.id, Instant::now());
+        let Some(request) = request else {
+            player
+                .send_system_message(TextComponent::text("You have no pending teleport requests"));
+            return Ok(());
+        };
+        let Some(requester) = server.get_player_by_uuid(&request.requester) else {
+            player.send_system_message(TextComponent::text(
+                "The player who sent the request is no longer online",
+            ));
+            return Ok(());
+        };
+
+        if !Location::from_player(player).teleport(&requester) {
+            player.send_system_message(TextComponent::text(&format!(
+                "{} is in another dimension",
+                requester.gameprofile.name
+            )));
+            return Ok(());
+        }
+        requester.send_system_message(TextComponent::text(&format!(
+            "Teleported to {}",
+            player.gameprofile.name
+        )));
+        player.send_system_message(TextComponent::text(&format!(
+            "Accepted the teleport request of {}",
+            requester.gameprofile.name
+        )));
+        Ok(())
+    })
+}
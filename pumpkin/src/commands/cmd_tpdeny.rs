@@ -0,0 +1,37 @@
+use std::time::Instant;
+
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::CommandTree;
+
+const NAMES: [&str; 1] = ["tpdeny"];
+
+const DESCRIPTION: &str = "Denies the newest teleport request.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).execute(&|sender, server, _| {
+        let Some(player) = sender.as_mut_player() else {
+            sender.send_message(TextComponent::text(
+                "Only players can deny teleport requests",
+            ));
+            return Ok(());
+        };
+        let request = server
+            .teleport_requests
+            .lock()
+            .take(&player.gameprofile.id, Instant::now());
+        let Some(request) = request else {
+            player
+                .send_system_message(TextComponent::text("You have no pending teleport requests"));
+            return Ok(());
+        };
+        if let Some(requester) = server.get_player_by_uuid(&request.requester) {
+            requester.send_system_message(TextComponent::text(&format!(
+                "{} denied your teleport request",
+                player.gameprofile.name
+            )));
+        }
+        player.send_system_message(TextComponent::text("Denied the teleport request"));
+        Ok(())
+    })
+}
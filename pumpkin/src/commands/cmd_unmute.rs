@@ -0,0 +1,47 @@
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::arg_targets::{consume_arg_targets, parse_arg_targets};
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::{argument, require};
+use crate::server::mutes::unix_now;
+
+const NAMES: [&str; 1] = ["unmute"];
+
+const DESCRIPTION: &str = "Allows muted players to chat again.";
+
+const ARG_TARGETS: &str = "targets";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).with_child(
+            argument(ARG_TARGETS, consume_arg_targets).execute(&|sender, server, args| {
+                let players = parse_arg_targets(sender, server, ARG_TARGETS, args)?;
+                let now = unix_now();
+                let mut mutes = server.mutes.write();
+                let mut unmuted = 0;
+                for player in &players {
+                    if mutes.unmute(&player.gameprofile.id, now).is_none() {
+                        continue;
+                    }
+                    unmuted += 1;
+                    player.send_system_message(TextComponent::text("You are no longer muted"));
+                    sender.send_message(TextComponent::text(&format!(
+                        "Unmuted {}",
+                        player.gameprofile.name
+                    )));
+                }
+                if unmuted == 0 {
+                    sender.send_message(
+                        TextComponent::text("No muted player was found")
+                            .color_named(NamedColor::Red),
+                    );
+                    return Ok(());
+                }
+                if let Err(err) = mutes.save() {
+                    log::error!("Couldn't save mutes: {}", err);
+                }
+                Ok(())
+            }),
+        ),
+    )
+}
@@ -0,0 +1,20 @@
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::CommandTree;
+use crate::server::bans::format_timestamp;
+use crate::server::uptime::format_uptime;
+
+const NAMES: [&str; 1] = ["uptime"];
+
+const DESCRIPTION: &str = "Shows how long the server has been running.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).execute(&|sender, server, _| {
+        sender.send_message(TextComponent::text(&format!(
+            "The server has been up for {} (since {})",
+            format_uptime(server.uptime.elapsed().as_secs()),
+            format_timestamp(server.uptime.started_at)
+        )));
+        Ok(())
+    })
+}
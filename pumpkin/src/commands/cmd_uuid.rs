@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+use uuid::Uuid;
+
+use crate::commands::arg_player::consume_arg_player_name;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::argument;
+use crate::server::name_cache::NameCache;
+use crate::world::World;
+
+const NAMES: [&str; 1] = ["uuid"];
+
+const DESCRIPTION: &str = "Prints the UUID of a player.";
+
+const ARG_PLAYER: &str = "player";
+
+/// The name and UUID of an online Player, or of an offline one who joined before
+pub fn find_player(worlds: &[Arc<World>], names: &NameCache, name: &str) -> Option<(String, Uuid)> {
+    let online = worlds
+        .iter()
+        .find_map(|world| world.get_player_by_name(name))
+        .map(|player| (player.gameprofile.name.clone(), player.gameprofile.id));
+    online.or_else(|| {
+        names
+            .get(name)
+            .map(|entry| (entry.name.clone(), entry.uuid))
+    })
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        argument(ARG_PLAYER, consume_arg_player_name).execute(&|sender, server, args| {
+            let name = args.get(ARG_PLAYER).ok_or(InvalidConsumptionError(None))?;
+            let found = find_player(&server.worlds, &server.name_cache.read(), name);
+            let Some((name, uuid)) = found else {
+                sender.send_message(
+                    TextComponent::text(&format!("Unknown player {}", name))
+                        .color_named(NamedColor::Red),
+                );
+                return Ok(());
+            };
+            sender.send_message(TextComponent::text(&format!(
+                "The UUID of {name} is {uuid}"
+            )));
+            Ok(())
+        }),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use mio::Token;
+    use pumpkin_core::GameMode;
+    use pumpkin_protocol::ConnectionState;
+    use uuid::Uuid;
+
+    use super::find_player;
+    use crate::{
+        entity::player::Player,
+        server::name_cache::NameCache,
+        test_helpers::{client, set_profile, world},
+        world::World,
+    };
+
+    fn world_with(name: &str, uuid: Uuid) -> Arc<World> {
+        let world = world("uuid");
+        let client = client(Token(1), ConnectionState::Play);
+        set_profile(&client, name, uuid);
+        let player = Player::new(Arc::new(client), world.clone(), 2, GameMode::Survival);
+        world.add_player(Token(1), Arc::new(player));
+        world
+    }
+
+    #[test]
+    fn test_online_player() {
+        let uuid = Uuid::new_v4();
+        let worlds = [world_with("Steve", uuid)];
+        let names = NameCache::default();
+        assert_eq!(
+            find_player(&worlds, &names, "Steve"),
+            Some(("Steve".to_string(), uuid))
+        );
+        assert_eq!(find_player(&worlds, &names, "Alex"), None);
+    }
+
+    #[test]
+    fn test_offline_player() {
+        let worlds = [world_with("Steve", Uuid::new_v4())];
+        let mut names = NameCache::default();
+        let uuid = Uuid::new_v4();
+        names.insert("Alex", uuid, 100);
+        assert_eq!(
+            find_player(&worlds, &names, "alex"),
+            Some(("Alex".to_string(), uuid))
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::require;
+
+const NAMES: [&str; 1] = ["vanish"];
+
+const DESCRIPTION: &str = "Hides you from other players or shows you again.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2).execute(&|sender, server, _| {
+            let Some(player) = sender.as_mut_player() else {
+                sender.send_message(TextComponent::text("Only players can vanish"));
+                return Ok(());
+            };
+            let vanished = !player.is_vanished();
+            let world = player.entity.world.clone();
+            world.set_vanished(player, vanished);
+            server.update_status();
+            let message = if vanished {
+                "You are now hidden from other players"
+            } else {
+                "You are visible again"
+            };
+            player.send_system_message(TextComponent::text(message));
+            Ok(())
+        }),
+    )
+}
@@ -0,0 +1,92 @@
+use std::sync::Arc;
+
+use pumpkin_config::BASIC_CONFIG;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::arg_targets::{consume_arg_targets, parse_arg_targets};
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, require};
+use crate::commands::CommandSender;
+use crate::entity::player::Player;
+use crate::world::player_chunker;
+
+const NAMES: [&str; 1] = ["viewdistance"];
+
+const DESCRIPTION: &str = "Overrides the view distance of players.";
+
+const ARG_TARGETS: &str = "targets";
+const ARG_DISTANCE: &str = "distance";
+
+fn consume_arg_distance(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    s.parse::<u8>().ok().map(|_| s.into())
+}
+
+fn parse_arg_distance(consumed_args: &ConsumedArgs) -> Result<i8, InvalidTreeError> {
+    let s = consumed_args
+        .get(ARG_DISTANCE)
+        .ok_or(InvalidConsumptionError(None))?;
+    // Anything above the server max gets capped anyway
+    s.parse::<u8>()
+        .map(|distance| distance.min(i8::MAX as u8) as i8)
+        .map_err(|_| InvalidConsumptionError(Some(s.into())))
+}
+
+fn set_view_distance(sender: &mut CommandSender, player: Arc<Player>, distance: i8) {
+    let old_view_distance = player_chunker::get_view_distance(&player) as i32;
+    player.view_distance_override.store(Some(distance));
+    let view_distance = player_chunker::get_view_distance(&player);
+    let mut message = format!(
+        "Set the view distance of {} to {}",
+        player.gameprofile.name, view_distance
+    );
+    if view_distance != distance {
+        message.push_str(&format!(
+            " (the server allows {} to {})",
+            2, BASIC_CONFIG.view_distance
+        ));
+    }
+    sender.send_message(TextComponent::text(&message));
+    tokio::spawn(async move {
+        player_chunker::update_view_distance(&player, old_view_distance).await;
+    });
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .with_child(argument(ARG_DISTANCE, consume_arg_distance).execute(
+                &|sender, server, args| {
+                    let distance = parse_arg_distance(args)?;
+                    let Some(player) = sender.as_mut_player() else {
+                        sender
+                            .send_message(TextComponent::text("Only players have a view distance"));
+                        return Ok(());
+                    };
+                    let Some(player) = server.get_player_by_uuid(&player.gameprofile.id) else {
+                        return Ok(());
+                    };
+                    set_view_distance(sender, player, distance);
+                    Ok(())
+                },
+            ))
+            .with_child(argument(ARG_TARGETS, consume_arg_targets).with_child(
+                argument(ARG_DISTANCE, consume_arg_distance).execute(&|sender, server, args| {
+                    let distance = parse_arg_distance(args)?;
+                    let players = parse_arg_targets(sender, server, ARG_TARGETS, args)?;
+                    if players.is_empty() {
+                        sender.send_message(
+                            TextComponent::text("No player was found").color_named(NamedColor::Red),
+                        );
+                        return Ok(());
+                    }
+                    for player in players {
+                        set_view_distance(sender, player, distance);
+                    }
+                    Ok(())
+                }),
+            )),
+    )
+}
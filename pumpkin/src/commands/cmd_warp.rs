@@ -0,0 +1,52 @@
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::argument;
+use crate::commands::CommandSender;
+use crate::world::location::is_valid_name;
+
+const NAMES: [&str; 1] = ["warp"];
+
+const DESCRIPTION: &str = "Teleports you to a warp.";
+
+pub const ARG_WARP: &str = "name";
+
+pub fn consume_arg_warp(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    is_valid_name(s).then(|| s.into())
+}
+
+pub fn parse_arg_warp<'a>(consumed_args: &'a ConsumedArgs) -> Result<&'a str, InvalidTreeError> {
+    consumed_args
+        .get(ARG_WARP)
+        .map(String::as_str)
+        .ok_or(InvalidConsumptionError(None))
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(argument(ARG_WARP, consume_arg_warp).execute(
+        &|sender, server, args| {
+            let name = parse_arg_warp(args)?;
+            let Some(player) = sender.as_mut_player() else {
+                sender.send_message(TextComponent::text("Only players can use warps"));
+                return Ok(());
+            };
+            let Some(location) = server.warps.read().get(name).cloned() else {
+                player.send_system_message(TextComponent::text(&format!(
+                    "There is no warp called {}, see /warps",
+                    name
+                )));
+                return Ok(());
+            };
+            let message = if location.teleport(player) {
+                format!("Teleported to warp {}", name)
+            } else {
+                format!("Warp {} is in another dimension", name)
+            };
+            player.send_system_message(TextComponent::text(&message));
+            Ok(())
+        },
+    ))
+}
@@ -0,0 +1,21 @@
+use itertools::Itertools;
+use pumpkin_core::text::TextComponent;
+
+use crate::commands::tree::CommandTree;
+
+const NAMES: [&str; 1] = ["warps"];
+
+const DESCRIPTION: &str = "Lists all warps.";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).execute(&|sender, server, _| {
+        let warps = server.warps.read().names().join(", ");
+        let message = if warps.is_empty() {
+            "There are no warps".to_string()
+        } else {
+            format!("Warps: {}", warps)
+        };
+        sender.send_message(TextComponent::text(&message));
+        Ok(())
+    })
+}
@@ -0,0 +1,163 @@
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use pumpkin_core::text::TextComponent;
+use rand::Rng;
+
+use crate::commands::dispatcher::InvalidTreeError;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::{CommandTree, ConsumedArgs, RawArgs};
+use crate::commands::tree_builder::{argument, literal, require};
+use crate::commands::CommandSender;
+use crate::server::Server;
+use crate::world::weather::{CLEAR_DURATION, RAIN_DURATION, THUNDER_DURATION};
+
+const NAMES: [&str; 1] = ["weather"];
+
+const DESCRIPTION: &str = "Sets the weather.";
+
+const ARG_DURATION: &str = "duration";
+
+/// Parses a duration like vanilla does, either in ticks or with a `t`, `s` or `d` suffix
+fn parse_duration(s: &str) -> Option<i32> {
+    let (number, ticks_per_unit) = match s.chars().last()? {
+        't' => (&s[..s.len() - 1], 1),
+        's' => (&s[..s.len() - 1], 20),
+        'd' => (&s[..s.len() - 1], 24000),
+        _ => (s, 1),
+    };
+    let number = number.parse::<i32>().ok()?;
+    if number <= 0 {
+        return None;
+    }
+    number.checked_mul(ticks_per_unit)
+}
+
+pub fn consume_arg_duration(_src: &CommandSender, args: &mut RawArgs) -> Option<String> {
+    let s = args.pop()?;
+    parse_duration(s).map(|_| s.into())
+}
+
+/// Returns the given duration in ticks or a random one in the default range
+pub fn parse_arg_duration(
+    consumed_args: &ConsumedArgs,
+    default: RangeInclusive<i32>,
+) -> Result<i32, InvalidTreeError> {
+    match consumed_args.get(ARG_DURATION) {
+        Some(s) => parse_duration(s).ok_or_else(|| InvalidConsumptionError(Some(s.into()))),
+        None => Ok(rand::thread_rng().gen_range(default)),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum WeatherType {
+    Clear,
+    Rain,
+    Thunder,
+}
+
+fn set_weather(
+    sender: &mut CommandSender,
+    server: &Arc<Server>,
+    args: &ConsumedArgs,
+    weather_type: WeatherType,
+) -> Result<(), InvalidTreeError> {
+    let (clear_time, weather_time, raining, thundering, message) = match weather_type {
+        WeatherType::Clear => (
+            parse_arg_duration(args, CLEAR_DURATION)?,
+            0,
+            false,
+            false,
+            "Set the weather to clear",
+        ),
+        WeatherType::Rain => (
+            0,
+            parse_arg_duration(args, RAIN_DURATION)?,
+            true,
+            false,
+            "Set the weather to rain",
+        ),
+        WeatherType::Thunder => (
+            0,
+            parse_arg_duration(args, THUNDER_DURATION)?,
+            true,
+            true,
+            "Set the weather to rain & thunder",
+        ),
+    };
+
+    // Players change the weather of their own world, the console changes it everywhere
+    if let Some(player) = sender.as_mut_player() {
+        player.entity.world.weather.lock().set_weather(
+            clear_time,
+            weather_time,
+            raining,
+            thundering,
+        );
+    } else {
+        for world in &server.worlds {
+            world
+                .weather
+                .lock()
+                .set_weather(clear_time, weather_time, raining, thundering);
+        }
+    }
+
+    sender.send_message(TextComponent::text(message));
+    Ok(())
+}
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 2)
+            .with_child(
+                literal("clear")
+                    .execute(&|sender, server, args| {
+                        set_weather(sender, server, args, WeatherType::Clear)
+                    })
+                    .with_child(argument(ARG_DURATION, consume_arg_duration).execute(
+                        &|sender, server, args| {
+                            set_weather(sender, server, args, WeatherType::Clear)
+                        },
+                    )),
+            )
+            .with_child(
+                literal("rain")
+                    .execute(&|sender, server, args| {
+                        set_weather(sender, server, args, WeatherType::Rain)
+                    })
+                    .with_child(argument(ARG_DURATION, consume_arg_duration).execute(
+                        &|sender, server, args| {
+                            set_weather(sender, server, args, WeatherType::Rain)
+                        },
+                    )),
+            )
+            .with_child(
+                literal("thunder")
+                    .execute(&|sender, server, args| {
+                        set_weather(sender, server, args, WeatherType::Thunder)
+                    })
+                    .with_child(argument(ARG_DURATION, consume_arg_duration).execute(
+                        &|sender, server, args| {
+                            set_weather(sender, server, args, WeatherType::Thunder)
+                        },
+                    )),
+            ),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_duration;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("100"), Some(100));
+        assert_eq!(parse_duration("100t"), Some(100));
+        assert_eq!(parse_duration("5s"), Some(100));
+        assert_eq!(parse_duration("1d"), Some(24000));
+        assert_eq!(parse_duration("0"), None);
+        assert_eq!(parse_duration("-5s"), None);
+        assert_eq!(parse_duration("rain"), None);
+    }
+}
@@ -0,0 +1,118 @@
+use std::sync::atomic::Ordering;
+
+use itertools::Itertools;
+use pumpkin_core::text::{color::NamedColor, TextComponent};
+
+use crate::commands::arg_player::consume_arg_player_name;
+use crate::commands::dispatcher::InvalidTreeError::InvalidConsumptionError;
+use crate::commands::tree::CommandTree;
+use crate::commands::tree_builder::{argument, literal, require};
+use crate::server::whitelist::Whitelist;
+
+const NAMES: [&str; 1] = ["whitelist"];
+
+const DESCRIPTION: &str = "Manages the players who may join.";
+
+const ARG_PLAYER: &str = "player";
+
+pub fn init_command_tree<'a>() -> CommandTree<'a> {
+    CommandTree::new(NAMES, DESCRIPTION).with_child(
+        require(&|sender| sender.permission_lvl() >= 3)
+            .with_child(literal("add").with_child(
+                argument(ARG_PLAYER, consume_arg_player_name).execute(&|sender, server, args| {
+                    let name = args.get(ARG_PLAYER).ok_or(InvalidConsumptionError(None))?;
+                    // The UUID is only known for online Players, the others are matched by name
+                    let (uuid, name) = match server.get_player_by_name(name) {
+                        Some(player) => {
+                            (Some(player.gameprofile.id), player.gameprofile.name.clone())
+                        }
+                        None => (None, name.clone()),
+                    };
+                    let mut whitelist = server.whitelist.write();
+                    if !whitelist.add(uuid, &name) {
+                        sender.send_message(
+                            TextComponent::text(&format!("{} is already whitelisted", name))
+                                .color_named(NamedColor::Red),
+                        );
+                        return Ok(());
+                    }
+                    if let Err(err) = whitelist.save() {
+                        log::error!("Couldn't save the whitelist: {}", err);
+                    }
+                    sender.send_message(TextComponent::text(&format!(
+                        "Added {} to the whitelist",
+                        name
+                    )));
+                    Ok(())
+                }),
+            ))
+            .with_child(literal("remove").with_child(
+                argument(ARG_PLAYER, consume_arg_player_name).execute(&|sender, server, args| {
+                    let name = args.get(ARG_PLAYER).ok_or(InvalidConsumptionError(None))?;
+                    {
+                        let mut whitelist = server.whitelist.write();
+                        if !whitelist.remove(name) {
+                            sender.send_message(
+                                TextComponent::text(&format!("{} is not whitelisted", name))
+                                    .color_named(NamedColor::Red),
+                            );
+                            return Ok(());
+                        }
+                        if let Err(err) = whitelist.save() {
+                            log::error!("Couldn't save the whitelist: {}", err);
+                        }
+                    }
+                    sender.send_message(TextComponent::text(&format!(
+                        "Removed {} from the whitelist",
+                        name
+                    )));
+                    server.enforce_whitelist();
+                    Ok(())
+                }),
+            ))
+            .with_child(literal("list").execute(&|sender, server, _| {
+                let whitelist = server.whitelist.read();
+                let names = whitelist.names().collect_vec();
+                let message = if names.is_empty() {
+                    "There are no whitelisted players".to_string()
+                } else {
+                    format!(
+                        "There are {} whitelisted players: {}",
+                        names.len(),
+                        names.join(", ")
+                    )
+                };
+                sender.send_message(TextComponent::text(&message));
+                Ok(())
+            }))
+            .with_child(literal("on").execute(&|sender, server, _| {
+                if server.whitelist_enabled.swap(true, Ordering::Relaxed) {
+                    sender.send_message(
+                        TextComponent::text("The whitelist is already turned on")
+                            .color_named(NamedColor::Red),
+                    );
+                    return Ok(());
+                }
+                sender.send_message(TextComponent::text("The whitelist is now turned on"));
+                server.enforce_whitelist();
+                Ok(())
+            }))
+            .with_child(literal("off").execute(&|sender, server, _| {
+                if !server.whitelist_enabled.swap(false, Ordering::Relaxed) {
+                    sender.send_message(
+                        TextComponent::text("The whitelist is already turned off")
+                            .color_named(NamedColor::Red),
+                    );
+                    return Ok(());
+                }
+                sender.send_message(TextComponent::text("The whitelist is now turned off"));
+                Ok(())
+            }))
+            .with_child(literal("reload").execute(&|sender, server, _| {
+                *server.whitelist.write() = Whitelist::load();
+                sender.send_message(TextComponent::text("Reloaded the whitelist"));
+                server.enforce_whitelist();
+                Ok(())
+            })),
+    )
+}
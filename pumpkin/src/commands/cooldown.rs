@@ -0,0 +1,60 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Remembers when a Player last used each command
+#[derive(Default)]
+pub struct CommandCooldowns {
+    last_used: HashMap<String, Instant>,
+}
+
+impl CommandCooldowns {
+    /// Returns how long the Player still has to wait before using the command again
+    pub fn remaining(&self, command: &str, cooldown: Duration, now: Instant) -> Option<Duration> {
+        let last_used = self.last_used.get(command)?;
+        let ready_at = *last_used + cooldown;
+        (ready_at > now).then(|| ready_at - now)
+    }
+
+    /// Starts the cooldown of the command
+    pub fn record(&mut self, command: &str, now: Instant) {
+        self.last_used.insert(command.to_string(), now);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::CommandCooldowns;
+
+    #[test]
+    fn test_blocks_rapid_use() {
+        let mut cooldowns = CommandCooldowns::default();
+        let cooldown = Duration::from_secs(5);
+        let now = Instant::now();
+        assert!(cooldowns.remaining("tp", cooldown, now).is_none());
+        cooldowns.record("tp", now);
+
+        let soon = now + Duration::from_secs(1);
+        assert_eq!(
+            cooldowns.remaining("tp", cooldown, soon),
+            Some(Duration::from_secs(4))
+        );
+        // Other commands have their own cooldown
+        assert!(cooldowns.remaining("home", cooldown, soon).is_none());
+    }
+
+    #[test]
+    fn test_allows_after_cooldown() {
+        let mut cooldowns = CommandCooldowns::default();
+        let cooldown = Duration::from_secs(5);
+        let now = Instant::now();
+        cooldowns.record("tp", now);
+        assert!(cooldowns
+            .remaining("tp", cooldown, now + cooldown)
+            .is_none());
+        assert!(cooldowns.remaining("tp", Duration::ZERO, now).is_none());
+    }
+}
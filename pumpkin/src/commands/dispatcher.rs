@@ -1,3 +1,4 @@
+use pumpkin_config::{BypassLimit, ADVANCED_CONFIG};
 use pumpkin_core::text::TextComponent;
 
 use crate::commands::dispatcher::InvalidTreeError::{
@@ -8,6 +9,7 @@ use crate::commands::CommandSender;
 use crate::server::Server;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Instant;
 
 #[derive(Debug)]
 pub(crate) enum InvalidTreeError {
@@ -48,6 +50,29 @@ impl<'a> CommandDispatcher<'a> {
 
         let tree = self.get_tree(key)?;
 
+        // Cooldowns only apply to Players, aliases share the cooldown of their command
+        let primary_name = tree.names[0];
+        let cooldown_player = match &*src {
+            CommandSender::Player(player) if !player.can_bypass(BypassLimit::CommandCooldowns) => {
+                Some(*player)
+            }
+            _ => None,
+        };
+        if let Some(player) = cooldown_player {
+            let cooldown = ADVANCED_CONFIG.commands.cooldown(primary_name);
+            if let Some(remaining) =
+                player
+                    .command_cooldowns
+                    .lock()
+                    .remaining(primary_name, cooldown, Instant::now())
+            {
+                return Err(format!(
+                    "Please wait {} seconds before using this command again",
+                    remaining.as_secs_f64().ceil() as u64
+                ));
+            }
+        }
+
         // try paths until fitting path is found
         for path in tree.iter_paths() {
             match Self::try_is_fitting_path(src, server, path, tree, raw_args.clone()) {
@@ -61,6 +86,12 @@ impl<'a> CommandDispatcher<'a> {
                 }
                 Ok(is_fitting_path) => {
                     if is_fitting_path {
+                        if let Some(player) = cooldown_player {
+                            player
+                                .command_cooldowns
+                                .lock()
+                                .record(primary_name, Instant::now());
+                        }
                         return Ok(());
                     }
                 }
@@ -8,19 +8,71 @@ use crate::commands::dispatcher::CommandDispatcher;
 use crate::entity::player::Player;
 use crate::server::Server;
 mod arg_player;
+mod arg_position;
+mod arg_targets;
+mod cmd_back;
+mod cmd_ban;
+mod cmd_ban_ip;
+mod cmd_banlist;
+mod cmd_clear;
+mod cmd_config;
+mod cmd_debug;
+mod cmd_delhome;
+mod cmd_delwarp;
+mod cmd_difficulty;
 mod cmd_echest;
+mod cmd_effect;
+mod cmd_enchant;
 mod cmd_gamemode;
+mod cmd_gamerule;
 mod cmd_help;
+mod cmd_home;
 mod cmd_kill;
+mod cmd_me;
+mod cmd_mute;
+mod cmd_near;
+mod cmd_pardon;
+mod cmd_pardon_ip;
 mod cmd_pumpkin;
+mod cmd_ride;
+mod cmd_save_all;
+mod cmd_save_off;
+mod cmd_save_on;
+mod cmd_say;
+mod cmd_seen;
+mod cmd_sethome;
+mod cmd_setwarp;
+mod cmd_spawn;
+mod cmd_spawnpoint;
 mod cmd_stop;
+mod cmd_summon;
+mod cmd_tellraw;
+mod cmd_tpa;
+mod cmd_tpaccept;
+mod cmd_tpdeny;
+mod cmd_unmute;
+mod cmd_uptime;
+mod cmd_uuid;
+mod cmd_vanish;
+mod cmd_viewdistance;
+mod cmd_warp;
+mod cmd_warps;
+mod cmd_weather;
+mod cmd_whitelist;
+pub mod cooldown;
 pub mod dispatcher;
+pub mod target_selector;
 mod tree;
 mod tree_builder;
 mod tree_format;
 
 pub enum CommandSender<'a> {
-    Rcon(&'a mut Vec<String>),
+    /// Output is captured into `buffer` and sent back to the RCON client once the command finished
+    Rcon {
+        buffer: &'a mut Vec<String>,
+        /// Limits which commands the connection can run, see `RCONConfig::permission_level`
+        permission_lvl: i32,
+    },
     Console,
     Player(&'a Player),
 }
@@ -31,7 +83,7 @@ impl<'a> CommandSender<'a> {
             // TODO: add color and stuff to console
             CommandSender::Console => log::info!("{}", text.to_pretty_console()),
             CommandSender::Player(c) => c.send_system_message(text),
-            CommandSender::Rcon(s) => s.push(text.to_pretty_console()),
+            CommandSender::Rcon { buffer, .. } => buffer.push(text.to_pretty_console()),
         }
     }
 
@@ -39,7 +91,7 @@ impl<'a> CommandSender<'a> {
         match self {
             CommandSender::Console => false,
             CommandSender::Player(_) => true,
-            CommandSender::Rcon(_) => false,
+            CommandSender::Rcon { .. } => false,
         }
     }
 
@@ -47,21 +99,21 @@ impl<'a> CommandSender<'a> {
         match self {
             CommandSender::Console => true,
             CommandSender::Player(_) => false,
-            CommandSender::Rcon(_) => true,
+            CommandSender::Rcon { .. } => true,
         }
     }
     pub fn as_mut_player(&mut self) -> Option<&Player> {
         match self {
             CommandSender::Player(player) => Some(player),
             CommandSender::Console => None,
-            CommandSender::Rcon(_) => None,
+            CommandSender::Rcon { .. } => None,
         }
     }
 
     /// todo: implement
     pub const fn permission_lvl(&self) -> i32 {
         match self {
-            CommandSender::Rcon(_) => 4,
+            CommandSender::Rcon { permission_lvl, .. } => *permission_lvl,
             CommandSender::Console => 4,
             CommandSender::Player(_) => 4,
         }
@@ -77,9 +129,97 @@ pub fn default_dispatcher<'a>() -> CommandDispatcher<'a> {
     dispatcher.register(cmd_help::init_command_tree());
     dispatcher.register(cmd_echest::init_command_tree());
     dispatcher.register(cmd_kill::init_command_tree());
+    dispatcher.register(cmd_effect::init_command_tree());
+    dispatcher.register(cmd_weather::init_command_tree());
+    dispatcher.register(cmd_difficulty::init_command_tree());
+    dispatcher.register(cmd_gamerule::init_command_tree());
+    dispatcher.register(cmd_home::init_command_tree());
+    dispatcher.register(cmd_sethome::init_command_tree());
+    dispatcher.register(cmd_delhome::init_command_tree());
+    dispatcher.register(cmd_warp::init_command_tree());
+    dispatcher.register(cmd_warps::init_command_tree());
+    dispatcher.register(cmd_setwarp::init_command_tree());
+    dispatcher.register(cmd_delwarp::init_command_tree());
+    dispatcher.register(cmd_tpa::init_command_tree());
+    dispatcher.register(cmd_tpaccept::init_command_tree());
+    dispatcher.register(cmd_tpdeny::init_command_tree());
+    dispatcher.register(cmd_back::init_command_tree());
+    dispatcher.register(cmd_tellraw::init_command_tree());
+    dispatcher.register(cmd_mute::init_command_tree());
+    dispatcher.register(cmd_unmute::init_command_tree());
+    dispatcher.register(cmd_say::init_command_tree());
+    dispatcher.register(cmd_me::init_command_tree());
+    dispatcher.register(cmd_viewdistance::init_command_tree());
+    dispatcher.register(cmd_whitelist::init_command_tree());
+    dispatcher.register(cmd_ban::init_command_tree());
+    dispatcher.register(cmd_ban_ip::init_command_tree());
+    dispatcher.register(cmd_pardon::init_command_tree());
+    dispatcher.register(cmd_pardon_ip::init_command_tree());
+    dispatcher.register(cmd_banlist::init_command_tree());
+    dispatcher.register(cmd_spawnpoint::init_command_tree());
+    dispatcher.register(cmd_save_all::init_command_tree());
+    dispatcher.register(cmd_save_off::init_command_tree());
+    dispatcher.register(cmd_save_on::init_command_tree());
+    dispatcher.register(cmd_config::init_command_tree());
+    dispatcher.register(cmd_debug::init_command_tree());
+    dispatcher.register(cmd_spawn::init_command_tree());
+    dispatcher.register(cmd_uuid::init_command_tree());
+    dispatcher.register(cmd_seen::init_command_tree());
+    dispatcher.register(cmd_vanish::init_command_tree());
+    dispatcher.register(cmd_summon::init_command_tree());
+    dispatcher.register(cmd_ride::init_command_tree());
+    dispatcher.register(cmd_clear::init_command_tree());
+    dispatcher.register(cmd_near::init_command_tree());
+    dispatcher.register(cmd_uptime::init_command_tree());
+    dispatcher.register(cmd_enchant::init_command_tree());
 
     dispatcher
 }
 
 type RunFunctionType = (dyn Fn(&mut CommandSender, &Arc<Server>, &ConsumedArgs) -> Result<(), InvalidTreeError>
      + Sync);
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::text::TextComponent;
+
+    use super::{cmd_stop, tree::NodeType, CommandSender};
+
+    #[test]
+    fn rcon_output_is_captured() {
+        let mut buffer = Vec::new();
+        let mut sender = CommandSender::Rcon {
+            buffer: &mut buffer,
+            permission_lvl: 4,
+        };
+        assert!(sender.is_console());
+        assert!(!sender.is_player());
+        assert!(sender.as_mut_player().is_none());
+
+        sender.send_message(TextComponent::text("Saved the game"));
+        assert_eq!(buffer, vec!["Saved the game".to_string()]);
+    }
+
+    #[test]
+    fn rcon_permission_level() {
+        let mut buffer = Vec::new();
+        let tree = cmd_stop::init_command_tree();
+        let permitted = |sender: &CommandSender| {
+            tree.nodes.iter().all(|node| match node.node_type {
+                NodeType::Require { predicate } => predicate(sender),
+                _ => true,
+            })
+        };
+        let restricted = CommandSender::Rcon {
+            buffer: &mut buffer,
+            permission_lvl: 3,
+        };
+        assert_eq!(restricted.permission_lvl(), 3);
+        assert!(!permitted(&restricted));
+        let full = CommandSender::Rcon {
+            buffer: &mut Vec::new(),
+            permission_lvl: 4,
+        };
+        assert!(permitted(&full));
+    }
+}
@@ -0,0 +1,359 @@
+use std::{str::FromStr, sync::Arc};
+
+use pumpkin_core::math::vector3::Vector3;
+
+use crate::commands::CommandSender;
+use crate::entity::player::Player;
+use crate::server::Server;
+
+/// Which targets a selector starts with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectorKind {
+    /// `@a`, all players
+    AllPlayers,
+    /// `@p`, the player nearest to the sender
+    NearestPlayer,
+    /// `@s`, the sender itself
+    Sender,
+    /// `@e`, all entities
+    AllEntities,
+}
+
+/// A distance like `5`, `..5`, `2..` or `2..5`, both ends are inclusive
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DistanceRange {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+impl DistanceRange {
+    pub fn contains(&self, distance: f64) -> bool {
+        self.min.is_none_or(|min| distance >= min) && self.max.is_none_or(|max| distance <= max)
+    }
+}
+
+impl FromStr for DistanceRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |s: &str| -> Result<Option<f64>, String> {
+            if s.is_empty() {
+                return Ok(None);
+            }
+            match s.parse::<f64>() {
+                Ok(distance) if distance >= 0.0 => Ok(Some(distance)),
+                _ => Err(format!("Invalid distance {}", s)),
+            }
+        };
+        let range = match s.split_once("..") {
+            Some((min, max)) => Self {
+                min: parse(min)?,
+                max: parse(max)?,
+            },
+            None => {
+                let distance = parse(s)?;
+                Self {
+                    min: distance,
+                    max: distance,
+                }
+            }
+        };
+        match (range.min, range.max) {
+            (None, None) => Err("Expected a distance".to_string()),
+            (Some(min), Some(max)) if min > max => {
+                Err("The minimum distance can't be bigger than the maximum".to_string())
+            }
+            _ => Ok(range),
+        }
+    }
+}
+
+/// Only keeps targets with this name, or all others when inverted with `name=!<name>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameFilter {
+    pub name: String,
+    pub inverted: bool,
+}
+
+/// A selector like `@a[distance=..10,limit=2]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntitySelector {
+    pub kind: SelectorKind,
+    pub distance: Option<DistanceRange>,
+    pub limit: Option<usize>,
+    pub name: Option<NameFilter>,
+}
+
+/// The target of a command, either a player name or a selector
+#[derive(Debug, Clone, PartialEq)]
+pub enum TargetSelector {
+    Name(String),
+    Selector(EntitySelector),
+}
+
+impl FromStr for TargetSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some(selector) = s.strip_prefix('@') else {
+            if s.is_empty() {
+                return Err("Expected a player name or selector".to_string());
+            }
+            return Ok(Self::Name(s.to_string()));
+        };
+        let (kind, options) = match selector.find('[') {
+            Some(i) => (&selector[..i], Some(&selector[i..])),
+            None => (selector, None),
+        };
+        let kind = match kind {
+            "a" => SelectorKind::AllPlayers,
+            "p" => SelectorKind::NearestPlayer,
+            "s" => SelectorKind::Sender,
+            "e" => SelectorKind::AllEntities,
+            _ => return Err(format!("Unknown selector type @{}", kind)),
+        };
+        let mut selector = EntitySelector {
+            kind,
+            distance: None,
+            limit: None,
+            name: None,
+        };
+
+        if let Some(options) = options {
+            let options = options
+                .strip_prefix('[')
+                .and_then(|options| options.strip_suffix(']'))
+                .ok_or_else(|| "Expected ']' at the end of the selector".to_string())?;
+            for option in options.split(',').filter(|option| !option.is_empty()) {
+                let (key, value) = option
+                    .split_once('=')
+                    .ok_or_else(|| format!("Expected a value for {}", option))?;
+                match key {
+                    "distance" => selector.distance = Some(value.parse()?),
+                    "limit" => match value.parse::<usize>() {
+                        Ok(limit) if limit > 0 => selector.limit = Some(limit),
+                        _ => return Err(format!("Invalid limit {}", value)),
+                    },
+                    "name" => {
+                        let (name, inverted) = match value.strip_prefix('!') {
+                            Some(name) => (name, true),
+                            None => (value, false),
+                        };
+                        selector.name = Some(NameFilter {
+                            name: name.to_string(),
+                            inverted,
+                        });
+                    }
+                    _ => return Err(format!("Unknown selector option {}", key)),
+                }
+            }
+        }
+        Ok(Self::Selector(selector))
+    }
+}
+
+/// What selectors need to know about a possible target
+pub trait Target: Clone {
+    fn name(&self) -> &str;
+
+    fn position(&self) -> Vector3<f64>;
+}
+
+impl Target for Arc<Player> {
+    fn name(&self) -> &str {
+        &self.gameprofile.name
+    }
+
+    fn position(&self) -> Vector3<f64> {
+        self.entity.pos.load()
+    }
+}
+
+impl TargetSelector {
+    /// Selects the targets out of the candidates, `sender` is the target running the command if there is one.
+    ///
+    /// Distances are measured from the sender, or from the world origin for the console
+    pub fn select<T: Target>(&self, candidates: &[T], sender: Option<&T>) -> Vec<T> {
+        let selector = match self {
+            Self::Name(name) => {
+                return candidates
+                    .iter()
+                    .find(|candidate| candidate.name() == name)
+                    .cloned()
+                    .into_iter()
+                    .collect()
+            }
+            Self::Selector(selector) => selector,
+        };
+
+        let origin = sender.map_or(Vector3::new(0.0, 0.0, 0.0), Target::position);
+        let distance = |target: &T| target.position().sub(&origin).length();
+
+        let mut targets: Vec<T> = match selector.kind {
+            SelectorKind::Sender => sender.cloned().into_iter().collect(),
+            // There are only players for now
+            SelectorKind::AllPlayers | SelectorKind::NearestPlayer | SelectorKind::AllEntities => {
+                candidates.to_vec()
+            }
+        };
+        targets.retain(|target| {
+            selector
+                .distance
+                .is_none_or(|range| range.contains(distance(target)))
+                && selector
+                    .name
+                    .as_ref()
+                    .is_none_or(|filter| (target.name() == filter.name) != filter.inverted)
+        });
+
+        let limit = if selector.kind == SelectorKind::NearestPlayer {
+            targets.sort_by(|a, b| distance(a).total_cmp(&distance(b)));
+            selector.limit.unwrap_or(1)
+        } else {
+            selector.limit.unwrap_or(usize::MAX)
+        };
+        targets.truncate(limit);
+        targets
+    }
+
+    /// Selects out of the online Players
+    pub fn select_players(&self, sender: &mut CommandSender, server: &Server) -> Vec<Arc<Player>> {
+        let players = server.get_all_players();
+        let sender = sender.as_mut_player().and_then(|sender| {
+            players
+                .iter()
+                .find(|player| player.gameprofile.id == sender.gameprofile.id)
+                .cloned()
+        });
+        self.select(&players, sender.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use pumpkin_core::math::vector3::Vector3;
+
+    use super::{DistanceRange, SelectorKind, Target, TargetSelector};
+
+    #[derive(Clone, Debug)]
+    struct MockTarget {
+        name: &'static str,
+        position: Vector3<f64>,
+    }
+
+    impl Target for MockTarget {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn position(&self) -> Vector3<f64> {
+            self.position
+        }
+    }
+
+    fn world() -> Vec<MockTarget> {
+        [
+            ("Steve", 0.0),
+            ("Alex", 3.0),
+            ("Notch", 20.0),
+            ("Jeb", -8.0),
+        ]
+        .into_iter()
+        .map(|(name, x)| MockTarget {
+            name,
+            position: Vector3::new(x, 64.0, 0.0),
+        })
+        .collect()
+    }
+
+    fn select(selector: &str, sender: Option<usize>) -> Vec<&'static str> {
+        let world = world();
+        let sender = sender.map(|i| &world[i]);
+        TargetSelector::from_str(selector)
+            .unwrap()
+            .select(&world, sender)
+            .into_iter()
+            .map(|target| target.name)
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            TargetSelector::from_str("Steve"),
+            Ok(TargetSelector::Name("Steve".to_string()))
+        );
+        let TargetSelector::Selector(selector) =
+            TargetSelector::from_str("@e[limit=2,distance=1..5,name=!Steve]").unwrap()
+        else {
+            panic!("expected a selector");
+        };
+        assert_eq!(selector.kind, SelectorKind::AllEntities);
+        assert_eq!(selector.limit, Some(2));
+        assert_eq!(
+            selector.distance,
+            Some(DistanceRange {
+                min: Some(1.0),
+                max: Some(5.0)
+            })
+        );
+        let name = selector.name.unwrap();
+        assert_eq!(name.name, "Steve");
+        assert!(name.inverted);
+
+        assert!(TargetSelector::from_str("@x").is_err());
+        assert!(TargetSelector::from_str("@a[limit=0]").is_err());
+        assert!(TargetSelector::from_str("@a[distance=5..1]").is_err());
+        assert!(TargetSelector::from_str("@a[limit=2").is_err());
+        assert!(TargetSelector::from_str("@a[foo=bar]").is_err());
+    }
+
+    #[test]
+    fn test_distance_range() {
+        let range: DistanceRange = "..5".parse().unwrap();
+        assert!(range.contains(0.0) && range.contains(5.0) && !range.contains(5.1));
+        let range: DistanceRange = "2..".parse().unwrap();
+        assert!(!range.contains(1.9) && range.contains(100.0));
+        let range: DistanceRange = "3".parse().unwrap();
+        assert!(range.contains(3.0) && !range.contains(3.5));
+        assert!("..".parse::<DistanceRange>().is_err());
+        assert!("-1".parse::<DistanceRange>().is_err());
+    }
+
+    #[test]
+    fn test_name() {
+        assert_eq!(select("Alex", None), vec!["Alex"]);
+        assert!(select("Herobrine", None).is_empty());
+    }
+
+    #[test]
+    fn test_all() {
+        assert_eq!(select("@a", None), vec!["Steve", "Alex", "Notch", "Jeb"]);
+        assert_eq!(select("@e[limit=2]", None), vec!["Steve", "Alex"]);
+        assert_eq!(
+            select("@a[name=!Steve]", None),
+            vec!["Alex", "Notch", "Jeb"]
+        );
+        assert_eq!(select("@e[name=Jeb]", None), vec!["Jeb"]);
+        // Measured from Alex at x=3
+        assert_eq!(select("@a[distance=..5]", Some(1)), vec!["Steve", "Alex"]);
+    }
+
+    #[test]
+    fn test_nearest() {
+        // The sender is the nearest player to themself
+        assert_eq!(select("@p", Some(2)), vec!["Notch"]);
+        assert_eq!(select("@p[distance=1..]", Some(2)), vec!["Alex"]);
+        assert_eq!(select("@p[limit=3]", Some(0)), vec!["Steve", "Alex", "Jeb"]);
+        assert_eq!(select("@p[name=!Steve]", None), vec!["Alex"]);
+    }
+
+    #[test]
+    fn test_sender() {
+        assert_eq!(select("@s", Some(3)), vec!["Jeb"]);
+        assert!(select("@s", None).is_empty());
+        assert!(select("@s[name=Steve]", Some(3)).is_empty());
+    }
+}
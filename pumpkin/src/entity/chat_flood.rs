@@ -0,0 +1,120 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+use pumpkin_config::ChatFloodConfig;
+
+/// Why a chat message was rejected
+#[derive(Debug, PartialEq, Eq)]
+pub enum Flood {
+    /// Too many messages in a short time
+    TooFast,
+    /// The same message too often in a row
+    Repeated,
+}
+
+/// Remembers the recent chat messages of a Player
+#[derive(Default)]
+pub struct ChatFlood {
+    sent: VecDeque<Instant>,
+    last_message: String,
+    repeats: u32,
+}
+
+impl ChatFlood {
+    /// Checks if the Player may send the message, allowed messages are recorded
+    pub fn check(
+        &mut self,
+        message: &str,
+        now: Instant,
+        config: &ChatFloodConfig,
+    ) -> Result<(), Flood> {
+        let interval = Duration::from_secs(config.interval_secs);
+        while self
+            .sent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= interval)
+        {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= config.max_messages {
+            return Err(Flood::TooFast);
+        }
+
+        // Changing the case or adding spaces does not make a message new
+        let message = message.trim().to_lowercase();
+        let repeats = if message == self.last_message {
+            self.repeats + 1
+        } else {
+            1
+        };
+        if repeats > config.max_repeats {
+            return Err(Flood::Repeated);
+        }
+
+        self.sent.push_back(now);
+        self.last_message = message;
+        self.repeats = repeats;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use pumpkin_config::ChatFloodConfig;
+
+    use super::{ChatFlood, Flood};
+
+    fn config() -> ChatFloodConfig {
+        ChatFloodConfig {
+            max_messages: 5,
+            interval_secs: 10,
+            max_repeats: 2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_normal_cadence_passes() {
+        let config = config();
+        let mut flood = ChatFlood::default();
+        let now = Instant::now();
+        // One message every 3 seconds is never more than 5 in 10 seconds
+        for i in 0..20 {
+            let sent = now + Duration::from_secs(3 * i);
+            assert_eq!(flood.check(&format!("hello {}", i), sent, &config), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_rapid_messages_trip() {
+        let config = config();
+        let mut flood = ChatFlood::default();
+        let now = Instant::now();
+        for i in 0..5 {
+            assert_eq!(flood.check(&format!("message {}", i), now, &config), Ok(()));
+        }
+        assert_eq!(flood.check("one more", now, &config), Err(Flood::TooFast));
+        // The window moves on
+        let later = now + Duration::from_secs(10);
+        assert_eq!(flood.check("one more", later, &config), Ok(()));
+    }
+
+    #[test]
+    fn test_identical_messages_trip() {
+        let config = config();
+        let mut flood = ChatFlood::default();
+        let now = Instant::now();
+        assert_eq!(flood.check("buy diamonds", now, &config), Ok(()));
+        assert_eq!(flood.check("Buy Diamonds ", now, &config), Ok(()));
+        assert_eq!(
+            flood.check("buy diamonds", now, &config),
+            Err(Flood::Repeated)
+        );
+        assert_eq!(flood.check("something else", now, &config), Ok(()));
+        assert_eq!(flood.check("buy diamonds", now, &config), Ok(()));
+    }
+}
@@ -0,0 +1,83 @@
+use pumpkin_protocol::client::play::CSetExperience;
+
+/// Like vanilla, Players drop 7 points per level when they die, but never more than this
+const MAX_DEATH_DROP: u32 = 100;
+
+/// How many points it takes to get from `level` to the next one, like vanilla
+pub const fn points_to_next_level(level: u32) -> u32 {
+    match level {
+        0..=15 => 2 * level + 7,
+        16..=30 => 5 * level - 38,
+        _ => 9 * level - 158,
+    }
+}
+
+/// The experience of a Player, only the total is stored and the level is calculated from it
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Experience {
+    pub total: u32,
+}
+
+impl Experience {
+    pub const fn new(total: u32) -> Self {
+        Self { total }
+    }
+
+    /// The level and the points collected towards the next one
+    pub fn level(&self) -> (u32, u32) {
+        let mut level = 0;
+        let mut points = self.total;
+        while points >= points_to_next_level(level) {
+            points -= points_to_next_level(level);
+            level += 1;
+        }
+        (level, points)
+    }
+
+    pub fn add(&mut self, points: u32) {
+        self.total = self.total.saturating_add(points);
+    }
+
+    /// How many points a Player with this experience drops when dying
+    pub fn death_drop(&self) -> u32 {
+        (self.level().0 * 7).min(MAX_DEATH_DROP)
+    }
+
+    pub fn packet(&self) -> CSetExperience {
+        let (level, points) = self.level();
+        let bar = points as f32 / points_to_next_level(level) as f32;
+        CSetExperience::new(bar, (level as i32).into(), (self.total as i32).into())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{points_to_next_level, Experience};
+
+    #[test]
+    fn test_points_to_next_level() {
+        assert_eq!(points_to_next_level(0), 7);
+        assert_eq!(points_to_next_level(15), 37);
+        assert_eq!(points_to_next_level(16), 42);
+        assert_eq!(points_to_next_level(30), 112);
+        assert_eq!(points_to_next_level(31), 121);
+    }
+
+    #[test]
+    fn test_level() {
+        assert_eq!(Experience::new(0).level(), (0, 0));
+        assert_eq!(Experience::new(6).level(), (0, 6));
+        assert_eq!(Experience::new(7).level(), (1, 0));
+        // Vanilla needs 352 points for level 16 and 1395 for level 30
+        assert_eq!(Experience::new(352).level(), (16, 0));
+        assert_eq!(Experience::new(1394).level(), (29, 106));
+        assert_eq!(Experience::new(1395).level(), (30, 0));
+    }
+
+    #[test]
+    fn test_death_drop() {
+        assert_eq!(Experience::new(6).death_drop(), 0);
+        assert_eq!(Experience::new(16).death_drop(), 14);
+        assert_eq!(Experience::new(1395).death_drop(), 100);
+    }
+}
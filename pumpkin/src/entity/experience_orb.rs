@@ -0,0 +1,156 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use pumpkin_core::{math::vector3::Vector3, GameMode};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::client::play::{CPickupItem, CSpawnExperienceOrb};
+use uuid::Uuid;
+
+use crate::world::World;
+
+use super::{player::Player, Entity};
+
+/// Like items, Players collect orbs which are at most this far away from their bounding box
+const PICKUP_RANGE: (f64, f64, f64) = (1.0, 0.5, 1.0);
+
+/// Experience lying on the ground, until a Player collects it
+pub struct ExperienceOrb {
+    pub entity: Arc<Entity>,
+    /// How many points the orb is worth, it grows when other orbs merge into it
+    pub value: AtomicU32,
+}
+
+impl ExperienceOrb {
+    pub fn new(entity: Arc<Entity>, value: u32) -> Self {
+        Self {
+            entity,
+            value: AtomicU32::new(value),
+        }
+    }
+
+    /// Orbs are spawned with their own packet instead of `CSpawnEntity`
+    pub fn spawn_packet(&self) -> CSpawnExperienceOrb {
+        let pos = self.entity.pos.load();
+        let count = self.value.load(Ordering::Relaxed).min(i16::MAX as u32) as i16;
+        CSpawnExperienceOrb::new(self.entity.entity_id.into(), pos.x, pos.y, pos.z, count)
+    }
+}
+
+/// Drops the experience at the position, an orb within `merge_radius` blocks gets it instead of a new one.
+///
+/// Returns the orb which holds the experience, `None` if the chunk already holds too many entities
+pub fn drop_experience(
+    world: &Arc<World>,
+    entity_id: EntityId,
+    value: u32,
+    position: Vector3<f64>,
+    merge_radius: f64,
+) -> Option<Arc<ExperienceOrb>> {
+    let nearby = world
+        .experience_orbs
+        .lock()
+        .values()
+        .find(|orb| {
+            orb.entity.pos.load().sub(&position).length_squared() <= merge_radius * merge_radius
+        })
+        .cloned();
+    if let Some(orb) = nearby {
+        orb.value.fetch_add(value, Ordering::Relaxed);
+        return Some(orb);
+    }
+    let entity = Arc::new(Entity::new(
+        entity_id,
+        Uuid::new_v4(),
+        world.clone(),
+        EntityType::ExperienceOrb,
+    ));
+    entity.set_pos(position.x, position.y, position.z);
+    let orb = Arc::new(ExperienceOrb::new(entity, value));
+    world.spawn_experience_orb(orb.clone()).then_some(orb)
+}
+
+/// Gives the Player the experience of the orbs in range and removes them.
+///
+/// Returns how many points were collected
+pub fn pickup_experience(world: &World, player: &Player) -> u32 {
+    if player.entity.health.load() <= 0.0 || player.gamemode.load() == GameMode::Spectator {
+        return 0;
+    }
+    let (x, y, z) = PICKUP_RANGE;
+    let range = player.entity.bounding_box().expand(x, y, z);
+    let in_range: Vec<_> = world
+        .experience_orbs
+        .lock()
+        .values()
+        .filter(|orb| range.intersects(&orb.entity.bounding_box()))
+        .cloned()
+        .collect();
+    let mut collected = 0;
+    for orb in in_range {
+        world.broadcast_packet_all(&CPickupItem::new(
+            orb.entity.entity_id.into(),
+            player.entity_id().into(),
+            1.into(),
+        ));
+        orb.entity.remove();
+        collected += orb.value.load(Ordering::Relaxed);
+    }
+    if collected > 0 {
+        player.add_experience(collected);
+    }
+    collected
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use pumpkin_core::{math::vector3::Vector3, GameMode};
+
+    use super::{drop_experience, pickup_experience};
+    use crate::test_helpers::{joined_player, world};
+
+    #[test]
+    fn test_pickup() {
+        let world = world("experience-orb");
+        let player = joined_player(&world);
+        player.entity.set_pos(0.5, 64.0, 0.5);
+        let far = drop_experience(&world, 10, 3, Vector3::new(5.5, 64.0, 0.5), 0.5).unwrap();
+        let near = drop_experience(&world, 11, 9, Vector3::new(1.5, 64.0, 0.5), 0.5).unwrap();
+
+        assert_eq!(pickup_experience(&world, &player), 9);
+        assert_eq!(player.experience.lock().level(), (1, 2));
+        assert!(world.get_entity(near.entity.entity_id).is_none());
+        assert!(world.experience_orbs.lock().get(&11).is_none());
+        // Out of range
+        assert!(world.get_entity(far.entity.entity_id).is_some());
+        assert_eq!(pickup_experience(&world, &player), 0);
+    }
+
+    #[test]
+    fn test_spectators_collect_nothing() {
+        let world = world("experience-orb");
+        let player = joined_player(&world);
+        player.entity.set_pos(0.5, 64.0, 0.5);
+        player.gamemode.store(GameMode::Spectator);
+        drop_experience(&world, 10, 3, Vector3::new(0.5, 64.0, 0.5), 0.5).unwrap();
+        assert_eq!(pickup_experience(&world, &player), 0);
+        assert_eq!(player.experience.lock().total, 0);
+    }
+
+    #[test]
+    fn test_merge() {
+        let world = world("experience-orb");
+        let first = drop_experience(&world, 10, 3, Vector3::new(0.5, 64.0, 0.5), 0.5).unwrap();
+        let merged = drop_experience(&world, 11, 4, Vector3::new(0.8, 64.2, 0.5), 0.5).unwrap();
+        assert!(Arc::ptr_eq(&first, &merged));
+        assert_eq!(merged.value.load(std::sync::atomic::Ordering::Relaxed), 7);
+        assert!(world.get_entity(11).is_none());
+        // Too far away to merge
+        let other = drop_experience(&world, 12, 2, Vector3::new(2.5, 64.0, 0.5), 0.5).unwrap();
+        assert_eq!(other.entity.entity_id, 12);
+        assert_eq!(world.experience_orbs.lock().len(), 2);
+    }
+}
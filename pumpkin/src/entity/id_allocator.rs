@@ -0,0 +1,76 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
+use pumpkin_entity::EntityId;
+
+/// Hands out entity ids which are unique across the whole Server.
+///
+/// Players, mobs and items all share these ids, they have nothing to do with the connection token of a Client
+pub struct EntityIdAllocator {
+    first: EntityId,
+    next: AtomicI32,
+}
+
+impl EntityIdAllocator {
+    pub const fn new(first: EntityId) -> Self {
+        Self {
+            first,
+            next: AtomicI32::new(first),
+        }
+    }
+
+    pub fn allocate(&self) -> EntityId {
+        // Entity ids must be positive, so start over instead of overflowing
+        self.next
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |id| {
+                Some(if id == EntityId::MAX {
+                    self.first
+                } else {
+                    id + 1
+                })
+            })
+            .unwrap_or(self.first)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, sync::Arc, thread};
+
+    use pumpkin_entity::EntityId;
+
+    use super::EntityIdAllocator;
+
+    #[test]
+    fn test_no_duplicates() {
+        let allocator = Arc::new(EntityIdAllocator::new(1));
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let allocator = allocator.clone();
+                thread::spawn(move || {
+                    (0..10_000)
+                        .map(|_| allocator.allocate())
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let mut ids = HashSet::new();
+        for thread in threads {
+            for id in thread.join().unwrap() {
+                assert!(id > 0);
+                assert!(ids.insert(id), "duplicate entity id {}", id);
+            }
+        }
+        assert_eq!(ids.len(), 40_000);
+    }
+
+    #[test]
+    fn test_wraps_to_first() {
+        let allocator = EntityIdAllocator::new(2);
+        allocator
+            .next
+            .store(EntityId::MAX, std::sync::atomic::Ordering::SeqCst);
+        assert_eq!(allocator.allocate(), EntityId::MAX);
+        assert_eq!(allocator.allocate(), 2);
+        assert_eq!(allocator.allocate(), 3);
+    }
+}
@@ -0,0 +1,250 @@
+use std::sync::{
+    atomic::{AtomicU32, Ordering},
+    Arc,
+};
+
+use parking_lot::Mutex;
+use pumpkin_core::{math::vector3::Vector3, GameMode};
+use pumpkin_entity::{entity_type::EntityType, EntityId};
+use pumpkin_protocol::{
+    client::play::{CPickupItem, CSetEntityMetadata, Metadata},
+    slot::Slot,
+};
+use pumpkin_world::item::ItemStack;
+use uuid::Uuid;
+
+use crate::world::World;
+
+use super::{player::Player, Entity};
+
+/// Like vanilla, Players can take items which are at most this far away from their bounding box
+const PICKUP_RANGE: (f64, f64, f64) = (1.0, 0.5, 1.0);
+
+/// Items dropped by Players can't be picked up for 2 seconds, so they don't take them right back
+pub const PLAYER_DROP_PICKUP_DELAY: u32 = 40;
+
+/// A stack of items lying on the ground, until a Player picks it up
+pub struct ItemEntity {
+    pub entity: Arc<Entity>,
+    pub item: Mutex<ItemStack>,
+    /// Ticks until the item can be picked up
+    pub pickup_delay: AtomicU32,
+}
+
+impl ItemEntity {
+    pub fn new(entity: Arc<Entity>, item: ItemStack, pickup_delay: u32) -> Self {
+        Self {
+            entity,
+            item: Mutex::new(item),
+            pickup_delay: AtomicU32::new(pickup_delay),
+        }
+    }
+
+    /// Tells viewers which item it is, without it the client shows nothing
+    pub fn metadata_packet(&self) -> CSetEntityMetadata<Slot> {
+        let item = Slot::from(&*self.item.lock());
+        // The item is at index 8 and has the slot type 7
+        CSetEntityMetadata::new(
+            self.entity.entity_id.into(),
+            Metadata::new(8, 7.into(), item),
+        )
+    }
+
+    pub fn tick(&self) {
+        let _ = self
+            .pickup_delay
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |delay| {
+                delay.checked_sub(1)
+            });
+    }
+
+    pub fn can_be_picked_up(&self) -> bool {
+        self.pickup_delay.load(Ordering::Relaxed) == 0
+    }
+}
+
+/// How fast an item thrown by an entity looking at `yaw` and `pitch` flies, in blocks per tick.
+///
+/// Unlike vanilla there is no random spread, so the item lands where the entity looks
+pub fn throw_velocity(yaw: f32, pitch: f32) -> Vector3<f64> {
+    let (yaw, pitch) = (f64::from(yaw).to_radians(), f64::from(pitch).to_radians());
+    Vector3::new(
+        -yaw.sin() * pitch.cos() * 0.3,
+        -pitch.sin() * 0.3 + 0.1,
+        yaw.cos() * pitch.cos() * 0.3,
+    )
+}
+
+/// Drops the item at the position, returns `None` if the chunk already holds too many entities
+pub fn drop_item(
+    world: &Arc<World>,
+    entity_id: EntityId,
+    item: ItemStack,
+    position: Vector3<f64>,
+    velocity: Vector3<f64>,
+    pickup_delay: u32,
+) -> Option<Arc<ItemEntity>> {
+    let entity = Arc::new(Entity::new(
+        entity_id,
+        Uuid::new_v4(),
+        world.clone(),
+        EntityType::Item,
+    ));
+    entity.set_pos(position.x, position.y, position.z);
+    entity.velocity.store(velocity);
+    let item = Arc::new(ItemEntity::new(entity, item, pickup_delay));
+    world.spawn_item(item.clone()).then_some(item)
+}
+
+/// Moves the items in range into the Player's inventory, items which don't fit stay on the ground.
+///
+/// Returns how many item entities were picked up
+pub fn pickup_items(world: &World, player: &Player) -> usize {
+    if player.entity.health.load() <= 0.0 || player.gamemode.load() == GameMode::Spectator {
+        return 0;
+    }
+    let (x, y, z) = PICKUP_RANGE;
+    let range = player.entity.bounding_box().expand(x, y, z);
+    let in_range: Vec<_> = world
+        .items
+        .lock()
+        .values()
+        .filter(|item| item.can_be_picked_up() && range.intersects(&item.entity.bounding_box()))
+        .cloned()
+        .collect();
+    let mut picked_up = 0;
+    for item in in_range {
+        let stack = item.item.lock().clone();
+        let count = stack.item_count;
+        if !player.inventory.lock().add_item(stack) {
+            // Full, so nothing else fits either
+            break;
+        }
+        world.broadcast_packet_all(&CPickupItem::new(
+            item.entity.entity_id.into(),
+            player.entity_id().into(),
+            i32::from(count).into(),
+        ));
+        item.entity.remove();
+        picked_up += 1;
+    }
+    if picked_up > 0 {
+        player.set_container_content(None);
+    }
+    picked_up
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::vector3::Vector3;
+    use pumpkin_world::item::{get_item_protocol_id, ItemStack};
+
+    use super::{drop_item, pickup_items, throw_velocity};
+    use crate::test_helpers::{joined_player, world};
+
+    fn still() -> Vector3<f64> {
+        Vector3::new(0.0, 0.0, 0.0)
+    }
+
+    fn bread(count: u8) -> ItemStack {
+        ItemStack::new(count, get_item_protocol_id("minecraft:bread").unwrap())
+    }
+
+    #[test]
+    fn test_pickup() {
+        let world = world("item");
+        let player = joined_player(&world);
+        player.entity.set_pos(0.5, 64.0, 0.5);
+        let far = drop_item(
+            &world,
+            10,
+            bread(1),
+            Vector3::new(5.5, 64.0, 0.5),
+            still(),
+            0,
+        )
+        .unwrap();
+        let near = drop_item(
+            &world,
+            11,
+            bread(3),
+            Vector3::new(1.5, 64.0, 0.5),
+            still(),
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(pickup_items(&world, &player), 1);
+        assert_eq!(
+            player.inventory.lock().get_slot(36).unwrap().as_ref(),
+            Some(&bread(3))
+        );
+        assert!(world.get_entity(near.entity.entity_id).is_none());
+        assert!(world.items.lock().get(&11).is_none());
+        // Out of range
+        assert!(world.get_entity(far.entity.entity_id).is_some());
+    }
+
+    #[test]
+    fn test_pickup_delay() {
+        let world = world("item");
+        let player = joined_player(&world);
+        player.entity.set_pos(0.5, 64.0, 0.5);
+        let item = drop_item(
+            &world,
+            10,
+            bread(1),
+            Vector3::new(0.5, 64.0, 0.5),
+            still(),
+            2,
+        )
+        .unwrap();
+
+        assert_eq!(pickup_items(&world, &player), 0);
+        item.tick();
+        item.tick();
+        assert_eq!(pickup_items(&world, &player), 1);
+    }
+
+    #[test]
+    fn test_throw_velocity() {
+        // Looking straight ahead towards positive z, the item flies forward and a bit up
+        let velocity = throw_velocity(0.0, 0.0);
+        assert!(velocity.x.abs() < 1.0E-9);
+        assert!((velocity.y - 0.1).abs() < 1.0E-9);
+        assert!((velocity.z - 0.3).abs() < 1.0E-9);
+        // Towards negative x
+        let velocity = throw_velocity(90.0, 0.0);
+        assert!((velocity.x + 0.3).abs() < 1.0E-9);
+        assert!(velocity.z.abs() < 1.0E-9);
+        // Straight down
+        let velocity = throw_velocity(0.0, 90.0);
+        assert!((velocity.y + 0.2).abs() < 1.0E-9);
+        assert!(velocity.z.abs() < 1.0E-9);
+    }
+
+    #[test]
+    fn test_full_inventory() {
+        let world = world("item");
+        let player = joined_player(&world);
+        player.entity.set_pos(0.5, 64.0, 0.5);
+        {
+            let mut inventory = player.inventory.lock();
+            while inventory.add_item(bread(64)) {}
+        }
+        drop_item(
+            &world,
+            10,
+            bread(1),
+            Vector3::new(0.5, 64.0, 0.5),
+            still(),
+            0,
+        )
+        .unwrap();
+
+        assert_eq!(pickup_items(&world, &player), 0);
+        // It stays on the ground
+        assert!(world.get_entity(10).is_some());
+        assert!(world.items.lock().contains_key(&10));
+    }
+}
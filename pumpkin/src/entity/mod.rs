@@ -3,22 +3,34 @@ use std::sync::{atomic::AtomicBool, Arc};
 use crossbeam::atomic::AtomicCell;
 use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::ToPrimitive;
+use parking_lot::Mutex;
 use pumpkin_core::math::{
-    get_section_cord, position::WorldPosition, vector2::Vector2, vector3::Vector3,
+    boundingbox::BoundingBox, get_section_cord, position::WorldPosition, vector2::Vector2,
+    vector3::Vector3,
 };
 use pumpkin_entity::{entity_type::EntityType, pose::EntityPose, EntityId};
 use pumpkin_protocol::{
     client::play::{CEntityStatus, CSetEntityMetadata, Metadata},
     VarInt,
 };
+use uuid::Uuid;
 
 use crate::world::World;
 
+pub mod chat_flood;
+pub mod experience;
+pub mod experience_orb;
+pub mod id_allocator;
+pub mod item;
+pub mod movement;
 pub mod player;
+pub mod pose;
 
 pub struct Entity {
     /// A unique identifier for the entity
     pub entity_id: EntityId,
+    /// The UUID viewers know the entity by, the one of the GameProfile for Players
+    pub uuid: Uuid,
     /// The type of entity (e.g., player, zombie, item)
     pub entity_type: EntityType,
     /// The world in which the entity exists.
@@ -37,6 +49,8 @@ pub struct Entity {
     pub sneaking: AtomicBool,
     /// Indicates whether the entity is sprinting
     pub sprinting: AtomicBool,
+    /// Indicates whether the entity is swimming
+    pub swimming: AtomicBool,
     /// Indicates whether the entity is flying due to a fall
     pub fall_flying: AtomicBool,
 
@@ -54,19 +68,30 @@ pub struct Entity {
     pub pitch: AtomicCell<f32>,
     /// The height of the entity's eyes from the ground.
     pub standing_eye_height: f32,
+    /// The width of the entity's bounding box, it's centered on the position.
+    pub width: f32,
+    /// The height of the entity's bounding box, it starts at the feet.
+    pub height: f32,
     /// The entity's current pose (e.g., standing, sitting, swimming).
     pub pose: AtomicCell<EntityPose>,
+
+    /// The entity this entity is riding, see `world::mount`.
+    pub vehicle: AtomicCell<Option<EntityId>>,
+    /// The entities riding this entity, the first one steers vehicles like boats.
+    pub passengers: Mutex<Vec<EntityId>>,
 }
 
 impl Entity {
     pub fn new(
         entity_id: EntityId,
+        uuid: Uuid,
         world: Arc<World>,
         entity_type: EntityType,
-        standing_eye_height: f32,
     ) -> Self {
+        let (width, height) = entity_type.dimensions();
         Self {
             entity_id,
+            uuid,
             entity_type,
             on_ground: AtomicBool::new(false),
             pos: AtomicCell::new(Vector3::new(0.0, 0.0, 0.0)),
@@ -77,16 +102,25 @@ impl Entity {
             // TODO: Load this from previous instance
             health: AtomicCell::new(20.0),
             sprinting: AtomicBool::new(false),
+            swimming: AtomicBool::new(false),
             fall_flying: AtomicBool::new(false),
             yaw: AtomicCell::new(0.0),
             head_yaw: AtomicCell::new(0.0),
             pitch: AtomicCell::new(0.0),
             velocity: AtomicCell::new(Vector3::new(0.0, 0.0, 0.0)),
-            standing_eye_height,
+            standing_eye_height: entity_type.standing_eye_height(),
+            width,
+            height,
             pose: AtomicCell::new(EntityPose::Standing),
+            vehicle: AtomicCell::new(None),
+            passengers: Mutex::new(Vec::new()),
         }
     }
 
+    pub fn is_riding(&self) -> bool {
+        self.vehicle.load().is_some()
+    }
+
     /// Updates the entity's position, block position, and chunk position.
     ///
     /// This function calculates the new position, block position, and chunk position based on the provided coordinates. If any of these values change, the corresponding fields are updated.
@@ -105,15 +139,24 @@ impl Entity {
 
                 let chunk_pos = self.chunk_pos.load();
                 if get_section_cord(i) != chunk_pos.x || get_section_cord(k) != chunk_pos.z {
-                    self.chunk_pos.store(Vector2::new(
-                        get_section_cord(block_pos_vec.x),
-                        get_section_cord(block_pos_vec.z),
-                    ));
+                    self.chunk_pos
+                        .store(Vector2::new(get_section_cord(i), get_section_cord(k)));
                 }
             }
         }
     }
 
+    /// The box the entity takes up at its current position
+    pub fn bounding_box(&self) -> BoundingBox {
+        BoundingBox::from_entity_pos(self.pos.load(), self.width.into(), self.height.into())
+    }
+
+    /// The position of the entity's eyes while standing
+    pub fn eye_pos(&self) -> Vector3<f64> {
+        let pos = self.pos.load();
+        Vector3::new(pos.x, pos.y + f64::from(self.standing_eye_height), pos.z)
+    }
+
     /// Sets the Entity yaw & pitch Rotation
     pub fn set_rotation(&self, yaw: f32, pitch: f32) {
         // TODO
@@ -134,6 +177,25 @@ impl Entity {
         self.remove();
     }
 
+    /// Hurts a mob, it dies once its health runs out.
+    ///
+    /// Returns false if the entity can't be hurt, like items and vehicles, or is dead already
+    pub fn damage(&self, amount: f32) -> bool {
+        if !self.entity_type.is_mob() {
+            return false;
+        }
+        let health = self.health.load();
+        if health <= 0.0 {
+            return false;
+        }
+        let health = (health - amount).max(0.0);
+        self.health.store(health);
+        if health <= 0.0 {
+            self.kill();
+        }
+        true
+    }
+
     /// Removes the Entity from their current World
     pub fn remove(&self) {
         self.world.remove_entity(self);
@@ -169,11 +231,6 @@ impl Entity {
         self.sneaking
             .store(sneaking, std::sync::atomic::Ordering::Relaxed);
         self.set_flag(Flag::Sneaking, sneaking).await;
-        // if sneaking {
-        //     self.set_pose(EntityPose::Crouching).await;
-        // } else {
-        //     self.set_pose(EntityPose::Standing).await;
-        // }
     }
 
     pub async fn set_sprinting(&self, sprinting: bool) {
@@ -183,6 +240,13 @@ impl Entity {
         self.set_flag(Flag::Sprinting, sprinting).await;
     }
 
+    pub async fn set_swimming(&self, swimming: bool) {
+        assert!(self.swimming.load(std::sync::atomic::Ordering::Relaxed) != swimming);
+        self.swimming
+            .store(swimming, std::sync::atomic::Ordering::Relaxed);
+        self.set_flag(Flag::Swimming, swimming).await;
+    }
+
     pub fn check_fall_flying(&self) -> bool {
         !self.on_ground.load(std::sync::atomic::Ordering::Relaxed)
     }
@@ -196,7 +260,8 @@ impl Entity {
 
     async fn set_flag(&self, flag: Flag, value: bool) {
         let index = flag.to_u32().unwrap();
-        let mut b = 0i8;
+        // The metadata holds every flag, so the others have to be sent again
+        let mut b = self.flags();
         if value {
             b |= 1 << index;
         } else {
@@ -206,17 +271,47 @@ impl Entity {
         self.world.broadcast_packet_all(&packet);
     }
 
-    pub async fn set_pose(&self, pose: EntityPose) {
+    /// The flags the entity currently has, as sent in the metadata
+    fn flags(&self) -> i8 {
+        let states = [
+            (Flag::Sneaking, &self.sneaking),
+            (Flag::Sprinting, &self.sprinting),
+            (Flag::Swimming, &self.swimming),
+            (Flag::FallFlying, &self.fall_flying),
+        ];
+        states
+            .into_iter()
+            .filter(|(_, state)| state.load(std::sync::atomic::Ordering::Relaxed))
+            .fold(0, |flags, (flag, _)| flags | 1 << flag.to_u32().unwrap())
+    }
+
+    /// Changes the pose, other clients are only told if it changed
+    pub fn update_pose(&self, pose: EntityPose) {
+        if self.pose.load() != pose {
+            self.set_pose(pose);
+        }
+    }
+
+    pub fn set_pose(&self, pose: EntityPose) {
         self.pose.store(pose);
         let pose = pose as i32;
+        // 21 is the pose metadata type since 1.20.5
         let packet = CSetEntityMetadata::<VarInt>::new(
             self.entity_id.into(),
-            Metadata::new(6, 20.into(), (pose).into()),
+            Metadata::new(6, 21.into(), (pose).into()),
         );
         self.world.broadcast_packet_all(&packet)
     }
 }
 
+/// The direction passed to `Entity::knockback` when attacking while looking at `yaw`.
+///
+/// Knockback is applied against this direction, so the target gets pushed where the attacker is looking
+pub fn knockback_direction(yaw: f32) -> (f64, f64) {
+    let yaw = yaw.to_radians();
+    (yaw.sin() as f64, -yaw.cos() as f64)
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, FromPrimitive, ToPrimitive)]
 /// Represents various entity flags that are sent in entity metadata.
 ///
@@ -241,3 +336,57 @@ pub enum Flag {
     /// Indicates if the entity is flying due to a fall.
     FallFlying,
 }
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::vector3::Vector3;
+    use pumpkin_entity::entity_type::EntityType;
+    use uuid::Uuid;
+
+    use super::{knockback_direction, Entity};
+    use crate::test_helpers::world;
+
+    fn entity(entity_type: EntityType) -> Entity {
+        Entity::new(1, Uuid::new_v4(), world("entity"), entity_type)
+    }
+
+    #[test]
+    fn test_player_bounding_box() {
+        let player = entity(EntityType::Player);
+        player.set_pos(10.5, 64.0, -3.5);
+        let bounding_box = player.bounding_box();
+        assert!((bounding_box.min_x - 10.2).abs() < 1.0E-6);
+        assert!((bounding_box.max_x - 10.8).abs() < 1.0E-6);
+        assert!((bounding_box.min_z + 3.8).abs() < 1.0E-6);
+        assert!((bounding_box.max_z + 3.2).abs() < 1.0E-6);
+        assert_eq!(bounding_box.min_y, 64.0);
+        assert!((bounding_box.max_y - 65.8).abs() < 1.0E-6);
+        // The eyes are inside the box
+        assert!(bounding_box.contains(player.eye_pos()));
+    }
+
+    #[test]
+    fn test_look_at_zombie() {
+        let (player, zombie) = (entity(EntityType::Player), entity(EntityType::Zombie));
+        zombie.set_pos(3.0, 64.0, 0.0);
+        let target = zombie.bounding_box();
+        // Zombies are taller than Players
+        assert!(target.contains(Vector3::new(3.0, 65.9, 0.0)));
+        let hit = target.ray_intersection(player.eye_pos(), Vector3::new(1.0, 0.0, 0.0), 3.0);
+        assert!(hit.is_some_and(|distance| (distance - 2.7).abs() < 1.0E-6));
+        let miss = target.ray_intersection(player.eye_pos(), Vector3::new(0.0, 0.0, 1.0), 3.0);
+        assert_eq!(miss, None);
+    }
+
+    #[test]
+    fn test_knockback_direction() {
+        // yaw 0 looks towards positive z, so knockback pushes towards positive z
+        let (x, z) = knockback_direction(0.0);
+        assert!(x.abs() < 1.0E-6);
+        assert!((z + 1.0).abs() < 1.0E-6);
+        // yaw 90 looks towards negative x
+        let (x, z) = knockback_direction(90.0);
+        assert!((x - 1.0).abs() < 1.0E-6);
+        assert!(z.abs() < 1.0E-6);
+    }
+}
@@ -0,0 +1,86 @@
+use pumpkin_config::MovementConfig;
+use pumpkin_core::math::vector3::Vector3;
+
+/// How a Player moves, which decides how far they may move at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MovementMode {
+    Walking,
+    Flying,
+    Gliding,
+}
+
+impl MovementMode {
+    fn max_speed(self, config: &MovementConfig) -> f64 {
+        match self {
+            Self::Walking => config.max_speed,
+            Self::Flying => config.max_flying_speed,
+            Self::Gliding => config.max_elytra_speed,
+        }
+    }
+}
+
+/// Returns the position a Player has to be moved back to when they moved from `from` to `to` too quickly
+pub fn correct_move(
+    from: Vector3<f64>,
+    to: Vector3<f64>,
+    velocity: Vector3<f64>,
+    mode: MovementMode,
+    config: &MovementConfig,
+) -> Option<Vector3<f64>> {
+    let max_speed = mode.max_speed(config);
+    // Knockback may push a Player further than they could move themselves
+    let allowed = max_speed.mul_add(max_speed, velocity.length_squared());
+    (to.sub(&from).length_squared() > allowed).then_some(from)
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::MovementConfig;
+    use pumpkin_core::math::vector3::Vector3;
+
+    use super::{correct_move, MovementMode};
+
+    const NO_VELOCITY: Vector3<f64> = Vector3 {
+        x: 0.0,
+        y: 0.0,
+        z: 0.0,
+    };
+
+    #[test]
+    fn test_normal_move_passes() {
+        let config = MovementConfig::default();
+        let from = Vector3::new(0.0, 64.0, 0.0);
+        let to = Vector3::new(0.3, 64.0, 0.2);
+        assert_eq!(
+            correct_move(from, to, NO_VELOCITY, MovementMode::Walking, &config),
+            None
+        );
+    }
+
+    #[test]
+    fn test_too_fast_move_is_corrected() {
+        let config = MovementConfig::default();
+        let from = Vector3::new(0.0, 64.0, 0.0);
+        let to = Vector3::new(50.0, 64.0, 0.0);
+        assert_eq!(
+            correct_move(from, to, NO_VELOCITY, MovementMode::Walking, &config),
+            Some(from)
+        );
+        // Even an elytra is not that fast
+        assert_eq!(
+            correct_move(from, to, NO_VELOCITY, MovementMode::Gliding, &config),
+            Some(from)
+        );
+    }
+
+    #[test]
+    fn test_elytra_and_knockback_allow_more() {
+        let config = MovementConfig::default();
+        let from = Vector3::new(0.0, 64.0, 0.0);
+        let to = Vector3::new(15.0, 64.0, 0.0);
+        assert!(correct_move(from, to, NO_VELOCITY, MovementMode::Walking, &config).is_some());
+        assert!(correct_move(from, to, NO_VELOCITY, MovementMode::Gliding, &config).is_none());
+        let knockback = Vector3::new(12.0, 0.0, 0.0);
+        assert!(correct_move(from, to, knockback, MovementMode::Walking, &config).is_none());
+    }
+}
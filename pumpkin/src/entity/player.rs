@@ -1,30 +1,43 @@
-use std::sync::{
-    atomic::{AtomicI32, AtomicU8},
-    Arc,
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, AtomicI32, AtomicU32, AtomicU8, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
 use crossbeam::atomic::AtomicCell;
 use num_derive::FromPrimitive;
 use num_traits::ToPrimitive;
 use parking_lot::Mutex;
+use pumpkin_config::{BypassLimit, ADVANCED_CONFIG, BASIC_CONFIG, LIVE_CONFIG};
 use pumpkin_core::{
-    math::{boundingbox::BoundingBox, position::WorldPosition, vector3::Vector3},
+    math::{boundingbox::BoundingBox, position::WorldPosition, vector2::Vector2, vector3::Vector3},
     text::TextComponent,
-    GameMode,
+    Difficulty, GameMode,
+};
+use pumpkin_entity::{
+    effect::{EffectType, StatusEffect, StatusEffects},
+    entity_type::EntityType,
+    pose::EntityPose,
+    EntityId,
 };
-use pumpkin_entity::{entity_type::EntityType, EntityId};
 use pumpkin_inventory::player::PlayerInventory;
 use pumpkin_protocol::{
     bytebuf::{packet_id::Packet, DeserializerError},
     client::play::{
-        CGameEvent, CPlayDisconnect, CPlayerAbilities, CPlayerInfoUpdate, CSyncPlayerPosition,
-        CSystemChatMessage, GameEvent, PlayerAction,
+        Animation, CEntityAnimation, CGameEvent, CPlayerAbilities, CPlayerInfoUpdate,
+        CRemoveMobEffect, CRespawn, CSetEntityMetadata, CSetHealth, CSyncPlayerPosition,
+        CSystemChatMessage, CUpdateMobEffect, GameEvent, Metadata, PlayerAction,
     },
     server::play::{
-        SChatCommand, SChatMessage, SClickContainer, SClientInformationPlay, SConfirmTeleport,
-        SInteract, SPlayPingRequest, SPlayerAction, SPlayerCommand, SPlayerPosition,
-        SPlayerPositionRotation, SPlayerRotation, SSetCreativeSlot, SSetHeldItem, SSetPlayerGround,
-        SSwingArm, SUseItem, SUseItemOn,
+        SChangeDifficulty, SChatCommand, SChatMessage, SClickContainer, SClientCommand,
+        SClientInformationPlay, SConfirmTeleport, SCookieResponsePlay, SInteract,
+        SMessageAcknowledgement, SMoveVehicle, SPlayPingRequest, SPlayerAction, SPlayerCommand,
+        SPlayerInput, SPlayerPosition, SPlayerPositionRotation, SPlayerRotation, SPlayerSession,
+        SSetCreativeSlot, SSetHeldItem, SSetPlayerGround, SSwingArm, SUpdateSign, SUseItem,
+        SUseItemOn,
     },
     ConnectionState, RawPacket, ServerPacket, VarInt,
 };
@@ -33,12 +46,36 @@ use pumpkin_protocol::server::play::{SCloseContainer, SKeepAlive};
 use pumpkin_world::item::ItemStack;
 
 use crate::{
-    client::{authentication::GameProfile, Client, PlayerConfig},
+    client::{
+        authentication::GameProfile,
+        disconnect::{DisconnectMessage, DisconnectReason},
+        skip_unknown_packet, Client, PlayerConfig,
+    },
+    commands::cooldown::CommandCooldowns,
     server::Server,
-    world::World,
+    world::{
+        entity_tracker::{self, MovementBatch},
+        hardcore::DeathOutcome,
+        location::{BackCause, LastLocation, Location},
+        player_chunker::{self, ChunkQueue},
+        player_data::LoginTimes,
+        respawn::{self, RespawnPoint},
+        sleep, World,
+    },
+};
+
+use super::{
+    chat_flood::ChatFlood,
+    experience::Experience,
+    movement::{correct_move, MovementMode},
+    pose::{self, PoseState},
+    Entity,
 };
 
-use super::Entity;
+/// How long a client has to confirm a teleport before it is sent again, vanilla waits 20 ticks
+const TELEPORT_RESEND_DELAY: Duration = Duration::from_secs(1);
+/// The chestplate slot of the inventory, see `PlayerInventory::get_slot`
+const CHEST_SLOT: usize = 6;
 
 /// Represents a Minecraft player entity.
 ///
@@ -59,12 +96,16 @@ pub struct Player {
     pub food: AtomicI32,
     /// The player's food saturation level.
     pub food_saturation: AtomicCell<f32>,
+    /// The player's experience, use `add_experience` so the client gets notified.
+    pub experience: Mutex<Experience>,
+    /// The experience dropped on the last death, the World spawns it as an orb next tick.
+    pub dropped_experience: AtomicU32,
     /// The player's inventory, containing items and equipment.
     pub inventory: Mutex<PlayerInventory>,
     /// The ID of the currently open container (if any).
     pub open_container: AtomicCell<Option<u64>>,
     /// The item currently being held by the player.
-    pub carried_item: AtomicCell<Option<ItemStack>>,
+    pub carried_item: Mutex<Option<ItemStack>>,
 
     /// send `send_abilties_update` when changed
     /// The player's abilities and special powers.
@@ -84,9 +125,57 @@ pub struct Player {
     pub teleport_id_count: AtomicI32,
     /// The pending teleport information, including the teleport ID and target location.
     pub awaiting_teleport: Mutex<Option<(VarInt, Vector3<f64>)>>,
+    /// When the pending teleport was last sent to the client
+    teleport_sent: AtomicCell<Instant>,
 
     /// The coordinates of the chunk section the player is currently watching.
     pub watched_section: AtomicCell<Vector3<i32>>,
+
+    /// The signed chat session the client started with `SPlayerSession`, if any.
+    pub chat_session: Mutex<Option<ChatSession>>,
+    /// The total number of chat messages the client has acknowledged.
+    ///
+    /// Clients acknowledge messages in offsets, so this is the sum of all offsets received so far.
+    pub acknowledged_messages: AtomicI32,
+
+    /// The status effects (e.g. Speed, Poison) which are currently active on the player.
+    ///
+    /// Use `add_effect` and `remove_effect` so the client gets notified.
+    pub status_effects: Mutex<StatusEffects>,
+    /// The amount of ticks the player has been online.
+    pub tick_count: AtomicI32,
+    /// When the player last used each command, see `CommandsConfig` for the cooldowns.
+    pub command_cooldowns: Mutex<CommandCooldowns>,
+    /// Where the player returns to with `/back`.
+    pub last_location: Mutex<LastLocation>,
+    /// The recent chat messages of the player, see `ChatFloodConfig`.
+    pub chat_flood: Mutex<ChatFlood>,
+    /// The view distance set with `/viewdistance`, used instead of the one the client asked for
+    pub view_distance_override: AtomicCell<Option<i8>>,
+    /// The entities this player's client has spawned, see `entity_tracker`.
+    pub tracked_entities: Mutex<HashSet<EntityId>>,
+    /// The chunks this player's client has or gets next from the `chunk_queue`, see `player_chunker`.
+    pub loaded_chunks: Mutex<HashSet<Vector2<i32>>>,
+    /// The fetched chunks waiting to be sent, see `player_chunker::send_queued_chunks`.
+    pub chunk_queue: Mutex<ChunkQueue>,
+    /// The moves other players weren't told about yet, see `entity_tracker::broadcast_movement`.
+    pub movement_batch: Mutex<MovementBatch>,
+    /// Where the player respawns instead of the world spawn, see `respawn`.
+    pub respawn_point: Mutex<Option<RespawnPoint>>,
+    /// When the player first joined, last joined and last left, see `player_data`.
+    pub login_times: Mutex<LoginTimes>,
+    /// Hidden from other players with `/vanish`, see `World::set_vanished`.
+    pub vanished: AtomicBool,
+    /// The head of the bed the player is sleeping in.
+    pub sleeping_in: AtomicCell<Option<WorldPosition>>,
+    /// How many ticks the player has been sleeping, see `sleep::DEEP_SLEEP_TICKS`.
+    pub sleep_timer: AtomicI32,
+    /// The permission level from `ops.json`, 0 for Players who are not an Operator.
+    pub permission_lvl: AtomicU8,
+    /// Set while the player is being respawned.
+    respawning: AtomicBool,
+    /// Set once the player was removed from the Server after disconnecting.
+    removed: AtomicBool,
 }
 
 impl Player {
@@ -110,29 +199,84 @@ impl Player {
         );
         let config = client.config.lock().clone().unwrap_or_default();
         Self {
-            entity: Entity::new(entity_id, world, EntityType::Player, 1.62),
+            entity: Entity::new(entity_id, gameprofile.id, world, EntityType::Player),
             config: Mutex::new(config),
             gameprofile,
             client,
             awaiting_teleport: Mutex::new(None),
+            teleport_sent: AtomicCell::new(Instant::now()),
             // TODO: Load this from previous instance
             food: AtomicI32::new(20),
+            experience: Mutex::new(Experience::default()),
+            dropped_experience: AtomicU32::new(0),
             food_saturation: AtomicCell::new(20.0),
             current_block_destroy_stage: AtomicU8::new(0),
             inventory: Mutex::new(PlayerInventory::new()),
             open_container: AtomicCell::new(None),
-            carried_item: AtomicCell::new(None),
+            carried_item: Mutex::new(None),
             teleport_id_count: AtomicI32::new(0),
             abilities: PlayerAbilities::default(),
             gamemode: AtomicCell::new(gamemode),
             watched_section: AtomicCell::new(Vector3::new(0, 0, 0)),
             last_position: AtomicCell::new(Vector3::new(0.0, 0.0, 0.0)),
+            chat_session: Mutex::new(None),
+            acknowledged_messages: AtomicI32::new(0),
+            status_effects: Mutex::new(StatusEffects::default()),
+            command_cooldowns: Mutex::new(CommandCooldowns::default()),
+            last_location: Mutex::new(LastLocation::default()),
+            chat_flood: Mutex::new(ChatFlood::default()),
+            view_distance_override: AtomicCell::new(None),
+            tracked_entities: Mutex::new(HashSet::new()),
+            loaded_chunks: Mutex::new(HashSet::new()),
+            chunk_queue: Mutex::new(ChunkQueue::default()),
+            movement_batch: Mutex::new(MovementBatch::default()),
+            respawn_point: Mutex::new(None),
+            login_times: Mutex::new(LoginTimes::default()),
+            vanished: AtomicBool::new(false),
+            sleeping_in: AtomicCell::new(None),
+            sleep_timer: AtomicI32::new(0),
+            tick_count: AtomicI32::new(0),
+            permission_lvl: AtomicU8::new(0),
+            respawning: AtomicBool::new(false),
+            removed: AtomicBool::new(false),
+        }
+    }
+
+    /// Marks the Player as removed, returns false if they already were.
+    ///
+    /// A connection can be closed from many places, this makes sure the cleanup only runs once
+    pub fn mark_removed(&self) -> bool {
+        !self.removed.swap(true, Ordering::AcqRel)
+    }
+
+    pub fn is_vanished(&self) -> bool {
+        self.vanished.load(Ordering::Relaxed)
+    }
+
+    /// Kicks the Player with the message if they are still connected, then removes them from the World and saves them.
+    ///
+    /// Returns false if they were already removed, so disconnecting twice does nothing.
+    /// `Server::remove_player` also tells plugins they left
+    pub async fn disconnect(&self, message: Option<DisconnectMessage<'_>>) -> bool {
+        if let Some(message) = message {
+            self.kick(message);
+        }
+        self.client.close();
+        if !self.mark_removed() {
+            return false;
         }
+        self.remove().await;
+        true
     }
 
-    /// Removes the Player out of the current World
+    /// Saves the Player and removes them out of the current World
     pub async fn remove(&self) {
+        // Removing first records when the Player left
         self.entity.world.remove_player(self);
+        self.entity.world.save_player(self);
+        self.chunk_queue.lock().clear();
+        let chunks = self.loaded_chunks.lock().drain().collect();
+        self.entity.world.unload_unwatched_chunks(chunks);
     }
 
     pub const fn entity_id(&self) -> EntityId {
@@ -178,6 +322,7 @@ impl Player {
         entity.set_pos(x, y, z);
         entity.set_rotation(yaw, pitch);
         *self.awaiting_teleport.lock() = Some((teleport_id.into(), Vector3::new(x, y, z)));
+        self.teleport_sent.store(Instant::now());
         self.client.send_packet(&CSyncPlayerPosition::new(
             x,
             y,
@@ -189,6 +334,82 @@ impl Player {
         ));
     }
 
+    /// Returns true while the client has not confirmed the last teleport yet.
+    ///
+    /// Like vanilla, the teleport is sent again if the client takes too long to confirm it
+    fn is_awaiting_teleport(&self) -> bool {
+        let awaiting_teleport = self.awaiting_teleport.lock();
+        let Some((id, position)) = awaiting_teleport.as_ref() else {
+            return false;
+        };
+        if self.teleport_sent.load().elapsed() >= TELEPORT_RESEND_DELAY {
+            self.teleport_sent.store(Instant::now());
+            let entity = &self.entity;
+            self.client.send_packet(&CSyncPlayerPosition::new(
+                position.x,
+                position.y,
+                position.z,
+                entity.yaw.load(),
+                entity.pitch.load(),
+                0,
+                id.clone(),
+            ));
+        }
+        true
+    }
+
+    /// Whether the Player wears an elytra, the client alone can't decide they are gliding
+    fn has_elytra(&self) -> bool {
+        self.inventory
+            .lock()
+            .get_slot(CHEST_SLOT)
+            .is_ok_and(|chest| chest.as_ref().is_some_and(ItemStack::is_elytra))
+    }
+
+    /// Checks a move sent by the client, a Player who moved too quickly is teleported back.
+    ///
+    /// Returns if the move is allowed
+    pub fn validate_move(&self, to: Vector3<f64>) -> bool {
+        // Moves sent before the client confirmed a teleport still start at the old position, they are ignored
+        if self.is_awaiting_teleport() {
+            return false;
+        }
+        let live = LIVE_CONFIG.load();
+        let config = &live.movement;
+        if !config.enabled {
+            return true;
+        }
+        let entity = &self.entity;
+        // Both flags are sent by the client, so they only count if the Player really can fly or glide
+        let mode = if entity.fall_flying.load(Ordering::Relaxed) && self.has_elytra() {
+            MovementMode::Gliding
+        } else if self.abilities.flying && self.abilities.allow_flying {
+            MovementMode::Flying
+        } else {
+            MovementMode::Walking
+        };
+        let from = entity.pos.load();
+        let Some(back) = correct_move(from, to, entity.velocity.load(), mode, config) else {
+            return true;
+        };
+        let delta = to.sub(&from);
+        log::warn!(
+            "{} moved too quickly! {:.2},{:.2},{:.2}",
+            self.gameprofile.name,
+            delta.x,
+            delta.y,
+            delta.z
+        );
+        self.teleport(
+            back.x,
+            back.y,
+            back.z,
+            entity.yaw.load(),
+            entity.pitch.load(),
+        );
+        false
+    }
+
     pub fn block_interaction_range(&self) -> f64 {
         if self.gamemode.load() == GameMode::Creative {
             5.0
@@ -209,29 +430,365 @@ impl Player {
         }) < d * d
     }
 
-    /// Kicks the Client with a reason depending on the connection state
-    pub fn kick(&self, reason: TextComponent) {
-        assert!(self.client.connection_state.load() == ConnectionState::Play);
-        assert!(!self
-            .client
-            .closed
-            .load(std::sync::atomic::Ordering::Relaxed));
+    pub fn entity_interaction_range(&self) -> f64 {
+        if self.gamemode.load() == GameMode::Creative {
+            5.0
+        } else {
+            3.0
+        }
+    }
+
+    /// Checks if the Player can reach the Entity, used to reject attacks from too far away
+    pub fn can_interact_with_entity(&self, target: &Entity, additional_range: f64) -> bool {
+        let d = self.entity_interaction_range() + additional_range;
+        target
+            .bounding_box()
+            .squared_magnitude(self.entity.eye_pos())
+            < d * d
+    }
+
+    /// Deals damage to the Player, returns false if the Player could not be damaged
+    pub fn damage(&self, amount: f32) -> bool {
+        if self.abilities.invulnerable || self.gamemode.load() == GameMode::Spectator {
+            return false;
+        }
+        let health = self.entity.health.load();
+        if health <= 0.0 {
+            // Already dead
+            return false;
+        }
+        self.stop_sleeping();
+        self.update_health(
+            (health - amount).max(0.0),
+            self.food.load(std::sync::atomic::Ordering::Relaxed),
+            self.food_saturation.load(),
+        );
+        true
+    }
+
+    /// Sets or clears where the Player respawns, it is saved with the rest of the player data
+    pub fn set_respawn_point(&self, point: Option<RespawnPoint>) {
+        *self.respawn_point.lock() = point;
+    }
+
+    /// Lays the Player down in a bed, everyone sees them sleeping
+    pub fn start_sleeping(&self, bed: WorldPosition) {
+        self.sleeping_in.store(Some(bed));
+        self.sleep_timer.store(0, Ordering::Relaxed);
+        self.entity.set_pose(EntityPose::Sleeping);
+        self.broadcast_sleeping_position(Some(bed));
+    }
+
+    /// Wakes the Player up, returns false if they were not sleeping
+    pub fn stop_sleeping(&self) -> bool {
+        if self.sleeping_in.take().is_none() {
+            return false;
+        }
+        self.sleep_timer.store(0, Ordering::Relaxed);
+        self.entity
+            .world
+            .broadcast_packet_all(&CEntityAnimation::new(
+                self.entity_id().into(),
+                Animation::LeaveBed as u8,
+            ));
+        self.entity.set_pose(EntityPose::Standing);
+        self.broadcast_sleeping_position(None);
+        true
+    }
 
+    /// Updates the swimming and gliding flags from how the Player moves and shows their pose to everyone
+    pub async fn update_pose(&self) {
+        let entity = &self.entity;
+        let world = &entity.world;
+        let feet = entity.block_pos.load();
+        let head = WorldPosition(feet.0.add(&Vector3::new(0, 1, 0)));
+
+        let swimming = entity.sprinting.load(Ordering::Relaxed)
+            && world.get_block(feet).is_some_and(pose::is_water);
+        if entity.swimming.load(Ordering::Relaxed) != swimming {
+            entity.set_swimming(swimming).await;
+        }
+        // Gliding ends once the Player lands
+        if entity.on_ground.load(Ordering::Relaxed) && entity.fall_flying.load(Ordering::Relaxed) {
+            entity.set_fall_flying(false).await;
+        }
+
+        let state = PoseState {
+            sleeping: self.sleeping_in.load().is_some(),
+            swimming,
+            fall_flying: entity.fall_flying.load(Ordering::Relaxed),
+            sneaking: entity.sneaking.load(Ordering::Relaxed) && !self.abilities.flying,
+            blocked_above: world.get_block(head).is_some_and(pose::is_obstruction),
+        };
+        entity.update_pose(state.pose());
+    }
+
+    /// Only Players who slept for a while count for skipping the night
+    pub fn is_deeply_sleeping(&self) -> bool {
+        self.sleeping_in.load().is_some()
+            && self.sleep_timer.load(Ordering::Relaxed) >= sleep::DEEP_SLEEP_TICKS
+    }
+
+    fn broadcast_sleeping_position(&self, bed: Option<WorldPosition>) {
+        // The optional position of the bed a living entity sleeps in
+        self.entity
+            .world
+            .broadcast_packet_all(&CSetEntityMetadata::new(
+                self.entity_id().into(),
+                Metadata::new(14, 11.into(), bed),
+            ));
+    }
+
+    /// Checks if the Player is not affected by the limit because of their permission level, see `OpBypassConfig`
+    pub fn can_bypass(&self, limit: BypassLimit) -> bool {
+        ADVANCED_CONFIG
+            .op_bypass
+            .can_bypass(limit, self.permission_lvl.load(Ordering::Relaxed))
+    }
+
+    /// Checks if the Player may break or place a block there, blocks around the spawn are protected
+    pub fn can_build_at(&self, location: &WorldPosition) -> bool {
+        !self.entity.world.is_spawn_protected(location)
+            || self.can_bypass(BypassLimit::SpawnProtection)
+    }
+
+    /// What happens to the Player once they die, depends on hardcore mode
+    pub fn death_outcome(&self) -> DeathOutcome {
+        DeathOutcome::new(BASIC_CONFIG.hardcore, ADVANCED_CONFIG.hardcore.death_action)
+    }
+
+    /// Whether the Player is dead and comes back without waiting for the client to ask, see `World::immediate_respawn`
+    pub fn respawns_immediately(&self) -> bool {
+        self.entity.health.load() <= 0.0
+            && self.death_outcome().can_respawn()
+            && self.entity.world.immediate_respawn()
+    }
+
+    /// Brings a dead Player back at their respawn point, or at the world spawn if it can't be used
+    pub async fn respawn(&self) {
+        // The client may ask while the Server already respawns them because of `immediate_respawn`
+        if self.entity.health.load() > 0.0 || self.respawning.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let world = &self.entity.world;
+        let point = *self.respawn_point.lock();
+        if let Some(point) = &point {
+            // The bed may be far away from every Player
+            let (x, _, z) = point.position;
+            world
+                .load_chunk(Vector2::new(x.div_euclid(16), z.div_euclid(16)))
+                .await;
+        }
+        let (spawn, invalid) =
+            respawn::respawn_location(point.as_ref(), world.spawn_location(), |position| {
+                world.get_block(position)
+            });
+
+        self.client.send_packet(&CRespawn::new(
+            0.into(),
+            world.dimension.name(),
+            0, // seed
+            self.gamemode.load().to_u8().unwrap(),
+            -1,
+            false,
+            false,
+            None,
+            0.into(),
+            0,
+        ));
+        if invalid {
+            self.client
+                .send_packet(&CGameEvent::new(GameEvent::NoRespawnBlockAvailable, 0.0));
+        }
+        self.status_effects.lock().clear();
+        self.sleeping_in.store(None);
+        self.update_health(20.0, 20, 5.0);
+        self.respawning.store(false, Ordering::Release);
+        let (x, y, z) = spawn.position;
+        self.teleport(x, y, z, spawn.yaw, spawn.pitch);
+        for (event, value) in world.weather.lock().join_events() {
+            self.client.send_packet(&CGameEvent::new(event, value));
+        }
+        self.client.send_packet(&world.time_packet());
         self.client
-            .try_send_packet(&CPlayDisconnect::new(&reason))
-            .unwrap_or_else(|_| self.client.close());
+            .send_packet(&CGameEvent::new(GameEvent::StartWaitingChunks, 0.0));
+
+        // The client forgot every entity and everyone else still sees the dead body
+        world.remove_entity(&self.entity);
+        self.tracked_entities.lock().clear();
+        entity_tracker::update_player(world, self);
+        player_chunker::player_join(world, self).await;
+    }
+
+    /// Kicks the Client with a `DisconnectReason` or a custom message.
+    ///
+    /// Returns false without doing anything if the Player already left, e.g. when they were kicked by another task
+    pub fn kick<'a>(&self, message: impl Into<DisconnectMessage<'a>>) -> bool {
+        if self.client.connection_state.load() != ConnectionState::Play
+            || self
+                .client
+                .closed
+                .load(std::sync::atomic::Ordering::Relaxed)
+        {
+            return false;
+        }
+
+        let message = message.into();
         log::info!(
             "Kicked {} for {}",
             self.gameprofile.name,
-            reason.to_pretty_console()
+            message
+                .message(&LIVE_CONFIG.load().messages)
+                .to_pretty_console()
         );
-        self.client.close()
+        self.client.kick(message);
+        true
     }
 
     pub fn update_health(&self, health: f32, food: i32, food_saturation: f32) {
-        self.entity.health.store(health);
+        let old_health = self.entity.health.swap(health);
+        if health <= 0.0 && old_health > 0.0 {
+            self.last_location.lock().record(
+                Location::from_player(self),
+                BackCause::Death,
+                &ADVANCED_CONFIG.back,
+            );
+            if !self.death_outcome().can_respawn() && self.gamemode.load() != GameMode::Spectator {
+                self.set_gamemode(GameMode::Spectator);
+            }
+            let dropped = self.experience.lock().death_drop();
+            self.dropped_experience.store(dropped, Ordering::Relaxed);
+            self.set_experience(Experience::default());
+        }
         self.food.store(food, std::sync::atomic::Ordering::Relaxed);
         self.food_saturation.store(food_saturation);
+        self.client
+            .send_packet(&CSetHealth::new(health, food.into(), food_saturation));
+    }
+
+    /// Adds experience points, e.g. from collected orbs, and updates the experience bar
+    pub fn add_experience(&self, points: u32) {
+        let mut experience = self.experience.lock();
+        experience.add(points);
+        self.client.send_packet(&experience.packet());
+    }
+
+    pub fn set_experience(&self, experience: Experience) {
+        *self.experience.lock() = experience;
+        self.client.send_packet(&experience.packet());
+    }
+
+    /// Adds or replaces a status effect and shows it to the client
+    pub fn add_effect(&self, effect: StatusEffect) {
+        self.client.send_packet(&CUpdateMobEffect::new(
+            self.entity_id().into(),
+            (effect.effect_type as i32).into(),
+            (effect.amplifier as i32).into(),
+            effect.duration.into(),
+            effect.flags(),
+        ));
+        self.status_effects.lock().add(effect);
+    }
+
+    /// Removes a status effect, returns false if the player did not have the effect
+    pub fn remove_effect(&self, effect_type: EffectType) -> bool {
+        let removed = self.status_effects.lock().remove(effect_type).is_some();
+        if removed {
+            self.send_effect_removal(effect_type);
+        }
+        removed
+    }
+
+    /// Removes all status effects, returns the amount of effects which were removed
+    pub fn clear_effects(&self) -> usize {
+        let removed = self.status_effects.lock().clear();
+        for effect_type in &removed {
+            self.send_effect_removal(*effect_type);
+        }
+        removed.len()
+    }
+
+    fn send_effect_removal(&self, effect_type: EffectType) {
+        self.client.send_packet(&CRemoveMobEffect::new(
+            self.entity_id().into(),
+            (effect_type as i32).into(),
+        ));
+    }
+
+    /// Called every game tick
+    pub fn tick(&self) {
+        let tick_count = self
+            .tick_count
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        self.tick_effects();
+        if self.sleeping_in.load().is_some()
+            && self.sleep_timer.load(Ordering::Relaxed) < sleep::DEEP_SLEEP_TICKS
+        {
+            self.sleep_timer.fetch_add(1, Ordering::Relaxed);
+        }
+        if self.entity.world.difficulty.lock().difficulty == Difficulty::Peaceful {
+            self.tick_peaceful_regeneration(tick_count);
+        }
+    }
+
+    /// In Peaceful players slowly regain their health and never get hungry
+    fn tick_peaceful_regeneration(&self, tick_count: i32) {
+        let health = self.entity.health.load();
+        let food = self.food.load(std::sync::atomic::Ordering::Relaxed);
+        let new_health = if health < 20.0 && tick_count % 20 == 0 {
+            (health + 1.0).min(20.0)
+        } else {
+            health
+        };
+        let new_food = if food < 20 && tick_count % 10 == 0 {
+            food + 1
+        } else {
+            food
+        };
+        if new_health != health || new_food != food {
+            self.update_health(new_health, new_food, self.food_saturation.load());
+        }
+    }
+
+    fn tick_effects(&self) {
+        let mut health_change = 0.0;
+        let expired = {
+            let mut effects = self.status_effects.lock();
+            if effects.is_empty() {
+                return;
+            }
+            for effect in effects.iter() {
+                match effect.effect_type {
+                    EffectType::Regeneration if should_apply_effect(effect, 50) => {
+                        health_change += 1.0
+                    }
+                    EffectType::Poison if should_apply_effect(effect, 25) => health_change -= 1.0,
+                    // Speed, Jump Boost and most other effects are predicted by the client itself
+                    _ => {}
+                }
+            }
+            effects.tick()
+        };
+        for effect_type in expired {
+            self.send_effect_removal(effect_type);
+        }
+
+        if health_change != 0.0 {
+            let health = self.entity.health.load();
+            let new_health = if health_change > 0.0 {
+                (health + health_change).min(20.0)
+            } else {
+                // Poison never kills
+                (health + health_change).max(1.0f32.min(health))
+            };
+            if new_health != health {
+                self.update_health(
+                    new_health,
+                    self.food.load(std::sync::atomic::Ordering::Relaxed),
+                    self.food_saturation.load(),
+                );
+            }
+        }
     }
 
     pub fn set_gamemode(&self, gamemode: GameMode) {
@@ -267,14 +824,13 @@ impl Player {
 
 impl Player {
     pub async fn process_packets(&self, server: &Arc<Server>) {
-        let mut packets = self.client.client_packets_queue.lock();
-        while let Some(mut packet) = packets.pop() {
+        while let Some(mut packet) = self.client.next_packet() {
             match self.handle_play_packet(server, &mut packet).await {
                 Ok(_) => {}
                 Err(e) => {
                     let text = format!("Error while reading incoming packet {}", e);
                     log::error!("{}", text);
-                    self.kick(TextComponent::text(&text))
+                    self.kick(DisconnectReason::ProtocolError(Some(text)))
                 }
             };
         }
@@ -314,6 +870,20 @@ impl Player {
                 self.handle_player_ground(server, SSetPlayerGround::read(bytebuf)?);
                 Ok(())
             }
+            SClientCommand::PACKET_ID => {
+                self.handle_client_command(server, SClientCommand::read(bytebuf)?)
+                    .await;
+                Ok(())
+            }
+            SPlayerInput::PACKET_ID => {
+                self.handle_player_input(server, SPlayerInput::read(bytebuf)?);
+                Ok(())
+            }
+            SMoveVehicle::PACKET_ID => {
+                self.handle_move_vehicle(server, SMoveVehicle::read(bytebuf)?)
+                    .await;
+                Ok(())
+            }
             SPlayerCommand::PACKET_ID => {
                 self.handle_player_command(server, SPlayerCommand::read(bytebuf)?)
                     .await;
@@ -324,13 +894,35 @@ impl Player {
                     .await;
                 Ok(())
             }
+            SChangeDifficulty::PACKET_ID => {
+                self.handle_change_difficulty(server, SChangeDifficulty::read(bytebuf)?);
+                Ok(())
+            }
             SChatMessage::PACKET_ID => {
                 self.handle_chat_message(server, SChatMessage::read(bytebuf)?)
                     .await;
                 Ok(())
             }
+            SPlayerSession::PACKET_ID => {
+                self.handle_player_session(server, SPlayerSession::read(bytebuf)?);
+                Ok(())
+            }
+            SMessageAcknowledgement::PACKET_ID => {
+                self.handle_message_acknowledgement(
+                    server,
+                    SMessageAcknowledgement::read(bytebuf)?,
+                );
+                Ok(())
+            }
+            SCookieResponsePlay::PACKET_ID => {
+                let response = SCookieResponsePlay::read(bytebuf)?;
+                self.client
+                    .handle_cookie_response(response.key, response.payload);
+                Ok(())
+            }
             SClientInformationPlay::PACKET_ID => {
-                self.handle_client_information_play(server, SClientInformationPlay::read(bytebuf)?);
+                self.handle_client_information_play(server, SClientInformationPlay::read(bytebuf)?)
+                    .await;
                 Ok(())
             }
             SInteract::PACKET_ID => {
@@ -348,6 +940,10 @@ impl Player {
                     .await;
                 Ok(())
             }
+            SUpdateSign::PACKET_ID => {
+                self.handle_update_sign(server, SUpdateSign::read(bytebuf)?);
+                Ok(())
+            }
             SUseItem::PACKET_ID => {
                 self.handle_use_item(server, SUseItem::read(bytebuf)?);
                 Ok(())
@@ -357,8 +953,18 @@ impl Player {
                 Ok(())
             }
             SSetCreativeSlot::PACKET_ID => {
-                self.handle_set_creative_slot(server, SSetCreativeSlot::read(bytebuf)?)
-                    .unwrap();
+                if let Err(err) =
+                    self.handle_set_creative_slot(server, SSetCreativeSlot::read(bytebuf)?)
+                {
+                    log::warn!(
+                        "{} sent an invalid creative inventory action: {}",
+                        self.gameprofile.name,
+                        err
+                    );
+                    if err.should_kick() {
+                        self.kick(DisconnectReason::ProtocolError(Some(err.to_string())));
+                    }
+                }
                 Ok(())
             }
             SPlayPingRequest::PACKET_ID => {
@@ -385,13 +991,19 @@ impl Player {
                 Ok(())
             }
             _ => {
-                log::error!("Failed to handle player packet id {:#04x}", packet.id.0);
+                skip_unknown_packet(ConnectionState::Play, packet);
                 Ok(())
             }
         }
     }
 }
 
+/// Effects like Regeneration or Poison only do something every few ticks, higher levels apply more often
+fn should_apply_effect(effect: &StatusEffect, base_interval: i32) -> bool {
+    let interval = base_interval >> effect.amplifier.min(31);
+    interval == 0 || effect.duration.rem_euclid(interval) == 0
+}
+
 /// Represents a player's abilities and special powers.
 ///
 /// This struct contains information about the player's current abilities, such as flight, invulnerability, and creative mode.
@@ -423,6 +1035,22 @@ impl Default for PlayerAbilities {
     }
 }
 
+/// The signed chat session of a player.
+///
+/// 1.19+ clients sign their chat messages with a Mojang-issued key pair. The client sends us its public key once after joining,
+/// so messages could be verified and relayed to other clients which then can validate the signature themselves.
+#[derive(Clone)]
+pub struct ChatSession {
+    /// A random id the client generated for this session.
+    pub session_id: uuid::Uuid,
+    /// The time in epoch milliseconds after which the public key is no longer valid.
+    pub expires_at: i64,
+    /// The player's public key, encoded in DER.
+    pub public_key: bytes::Bytes,
+    /// The signature of the public key, signed by Mojang.
+    pub key_signature: bytes::Bytes,
+}
+
 /// Represents the player's dominant hand.
 #[derive(FromPrimitive, Clone)]
 pub enum Hand {
@@ -432,6 +1060,16 @@ pub enum Hand {
     Off,
 }
 
+impl Hand {
+    /// The animation other players see when this hand is swung
+    pub const fn swing_animation(&self) -> Animation {
+        match self {
+            Self::Main => Animation::SwingMainArm,
+            Self::Off => Animation::SwingOffhand,
+        }
+    }
+}
+
 /// Represents the player's chat mode settings.
 #[derive(FromPrimitive, Clone)]
 pub enum ChatMode {
@@ -442,3 +1080,33 @@ pub enum ChatMode {
     /// All messages should be hidden
     Hidden,
 }
+
+#[cfg(test)]
+mod test {
+    use mio::Token;
+    use pumpkin_protocol::{client::play::Animation, ConnectionState};
+
+    use super::Hand;
+    use crate::{
+        client::disconnect::DisconnectReason,
+        test_helpers::{client, new_player, player_of, world},
+    };
+
+    #[test]
+    fn test_swing_animation() {
+        assert_eq!(Hand::Main.swing_animation(), Animation::SwingMainArm);
+        assert_eq!(Hand::Off.swing_animation(), Animation::SwingOffhand);
+    }
+
+    #[test]
+    fn test_kick_after_leaving() {
+        let world = world("kick");
+        let player = new_player(&world);
+        assert!(player.kick(DisconnectReason::Kicked(None)));
+        // e.g. a ban and the whitelist kicking at the same time
+        assert!(!player.kick(DisconnectReason::Kicked(None)));
+
+        let configuring = player_of(&world, client(Token(2), ConnectionState::Config), 3, "Alex");
+        assert!(!configuring.kick(DisconnectReason::Kicked(None)));
+    }
+}
@@ -0,0 +1,114 @@
+use pumpkin_entity::pose::EntityPose;
+use pumpkin_world::block::{find_block_state, BlockId};
+
+/// What decides the pose of a Player, see `PoseState::pose`
+#[derive(Default, Clone, Copy, Debug)]
+pub struct PoseState {
+    pub sleeping: bool,
+    /// Sprinting in water
+    pub swimming: bool,
+    /// Gliding with an elytra
+    pub fall_flying: bool,
+    /// Sneaking while not flying
+    pub sneaking: bool,
+    /// Something is in the way of the Player's head, so they have to crawl
+    pub blocked_above: bool,
+}
+
+impl PoseState {
+    /// The pose in the order vanilla picks it, crawling uses the swimming pose
+    pub const fn pose(&self) -> EntityPose {
+        if self.sleeping {
+            EntityPose::Sleeping
+        } else if self.swimming {
+            EntityPose::Swimming
+        } else if self.fall_flying {
+            EntityPose::FallFlying
+        } else if self.blocked_above {
+            EntityPose::Swimming
+        } else if self.sneaking {
+            EntityPose::Crouching
+        } else {
+            EntityPose::Standing
+        }
+    }
+}
+
+pub fn is_water(block: BlockId) -> bool {
+    find_block_state(block).is_some_and(|(name, _)| name == "minecraft:water")
+}
+
+/// We don't know which blocks are solid yet, so anything but air and water is in the way
+pub fn is_obstruction(block: BlockId) -> bool {
+    !block.is_air() && !is_water(block)
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_entity::pose::EntityPose;
+
+    use super::PoseState;
+
+    #[test]
+    fn test_pose_metadata_values() {
+        let cases = [
+            (PoseState::default(), EntityPose::Standing, 0),
+            (
+                PoseState {
+                    sneaking: true,
+                    ..Default::default()
+                },
+                EntityPose::Crouching,
+                5,
+            ),
+            (
+                PoseState {
+                    swimming: true,
+                    sneaking: true,
+                    ..Default::default()
+                },
+                EntityPose::Swimming,
+                3,
+            ),
+            (
+                PoseState {
+                    fall_flying: true,
+                    sneaking: true,
+                    ..Default::default()
+                },
+                EntityPose::FallFlying,
+                1,
+            ),
+            (
+                PoseState {
+                    sleeping: true,
+                    swimming: true,
+                    ..Default::default()
+                },
+                EntityPose::Sleeping,
+                2,
+            ),
+        ];
+        for (state, pose, value) in cases {
+            assert_eq!(state.pose(), pose, "{:?}", state);
+            assert_eq!(state.pose() as i32, value, "{:?}", state);
+        }
+    }
+
+    #[test]
+    fn test_crawling() {
+        let state = PoseState {
+            blocked_above: true,
+            sneaking: true,
+            ..Default::default()
+        };
+        assert_eq!(state.pose(), EntityPose::Swimming);
+        // Gliding and swimming don't need more room
+        let state = PoseState {
+            blocked_above: true,
+            fall_flying: true,
+            ..Default::default()
+        };
+        assert_eq!(state.pose(), EntityPose::FallFlying);
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+};
+
+use parking_lot::RwLock;
+use pumpkin_core::math::position::WorldPosition;
+use pumpkin_entity::EntityId;
+
+use crate::client::authentication::GameProfile;
+
+/// Something which happened on the Server that handlers can react to
+pub trait Event: Any + Send + Sync {}
+
+/// An Event which can stop the Server from doing its default action
+pub trait Cancellable: Event {
+    fn is_cancelled(&self) -> bool;
+
+    fn set_cancelled(&mut self, cancelled: bool);
+}
+
+/// Fired after a Player was added to a World, before the World is sent to them
+pub struct PlayerJoin {
+    pub profile: GameProfile,
+    pub entity_id: EntityId,
+}
+
+impl Event for PlayerJoin {}
+
+/// Fired when a Player disconnects, before they are removed from their World
+pub struct PlayerQuit {
+    pub profile: GameProfile,
+    pub entity_id: EntityId,
+}
+
+impl Event for PlayerQuit {}
+
+/// Fired when a Player sends a chat message, cancelling it stops the message from being broadcast
+pub struct PlayerChat {
+    pub profile: GameProfile,
+    pub message: String,
+    pub cancelled: bool,
+}
+
+impl Event for PlayerChat {}
+
+impl Cancellable for PlayerChat {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    fn set_cancelled(&mut self, cancelled: bool) {
+        self.cancelled = cancelled;
+    }
+}
+
+/// Fired when a Player places a Block, cancelling it keeps the old Block
+pub struct BlockPlace {
+    pub profile: GameProfile,
+    pub location: WorldPosition,
+    pub block_state_id: u16,
+    pub cancelled: bool,
+}
+
+impl Event for BlockPlace {}
+
+impl Cancellable for BlockPlace {
+    fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    fn set_cancelled(&mut self, cancelled: bool) {
+        self.cancelled = cancelled;
+    }
+}
+
+type Handler<E> = Box<dyn Fn(&mut E) + Send + Sync>;
+
+/// Dispatches Events to the handlers subscribed to them.
+///
+/// Dispatching is synchronous, handlers are called in the order they subscribed
+#[derive(Default)]
+pub struct EventBus {
+    /// Maps the type of an Event to a `Vec<Handler<E>>`
+    handlers: RwLock<HashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+}
+
+impl EventBus {
+    pub fn subscribe<E: Event>(&self, handler: impl Fn(&mut E) + Send + Sync + 'static) {
+        let mut handlers = self.handlers.write();
+        let handlers = handlers
+            .entry(TypeId::of::<E>())
+            .or_insert_with(|| Box::new(Vec::<Handler<E>>::new()));
+        if let Some(handlers) = handlers.downcast_mut::<Vec<Handler<E>>>() {
+            handlers.push(Box::new(handler));
+        }
+    }
+
+    /// Calls every handler of the Event and returns it, so callers can check if it was cancelled.
+    ///
+    /// **Note:** Handlers must not subscribe new handlers, this would deadlock
+    pub fn fire<E: Event>(&self, mut event: E) -> E {
+        let handlers = self.handlers.read();
+        if let Some(handlers) = handlers
+            .get(&TypeId::of::<E>())
+            .and_then(|handlers| handlers.downcast_ref::<Vec<Handler<E>>>())
+        {
+            for handler in handlers {
+                handler(&mut event);
+            }
+        }
+        event
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use parking_lot::Mutex;
+    use uuid::Uuid;
+
+    use super::{Cancellable, EventBus, PlayerChat, PlayerJoin, PlayerQuit};
+    use crate::client::authentication::GameProfile;
+
+    fn profile() -> GameProfile {
+        GameProfile {
+            id: Uuid::nil(),
+            name: "Steve".to_string(),
+            properties: Vec::new(),
+            profile_actions: None,
+        }
+    }
+
+    #[test]
+    fn test_observe_join_and_quit() {
+        let bus = EventBus::default();
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let join_log = log.clone();
+        bus.subscribe(move |event: &mut PlayerJoin| {
+            join_log.lock().push(format!("join {}", event.profile.name))
+        });
+        let quit_log = log.clone();
+        bus.subscribe(move |event: &mut PlayerQuit| {
+            quit_log.lock().push(format!("quit {}", event.profile.name))
+        });
+
+        bus.fire(PlayerJoin {
+            profile: profile(),
+            entity_id: 2,
+        });
+        bus.fire(PlayerQuit {
+            profile: profile(),
+            entity_id: 2,
+        });
+
+        assert_eq!(*log.lock(), vec!["join Steve", "quit Steve"]);
+    }
+
+    #[test]
+    fn test_handlers_run_in_order() {
+        let bus = EventBus::default();
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let first = calls.clone();
+        bus.subscribe(move |_: &mut PlayerJoin| {
+            assert_eq!(first.fetch_add(1, Ordering::SeqCst), 0);
+        });
+        let second = calls.clone();
+        bus.subscribe(move |_: &mut PlayerJoin| {
+            assert_eq!(second.fetch_add(1, Ordering::SeqCst), 1);
+        });
+
+        bus.fire(PlayerJoin {
+            profile: profile(),
+            entity_id: 2,
+        });
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_cancel_chat() {
+        let bus = EventBus::default();
+        let chat = || PlayerChat {
+            profile: profile(),
+            message: "hello".to_string(),
+            cancelled: false,
+        };
+        assert!(!bus.fire(chat()).is_cancelled());
+
+        bus.subscribe(|event: &mut PlayerChat| {
+            if event.message.contains("hello") {
+                event.set_cancelled(true);
+            }
+        });
+        assert!(bus.fire(chat()).is_cancelled());
+    }
+}
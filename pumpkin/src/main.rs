@@ -16,13 +16,12 @@
 #[cfg(target_os = "wasi")]
 compile_error!("Compiling for WASI targets is not supported!");
 
-use mio::net::TcpListener;
 use mio::{Events, Interest, Poll, Token};
 
-use client::{interrupted, Client};
+use client::{disconnect::DisconnectReason, interrupted, Client};
 use pumpkin_protocol::client::play::CKeepAlive;
 use pumpkin_protocol::ConnectionState;
-use server::Server;
+use server::{metrics::METRICS, Server};
 use std::collections::HashMap;
 use std::io::{self, Read};
 use std::time::Duration;
@@ -32,9 +31,13 @@ use std::time::Duration;
 pub mod client;
 pub mod commands;
 pub mod entity;
+pub mod events;
 pub mod proxy;
+pub mod query;
 pub mod rcon;
 pub mod server;
+#[cfg(test)]
+mod test_helpers;
 pub mod util;
 pub mod world;
 
@@ -51,10 +54,8 @@ fn main() -> io::Result<()> {
         .init()
         .unwrap();
 
-    let rt = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .unwrap();
+    let threads = &ADVANCED_CONFIG.threads;
+    let rt = server::threads::runtime_builder(threads).build().unwrap();
 
     ctrlc::set_handler(|| {
         log::warn!(
@@ -67,7 +68,14 @@ fn main() -> io::Result<()> {
     })
     .unwrap();
     // ensure rayon is built outside of tokio scope
-    rayon::ThreadPoolBuilder::new().build_global().unwrap();
+    server::threads::rayon_pool_builder(threads)
+        .build_global()
+        .unwrap();
+    log::info!(
+        "Using {} tokio worker threads and {} rayon threads",
+        threads.tokio_worker_threads,
+        threads.rayon_threads
+    );
     rt.block_on(async {
         const SERVER: Token = Token(0);
         use std::time::Instant;
@@ -81,7 +89,7 @@ fn main() -> io::Result<()> {
 
         // Setup the TCP server socket.
         let addr = BASIC_CONFIG.server_address;
-        let mut listener = TcpListener::bind(addr)?;
+        let mut listener = server::socket::bind_listener(addr, &ADVANCED_CONFIG.socket)?;
 
         // Register the server with poll we can receive events for it.
         poll.registry()
@@ -127,6 +135,93 @@ fn main() -> io::Result<()> {
                 RCONServer::new(&rcon, server).await.unwrap();
             });
         }
+        if ADVANCED_CONFIG.query.enabled {
+            let server = server.clone();
+            tokio::spawn(async move {
+                let config = &ADVANCED_CONFIG.query;
+                log::info!("Answering queries on {}", config.address);
+                if let Err(err) = query::run(config, server).await {
+                    log::error!("Query stopped: {}", err);
+                }
+            });
+        }
+        if BASIC_CONFIG.autosave_interval_secs > 0 {
+            let server = server.clone();
+            tokio::spawn(async move {
+                let period = Duration::from_secs(BASIC_CONFIG.autosave_interval_secs);
+                let mut interval =
+                    tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+                loop {
+                    interval.tick().await;
+                    let server = server.clone();
+                    let time = Instant::now();
+                    if let Ok(Some(saved)) =
+                        tokio::task::spawn_blocking(move || server.autosave.run(|| server.save()))
+                            .await
+                    {
+                        log::info!(
+                            "Saved the game ({} chunks), took {}ms",
+                            saved,
+                            time.elapsed().as_millis()
+                        );
+                    }
+                }
+            });
+        }
+        if ADVANCED_CONFIG.metrics.enabled {
+            let server = server.clone();
+            tokio::spawn(async move {
+                let address = ADVANCED_CONFIG.metrics.address;
+                match tokio::net::TcpListener::bind(address).await {
+                    Ok(listener) => {
+                        log::info!("Serving metrics on http://{}/metrics", address);
+                        if let Err(err) =
+                            server::metrics::serve(listener, move || METRICS.render_server(&server))
+                                .await
+                        {
+                            log::error!("Metrics endpoint stopped: {}", err);
+                        }
+                    }
+                    Err(err) => log::error!("Failed to bind metrics to {}: {}", address, err),
+                }
+            });
+        }
+        if ADVANCED_CONFIG.http_api.enabled {
+            let server = server.clone();
+            tokio::spawn(async move {
+                let config = &ADVANCED_CONFIG.http_api;
+                match tokio::net::TcpListener::bind(config.address).await {
+                    Ok(listener) => {
+                        log::info!("Serving the HTTP API on http://{}", config.address);
+                        let status_server = server.clone();
+                        let api = server::http_api::HttpApi::new(
+                            config,
+                            move || server::http_api::StatusReport::new(&status_server),
+                            move |command: &str| server::http_api::run_command(&server, command),
+                        );
+                        if let Err(err) = Arc::new(api).serve(listener).await {
+                            log::error!("HTTP API stopped: {}", err);
+                        }
+                    }
+                    Err(err) => {
+                        log::error!("Failed to bind the HTTP API to {}: {}", config.address, err)
+                    }
+                }
+            });
+        }
+        {
+            let server = server.clone();
+            tokio::spawn(async move {
+                // Every World ticks at its own rate, this wakes up for the next one which is due
+                loop {
+                    let start = std::time::Instant::now();
+                    let next_tick = server.tick(start);
+                    server.remove_closed_players().await;
+                    METRICS.record_tick(start, start.elapsed());
+                    tokio::time::sleep_until(next_tick.into()).await;
+                }
+            });
+        }
         loop {
             if let Err(err) = poll.poll(&mut events, None) {
                 if interrupted(&err) {
@@ -140,36 +235,32 @@ fn main() -> io::Result<()> {
                     SERVER => loop {
                         // Received an event for the TCP server socket, which
                         // indicates we can accept an connection.
-                        let (mut connection, address) = match listener.accept() {
-                            Ok((connection, address)) => (connection, address),
-                            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
-                                // If we get a `WouldBlock` error we know our
-                                // listener has no more incoming connections queued,
-                                // so we can return to polling and wait for some
-                                // more.
-                                break;
-                            }
+                        let keep_alive = tokio::sync::mpsc::channel(1024);
+                        let client = match Client::accept(
+                            &listener,
+                            || next(&mut unique_token),
+                            keep_alive.0.into(),
+                        ) {
+                            Ok(Some(client)) => Arc::new(client),
+                            // If we get no client we know our listener has no more
+                            // incoming connections queued, so we can return to
+                            // polling and wait for some more.
+                            Ok(None) => break,
                             Err(e) => {
                                 // If it was any other kind of error, something went
                                 // wrong and we terminate with an error.
                                 return Err(e);
                             }
                         };
-                        if let Err(e) = connection.set_nodelay(true) {
-                            log::warn!("failed to set TCP_NODELAY {e}");
-                        }
 
-                        log::info!("Accepted connection from: {}", address);
+                        log::info!("Accepted connection from: {}", client.address.lock());
+                        METRICS.connection_opened();
 
-                        let token = next(&mut unique_token);
                         poll.registry().register(
-                            &mut connection,
-                            token,
+                            &mut *client.connection.lock(),
+                            client.token,
                             Interest::READABLE.add(Interest::WRITABLE),
                         )?;
-                        let keep_alive = tokio::sync::mpsc::channel(1024);
-                        let client =
-                            Arc::new(Client::new(token, connection, addr, keep_alive.0.into()));
 
                         {
                             let client = client.clone();
@@ -184,7 +275,7 @@ fn main() -> io::Result<()> {
                                             >= Duration::from_secs(15)
                                         {
                                             dbg!("no keep alive");
-                                            client.kick("No keep alive received");
+                                            client.kick(DisconnectReason::Timeout);
                                             break;
                                         }
                                         let random = rand::random::<i64>();
@@ -194,6 +285,7 @@ fn main() -> io::Result<()> {
                                         if let Some(id) = receiver.recv().await {
                                             if id == random {
                                                 client.last_alive_received.store(now);
+                                                client.latency.store(now.elapsed());
                                             }
                                         }
                                     } else {
@@ -202,7 +294,7 @@ fn main() -> io::Result<()> {
                                 }
                             });
                         }
-                        clients.insert(token, client);
+                        clients.insert(client.token, client);
                     },
 
                     token => {
@@ -218,9 +310,12 @@ fn main() -> io::Result<()> {
                             }
                             if closed {
                                 if let Some(player) = players.remove(&token) {
-                                    player.remove().await;
+                                    server.remove_player(&player).await;
+                                    // Writes what is left of the queue, e.g. the disconnect packet, as far as the connection takes it
+                                    player.client.flush_write_queue();
                                     let connection = &mut player.client.connection.lock();
                                     poll.registry().deregister(connection.by_ref())?;
+                                    METRICS.connection_closed();
                                 }
                             }
                         };
@@ -246,13 +341,18 @@ fn main() -> io::Result<()> {
                         if done || make_player {
                             if let Some(client) = clients.remove(&token) {
                                 if done {
+                                    server.release_player_slot(&client);
+                                    client.flush_write_queue();
                                     let connection = &mut client.connection.lock();
                                     poll.registry().deregister(connection.by_ref())?;
+                                    METRICS.connection_closed();
                                 } else if make_player {
                                     let token = client.token;
                                     let (player, world) = server.add_player(token, client).await;
                                     players.insert(token, player.clone());
                                     world.spawn_player(&BASIC_CONFIG, player).await;
+                                    // Players who left vanished are vanished again once their data is loaded
+                                    server.update_status();
                                 }
                             }
                         }
@@ -8,7 +8,7 @@ use pumpkin_protocol::{
 };
 use sha2::Sha256;
 
-use crate::client::Client;
+use crate::client::{disconnect::DisconnectReason, Client};
 
 type HmacSha256 = Hmac<Sha256>;
 
@@ -45,7 +45,9 @@ pub fn receive_plugin_response(
         let (signature, data_without_signature) = data.split_at(32);
 
         if !check_integrity((signature, data_without_signature), config.secret) {
-            client.kick("Unable to verify player details");
+            client.kick(DisconnectReason::Unverified(Some(
+                "Unable to verify player details".to_string(),
+            )));
             return;
         }
         let mut buf = ByteBuffer::new(BytesMut::new());
@@ -55,9 +57,9 @@ pub fn receive_plugin_response(
         let version = buf.get_var_int().unwrap();
         let version = version.0;
         if version > MAX_SUPPORTED_FORWARDING_VERSION {
-            client.kick(&format!(
+            client.kick(DisconnectReason::Unverified(Some(format!(
                 "Unsupported forwarding version {version}, Max: {MAX_SUPPORTED_FORWARDING_VERSION}"
-            ));
+            ))));
             return;
         }
         // TODO: no unwrap
@@ -65,6 +67,8 @@ pub fn receive_plugin_response(
         *client.address.lock() = addr;
         todo!()
     } else {
-        client.kick("This server requires you to connect with Velocity.")
+        client.kick(DisconnectReason::Unverified(Some(
+            "This server requires you to connect with Velocity.".to_string(),
+        )))
     }
 }
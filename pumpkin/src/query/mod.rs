@@ -0,0 +1,348 @@
+//! Answers the UDP Query protocol (also known as GameSpy4) used by server lists and monitoring tools.
+//!
+//! A client first sends a handshake to get a challenge token, then requests a basic or full stat with it.
+use std::{
+    collections::HashMap,
+    io,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use pumpkin_config::{QueryConfig, BASIC_CONFIG};
+use tokio::net::UdpSocket;
+
+use crate::server::{Server, CURRENT_MC_VERSION};
+
+const MAGIC: [u8; 2] = [0xFE, 0xFD];
+
+const TYPE_HANDSHAKE: u8 = 9;
+const TYPE_STAT: u8 = 0;
+
+/// Challenge tokens have to be requested again after this time
+const TOKEN_LIFETIME: Duration = Duration::from_secs(30);
+
+/// Constant padding before the key values of a full stat response
+const FULL_STAT_PADDING: &[u8] = b"splitnum\x00\x80\x00";
+/// Constant padding before the player list of a full stat response
+const PLAYER_PADDING: &[u8] = b"\x01player_\x00\x00";
+
+const GAME_TYPE: &str = "SMP";
+const GAME_ID: &str = "MINECRAFT";
+
+/// The challenge tokens handed out to each address
+#[derive(Default)]
+pub struct ChallengeTokens {
+    tokens: HashMap<SocketAddr, (i32, Instant)>,
+}
+
+impl ChallengeTokens {
+    /// Creates a new token for the address, replacing the old one
+    pub fn generate(&mut self, address: SocketAddr, now: Instant) -> i32 {
+        // Tools parse the token from a string, so keep it positive
+        let token = rand::random::<i32>() & i32::MAX;
+        self.tokens.insert(address, (token, now));
+        token
+    }
+
+    pub fn is_valid(&self, address: SocketAddr, token: i32, now: Instant) -> bool {
+        self.tokens.get(&address).is_some_and(|(expected, issued)| {
+            *expected == token && now.duration_since(*issued) < TOKEN_LIFETIME
+        })
+    }
+
+    pub fn remove_expired(&mut self, now: Instant) {
+        self.tokens
+            .retain(|_, (_, issued)| now.duration_since(*issued) < TOKEN_LIFETIME);
+    }
+}
+
+/// Everything a stat response tells about the Server
+pub struct QueryInfo {
+    pub motd: String,
+    pub version: String,
+    pub map: String,
+    pub players: Vec<String>,
+    pub max_players: u32,
+    pub host_ip: String,
+    pub host_port: u16,
+}
+
+impl QueryInfo {
+    pub fn from_server(server: &Server) -> Self {
+        let map = server.worlds[0]
+            .level
+            .root_folder()
+            .file_name()
+            .map_or_else(|| "world".to_string(), |name| name.to_string_lossy().into());
+        let players = server
+            .worlds
+            .iter()
+            .flat_map(|world| {
+                world
+                    .current_players
+                    .lock()
+                    .values()
+                    .filter(|player| !player.is_vanished())
+                    .map(|player| player.gameprofile.name.clone())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        Self {
+            motd: BASIC_CONFIG.motd.clone(),
+            version: CURRENT_MC_VERSION.to_string(),
+            map,
+            players,
+            max_players: BASIC_CONFIG.max_players,
+            host_ip: BASIC_CONFIG.server_address.ip().to_string(),
+            host_port: BASIC_CONFIG.server_address.port(),
+        }
+    }
+}
+
+/// Returns the response to a query packet, invalid packets are not answered
+pub fn handle_packet(
+    tokens: &mut ChallengeTokens,
+    address: SocketAddr,
+    packet: &[u8],
+    now: Instant,
+    info: impl FnOnce() -> QueryInfo,
+) -> Option<Vec<u8>> {
+    if packet.len() < 7 || packet[..2] != MAGIC {
+        return None;
+    }
+    let session_id = i32::from_be_bytes(packet[3..7].try_into().ok()?);
+    let payload = &packet[7..];
+    match packet[2] {
+        TYPE_HANDSHAKE => Some(handshake_response(
+            session_id,
+            tokens.generate(address, now),
+        )),
+        TYPE_STAT if payload.len() >= 4 => {
+            let token = i32::from_be_bytes(payload[..4].try_into().ok()?);
+            if !tokens.is_valid(address, token, now) {
+                return None;
+            }
+            // A full stat request pads the challenge token with 4 bytes
+            if payload.len() >= 8 {
+                Some(full_stat(session_id, &info()))
+            } else {
+                Some(basic_stat(session_id, &info()))
+            }
+        }
+        _ => None,
+    }
+}
+
+fn response_header(kind: u8, session_id: i32) -> Vec<u8> {
+    let mut buf = vec![kind];
+    buf.extend_from_slice(&session_id.to_be_bytes());
+    buf
+}
+
+/// Strings are null terminated, so they can not contain null bytes themselves
+fn put_string(buf: &mut Vec<u8>, s: &str) {
+    buf.extend(s.bytes().filter(|byte| *byte != 0));
+    buf.push(0);
+}
+
+fn handshake_response(session_id: i32, token: i32) -> Vec<u8> {
+    let mut buf = response_header(TYPE_HANDSHAKE, session_id);
+    put_string(&mut buf, &token.to_string());
+    buf
+}
+
+fn basic_stat(session_id: i32, info: &QueryInfo) -> Vec<u8> {
+    let mut buf = response_header(TYPE_STAT, session_id);
+    put_string(&mut buf, &info.motd);
+    put_string(&mut buf, GAME_TYPE);
+    put_string(&mut buf, &info.map);
+    put_string(&mut buf, &info.players.len().to_string());
+    put_string(&mut buf, &info.max_players.to_string());
+    // The only little endian field of the protocol
+    buf.extend_from_slice(&info.host_port.to_le_bytes());
+    put_string(&mut buf, &info.host_ip);
+    buf
+}
+
+fn full_stat(session_id: i32, info: &QueryInfo) -> Vec<u8> {
+    let mut buf = response_header(TYPE_STAT, session_id);
+    buf.extend_from_slice(FULL_STAT_PADDING);
+    let key_values = [
+        ("hostname", info.motd.clone()),
+        ("gametype", GAME_TYPE.to_string()),
+        ("game_id", GAME_ID.to_string()),
+        ("version", info.version.clone()),
+        ("plugins", String::new()),
+        ("map", info.map.clone()),
+        ("numplayers", info.players.len().to_string()),
+        ("maxplayers", info.max_players.to_string()),
+        ("hostport", info.host_port.to_string()),
+        ("hostip", info.host_ip.clone()),
+    ];
+    for (key, value) in key_values {
+        put_string(&mut buf, key);
+        put_string(&mut buf, &value);
+    }
+    buf.push(0);
+    buf.extend_from_slice(PLAYER_PADDING);
+    for player in &info.players {
+        put_string(&mut buf, player);
+    }
+    buf.push(0);
+    buf
+}
+
+/// Answers query requests until the socket fails
+pub async fn run(config: &QueryConfig, server: Arc<Server>) -> io::Result<()> {
+    let socket = UdpSocket::bind(config.address).await?;
+    let mut tokens = ChallengeTokens::default();
+    let mut buf = [0; 1460];
+    loop {
+        let (len, address) = socket.recv_from(&mut buf).await?;
+        let now = Instant::now();
+        tokens.remove_expired(now);
+        if let Some(response) = handle_packet(&mut tokens, address, &buf[..len], now, || {
+            QueryInfo::from_server(&server)
+        }) {
+            if let Err(err) = socket.send_to(&response, address).await {
+                log::debug!("Failed to answer query from {}: {}", address, err);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        net::SocketAddr,
+        time::{Duration, Instant},
+    };
+
+    use super::{handle_packet, ChallengeTokens, QueryInfo, TOKEN_LIFETIME};
+
+    fn address(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    fn info() -> QueryInfo {
+        QueryInfo {
+            motd: "A Pumpkin Server".to_string(),
+            version: "1.21.1".to_string(),
+            map: "world".to_string(),
+            players: vec!["Alex".to_string(), "Steve".to_string()],
+            max_players: 20,
+            host_ip: "127.0.0.1".to_string(),
+            host_port: 25565,
+        }
+    }
+
+    fn stat_request(session_id: i32, token: i32, full: bool) -> Vec<u8> {
+        let mut packet = vec![0xFE, 0xFD, 0];
+        packet.extend_from_slice(&session_id.to_be_bytes());
+        packet.extend_from_slice(&token.to_be_bytes());
+        if full {
+            packet.extend_from_slice(&[0; 4]);
+        }
+        packet
+    }
+
+    /// Sends the handshake and parses the token from the response
+    fn handshake(tokens: &mut ChallengeTokens, from: SocketAddr, now: Instant) -> i32 {
+        let mut packet = vec![0xFE, 0xFD, 9];
+        packet.extend_from_slice(&1_i32.to_be_bytes());
+        let response = handle_packet(tokens, from, &packet, now, info).unwrap();
+        assert_eq!(response[..5], [9, 0, 0, 0, 1]);
+        assert_eq!(*response.last().unwrap(), 0);
+        std::str::from_utf8(&response[5..response.len() - 1])
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_challenge_tokens() {
+        let mut tokens = ChallengeTokens::default();
+        let now = Instant::now();
+        let token = tokens.generate(address(1), now);
+        assert!(token >= 0);
+        assert!(tokens.is_valid(address(1), token, now));
+        assert!(!tokens.is_valid(address(1), token.wrapping_add(1), now));
+        assert!(!tokens.is_valid(address(2), token, now));
+        assert!(!tokens.is_valid(address(1), token, now + TOKEN_LIFETIME));
+
+        tokens.remove_expired(now + TOKEN_LIFETIME);
+        assert!(!tokens.is_valid(address(1), token, now));
+    }
+
+    #[test]
+    fn test_stat_needs_token() {
+        let mut tokens = ChallengeTokens::default();
+        let now = Instant::now();
+        let token = handshake(&mut tokens, address(1), now);
+
+        assert!(handle_packet(
+            &mut tokens,
+            address(2),
+            &stat_request(1, token, false),
+            now,
+            info
+        )
+        .is_none());
+        assert!(handle_packet(
+            &mut tokens,
+            address(1),
+            &stat_request(1, token, false),
+            now + Duration::from_secs(31),
+            info
+        )
+        .is_none());
+        assert!(handle_packet(&mut tokens, address(1), &[0xFE, 0xFD], now, info).is_none());
+    }
+
+    #[test]
+    fn test_basic_stat() {
+        let mut tokens = ChallengeTokens::default();
+        let now = Instant::now();
+        let token = handshake(&mut tokens, address(1), now);
+        let response = handle_packet(
+            &mut tokens,
+            address(1),
+            &stat_request(7, token, false),
+            now,
+            info,
+        )
+        .unwrap();
+
+        let mut expected = vec![0, 0, 0, 0, 7];
+        expected.extend_from_slice(b"A Pumpkin Server\0SMP\0world\x002\x0020\0");
+        expected.extend_from_slice(&25565_u16.to_le_bytes());
+        expected.extend_from_slice(b"127.0.0.1\0");
+        assert_eq!(response, expected);
+    }
+
+    #[test]
+    fn test_full_stat() {
+        let mut tokens = ChallengeTokens::default();
+        let now = Instant::now();
+        let token = handshake(&mut tokens, address(1), now);
+        let response = handle_packet(
+            &mut tokens,
+            address(1),
+            &stat_request(7, token, true),
+            now,
+            info,
+        )
+        .unwrap();
+
+        let mut expected = vec![0, 0, 0, 0, 7];
+        expected.extend_from_slice(b"splitnum\0\x80\0");
+        expected.extend_from_slice(
+            b"hostname\0A Pumpkin Server\0gametype\0SMP\0game_id\0MINECRAFT\0version\x001.21.1\0\
+              plugins\0\0map\0world\0numplayers\x002\0maxplayers\x0020\0hostport\x0025565\0\
+              hostip\x00127.0.0.1\0\0",
+        );
+        expected.extend_from_slice(b"\x01player_\0\0Alex\0Steve\0\0");
+        assert_eq!(response, expected);
+    }
+}
@@ -4,17 +4,16 @@ use std::{
     sync::Arc,
 };
 
-use mio::{
-    net::{TcpListener, TcpStream},
-    Events, Interest, Poll, Token,
-};
+use mio::{net::TcpListener, Events, Interest, Poll, Token};
 use packet::{ClientboundPacket, Packet, PacketError, ServerboundPacket};
 use pumpkin_config::RCONConfig;
 use thiserror::Error;
+use tls::RCONStream;
 
 use crate::server::Server;
 
 mod packet;
+mod tls;
 
 #[derive(Debug, Error)]
 pub enum RCONError {
@@ -28,6 +27,28 @@ pub enum RCONError {
 
 const SERVER: Token = Token(0);
 
+/// Largest body a single response packet may carry, longer responses are split across multiple packets
+const MAX_RESPONSE_BODY: usize = 4096;
+
+/// Joins the buffered output of a command into response bodies, one line per message
+fn response_bodies(output: &[String]) -> Vec<String> {
+    let response = output.join("\n");
+    let mut bodies = Vec::new();
+    let mut rest = response.as_str();
+    while rest.len() > MAX_RESPONSE_BODY {
+        let mut split = MAX_RESPONSE_BODY;
+        while !rest.is_char_boundary(split) {
+            split -= 1;
+        }
+        let (body, tail) = rest.split_at(split);
+        bodies.push(body.to_string());
+        rest = tail;
+    }
+    // Always answer, even if the command had no output, so clients don't wait forever
+    bodies.push(rest.to_string());
+    bodies
+}
+
 pub struct RCONServer;
 
 impl RCONServer {
@@ -48,6 +69,12 @@ impl RCONServer {
 
         let password = config.password.clone();
 
+        let tls = if config.tls.enabled {
+            Some(tls::server_config(&config.tls)?)
+        } else {
+            None
+        };
+
         loop {
             poll.poll(&mut events, None).unwrap();
 
@@ -87,7 +114,13 @@ impl RCONServer {
                                 Interest::READABLE.add(Interest::WRITABLE),
                             )
                             .unwrap();
-                        connections.insert(token, RCONClient::new(connection));
+                        connections.insert(
+                            token,
+                            RCONClient::new(
+                                RCONStream::new(connection, tls.as_ref())?,
+                                config.permission_level,
+                            ),
+                        );
                     },
 
                     token => {
@@ -98,7 +131,7 @@ impl RCONServer {
                         };
                         if done {
                             if let Some(mut client) = connections.remove(&token) {
-                                poll.registry().deregister(&mut client.connection)?;
+                                poll.registry().deregister(client.connection.socket())?;
                             }
                         }
                     }
@@ -115,16 +148,19 @@ impl RCONServer {
 }
 
 pub struct RCONClient {
-    connection: TcpStream,
+    connection: RCONStream,
+    /// The permission level the commands of this connection run with
+    permission_lvl: i32,
     logged_in: bool,
     incoming: Vec<u8>,
     closed: bool,
 }
 
 impl RCONClient {
-    pub const fn new(connection: TcpStream) -> Self {
+    pub const fn new(connection: RCONStream, permission_lvl: i32) -> Self {
         Self {
             connection,
+            permission_lvl,
             logged_in: false,
             incoming: Vec::new(),
             closed: false,
@@ -162,31 +198,21 @@ impl RCONClient {
             };
 
             match packet.get_type() {
-                ServerboundPacket::Auth => {
-                    let body = packet.get_body();
-                    if !body.is_empty() && packet.get_body() == password {
-                        self.send(ClientboundPacket::AuthResponse, packet.get_id(), "".into())
-                            .await?;
-                        log::info!("RCON Client logged in successfully");
-                        self.logged_in = true;
-                    } else {
-                        log::warn!("RCON Client has tried wrong password");
-                        self.send(ClientboundPacket::AuthResponse, -1, "".into())
-                            .await?;
-                        self.closed = true;
-                    }
-                }
+                ServerboundPacket::Auth => self.authenticate(&packet, password).await?,
                 ServerboundPacket::ExecCommand => {
                     if self.logged_in {
                         let mut output = Vec::new();
                         let dispatcher = server.command_dispatcher.clone();
                         dispatcher.handle_command(
-                            &mut crate::commands::CommandSender::Rcon(&mut output),
+                            &mut crate::commands::CommandSender::Rcon {
+                                buffer: &mut output,
+                                permission_lvl: self.permission_lvl,
+                            },
                             server,
                             packet.get_body(),
                         );
-                        for line in output {
-                            self.send(ClientboundPacket::Output, packet.get_id(), line)
+                        for body in response_bodies(&output) {
+                            self.send(ClientboundPacket::Output, packet.get_id(), body)
                                 .await?;
                         }
                     }
@@ -195,6 +221,22 @@ impl RCONClient {
         }
     }
 
+    async fn authenticate(&mut self, packet: &Packet, password: &str) -> Result<(), PacketError> {
+        let body = packet.get_body();
+        if !body.is_empty() && body == password {
+            self.send(ClientboundPacket::AuthResponse, packet.get_id(), "".into())
+                .await?;
+            log::info!("RCON Client logged in successfully");
+            self.logged_in = true;
+        } else {
+            log::warn!("RCON Client has tried wrong password");
+            self.send(ClientboundPacket::AuthResponse, -1, "".into())
+                .await?;
+            self.closed = true;
+        }
+        Ok(())
+    }
+
     fn read_bytes(&mut self) -> io::Result<bool> {
         let mut buf = [0; 1460];
         let n = self.connection.read(&mut buf)?;
@@ -215,10 +257,139 @@ impl RCONClient {
         self.connection
             .write(&buf)
             .map_err(PacketError::FailedSend)?;
-        Ok(())
+        // TLS buffers what it can't send yet, the rest goes out once the socket is writable again
+        match self.connection.flush() {
+            Err(e) if e.kind() != io::ErrorKind::WouldBlock => Err(PacketError::FailedSend(e)),
+            _ => Ok(()),
+        }
     }
 
     async fn receive_packet(&mut self) -> Result<Option<Packet>, PacketError> {
         Packet::deserialize(&mut self.incoming).await
     }
 }
+
+#[cfg(test)]
+mod test {
+    use std::{
+        env, fs,
+        io::{self, Read, Write},
+        sync::Arc,
+        time::Duration,
+    };
+
+    use pumpkin_config::RCONTlsConfig;
+    use pumpkin_core::text::TextComponent;
+    use rustls::{pki_types::ServerName, ClientConfig, ClientConnection, RootCertStore};
+
+    use super::{
+        response_bodies,
+        tls::{server_config, RCONStream},
+        RCONClient, MAX_RESPONSE_BODY,
+    };
+    use crate::commands::CommandSender;
+
+    #[test]
+    fn multi_line_output_is_buffered() {
+        let mut output = Vec::new();
+        {
+            let mut sender = CommandSender::Rcon {
+                buffer: &mut output,
+                permission_lvl: 4,
+            };
+            sender.send_message(TextComponent::text(
+                "There are 2 of a max 20 players online:",
+            ));
+            sender.send_message(TextComponent::text("Alex"));
+            sender.send_message(TextComponent::text("Steve"));
+        }
+        assert_eq!(output.len(), 3);
+        assert_eq!(
+            response_bodies(&output),
+            vec!["There are 2 of a max 20 players online:\nAlex\nSteve".to_string()]
+        );
+    }
+
+    #[test]
+    fn empty_output_still_responds() {
+        assert_eq!(response_bodies(&[]), vec![String::new()]);
+    }
+
+    #[test]
+    fn long_output_is_split() {
+        let output = vec!["a".repeat(MAX_RESPONSE_BODY), "ä".repeat(10)];
+        let bodies = response_bodies(&output);
+        assert_eq!(bodies.len(), 2);
+        assert!(bodies.iter().all(|body| body.len() <= MAX_RESPONSE_BODY));
+        assert_eq!(bodies.concat(), output.join("\n"));
+    }
+
+    #[tokio::test]
+    async fn test_tls_auth() {
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let path = |name| env::temp_dir().join(name).to_string_lossy().into_owned();
+        let config = RCONTlsConfig {
+            enabled: true,
+            cert_path: path("pumpkin-rcon-test-cert.pem"),
+            key_path: path("pumpkin-rcon-test-key.pem"),
+        };
+        fs::write(&config.cert_path, cert.cert.pem()).unwrap();
+        fs::write(&config.key_path, cert.key_pair.serialize_pem()).unwrap();
+        let tls = server_config(&config).unwrap();
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let root = cert.cert.der().clone();
+        let rcon_client = std::thread::spawn(move || {
+            let mut roots = RootCertStore::empty();
+            roots.add(root).unwrap();
+            let config = ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth();
+            let name = ServerName::try_from("localhost").unwrap();
+            let connection = ClientConnection::new(Arc::new(config), name).unwrap();
+            let socket = std::net::TcpStream::connect(address).unwrap();
+            let mut stream = rustls::StreamOwned::new(connection, socket);
+
+            let password = b"hunter2";
+            let mut auth = Vec::new();
+            auth.extend_from_slice(&(10 + password.len() as i32).to_le_bytes());
+            auth.extend_from_slice(&7_i32.to_le_bytes());
+            auth.extend_from_slice(&3_i32.to_le_bytes());
+            auth.extend_from_slice(password);
+            auth.extend_from_slice(&[0, 0]);
+            stream.write_all(&auth).unwrap();
+            let mut response = [0; 14];
+            stream.read_exact(&mut response).unwrap();
+            response
+        });
+
+        let (connection, _) = listener.accept().unwrap();
+        connection.set_nonblocking(true).unwrap();
+        let connection = mio::net::TcpStream::from_std(connection);
+        let mut client = RCONClient::new(RCONStream::new(connection, Some(&tls)).unwrap(), 4);
+        let packet = loop {
+            match client.read_bytes() {
+                Ok(closed) => assert!(!closed, "The client closed the connection"),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(Duration::from_millis(5))
+                }
+                Err(e) => panic!("{e}"),
+            }
+            if let Some(packet) = client.receive_packet().await.unwrap() {
+                break packet;
+            }
+        };
+        client.authenticate(&packet, "hunter2").await.unwrap();
+        assert!(client.logged_in);
+        while let Err(e) = client.connection.flush() {
+            assert_eq!(e.kind(), io::ErrorKind::WouldBlock);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let response = rcon_client.join().unwrap();
+        // The id of the auth request means it succeeded
+        assert_eq!(response[4..8], 7_i32.to_le_bytes());
+        assert_eq!(response[8..12], 2_i32.to_le_bytes());
+    }
+}
@@ -0,0 +1,79 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Write},
+    sync::Arc,
+};
+
+use mio::net::TcpStream;
+use pumpkin_config::RCONTlsConfig;
+use rustls::{ServerConfig, ServerConnection, StreamOwned};
+
+/// Loads the certificate chain and private key of the config
+pub fn server_config(config: &RCONTlsConfig) -> io::Result<Arc<ServerConfig>> {
+    let mut certs = BufReader::new(File::open(&config.cert_path)?);
+    let certs = rustls_pemfile::certs(&mut certs).collect::<Result<Vec<_>, _>>()?;
+    let mut key = BufReader::new(File::open(&config.key_path)?);
+    let key = rustls_pemfile::private_key(&mut key)?.ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} contains no private key", config.key_path),
+        )
+    })?;
+    let config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    Ok(Arc::new(config))
+}
+
+/// The connection to a RCON client, encrypted when TLS is enabled
+pub enum RCONStream {
+    Plain(TcpStream),
+    Tls(Box<StreamOwned<ServerConnection, TcpStream>>),
+}
+
+impl RCONStream {
+    pub fn new(connection: TcpStream, tls: Option<&Arc<ServerConfig>>) -> io::Result<Self> {
+        match tls {
+            Some(config) => {
+                let tls = ServerConnection::new(config.clone()).map_err(io::Error::other)?;
+                Ok(Self::Tls(Box::new(StreamOwned::new(tls, connection))))
+            }
+            None => Ok(Self::Plain(connection)),
+        }
+    }
+
+    /// The socket which is registered in the poll
+    pub fn socket(&mut self) -> &mut TcpStream {
+        match self {
+            Self::Plain(connection) => connection,
+            Self::Tls(stream) => &mut stream.sock,
+        }
+    }
+}
+
+impl Read for RCONStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(connection) => connection.read(buf),
+            // This also continues the handshake
+            Self::Tls(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for RCONStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(connection) => connection.write(buf),
+            Self::Tls(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(connection) => connection.flush(),
+            Self::Tls(stream) => stream.flush(),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Whether the autosave task may write, `/save-off` pauses it e.g. for backups
+pub struct AutoSave {
+    enabled: AtomicBool,
+}
+
+impl Default for AutoSave {
+    fn default() -> Self {
+        Self {
+            enabled: AtomicBool::new(true),
+        }
+    }
+}
+
+impl AutoSave {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Turns autosaving on or off, returns false if it already was
+    pub fn set_enabled(&self, enabled: bool) -> bool {
+        self.enabled.swap(enabled, Ordering::Relaxed) != enabled
+    }
+
+    /// Runs `save` unless autosaving is paused, returns how many chunks it saved
+    pub fn run(&self, save: impl FnOnce() -> usize) -> Option<usize> {
+        self.is_enabled().then(save)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use pumpkin_core::math::vector2::Vector2;
+    use uuid::Uuid;
+
+    use crate::test_helpers::world_in;
+
+    use super::AutoSave;
+
+    #[test]
+    fn test_set_enabled() {
+        let autosave = AutoSave::default();
+        assert!(autosave.is_enabled());
+        assert!(!autosave.set_enabled(true));
+        assert!(autosave.set_enabled(false));
+        assert!(!autosave.is_enabled());
+        assert!(!autosave.set_enabled(false));
+    }
+
+    #[tokio::test]
+    async fn test_save_off_pauses_autosave() {
+        let folder = env::temp_dir().join(format!("pumpkin-autosave-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        world.load_chunk(Vector2::new(0, 0)).await;
+        // A changed chunk which was not saved yet
+        world.level.mark_chunk_dirty(Vector2::new(0, 0));
+
+        let autosave = AutoSave::default();
+        autosave.set_enabled(false);
+        assert_eq!(autosave.run(|| world.save()), None);
+        assert!(!folder.join("region").exists());
+
+        // `/save-all` saves anyway
+        assert_eq!(world.save(), 1);
+        assert!(folder.join("region").exists());
+
+        autosave.set_enabled(true);
+        // Nothing changed since `/save-all`
+        assert_eq!(autosave.run(|| world.save()), Some(0));
+
+        fs::remove_dir_all(folder).unwrap();
+    }
+}
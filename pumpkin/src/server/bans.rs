@@ -0,0 +1,366 @@
+use std::{fs, io, net::IpAddr, path::Path};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use uuid::Uuid;
+
+const BANNED_PLAYERS_FILE: &str = "banned-players.json";
+const BANNED_IPS_FILE: &str = "banned-ips.json";
+
+/// Who banned, when and why
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BanInfo {
+    /// When the ban was created in seconds since the unix epoch
+    pub created: u64,
+    /// The name of whoever banned
+    pub source: String,
+    /// When the ban ends in seconds since the unix epoch, None means never
+    #[serde(default)]
+    pub expires: Option<u64>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl BanInfo {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    /// The message banned Players are kicked with, temporary bans tell when they end
+    pub fn kick_message(&self, now: u64) -> String {
+        let mut message = "You are banned from this server.".to_string();
+        if let Some(reason) = &self.reason {
+            message.push_str(&format!("\nReason: {}", reason));
+        }
+        if let Some(expires) = self.expires {
+            message.push_str(&format!(
+                "\nBanned until {} ({} left)",
+                format_timestamp(expires),
+                format_duration(expires.saturating_sub(now))
+            ));
+        }
+        message
+    }
+}
+
+/// Formats seconds since the unix epoch as a UTC date, e.g. `2024-05-01 13:45 UTC`
+pub fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+    // Howard Hinnant's days to civil date algorithm
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+/// Formats seconds with their two largest units, e.g. `2d 3h` or `45s`
+pub fn format_duration(seconds: u64) -> String {
+    let units = [
+        (seconds / 86400, "d"),
+        (seconds % 86400 / 3600, "h"),
+        (seconds % 3600 / 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let parts: Vec<String> = units
+        .iter()
+        .skip_while(|(value, _)| *value == 0)
+        .take(2)
+        .filter(|(value, _)| *value != 0)
+        .map(|(value, unit)| format!("{value}{unit}"))
+        .collect();
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlayerBan {
+    /// Unknown when the Player was banned while offline, then the name is used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+    pub name: String,
+    #[serde(flatten)]
+    pub info: BanInfo,
+}
+
+impl PlayerBan {
+    fn matches(&self, uuid: &Uuid, name: &str) -> bool {
+        match self.uuid {
+            Some(ban_uuid) => &ban_uuid == uuid,
+            None => self.name.eq_ignore_ascii_case(name),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct IpBan {
+    pub ip: IpAddr,
+    #[serde(flatten)]
+    pub info: BanInfo,
+}
+
+/// Banned Players and IPs, stored in `banned-players.json` and `banned-ips.json`
+#[derive(Default, Debug, PartialEq)]
+pub struct BanList {
+    players: Vec<PlayerBan>,
+    ips: Vec<IpBan>,
+}
+
+fn load_file<T: DeserializeOwned>(path: &Path) -> io::Result<Vec<T>> {
+    match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).map_err(io::Error::other),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(err) => Err(err),
+    }
+}
+
+fn save_file<T: Serialize>(path: &Path, entries: &[T]) -> io::Result<()> {
+    let content = serde_json::to_string_pretty(entries).map_err(io::Error::other)?;
+    let temp_path = path.with_extension("json.tmp");
+    fs::write(&temp_path, content)?;
+    fs::rename(temp_path, path)
+}
+
+impl BanList {
+    /// Loads the bans, missing files mean nobody is banned
+    pub fn load() -> Self {
+        Self::load_from(Path::new(BANNED_PLAYERS_FILE), Path::new(BANNED_IPS_FILE)).unwrap_or_else(
+            |err| {
+                log::error!("Couldn't load the ban lists: {}", err);
+                Self::default()
+            },
+        )
+    }
+
+    /// Saves the bans to `banned-players.json` and `banned-ips.json`
+    pub fn save(&self) -> io::Result<()> {
+        self.save_to(Path::new(BANNED_PLAYERS_FILE), Path::new(BANNED_IPS_FILE))
+    }
+
+    fn load_from(players_path: &Path, ips_path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            players: load_file(players_path)?,
+            ips: load_file(ips_path)?,
+        })
+    }
+
+    fn save_to(&self, players_path: &Path, ips_path: &Path) -> io::Result<()> {
+        save_file(players_path, &self.players)?;
+        save_file(ips_path, &self.ips)
+    }
+
+    /// Returns the ban of the Player if it hasn't expired yet
+    pub fn player_ban(&self, uuid: &Uuid, name: &str, now: u64) -> Option<&PlayerBan> {
+        self.players
+            .iter()
+            .find(|ban| ban.matches(uuid, name) && !ban.info.is_expired(now))
+    }
+
+    /// Returns the ban of the IP if it hasn't expired yet
+    pub fn ip_ban(&self, ip: &IpAddr, now: u64) -> Option<&IpBan> {
+        self.ips
+            .iter()
+            .find(|ban| &ban.ip == ip && !ban.info.is_expired(now))
+    }
+
+    /// Returns the ban keeping the Player out, either of its account or of its IP
+    pub fn find_ban(&self, uuid: &Uuid, name: &str, ip: &IpAddr, now: u64) -> Option<&BanInfo> {
+        self.player_ban(uuid, name, now)
+            .map(|ban| &ban.info)
+            .or_else(|| self.ip_ban(ip, now).map(|ban| &ban.info))
+    }
+
+    /// Bans the Player, replacing an earlier ban
+    pub fn ban_player(&mut self, uuid: Option<Uuid>, name: &str, info: BanInfo) {
+        self.players.retain(|ban| {
+            !ban.name.eq_ignore_ascii_case(name) && (uuid.is_none() || ban.uuid != uuid)
+        });
+        self.players.push(PlayerBan {
+            uuid,
+            name: name.to_string(),
+            info,
+        });
+    }
+
+    /// Bans the IP, replacing an earlier ban
+    pub fn ban_ip(&mut self, ip: IpAddr, info: BanInfo) {
+        self.ips.retain(|ban| ban.ip != ip);
+        self.ips.push(IpBan { ip, info });
+    }
+
+    /// Removes the ban of the Player by name, returns false if it wasn't banned
+    pub fn pardon_player(&mut self, name: &str) -> bool {
+        let len = self.players.len();
+        self.players
+            .retain(|ban| !ban.name.eq_ignore_ascii_case(name));
+        self.players.len() != len
+    }
+
+    /// Removes the ban of the IP, returns false if it wasn't banned
+    pub fn pardon_ip(&mut self, ip: &IpAddr) -> bool {
+        let len = self.ips.len();
+        self.ips.retain(|ban| &ban.ip != ip);
+        self.ips.len() != len
+    }
+
+    /// Forgets expired bans so they don't pile up in the files
+    pub fn remove_expired(&mut self, now: u64) {
+        self.players.retain(|ban| !ban.info.is_expired(now));
+        self.ips.retain(|ban| !ban.info.is_expired(now));
+    }
+
+    pub fn players(&self) -> &[PlayerBan] {
+        &self.players
+    }
+
+    pub fn ips(&self) -> &[IpBan] {
+        &self.ips
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, net::IpAddr};
+
+    use uuid::Uuid;
+
+    use super::{format_duration, format_timestamp, BanInfo, BanList};
+
+    fn info(expires: Option<u64>) -> BanInfo {
+        BanInfo {
+            created: 0,
+            source: "Server".to_string(),
+            expires,
+            reason: Some("Griefing".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_ban_and_pardon_player() {
+        let mut bans = BanList::default();
+        let steve = Uuid::new_v4();
+        bans.ban_player(Some(steve), "Steve", info(None));
+        bans.ban_player(None, "Alex", info(Some(100)));
+
+        assert_eq!(bans.player_ban(&steve, "Steve", 50).unwrap().name, "Steve");
+        // Banned by UUID, so a new name doesn't help
+        assert!(bans.player_ban(&steve, "Steve2", 50).is_some());
+        assert!(bans.player_ban(&Uuid::new_v4(), "alex", 50).is_some());
+        assert!(bans.player_ban(&Uuid::new_v4(), "alex", 100).is_none());
+
+        // Banning again replaces the ban
+        bans.ban_player(Some(steve), "Steve", info(Some(10)));
+        assert_eq!(bans.players().len(), 2);
+
+        assert!(bans.pardon_player("steve"));
+        assert!(!bans.pardon_player("Steve"));
+        assert!(bans.player_ban(&steve, "Steve", 0).is_none());
+    }
+
+    #[test]
+    fn test_find_ban() {
+        let mut bans = BanList::default();
+        let steve = Uuid::new_v4();
+        let home: IpAddr = "10.0.0.1".parse().unwrap();
+        let other: IpAddr = "10.0.0.2".parse().unwrap();
+        assert!(bans.find_ban(&steve, "Steve", &home, 0).is_none());
+
+        bans.ban_player(Some(steve), "Steve", info(None));
+        let ban = bans.find_ban(&steve, "Steve", &other, 0).unwrap();
+        assert_eq!(
+            ban.kick_message(0),
+            "You are banned from this server.\nReason: Griefing"
+        );
+
+        // Another account from a banned IP is kept out as well
+        bans.ban_ip(home, info(None));
+        assert!(bans.find_ban(&Uuid::new_v4(), "Alex", &home, 0).is_some());
+        assert!(bans.find_ban(&Uuid::new_v4(), "Alex", &other, 0).is_none());
+    }
+
+    #[test]
+    fn test_temporary_ban_message() {
+        // 2024-05-01 13:45 UTC
+        let expires = 1_714_571_100;
+        let ban = info(Some(expires));
+        assert_eq!(
+            ban.kick_message(expires - 2 * 86400 - 3 * 3600 - 20),
+            "You are banned from this server.\nReason: Griefing\nBanned until 2024-05-01 13:45 UTC (2d 3h left)"
+        );
+        let ban = BanInfo {
+            reason: None,
+            ..ban
+        };
+        assert_eq!(
+            ban.kick_message(expires - 45),
+            "You are banned from this server.\nBanned until 2024-05-01 13:45 UTC (45s left)"
+        );
+    }
+
+    #[test]
+    fn test_formatting() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00 UTC");
+        assert_eq!(format_timestamp(951_782_400), "2000-02-29 00:00 UTC");
+        assert_eq!(format_timestamp(1_735_689_599), "2024-12-31 23:59 UTC");
+        assert_eq!(format_duration(0), "0s");
+        assert_eq!(format_duration(90), "1m 30s");
+        assert_eq!(format_duration(3600), "1h");
+        // Only the two largest units are shown
+        assert_eq!(format_duration(86400 + 60 + 1), "1d");
+        assert_eq!(format_duration(86400 + 3600 + 1), "1d 1h");
+    }
+
+    #[test]
+    fn test_ban_and_pardon_ip() {
+        let mut bans = BanList::default();
+        let ip: IpAddr = "192.168.0.5".parse().unwrap();
+        bans.ban_ip(ip, info(None));
+        assert!(bans.ip_ban(&ip, 0).is_some());
+        assert!(bans.ip_ban(&"192.168.0.6".parse().unwrap(), 0).is_none());
+        assert!(bans.pardon_ip(&ip));
+        assert!(bans.ip_ban(&ip, 0).is_none());
+        assert!(!bans.pardon_ip(&ip));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let players_path = env::temp_dir().join("pumpkin-banned-players-test.json");
+        let ips_path = env::temp_dir().join("pumpkin-banned-ips-test.json");
+        let _ = fs::remove_file(&players_path);
+        let _ = fs::remove_file(&ips_path);
+        assert_eq!(
+            BanList::load_from(&players_path, &ips_path).unwrap(),
+            BanList::default()
+        );
+
+        let mut bans = BanList::default();
+        bans.ban_player(Some(Uuid::new_v4()), "Steve", info(Some(1_000_000)));
+        bans.ban_ip("::1".parse().unwrap(), info(None));
+        bans.save_to(&players_path, &ips_path).unwrap();
+        assert_eq!(BanList::load_from(&players_path, &ips_path).unwrap(), bans);
+
+        // A pardon is persisted too
+        bans.pardon_player("Steve");
+        bans.save_to(&players_path, &ips_path).unwrap();
+        let loaded = BanList::load_from(&players_path, &ips_path).unwrap();
+        assert!(loaded.players().is_empty());
+        assert_eq!(loaded.ips().len(), 1);
+        fs::remove_file(players_path).unwrap();
+        fs::remove_file(ips_path).unwrap();
+    }
+}
@@ -1,19 +1,28 @@
 use std::{fs::File, path::Path};
 
 use base64::{engine::general_purpose, Engine as _};
-use pumpkin_config::{BasicConfiguration, BASIC_CONFIG};
+use parking_lot::{Mutex, RwLock};
+use pumpkin_config::{
+    BasicConfiguration, StatusSampleConfig, StatusSampleMode, ADVANCED_CONFIG, BASIC_CONFIG,
+};
 use pumpkin_protocol::{
     client::{config::CPluginMessage, status::CStatusResponse},
-    Players, Sample, StatusResponse, VarInt, Version, CURRENT_MC_PROTOCOL,
+    Players, Sample, StatusResponse, VarInt, Version,
 };
+use uuid::Uuid;
+
+use crate::client::Client;
+
+use super::supported_version;
 
-use super::CURRENT_MC_VERSION;
+/// The name vanilla shows for Players who disabled being listed
+const ANONYMOUS_PLAYER: &str = "Anonymous Player";
 
 pub struct CachedStatus {
-    _status_response: StatusResponse,
+    status_response: Mutex<StatusResponse>,
     // We cache the json response here so we don't parse it every time someone makes a Status request.
     // Keep in mind that we must parse this again, when the StatusResponse changes which usually happen when a player joins or leaves
-    status_response_json: String,
+    status_response_json: RwLock<String>,
 }
 
 pub struct CachedBranding {
@@ -47,13 +56,25 @@ impl CachedStatus {
             .expect("Failed to parse Status response into JSON");
 
         Self {
-            _status_response: status_response,
-            status_response_json,
+            status_response: Mutex::new(status_response),
+            status_response_json: RwLock::new(status_response_json),
         }
     }
 
-    pub fn get_status(&self) -> CStatusResponse<'_> {
-        CStatusResponse::new(&self.status_response_json)
+    pub fn send_status(&self, client: &Client) {
+        client.send_packet(&CStatusResponse::new(&self.status_response_json.read()));
+    }
+
+    /// Rebuilds the Player count and sample, `online` are the Players who are online now
+    pub fn update_players(&self, online: Vec<Sample>) {
+        let mut status_response = self.status_response.lock();
+        status_response.players = build_players(
+            &ADVANCED_CONFIG.status_sample,
+            BASIC_CONFIG.max_players,
+            online,
+        );
+        *self.status_response_json.write() = serde_json::to_string(&*status_response)
+            .expect("Failed to parse Status response into JSON");
     }
 
     pub fn build_response(config: &BasicConfiguration) -> StatusResponse {
@@ -64,19 +85,17 @@ impl CachedStatus {
             None
         };
 
+        let (protocol, name) = supported_version(&ADVANCED_CONFIG.handshake);
         StatusResponse {
             version: Some(Version {
-                name: CURRENT_MC_VERSION.into(),
-                protocol: CURRENT_MC_PROTOCOL,
-            }),
-            players: Some(Players {
-                max: config.max_players,
-                online: 0,
-                sample: vec![Sample {
-                    name: "".into(),
-                    id: "".into(),
-                }],
+                name: name.into(),
+                protocol,
             }),
+            players: build_players(
+                &ADVANCED_CONFIG.status_sample,
+                config.max_players,
+                Vec::new(),
+            ),
             description: config.motd.clone(),
             favicon: icon,
             enforce_secure_chat: false,
@@ -100,3 +119,133 @@ impl CachedStatus {
         result
     }
 }
+
+/// How an online Player shows up in the sample, Players who disabled being listed stay anonymous like in vanilla
+pub fn player_sample(name: &str, id: Uuid, listed: bool) -> Sample {
+    if listed {
+        Sample {
+            name: name.into(),
+            id: id.to_string(),
+        }
+    } else {
+        text_sample(ANONYMOUS_PLAYER)
+    }
+}
+
+/// A line of the sample which is not a Player
+fn text_sample(text: &str) -> Sample {
+    Sample {
+        name: text.into(),
+        id: Uuid::nil().to_string(),
+    }
+}
+
+/// The Player count and sample for the configured mode, the count is always the real one unless it's hidden
+pub fn build_players(
+    config: &StatusSampleConfig,
+    max_players: u32,
+    online: Vec<Sample>,
+) -> Option<Players> {
+    let online_count = online.len() as u32;
+    let mut sample: Vec<Sample> = match config.mode {
+        StatusSampleMode::Hidden => return None,
+        StatusSampleMode::Real => online.into_iter().take(config.max_players).collect(),
+        StatusSampleMode::Custom => config.custom.iter().map(|line| text_sample(line)).collect(),
+    };
+    if !config.hover_line.is_empty() {
+        sample.push(text_sample(&config.hover_line));
+    }
+    Some(Players {
+        max: max_players,
+        online: online_count,
+        sample,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::{StatusSampleConfig, StatusSampleMode};
+    use pumpkin_protocol::{Players, Sample};
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    use super::{build_players, player_sample, CachedStatus};
+
+    const STEVE: Uuid = Uuid::from_u128(1);
+    const ALEX: Uuid = Uuid::from_u128(2);
+
+    fn online() -> Vec<Sample> {
+        vec![
+            player_sample("Steve", STEVE, true),
+            player_sample("Alex", ALEX, false),
+        ]
+    }
+
+    fn config(mode: StatusSampleMode, hover_line: &str) -> StatusSampleConfig {
+        StatusSampleConfig {
+            mode,
+            max_players: 12,
+            custom: vec!["Welcome".to_string(), "to Pumpkin".to_string()],
+            hover_line: hover_line.to_string(),
+        }
+    }
+
+    fn sample_json(players: &Players) -> Value {
+        serde_json::to_value(players).unwrap()["sample"].clone()
+    }
+
+    #[test]
+    fn test_real_sample() {
+        let players = build_players(&config(StatusSampleMode::Real, ""), 20, online()).unwrap();
+        assert_eq!(players.online, 2);
+        assert_eq!(players.max, 20);
+        assert_eq!(
+            sample_json(&players),
+            json!([
+                { "name": "Steve", "id": STEVE.to_string() },
+                { "name": "Anonymous Player", "id": Uuid::nil().to_string() },
+            ])
+        );
+
+        let mut limited = config(StatusSampleMode::Real, "play.example.com");
+        limited.max_players = 1;
+        let players = build_players(&limited, 20, online()).unwrap();
+        assert_eq!(players.online, 2);
+        assert_eq!(
+            sample_json(&players),
+            json!([
+                { "name": "Steve", "id": STEVE.to_string() },
+                { "name": "play.example.com", "id": Uuid::nil().to_string() },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_custom_sample() {
+        let players =
+            build_players(&config(StatusSampleMode::Custom, "Join us"), 20, online()).unwrap();
+        // The count stays the real one
+        assert_eq!(players.online, 2);
+        let nil = Uuid::nil().to_string();
+        assert_eq!(
+            sample_json(&players),
+            json!([
+                { "name": "Welcome", "id": nil },
+                { "name": "to Pumpkin", "id": nil },
+                { "name": "Join us", "id": nil },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_hidden_sample() {
+        assert!(
+            build_players(&config(StatusSampleMode::Hidden, "Join us"), 20, online()).is_none()
+        );
+
+        let mut response = CachedStatus::build_response(&Default::default());
+        response.players = None;
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json.get("players").is_none());
+    }
+}
@@ -0,0 +1,535 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io,
+    net::IpAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use pumpkin_config::HttpApiConfig;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::commands::CommandSender;
+
+use super::{metrics::METRICS, Server};
+
+/// Requests bigger than this are not answered, commands are short
+const MAX_REQUEST_SIZE: usize = 16 * 1024;
+
+/// What `GET /status` answers with
+#[derive(Serialize, Debug, PartialEq)]
+pub struct StatusReport {
+    pub players: Vec<PlayerStatus>,
+    pub tps: f64,
+    pub uptime_secs: u64,
+    pub worlds: Vec<WorldStatus>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct PlayerStatus {
+    pub name: String,
+    pub uuid: String,
+    pub ping_ms: u64,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct WorldStatus {
+    pub dimension: &'static str,
+    pub players: usize,
+    pub loaded_chunks: usize,
+}
+
+impl StatusReport {
+    pub fn new(server: &Server) -> Self {
+        let players = server
+            .get_all_players()
+            .iter()
+            .filter(|player| !player.is_vanished())
+            .map(|player| PlayerStatus {
+                name: player.gameprofile.name.clone(),
+                uuid: player.gameprofile.id.to_string(),
+                ping_ms: player.client.latency.load().as_millis() as u64,
+            })
+            .collect();
+        let worlds = server
+            .worlds
+            .iter()
+            .map(|world| WorldStatus {
+                dimension: world.dimension.name(),
+                players: world
+                    .current_players
+                    .lock()
+                    .values()
+                    .filter(|player| !player.is_vanished())
+                    .count(),
+                loaded_chunks: world.level.loaded_chunk_count(),
+            })
+            .collect();
+        Self {
+            players,
+            tps: METRICS.tps(),
+            uptime_secs: server.uptime.elapsed().as_secs(),
+            worlds,
+        }
+    }
+}
+
+/// The body of `POST /command`
+#[derive(Deserialize)]
+struct CommandRequest {
+    command: String,
+}
+
+/// What `POST /command` answers with, the messages the command sent
+#[derive(Serialize)]
+struct CommandResponse {
+    output: Vec<String>,
+}
+
+/// Recent requests by IP, so a client can't flood the API or guess the token
+struct RateLimiter {
+    max_requests: usize,
+    interval: Duration,
+    requests: HashMap<IpAddr, VecDeque<Instant>>,
+}
+
+impl RateLimiter {
+    fn new(config: &HttpApiConfig) -> Self {
+        Self {
+            max_requests: config.max_requests,
+            interval: Duration::from_secs(config.interval_secs),
+            requests: HashMap::new(),
+        }
+    }
+
+    /// Records a request, returns `false` if the IP made too many recently
+    fn allow(&mut self, ip: IpAddr, now: Instant) -> bool {
+        let interval = self.interval;
+        // Forget IPs which didn't ask for a while, so the map doesn't grow forever
+        self.requests.retain(|_, requests| {
+            while requests
+                .front()
+                .is_some_and(|request| now.duration_since(*request) >= interval)
+            {
+                requests.pop_front();
+            }
+            !requests.is_empty()
+        });
+        let requests = self.requests.entry(ip).or_default();
+        if requests.len() >= self.max_requests {
+            return false;
+        }
+        requests.push_back(now);
+        true
+    }
+}
+
+/// The parts of an HTTP request the API looks at
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub authorization: Option<String>,
+    pub body: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub struct Response {
+    pub status: &'static str,
+    pub body: String,
+}
+
+impl Response {
+    fn json(status: &'static str, body: &impl Serialize) -> Self {
+        Self {
+            status,
+            body: serde_json::to_string(body).unwrap_or_default(),
+        }
+    }
+
+    fn error(status: &'static str, message: &str) -> Self {
+        Self::json(status, &serde_json::json!({ "error": message }))
+    }
+}
+
+/// Whether the `Authorization` header carries the token.
+///
+/// Compares every byte so the time it takes doesn't tell how much of the token was right
+fn is_authorized(authorization: Option<&str>, token: &str) -> bool {
+    let Some(given) = authorization.and_then(|value| value.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    if token.is_empty() || given.len() != token.len() {
+        return false;
+    }
+    given
+        .bytes()
+        .zip(token.bytes())
+        .fold(0, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+/// Answers the requests of the API, `status` and `command` reach into the Server
+pub struct HttpApi<S, C> {
+    token: String,
+    rate_limiter: Mutex<RateLimiter>,
+    status: S,
+    command: C,
+}
+
+impl<S, C> HttpApi<S, C>
+where
+    S: Fn() -> StatusReport + Send + Sync + 'static,
+    C: Fn(&str) -> Vec<String> + Send + Sync + 'static,
+{
+    pub fn new(config: &HttpApiConfig, status: S, command: C) -> Self {
+        Self {
+            token: config.token.clone(),
+            rate_limiter: Mutex::new(RateLimiter::new(config)),
+            status,
+            command,
+        }
+    }
+
+    pub fn respond(&self, ip: IpAddr, request: &Request, now: Instant) -> Response {
+        if !self.rate_limiter.lock().allow(ip, now) {
+            return Response::error("429 Too Many Requests", "Too many requests");
+        }
+        if !is_authorized(request.authorization.as_deref(), &self.token) {
+            return Response::error("401 Unauthorized", "Missing or wrong token");
+        }
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/status") => Response::json("200 OK", &(self.status)()),
+            ("POST", "/command") => {
+                let Ok(CommandRequest { command }) = serde_json::from_slice(&request.body) else {
+                    return Response::error("400 Bad Request", "Expected {\"command\": \"...\"}");
+                };
+                let output = (self.command)(command.trim_start_matches('/'));
+                Response::json("200 OK", &CommandResponse { output })
+            }
+            (_, "/status" | "/command") => {
+                Response::error("405 Method Not Allowed", "Method not allowed")
+            }
+            _ => Response::error("404 Not Found", "Not found"),
+        }
+    }
+
+    /// Answers every connection on the listener, one request per connection
+    pub async fn serve(self: Arc<Self>, listener: TcpListener) -> io::Result<()> {
+        loop {
+            let (stream, address) = listener.accept().await?;
+            let api = self.clone();
+            tokio::spawn(async move {
+                if let Err(err) = api.handle_connection(stream, address.ip()).await {
+                    log::debug!("Failed to answer HTTP API request: {}", err);
+                }
+            });
+        }
+    }
+
+    async fn handle_connection(&self, mut stream: TcpStream, ip: IpAddr) -> io::Result<()> {
+        let Some(request) = read_request(&mut stream).await? else {
+            return Ok(());
+        };
+        let response = self.respond(ip, &request, Instant::now());
+        let mut head = format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+            response.status,
+            response.body.len()
+        );
+        if response.status.starts_with("401") {
+            head.push_str("WWW-Authenticate: Bearer\r\n");
+        }
+        stream
+            .write_all(format!("{head}\r\n{}", response.body).as_bytes())
+            .await?;
+        stream.shutdown().await
+    }
+}
+
+/// Runs a command as the console and captures what it sends back
+pub fn run_command(server: &Arc<Server>, command: &str) -> Vec<String> {
+    let mut output = Vec::new();
+    server.command_dispatcher.clone().handle_command(
+        &mut CommandSender::Rcon {
+            buffer: &mut output,
+            permission_lvl: 4,
+        },
+        server,
+        command,
+    );
+    output
+}
+
+/// Reads a request with its body, `None` if it is too big or the connection closed early
+async fn read_request(stream: &mut TcpStream) -> io::Result<Option<Request>> {
+    let mut data = Vec::new();
+    let mut buf = [0; 1024];
+    let head_end = loop {
+        if let Some(end) = data.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end + 4;
+        }
+        if data.len() > MAX_REQUEST_SIZE {
+            return Ok(None);
+        }
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        data.extend_from_slice(&buf[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&data[..head_end]).into_owned();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Ok(None);
+    };
+    let mut authorization = None;
+    let mut content_length = 0;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.parse().unwrap_or(0);
+        }
+    }
+    if head_end + content_length > MAX_REQUEST_SIZE {
+        return Ok(None);
+    }
+    while data.len() < head_end + content_length {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        data.extend_from_slice(&buf[..n]);
+    }
+
+    Ok(Some(Request {
+        method: method.to_string(),
+        path: path.split('?').next().unwrap_or_default().to_string(),
+        authorization,
+        body: data[head_end..head_end + content_length].to_vec(),
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        net::{IpAddr, Ipv4Addr},
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    use pumpkin_config::HttpApiConfig;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::{is_authorized, HttpApi, PlayerStatus, Request, StatusReport, WorldStatus};
+
+    const TOKEN: &str = "s3cret-token";
+
+    fn config(max_requests: usize) -> HttpApiConfig {
+        HttpApiConfig {
+            enabled: true,
+            token: TOKEN.to_string(),
+            max_requests,
+            ..Default::default()
+        }
+    }
+
+    fn status() -> StatusReport {
+        StatusReport {
+            players: vec![PlayerStatus {
+                name: "Steve".to_string(),
+                uuid: "069a79f4-44e9-4726-a5be-fca90e38aaf5".to_string(),
+                ping_ms: 42,
+            }],
+            tps: 19.5,
+            uptime_secs: 3600,
+            worlds: vec![WorldStatus {
+                dimension: "minecraft:overworld",
+                players: 1,
+                loaded_chunks: 441,
+            }],
+        }
+    }
+
+    fn api(
+        max_requests: usize,
+    ) -> HttpApi<
+        impl Fn() -> StatusReport + Send + Sync + 'static,
+        impl Fn(&str) -> Vec<String> + Send + Sync + 'static,
+    > {
+        HttpApi::new(&config(max_requests), status, |command: &str| {
+            vec![format!("ran {command}")]
+        })
+    }
+
+    fn request(method: &str, path: &str, token: Option<&str>, body: &str) -> Request {
+        Request {
+            method: method.to_string(),
+            path: path.to_string(),
+            authorization: token.map(|token| format!("Bearer {token}")),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn localhost() -> IpAddr {
+        IpAddr::V4(Ipv4Addr::LOCALHOST)
+    }
+
+    #[test]
+    fn test_status_json() {
+        let api = api(10);
+        let response = api.respond(
+            localhost(),
+            &request("GET", "/status", Some(TOKEN), ""),
+            Instant::now(),
+        );
+        assert_eq!(response.status, "200 OK");
+        let json: serde_json::Value = serde_json::from_str(&response.body).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "players": [{
+                    "name": "Steve",
+                    "uuid": "069a79f4-44e9-4726-a5be-fca90e38aaf5",
+                    "ping_ms": 42,
+                }],
+                "tps": 19.5,
+                "uptime_secs": 3600,
+                "worlds": [{
+                    "dimension": "minecraft:overworld",
+                    "players": 1,
+                    "loaded_chunks": 441,
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_command_needs_token() {
+        let api = api(10);
+        let body = r#"{"command": "/say hi"}"#;
+        let now = Instant::now();
+        for token in [None, Some("wrong"), Some("s3cret-tokem"), Some("")] {
+            let response = api.respond(localhost(), &request("POST", "/command", token, body), now);
+            assert_eq!(response.status, "401 Unauthorized");
+            assert!(!response.body.contains("ran"));
+        }
+        // Only as a bearer token
+        let mut basic = request("POST", "/command", None, body);
+        basic.authorization = Some(format!("Basic {TOKEN}"));
+        assert_eq!(
+            api.respond(localhost(), &basic, now).status,
+            "401 Unauthorized"
+        );
+
+        let response = api.respond(
+            localhost(),
+            &request("POST", "/command", Some(TOKEN), body),
+            now,
+        );
+        assert_eq!(response.status, "200 OK");
+        assert_eq!(response.body, r#"{"output":["ran say hi"]}"#);
+
+        let response = api.respond(
+            localhost(),
+            &request("POST", "/command", Some(TOKEN), "say hi"),
+            now,
+        );
+        assert_eq!(response.status, "400 Bad Request");
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        assert!(is_authorized(Some("Bearer abc"), "abc"));
+        assert!(!is_authorized(Some("Bearer abcd"), "abc"));
+        assert!(!is_authorized(Some("bearer abc"), "abc"));
+        // An empty token never matches, the API would be open otherwise
+        assert!(!is_authorized(Some("Bearer "), ""));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let api = api(2);
+        let start = Instant::now();
+        let status = request("GET", "/status", Some(TOKEN), "");
+        assert_eq!(api.respond(localhost(), &status, start).status, "200 OK");
+        // Wrong tokens count too, so they can't be guessed quickly
+        let wrong = request("GET", "/status", Some("wrong"), "");
+        assert_eq!(
+            api.respond(localhost(), &wrong, start).status,
+            "401 Unauthorized"
+        );
+        assert_eq!(
+            api.respond(localhost(), &status, start).status,
+            "429 Too Many Requests"
+        );
+        // Other IPs aren't affected
+        let other = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        assert_eq!(api.respond(other, &status, start).status, "200 OK");
+        // Allowed again once the window passed
+        let later = start + Duration::from_secs(60);
+        assert_eq!(api.respond(localhost(), &status, later).status, "200 OK");
+    }
+
+    #[test]
+    fn test_routes() {
+        let api = api(10);
+        let now = Instant::now();
+        let respond = |method, path| {
+            api.respond(localhost(), &request(method, path, Some(TOKEN), ""), now)
+                .status
+        };
+        assert_eq!(respond("POST", "/status"), "405 Method Not Allowed");
+        assert_eq!(respond("GET", "/command"), "405 Method Not Allowed");
+        assert_eq!(respond("GET", "/"), "404 Not Found");
+    }
+
+    #[tokio::test]
+    async fn test_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(Arc::new(api(10)).serve(listener));
+
+        let body = r#"{"command":"list"}"#;
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(
+                format!(
+                    "POST /command HTTP/1.1\r\nHost: localhost\r\nauthorization: Bearer {TOKEN}\r\nContent-Length: {}\r\n\r\n{body}",
+                    body.len()
+                )
+                .as_bytes(),
+            )
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: application/json\r\n"));
+        assert!(response.ends_with(r#"{"output":["ran list"]}"#));
+
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(b"GET /status HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+        assert!(response.contains("WWW-Authenticate: Bearer\r\n"));
+    }
+}
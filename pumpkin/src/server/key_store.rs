@@ -13,9 +13,9 @@ pub struct KeyStore {
 }
 
 impl KeyStore {
-    pub fn new() -> Self {
-        log::debug!("Creating encryption keys...");
-        let (public_key, private_key) = Self::generate_keys();
+    pub fn new(key_size: usize) -> Self {
+        log::debug!("Creating {} bit encryption keys...", key_size);
+        let (public_key, private_key) = Self::generate_keys(key_size);
 
         let public_key_der = rsa_der::public_key_to_der(
             &private_key.n().to_bytes_be(),
@@ -29,10 +29,10 @@ impl KeyStore {
         }
     }
 
-    fn generate_keys() -> (RsaPublicKey, RsaPrivateKey) {
+    fn generate_keys(key_size: usize) -> (RsaPublicKey, RsaPrivateKey) {
         let mut rng = rand::thread_rng();
 
-        let priv_key = RsaPrivateKey::new(&mut rng, 1024).expect("failed to generate a key");
+        let priv_key = RsaPrivateKey::new(&mut rng, key_size).expect("failed to generate a key");
         let pub_key = RsaPublicKey::from(&priv_key);
         (pub_key, priv_key)
     }
@@ -59,6 +59,18 @@ impl KeyStore {
         Ok(decrypted)
     }
 
+    /// Checks that the client sent back the token of the encryption request
+    pub fn verify_token(
+        &self,
+        expected: &[u8; 4],
+        encrypted: &[u8],
+    ) -> Result<(), EncryptionError> {
+        if self.decrypt(encrypted)? != expected {
+            return Err(EncryptionError::VerifyTokenMismatch);
+        }
+        Ok(())
+    }
+
     pub fn get_digest(&self, secret: &[u8]) -> String {
         auth_digest(
             &Sha1::new()
@@ -72,3 +84,58 @@ impl KeyStore {
 pub fn auth_digest(bytes: &[u8]) -> String {
     BigInt::from_signed_bytes_be(bytes).to_str_radix(16)
 }
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::encryption::SUPPORTED_KEY_SIZES;
+    use rsa::{traits::PublicKeyParts as _, Pkcs1v15Encrypt};
+
+    use super::KeyStore;
+    use crate::client::EncryptionError;
+
+    fn encrypt(key_store: &KeyStore, data: &[u8]) -> Vec<u8> {
+        key_store
+            ._public_key
+            .encrypt(&mut rand::thread_rng(), Pkcs1v15Encrypt, data)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_verify_token() {
+        let key_store = KeyStore::new(1024);
+        let token = [1, 2, 3, 4];
+        assert!(key_store
+            .verify_token(&token, &encrypt(&key_store, &token))
+            .is_ok());
+        assert!(matches!(
+            key_store.verify_token(&token, &encrypt(&key_store, &[4, 3, 2, 1])),
+            Err(EncryptionError::VerifyTokenMismatch)
+        ));
+        assert!(matches!(
+            key_store.verify_token(&token, &token),
+            Err(EncryptionError::FailedDecrypt)
+        ));
+    }
+
+    #[test]
+    fn test_shared_secret_round_trip() {
+        let shared_secret = [7u8; 16];
+        for key_size in SUPPORTED_KEY_SIZES {
+            let key_store = KeyStore::new(key_size);
+            assert_eq!(key_store._public_key.size() * 8, key_size);
+
+            // Clients encrypt with the key from the encryption request
+            let (n, e) = rsa_der::public_key_from_der(&key_store.public_key_der).unwrap();
+            let public_key = rsa::RsaPublicKey::new(
+                rsa::BigUint::from_bytes_be(&n),
+                rsa::BigUint::from_bytes_be(&e),
+            )
+            .unwrap();
+            let encrypted = public_key
+                .encrypt(&mut rand::thread_rng(), Pkcs1v15Encrypt, &shared_secret)
+                .unwrap();
+            assert_eq!(encrypted.len() * 8, key_size);
+            assert_eq!(key_store.decrypt(&encrypted).unwrap(), shared_secret);
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+use pumpkin_config::LoginThrottleConfig;
+
+/// Recent login starts by name, so one account can't be hammered from many IPs
+#[derive(Default)]
+pub struct LoginThrottle {
+    attempts: HashMap<String, VecDeque<Instant>>,
+}
+
+impl LoginThrottle {
+    /// Records a login start of the name, returns how long it has to wait if it came too soon
+    pub fn attempt(
+        &mut self,
+        name: &str,
+        now: Instant,
+        config: &LoginThrottleConfig,
+    ) -> Result<(), Duration> {
+        if !config.enabled {
+            return Ok(());
+        }
+        let interval = Duration::from_secs(config.interval_secs);
+        // Forget names which didn't try for a while, so the map doesn't grow forever
+        self.attempts.retain(|_, attempts| {
+            while attempts
+                .front()
+                .is_some_and(|attempt| now.duration_since(*attempt) >= interval)
+            {
+                attempts.pop_front();
+            }
+            !attempts.is_empty()
+        });
+
+        let attempts = self.attempts.entry(name.to_lowercase()).or_default();
+        if attempts.len() >= config.max_attempts {
+            let oldest = attempts.front().copied().unwrap_or(now);
+            return Err(interval.saturating_sub(now.duration_since(oldest)));
+        }
+        attempts.push_back(now);
+        Ok(())
+    }
+
+    /// Forgets the attempts of a name after it logged in
+    pub fn clear(&mut self, name: &str) {
+        self.attempts.remove(&name.to_lowercase());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use pumpkin_config::LoginThrottleConfig;
+
+    use super::LoginThrottle;
+
+    #[test]
+    fn test_repeated_logins_are_throttled() {
+        let config = LoginThrottleConfig::default();
+        let mut throttle = LoginThrottle::default();
+        let now = Instant::now();
+        for i in 0..3 {
+            assert!(throttle
+                .attempt("Steve", now + Duration::from_secs(i), &config)
+                .is_ok());
+        }
+        let later = now + Duration::from_secs(4);
+        assert_eq!(
+            throttle.attempt("steve", later, &config),
+            Err(Duration::from_secs(6))
+        );
+        // Other names aren't affected
+        assert!(throttle.attempt("Alex", later, &config).is_ok());
+
+        // Once the first attempt is old enough there is room again
+        let much_later = now + Duration::from_secs(10);
+        assert!(throttle.attempt("Steve", much_later, &config).is_ok());
+    }
+
+    #[test]
+    fn test_login_clears_throttle() {
+        let config = LoginThrottleConfig {
+            max_attempts: 1,
+            ..Default::default()
+        };
+        let mut throttle = LoginThrottle::default();
+        let now = Instant::now();
+        assert!(throttle.attempt("Steve", now, &config).is_ok());
+        assert!(throttle.attempt("Steve", now, &config).is_err());
+        throttle.clear("Steve");
+        assert!(throttle.attempt("Steve", now, &config).is_ok());
+
+        let disabled = LoginThrottleConfig {
+            enabled: false,
+            ..config
+        };
+        assert!(throttle.attempt("Steve", now, &disabled).is_ok());
+    }
+}
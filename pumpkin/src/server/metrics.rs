@@ -0,0 +1,348 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::Write as _,
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use super::{Server, TICK_DURATION};
+
+/// How many of the last ticks are used to calculate the TPS and MSPT
+const TICK_WINDOW: usize = 100;
+
+/// Requests bigger than this are not answered, a scrape request is only a few lines
+const MAX_REQUEST_SIZE: usize = 8192;
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub static METRICS: Metrics = Metrics::new();
+
+/// Counters which are updated while the Server runs and exported in the Prometheus text format
+pub struct Metrics {
+    pub bytes_sent: AtomicU64,
+    pub bytes_received: AtomicU64,
+    connections_total: AtomicU64,
+    connections_open: AtomicU64,
+    /// Start and duration of the last ticks
+    ticks: Mutex<VecDeque<(Instant, Duration)>>,
+    /// Disconnects by the category of their reason
+    disconnects: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            bytes_sent: AtomicU64::new(0),
+            bytes_received: AtomicU64::new(0),
+            connections_total: AtomicU64::new(0),
+            connections_open: AtomicU64::new(0),
+            ticks: Mutex::new(VecDeque::new()),
+            disconnects: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn connection_opened(&self) {
+        self.connections_total.fetch_add(1, Ordering::Relaxed);
+        self.connections_open.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn connection_closed(&self) {
+        self.connections_open.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn record_disconnect(&self, category: &'static str) {
+        *self.disconnects.lock().entry(category).or_default() += 1;
+    }
+
+    pub fn record_tick(&self, start: Instant, took: Duration) {
+        let mut ticks = self.ticks.lock();
+        if ticks.len() == TICK_WINDOW {
+            ticks.pop_front();
+        }
+        ticks.push_back((start, took));
+    }
+
+    /// Ticks per second over the last ticks, at most 20 like vanilla reports it
+    pub fn tps(&self) -> f64 {
+        let max_tps = 1.0 / TICK_DURATION.as_secs_f64();
+        let ticks = self.ticks.lock();
+        let (Some((first, _)), Some((last, _))) = (ticks.front(), ticks.back()) else {
+            return max_tps;
+        };
+        let elapsed = last.duration_since(*first).as_secs_f64();
+        if elapsed == 0.0 {
+            return max_tps;
+        }
+        ((ticks.len() - 1) as f64 / elapsed).min(max_tps)
+    }
+
+    /// The average milliseconds a tick took over the last ticks
+    pub fn mspt(&self) -> f64 {
+        let ticks = self.ticks.lock();
+        if ticks.is_empty() {
+            return 0.0;
+        }
+        let total: Duration = ticks.iter().map(|(_, took)| *took).sum();
+        total.as_nanos() as f64 / 1_000_000.0 / ticks.len() as f64
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format
+    pub fn render(&self, players_online: usize, loaded_chunks: usize, uptime_secs: u64) -> String {
+        let metrics: [(&str, &str, &str, String); 9] = [
+            (
+                "pumpkin_players_online",
+                "gauge",
+                "Players currently online",
+                players_online.to_string(),
+            ),
+            (
+                "pumpkin_tps",
+                "gauge",
+                "Ticks per second",
+                self.tps().to_string(),
+            ),
+            (
+                "pumpkin_mspt",
+                "gauge",
+                "Average milliseconds per tick",
+                self.mspt().to_string(),
+            ),
+            (
+                "pumpkin_loaded_chunks",
+                "gauge",
+                "Chunks loaded in all worlds",
+                loaded_chunks.to_string(),
+            ),
+            (
+                "pumpkin_network_sent_bytes_total",
+                "counter",
+                "Bytes sent to clients",
+                self.bytes_sent.load(Ordering::Relaxed).to_string(),
+            ),
+            (
+                "pumpkin_network_received_bytes_total",
+                "counter",
+                "Bytes received from clients",
+                self.bytes_received.load(Ordering::Relaxed).to_string(),
+            ),
+            (
+                "pumpkin_connections_open",
+                "gauge",
+                "Connections currently open",
+                self.connections_open.load(Ordering::Relaxed).to_string(),
+            ),
+            (
+                "pumpkin_connections_total",
+                "counter",
+                "Connections accepted since the Server started",
+                self.connections_total.load(Ordering::Relaxed).to_string(),
+            ),
+            (
+                "pumpkin_uptime_seconds",
+                "counter",
+                "Seconds since the Server started",
+                uptime_secs.to_string(),
+            ),
+        ];
+        let mut out = String::new();
+        for (name, kind, help, value) in metrics {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} {kind}");
+            let _ = writeln!(out, "{name} {value}");
+        }
+        let disconnects = self.disconnects.lock();
+        if !disconnects.is_empty() {
+            let name = "pumpkin_disconnects_total";
+            let _ = writeln!(out, "# HELP {name} Clients kicked since the Server started");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            for (reason, count) in disconnects.iter() {
+                let _ = writeln!(out, "{name}{{reason=\"{reason}\"}} {count}");
+            }
+        }
+        out
+    }
+
+    /// Renders the metrics with the current state of the Server
+    pub fn render_server(&self, server: &Server) -> String {
+        let loaded_chunks = server
+            .worlds
+            .iter()
+            .map(|world| world.level.loaded_chunk_count())
+            .sum();
+        self.render(
+            server.get_player_count(),
+            loaded_chunks,
+            server.uptime.elapsed().as_secs(),
+        )
+    }
+}
+
+/// Answers `GET /metrics` on the listener with the output of `render`, every other request gets a 404.
+///
+/// This only understands as much HTTP as a Prometheus scrape needs
+pub async fn serve<F>(listener: TcpListener, render: F) -> io::Result<()>
+where
+    F: Fn() -> String + Send + Sync + 'static,
+{
+    let render = Arc::new(render);
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let render = render.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_connection(stream, render.as_ref()).await {
+                log::debug!("Failed to answer metrics request: {}", err);
+            }
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, render: &impl Fn() -> String) -> io::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        if request.len() > MAX_REQUEST_SIZE {
+            return Ok(());
+        }
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        request.extend_from_slice(&buf[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.lines().next().unwrap_or_default().split(' ');
+    let path = match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(path)) => path.split('?').next(),
+        _ => None,
+    };
+    let (status, content_type, body) = if path == Some("/metrics") {
+        ("200 OK", CONTENT_TYPE, render())
+    } else {
+        ("404 Not Found", "text/plain", "Not Found\n".to_string())
+    };
+
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{atomic::Ordering, Arc},
+        time::{Duration, Instant},
+    };
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::{serve, Metrics};
+
+    #[test]
+    fn test_tps_and_mspt() {
+        let metrics = Metrics::new();
+        assert_eq!(metrics.tps(), 20.0);
+        assert_eq!(metrics.mspt(), 0.0);
+
+        // The Server is lagging, ticks take 100ms
+        let start = Instant::now();
+        for i in 0..11 {
+            metrics.record_tick(
+                start + Duration::from_millis(100 * i),
+                Duration::from_millis(100),
+            );
+        }
+        assert!((metrics.tps() - 10.0).abs() < 1e-9);
+        assert!((metrics.mspt() - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_disconnects() {
+        let metrics = Metrics::new();
+        assert!(!metrics
+            .render(0, 0, 0)
+            .contains("pumpkin_disconnects_total"));
+
+        metrics.record_disconnect("timeout");
+        metrics.record_disconnect("spam");
+        metrics.record_disconnect("timeout");
+        let out = metrics.render(0, 0, 0);
+        assert!(out.contains("pumpkin_disconnects_total{reason=\"spam\"} 1\n"));
+        assert!(out.contains("pumpkin_disconnects_total{reason=\"timeout\"} 2\n"));
+    }
+
+    async fn scrape(address: std::net::SocketAddr, path: &str) -> String {
+        let mut stream = TcpStream::connect(address).await.unwrap();
+        stream
+            .write_all(format!("GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_scrape() {
+        let metrics = Arc::new(Metrics::new());
+        metrics.connection_opened();
+        metrics.connection_opened();
+        metrics.connection_closed();
+        metrics.bytes_sent.fetch_add(1234, Ordering::Relaxed);
+        metrics.bytes_received.fetch_add(56, Ordering::Relaxed);
+        let start = Instant::now();
+        metrics.record_tick(start, Duration::from_millis(4));
+        metrics.record_tick(start + Duration::from_millis(50), Duration::from_millis(6));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let rendered = metrics.clone();
+        tokio::spawn(serve(listener, move || rendered.render(3, 49, 3725)));
+
+        let response = scrape(address, "/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        let body = response.split("\r\n\r\n").nth(1).unwrap();
+        let values: Vec<_> = body.lines().filter(|line| !line.starts_with('#')).collect();
+        assert_eq!(
+            values,
+            vec![
+                "pumpkin_players_online 3",
+                "pumpkin_tps 20",
+                "pumpkin_mspt 5",
+                "pumpkin_loaded_chunks 49",
+                "pumpkin_network_sent_bytes_total 1234",
+                "pumpkin_network_received_bytes_total 56",
+                "pumpkin_connections_open 1",
+                "pumpkin_connections_total 2",
+                "pumpkin_uptime_seconds 3725",
+            ]
+        );
+        assert!(body.contains("# TYPE pumpkin_network_sent_bytes_total counter"));
+
+        // Values are read on every scrape
+        metrics.bytes_sent.fetch_add(1, Ordering::Relaxed);
+        assert!(scrape(address, "/metrics")
+            .await
+            .contains("pumpkin_network_sent_bytes_total 1235"));
+
+        assert!(scrape(address, "/")
+            .await
+            .starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
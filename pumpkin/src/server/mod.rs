@@ -1,39 +1,84 @@
-use connection_cache::{CachedBranding, CachedStatus};
+use autosave::AutoSave;
+use bans::BanList;
+use connection_cache::{player_sample, CachedBranding, CachedStatus};
 use key_store::KeyStore;
+use login_throttle::LoginThrottle;
 use mio::Token;
+use mutes::{unix_now, MuteList};
+use name_cache::NameCache;
+use operators::OperatorList;
 use parking_lot::{Mutex, RwLock};
-use pumpkin_config::BASIC_CONFIG;
-use pumpkin_core::GameMode;
+use player_slots::PlayerSlots;
+use pumpkin_config::{HandshakeConfig, ADVANCED_CONFIG, BASIC_CONFIG};
 use pumpkin_entity::EntityId;
 use pumpkin_inventory::drag_handler::DragHandler;
 use pumpkin_inventory::{Container, OpenContainer};
 use pumpkin_plugin::PluginLoader;
 use pumpkin_protocol::client::login::CEncryptionRequest;
-use pumpkin_protocol::client::status::CStatusResponse;
-use pumpkin_protocol::{client::config::CPluginMessage, ClientPacket};
-use pumpkin_registry::Registry;
+use pumpkin_protocol::{client::config::CPluginMessage, ClientPacket, Sample, CURRENT_MC_PROTOCOL};
+use pumpkin_registry::{Registry, REGISTRY_FOLDER};
 use pumpkin_world::dimension::Dimension;
 use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::Path;
 use std::{
     sync::{
-        atomic::{AtomicI32, Ordering},
+        atomic::{AtomicBool, Ordering},
         Arc,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
+use teleport_requests::TeleportRequests;
+use uptime::Uptime;
+use uuid::Uuid;
+use warps::WarpList;
+use whitelist::Whitelist;
 
 use crate::client::EncryptionError;
 use crate::{
-    client::Client,
+    client::{authentication::GameProfile, disconnect::DisconnectReason, Client},
     commands::{default_dispatcher, dispatcher::CommandDispatcher},
-    entity::player::Player,
-    world::World,
+    entity::{id_allocator::EntityIdAllocator, player::Player},
+    events::{EventBus, PlayerJoin, PlayerQuit},
+    world::{player_data::default_gamemode, World},
 };
 
+pub mod autosave;
+pub mod bans;
 mod connection_cache;
+pub mod http_api;
 mod key_store;
+pub mod login_throttle;
+pub mod metrics;
+pub mod mutes;
+pub mod name_cache;
+pub mod operators;
+pub mod player_slots;
+pub mod server_links;
+pub mod socket;
+pub mod teleport_requests;
+pub mod threads;
+pub mod uptime;
+pub mod warps;
+pub mod whitelist;
 pub const CURRENT_MC_VERSION: &str = "1.21.1";
 
+/// The protocol and Minecraft version Clients need to log in
+pub fn supported_version(config: &HandshakeConfig) -> (u32, &str) {
+    let protocol = match config.protocol_version {
+        0 => CURRENT_MC_PROTOCOL,
+        protocol => protocol,
+    };
+    let name = match config.minecraft_version.as_str() {
+        "" => CURRENT_MC_VERSION,
+        name => name,
+    };
+    (protocol, name)
+}
+
+/// How long a single game tick takes, Minecraft runs at 20 ticks per second
+pub const TICK_DURATION: Duration = Duration::from_millis(50);
+
 pub struct Server {
     key_store: KeyStore,
     server_listing: CachedStatus,
@@ -48,7 +93,35 @@ pub struct Server {
 
     pub open_containers: RwLock<HashMap<u64, OpenContainer>>,
     pub drag_handler: DragHandler,
-    entity_id: AtomicI32,
+    entity_ids: EntityIdAllocator,
+
+    /// Players with elevated permissions, loaded from `ops.json`
+    pub operators: RwLock<OperatorList>,
+    /// Named locations every Player can teleport to
+    pub warps: RwLock<WarpList>,
+    /// Players who may not chat
+    pub mutes: RwLock<MuteList>,
+    /// Players who may join while the whitelist is enabled
+    pub whitelist: RwLock<Whitelist>,
+    /// Banned Players and IPs
+    pub bans: RwLock<BanList>,
+    /// Names of Players who joined before, to find them while offline
+    pub name_cache: RwLock<NameCache>,
+    /// Recent login starts by name
+    pub login_throttle: Mutex<LoginThrottle>,
+    /// Can be changed with `/whitelist on|off`
+    pub whitelist_enabled: AtomicBool,
+    /// Taken at the login success, so concurrent logins can't exceed `max_players`
+    pub player_slots: PlayerSlots,
+    /// Pending `/tpa` requests
+    pub teleport_requests: Mutex<TeleportRequests>,
+    /// Paused with `/save-off`
+    pub autosave: AutoSave,
+
+    /// Handlers which react to things happening on the Server
+    pub events: EventBus,
+    /// When the Server started
+    pub uptime: Uptime,
 
     /// Used for Authentication, None is Online mode is disabled
     pub auth_client: Option<reqwest::Client>,
@@ -75,21 +148,37 @@ impl Server {
         log::info!("Loading Plugins");
         let plugin_loader = PluginLoader::load();
 
-        let world = World::load(Dimension::OverWorld.into_level(
-            // TODO: load form config
-            "./world".parse().unwrap(),
-        ));
+        let world = World::load(
+            Dimension::OverWorld,
+            Dimension::OverWorld.into_level(
+                // TODO: load form config
+                "./world".parse().unwrap(),
+            ),
+        );
         Self {
             plugin_loader,
-            cached_registry: Registry::get_static(),
+            cached_registry: Registry::load(Path::new(REGISTRY_FOLDER)),
             open_containers: RwLock::new(HashMap::new()),
             drag_handler: DragHandler::new(),
             // 0 is invalid
-            entity_id: 2.into(),
+            entity_ids: EntityIdAllocator::new(2),
             worlds: vec![Arc::new(world)],
             command_dispatcher: Arc::new(command_dispatcher),
+            operators: RwLock::new(OperatorList::load()),
+            warps: RwLock::new(WarpList::load()),
+            mutes: RwLock::new(MuteList::load()),
+            whitelist: RwLock::new(Whitelist::load()),
+            bans: RwLock::new(BanList::load()),
+            name_cache: RwLock::new(NameCache::load()),
+            login_throttle: Mutex::new(LoginThrottle::default()),
+            whitelist_enabled: AtomicBool::new(ADVANCED_CONFIG.whitelist.enabled),
+            player_slots: PlayerSlots::default(),
+            teleport_requests: Mutex::new(TeleportRequests::default()),
+            autosave: AutoSave::default(),
+            events: EventBus::default(),
+            uptime: Uptime::start(),
             auth_client,
-            key_store: KeyStore::new(),
+            key_store: KeyStore::new(ADVANCED_CONFIG.encryption.key_size),
             server_listing: CachedStatus::new(),
             server_branding: CachedBranding::new(),
         }
@@ -97,19 +186,74 @@ impl Server {
 
     pub async fn add_player(&self, token: Token, client: Arc<Client>) -> (Arc<Player>, Arc<World>) {
         let entity_id = self.new_entity_id();
-        let gamemode = match BASIC_CONFIG.default_gamemode {
-            GameMode::Undefined => GameMode::Survival,
-            game_mode => game_mode,
-        };
+        // Replaced by the saved gamemode once the Player spawns
+        let gamemode = default_gamemode(BASIC_CONFIG.default_gamemode);
         // Basically the default world
         // TODO: select default from config
         let world = &self.worlds[0];
 
         let player = Arc::new(Player::new(client, world.clone(), entity_id, gamemode));
+        let permission_lvl = self.operators.read().permission_lvl(&player.gameprofile.id);
+        player
+            .permission_lvl
+            .store(permission_lvl, Ordering::Relaxed);
         world.add_player(token, player.clone());
+        self.update_status();
+        {
+            let mut names = self.name_cache.write();
+            names.insert(&player.gameprofile.name, player.gameprofile.id, unix_now());
+            if let Err(err) = names.save() {
+                log::error!("Couldn't save the name cache: {}", err);
+            }
+        }
+        self.events.fire(PlayerJoin {
+            profile: player.gameprofile.clone(),
+            entity_id,
+        });
         (player, world.clone())
     }
 
+    /// Removes a disconnected Player from the Server, this does nothing if they were already removed
+    pub async fn remove_player(&self, player: &Player) {
+        self.release_player_slot(&player.client);
+        // The connection is already closed, so there is nobody to send a message to
+        if !player.disconnect(None).await {
+            return;
+        }
+        self.update_status();
+        self.events.fire(PlayerQuit {
+            profile: player.gameprofile.clone(),
+            entity_id: player.entity_id(),
+        });
+    }
+
+    /// Removes the Players whose connection was closed without the network loop noticing,
+    /// e.g. when they were kicked for not answering keep alives
+    pub async fn remove_closed_players(&self) {
+        for world in &self.worlds {
+            for player in world.closed_players() {
+                self.remove_player(&player).await;
+            }
+        }
+    }
+
+    /// Saves every World and the Players in them, returns how many chunks were saved
+    pub fn save(&self) -> usize {
+        self.worlds.iter().map(|world| world.save()).sum()
+    }
+
+    /// Ticks the Worlds which are due at `now`, returns when the next World has to tick
+    pub fn tick(&self, now: Instant) -> Instant {
+        let next_tick = self
+            .worlds
+            .iter()
+            .map(|world| world.tick_scheduled(now, &self.entity_ids))
+            .min()
+            .unwrap_or(now + TICK_DURATION);
+        self.teleport_requests.lock().remove_expired(now);
+        next_tick
+    }
+
     pub fn try_get_container(
         &self,
         player_id: EntityId,
@@ -142,18 +286,131 @@ impl Server {
         None
     }
 
-    /// Generates a new entity id
-    /// This should be global
+    /// Searches every world for a player by UUID
+    pub fn get_player_by_uuid(&self, uuid: &Uuid) -> Option<Arc<Player>> {
+        for world in self.worlds.iter() {
+            if let Some(player) = world.get_player_by_uuid(uuid) {
+                return Some(player);
+            }
+        }
+        None
+    }
+
+    /// Returns the Players of all worlds
+    pub fn get_all_players(&self) -> Vec<Arc<Player>> {
+        self.worlds
+            .iter()
+            .flat_map(|world| {
+                world
+                    .current_players
+                    .lock()
+                    .values()
+                    .cloned()
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Returns the amount of Players in all worlds
+    pub fn get_player_count(&self) -> usize {
+        self.worlds
+            .iter()
+            .map(|world| world.current_players.lock().len())
+            .sum()
+    }
+
+    /// Takes a slot for the Client logging in with the given profile, returns false if the Server is full
+    pub fn take_player_slot(&self, client: &Client, profile: &GameProfile) -> bool {
+        let bypasses = self
+            .operators
+            .read()
+            .bypasses_player_limit(&profile.id, &ADVANCED_CONFIG.op_bypass);
+        if !self
+            .player_slots
+            .try_take(BASIC_CONFIG.max_players, bypasses)
+        {
+            return false;
+        }
+        client.holds_player_slot.store(true, Ordering::Release);
+        true
+    }
+
+    /// Gives back the slot of a Client who left, this does nothing if they had none or it was given back already
+    pub fn release_player_slot(&self, client: &Client) {
+        if client.holds_player_slot.swap(false, Ordering::AcqRel) {
+            self.player_slots.release();
+        }
+    }
+
+    /// Checks if the Player may join, operators don't need to be whitelisted
+    pub fn is_whitelisted(&self, profile: &GameProfile) -> bool {
+        !self.whitelist_enabled.load(Ordering::Relaxed)
+            || self.operators.read().get(&profile.id).is_some()
+            || self.whitelist.read().contains(&profile.id, &profile.name)
+    }
+
+    /// Kicks every online Player who is not whitelisted, if the whitelist is enabled and enforced
+    pub fn enforce_whitelist(&self) -> usize {
+        if !self.whitelist_enabled.load(Ordering::Relaxed) || !ADVANCED_CONFIG.whitelist.enforce {
+            return 0;
+        }
+        let operators = self.operators.read();
+        let players: Vec<_> = self
+            .get_all_players()
+            .into_iter()
+            .filter(|player| operators.get(&player.gameprofile.id).is_none())
+            .collect();
+        let not_whitelisted = self.whitelist.read().not_whitelisted(&players, |player| {
+            (&player.gameprofile.id, player.gameprofile.name.as_str())
+        });
+        // Players who left in the meantime are skipped by the kick
+        not_whitelisted
+            .iter()
+            .filter(|player| player.kick(DisconnectReason::NotWhitelisted))
+            .count()
+    }
+
+    /// Returns the message to kick the Player with if it or its IP is banned
+    pub fn ban_message(&self, profile: &GameProfile, ip: &IpAddr) -> Option<String> {
+        let now = unix_now();
+        self.bans
+            .read()
+            .find_ban(&profile.id, &profile.name, ip, now)
+            .map(|ban| ban.kick_message(now))
+    }
+
+    /// Kicks every online Player who is banned, right after a ban was added
+    pub fn kick_banned(&self) -> usize {
+        let mut kicked = 0;
+        for player in self.get_all_players() {
+            let ip = player.client.address.lock().ip();
+            if let Some(message) = self.ban_message(&player.gameprofile, &ip) {
+                if player.kick(DisconnectReason::Banned(Some(message))) {
+                    kicked += 1;
+                }
+            }
+        }
+        kicked
+    }
+
+    /// Generates a new entity id, every entity on the Server has to get its id from here
     pub fn new_entity_id(&self) -> EntityId {
-        self.entity_id.fetch_add(1, Ordering::SeqCst)
+        self.entity_ids.allocate()
     }
 
     pub fn get_branding(&self) -> CPluginMessage<'_> {
         self.server_branding.get_branding()
     }
 
-    pub fn get_status(&self) -> CStatusResponse<'_> {
-        self.server_listing.get_status()
+    pub fn send_status(&self, client: &Client) {
+        self.server_listing.send_status(client);
+    }
+
+    /// Rebuilds the Player count and sample of the Status response, has to be called when a Player joins, leaves
+    /// or vanishes
+    pub fn update_status(&self) {
+        self.server_listing
+            .update_players(status_sample(&self.get_all_players()));
     }
 
     pub fn encryption_request<'a>(
@@ -169,7 +426,53 @@ impl Server {
         self.key_store.decrypt(data)
     }
 
+    pub fn verify_token(
+        &self,
+        expected: &[u8; 4],
+        encrypted: &[u8],
+    ) -> Result<(), EncryptionError> {
+        self.key_store.verify_token(expected, encrypted)
+    }
+
     pub fn digest_secret(&self, secret: &[u8]) -> String {
         self.key_store.get_digest(secret)
     }
 }
+
+/// The sample of the Status response, vanished Players are neither listed nor counted
+fn status_sample(players: &[Arc<Player>]) -> Vec<Sample> {
+    players
+        .iter()
+        .filter(|player| !player.is_vanished())
+        .map(|player| {
+            let listed = player.config.lock().server_listing;
+            player_sample(&player.gameprofile.name, player.gameprofile.id, listed)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::Ordering;
+
+    use mio::Token;
+    use pumpkin_protocol::ConnectionState;
+
+    use super::status_sample;
+    use crate::test_helpers::{client, player_of, world};
+
+    #[test]
+    fn test_status_sample_hides_vanished() {
+        let world = world("status-sample");
+        let steve = player_of(&world, client(Token(1), ConnectionState::Play), 2, "Steve");
+        let alex = player_of(&world, client(Token(2), ConnectionState::Play), 3, "Alex");
+        steve.config.lock().server_listing = true;
+        alex.config.lock().server_listing = true;
+        alex.vanished.store(true, Ordering::Relaxed);
+
+        let sample = status_sample(&[steve.clone(), alex]);
+        assert_eq!(sample.len(), 1);
+        assert_eq!(sample[0].name, "Steve");
+        assert_eq!(sample[0].id, steve.gameprofile.id.to_string());
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const MUTES_FILE: &str = "muted-players.json";
+
+/// A Player who may not chat
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Mute {
+    pub name: String,
+    /// When the mute ends in seconds since the unix epoch, None means never
+    pub expires: Option<u64>,
+    pub reason: Option<String>,
+}
+
+impl Mute {
+    pub fn is_expired(&self, now: u64) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+}
+
+/// The seconds since the unix epoch, used for mute expiry
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+/// Muted Players by UUID, stored in `muted-players.json`
+#[derive(Default, Debug, PartialEq)]
+pub struct MuteList {
+    mutes: BTreeMap<Uuid, Mute>,
+}
+
+impl MuteList {
+    /// Loads the mutes from `muted-players.json`, a missing file means nobody is muted
+    pub fn load() -> Self {
+        Self::load_from(Path::new(MUTES_FILE)).unwrap_or_else(|err| {
+            log::error!("Couldn't load {}: {}", MUTES_FILE, err);
+            Self::default()
+        })
+    }
+
+    /// Saves the mutes to `muted-players.json`
+    pub fn save(&self) -> io::Result<()> {
+        self.save_to(Path::new(MUTES_FILE))
+    }
+
+    fn load_from(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map(|mutes| Self { mutes })
+                .map_err(io::Error::other),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn save_to(&self, path: &Path) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(&self.mutes).map_err(io::Error::other)?;
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(temp_path, path)
+    }
+
+    /// Returns the mute of the Player if it hasn't expired yet
+    pub fn get(&self, uuid: &Uuid, now: u64) -> Option<&Mute> {
+        self.mutes.get(uuid).filter(|mute| !mute.is_expired(now))
+    }
+
+    /// Mutes the Player, replacing an earlier mute
+    pub fn mute(&mut self, uuid: Uuid, mute: Mute) {
+        self.mutes.insert(uuid, mute);
+    }
+
+    /// Unmutes the Player, returning the mute if it was still active
+    pub fn unmute(&mut self, uuid: &Uuid, now: u64) -> Option<Mute> {
+        self.mutes.remove(uuid).filter(|mute| !mute.is_expired(now))
+    }
+
+    /// Forgets expired mutes so they don't pile up in the file
+    pub fn remove_expired(&mut self, now: u64) {
+        self.mutes.retain(|_, mute| !mute.is_expired(now));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use uuid::Uuid;
+
+    use super::{Mute, MuteList};
+
+    fn mute(expires: Option<u64>) -> Mute {
+        Mute {
+            name: "Steve".to_string(),
+            expires,
+            reason: Some("Spam".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_muted_player_is_blocked() {
+        let mut mutes = MuteList::default();
+        let steve = Uuid::new_v4();
+        let alex = Uuid::new_v4();
+        mutes.mute(steve, mute(None));
+
+        assert_eq!(mutes.get(&steve, 1000), Some(&mute(None)));
+        assert!(mutes.get(&alex, 1000).is_none());
+
+        assert_eq!(mutes.unmute(&steve, 1000), Some(mute(None)));
+        assert!(mutes.get(&steve, 1000).is_none());
+        assert!(mutes.unmute(&steve, 1000).is_none());
+    }
+
+    #[test]
+    fn test_expiry_clears_mute() {
+        let mut mutes = MuteList::default();
+        let steve = Uuid::new_v4();
+        let alex = Uuid::new_v4();
+        mutes.mute(steve, mute(Some(100)));
+        mutes.mute(alex, mute(Some(200)));
+
+        assert!(mutes.get(&steve, 99).is_some());
+        assert!(mutes.get(&steve, 100).is_none());
+        // An expired mute is nothing to unmute
+        assert!(mutes.unmute(&steve, 100).is_none());
+
+        mutes.mute(steve, mute(Some(100)));
+        mutes.remove_expired(100);
+        assert!(mutes.get(&steve, 0).is_none());
+        assert!(mutes.get(&alex, 100).is_some());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = env::temp_dir().join("pumpkin-mutes-test.json");
+        let _ = fs::remove_file(&path);
+        assert_eq!(MuteList::load_from(&path).unwrap(), MuteList::default());
+
+        let mut mutes = MuteList::default();
+        mutes.mute(Uuid::new_v4(), mute(None));
+        mutes.mute(Uuid::new_v4(), mute(Some(1_000_000)));
+        mutes.save_to(&path).unwrap();
+        assert_eq!(MuteList::load_from(&path).unwrap(), mutes);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,133 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const NAME_CACHE_FILE: &str = "usercache.json";
+
+/// Like vanilla only the most recent Players are remembered
+const MAX_ENTRIES: usize = 1000;
+
+/// The UUID a name belonged to when the Player last joined
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CachedName {
+    pub name: String,
+    pub uuid: Uuid,
+    /// Seconds since the unix epoch
+    pub last_join: u64,
+}
+
+/// Names of Players who joined before, so they can be resolved while they are offline.
+/// Stored in `usercache.json`, the most recent join first
+#[derive(Default, Debug, PartialEq)]
+pub struct NameCache {
+    entries: Vec<CachedName>,
+}
+
+impl NameCache {
+    /// Loads the names from `usercache.json`, a missing file means nobody joined yet
+    pub fn load() -> Self {
+        Self::load_from(Path::new(NAME_CACHE_FILE)).unwrap_or_else(|err| {
+            log::error!("Couldn't load {}: {}", NAME_CACHE_FILE, err);
+            Self::default()
+        })
+    }
+
+    /// Saves the names to `usercache.json`
+    pub fn save(&self) -> io::Result<()> {
+        self.save_to(Path::new(NAME_CACHE_FILE))
+    }
+
+    fn load_from(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map(|entries| Self { entries })
+                .map_err(io::Error::other),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn save_to(&self, path: &Path) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(&self.entries).map_err(io::Error::other)?;
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(temp_path, path)
+    }
+
+    /// Remembers a joining Player, a renamed Player replaces their old name
+    pub fn insert(&mut self, name: &str, uuid: Uuid, now: u64) {
+        self.entries
+            .retain(|entry| entry.uuid != uuid && !entry.name.eq_ignore_ascii_case(name));
+        self.entries.insert(
+            0,
+            CachedName {
+                name: name.to_string(),
+                uuid,
+                last_join: now,
+            },
+        );
+        self.entries.truncate(MAX_ENTRIES);
+    }
+
+    /// Looks up a name ignoring its case, as names are unique regardless of it
+    pub fn get(&self, name: &str) -> Option<&CachedName> {
+        self.entries
+            .iter()
+            .find(|entry| entry.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use uuid::Uuid;
+
+    use super::{NameCache, MAX_ENTRIES};
+
+    #[test]
+    fn test_insert_and_get() {
+        let mut names = NameCache::default();
+        let steve = Uuid::new_v4();
+        names.insert("Steve", steve, 100);
+        assert_eq!(names.get("steve").map(|entry| entry.uuid), Some(steve));
+        assert!(names.get("Alex").is_none());
+
+        // Renaming frees the old name
+        names.insert("Steve2", steve, 200);
+        assert!(names.get("Steve").is_none());
+        assert_eq!(names.get("Steve2").unwrap().last_join, 200);
+
+        // The name now belongs to someone else
+        let other = Uuid::new_v4();
+        names.insert("Steve2", other, 300);
+        assert_eq!(names.get("Steve2").unwrap().uuid, other);
+        assert_eq!(names.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_oldest_entries_are_dropped() {
+        let mut names = NameCache::default();
+        for i in 0..=MAX_ENTRIES {
+            names.insert(&format!("player{i}"), Uuid::new_v4(), i as u64);
+        }
+        assert_eq!(names.entries.len(), MAX_ENTRIES);
+        assert!(names.get("player0").is_none());
+        assert!(names.get(&format!("player{MAX_ENTRIES}")).is_some());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = env::temp_dir().join("pumpkin-usercache-test.json");
+        let _ = fs::remove_file(&path);
+        assert_eq!(NameCache::load_from(&path).unwrap(), NameCache::default());
+
+        let mut names = NameCache::default();
+        names.insert("Steve", Uuid::new_v4(), 100);
+        names.insert("Alex", Uuid::new_v4(), 200);
+        names.save_to(&path).unwrap();
+        assert_eq!(NameCache::load_from(&path).unwrap(), names);
+        fs::remove_file(path).unwrap();
+    }
+}
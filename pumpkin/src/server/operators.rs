@@ -0,0 +1,59 @@
+use std::{fs, path::Path};
+
+use pumpkin_config::{BypassLimit, OpBypassConfig};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const OPERATORS_FILE: &str = "ops.json";
+
+/// A Player with elevated permissions, stored in `ops.json` just like vanilla does
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Operator {
+    pub uuid: Uuid,
+    pub name: String,
+    /// The permission level from 0 to 4
+    pub level: u8,
+    /// Whether the Operator can join even when the server is full
+    #[serde(default)]
+    pub bypasses_player_limit: bool,
+}
+
+#[derive(Default)]
+pub struct OperatorList {
+    pub operators: Vec<Operator>,
+}
+
+impl OperatorList {
+    /// Loads the operators from `ops.json`, a missing file means there are no operators
+    pub fn load() -> Self {
+        let path = Path::new(OPERATORS_FILE);
+        if !path.exists() {
+            return Self::default();
+        }
+        let operators = fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|content| serde_json::from_str(&content).map_err(|err| err.to_string()))
+            .unwrap_or_else(|err| {
+                log::error!("Couldn't load {}: {}", OPERATORS_FILE, err);
+                Vec::new()
+            });
+        Self { operators }
+    }
+
+    pub fn get(&self, uuid: &Uuid) -> Option<&Operator> {
+        self.operators.iter().find(|op| &op.uuid == uuid)
+    }
+
+    /// The permission level of the Player, 0 if they are not an Operator
+    pub fn permission_lvl(&self, uuid: &Uuid) -> u8 {
+        self.get(uuid).map_or(0, |op| op.level)
+    }
+
+    /// Operators join a full server if `ops.json` allows it or their level is high enough
+    pub fn bypasses_player_limit(&self, uuid: &Uuid, config: &OpBypassConfig) -> bool {
+        self.get(uuid).is_some_and(|op| {
+            op.bypasses_player_limit || config.can_bypass(BypassLimit::MaxPlayers, op.level)
+        })
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts the Players who logged in, from their login success until they leave.
+///
+/// A slot is taken before the login success is sent, so two Clients logging in at the same time can't both take the last slot
+#[derive(Default)]
+pub struct PlayerSlots {
+    taken: AtomicUsize,
+}
+
+impl PlayerSlots {
+    /// Takes a slot, returns false if all `max_players` are taken and the Player can't bypass the limit
+    pub fn try_take(&self, max_players: u32, bypasses_player_limit: bool) -> bool {
+        if bypasses_player_limit {
+            self.taken.fetch_add(1, Ordering::AcqRel);
+            return true;
+        }
+        self.taken
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |taken| {
+                (taken < max_players as usize).then_some(taken + 1)
+            })
+            .is_ok()
+    }
+
+    /// Gives back a slot which was taken with `try_take`
+    pub fn release(&self) {
+        let _ = self
+            .taken
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |taken| {
+                taken.checked_sub(1)
+            });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        sync::{Arc, Barrier},
+        thread,
+    };
+
+    use super::PlayerSlots;
+
+    #[test]
+    fn test_max_players() {
+        let slots = PlayerSlots::default();
+        assert!(slots.try_take(2, false));
+        assert!(slots.try_take(2, false));
+        // the (N+1)th Player gets rejected
+        assert!(!slots.try_take(2, false));
+        // unless they are allowed to bypass the limit
+        assert!(slots.try_take(2, true));
+
+        slots.release();
+        slots.release();
+        assert!(slots.try_take(2, false));
+    }
+
+    #[test]
+    fn test_concurrent_logins_take_one_slot() {
+        const MAX_PLAYERS: u32 = 10;
+        for _ in 0..100 {
+            let slots = Arc::new(PlayerSlots::default());
+            for _ in 0..MAX_PLAYERS - 1 {
+                assert!(slots.try_take(MAX_PLAYERS, false));
+            }
+            let barrier = Arc::new(Barrier::new(2));
+            let logins = (0..2)
+                .map(|_| {
+                    let slots = slots.clone();
+                    let barrier = barrier.clone();
+                    thread::spawn(move || {
+                        barrier.wait();
+                        slots.try_take(MAX_PLAYERS, false)
+                    })
+                })
+                .collect::<Vec<_>>();
+            let joined = logins
+                .into_iter()
+                .map(|login| login.join().unwrap())
+                .filter(|joined| *joined)
+                .count();
+            assert_eq!(joined, 1);
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use pumpkin_config::ServerLinksConfig;
+use pumpkin_core::text::TextComponent;
+use pumpkin_protocol::client::config::{BuiltInLinkLabel, Link, LinkLabel};
+
+/// The client only opens http and https links which have a host
+fn is_valid_url(url: &str) -> bool {
+    let Some(rest) = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+    else {
+        return false;
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    !host.is_empty() && !url.chars().any(char::is_whitespace)
+}
+
+/// Builds the configured links, skipping the ones with an invalid URL
+pub fn server_links(config: &ServerLinksConfig) -> Vec<Link<'_>> {
+    config
+        .links
+        .iter()
+        .filter(|link| {
+            let valid = is_valid_url(&link.url);
+            if !valid {
+                log::warn!(
+                    "Skipping server link {} with invalid URL {}",
+                    link.label,
+                    link.url
+                );
+            }
+            valid
+        })
+        .map(|link| Link {
+            label: match BuiltInLinkLabel::from_name(&link.label) {
+                Some(label) => LinkLabel::BuiltIn(label),
+                None => LinkLabel::Custom(TextComponent::text(&link.label)),
+            },
+            url: &link.url,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::{ServerLink, ServerLinksConfig};
+    use pumpkin_protocol::client::config::{BuiltInLinkLabel, LinkLabel};
+
+    use super::{is_valid_url, server_links};
+
+    #[test]
+    fn test_is_valid_url() {
+        assert!(is_valid_url("https://example.com"));
+        assert!(is_valid_url("http://example.com/rules?lang=en"));
+        assert!(!is_valid_url("ftp://example.com"));
+        assert!(!is_valid_url("example.com"));
+        assert!(!is_valid_url("https://"));
+        assert!(!is_valid_url("https:///rules"));
+        assert!(!is_valid_url("https://example.com/our rules"));
+    }
+
+    #[test]
+    fn test_skip_invalid_links() {
+        let config = ServerLinksConfig {
+            enabled: true,
+            links: vec![
+                ServerLink {
+                    label: "website".into(),
+                    url: "https://example.com".into(),
+                },
+                ServerLink {
+                    label: "Broken".into(),
+                    url: "not a url".into(),
+                },
+                ServerLink {
+                    label: "Rules".into(),
+                    url: "https://example.com/rules".into(),
+                },
+            ],
+        };
+        let links = server_links(&config);
+        assert_eq!(links.len(), 2);
+        assert!(matches!(
+            links[0].label,
+            LinkLabel::BuiltIn(BuiltInLinkLabel::Website)
+        ));
+        assert!(matches!(links[1].label, LinkLabel::Custom(_)));
+        assert_eq!(links[1].url, "https://example.com/rules");
+    }
+}
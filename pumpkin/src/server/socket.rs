@@ -0,0 +1,97 @@
+use std::{io, net::SocketAddr};
+
+use mio::net::{TcpListener, TcpStream};
+use pumpkin_config::SocketConfig;
+use socket2::{Domain, Protocol, SockRef, Socket, Type};
+
+/// Binds the listener of the Server with the configured options
+pub fn bind_listener(address: SocketAddr, config: &SocketConfig) -> io::Result<TcpListener> {
+    let socket = Socket::new(
+        Domain::for_address(address),
+        Type::STREAM,
+        Some(Protocol::TCP),
+    )?;
+    // Like mio does, so the Server can restart while old connections are still in TIME_WAIT
+    #[cfg(not(windows))]
+    socket.set_reuse_address(true)?;
+    // Accepted connections inherit the buffer sizes of the listener on most platforms
+    set_buffer_sizes(&socket, config);
+    socket.set_nonblocking(true)?;
+    socket.bind(&address.into())?;
+    socket.listen(config.backlog)?;
+    Ok(TcpListener::from_std(socket.into()))
+}
+
+/// Applies the configured options to an accepted connection, failures are only logged
+pub fn configure_connection(connection: &TcpStream, config: &SocketConfig) {
+    if let Err(e) = connection.set_nodelay(true) {
+        log::warn!("failed to set TCP_NODELAY {e}");
+    }
+    let socket = SockRef::from(connection);
+    if config.keepalive {
+        if let Err(e) = socket.set_keepalive(true) {
+            log::warn!("failed to set SO_KEEPALIVE {e}");
+        }
+    }
+    set_buffer_sizes(&socket, config);
+}
+
+fn set_buffer_sizes(socket: &Socket, config: &SocketConfig) {
+    if config.send_buffer_size > 0 {
+        if let Err(e) = socket.set_send_buffer_size(config.send_buffer_size) {
+            log::warn!("failed to set SO_SNDBUF {e}");
+        }
+    }
+    if config.receive_buffer_size > 0 {
+        if let Err(e) = socket.set_recv_buffer_size(config.receive_buffer_size) {
+            log::warn!("failed to set SO_RCVBUF {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{io, net::SocketAddr, thread, time::Duration};
+
+    use pumpkin_config::SocketConfig;
+    use socket2::SockRef;
+
+    use super::{bind_listener, configure_connection};
+
+    fn config() -> SocketConfig {
+        SocketConfig {
+            send_buffer_size: 64 * 1024,
+            receive_buffer_size: 128 * 1024,
+            keepalive: true,
+            backlog: 16,
+        }
+    }
+
+    #[test]
+    fn test_options_are_applied() {
+        let config = config();
+        let listener = bind_listener(SocketAddr::from(([127, 0, 0, 1], 0)), &config).unwrap();
+        let listener_socket = SockRef::from(&listener);
+        // The OS may round the sizes up, Linux even doubles them
+        assert!(listener_socket.send_buffer_size().unwrap() >= config.send_buffer_size);
+        assert!(listener_socket.recv_buffer_size().unwrap() >= config.receive_buffer_size);
+
+        let _client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let connection = loop {
+            match listener.accept() {
+                Ok((connection, _)) => break connection,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(10))
+                }
+                Err(e) => panic!("{e}"),
+            }
+        };
+        configure_connection(&connection, &config);
+
+        assert!(connection.nodelay().unwrap());
+        let socket = SockRef::from(&connection);
+        assert!(socket.keepalive().unwrap());
+        assert!(socket.send_buffer_size().unwrap() >= config.send_buffer_size);
+        assert!(socket.recv_buffer_size().unwrap() >= config.receive_buffer_size);
+    }
+}
@@ -0,0 +1,105 @@
+use std::time::Instant;
+
+use uuid::Uuid;
+
+/// A Player asking to be teleported to another Player with `/tpa`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeleportRequest {
+    pub requester: Uuid,
+    pub target: Uuid,
+    pub expires_at: Instant,
+}
+
+/// The pending `/tpa` requests, each Player can only have one outgoing request at a time
+#[derive(Default)]
+pub struct TeleportRequests {
+    requests: Vec<TeleportRequest>,
+}
+
+impl TeleportRequests {
+    /// Adds the request, replacing an older request of the same requester
+    pub fn request(&mut self, requester: Uuid, target: Uuid, expires_at: Instant) {
+        self.requests
+            .retain(|request| request.requester != requester);
+        self.requests.push(TeleportRequest {
+            requester,
+            target,
+            expires_at,
+        });
+    }
+
+    /// Removes and returns the newest request to the target which did not expire yet.
+    ///
+    /// Used for both accepting and denying
+    pub fn take(&mut self, target: &Uuid, now: Instant) -> Option<TeleportRequest> {
+        self.remove_expired(now);
+        let index = self
+            .requests
+            .iter()
+            .rposition(|request| &request.target == target)?;
+        Some(self.requests.remove(index))
+    }
+
+    /// Forgets about every request which expired
+    pub fn remove_expired(&mut self, now: Instant) {
+        self.requests.retain(|request| request.expires_at > now);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use uuid::Uuid;
+
+    use super::TeleportRequests;
+
+    #[test]
+    fn test_accept_newest_request() {
+        let mut requests = TeleportRequests::default();
+        let (alex, steve, target) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let now = Instant::now();
+        let expires_at = now + Duration::from_secs(60);
+        requests.request(alex, target, expires_at);
+        requests.request(steve, target, expires_at);
+
+        // Accepting teleports the newest requester first
+        assert_eq!(requests.take(&target, now).unwrap().requester, steve);
+        assert_eq!(requests.take(&target, now).unwrap().requester, alex);
+        assert!(requests.take(&target, now).is_none());
+    }
+
+    #[test]
+    fn test_deny_and_replace() {
+        let mut requests = TeleportRequests::default();
+        let (requester, first, second) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let now = Instant::now();
+        let expires_at = now + Duration::from_secs(60);
+        requests.request(requester, first, expires_at);
+        // A new request replaces the old one
+        requests.request(requester, second, expires_at);
+        assert!(requests.take(&first, now).is_none());
+
+        // Denying removes the request, so it can't be accepted anymore
+        assert_eq!(requests.take(&second, now).unwrap().requester, requester);
+        assert!(requests.take(&second, now).is_none());
+    }
+
+    #[test]
+    fn test_expiry() {
+        let mut requests = TeleportRequests::default();
+        let (requester, target) = (Uuid::new_v4(), Uuid::new_v4());
+        let now = Instant::now();
+        requests.request(requester, target, now + Duration::from_secs(60));
+
+        requests.remove_expired(now + Duration::from_secs(30));
+        assert_eq!(requests.requests.len(), 1);
+        requests.remove_expired(now + Duration::from_secs(60));
+        assert!(requests.requests.is_empty());
+
+        requests.request(requester, target, now + Duration::from_secs(60));
+        assert!(requests
+            .take(&target, now + Duration::from_secs(61))
+            .is_none());
+    }
+}
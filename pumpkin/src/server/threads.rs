@@ -0,0 +1,43 @@
+use pumpkin_config::ThreadsConfig;
+use rayon::ThreadPoolBuilder;
+use tokio::runtime;
+
+/// The runtime which handles the networking, ticking and commands
+pub fn runtime_builder(config: &ThreadsConfig) -> runtime::Builder {
+    let mut builder = runtime::Builder::new_multi_thread();
+    builder
+        .worker_threads(config.tokio_worker_threads)
+        .enable_all();
+    builder
+}
+
+/// The pool which reads and generates chunks, build it outside of the tokio runtime
+pub fn rayon_pool_builder(config: &ThreadsConfig) -> ThreadPoolBuilder {
+    ThreadPoolBuilder::new().num_threads(config.rayon_threads)
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::ThreadsConfig;
+
+    use super::{rayon_pool_builder, runtime_builder};
+
+    fn config() -> ThreadsConfig {
+        ThreadsConfig {
+            tokio_worker_threads: 3,
+            rayon_threads: 5,
+        }
+    }
+
+    #[test]
+    fn test_runtime_worker_threads() {
+        let runtime = runtime_builder(&config()).build().unwrap();
+        assert_eq!(runtime.metrics().num_workers(), 3);
+    }
+
+    #[test]
+    fn test_rayon_threads() {
+        let pool = rayon_pool_builder(&config()).build().unwrap();
+        assert_eq!(pool.current_num_threads(), 5);
+    }
+}
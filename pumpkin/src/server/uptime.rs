@@ -0,0 +1,72 @@
+use std::time::{Duration, Instant};
+
+use super::mutes::unix_now;
+
+/// When the Server started, measured with a monotonic clock so changing the system time doesn't affect it
+pub struct Uptime {
+    started: Instant,
+    /// The unix timestamp of the start, only used to show it
+    pub started_at: u64,
+}
+
+impl Uptime {
+    pub fn start() -> Self {
+        Self {
+            started: Instant::now(),
+            started_at: unix_now(),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+/// Formats seconds with every unit from the largest one down to seconds, e.g. `2h 0m 5s` or `45s`
+pub fn format_uptime(seconds: u64) -> String {
+    let units = [
+        (seconds / 86400, "d"),
+        (seconds % 86400 / 3600, "h"),
+        (seconds % 3600 / 60, "m"),
+        (seconds % 60, "s"),
+    ];
+    let parts: Vec<String> = units
+        .iter()
+        .enumerate()
+        // Seconds are always shown
+        .skip_while(|(i, (value, _))| *value == 0 && *i < units.len() - 1)
+        .map(|(_, (value, unit))| format!("{value}{unit}"))
+        .collect();
+    parts.join(" ")
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread, time::Duration};
+
+    use super::{format_uptime, Uptime};
+
+    #[test]
+    fn test_uptime_increases() {
+        let uptime = Uptime::start();
+        let mut last = uptime.elapsed();
+        for _ in 0..3 {
+            thread::sleep(Duration::from_millis(5));
+            let now = uptime.elapsed();
+            assert!(now > last);
+            last = now;
+        }
+        assert!(last >= Duration::from_millis(15));
+    }
+
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(0), "0s");
+        assert_eq!(format_uptime(45), "45s");
+        assert_eq!(format_uptime(65), "1m 5s");
+        assert_eq!(format_uptime(3600), "1h 0m 0s");
+        assert_eq!(format_uptime(2 * 3600 + 5), "2h 0m 5s");
+        assert_eq!(format_uptime(3 * 3600 + 25 * 60 + 7), "3h 25m 7s");
+        assert_eq!(format_uptime(86400 + 60), "1d 0h 1m 0s");
+    }
+}
@@ -0,0 +1,110 @@
+use std::{collections::BTreeMap, fs, io, path::Path};
+
+use crate::world::location::Location;
+
+const WARPS_FILE: &str = "warps.json";
+
+/// Named locations every Player can teleport to, stored in `warps.json`
+#[derive(Default, Debug, PartialEq)]
+pub struct WarpList {
+    warps: BTreeMap<String, Location>,
+}
+
+impl WarpList {
+    /// Loads the warps from `warps.json`, a missing file means there are no warps
+    pub fn load() -> Self {
+        Self::load_from(Path::new(WARPS_FILE)).unwrap_or_else(|err| {
+            log::error!("Couldn't load {}: {}", WARPS_FILE, err);
+            Self::default()
+        })
+    }
+
+    /// Saves the warps to `warps.json`
+    pub fn save(&self) -> io::Result<()> {
+        self.save_to(Path::new(WARPS_FILE))
+    }
+
+    fn load_from(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map(|warps| Self { warps })
+                .map_err(io::Error::other),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn save_to(&self, path: &Path) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(&self.warps).map_err(io::Error::other)?;
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(temp_path, path)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Location> {
+        self.warps.get(name)
+    }
+
+    /// Sets the warp, returning the location it had before
+    pub fn set(&mut self, name: &str, location: Location) -> Option<Location> {
+        self.warps.insert(name.to_string(), location)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Location> {
+        self.warps.remove(name)
+    }
+
+    /// The names of all warps in alphabetical order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.warps.keys().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use pumpkin_world::dimension::Dimension;
+
+    use super::WarpList;
+    use crate::world::location::Location;
+
+    fn location(x: f64) -> Location {
+        Location {
+            dimension: Dimension::OverWorld,
+            position: (x, 80.0, 0.0),
+            yaw: 90.0,
+            pitch: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_create_list_and_remove() {
+        let mut warps = WarpList::default();
+        assert!(warps.set("spawn", location(0.0)).is_none());
+        assert!(warps.set("arena", location(100.0)).is_none());
+        assert_eq!(warps.names().collect::<Vec<_>>(), vec!["arena", "spawn"]);
+
+        // The stored location is where Players get teleported to
+        assert_eq!(warps.get("arena"), Some(&location(100.0)));
+        assert_eq!(warps.set("arena", location(50.0)), Some(location(100.0)));
+
+        assert_eq!(warps.remove("arena"), Some(location(50.0)));
+        assert!(warps.get("arena").is_none());
+        assert!(warps.remove("arena").is_none());
+        assert_eq!(warps.names().collect::<Vec<_>>(), vec!["spawn"]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = env::temp_dir().join("pumpkin-warps-test.json");
+        let _ = fs::remove_file(&path);
+        assert_eq!(WarpList::load_from(&path).unwrap(), WarpList::default());
+
+        let mut warps = WarpList::default();
+        warps.set("spawn", location(0.5));
+        warps.save_to(&path).unwrap();
+        assert_eq!(WarpList::load_from(&path).unwrap(), warps);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,165 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const WHITELIST_FILE: &str = "whitelist.json";
+
+/// A Player who may join while the whitelist is enabled
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WhitelistEntry {
+    /// Unknown when the Player was added while offline, then the name is used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<Uuid>,
+    pub name: String,
+}
+
+/// The whitelisted Players, stored in `whitelist.json` just like vanilla does
+#[derive(Default, Debug, PartialEq)]
+pub struct Whitelist {
+    entries: Vec<WhitelistEntry>,
+}
+
+impl Whitelist {
+    /// Loads the whitelist from `whitelist.json`, a missing file means nobody is whitelisted
+    pub fn load() -> Self {
+        Self::load_from(Path::new(WHITELIST_FILE)).unwrap_or_else(|err| {
+            log::error!("Couldn't load {}: {}", WHITELIST_FILE, err);
+            Self::default()
+        })
+    }
+
+    /// Saves the whitelist to `whitelist.json`
+    pub fn save(&self) -> io::Result<()> {
+        self.save_to(Path::new(WHITELIST_FILE))
+    }
+
+    fn load_from(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map(|entries| Self { entries })
+                .map_err(io::Error::other),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn save_to(&self, path: &Path) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(&self.entries).map_err(io::Error::other)?;
+        let temp_path = path.with_extension("json.tmp");
+        fs::write(&temp_path, content)?;
+        fs::rename(temp_path, path)
+    }
+
+    pub fn contains(&self, uuid: &Uuid, name: &str) -> bool {
+        self.entries.iter().any(|entry| match entry.uuid {
+            Some(entry_uuid) => &entry_uuid == uuid,
+            None => entry.name.eq_ignore_ascii_case(name),
+        })
+    }
+
+    /// Adds the Player, returns false if it was already whitelisted
+    pub fn add(&mut self, uuid: Option<Uuid>, name: &str) -> bool {
+        let already_added = self.entries.iter().any(|entry| {
+            entry.name.eq_ignore_ascii_case(name) || (uuid.is_some() && entry.uuid == uuid)
+        });
+        if already_added {
+            return false;
+        }
+        self.entries.push(WhitelistEntry {
+            uuid,
+            name: name.to_string(),
+        });
+        true
+    }
+
+    /// Removes the Player by name, returns false if it wasn't whitelisted
+    pub fn remove(&mut self, name: &str) -> bool {
+        let len = self.entries.len();
+        self.entries
+            .retain(|entry| !entry.name.eq_ignore_ascii_case(name));
+        self.entries.len() != len
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(|entry| entry.name.as_str())
+    }
+
+    /// Returns the Players who have to leave when the whitelist gets enforced
+    pub fn not_whitelisted<'a, T>(
+        &self,
+        players: &'a [T],
+        profile: impl Fn(&T) -> (&Uuid, &str),
+    ) -> Vec<&'a T> {
+        players
+            .iter()
+            .filter(|player| {
+                let (uuid, name) = profile(player);
+                !self.contains(uuid, name)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use uuid::Uuid;
+
+    use super::Whitelist;
+
+    #[test]
+    fn test_add_and_remove() {
+        let mut whitelist = Whitelist::default();
+        let steve = Uuid::new_v4();
+        assert!(whitelist.add(Some(steve), "Steve"));
+        assert!(!whitelist.add(Some(steve), "Steve"));
+        assert!(whitelist.add(None, "Alex"));
+        assert!(!whitelist.add(None, "alex"));
+
+        assert!(whitelist.contains(&steve, "Steve"));
+        // Renamed Players stay whitelisted, others with the name don't get in
+        assert!(whitelist.contains(&steve, "Steve2"));
+        assert!(!whitelist.contains(&Uuid::new_v4(), "Steve"));
+        // Without a known UUID the name has to match
+        assert!(whitelist.contains(&Uuid::new_v4(), "ALEX"));
+
+        assert!(whitelist.remove("steve"));
+        assert!(!whitelist.remove("Steve"));
+        assert!(!whitelist.contains(&steve, "Steve"));
+        assert_eq!(whitelist.names().collect::<Vec<_>>(), vec!["Alex"]);
+    }
+
+    #[test]
+    fn test_not_whitelisted_get_kicked() {
+        let mut whitelist = Whitelist::default();
+        let steve = (Uuid::new_v4(), "Steve".to_string());
+        let alex = (Uuid::new_v4(), "Alex".to_string());
+        whitelist.add(Some(steve.0), &steve.1);
+
+        let online = [steve.clone(), alex.clone()];
+        let kicked = whitelist.not_whitelisted(&online, |(uuid, name)| (uuid, name.as_str()));
+        assert_eq!(kicked, vec![&alex]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = env::temp_dir().join("pumpkin-whitelist-test.json");
+        let _ = fs::remove_file(&path);
+        assert_eq!(Whitelist::load_from(&path).unwrap(), Whitelist::default());
+
+        let mut whitelist = Whitelist::default();
+        whitelist.add(Some(Uuid::new_v4()), "Steve");
+        whitelist.add(None, "Alex");
+        whitelist.save_to(&path).unwrap();
+        assert_eq!(Whitelist::load_from(&path).unwrap(), whitelist);
+
+        // Removing is persisted too
+        whitelist.remove("Steve");
+        whitelist.save_to(&path).unwrap();
+        let loaded = Whitelist::load_from(&path).unwrap();
+        assert_eq!(loaded.names().collect::<Vec<_>>(), vec!["Alex"]);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,156 @@
+//! Builds the Worlds, Clients and Players tests need, without starting a Server
+
+use std::{
+    env,
+    io::Read,
+    net::{IpAddr, SocketAddr, TcpListener, TcpStream},
+    path::Path,
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use bytes::BytesMut;
+use mio::Token;
+use pumpkin_config::ADVANCED_CONFIG;
+use pumpkin_core::GameMode;
+use pumpkin_entity::EntityId;
+use pumpkin_protocol::{bytebuf::ByteBuffer, ConnectionState};
+use pumpkin_world::{dimension::Dimension, level::Level};
+use socket2::{Domain, Socket, Type};
+use uuid::Uuid;
+
+use crate::{
+    client::{authentication::GameProfile, Client},
+    entity::player::Player,
+    server::socket,
+    world::World,
+};
+
+/// An empty Overworld which is saved into `folder`, the folder is only created once something is saved
+pub fn world_in(folder: &Path) -> Arc<World> {
+    Arc::new(World::load(
+        Dimension::OverWorld,
+        Level::from_root_folder(folder.to_path_buf()),
+    ))
+}
+
+/// An empty Overworld which is saved into a new folder in the temp dir, `name` tells the folders of the tests apart
+pub fn world(name: &str) -> Arc<World> {
+    world_in(&env::temp_dir().join(format!("pumpkin-{}-{}", name, Uuid::new_v4())))
+}
+
+/// A Client whose connection was never accepted, for tests which don't read what it sends
+pub fn client(token: Token, state: ConnectionState) -> Client {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let connection = TcpStream::connect(address).unwrap();
+    new_client(token, state, connection)
+}
+
+/// A Client and the other end of its connection, to read the packets sent to it
+pub fn connected_client(token: Token, state: ConnectionState) -> (Client, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let connection = TcpStream::connect(address).unwrap();
+    let (remote, _) = listener.accept().unwrap();
+    (new_client(token, state, connection), remote)
+}
+
+/// A Client accepted like the Server does, connected from `from` to a listener on 127.0.0.1.
+///
+/// Linux routes all of 127.0.0.0/8 to loopback, so each Client can get its own IP
+pub fn accepted_client(token: Token, from: IpAddr) -> (Client, TcpStream) {
+    let listener = socket::bind_listener(
+        SocketAddr::from(([127, 0, 0, 1], 0)),
+        &ADVANCED_CONFIG.socket,
+    )
+    .unwrap();
+    let socket = Socket::new(Domain::IPV4, Type::STREAM, None).unwrap();
+    socket.bind(&SocketAddr::new(from, 0).into()).unwrap();
+    socket
+        .connect(&listener.local_addr().unwrap().into())
+        .unwrap();
+    let (keep_alive, _) = tokio::sync::mpsc::channel(1);
+    let keep_alive = Arc::new(keep_alive);
+    let client = loop {
+        match Client::accept(&listener, || token, keep_alive.clone()).unwrap() {
+            Some(client) => break client,
+            None => thread::sleep(Duration::from_millis(10)),
+        }
+    };
+    (client, socket.into())
+}
+
+fn new_client(token: Token, state: ConnectionState, connection: TcpStream) -> Client {
+    let address = connection.peer_addr().unwrap();
+    let (keep_alive, _) = tokio::sync::mpsc::channel(1);
+    let client = Client::new(
+        token,
+        mio::net::TcpStream::from_std(connection),
+        address,
+        Arc::new(keep_alive),
+    );
+    client.connection_state.store(state);
+    client
+}
+
+/// Logs the Client in as `name`, a Player made from it gets this profile
+pub fn set_profile(client: &Client, name: &str, id: Uuid) {
+    *client.gameprofile.lock() = Some(GameProfile {
+        id,
+        name: name.to_string(),
+        properties: Vec::new(),
+        profile_actions: None,
+    });
+}
+
+/// A survival Player named `name` with a new uuid, they are not added to the World
+pub fn player_of(
+    world: &Arc<World>,
+    client: impl Into<Arc<Client>>,
+    entity_id: EntityId,
+    name: &str,
+) -> Arc<Player> {
+    let client = client.into();
+    set_profile(&client, name, Uuid::new_v4());
+    Arc::new(Player::new(
+        client,
+        world.clone(),
+        entity_id,
+        GameMode::Survival,
+    ))
+}
+
+/// Steve with the token 1 and the entity id 2, who is not added to the World yet
+pub fn new_player(world: &Arc<World>) -> Arc<Player> {
+    player_of(world, client(Token(1), ConnectionState::Play), 2, "Steve")
+}
+
+/// Steve, added to the World, see `new_player`
+pub fn joined_player(world: &Arc<World>) -> Arc<Player> {
+    let player = new_player(world);
+    world.add_player(Token(1), player.clone());
+    player
+}
+
+/// Reads the next packet the Client sent, returning its id and the rest of it
+pub fn read_packet(remote: &mut TcpStream) -> (i32, ByteBuffer) {
+    remote
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let mut length = 0;
+    for i in 0..5 {
+        let mut byte = [0];
+        remote.read_exact(&mut byte).unwrap();
+        length |= i32::from(byte[0] & 0x7F) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            break;
+        }
+    }
+    let mut bytes = vec![0; length as usize];
+    remote.read_exact(&mut bytes).unwrap();
+    let mut bytebuf = ByteBuffer::new(BytesMut::from(&bytes[..]));
+    let id = bytebuf.get_var_int().unwrap().0;
+    (id, bytebuf)
+}
@@ -0,0 +1,47 @@
+use pumpkin_core::Difficulty;
+
+/// The difficulty of a World and whether it may be changed by players
+pub struct DifficultyState {
+    pub difficulty: Difficulty,
+    /// A locked difficulty can only be changed by forcing it, e.g. using `/difficulty`
+    pub locked: bool,
+}
+
+impl DifficultyState {
+    pub const fn new(difficulty: Difficulty) -> Self {
+        Self {
+            difficulty,
+            locked: false,
+        }
+    }
+
+    /// Changes the difficulty, returns false when it is locked and the change was not forced
+    pub fn set(&mut self, difficulty: Difficulty, forced: bool) -> bool {
+        if self.locked && !forced {
+            return false;
+        }
+        self.difficulty = difficulty;
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::Difficulty;
+
+    use super::DifficultyState;
+
+    #[test]
+    fn test_locked_difficulty() {
+        let mut state = DifficultyState::new(Difficulty::Normal);
+        assert!(state.set(Difficulty::Easy, false));
+        assert_eq!(state.difficulty, Difficulty::Easy);
+
+        state.locked = true;
+        assert!(!state.set(Difficulty::Hard, false));
+        assert_eq!(state.difficulty, Difficulty::Easy);
+        // commands always force the change
+        assert!(state.set(Difficulty::Hard, true));
+        assert_eq!(state.difficulty, Difficulty::Hard);
+    }
+}
@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+
+use pumpkin_core::math::vector2::Vector2;
+
+/// How many entities each chunk holds, to refuse spawning once a chunk is full.
+///
+/// Players don't count, they can always join and move around
+#[derive(Default)]
+pub struct ChunkEntityCounts {
+    counts: HashMap<Vector2<i32>, u32>,
+}
+
+impl ChunkEntityCounts {
+    /// Counts one more entity in the chunk, unless it already holds `max` entities.
+    ///
+    /// A `max` of 0 means there is no limit
+    pub fn try_add(&mut self, chunk: Vector2<i32>, max: u32) -> bool {
+        let count = self.counts.entry(chunk).or_default();
+        if max != 0 && *count >= max {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+
+    /// Frees the place of an entity which left the chunk
+    pub fn remove(&mut self, chunk: Vector2<i32>) {
+        if let Some(count) = self.counts.get_mut(&chunk) {
+            *count -= 1;
+            if *count == 0 {
+                self.counts.remove(&chunk);
+            }
+        }
+    }
+
+    pub fn get(&self, chunk: Vector2<i32>) -> u32 {
+        self.counts.get(&chunk).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::vector2::Vector2;
+
+    use super::ChunkEntityCounts;
+
+    #[test]
+    fn test_full_chunk() {
+        let mut counts = ChunkEntityCounts::default();
+        let chunk = Vector2::new(0, 0);
+        for _ in 0..3 {
+            assert!(counts.try_add(chunk, 3));
+        }
+        assert!(!counts.try_add(chunk, 3));
+        assert_eq!(counts.get(chunk), 3);
+        // Other chunks have their own limit
+        assert!(counts.try_add(Vector2::new(1, 0), 3));
+
+        counts.remove(chunk);
+        assert!(counts.try_add(chunk, 3));
+        assert!(!counts.try_add(chunk, 3));
+    }
+
+    #[test]
+    fn test_no_limit() {
+        let mut counts = ChunkEntityCounts::default();
+        let chunk = Vector2::new(-4, 7);
+        for _ in 0..1000 {
+            assert!(counts.try_add(chunk, 0));
+        }
+        for _ in 0..1000 {
+            counts.remove(chunk);
+        }
+        assert_eq!(counts.get(chunk), 0);
+        assert!(counts.counts.is_empty());
+    }
+}
@@ -0,0 +1,340 @@
+use std::sync::atomic::Ordering;
+
+use pumpkin_config::{ADVANCED_CONFIG, BASIC_CONFIG};
+use pumpkin_core::math::{vector2::Vector2, vector3::Vector3};
+use pumpkin_entity::entity_type::EntityType;
+use pumpkin_protocol::{
+    client::play::{
+        CRemoveEntities, CSetPassengers, CSpawnEntity, CTeleportEntitiy, CUpdateEntityPos,
+        CUpdateEntityPosRot, CUpdateEntityRot,
+    },
+    VarInt,
+};
+
+use crate::entity::{player::Player, Entity};
+
+use super::{player_chunker, World};
+
+/// The tracking range is capped by the view distance, entities in unloaded chunks can't be seen
+fn tracking_range(configured: i32, view_distance: i32) -> i32 {
+    configured.min(view_distance)
+}
+
+/// Whether an entity in the `target` chunk is in range of a viewer in the `viewer` chunk
+fn is_in_tracking_range(viewer: Vector2<i32>, target: Vector2<i32>, range: i32) -> bool {
+    (viewer.x - target.x).abs() <= range && (viewer.z - target.z).abs() <= range
+}
+
+/// Spawns the target for the viewer when it came in range and removes it when it left.
+///
+/// Vanished targets are never in range
+pub fn update_tracking(viewer: &Player, target: &Player) {
+    track(viewer, &target.entity, !target.is_vanished());
+}
+
+/// Like `update_tracking`, for entities which aren't Players
+pub fn update_entity_tracking(viewer: &Player, entity: &Entity) {
+    track(viewer, entity, true);
+}
+
+fn track(viewer: &Player, entity: &Entity, visible: bool) {
+    let mut configured = BASIC_CONFIG.entity_tracking_range();
+    if entity.entity_type == EntityType::ExperienceOrb {
+        configured = configured.min(ADVANCED_CONFIG.experience_orbs.tracking_range);
+    }
+    let range = tracking_range(
+        configured.into(),
+        player_chunker::get_view_distance(viewer).into(),
+    );
+    let in_range = visible
+        && is_in_tracking_range(
+            viewer.entity.chunk_pos.load(),
+            entity.chunk_pos.load(),
+            range,
+        );
+    let entity_id = entity.entity_id;
+    let mut tracked = viewer.tracked_entities.lock();
+    if in_range && tracked.insert(entity_id) {
+        if let Some(orb) = entity.world.experience_orbs.lock().get(&entity_id) {
+            viewer.client.send_packet(&orb.spawn_packet());
+            return;
+        }
+        let (pos, velocity) = (entity.pos.load(), entity.velocity.load());
+        viewer.client.send_packet(&CSpawnEntity::new(
+            entity_id.into(),
+            entity.uuid,
+            (entity.entity_type as i32).into(),
+            pos.x,
+            pos.y,
+            pos.z,
+            entity.pitch.load(),
+            entity.yaw.load(),
+            entity.head_yaw.load(),
+            0.into(),
+            velocity.x as f32,
+            velocity.y as f32,
+            velocity.z as f32,
+        ));
+        if let Some(item) = entity.world.items.lock().get(&entity_id) {
+            viewer.client.send_packet(&item.metadata_packet());
+        }
+        // The viewer also has to know who rides it
+        let passengers: Vec<VarInt> = entity
+            .passengers
+            .lock()
+            .iter()
+            .map(|id| VarInt(*id))
+            .collect();
+        if !passengers.is_empty() {
+            viewer
+                .client
+                .send_packet(&CSetPassengers::new(entity_id.into(), &passengers));
+        }
+    } else if !in_range && tracked.remove(&entity_id) {
+        viewer
+            .client
+            .send_packet(&CRemoveEntities::new(&[entity_id.into()]));
+    }
+}
+
+/// Updates which entities the Player sees and which Players see it, after it joined or moved
+pub fn update_player(world: &World, player: &Player) {
+    let token = player.client.token;
+    for (_, other) in world
+        .current_players
+        .lock()
+        .iter()
+        .filter(|(other_token, _)| **other_token != token)
+    {
+        update_tracking(player, other);
+        update_tracking(other, player);
+    }
+    for entity in world.entities.lock().values() {
+        update_entity_tracking(player, entity);
+    }
+}
+
+/// After this many ticks viewers get the exact position again, relative moves can add up rounding errors
+const FULL_SYNC_INTERVAL: u32 = 400;
+
+/// What viewers have to be told about an entity which moved
+#[derive(Debug, PartialEq)]
+pub enum MovementUpdate {
+    /// Moved by less than 8 blocks, in 1/4096 blocks
+    Move(Vector3<i16>),
+    Rotate,
+    MoveRotate(Vector3<i16>),
+    /// Moved too far for a relative move
+    Teleport,
+}
+
+/// Collects the moves of an entity, so viewers get at most one update per `entity_update_interval`
+#[derive(Default)]
+pub struct MovementBatch {
+    /// The position viewers last got, in 1/4096 blocks
+    sent_position: Option<Vector3<i64>>,
+    /// The yaw and pitch viewers last got
+    sent_rotation: (u8, u8),
+    /// Ticks since the last update was sent
+    ticks: u32,
+    /// Ticks since the last teleport was sent
+    ticks_since_sync: u32,
+}
+
+impl MovementBatch {
+    /// Advances the batch by one tick.
+    ///
+    /// Returns the one update which covers all moves since the last one, once the interval passed
+    pub fn tick(
+        &mut self,
+        interval: u32,
+        position: Vector3<f64>,
+        rotation: (u8, u8),
+    ) -> Option<MovementUpdate> {
+        self.ticks += 1;
+        self.ticks_since_sync += 1;
+        if self.ticks < interval {
+            return None;
+        }
+        self.ticks = 0;
+
+        let encoded = Vector3::new(
+            encode_coordinate(position.x),
+            encode_coordinate(position.y),
+            encode_coordinate(position.z),
+        );
+        let rotated = rotation != self.sent_rotation;
+        self.sent_rotation = rotation;
+        let Some(sent) = self.sent_position.replace(encoded) else {
+            self.ticks_since_sync = 0;
+            return Some(MovementUpdate::Teleport);
+        };
+        let delta = (
+            i16::try_from(encoded.x - sent.x),
+            i16::try_from(encoded.y - sent.y),
+            i16::try_from(encoded.z - sent.z),
+        );
+        let (Ok(x), Ok(y), Ok(z)) = delta else {
+            self.ticks_since_sync = 0;
+            return Some(MovementUpdate::Teleport);
+        };
+        let moved = encoded != sent;
+        if moved && self.ticks_since_sync >= FULL_SYNC_INTERVAL {
+            self.ticks_since_sync = 0;
+            return Some(MovementUpdate::Teleport);
+        }
+        let delta = Vector3 { x, y, z };
+        match (moved, rotated) {
+            (true, true) => Some(MovementUpdate::MoveRotate(delta)),
+            (true, false) => Some(MovementUpdate::Move(delta)),
+            (false, true) => Some(MovementUpdate::Rotate),
+            (false, false) => None,
+        }
+    }
+}
+
+/// Positions in relative moves are sent in 1/4096 blocks
+fn encode_coordinate(coordinate: f64) -> i64 {
+    (coordinate * 4096.0).round() as i64
+}
+
+/// Yaw and pitch are sent in 1/256 of a full turn
+pub fn encode_angle(degrees: f32) -> u8 {
+    (degrees * 256.0 / 360.0).rem_euclid(256.0) as u8
+}
+
+/// Sends the moves the Player made since the last update to everyone else in the World.
+///
+/// The head rotation is sent right away instead, see `handle_rotation`
+pub fn broadcast_movement(world: &World, player: &Player) {
+    // Nobody can see it, `World::set_vanished` resyncs the position once it shows up again
+    if player.is_vanished() {
+        return;
+    }
+    let entity = &player.entity;
+    let (yaw, pitch) = (
+        encode_angle(entity.yaw.load()),
+        encode_angle(entity.pitch.load()),
+    );
+    let update = player.movement_batch.lock().tick(
+        BASIC_CONFIG.entity_update_interval.into(),
+        entity.pos.load(),
+        (yaw, pitch),
+    );
+    let Some(update) = update else {
+        return;
+    };
+    let entity_id = entity.entity_id.into();
+    let on_ground = entity.on_ground.load(Ordering::Relaxed);
+    let except = [player.client.token];
+    match update {
+        MovementUpdate::Move(delta) => world.broadcast_packet_expect(
+            &except,
+            &CUpdateEntityPos::new(entity_id, delta.x, delta.y, delta.z, on_ground),
+        ),
+        MovementUpdate::Rotate => world.broadcast_packet_expect(
+            &except,
+            &CUpdateEntityRot::new(entity_id, yaw, pitch, on_ground),
+        ),
+        MovementUpdate::MoveRotate(delta) => world.broadcast_packet_expect(
+            &except,
+            &CUpdateEntityPosRot::new(entity_id, delta.x, delta.y, delta.z, yaw, pitch, on_ground),
+        ),
+        MovementUpdate::Teleport => {
+            let pos = entity.pos.load();
+            world.broadcast_packet_expect(
+                &except,
+                &CTeleportEntitiy::new(entity_id, pos.x, pos.y, pos.z, yaw, pitch, on_ground),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_core::math::{vector2::Vector2, vector3::Vector3};
+
+    use super::{is_in_tracking_range, tracking_range, MovementBatch, MovementUpdate};
+
+    /// A batch which already sent the entity standing at the origin
+    fn synced_batch() -> MovementBatch {
+        let mut batch = MovementBatch::default();
+        assert_eq!(
+            batch.tick(1, Vector3::new(0.0, 0.0, 0.0), (0, 0)),
+            Some(MovementUpdate::Teleport)
+        );
+        batch
+    }
+
+    #[test]
+    fn test_moves_coalesce() {
+        let mut batch = synced_batch();
+        // Three moves within an interval of 3 ticks
+        assert_eq!(batch.tick(3, Vector3::new(0.5, 0.0, 0.0), (0, 0)), None);
+        assert_eq!(batch.tick(3, Vector3::new(1.0, 0.0, 0.25), (0, 0)), None);
+        assert_eq!(
+            batch.tick(3, Vector3::new(1.5, 1.0, 0.5), (0, 0)),
+            Some(MovementUpdate::Move(Vector3 {
+                x: 6144,
+                y: 4096,
+                z: 2048
+            }))
+        );
+        // Standing still sends nothing
+        for _ in 0..6 {
+            assert_eq!(batch.tick(3, Vector3::new(1.5, 1.0, 0.5), (0, 0)), None);
+        }
+    }
+
+    #[test]
+    fn test_rotation_and_teleport() {
+        let mut batch = synced_batch();
+        assert_eq!(
+            batch.tick(1, Vector3::new(0.0, 0.0, 0.0), (64, 0)),
+            Some(MovementUpdate::Rotate)
+        );
+        assert_eq!(
+            batch.tick(1, Vector3::new(0.0, -1.0, 0.0), (0, 0)),
+            Some(MovementUpdate::MoveRotate(Vector3 {
+                x: 0,
+                y: -4096,
+                z: 0
+            }))
+        );
+        // Relative moves only reach 8 blocks
+        assert_eq!(
+            batch.tick(1, Vector3::new(8.0, -1.0, 0.0), (0, 0)),
+            Some(MovementUpdate::Teleport)
+        );
+        assert_eq!(
+            batch.tick(1, Vector3::new(7.0, -1.0, 0.0), (0, 0)),
+            Some(MovementUpdate::Move(Vector3 {
+                x: -4096,
+                y: 0,
+                z: 0
+            }))
+        );
+    }
+
+    #[test]
+    fn test_tracking_range() {
+        assert_eq!(tracking_range(10, 10), 10);
+        assert_eq!(tracking_range(4, 10), 4);
+        assert_eq!(tracking_range(10, 6), 6);
+    }
+
+    #[test]
+    fn test_beyond_tracking_range_not_spawned() {
+        let viewer = Vector2::new(0, 0);
+        let view_distance = 10;
+        let range = tracking_range(4, view_distance);
+
+        assert!(is_in_tracking_range(viewer, Vector2::new(0, 0), range));
+        assert!(is_in_tracking_range(viewer, Vector2::new(4, -4), range));
+        // The chunk is loaded for the viewer, but the entity is still too far away
+        let far = Vector2::new(6, 0);
+        assert!((far.x - viewer.x).abs() <= view_distance);
+        assert!(!is_in_tracking_range(viewer, far, range));
+        assert!(!is_in_tracking_range(viewer, Vector2::new(-2, 5), range));
+    }
+}
@@ -0,0 +1,200 @@
+use std::str::FromStr;
+
+/// Per-world rules which change how the game behaves, like vanilla's `/gamerule`
+pub struct GameRules {
+    /// Whether the weather changes by itself over time
+    pub do_weather_cycle: bool,
+    /// Whether the time of day advances
+    pub do_daylight_cycle: bool,
+    /// Whether mobs spawn by themselves around Players
+    pub do_mob_spawning: bool,
+    /// Overrides the `pvp.enabled` config for this world when set
+    pub pvp: Option<bool>,
+    /// Overrides the `sleeping.players_sleeping_percentage` config for this world when set, from 0 to 100
+    pub players_sleeping_percentage: Option<u8>,
+    /// Overrides the `immediate_respawn` config for this world when set
+    pub do_immediate_respawn: Option<bool>,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        Self {
+            do_weather_cycle: true,
+            do_daylight_cycle: true,
+            do_mob_spawning: true,
+            pvp: None,
+            players_sleeping_percentage: None,
+            do_immediate_respawn: None,
+        }
+    }
+}
+
+/// A rule which `/gamerule` can change, named like in vanilla
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameRule {
+    DoDaylightCycle,
+    DoImmediateRespawn,
+    DoMobSpawning,
+    DoWeatherCycle,
+    PlayersSleepingPercentage,
+    Pvp,
+}
+
+impl GameRule {
+    pub const ALL: [Self; 6] = [
+        Self::DoDaylightCycle,
+        Self::DoImmediateRespawn,
+        Self::DoMobSpawning,
+        Self::DoWeatherCycle,
+        Self::PlayersSleepingPercentage,
+        Self::Pvp,
+    ];
+
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::DoDaylightCycle => "doDaylightCycle",
+            Self::DoImmediateRespawn => "doImmediateRespawn",
+            Self::DoMobSpawning => "doMobSpawning",
+            Self::DoWeatherCycle => "doWeatherCycle",
+            Self::PlayersSleepingPercentage => "playersSleepingPercentage",
+            Self::Pvp => "pvp",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|rule| rule.name() == name)
+    }
+}
+
+impl GameRules {
+    /// The value of the rule like `/gamerule` shows it, `default` if the world uses the config
+    pub fn get(&self, rule: GameRule) -> String {
+        match rule {
+            GameRule::DoDaylightCycle => self.do_daylight_cycle.to_string(),
+            GameRule::DoImmediateRespawn => override_to_string(self.do_immediate_respawn),
+            GameRule::DoMobSpawning => self.do_mob_spawning.to_string(),
+            GameRule::DoWeatherCycle => self.do_weather_cycle.to_string(),
+            GameRule::PlayersSleepingPercentage => {
+                override_to_string(self.players_sleeping_percentage)
+            }
+            GameRule::Pvp => override_to_string(self.pvp),
+        }
+    }
+
+    /// Changes the rule to a value typed into `/gamerule`, returns false if the value doesn't fit the rule
+    pub fn set(&mut self, rule: GameRule, value: &str) -> bool {
+        match rule {
+            GameRule::DoDaylightCycle => value
+                .parse()
+                .ok()
+                .map(|do_daylight_cycle| self.do_daylight_cycle = do_daylight_cycle),
+            GameRule::DoImmediateRespawn => parse_override(value)
+                .map(|immediate_respawn| self.do_immediate_respawn = immediate_respawn),
+            GameRule::DoMobSpawning => value
+                .parse()
+                .ok()
+                .map(|do_mob_spawning| self.do_mob_spawning = do_mob_spawning),
+            GameRule::DoWeatherCycle => value
+                .parse()
+                .ok()
+                .map(|do_weather_cycle| self.do_weather_cycle = do_weather_cycle),
+            GameRule::PlayersSleepingPercentage => parse_override(value)
+                .filter(|percentage| percentage.map_or(true, |percentage| percentage <= 100))
+                .map(|percentage| self.players_sleeping_percentage = percentage),
+            GameRule::Pvp => parse_override(value).map(|pvp| self.pvp = pvp),
+        }
+        .is_some()
+    }
+}
+
+/// `default` clears the override, so the config is used again
+fn parse_override<T: FromStr>(value: &str) -> Option<Option<T>> {
+    match value {
+        "default" => Some(None),
+        value => value.parse().ok().map(Some),
+    }
+}
+
+fn override_to_string<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "default".to_string(), |value| value.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GameRule, GameRules};
+
+    #[test]
+    fn test_set_pvp() {
+        let mut game_rules = GameRules::default();
+        assert_eq!(game_rules.get(GameRule::Pvp), "default");
+        assert!(game_rules.set(GameRule::Pvp, "false"));
+        assert_eq!(game_rules.pvp, Some(false));
+        assert_eq!(game_rules.get(GameRule::Pvp), "false");
+        assert!(!game_rules.set(GameRule::Pvp, "maybe"));
+        assert_eq!(game_rules.pvp, Some(false));
+        assert!(game_rules.set(GameRule::Pvp, "default"));
+        assert_eq!(game_rules.pvp, None);
+    }
+
+    #[test]
+    fn test_set_do_weather_cycle() {
+        let mut game_rules = GameRules::default();
+        assert_eq!(game_rules.get(GameRule::DoWeatherCycle), "true");
+        assert!(game_rules.set(GameRule::DoWeatherCycle, "false"));
+        assert!(!game_rules.do_weather_cycle);
+        // The weather cycle has no config to fall back to
+        assert!(!game_rules.set(GameRule::DoWeatherCycle, "default"));
+        assert!(!game_rules.do_weather_cycle);
+    }
+
+    #[test]
+    fn test_set_daylight_and_mob_spawning() {
+        let mut game_rules = GameRules::default();
+        assert!(game_rules.set(GameRule::DoDaylightCycle, "false"));
+        assert!(!game_rules.do_daylight_cycle);
+        assert_eq!(game_rules.get(GameRule::DoDaylightCycle), "false");
+        assert!(game_rules.set(GameRule::DoMobSpawning, "false"));
+        assert!(!game_rules.do_mob_spawning);
+        assert!(!game_rules.set(GameRule::DoMobSpawning, "0"));
+        assert!(!game_rules.do_mob_spawning);
+    }
+
+    #[test]
+    fn test_set_players_sleeping_percentage() {
+        let mut game_rules = GameRules::default();
+        assert_eq!(
+            game_rules.get(GameRule::PlayersSleepingPercentage),
+            "default"
+        );
+        assert!(game_rules.set(GameRule::PlayersSleepingPercentage, "50"));
+        assert_eq!(game_rules.players_sleeping_percentage, Some(50));
+        assert!(game_rules.set(GameRule::PlayersSleepingPercentage, "100"));
+        assert!(!game_rules.set(GameRule::PlayersSleepingPercentage, "101"));
+        assert!(!game_rules.set(GameRule::PlayersSleepingPercentage, "-1"));
+        assert_eq!(game_rules.players_sleeping_percentage, Some(100));
+        assert!(game_rules.set(GameRule::PlayersSleepingPercentage, "default"));
+        assert_eq!(game_rules.players_sleeping_percentage, None);
+    }
+
+    #[test]
+    fn test_rule_names() {
+        assert_eq!(GameRule::from_name("pvp"), Some(GameRule::Pvp));
+        assert_eq!(
+            GameRule::from_name("doWeatherCycle"),
+            Some(GameRule::DoWeatherCycle)
+        );
+        assert_eq!(
+            GameRule::from_name("doImmediateRespawn"),
+            Some(GameRule::DoImmediateRespawn)
+        );
+        assert_eq!(
+            GameRule::from_name("doMobSpawning"),
+            Some(GameRule::DoMobSpawning)
+        );
+        assert_eq!(
+            GameRule::from_name("doDaylightCycle"),
+            Some(GameRule::DoDaylightCycle)
+        );
+        assert_eq!(GameRule::from_name("doSomething"), None);
+    }
+}
@@ -0,0 +1,60 @@
+use pumpkin_config::HardcoreDeathAction;
+
+/// The reason Players banned for dying in hardcore mode are given
+pub const BAN_REASON: &str = "Died in hardcore mode";
+
+/// What happens to a Player after they died
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeathOutcome {
+    /// The Player may respawn normally
+    Respawn,
+    /// The Player is locked into spectator mode
+    Spectate,
+    /// The Player is locked into spectator mode and banned instead of respawning
+    Ban,
+}
+
+impl DeathOutcome {
+    pub const fn new(hardcore: bool, action: HardcoreDeathAction) -> Self {
+        match (hardcore, action) {
+            (false, _) => Self::Respawn,
+            (true, HardcoreDeathAction::Spectator) => Self::Spectate,
+            (true, HardcoreDeathAction::Ban) => Self::Ban,
+        }
+    }
+
+    /// Whether the Player gets back into their old gamemode
+    pub const fn can_respawn(&self) -> bool {
+        matches!(self, Self::Respawn)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::HardcoreDeathAction;
+
+    use super::DeathOutcome;
+
+    #[test]
+    fn test_normal_death() {
+        for action in [HardcoreDeathAction::Spectator, HardcoreDeathAction::Ban] {
+            let outcome = DeathOutcome::new(false, action);
+            assert_eq!(outcome, DeathOutcome::Respawn);
+            assert!(outcome.can_respawn());
+        }
+    }
+
+    #[test]
+    fn test_hardcore_spectator() {
+        let outcome = DeathOutcome::new(true, HardcoreDeathAction::Spectator);
+        assert_eq!(outcome, DeathOutcome::Spectate);
+        assert!(!outcome.can_respawn());
+    }
+
+    #[test]
+    fn test_hardcore_ban() {
+        let outcome = DeathOutcome::new(true, HardcoreDeathAction::Ban);
+        assert_eq!(outcome, DeathOutcome::Ban);
+        assert!(!outcome.can_respawn());
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::server::Server;
+
+use super::location::Location;
+
+const HOMES_FOLDER: &str = "homes";
+
+/// The name of a home when the Player does not give one
+pub const DEFAULT_HOME: &str = "home";
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct TooManyHomes;
+
+/// The named homes of a Player
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct Homes {
+    homes: BTreeMap<String, Location>,
+}
+
+impl Homes {
+    /// Homes are stored in the folder of the main world, so they work across dimensions
+    pub fn folder(server: &Server) -> PathBuf {
+        server.worlds[0].level.root_folder().to_path_buf()
+    }
+
+    /// Loads the homes from `homes/<uuid>.json` in the world folder, a Player without the file has no homes
+    pub fn load(world_folder: &Path, uuid: &Uuid) -> io::Result<Self> {
+        let path = world_folder
+            .join(HOMES_FOLDER)
+            .join(format!("{}.json", uuid));
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).map_err(io::Error::other),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Saves the homes, replacing the old file at once
+    pub fn save(&self, world_folder: &Path, uuid: &Uuid) -> io::Result<()> {
+        let folder = world_folder.join(HOMES_FOLDER);
+        fs::create_dir_all(&folder)?;
+        let content = serde_json::to_string(self).map_err(io::Error::other)?;
+        let temp_path = folder.join(format!("{}.json.tmp", uuid));
+        fs::write(&temp_path, content)?;
+        fs::rename(temp_path, folder.join(format!("{}.json", uuid)))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Location> {
+        self.homes.get(name)
+    }
+
+    /// Sets the home, returning the home it replaced.
+    ///
+    /// Replacing a home is always allowed, new homes only if there are less than `max_homes`
+    pub fn set(
+        &mut self,
+        name: &str,
+        home: Location,
+        max_homes: usize,
+    ) -> Result<Option<Location>, TooManyHomes> {
+        if !self.homes.contains_key(name) && self.homes.len() >= max_homes {
+            return Err(TooManyHomes);
+        }
+        Ok(self.homes.insert(name.to_string(), home))
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Location> {
+        self.homes.remove(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.homes.keys().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use pumpkin_world::dimension::Dimension;
+    use uuid::Uuid;
+
+    use super::{Homes, TooManyHomes, DEFAULT_HOME};
+    use crate::world::location::Location;
+
+    fn home(x: f64) -> Location {
+        Location {
+            dimension: Dimension::OverWorld,
+            position: (x, 64.0, 0.0),
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_set_overwrite_and_delete() {
+        let mut homes = Homes::default();
+        assert_eq!(homes.set(DEFAULT_HOME, home(1.0), 3), Ok(None));
+        assert_eq!(homes.get(DEFAULT_HOME), Some(&home(1.0)));
+
+        assert_eq!(homes.set(DEFAULT_HOME, home(2.0), 3), Ok(Some(home(1.0))));
+        assert_eq!(homes.get(DEFAULT_HOME).unwrap().position.0, 2.0);
+
+        assert_eq!(homes.remove(DEFAULT_HOME), Some(home(2.0)));
+        assert!(homes.get(DEFAULT_HOME).is_none());
+        assert!(homes.remove(DEFAULT_HOME).is_none());
+    }
+
+    #[test]
+    fn test_max_homes() {
+        let mut homes = Homes::default();
+        homes.set("a", home(1.0), 2).unwrap();
+        homes.set("b", home(2.0), 2).unwrap();
+        assert_eq!(homes.set("c", home(3.0), 2), Err(TooManyHomes));
+        // Existing homes can still be moved
+        assert!(homes.set("b", home(4.0), 2).is_ok());
+        assert_eq!(homes.names().collect::<Vec<_>>(), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let world_folder = env::temp_dir().join("pumpkin-homes-test");
+        let uuid = Uuid::new_v4();
+        assert_eq!(Homes::load(&world_folder, &uuid).unwrap(), Homes::default());
+
+        let mut homes = Homes::default();
+        homes.set("base", home(-12.5), 3).unwrap();
+        homes.save(&world_folder, &uuid).unwrap();
+        assert_eq!(Homes::load(&world_folder, &uuid).unwrap(), homes);
+
+        fs::remove_file(
+            world_folder
+                .join(super::HOMES_FOLDER)
+                .join(format!("{}.json", uuid)),
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,139 @@
+use pumpkin_config::{BackConfig, ADVANCED_CONFIG};
+use pumpkin_world::dimension::Dimension;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::player::Player;
+
+/// A position in a dimension which Players can be teleported to, e.g. a home or a warp
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Location {
+    pub dimension: Dimension,
+    pub position: (f64, f64, f64),
+    pub yaw: f32,
+    pub pitch: f32,
+}
+
+impl Location {
+    pub fn from_player(player: &Player) -> Self {
+        let entity = &player.entity;
+        let position = entity.pos.load();
+        Self {
+            dimension: entity.world.dimension,
+            position: (position.x, position.y, position.z),
+            yaw: entity.yaw.load(),
+            pitch: entity.pitch.load(),
+        }
+    }
+
+    /// Teleports the Player here, returns false if the location is in another dimension.
+    ///
+    /// The Player can return to where they were with `/back`
+    pub fn teleport(&self, player: &Player) -> bool {
+        if self.dimension != player.entity.world.dimension {
+            // TODO: teleport between worlds once there is more than one
+            return false;
+        }
+        player.last_location.lock().record(
+            Self::from_player(player),
+            BackCause::Teleport,
+            &ADVANCED_CONFIG.back,
+        );
+        let (x, y, z) = self.position;
+        player.teleport(x, y, z, self.yaw, self.pitch);
+        true
+    }
+}
+
+/// Why a Player left a location they can return to with `/back`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackCause {
+    Teleport,
+    Death,
+}
+
+/// The location a Player returns to with `/back`
+#[derive(Default)]
+pub struct LastLocation {
+    location: Option<Location>,
+}
+
+impl LastLocation {
+    /// Remembers the location if the config allows `/back` for the cause, returns whether it did
+    pub fn record(&mut self, location: Location, cause: BackCause, config: &BackConfig) -> bool {
+        let enabled = match cause {
+            BackCause::Teleport => config.on_teleport,
+            BackCause::Death => config.on_death,
+        };
+        if enabled {
+            self.location = Some(location);
+        }
+        enabled
+    }
+
+    pub fn get(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+}
+
+/// Names of homes and warps are single words made of letters, digits, `_` and `-`
+pub fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 32
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_config::BackConfig;
+    use pumpkin_world::dimension::Dimension;
+
+    use super::{is_valid_name, BackCause, LastLocation, Location};
+
+    fn location(x: f64) -> Location {
+        Location {
+            dimension: Dimension::OverWorld,
+            position: (x, 64.0, 0.0),
+            yaw: 0.0,
+            pitch: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_back_after_teleport() {
+        let config = BackConfig::default();
+        let mut last = LastLocation::default();
+        assert!(last.get().is_none());
+
+        // Teleporting away from 1 and using /back from 2 returns to 1, which records 2
+        assert!(last.record(location(1.0), BackCause::Teleport, &config));
+        assert_eq!(last.get(), Some(&location(1.0)));
+        assert!(last.record(location(2.0), BackCause::Teleport, &config));
+        assert_eq!(last.get(), Some(&location(2.0)));
+    }
+
+    #[test]
+    fn test_back_after_death() {
+        let mut last = LastLocation::default();
+        assert!(last.record(location(-5.0), BackCause::Death, &BackConfig::default()));
+        assert_eq!(last.get(), Some(&location(-5.0)));
+
+        let config = BackConfig {
+            on_teleport: true,
+            on_death: false,
+        };
+        assert!(!last.record(location(7.0), BackCause::Death, &config));
+        assert_eq!(last.get(), Some(&location(-5.0)));
+    }
+
+    #[test]
+    fn test_valid_names() {
+        assert!(is_valid_name("home"));
+        assert!(is_valid_name("spawn_2-east"));
+        assert!(!is_valid_name(""));
+        assert!(!is_valid_name("my home"));
+        assert!(!is_valid_name("§chome"));
+        assert!(!is_valid_name(&"a".repeat(33)));
+    }
+}
@@ -0,0 +1,212 @@
+use std::sync::Arc;
+
+use pumpkin_core::{
+    math::{vector2::Vector2, vector3::Vector3},
+    GameMode,
+};
+use pumpkin_entity::entity_type::EntityType;
+use pumpkin_world::{coordinates::ChunkRelativeBlockCoordinates, WORLD_LOWEST_Y, WORLD_MAX_Y};
+use rand::Rng;
+use uuid::Uuid;
+
+use crate::entity::{id_allocator::EntityIdAllocator, player::Player, Entity};
+
+use super::World;
+
+/// The only mob which spawns for now, until there are spawn rules for the others
+const SPAWNED_MOB: EntityType = EntityType::Pig;
+
+/// Counts the ticks until mobs spawn again
+#[derive(Default)]
+pub struct SpawnCycle {
+    ticks: u32,
+}
+
+impl SpawnCycle {
+    /// Returns whether mobs spawn in this tick, which happens every `interval` ticks
+    pub fn tick(&mut self, interval: u32) -> bool {
+        self.ticks += 1;
+        if self.ticks < interval {
+            return false;
+        }
+        self.ticks = 0;
+        true
+    }
+}
+
+/// Whether the chunk is simulated for a Player standing in `center`, which covers the same square as the view distance
+pub fn is_in_simulation_distance(center: Vector2<i32>, chunk: Vector2<i32>, distance: i32) -> bool {
+    (chunk.x - center.x).abs() <= distance && (chunk.z - center.z).abs() <= distance
+}
+
+/// How many mobs are in the chunks simulated around `center`
+fn local_mob_count(world: &World, center: Vector2<i32>, distance: i32) -> u32 {
+    world
+        .entities
+        .lock()
+        .values()
+        .filter(|entity| {
+            entity.entity_type.is_mob()
+                && is_in_simulation_distance(center, entity.chunk_pos.load(), distance)
+        })
+        .count() as u32
+}
+
+/// The position on top of the highest block in the column, `None` if the chunk isn't loaded or the column is empty
+fn surface_position(world: &World, chunk: Vector2<i32>, x: u8, z: u8) -> Option<Vector3<f64>> {
+    let chunk_data = world.get_loaded_chunk(chunk)?;
+    let blocks = chunk_data.blocks.read();
+    let y = (WORLD_LOWEST_Y..WORLD_MAX_Y).rev().find(|&y| {
+        !blocks
+            .get_block(ChunkRelativeBlockCoordinates {
+                x: x.into(),
+                y: y.into(),
+                z: z.into(),
+            })
+            .is_air()
+    })?;
+    // Mobs spawn at the center of the block above
+    Some(Vector3::new(
+        f64::from(chunk.x * 16 + i32::from(x)) + 0.5,
+        f64::from(y) + 1.0,
+        f64::from(chunk.z * 16 + i32::from(z)) + 0.5,
+    ))
+}
+
+/// Tries to spawn mobs around the Player until the mob cap within `distance` chunks is reached.
+///
+/// Every attempt picks a random column in a chunk in range, attempts in chunks which aren't loaded fail.
+/// Returns how many mobs spawned
+pub fn spawn_around(
+    player: &Player,
+    entity_ids: &EntityIdAllocator,
+    mob_cap: u32,
+    distance: i32,
+) -> u32 {
+    if player.gamemode.load() == GameMode::Spectator {
+        return 0;
+    }
+    let world = &player.entity.world;
+    let center = player.entity.chunk_pos.load();
+    let attempts = mob_cap.saturating_sub(local_mob_count(world, center, distance));
+    let mut rng = rand::thread_rng();
+    let mut spawned = 0;
+    for _ in 0..attempts {
+        let chunk = Vector2::new(
+            center.x + rng.gen_range(-distance..=distance),
+            center.z + rng.gen_range(-distance..=distance),
+        );
+        let Some(position) =
+            surface_position(world, chunk, rng.gen_range(0..16), rng.gen_range(0..16))
+        else {
+            continue;
+        };
+        let mob = Entity::new(
+            entity_ids.allocate(),
+            Uuid::new_v4(),
+            world.clone(),
+            SPAWNED_MOB,
+        );
+        mob.set_pos(position.x, position.y, position.z);
+        mob.set_rotation(rng.gen_range(0.0..360.0), 0.0);
+        if world.spawn_entity(Arc::new(mob)) {
+            spawned += 1;
+        }
+    }
+    spawned
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use pumpkin_core::math::vector2::Vector2;
+    use pumpkin_entity::entity_type::EntityType;
+    use uuid::Uuid;
+
+    use super::{is_in_simulation_distance, spawn_around, SpawnCycle};
+    use crate::{
+        entity::{id_allocator::EntityIdAllocator, Entity},
+        test_helpers::{joined_player, world},
+        world::World,
+    };
+
+    fn pigs_in(world: &World, chunk: Vector2<i32>) -> usize {
+        world
+            .entities
+            .lock()
+            .values()
+            .filter(|entity| {
+                entity.entity_type == EntityType::Pig && entity.chunk_pos.load() == chunk
+            })
+            .count()
+    }
+
+    #[tokio::test]
+    async fn test_mob_cap() {
+        let world = world("mob-spawning");
+        let player = joined_player(&world);
+        player.entity.set_pos(8.5, 100.0, 8.5);
+        let entity_ids = EntityIdAllocator::new(10);
+        let chunk = Vector2::new(0, 0);
+        world.load_chunk(chunk).await;
+
+        assert_eq!(spawn_around(&player, &entity_ids, 3, 0), 3);
+        assert_eq!(pigs_in(&world, chunk), 3);
+        // Players around see them
+        assert_eq!(player.tracked_entities.lock().len(), 3);
+        // The cap is reached
+        assert_eq!(spawn_around(&player, &entity_ids, 3, 0), 0);
+        assert_eq!(spawn_around(&player, &entity_ids, 5, 0), 2);
+        assert_eq!(pigs_in(&world, chunk), 5);
+    }
+
+    #[tokio::test]
+    async fn test_outside_simulation_distance() {
+        let world = world("mob-spawning");
+        let player = joined_player(&world);
+        player.entity.set_pos(8.5, 100.0, 8.5);
+        let entity_ids = EntityIdAllocator::new(10);
+        world.load_chunk(Vector2::new(0, 0)).await;
+        world.load_chunk(Vector2::new(1, 0)).await;
+        // Mobs far away don't count towards the cap
+        let far = Entity::new(
+            entity_ids.allocate(),
+            Uuid::new_v4(),
+            world.clone(),
+            EntityType::Pig,
+        );
+        far.set_pos(100.5, 64.0, 0.5);
+        assert!(world.spawn_entity(Arc::new(far)));
+
+        assert_eq!(spawn_around(&player, &entity_ids, 4, 0), 4);
+        assert_eq!(pigs_in(&world, Vector2::new(0, 0)), 4);
+        // The next chunk is loaded but not simulated
+        assert_eq!(pigs_in(&world, Vector2::new(1, 0)), 0);
+    }
+
+    #[test]
+    fn test_unloaded_chunks() {
+        let world = world("mob-spawning");
+        let player = joined_player(&world);
+        player.entity.set_pos(8.5, 100.0, 8.5);
+        assert_eq!(spawn_around(&player, &EntityIdAllocator::new(10), 3, 2), 0);
+        assert!(world.entities.lock().is_empty());
+    }
+
+    #[test]
+    fn test_is_in_simulation_distance() {
+        let center = Vector2::new(2, -3);
+        assert!(is_in_simulation_distance(center, center, 0));
+        assert!(is_in_simulation_distance(center, Vector2::new(4, -1), 2));
+        assert!(!is_in_simulation_distance(center, Vector2::new(5, -3), 2));
+        assert!(!is_in_simulation_distance(center, Vector2::new(2, 0), 2));
+    }
+
+    #[test]
+    fn test_spawn_cycle() {
+        let mut cycle = SpawnCycle::default();
+        let spawns: Vec<_> = (0..6).map(|_| cycle.tick(3)).collect();
+        assert_eq!(spawns, [false, false, true, false, false, true]);
+    }
+}
@@ -1,26 +1,83 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
 
+pub mod difficulty;
+pub mod entity_limit;
+pub mod entity_tracker;
+pub mod game_rules;
+pub mod hardcore;
+pub mod homes;
+pub mod location;
+pub mod mob_spawning;
+pub mod mount;
 pub mod player_chunker;
+pub mod player_data;
+pub mod respawn;
+pub mod sleep;
+pub mod tick_schedule;
+pub mod time;
+pub mod weather;
 
 use crate::{
     client::Client,
-    entity::{player::Player, Entity},
+    entity::{
+        experience::Experience,
+        experience_orb::{self, ExperienceOrb},
+        id_allocator::EntityIdAllocator,
+        item::ItemEntity,
+        player::Player,
+        Entity,
+    },
+    server::mutes::unix_now,
 };
+use difficulty::DifficultyState;
+use entity_limit::ChunkEntityCounts;
+use entity_tracker::MovementBatch;
+use game_rules::{GameRule, GameRules};
+use location::Location;
 use mio::Token;
+use mob_spawning::SpawnCycle;
 use num_traits::ToPrimitive;
 use parking_lot::Mutex;
-use pumpkin_config::BasicConfiguration;
-use pumpkin_core::math::vector2::Vector2;
-use pumpkin_entity::{entity_type::EntityType, EntityId};
+use player_data::{join_gamemode, PlayerData};
+use pumpkin_config::{
+    BasicConfiguration, FirstJoinConfig, ADVANCED_CONFIG, BASIC_CONFIG, LIVE_CONFIG,
+};
+use pumpkin_core::{
+    math::{position::WorldPosition, vector2::Vector2},
+    text::{color::NamedColor, TextComponent},
+    Difficulty, GameMode,
+};
+use pumpkin_entity::EntityId;
 use pumpkin_protocol::{
+    bytebuf::ByteBuffer,
     client::play::{
-        CChunkData, CGameEvent, CLogin, CPlayerAbilities, CPlayerInfoUpdate, CRemoveEntities,
-        CRemovePlayerInfo, CSetEntityMetadata, CSpawnEntity, GameEvent, Metadata, PlayerAction,
+        CBlockEntityData, CChangeDifficulty, CChunkData, CGameEvent, CLogin, CPlayerAbilities,
+        CPlayerInfoUpdate, CRemoveEntities, CRemovePlayerInfo, CSetEntityMetadata,
+        CSetTickingState, CSystemChatMessage, CUpdateTime, GameEvent, Metadata, PlayerAction,
     },
     ClientPacket, VarInt,
 };
-use pumpkin_world::level::Level;
+use pumpkin_world::{
+    block::{block_entity::BlockEntity, find_block_state, BlockId},
+    chunk::{ChunkBlocks, ChunkData},
+    coordinates::ChunkRelativeBlockCoordinates,
+    dimension::Dimension,
+    item::{get_item_protocol_id, ItemStack},
+    level::{Level, WorldError},
+};
+use tick_schedule::TickSchedule;
+use time::WorldTime;
 use tokio::sync::mpsc;
+use uuid::Uuid;
+use weather::Weather;
+
+/// How long to wait for the next chunk before the missing ones are sent empty
+const CHUNK_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Represents a Minecraft world, containing entities, players, and the underlying level data.
 ///
@@ -32,18 +89,53 @@ use tokio::sync::mpsc;
 /// - Stores and tracks active `Player` entities within the world.
 /// - Provides a central hub for interacting with the world's entities and environment.
 pub struct World {
+    /// Which dimension this world is.
+    pub dimension: Dimension,
     /// The underlying level, responsible for chunk management and terrain generation.
-    pub level: Arc<Mutex<Level>>,
+    /// It locks its chunks itself, so reading blocks never waits for chunks being written to disk.
+    pub level: Arc<Level>,
     /// A map of active players within the world, keyed by their unique token.
     pub current_players: Arc<Mutex<HashMap<Token, Arc<Player>>>>,
-    // TODO: entities
+    /// The current rain and thunder state.
+    pub weather: Mutex<Weather>,
+    /// The game rules of this world.
+    pub game_rules: Mutex<GameRules>,
+    /// The difficulty of this world.
+    pub difficulty: Mutex<DifficultyState>,
+    /// The age of this world and the time of day.
+    pub time: Mutex<WorldTime>,
+    /// When this world ticks, see `TickRateConfig`.
+    pub tick_schedule: Mutex<TickSchedule>,
+    /// The entities in this world which aren't Players, keyed by their entity id.
+    pub entities: Mutex<HashMap<EntityId, Arc<Entity>>>,
+    /// How many of these entities each chunk holds, see `max_entities_per_chunk`.
+    pub entity_counts: Mutex<ChunkEntityCounts>,
+    /// The dropped items among these entities.
+    pub items: Mutex<HashMap<EntityId, Arc<ItemEntity>>>,
+    /// The experience orbs among these entities.
+    pub experience_orbs: Mutex<HashMap<EntityId, Arc<ExperienceOrb>>>,
+    /// When mobs spawn next, see `MobSpawningConfig`.
+    pub mob_spawn_cycle: Mutex<SpawnCycle>,
 }
 
 impl World {
-    pub fn load(level: Level) -> Self {
+    pub fn load(dimension: Dimension, level: Level) -> Self {
         Self {
-            level: Arc::new(Mutex::new(level)),
+            dimension,
+            level: Arc::new(level),
             current_players: Arc::new(Mutex::new(HashMap::new())),
+            weather: Mutex::new(Weather::default()),
+            game_rules: Mutex::new(GameRules::default()),
+            difficulty: Mutex::new(DifficultyState::new(BASIC_CONFIG.default_difficulty)),
+            time: Mutex::new(WorldTime::default()),
+            tick_schedule: Mutex::new(TickSchedule::new(
+                ADVANCED_CONFIG.tick_rate.rate(dimension.name()),
+            )),
+            entities: Mutex::new(HashMap::new()),
+            entity_counts: Mutex::new(ChunkEntityCounts::default()),
+            items: Mutex::new(HashMap::new()),
+            experience_orbs: Mutex::new(HashMap::new()),
+            mob_spawn_cycle: Mutex::new(SpawnCycle::default()),
         }
     }
 
@@ -77,10 +169,430 @@ impl World {
         }
     }
 
+    /// Runs the ticks which are due at `now` and returns when the World ticks next.
+    ///
+    /// Spawned mobs get their ids from `entity_ids`, which are shared by every World of the Server
+    pub fn tick_scheduled(&self, now: Instant, entity_ids: &EntityIdAllocator) -> Instant {
+        let ticks = self.tick_schedule.lock().due_ticks(now);
+        for _ in 0..ticks {
+            self.tick(entity_ids);
+        }
+        self.tick_schedule
+            .lock()
+            .next_tick()
+            .expect("The schedule started with the first tick")
+    }
+
+    /// How many times per second the World ticks
+    pub fn tick_rate(&self) -> f32 {
+        self.tick_schedule.lock().tick_rate()
+    }
+
+    /// Changes how fast the World ticks, the clients run their time and movement at the same rate
+    pub fn set_tick_rate(&self, tick_rate: f32) {
+        self.tick_schedule.lock().set_tick_rate(tick_rate);
+        self.broadcast_packet_all(&CSetTickingState::new(tick_rate, false));
+    }
+
+    /// Advances the World by one game tick
+    pub fn tick(&self, entity_ids: &EntityIdAllocator) {
+        let (do_weather_cycle, do_daylight_cycle) = {
+            let game_rules = self.game_rules.lock();
+            (game_rules.do_weather_cycle, game_rules.do_daylight_cycle)
+        };
+        let weather_events = self.weather.lock().tick(do_weather_cycle);
+        for (event, value) in weather_events {
+            self.broadcast_packet_all(&CGameEvent::new(event, value));
+        }
+        let time_packet = {
+            let mut time = self.time.lock();
+            time.tick(do_daylight_cycle);
+            (time.world_age % time::TIME_SYNC_INTERVAL == 0).then(|| time.packet(do_daylight_cycle))
+        };
+        if let Some(packet) = time_packet {
+            self.broadcast_packet_all(&packet);
+        }
+
+        let players = {
+            let current_players = self.current_players.lock();
+            for player in current_players.values() {
+                player.tick();
+            }
+            current_players.values().cloned().collect::<Vec<_>>()
+        };
+        // Broadcasting locks the Players again
+        let max_chunks = ADVANCED_CONFIG.chunk_sending.max_chunks_per_tick as usize;
+        for player in &players {
+            entity_tracker::broadcast_movement(self, player);
+            player_chunker::send_queued_chunks(player, max_chunks);
+        }
+        if self
+            .mob_spawn_cycle
+            .lock()
+            .tick(ADVANCED_CONFIG.mob_spawning.spawn_interval_ticks)
+        {
+            self.spawn_mobs(
+                &players,
+                entity_ids,
+                BASIC_CONFIG.simulation_distance.into(),
+            );
+        }
+        for item in self.items.lock().values() {
+            item.tick();
+        }
+        self.drop_death_experience(&players, entity_ids);
+        self.tick_sleeping();
+        for player in self.players_to_respawn() {
+            tokio::spawn(async move { player.respawn().await });
+        }
+    }
+
+    /// Spawns mobs in the chunks within `distance` of the Players unless `do_mob_spawning` is off,
+    /// returns how many were spawned
+    fn spawn_mobs(
+        &self,
+        players: &[Arc<Player>],
+        entity_ids: &EntityIdAllocator,
+        distance: i32,
+    ) -> u32 {
+        if !self.game_rules.lock().do_mob_spawning {
+            return 0;
+        }
+        players
+            .iter()
+            .map(|player| {
+                mob_spawning::spawn_around(
+                    player,
+                    entity_ids,
+                    ADVANCED_CONFIG.mob_spawning.mob_cap_per_player,
+                    distance,
+                )
+            })
+            .sum()
+    }
+
+    /// Spawns the experience Players dropped when they died where they died
+    fn drop_death_experience(&self, players: &[Arc<Player>], entity_ids: &EntityIdAllocator) {
+        let merge_radius = ADVANCED_CONFIG.experience_orbs.merge_radius;
+        for player in players {
+            let dropped = player.dropped_experience.swap(0, Ordering::Relaxed);
+            if dropped > 0 {
+                experience_orb::drop_experience(
+                    &player.entity.world,
+                    entity_ids.allocate(),
+                    dropped,
+                    player.entity.pos.load(),
+                    merge_radius,
+                );
+            }
+        }
+    }
+
+    /// The packet which tells a client the current time
+    pub fn time_packet(&self) -> CUpdateTime {
+        let do_daylight_cycle = self.game_rules.lock().do_daylight_cycle;
+        self.time.lock().packet(do_daylight_cycle)
+    }
+
+    /// How many of the Players have to sleep to skip the night, the world's override wins over the config
+    pub fn players_sleeping_percentage(&self) -> u8 {
+        self.game_rules
+            .lock()
+            .players_sleeping_percentage
+            .unwrap_or(LIVE_CONFIG.load().sleeping.players_sleeping_percentage)
+    }
+
+    /// Skips the night once enough Players are sleeping
+    fn tick_sleeping(&self) {
+        let (sleeping, active_players) = {
+            let current_players = self.current_players.lock();
+            let sleeping: Vec<_> = current_players
+                .values()
+                .filter(|player| player.sleeping_in.load().is_some())
+                .cloned()
+                .collect();
+            let active_players = current_players
+                .values()
+                .filter(|player| player.gamemode.load() != GameMode::Spectator)
+                .count();
+            (sleeping, active_players)
+        };
+        let deeply_sleeping = sleeping
+            .iter()
+            .filter(|player| player.is_deeply_sleeping())
+            .count();
+        if deeply_sleeping == 0
+            || !sleep::can_skip_night(
+                deeply_sleeping,
+                active_players,
+                self.players_sleeping_percentage(),
+            )
+        {
+            return;
+        }
+
+        let do_weather_cycle = {
+            let game_rules = self.game_rules.lock();
+            if game_rules.do_daylight_cycle {
+                self.time.lock().skip_to_morning();
+            }
+            game_rules.do_weather_cycle
+        };
+        if do_weather_cycle {
+            let mut weather = self.weather.lock();
+            if weather.raining {
+                weather.set_weather(0, 0, false, false);
+            }
+        }
+        self.broadcast_packet_all(&self.time_packet());
+        for player in sleeping {
+            player.stop_sleeping();
+        }
+    }
+
+    /// Stores a Block change in the Level, so it gets saved with its chunk.
+    ///
+    /// This does not send the change to the Players
+    pub fn set_block(&self, position: WorldPosition, block: BlockId) {
+        if let Err(err) = self.level.set_block(position, block) {
+            log::debug!(
+                "Couldn't set block at {} {} {}: {}",
+                position.0.x,
+                position.0.y,
+                position.0.z,
+                err
+            );
+        }
+    }
+
+    /// Gets a Block, returns `None` if its chunk is not loaded
+    pub fn get_block(&self, position: WorldPosition) -> Option<BlockId> {
+        self.level.get_block(position).ok()
+    }
+
+    /// Gets a copy of the block entity at the given position, returns `None` if its chunk is not loaded
+    pub fn get_block_entity(&self, position: WorldPosition) -> Option<BlockEntity> {
+        let (at, _) = ChunkRelativeBlockCoordinates::from_world_position(position)?;
+        self.get_loaded_chunk(at)?.get_block_entity(position)
+    }
+
+    /// Stores a block entity in its chunk and sends it to the Players
+    pub fn update_block_entity(&self, block_entity: BlockEntity) {
+        let Some((at, _)) =
+            ChunkRelativeBlockCoordinates::from_world_position(block_entity.position)
+        else {
+            return;
+        };
+        let Some(chunk) = self.get_loaded_chunk(at) else {
+            return;
+        };
+        if let Some(packet) = CBlockEntityData::new(&block_entity) {
+            self.broadcast_packet_all(&packet);
+        }
+        chunk.set_block_entity(block_entity);
+    }
+
+    /// Replaces the text on one side of the sign at the given position.
+    ///
+    /// Returns `false` if there is no sign or it is waxed
+    pub fn edit_sign(&self, position: WorldPosition, front: bool, lines: &[String]) -> bool {
+        let sign = self.get_block_entity(position).or_else(|| {
+            // Signs placed before we kept block entities don't have one yet
+            let (name, _) = find_block_state(self.get_block(position)?)?;
+            BlockEntity::sign(name, position)
+        });
+        let Some(mut sign) = sign else {
+            return false;
+        };
+        if !sign.set_sign_text(front, lines) {
+            return false;
+        }
+        self.update_block_entity(sign);
+        true
+    }
+
+    /// Gets a chunk if it is in memory
+    pub fn get_loaded_chunk(&self, at: Vector2<i32>) -> Option<Arc<ChunkData>> {
+        self.level.get_loaded_chunk(at)
+    }
+
+    /// Makes sure a chunk is in memory, e.g. to read blocks outside of every Player's view distance
+    pub async fn load_chunk(&self, at: Vector2<i32>) {
+        let (sender, mut chunk_receiver) = mpsc::channel(1);
+        let level = self.level.clone();
+        tokio::task::spawn_blocking(move || level.fetch_chunks(&[at], sender, false));
+        chunk_receiver.recv().await;
+    }
+
+    /// Where Players spawn when they join and respawn without a respawn point
+    pub fn spawn_location(&self) -> Location {
+        Location {
+            dimension: self.dimension,
+            position: (10.0, 120.0, 10.0),
+            yaw: 10.0,
+            pitch: 10.0,
+        }
+    }
+
+    /// Checks if the block is within the spawn protection, see `BasicConfiguration::spawn_protection`
+    pub fn is_spawn_protected(&self, location: &WorldPosition) -> bool {
+        is_spawn_protected(
+            self.spawn_location().position,
+            location,
+            BASIC_CONFIG.spawn_protection,
+        )
+    }
+
+    /// Saves the changed chunks and every Player in this World, returns how many chunks were saved
+    pub fn save(&self) -> usize {
+        let saved = match self.level.save_chunks() {
+            Ok(saved) => {
+                log::debug!("Saved {} chunks", saved);
+                saved
+            }
+            Err(err) => {
+                log::error!("Couldn't save chunks: {}", err);
+                0
+            }
+        };
+        // Players can still join and leave while the others are saved
+        let players = self
+            .current_players
+            .lock()
+            .values()
+            .cloned()
+            .collect::<Vec<_>>();
+        for player in players {
+            save_player_data(self.level.root_folder(), &player);
+        }
+        saved
+    }
+
+    /// Saves and removes the chunks no Player sees anymore, e.g. after they left the view of a Player.
+    ///
+    /// This happens in the background, the chunks are checked again while they are removed.
+    /// A Player who starts watching one of them in the meantime gets it back from memory
+    pub fn unload_unwatched_chunks(&self, chunks: Vec<Vector2<i32>>) {
+        if chunks.is_empty() {
+            return;
+        }
+        let level = self.level.clone();
+        let players = self.current_players.clone();
+        tokio::task::spawn_blocking(move || {
+            let is_watched = |at| {
+                players
+                    .lock()
+                    .values()
+                    .any(|player| player.loaded_chunks.lock().contains(&at))
+            };
+            if let Err(err) = level.unload_chunks(&chunks, is_watched) {
+                log::error!("Couldn't save the unloaded chunks: {}", err);
+            }
+        });
+    }
+
+    /// Saves the data of a single Player, e.g. when they leave
+    pub fn save_player(&self, player: &Player) {
+        save_player_data(self.level.root_folder(), player);
+    }
+
+    /// The Players whose connection was closed but who were not removed yet
+    pub fn closed_players(&self) -> Vec<Arc<Player>> {
+        self.current_players
+            .lock()
+            .values()
+            .filter(|player| player.client.closed.load(Ordering::Relaxed))
+            .cloned()
+            .collect()
+    }
+
+    /// Whether Players can hurt each other in this world, the world's override wins over the config
+    pub fn is_pvp_enabled(&self) -> bool {
+        self.game_rules
+            .lock()
+            .pvp
+            .unwrap_or(LIVE_CONFIG.load().pvp.enabled)
+    }
+
+    /// Whether dead Players respawn without the death screen, the world's override wins over the config
+    pub fn immediate_respawn(&self) -> bool {
+        self.game_rules
+            .lock()
+            .do_immediate_respawn
+            .unwrap_or(BASIC_CONFIG.immediate_respawn)
+    }
+
+    /// Changes a game rule like `/gamerule` does and tells the clients what changed for them.
+    ///
+    /// Returns false if the value doesn't fit the rule
+    pub fn set_game_rule(&self, rule: GameRule, value: &str) -> bool {
+        if !self.game_rules.lock().set(rule, value) {
+            return false;
+        }
+        match rule {
+            GameRule::DoImmediateRespawn => {
+                self.broadcast_packet_all(&respawn_screen_event(self.immediate_respawn()));
+            }
+            // The clients stop or continue advancing the time themselves
+            GameRule::DoDaylightCycle => self.broadcast_packet_all(&self.time_packet()),
+            _ => {}
+        }
+        true
+    }
+
+    /// The dead Players who respawn right away because of `immediate_respawn`
+    fn players_to_respawn(&self) -> Vec<Arc<Player>> {
+        self.current_players
+            .lock()
+            .values()
+            .filter(|player| player.respawns_immediately())
+            .cloned()
+            .collect()
+    }
+
+    /// Changes the difficulty and tells every Player about it.
+    ///
+    /// Returns false when the difficulty is locked and the change was not `forced`
+    pub fn set_difficulty(&self, difficulty: Difficulty, forced: bool) -> bool {
+        let locked = {
+            let mut state = self.difficulty.lock();
+            if !state.set(difficulty, forced) {
+                return false;
+            }
+            state.locked
+        };
+        self.broadcast_packet_all(&CChangeDifficulty::new(difficulty as u8, locked));
+        true
+    }
+
     pub async fn spawn_player(&self, base_config: &BasicConfiguration, player: Arc<Player>) {
         // This code follows the vanilla packet order
         let entity_id = player.entity_id();
-        let gamemode = player.gamemode.load();
+        let gameprofile = &player.gameprofile;
+        // TODO: restore the rest of the saved data
+        let data = PlayerData::load(self.level.root_folder(), &gameprofile.id);
+        // Without saved data the Player never joined before
+        let first_join = matches!(data, Ok(None));
+        let data = data.unwrap_or_else(|err| {
+            log::error!(
+                "Couldn't load the player data of {}: {}",
+                gameprofile.name,
+                err
+            );
+            None
+        });
+        let gamemode = join_gamemode(
+            data.as_ref(),
+            base_config.default_gamemode,
+            base_config.force_gamemode,
+        );
+        player.gamemode.store(gamemode);
+        if let Some(data) = data {
+            *player.respawn_point.lock() = data.respawn_point;
+            player.login_times.lock().restore(data.login_times);
+            player.vanished.store(data.vanished, Ordering::Relaxed);
+            *player.experience.lock() = Experience::new(data.experience);
+        }
         log::debug!("spawning player, entity id {}", entity_id);
 
         // login packet for our new player
@@ -92,7 +604,7 @@ impl World {
             base_config.view_distance.into(), //  TODO: view distance
             base_config.simulation_distance.into(), // TODO: sim view dinstance
             false,
-            false,
+            !self.immediate_respawn(),
             false,
             0.into(),
             "minecraft:overworld",
@@ -105,6 +617,13 @@ impl World {
             0.into(),
             false,
         ));
+        {
+            let difficulty = self.difficulty.lock();
+            player.client.send_packet(&CChangeDifficulty::new(
+                difficulty.difficulty as u8,
+                difficulty.locked,
+            ));
+        }
         dbg!("sending abilities");
         // player abilities
         // TODO: this is for debug purpose, remove later
@@ -113,28 +632,19 @@ impl World {
             .send_packet(&CPlayerAbilities::new(0x02, 0.4, 0.1));
 
         // teleport
-        let x = 10.0;
-        let y = 120.0;
-        let z = 10.0;
-        let yaw = 10.0;
-        let pitch = 10.0;
-        player.teleport(x, y, z, 10.0, 10.0);
-        let gameprofile = &player.gameprofile;
+        let spawn = self.spawn_location();
+        let (x, y, z) = spawn.position;
+        player.teleport(x, y, z, spawn.yaw, spawn.pitch);
         // first send info update to our new player, So he can see his Skin
-        // also send his info to everyone else
-        self.broadcast_packet_all(&CPlayerInfoUpdate::new(
-            0x01 | 0x08,
-            &[pumpkin_protocol::client::play::Player {
-                uuid: gameprofile.id,
-                actions: vec![
-                    PlayerAction::AddPlayer {
-                        name: &gameprofile.name,
-                        properties: &gameprofile.properties,
-                    },
-                    PlayerAction::UpdateListed(true),
-                ],
-            }],
-        ));
+        // also send his info to everyone else, unless he is vanished
+        let vanished = player.is_vanished();
+        let entry = [tab_list_entry(&player)];
+        let info = CPlayerInfoUpdate::new(0x01 | 0x08, &entry);
+        if vanished {
+            player.client.send_packet(&info);
+        } else {
+            self.broadcast_packet_all(&info);
+        }
 
         // here we send all the infos of already joined players
         let mut entries = Vec::new();
@@ -142,7 +652,7 @@ impl World {
             let current_players = self.current_players.lock();
             for (_, playerr) in current_players
                 .iter()
-                .filter(|(c, _)| **c != player.client.token)
+                .filter(|(c, p)| **c != player.client.token && !p.is_vanished())
             {
                 let gameprofile = &playerr.gameprofile;
                 entries.push(pumpkin_protocol::client::play::Player {
@@ -161,50 +671,9 @@ impl World {
                 .send_packet(&CPlayerInfoUpdate::new(0x01 | 0x08, &entries));
         }
 
-        let gameprofile = &player.gameprofile;
+        // spawn the player for every client in range and the ones in range for our client
+        entity_tracker::update_player(self, &player);
 
-        // spawn player for every client
-        self.broadcast_packet_expect(
-            &[player.client.token],
-            // TODO: add velo
-            &CSpawnEntity::new(
-                entity_id.into(),
-                gameprofile.id,
-                (EntityType::Player as i32).into(),
-                x,
-                y,
-                z,
-                pitch,
-                yaw,
-                yaw,
-                0.into(),
-                0.0,
-                0.0,
-                0.0,
-            ),
-        );
-        // spawn players for our client
-        let token = player.client.token;
-        for (_, existing_player) in self.current_players.lock().iter().filter(|c| c.0 != &token) {
-            let entity = &existing_player.entity;
-            let pos = entity.pos.load();
-            let gameprofile = &existing_player.gameprofile;
-            player.client.send_packet(&CSpawnEntity::new(
-                existing_player.entity_id().into(),
-                gameprofile.id,
-                (EntityType::Player as i32).into(),
-                pos.x,
-                pos.y,
-                pos.z,
-                entity.yaw.load(),
-                entity.pitch.load(),
-                entity.head_yaw.load(),
-                0.into(),
-                0.0,
-                0.0,
-                0.0,
-            ))
-        }
         // entity meta data
         // set skin parts
         if let Some(config) = player.client.config.lock().as_ref() {
@@ -215,47 +684,90 @@ impl World {
             self.broadcast_packet_all(&packet)
         }
 
+        // send the current weather
+        for (event, value) in self.weather.lock().join_events() {
+            player.client.send_packet(&CGameEvent::new(event, value));
+        }
+        player.client.send_packet(&self.time_packet());
+        player
+            .client
+            .send_packet(&CSetTickingState::new(self.tick_rate(), false));
+        player
+            .client
+            .send_packet(&player.experience.lock().packet());
+
         // Start waiting for level chunks, Sets the "Loading Terrain" screen
         player
             .client
             .send_packet(&CGameEvent::new(GameEvent::StartWaitingChunks, 0.0));
 
+        if let Some(message) = LIVE_CONFIG
+            .load()
+            .messages
+            .join_message(&gameprofile.name)
+            .filter(|_| !vanished)
+        {
+            self.broadcast_message(&message);
+        }
+        self.welcome_player(&player, first_join, &ADVANCED_CONFIG.first_join);
+
         // Spawn in initial chunks
-        player_chunker::player_join(self, player.clone()).await;
+        player_chunker::player_join(self, &player).await;
+    }
+
+    /// Greets Players who join for the first time and hands out the starter kit, returning Players get nothing
+    pub fn welcome_player(&self, player: &Player, first_join: bool, config: &FirstJoinConfig) {
+        if !first_join {
+            return;
+        }
+        if let Some(message) = config.welcome_message(&player.gameprofile.name) {
+            self.broadcast_message(&message);
+        }
+        if config.starter_kit.is_empty() {
+            return;
+        }
+        {
+            let mut inventory = player.inventory.lock();
+            for kit_item in &config.starter_kit {
+                let Some(item_id) = get_item_protocol_id(&kit_item.item) else {
+                    log::warn!("Unknown item {} in the starter kit", kit_item.item);
+                    continue;
+                };
+                if !inventory.add_item(ItemStack::new(kit_item.count, item_id)) {
+                    break;
+                }
+            }
+        }
+        player.set_container_content(None);
     }
 
-    async fn spawn_world_chunks(&self, client: &Client, chunks: Vec<Vector2<i32>>, distance: i32) {
+    /// Teleports the Player to the world spawn, returns false if they are in another dimension
+    pub fn teleport_to_spawn(&self, player: &Player) -> bool {
+        self.spawn_location().teleport(player)
+    }
+
+    /// Sends a yellow system message to every Player, like vanilla's join and leave messages
+    pub fn broadcast_message(&self, message: &str) {
+        self.broadcast_packet_all(&CSystemChatMessage::new(
+            TextComponent::text(message).color_named(NamedColor::Yellow),
+            false,
+        ));
+    }
+
+    async fn spawn_world_chunks(&self, player: &Player, chunks: Vec<Vector2<i32>>, distance: i32) {
         let inst = std::time::Instant::now();
-        let (sender, mut chunk_receiver) = mpsc::channel(distance as usize);
+        let (sender, chunk_receiver) = mpsc::channel(distance as usize);
 
         let level = self.level.clone();
-        let closed = client.closed.load(std::sync::atomic::Ordering::Relaxed);
+        let closed = player
+            .client
+            .closed
+            .load(std::sync::atomic::Ordering::Relaxed);
         let chunks = Arc::new(chunks);
-        tokio::task::spawn_blocking(move || level.lock().fetch_chunks(&chunks, sender, closed));
+        let fetched = chunks.clone();
+        tokio::task::spawn_blocking(move || level.fetch_chunks(&fetched, sender, closed));
 
-        while let Some(chunk_data) = chunk_receiver.recv().await {
-            // dbg!(chunk_pos);
-            let chunk_data = match chunk_data {
-                Ok(d) => d,
-                Err(_) => continue,
-            };
-            #[cfg(debug_assertions)]
-            if chunk_data.position == (0, 0).into() {
-                use pumpkin_protocol::bytebuf::ByteBuffer;
-                let mut test = ByteBuffer::empty();
-                CChunkData(&chunk_data).write(&mut test);
-                let len = test.buf().len();
-                log::debug!(
-                    "Chunk packet size: {}B {}KB {}MB",
-                    len,
-                    len / 1024,
-                    len / (1024 * 1024)
-                );
-            }
-            if !client.closed.load(std::sync::atomic::Ordering::Relaxed) {
-                client.send_packet(&CChunkData(&chunk_data));
-            }
-        }
+        queue_chunks(player, &chunks, chunk_receiver, CHUNK_FETCH_TIMEOUT).await;
         dbg!("DONE CHUNKS", inst.elapsed());
     }
 
@@ -279,24 +791,934 @@ impl World {
         None
     }
 
+    /// Gets a Player by their UUID
+    pub fn get_player_by_uuid(&self, uuid: &Uuid) -> Option<Arc<Player>> {
+        for player in self.current_players.lock().values() {
+            if &player.gameprofile.id == uuid {
+                return Some(player.clone());
+            }
+        }
+        None
+    }
+
     pub fn add_player(&self, token: Token, player: Arc<Player>) {
+        player.login_times.lock().join(unix_now());
         self.current_players.lock().insert(token, player);
     }
 
-    pub fn remove_player(&self, player: &Player) {
-        self.current_players
+    /// Removes the Player and tells everyone else they left, returns false if they weren't in this World
+    pub fn remove_player(&self, player: &Player) -> bool {
+        if self
+            .current_players
             .lock()
             .remove(&player.client.token)
-            .unwrap();
+            .is_none()
+        {
+            log::debug!("{} was already removed", player.gameprofile.name);
+            return false;
+        }
+        mount::dismount(self, player);
+        mount::eject_passengers(self, player);
+        player.login_times.lock().leave(unix_now());
         let uuid = player.gameprofile.id;
         self.broadcast_packet_expect(
             &[player.client.token],
             &CRemovePlayerInfo::new(1.into(), &[uuid]),
         );
         self.remove_entity(&player.entity);
+        if let Some(message) = LIVE_CONFIG
+            .load()
+            .messages
+            .leave_message(&player.gameprofile.name)
+            .filter(|_| !player.is_vanished())
+        {
+            self.broadcast_message(&message);
+        }
+        true
+    }
+
+    /// Hides the Player from everyone else while they keep seeing the World, or shows them again.
+    ///
+    /// Returns false if they already were (not) vanished
+    pub fn set_vanished(&self, player: &Player, vanished: bool) -> bool {
+        if player.vanished.swap(vanished, Ordering::Relaxed) == vanished {
+            return false;
+        }
+        let token = player.client.token;
+        if vanished {
+            self.broadcast_packet_expect(
+                &[token],
+                &CRemovePlayerInfo::new(1.into(), &[player.gameprofile.id]),
+            );
+        } else {
+            // The tab list entry has to be there before the entity spawns
+            let entry = [tab_list_entry(player)];
+            self.broadcast_packet_expect(&[token], &CPlayerInfoUpdate::new(0x01 | 0x08, &entry));
+            // Viewers get the current position when it spawns, relative moves would start from an old one
+            *player.movement_batch.lock() = MovementBatch::default();
+        }
+        for (_, other) in self
+            .current_players
+            .lock()
+            .iter()
+            .filter(|(other_token, _)| **other_token != token)
+        {
+            entity_tracker::update_tracking(other, player);
+        }
+        true
+    }
+
+    /// Adds the entity to the World and spawns it for the Players in tracking range.
+    ///
+    /// Returns false if its chunk already holds `max_entities_per_chunk` entities
+    pub fn spawn_entity(&self, entity: Arc<Entity>) -> bool {
+        self.spawn_entity_limited(entity, BASIC_CONFIG.max_entities_per_chunk)
+    }
+
+    fn spawn_entity_limited(&self, entity: Arc<Entity>, max_per_chunk: u32) -> bool {
+        if !self
+            .entity_counts
+            .lock()
+            .try_add(entity.chunk_pos.load(), max_per_chunk)
+        {
+            return false;
+        }
+        self.entities
+            .lock()
+            .insert(entity.entity_id, entity.clone());
+        self.level.mark_chunk_dirty(entity.chunk_pos.load());
+        for player in self.current_players.lock().values() {
+            entity_tracker::update_entity_tracking(player, &entity);
+        }
+        true
+    }
+
+    /// Like `spawn_entity`, the item is also kept so Players can pick it up
+    pub fn spawn_item(&self, item: Arc<ItemEntity>) -> bool {
+        let entity_id = item.entity.entity_id;
+        // Viewers get the item together with the entity
+        self.items.lock().insert(entity_id, item.clone());
+        if !self.spawn_entity(item.entity.clone()) {
+            self.items.lock().remove(&entity_id);
+            return false;
+        }
+        true
+    }
+
+    /// Like `spawn_item`, for experience orbs
+    pub fn spawn_experience_orb(&self, orb: Arc<ExperienceOrb>) -> bool {
+        let entity_id = orb.entity.entity_id;
+        self.experience_orbs.lock().insert(entity_id, orb.clone());
+        if !self.spawn_entity(orb.entity.clone()) {
+            self.experience_orbs.lock().remove(&entity_id);
+            return false;
+        }
+        true
+    }
+
+    /// Gets an entity which isn't a Player by entity id
+    pub fn get_entity(&self, id: EntityId) -> Option<Arc<Entity>> {
+        self.entities.lock().get(&id).cloned()
     }
 
     pub fn remove_entity(&self, entity: &Entity) {
+        if self.entities.lock().remove(&entity.entity_id).is_some() {
+            let chunk_pos = entity.chunk_pos.load();
+            self.entity_counts.lock().remove(chunk_pos);
+            self.level.mark_chunk_dirty(chunk_pos);
+            self.items.lock().remove(&entity.entity_id);
+            self.experience_orbs.lock().remove(&entity.entity_id);
+        }
+        for player in self.current_players.lock().values() {
+            player.tracked_entities.lock().remove(&entity.entity_id);
+        }
         self.broadcast_packet_all(&CRemoveEntities::new(&[entity.entity_id.into()]))
     }
 }
+
+/// The tab list entry of the Player, sent with the add player and update listed actions
+fn tab_list_entry(player: &Player) -> pumpkin_protocol::client::play::Player<'_> {
+    let gameprofile = &player.gameprofile;
+    pumpkin_protocol::client::play::Player {
+        uuid: gameprofile.id,
+        actions: vec![
+            PlayerAction::AddPlayer {
+                name: &gameprofile.name,
+                properties: &gameprofile.properties,
+            },
+            PlayerAction::UpdateListed(true),
+        ],
+    }
+}
+
+fn save_player_data(world_folder: &Path, player: &Player) {
+    if let Err(err) = PlayerData::from_player(player).save(world_folder, &player.gameprofile.id) {
+        log::error!(
+            "Couldn't save player data of {}: {}",
+            player.gameprofile.name,
+            err
+        );
+    }
+}
+
+/// Tells the client whether to show the death screen, it respawns by itself when it's hidden
+pub fn respawn_screen_event(immediate_respawn: bool) -> CGameEvent {
+    CGameEvent::new(
+        GameEvent::EnabledRespawnScreen,
+        if immediate_respawn { 1.0 } else { 0.0 },
+    )
+}
+
+/// Like vanilla, the protection is a square of `radius` blocks around the spawn, 0 disables it
+pub fn is_spawn_protected(spawn: (f64, f64, f64), location: &WorldPosition, radius: u32) -> bool {
+    if radius == 0 {
+        return false;
+    }
+    let dx = (location.0.x - spawn.0.floor() as i32).unsigned_abs();
+    let dz = (location.0.z - spawn.2.floor() as i32).unsigned_abs();
+    dx.max(dz) <= radius
+}
+
+/// Queues the fetched chunks for the Player as they arrive, see `player_chunker::send_queued_chunks`.
+///
+/// If fetching stops early or the next chunk takes longer than `timeout`, the missing chunks are sent empty.
+/// Otherwise the client would wait on the "Loading Terrain" screen forever
+async fn queue_chunks(
+    player: &Player,
+    requested: &[Vector2<i32>],
+    mut chunk_receiver: mpsc::Receiver<Result<Arc<ChunkData>, WorldError>>,
+    timeout: Duration,
+) {
+    let closed = || {
+        player
+            .client
+            .closed
+            .load(std::sync::atomic::Ordering::Relaxed)
+    };
+    let mut missing: HashSet<_> = requested.iter().copied().collect();
+    loop {
+        let chunk_data = match tokio::time::timeout(timeout, chunk_receiver.recv()).await {
+            Ok(Some(chunk_data)) => chunk_data,
+            Ok(None) => break,
+            Err(_) => {
+                log::warn!("Fetching chunks took longer than {:?}", timeout);
+                break;
+            }
+        };
+        let chunk_data = match chunk_data {
+            Ok(d) => d,
+            Err(err) => {
+                log::error!("Couldn't fetch a chunk: {}", err);
+                continue;
+            }
+        };
+        #[cfg(debug_assertions)]
+        if chunk_data.position == (0, 0).into() {
+            let len = chunk_packet_size(&chunk_data);
+            log::debug!(
+                "Chunk packet size: {}B {}KB {}MB",
+                len,
+                len / 1024,
+                len / (1024 * 1024)
+            );
+        }
+        // Nobody waits for the chunks of a disconnected Player
+        if closed() {
+            return;
+        }
+        missing.remove(&chunk_data.position);
+        player.chunk_queue.lock().push(chunk_data);
+    }
+    if missing.is_empty() || closed() {
+        return;
+    }
+    log::warn!(
+        "{} chunks couldn't be fetched, sending them empty",
+        missing.len()
+    );
+    let mut chunk_queue = player.chunk_queue.lock();
+    for at in missing {
+        chunk_queue.push(Arc::new(ChunkData::new(ChunkBlocks::default(), at)));
+    }
+}
+
+/// The size of the packet a chunk is sent with in bytes
+pub fn chunk_packet_size(chunk: &ChunkData) -> usize {
+    let mut buf = ByteBuffer::empty();
+    CChunkData(chunk).write(&mut buf);
+    buf.buf().len()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::HashSet,
+        env, fs,
+        io::Read,
+        net::TcpStream,
+        sync::{atomic::Ordering, Arc},
+        time::{Duration, Instant},
+    };
+
+    use bytes::BytesMut;
+    use mio::Token;
+    use pumpkin_config::{BypassLimit, FirstJoinConfig, KitItem, BASIC_CONFIG, LIVE_CONFIG};
+    use pumpkin_core::{
+        math::{position::WorldPosition, vector2::Vector2, vector3::Vector3},
+        GameMode,
+    };
+    use pumpkin_entity::entity_type::EntityType;
+    use pumpkin_protocol::{bytebuf::ByteBuffer, client::play::GameEvent, ConnectionState};
+    use pumpkin_world::{
+        block::{BlockId, BlockState},
+        item::{get_item_protocol_id, ItemStack},
+    };
+    use tokio::sync::mpsc;
+    use uuid::Uuid;
+
+    use super::{
+        entity_tracker, game_rules::GameRule, is_spawn_protected, player_chunker,
+        player_data::PlayerData, queue_chunks,
+    };
+    use crate::{
+        client::disconnect::DisconnectReason,
+        entity::{experience::Experience, id_allocator::EntityIdAllocator, player::Player, Entity},
+        server::mutes::unix_now,
+        test_helpers::{
+            client, connected_client, new_player, player_of, read_packet, set_profile, world,
+            world_in,
+        },
+    };
+
+    fn starter_kit() -> FirstJoinConfig {
+        FirstJoinConfig {
+            welcome: "Welcome {player}".to_string(),
+            starter_kit: vec![
+                KitItem {
+                    item: "minecraft:bread".to_string(),
+                    count: 8,
+                },
+                KitItem {
+                    item: "minecraft:not_an_item".to_string(),
+                    count: 1,
+                },
+                KitItem {
+                    item: "minecraft:stone_sword".to_string(),
+                    count: 1,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn test_first_join() {
+        let folder = env::temp_dir().join(format!("pumpkin-first-join-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let player = new_player(&world);
+
+        world.welcome_player(&player, true, &starter_kit());
+        let mut inventory = player.inventory.lock();
+        // The hotbar is filled first, unknown items are skipped
+        let bread = ItemStack::new(8, get_item_protocol_id("minecraft:bread").unwrap());
+        let sword = ItemStack::new(1, get_item_protocol_id("minecraft:stone_sword").unwrap());
+        assert_eq!(inventory.get_slot(36).unwrap().as_ref(), Some(&bread));
+        assert_eq!(inventory.get_slot(37).unwrap().as_ref(), Some(&sword));
+        assert!(inventory.get_slot(38).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_returning_join() {
+        let folder = env::temp_dir().join(format!("pumpkin-returning-join-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let player = new_player(&world);
+
+        world.welcome_player(&player, false, &starter_kit());
+        let inventory = player.inventory.lock();
+        assert!(inventory.slots().iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_teleport_to_spawn() {
+        let folder = env::temp_dir().join(format!("pumpkin-spawn-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let player = new_player(&world);
+        player.teleport(100.0, 70.0, -40.0, 0.0, 0.0);
+
+        assert!(world.teleport_to_spawn(&player));
+        let spawn = world.spawn_location();
+        let position = player.entity.pos.load();
+        assert_eq!((position.x, position.y, position.z), spawn.position);
+        assert_eq!(player.entity.yaw.load(), spawn.yaw);
+    }
+
+    #[test]
+    fn test_spawn_protection() {
+        let spawn = (10.0, 120.0, 10.0);
+        let at = |x, z| WorldPosition(Vector3::new(x, 64, z));
+        assert!(is_spawn_protected(spawn, &at(10, 10), 16));
+        assert!(is_spawn_protected(spawn, &at(26, -6), 16));
+        assert!(!is_spawn_protected(spawn, &at(27, 10), 16));
+        assert!(!is_spawn_protected(spawn, &at(10, 10), 0));
+    }
+
+    #[test]
+    fn test_op_bypass() {
+        let folder = env::temp_dir().join(format!("pumpkin-op-bypass-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let player = new_player(&world);
+        let limits = [
+            BypassLimit::SpawnProtection,
+            BypassLimit::MaxPlayers,
+            BypassLimit::CommandCooldowns,
+            BypassLimit::ChatFlood,
+        ];
+        for limit in limits {
+            assert!(!player.can_bypass(limit), "{:?}", limit);
+        }
+        player.permission_lvl.store(4, Ordering::Relaxed);
+        for limit in limits {
+            assert!(player.can_bypass(limit), "{:?}", limit);
+        }
+    }
+
+    #[test]
+    fn test_respawn_waits_for_client() {
+        let folder = env::temp_dir().join(format!("pumpkin-respawn-wait-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let player = new_player(&world);
+        world.add_player(Token(1), player.clone());
+        world.game_rules.lock().do_immediate_respawn = Some(false);
+
+        assert!(player.damage(100.0));
+        assert!(!player.respawns_immediately());
+        assert!(world.players_to_respawn().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_immediate_respawn() {
+        let folder = env::temp_dir().join(format!("pumpkin-respawn-now-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let player = new_player(&world);
+        world.add_player(Token(1), player.clone());
+        assert!(world.set_game_rule(GameRule::DoImmediateRespawn, "true"));
+        assert!(!player.respawns_immediately());
+
+        assert!(player.damage(100.0));
+        assert!(player.respawns_immediately());
+        assert_eq!(world.players_to_respawn().len(), 1);
+
+        // The next tick respawns the Player without a request from the client
+        world.tick(&EntityIdAllocator::new(2));
+        tokio::task::yield_now().await;
+        assert_eq!(player.entity.health.load(), 20.0);
+        assert!(world.players_to_respawn().is_empty());
+    }
+
+    #[test]
+    fn test_immediate_respawn_game_rule() {
+        let world = world("respawn-rule");
+        let (client, mut remote) = connected_client(Token(1), ConnectionState::Play);
+        let player = player_of(&world, client, 2, "Steve");
+        world.add_player(Token(1), player);
+        drain(&mut remote);
+
+        assert!(world.set_game_rule(GameRule::DoImmediateRespawn, "true"));
+        assert!(world.immediate_respawn());
+        // The death screen is hidden right away
+        let (id, mut bytebuf) = read_packet(&mut remote);
+        assert_eq!(id, 0x22);
+        assert_eq!(
+            bytebuf.get_u8().unwrap(),
+            GameEvent::EnabledRespawnScreen as u8
+        );
+        assert_eq!(bytebuf.get_f32().unwrap(), 1.0);
+
+        assert!(!world.set_game_rule(GameRule::DoImmediateRespawn, "soon"));
+        assert!(world.set_game_rule(GameRule::DoImmediateRespawn, "default"));
+        assert_eq!(world.immediate_respawn(), BASIC_CONFIG.immediate_respawn);
+    }
+
+    #[tokio::test]
+    async fn test_mob_spawning_game_rule() {
+        let world = world("spawning-rule");
+        let player = new_player(&world);
+        world.add_player(Token(1), player.clone());
+        player.entity.set_pos(8.5, 100.0, 8.5);
+        world.load_chunk(Vector2::new(0, 0)).await;
+        let entity_ids = EntityIdAllocator::new(10);
+        let players = [player];
+
+        assert!(world.set_game_rule(GameRule::DoMobSpawning, "false"));
+        assert_eq!(world.spawn_mobs(&players, &entity_ids, 0), 0);
+        assert!(world.entities.lock().is_empty());
+
+        assert!(world.set_game_rule(GameRule::DoMobSpawning, "true"));
+        assert!(world.spawn_mobs(&players, &entity_ids, 0) > 0);
+        assert!(!world.entities.lock().is_empty());
+    }
+
+    #[test]
+    fn test_worlds_tick_at_their_own_rate() {
+        let lobby_folder = env::temp_dir().join(format!("pumpkin-lobby-{}", Uuid::new_v4()));
+        let game_folder = env::temp_dir().join(format!("pumpkin-game-{}", Uuid::new_v4()));
+        let lobby = world_in(&lobby_folder);
+        let game = world_in(&game_folder);
+        lobby.set_tick_rate(10.0);
+        game.set_tick_rate(20.0);
+
+        let entity_ids = EntityIdAllocator::new(2);
+        let start = Instant::now();
+        for i in 0..40 {
+            let now = start + Duration::from_millis(50) * i;
+            lobby.tick_scheduled(now, &entity_ids);
+            game.tick_scheduled(now, &entity_ids);
+        }
+        assert_eq!(lobby.time.lock().world_age, 20);
+        assert_eq!(game.time.lock().world_age, 40);
+    }
+
+    #[tokio::test]
+    async fn test_edit_sign() {
+        let folder = env::temp_dir().join(format!("pumpkin-sign-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let position = WorldPosition(Vector3::new(3, 100, 5));
+        world.load_chunk(Vector2::new(0, 0)).await;
+        let sign = BlockState::new("minecraft:oak_sign", None).unwrap();
+        world.set_block(position, sign.into());
+
+        let (client, mut remote) = connected_client(Token(1), ConnectionState::Play);
+        let player = player_of(&world, client, 2, "Steve");
+        world.add_player(Token(1), player);
+        assert!(world.edit_sign(position, true, &["Hello".to_string()]));
+        let sign = world.get_block_entity(position).unwrap();
+        assert_eq!(sign.sign_messages(true)[0], r#"{"text":"Hello"}"#);
+
+        // The new text is sent to every Player
+        remote
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut bytes = vec![0; 1024];
+        let read = remote.read(&mut bytes).unwrap();
+        let mut bytebuf = ByteBuffer::new(BytesMut::from(&bytes[..read]));
+        bytebuf.get_var_int().unwrap();
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0x07);
+        assert_eq!(bytebuf.get_i64().unwrap(), position.as_long());
+        assert_eq!(
+            bytebuf.get_var_int().unwrap().0,
+            sign.type_id().unwrap() as i32
+        );
+
+        // Breaking the sign removes its text
+        world.set_block(position, Default::default());
+        assert!(world.get_block_entity(position).is_none());
+        assert!(!world.edit_sign(position, true, &["Hello".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn test_login_times() {
+        let folder = env::temp_dir().join(format!("pumpkin-login-times-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let player = new_player(&world);
+        let before = unix_now();
+        world.add_player(Token(1), player.clone());
+        let times = *player.login_times.lock();
+        assert!(times.last_login.is_some_and(|time| time >= before));
+        assert_eq!(times.first_login, times.last_login);
+        assert_eq!(times.last_seen, None);
+
+        assert!(player.mark_removed());
+        player.remove().await;
+        let saved = PlayerData::load(&folder, &player.gameprofile.id)
+            .unwrap()
+            .unwrap()
+            .login_times;
+        assert!(saved.last_seen.is_some_and(|time| time >= before));
+        assert_eq!(saved.first_login, times.first_login);
+        assert_eq!(saved.last_login, times.last_login);
+    }
+
+    #[test]
+    fn test_vanish() {
+        let folder = env::temp_dir().join(format!("pumpkin-vanish-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let staff = player_of(&world, client(Token(1), ConnectionState::Play), 2, "Steve");
+        let other = player_of(&world, client(Token(2), ConnectionState::Play), 3, "Steve");
+        world.add_player(Token(1), staff.clone());
+        world.add_player(Token(2), other.clone());
+        entity_tracker::update_player(&world, &staff);
+        assert!(other.tracked_entities.lock().contains(&2));
+
+        assert!(world.set_vanished(&staff, true));
+        assert!(!world.set_vanished(&staff, true));
+        assert!(!other.tracked_entities.lock().contains(&2));
+        // Moving around doesn't reveal them
+        entity_tracker::update_player(&world, &staff);
+        assert!(!other.tracked_entities.lock().contains(&2));
+        // They still see everyone else
+        assert!(staff.tracked_entities.lock().contains(&3));
+
+        assert!(world.set_vanished(&staff, false));
+        assert!(other.tracked_entities.lock().contains(&2));
+    }
+
+    #[test]
+    fn test_pvp_game_rule() {
+        let world = world("pvp-rule");
+        let config = LIVE_CONFIG.load().pvp.enabled;
+        assert_eq!(world.is_pvp_enabled(), config);
+
+        assert!(world
+            .game_rules
+            .lock()
+            .set(GameRule::Pvp, &(!config).to_string()));
+        assert_eq!(world.is_pvp_enabled(), !config);
+
+        assert!(world.game_rules.lock().set(GameRule::Pvp, "default"));
+        assert_eq!(world.is_pvp_enabled(), config);
+    }
+
+    #[test]
+    fn test_weather_cycle_game_rule() {
+        let world = world("weather-rule");
+        world.weather.lock().set_weather(0, 1, true, false);
+        assert!(world
+            .game_rules
+            .lock()
+            .set(GameRule::DoWeatherCycle, "false"));
+        let entity_ids = EntityIdAllocator::new(2);
+        for _ in 0..10 {
+            world.tick(&entity_ids);
+        }
+        assert!(world.weather.lock().raining);
+
+        assert!(world
+            .game_rules
+            .lock()
+            .set(GameRule::DoWeatherCycle, "true"));
+        world.tick(&entity_ids);
+        assert!(!world.weather.lock().raining);
+    }
+
+    #[test]
+    fn test_max_entities_per_chunk() {
+        let folder = env::temp_dir().join(format!("pumpkin-entity-limit-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let zombie = |entity_id, x| {
+            let entity = Entity::new(entity_id, Uuid::new_v4(), world.clone(), EntityType::Zombie);
+            entity.set_pos(x, 64.0, 0.0);
+            Arc::new(entity)
+        };
+        let first = zombie(10, 1.0);
+        assert!(world.spawn_entity_limited(first.clone(), 2));
+        assert!(world.spawn_entity_limited(zombie(11, 2.0), 2));
+        // The chunk is full
+        assert!(!world.spawn_entity_limited(zombie(12, 3.0), 2));
+        assert!(world.get_entity(12).is_none());
+        // The next chunk isn't
+        assert!(world.spawn_entity_limited(zombie(13, 17.0), 2));
+
+        first.remove();
+        assert_eq!(world.entity_counts.lock().get(Vector2::new(0, 0)), 1);
+        assert!(world.spawn_entity_limited(zombie(12, 3.0), 2));
+    }
+
+    #[tokio::test]
+    async fn test_entities_mark_their_chunk_dirty() {
+        let folder = env::temp_dir().join(format!("pumpkin-entity-dirty-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        world.load_chunk(Vector2::new(0, 0)).await;
+        world.level.take_dirty_chunks();
+
+        let zombie = Entity::new(10, Uuid::new_v4(), world.clone(), EntityType::Zombie);
+        zombie.set_pos(3.0, 64.0, 3.0);
+        let zombie = Arc::new(zombie);
+        assert!(world.spawn_entity(zombie.clone()));
+        let dirty = world.level.take_dirty_chunks();
+        assert_eq!(dirty.len(), 1);
+        assert_eq!(dirty[0].position, Vector2::new(0, 0));
+
+        zombie.remove();
+        assert_eq!(world.level.take_dirty_chunks().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unload_unwatched_chunks() {
+        let folder = env::temp_dir().join(format!("pumpkin-unload-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let watched = Vector2::new(0, 0);
+        let unwatched = Vector2::new(1, 0);
+        world.load_chunk(watched).await;
+        world.load_chunk(unwatched).await;
+        let stone: BlockId = BlockState::new("minecraft:stone", None).unwrap().into();
+        world.set_block(WorldPosition(Vector3::new(20, 100, 5)), stone);
+
+        let player = new_player(&world);
+        player.loaded_chunks.lock().insert(watched);
+        world.add_player(Token(1), player);
+        world.unload_unwatched_chunks(vec![watched, unwatched]);
+
+        // Unloading happens in the background
+        for _ in 0..500 {
+            if world.level.loaded_chunk_count() == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(world.get_loaded_chunk(watched).is_some());
+        assert!(world.get_loaded_chunk(unwatched).is_none());
+        // The change was saved before the chunk was dropped
+        assert!(folder.join("region").join("r.0.0.mca").exists());
+        world.load_chunk(unwatched).await;
+        assert_eq!(
+            world.get_block(WorldPosition(Vector3::new(20, 100, 5))),
+            Some(stone)
+        );
+
+        fs::remove_dir_all(folder).unwrap();
+    }
+
+    #[test]
+    fn test_close_in_config() {
+        let folder = env::temp_dir().join(format!("pumpkin-close-config-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let client = client(Token(1), ConnectionState::Config);
+        client.close();
+
+        // The Client never joined the World, so there is nothing to clean up
+        assert!(world.closed_players().is_empty());
+        assert!(world.current_players.lock().is_empty());
+        assert!(!folder.exists());
+    }
+
+    #[tokio::test]
+    async fn test_close_in_play() {
+        let folder = env::temp_dir().join(format!("pumpkin-close-play-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let client = Arc::new(client(Token(1), ConnectionState::Play));
+        let uuid = Uuid::new_v4();
+        set_profile(&client, "Steve", uuid);
+        let player = Arc::new(Player::new(
+            client.clone(),
+            world.clone(),
+            2,
+            GameMode::Survival,
+        ));
+        world.add_player(Token(1), player.clone());
+        assert!(world.closed_players().is_empty());
+
+        client.close();
+        assert_eq!(world.closed_players().len(), 1);
+
+        assert!(player.mark_removed());
+        player.remove().await;
+        assert!(world.current_players.lock().is_empty());
+        assert!(folder
+            .join("playerdata")
+            .join(format!("{}.json", uuid))
+            .exists());
+
+        // Closing again must not run the cleanup a second time
+        client.close();
+        assert!(!player.mark_removed());
+
+        fs::remove_dir_all(folder).unwrap();
+    }
+
+    /// Reads everything the Client was sent so far, returns how many bytes that were
+    fn drain(remote: &mut TcpStream) -> usize {
+        remote
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .unwrap();
+        let mut bytes = vec![0; 4096];
+        let mut total = 0;
+        while let Ok(read) = remote.read(&mut bytes) {
+            if read == 0 {
+                break;
+            }
+            total += read;
+        }
+        total
+    }
+
+    #[test]
+    fn test_remove_player_twice() {
+        let folder = env::temp_dir().join(format!("pumpkin-remove-twice-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let (viewer_client, mut viewer_remote) = connected_client(Token(2), ConnectionState::Play);
+        let viewer = player_of(&world, viewer_client, 3, "Steve");
+        world.add_player(Token(2), viewer);
+        let player = new_player(&world);
+        world.add_player(Token(1), player.clone());
+        drain(&mut viewer_remote);
+
+        assert!(world.remove_player(&player));
+        assert_eq!(read_packet(&mut viewer_remote).0, 0x3D);
+        drain(&mut viewer_remote);
+        // Nobody is told twice
+        assert!(!world.remove_player(&player));
+        assert_eq!(drain(&mut viewer_remote), 0);
+        assert_eq!(world.current_players.lock().len(), 1);
+    }
+
+    #[test]
+    fn test_death_drops_experience() {
+        let folder = env::temp_dir().join(format!("pumpkin-death-experience-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let player = new_player(&world);
+        world.add_player(Token(1), player.clone());
+        player.entity.set_pos(3.5, 70.0, -2.5);
+        player.set_experience(Experience::new(352));
+
+        assert!(player.damage(100.0));
+        assert_eq!(player.experience.lock().total, 0);
+        world.tick(&EntityIdAllocator::new(10));
+        let orbs = world.experience_orbs.lock();
+        let orb = orbs.get(&10).unwrap();
+        // 7 points for each of the 16 levels, capped at 100
+        assert_eq!(orb.value.load(Ordering::Relaxed), 100);
+        assert_eq!(orb.entity.pos.load(), Vector3::new(3.5, 70.0, -2.5));
+    }
+
+    #[tokio::test]
+    async fn test_double_disconnect() {
+        let folder = env::temp_dir().join(format!("pumpkin-disconnect-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let player = new_player(&world);
+        world.add_player(Token(1), player.clone());
+
+        assert!(
+            player
+                .disconnect(Some(DisconnectReason::Kicked(None).into()))
+                .await
+        );
+        assert!(player.client.closed.load(Ordering::Relaxed));
+        assert!(world.current_players.lock().is_empty());
+        assert!(folder.join("playerdata").exists());
+        // The second time does nothing
+        assert!(
+            !player
+                .disconnect(Some(DisconnectReason::Kicked(None).into()))
+                .await
+        );
+        assert!(!world.remove_player(&player));
+
+        fs::remove_dir_all(folder).unwrap();
+    }
+
+    /// Skips packets until the next system chat message and returns what it contains
+    fn next_system_message(remote: &mut TcpStream) -> Vec<u8> {
+        loop {
+            let (id, mut bytebuf) = read_packet(remote);
+            if id == 0x6C {
+                return bytebuf.buf().to_vec();
+            }
+        }
+    }
+
+    fn contains(message: &[u8], text: &str) -> bool {
+        message
+            .windows(text.len())
+            .any(|window| window == text.as_bytes())
+    }
+
+    fn queued_positions(player: &Player) -> HashSet<Vector2<i32>> {
+        player
+            .chunk_queue
+            .lock()
+            .take_nearest(Vector2::new(0, 0), usize::MAX)
+            .iter()
+            .map(|chunk| chunk.position)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_missing_chunks_are_sent_empty() {
+        let folder = env::temp_dir().join(format!("pumpkin-missing-chunks-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let player = new_player(&world);
+        let requested = [Vector2::new(0, 0), Vector2::new(1, -2)];
+
+        // Fetching stopped without yielding anything
+        let (sender, receiver) = mpsc::channel(1);
+        drop(sender);
+        queue_chunks(&player, &requested, receiver, Duration::from_secs(5)).await;
+        assert_eq!(queued_positions(&player), requested.into_iter().collect());
+
+        // Fetching takes too long
+        let (_sender, receiver) = mpsc::channel(1);
+        queue_chunks(&player, &requested, receiver, Duration::from_millis(10)).await;
+        assert_eq!(queued_positions(&player), requested.into_iter().collect());
+    }
+
+    #[tokio::test]
+    async fn test_join_sends_center_chunk() {
+        let folder = env::temp_dir().join(format!("pumpkin-join-chunks-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let (client, mut remote) = connected_client(Token(1), ConnectionState::Play);
+        let player = player_of(&world, client, 2, "Steve");
+        player.view_distance_override.store(Some(2));
+        player.entity.set_pos(40.5, 100.0, -20.5);
+
+        player_chunker::player_join(&world, &player).await;
+        let (id, mut bytebuf) = read_packet(&mut remote);
+        assert_eq!(id, 0x54);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 2);
+        assert_eq!(bytebuf.get_var_int().unwrap().0, -2);
+        // Followed by the chunks, the first being sent ends the loading screen
+        player_chunker::send_queued_chunks(&player, 1);
+        let (id, mut bytebuf) = read_packet(&mut remote);
+        assert_eq!(id, 0x27);
+        assert_eq!(bytebuf.get_i32().unwrap(), 2);
+        assert_eq!(bytebuf.get_i32().unwrap(), -2);
+
+        let _ = fs::remove_dir_all(folder);
+    }
+
+    #[tokio::test]
+    async fn test_join_and_leave_messages() {
+        let folder = env::temp_dir().join(format!("pumpkin-join-leave-{}", Uuid::new_v4()));
+        let world = world_in(&folder);
+        let (viewer_client, mut viewer_remote) = connected_client(Token(2), ConnectionState::Play);
+        let viewer = player_of(&world, viewer_client, 3, "Alex");
+        world.add_player(Token(2), viewer);
+        let config = LIVE_CONFIG.load();
+        let messages = &config.messages;
+
+        let player = new_player(&world);
+        player.view_distance_override.store(Some(2));
+        // A returning Player, so no welcome message is broadcast after the join message
+        world.save_player(&player);
+        world.add_player(Token(1), player.clone());
+        world.spawn_player(&BASIC_CONFIG, player.clone()).await;
+        let message = next_system_message(&mut viewer_remote);
+        assert!(contains(&message, &messages.join_message("Steve").unwrap()));
+        assert!(world.remove_player(&player));
+        let message = next_system_message(&mut viewer_remote);
+        assert!(contains(
+            &message,
+            &messages.leave_message("Steve").unwrap()
+        ));
+
+        // Vanished Players come and go silently, they stay vanished through their saved data
+        let vanished = player_of(
+            &world,
+            client(Token(4), ConnectionState::Play),
+            5,
+            "Herobrine",
+        );
+        vanished.view_distance_override.store(Some(2));
+        vanished.vanished.store(true, Ordering::Relaxed);
+        world.save_player(&vanished);
+        vanished.vanished.store(false, Ordering::Relaxed);
+        world.add_player(Token(4), vanished.clone());
+        world.spawn_player(&BASIC_CONFIG, vanished.clone()).await;
+        assert!(vanished.is_vanished());
+        assert!(world.remove_player(&vanished));
+        world.broadcast_message("marker");
+        let message = next_system_message(&mut viewer_remote);
+        assert!(contains(&message, "marker"));
+        assert!(!contains(&message, "Herobrine"));
+
+        let _ = fs::remove_dir_all(folder);
+    }
+}
@@ -0,0 +1,197 @@
+use std::sync::Arc;
+
+use pumpkin_entity::entity_type::EntityType;
+use pumpkin_protocol::{client::play::CSetPassengers, VarInt};
+
+use crate::entity::{player::Player, Entity};
+
+use super::World;
+
+/// How far above the vehicle's position its passengers sit
+pub fn passenger_offset(vehicle: &EntityType) -> f64 {
+    match vehicle {
+        EntityType::Player => 1.8,
+        EntityType::Zombie => 1.95,
+        // A placeholder until boats exist as entities
+        EntityType::Boat => 0.1875,
+        EntityType::Item => 0.25,
+        EntityType::ExperienceOrb => 0.5,
+        EntityType::Pig => 0.8625,
+    }
+}
+
+/// Whether the first passenger steers the vehicle with `SMoveVehicle`, other vehicles move by themselves
+pub fn is_steered_by_passenger(vehicle: &EntityType) -> bool {
+    matches!(vehicle, EntityType::Boat)
+}
+
+/// Tells every Player who rides the vehicle now
+fn send_passengers(world: &World, vehicle: &Entity) {
+    let passengers: Vec<VarInt> = vehicle
+        .passengers
+        .lock()
+        .iter()
+        .map(|id| VarInt(*id))
+        .collect();
+    world.broadcast_packet_all(&CSetPassengers::new(vehicle.entity_id.into(), &passengers));
+}
+
+/// Whether the entity rides the vehicle, directly or through other passengers
+fn rides_on(world: &World, entity: &Entity, vehicle: &Entity) -> bool {
+    let mut current = entity.vehicle.load();
+    while let Some(id) = current {
+        if id == vehicle.entity_id {
+            return true;
+        }
+        current = world
+            .get_player_by_entityid(id)
+            .and_then(|player| player.entity.vehicle.load());
+    }
+    false
+}
+
+/// Lets the passenger ride the vehicle, leaving the vehicle it was riding before.
+///
+/// Returns false if the passenger would ride itself or one of its own passengers
+pub fn mount(world: &World, passenger: &Player, vehicle: &Player) -> bool {
+    let (passenger_entity, vehicle_entity) = (&passenger.entity, &vehicle.entity);
+    if passenger_entity.entity_id == vehicle_entity.entity_id
+        || rides_on(world, vehicle_entity, passenger_entity)
+    {
+        return false;
+    }
+    dismount(world, passenger);
+    passenger_entity
+        .vehicle
+        .store(Some(vehicle_entity.entity_id));
+    vehicle_entity
+        .passengers
+        .lock()
+        .push(passenger_entity.entity_id);
+    send_passengers(world, vehicle_entity);
+    move_passengers(world, vehicle);
+    true
+}
+
+/// Lets the passenger leave its vehicle, it stays where it was sitting.
+///
+/// Returns false if it wasn't riding anything
+pub fn dismount(world: &World, passenger: &Player) -> bool {
+    let entity = &passenger.entity;
+    let Some(vehicle_id) = entity.vehicle.swap(None) else {
+        return false;
+    };
+    if let Some(vehicle) = world.get_player_by_entityid(vehicle_id) {
+        vehicle
+            .entity
+            .passengers
+            .lock()
+            .retain(|id| *id != entity.entity_id);
+        send_passengers(world, &vehicle.entity);
+    }
+    // The client only knew where the vehicle was
+    let pos = entity.pos.load();
+    passenger.teleport(pos.x, pos.y, pos.z, entity.yaw.load(), entity.pitch.load());
+    true
+}
+
+/// Lets every passenger leave the vehicle, e.g. when it leaves the World
+pub fn eject_passengers(world: &World, vehicle: &Player) {
+    let passengers = vehicle.entity.passengers.lock().clone();
+    for id in passengers {
+        if let Some(passenger) = world.get_player_by_entityid(id) {
+            dismount(world, &passenger);
+        }
+    }
+}
+
+/// Moves the passengers, and their passengers, along with the vehicle.
+///
+/// Returns the moved passengers, their chunks may have to be updated
+pub fn move_passengers(world: &World, vehicle: &Player) -> Vec<Arc<Player>> {
+    let mut moved = Vec::new();
+    let mut vehicles = vec![(
+        vehicle.entity.pos.load(),
+        passenger_offset(&vehicle.entity.entity_type),
+        vehicle.entity.passengers.lock().clone(),
+    )];
+    while let Some((pos, offset, passengers)) = vehicles.pop() {
+        for id in passengers {
+            let Some(passenger) = world.get_player_by_entityid(id) else {
+                continue;
+            };
+            let entity = &passenger.entity;
+            entity.set_pos(pos.x, pos.y + offset, pos.z);
+            vehicles.push((
+                entity.pos.load(),
+                passenger_offset(&entity.entity_type),
+                entity.passengers.lock().clone(),
+            ));
+            moved.push(passenger);
+        }
+    }
+    moved
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use mio::Token;
+    use pumpkin_entity::EntityId;
+    use pumpkin_protocol::ConnectionState;
+
+    use super::{dismount, eject_passengers, mount, move_passengers};
+    use crate::{
+        entity::player::Player,
+        test_helpers::{client, player_of, world},
+        world::World,
+    };
+
+    fn joined_player(world: &Arc<World>, entity_id: EntityId) -> Arc<Player> {
+        let token = Token(entity_id as usize);
+        let client = client(token, ConnectionState::Play);
+        let player = player_of(world, client, entity_id, &format!("Player{}", entity_id));
+        world.add_player(token, player.clone());
+        player
+    }
+
+    #[test]
+    fn test_ride_player() {
+        let world = world("mount");
+        let (steve, alex) = (joined_player(&world, 2), joined_player(&world, 3));
+        alex.entity.set_pos(10.0, 64.0, -5.0);
+
+        assert!(mount(&world, &steve, &alex));
+        assert_eq!(steve.entity.vehicle.load(), Some(3));
+        assert_eq!(*alex.entity.passengers.lock(), vec![2]);
+        // The passenger sits on top of the vehicle and follows it
+        let pos = steve.entity.pos.load();
+        assert_eq!((pos.x, pos.y, pos.z), (10.0, 65.8, -5.0));
+        alex.entity.set_pos(12.0, 64.0, -5.0);
+        assert_eq!(move_passengers(&world, &alex).len(), 1);
+        assert_eq!(steve.entity.pos.load().x, 12.0);
+
+        // Nobody can ride themselves or their own passengers
+        assert!(!mount(&world, &alex, &alex));
+        assert!(!mount(&world, &alex, &steve));
+    }
+
+    #[test]
+    fn test_dismount() {
+        let world = world("mount");
+        let (steve, alex) = (joined_player(&world, 2), joined_player(&world, 3));
+        assert!(mount(&world, &steve, &alex));
+
+        assert!(dismount(&world, &steve));
+        assert!(!steve.entity.is_riding());
+        assert!(alex.entity.passengers.lock().is_empty());
+        assert!(!dismount(&world, &steve));
+
+        // A vehicle leaving takes nobody with it
+        assert!(mount(&world, &steve, &alex));
+        eject_passengers(&world, &alex);
+        assert!(!steve.entity.is_riding());
+        assert!(alex.entity.passengers.lock().is_empty());
+    }
+}
@@ -1,98 +1,173 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
 use pumpkin_config::BASIC_CONFIG;
 use pumpkin_core::math::{
     get_section_cord, position::WorldPosition, vector2::Vector2, vector3::Vector3,
 };
-use pumpkin_protocol::client::play::{CCenterChunk, CUnloadChunk};
-use pumpkin_world::cylindrical_chunk_iterator::Cylindrical;
+use pumpkin_protocol::client::play::{CCenterChunk, CChunkData, CUnloadChunk};
+use pumpkin_world::{chunk::ChunkData, cylindrical_chunk_iterator::Cylindrical};
 
 use crate::entity::{player::Player, Entity};
 
-use super::World;
+use super::{entity_tracker, World};
 
-fn get_view_distance(player: &Player) -> i8 {
-    player
-        .config
-        .lock()
-        .view_distance
-        .clamp(2, BASIC_CONFIG.view_distance as i8)
+/// The view distance set with `/viewdistance` wins over the one the client asked for, both are
+/// capped by the server
+fn effective_view_distance(requested: i8, view_distance_override: Option<i8>, max: i8) -> i8 {
+    view_distance_override.unwrap_or(requested).clamp(2, max)
 }
 
-pub async fn player_join(world: &World, player: Arc<Player>) {
-    let new_watched = chunk_section_from_pos(&player.entity.block_pos.load());
-    player.watched_section.store(new_watched);
-    let watched_section = new_watched;
-    let chunk_pos = player.entity.chunk_pos.load();
-    player.client.send_packet(&CCenterChunk {
-        chunk_x: chunk_pos.x.into(),
-        chunk_z: chunk_pos.z.into(),
-    });
-    let view_distance = get_view_distance(&player) as i32;
-    dbg!(view_distance);
-    let old_cylindrical = Cylindrical::new(
-        Vector2::new(watched_section.x, watched_section.z),
-        view_distance,
-    );
-    let new_cylindrical = Cylindrical::new(Vector2::new(chunk_pos.x, chunk_pos.z), view_distance);
-    let mut loading_chunks = Vec::new();
-    Cylindrical::for_each_changed_chunk(
-        old_cylindrical,
-        new_cylindrical,
-        |chunk_pos| {
-            loading_chunks.push(chunk_pos);
-        },
-        |chunk_pos| {
-            player
-                .client
-                .send_packet(&CUnloadChunk::new(chunk_pos.x, chunk_pos.z));
-        },
-        true,
+pub fn get_view_distance(player: &Player) -> i8 {
+    effective_view_distance(
+        player.config.lock().view_distance,
+        player.view_distance_override.load(),
+        BASIC_CONFIG.view_distance as i8,
+    )
+}
+
+/// Updates the chunks the client has to the ones in `cylindrical`.
+///
+/// Returns the chunks which have to be sent and the ones which left the cylinder, nearest first
+fn chunk_delta(
+    loaded: &mut HashSet<Vector2<i32>>,
+    cylindrical: &Cylindrical,
+) -> (Vec<Vector2<i32>>, Vec<Vector2<i32>>) {
+    let within = |pos: &Vector2<i32>| cylindrical.is_within_distance(pos.x, pos.z);
+    let unloading: Vec<_> = loaded.iter().filter(|pos| !within(*pos)).copied().collect();
+    loaded.retain(within);
+
+    let center = cylindrical.center;
+    let range = cylindrical.view_distance + 1;
+    let mut loading = Vec::new();
+    for x in center.x - range..=center.x + range {
+        for z in center.z - range..=center.z + range {
+            let pos = Vector2::new(x, z);
+            if within(&pos) && loaded.insert(pos) {
+                loading.push(pos);
+            }
+        }
+    }
+    loading.sort_by_key(|pos| (pos.x - center.x).pow(2) + (pos.z - center.z).pow(2));
+    (loading, unloading)
+}
+
+/// Unloads the chunks which left the view of the Player and sends the ones which entered it
+async fn send_chunk_delta(world: &World, player: &Player, center: Vector2<i32>) {
+    let view_distance = get_view_distance(player) as i32;
+    let (loading_chunks, unloading_chunks) = chunk_delta(
+        &mut player.loaded_chunks.lock(),
+        &Cylindrical::new(center, view_distance),
     );
+    for chunk_pos in &unloading_chunks {
+        player
+            .client
+            .send_packet(&CUnloadChunk::new(chunk_pos.x, chunk_pos.z));
+    }
+    world.unload_unwatched_chunks(unloading_chunks);
     if !loading_chunks.is_empty() {
         world
-            .spawn_world_chunks(&player.client, loading_chunks, view_distance)
+            .spawn_world_chunks(player, loading_chunks, view_distance)
             .await;
     }
 }
 
+pub async fn player_join(world: &World, player: &Player) {
+    player
+        .watched_section
+        .store(chunk_section_from_pos(&player.entity.block_pos.load()));
+    let chunk_pos = player.entity.chunk_pos.load();
+    player.client.send_packet(&CCenterChunk {
+        chunk_x: chunk_pos.x.into(),
+        chunk_z: chunk_pos.z.into(),
+    });
+    // The client starts without any chunks, also after respawning
+    player.loaded_chunks.lock().clear();
+    player.chunk_queue.lock().clear();
+    send_chunk_delta(world, player, chunk_pos).await;
+}
+
 pub async fn update_position(entity: &Entity, player: &Player) {
     let current_watched = player.watched_section.load();
     let new_watched = chunk_section_from_pos(&entity.block_pos.load());
     if current_watched != new_watched {
-        let chunk_pos = entity.chunk_pos.load();
-        player.client.send_packet(&CCenterChunk {
-            chunk_x: chunk_pos.x.into(),
-            chunk_z: chunk_pos.z.into(),
+        player.watched_section.store(new_watched);
+        entity_tracker::update_player(&entity.world, player);
+        // Moving up or down a section doesn't move the chunk the client centers on
+        if current_watched.x != new_watched.x || current_watched.z != new_watched.z {
+            let chunk_pos = entity.chunk_pos.load();
+            player.client.send_packet(&CCenterChunk {
+                chunk_x: chunk_pos.x.into(),
+                chunk_z: chunk_pos.z.into(),
+            });
+            send_chunk_delta(&entity.world, player, chunk_pos).await;
+        }
+    }
+}
+
+/// Loads and unloads chunks around the Player after its view distance changed
+pub async fn update_view_distance(player: &Player, old_view_distance: i32) {
+    let view_distance = get_view_distance(player) as i32;
+    if view_distance == old_view_distance {
+        return;
+    }
+    entity_tracker::update_player(&player.entity.world, player);
+    let watched_section = player.watched_section.load();
+    let center = Vector2::new(watched_section.x, watched_section.z);
+    send_chunk_delta(&player.entity.world, player, center).await;
+}
+
+/// The fetched chunks waiting to be sent to a Player, so joining doesn't send hundreds at once
+#[derive(Default)]
+pub struct ChunkQueue {
+    chunks: Vec<Arc<ChunkData>>,
+}
+
+impl ChunkQueue {
+    pub fn push(&mut self, chunk: Arc<ChunkData>) {
+        self.chunks.push(chunk);
+    }
+
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// Takes up to `max` chunks, the ones nearest to `center` first
+    pub fn take_nearest(&mut self, center: Vector2<i32>, max: usize) -> Vec<Arc<ChunkData>> {
+        // Farthest first, so the nearest can be split off the end
+        self.chunks.sort_by_key(|chunk| {
+            let pos = chunk.position;
+            std::cmp::Reverse((pos.x - center.x).pow(2) + (pos.z - center.z).pow(2))
         });
+        let at = self.chunks.len().saturating_sub(max);
+        let mut nearest = self.chunks.split_off(at);
+        nearest.reverse();
+        nearest
+    }
+}
 
-        let view_distance = get_view_distance(player) as i32;
-        let old_cylindrical = Cylindrical::new(
-            Vector2::new(current_watched.x, current_watched.z),
-            view_distance,
-        );
-        let new_cylindrical =
-            Cylindrical::new(Vector2::new(chunk_pos.x, chunk_pos.z), view_distance);
-        player.watched_section.store(new_watched);
-        let mut loading_chunks = Vec::new();
-        Cylindrical::for_each_changed_chunk(
-            old_cylindrical,
-            new_cylindrical,
-            |chunk_pos| {
-                loading_chunks.push(chunk_pos);
-            },
-            |chunk_pos| {
-                player
-                    .client
-                    .send_packet(&CUnloadChunk::new(chunk_pos.x, chunk_pos.z));
-            },
-            false,
-        );
-        if !loading_chunks.is_empty() {
-            entity
-                .world
-                .spawn_world_chunks(&player.client, loading_chunks, view_distance)
-                .await;
+/// Sends the Player up to `max` of its queued chunks, called every tick.
+///
+/// Chunks which left the view while waiting are dropped, the queue of a disconnected Player is cleared
+pub fn send_queued_chunks(player: &Player, max: usize) {
+    if player
+        .client
+        .closed
+        .load(std::sync::atomic::Ordering::Relaxed)
+    {
+        player.chunk_queue.lock().clear();
+        return;
+    }
+    let chunks = player
+        .chunk_queue
+        .lock()
+        .take_nearest(player.entity.chunk_pos.load(), max);
+    if chunks.is_empty() {
+        return;
+    }
+    let loaded_chunks = player.loaded_chunks.lock();
+    for chunk in chunks {
+        if loaded_chunks.contains(&chunk.position) {
+            player.client.send_packet(&CChunkData(&chunk));
         }
     }
 }
@@ -105,3 +180,203 @@ fn chunk_section_from_pos(block_pos: &WorldPosition) -> Vector3<i32> {
         get_section_cord(block_pos.z),
     )
 }
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, io::Read, net::TcpStream, sync::Arc, time::Duration};
+
+    use bytes::BytesMut;
+    use mio::Token;
+    use pumpkin_core::math::vector2::Vector2;
+    use pumpkin_protocol::{bytebuf::ByteBuffer, ConnectionState};
+    use pumpkin_world::{
+        chunk::{ChunkBlocks, ChunkData},
+        cylindrical_chunk_iterator::Cylindrical,
+    };
+
+    use super::{
+        chunk_delta, chunk_section_from_pos, effective_view_distance, send_queued_chunks,
+        update_position, ChunkQueue,
+    };
+    use crate::{
+        entity::player::Player,
+        test_helpers::{connected_client, player_of, world},
+    };
+
+    /// A Player standing in the chunk 0 0, but with no chunks sent yet
+    fn player() -> (Arc<Player>, TcpStream) {
+        let world = world("center-chunk");
+        let (client, remote) = connected_client(Token(1), ConnectionState::Play);
+        let player = player_of(&world, client, 2, "Steve");
+        player.view_distance_override.store(Some(2));
+        player.entity.set_pos(8.5, 100.0, 8.5);
+        player
+            .watched_section
+            .store(chunk_section_from_pos(&player.entity.block_pos.load()));
+        (player, remote)
+    }
+
+    /// Reads the id of the next packet and its first two VarInts
+    fn read_center_chunk(remote: &mut TcpStream) -> (i32, i32, i32) {
+        remote
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut bytes = vec![0; 16];
+        let read = remote.read(&mut bytes).unwrap();
+        let mut bytebuf = ByteBuffer::new(BytesMut::from(&bytes[..read]));
+        bytebuf.get_var_int().unwrap();
+        (
+            bytebuf.get_var_int().unwrap().0,
+            bytebuf.get_var_int().unwrap().0,
+            bytebuf.get_var_int().unwrap().0,
+        )
+    }
+
+    /// Reads the position of the next chunk packet
+    fn read_chunk(remote: &mut TcpStream) -> (i32, i32) {
+        remote
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut length = 0;
+        for i in 0..5 {
+            let mut byte = [0];
+            remote.read_exact(&mut byte).unwrap();
+            length |= i32::from(byte[0] & 0x7F) << (7 * i);
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+        }
+        let mut bytes = vec![0; length as usize];
+        remote.read_exact(&mut bytes).unwrap();
+        let mut bytebuf = ByteBuffer::new(BytesMut::from(&bytes[..]));
+        assert_eq!(bytebuf.get_var_int().unwrap().0, 0x27);
+        (bytebuf.get_i32().unwrap(), bytebuf.get_i32().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_crossing_chunks_sends_center() {
+        let (player, mut remote) = player();
+        // Going down a section stays in the same chunk column
+        player.entity.set_pos(8.5, 40.0, 8.5);
+        update_position(&player.entity, &player).await;
+        assert_eq!(player.watched_section.load().y, 2);
+
+        player.entity.set_pos(20.5, 40.0, -3.5);
+        update_position(&player.entity, &player).await;
+        // The first packet is the new center, nothing was sent for the section change
+        assert_eq!(read_center_chunk(&mut remote), (0x54, 1, -1));
+    }
+
+    #[test]
+    fn test_effective_view_distance() {
+        assert_eq!(effective_view_distance(8, None, 10), 8);
+        assert_eq!(effective_view_distance(16, None, 10), 10);
+        assert_eq!(effective_view_distance(0, None, 10), 2);
+        // The override ignores what the client asked for, but not the server max
+        assert_eq!(effective_view_distance(8, Some(4), 10), 4);
+        assert_eq!(effective_view_distance(4, Some(12), 10), 10);
+        assert_eq!(effective_view_distance(16, Some(12), 32), 12);
+    }
+
+    #[test]
+    fn test_chunk_delta() {
+        let mut loaded = HashSet::new();
+        let (loading, unloading) =
+            chunk_delta(&mut loaded, &Cylindrical::new(Vector2::new(0, 0), 2));
+        assert!(unloading.is_empty());
+        assert_eq!(loading[0], Vector2::new(0, 0));
+        assert_eq!(loading.len(), loaded.len());
+        // Nothing is sent twice
+        let (loading, unloading) =
+            chunk_delta(&mut loaded, &Cylindrical::new(Vector2::new(0, 0), 2));
+        assert!(loading.is_empty() && unloading.is_empty());
+
+        // Moving by one chunk only sends the column ring which was entered
+        let mut entered = HashSet::new();
+        let mut exited = HashSet::new();
+        Cylindrical::for_each_changed_chunk(
+            Cylindrical::new(Vector2::new(0, 0), 2),
+            Cylindrical::new(Vector2::new(1, 0), 2),
+            |pos| {
+                entered.insert(pos);
+            },
+            |pos| {
+                exited.insert(pos);
+            },
+            false,
+        );
+        let (loading, unloading) =
+            chunk_delta(&mut loaded, &Cylindrical::new(Vector2::new(1, 0), 2));
+        assert_eq!(loading.into_iter().collect::<HashSet<_>>(), entered);
+        assert_eq!(unloading.into_iter().collect::<HashSet<_>>(), exited);
+        assert!(entered.iter().all(|pos| pos.x == 3));
+        assert!(exited.iter().all(|pos| pos.x == -2));
+        assert!(entered.iter().all(|pos| loaded.contains(pos)));
+        assert!(exited.iter().all(|pos| !loaded.contains(pos)));
+    }
+
+    fn queue(positions: &[(i32, i32)]) -> ChunkQueue {
+        let mut queue = ChunkQueue::default();
+        for &(x, z) in positions {
+            queue.push(Arc::new(ChunkData::new(
+                ChunkBlocks::default(),
+                Vector2::new(x, z),
+            )));
+        }
+        queue
+    }
+
+    fn positions(chunks: &[Arc<ChunkData>]) -> Vec<(i32, i32)> {
+        chunks
+            .iter()
+            .map(|chunk| (chunk.position.x, chunk.position.z))
+            .collect()
+    }
+
+    #[test]
+    fn test_chunk_queue() {
+        let mut queue = queue(&[(3, 0), (0, 0), (-1, 1), (0, -2), (5, 5)]);
+        let center = Vector2::new(0, 0);
+        // Never more than the cap per tick
+        assert_eq!(positions(&queue.take_nearest(center, 2)), [(0, 0), (-1, 1)]);
+        assert_eq!(positions(&queue.take_nearest(center, 2)), [(0, -2), (3, 0)]);
+        assert_eq!(positions(&queue.take_nearest(center, 2)), [(5, 5)]);
+        assert!(queue.take_nearest(center, 2).is_empty());
+    }
+
+    #[test]
+    fn test_queued_chunks_follow_the_player() {
+        let mut queue = queue(&[(0, 0), (4, 0), (1, 0)]);
+        assert_eq!(
+            positions(&queue.take_nearest(Vector2::new(4, 0), 1)),
+            [(4, 0)]
+        );
+    }
+
+    #[test]
+    fn test_send_queued_chunks() {
+        let (player, mut remote) = player();
+        player.loaded_chunks.lock().extend([
+            Vector2::new(0, 0),
+            Vector2::new(1, 0),
+            Vector2::new(0, 2),
+        ]);
+        *player.chunk_queue.lock() = queue(&[(0, 2), (1, 0), (0, 0), (9, 9)]);
+
+        send_queued_chunks(&player, 2);
+        send_queued_chunks(&player, 2);
+        // Every chunk arrives nearest first, except the one which isn't in view anymore
+        let sent: Vec<_> = (0..3).map(|_| read_chunk(&mut remote)).collect();
+        assert_eq!(sent, [(0, 0), (1, 0), (0, 2)]);
+
+        // Nothing is sent after disconnecting
+        *player.chunk_queue.lock() = queue(&[(0, 0)]);
+        player.client.close();
+        send_queued_chunks(&player, 2);
+        assert!(player
+            .chunk_queue
+            .lock()
+            .take_nearest(Vector2::new(0, 0), 1)
+            .is_empty());
+    }
+}
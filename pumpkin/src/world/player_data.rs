@@ -0,0 +1,274 @@
+use std::{fs, io, path::Path};
+
+use pumpkin_core::GameMode;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::entity::player::Player;
+
+use super::respawn::RespawnPoint;
+
+const PLAYER_DATA_FOLDER: &str = "playerdata";
+
+/// The state of a Player which is kept between sessions
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+pub struct PlayerData {
+    pub position: (f64, f64, f64),
+    pub yaw: f32,
+    pub pitch: f32,
+    pub gamemode: GameMode,
+    pub health: f32,
+    pub food: i32,
+    pub food_saturation: f32,
+    /// Missing in data saved before respawn points existed
+    #[serde(default)]
+    pub respawn_point: Option<RespawnPoint>,
+    #[serde(flatten)]
+    pub login_times: LoginTimes,
+    /// Staff stay hidden after rejoining
+    #[serde(default)]
+    pub vanished: bool,
+    /// The total experience points, missing in older data
+    #[serde(default)]
+    pub experience: u32,
+}
+
+/// When a Player joined and left in seconds since the unix epoch, missing in older data
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct LoginTimes {
+    #[serde(default)]
+    pub first_login: Option<u64>,
+    #[serde(default)]
+    pub last_login: Option<u64>,
+    #[serde(default)]
+    pub last_seen: Option<u64>,
+}
+
+impl LoginTimes {
+    pub fn join(&mut self, now: u64) {
+        self.first_login.get_or_insert(now);
+        self.last_login = Some(now);
+    }
+
+    pub fn leave(&mut self, now: u64) {
+        self.last_seen = Some(now);
+    }
+
+    /// Takes over the times of earlier sessions once the saved data is loaded, keeping the current login
+    pub fn restore(&mut self, saved: LoginTimes) {
+        if saved.first_login.is_some() {
+            self.first_login = saved.first_login;
+        }
+        self.last_seen = saved.last_seen;
+    }
+}
+
+/// The gamemode `Undefined` can't be played in, Players get Survival instead
+pub const fn default_gamemode(configured: GameMode) -> GameMode {
+    match configured {
+        GameMode::Undefined => GameMode::Survival,
+        gamemode => gamemode,
+    }
+}
+
+/// The gamemode a joining Player gets, new Players and every Player when `force_gamemode` is set get the default one
+pub fn join_gamemode(
+    data: Option<&PlayerData>,
+    configured: GameMode,
+    force_gamemode: bool,
+) -> GameMode {
+    match data {
+        Some(data) if !force_gamemode && data.gamemode != GameMode::Undefined => data.gamemode,
+        _ => default_gamemode(configured),
+    }
+}
+
+impl PlayerData {
+    pub fn from_player(player: &Player) -> Self {
+        let entity = &player.entity;
+        let position = entity.pos.load();
+        Self {
+            position: (position.x, position.y, position.z),
+            yaw: entity.yaw.load(),
+            pitch: entity.pitch.load(),
+            gamemode: player.gamemode.load(),
+            health: entity.health.load(),
+            food: player.food.load(std::sync::atomic::Ordering::Relaxed),
+            food_saturation: player.food_saturation.load(),
+            respawn_point: *player.respawn_point.lock(),
+            login_times: *player.login_times.lock(),
+            vanished: player.is_vanished(),
+            experience: player.experience.lock().total,
+        }
+    }
+
+    /// Loads the data from `playerdata/<uuid>.json` in the world folder, `None` if the Player never joined
+    pub fn load(world_folder: &Path, uuid: &Uuid) -> io::Result<Option<Self>> {
+        let path = world_folder
+            .join(PLAYER_DATA_FOLDER)
+            .join(format!("{}.json", uuid));
+        match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content)
+                .map(Some)
+                .map_err(io::Error::other),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Saves the data to `playerdata/<uuid>.json` in the world folder.
+    ///
+    /// The file is replaced at once, so a crash while saving never leaves a broken file behind
+    pub fn save(&self, world_folder: &Path, uuid: &Uuid) -> io::Result<()> {
+        let folder = world_folder.join(PLAYER_DATA_FOLDER);
+        fs::create_dir_all(&folder)?;
+        let content = serde_json::to_string(self).map_err(io::Error::other)?;
+        let temp_path = folder.join(format!("{}.json.tmp", uuid));
+        fs::write(&temp_path, content)?;
+        fs::rename(temp_path, folder.join(format!("{}.json", uuid)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+
+    use pumpkin_core::GameMode;
+    use pumpkin_world::dimension::Dimension;
+    use uuid::Uuid;
+
+    use crate::world::respawn::RespawnPoint;
+
+    use super::{join_gamemode, LoginTimes, PlayerData, PLAYER_DATA_FOLDER};
+
+    fn data(gamemode: GameMode) -> PlayerData {
+        PlayerData {
+            position: (0.0, 64.0, 0.0),
+            yaw: 0.0,
+            pitch: 0.0,
+            gamemode,
+            health: 20.0,
+            food: 20,
+            food_saturation: 5.0,
+            respawn_point: None,
+            login_times: LoginTimes::default(),
+            vanished: false,
+            experience: 0,
+        }
+    }
+
+    #[test]
+    fn test_save() {
+        let world_folder = env::temp_dir().join("pumpkin-player-data-test");
+        let uuid = Uuid::new_v4();
+        let data = PlayerData {
+            position: (1.5, 64.0, -3.5),
+            yaw: 90.0,
+            pitch: 0.0,
+            gamemode: GameMode::Creative,
+            health: 12.0,
+            food: 18,
+            food_saturation: 5.0,
+            respawn_point: Some(RespawnPoint {
+                dimension: Dimension::OverWorld,
+                position: (3, 64, -7),
+                yaw: 180.0,
+                forced: false,
+            }),
+            login_times: LoginTimes {
+                first_login: Some(1_600_000_000),
+                last_login: Some(1_700_000_000),
+                last_seen: Some(1_700_003_600),
+            },
+            vanished: true,
+            experience: 352,
+        };
+        data.save(&world_folder, &uuid).unwrap();
+
+        let path = world_folder
+            .join(PLAYER_DATA_FOLDER)
+            .join(format!("{}.json", uuid));
+        let read: PlayerData = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(read, data);
+        assert_eq!(PlayerData::load(&world_folder, &uuid).unwrap(), Some(data));
+        fs::remove_file(path).unwrap();
+        assert_eq!(PlayerData::load(&world_folder, &uuid).unwrap(), None);
+    }
+
+    #[test]
+    fn test_load_without_respawn_point() {
+        let data: PlayerData = serde_json::from_str(
+            r#"{"position":[0.0,64.0,0.0],"yaw":0.0,"pitch":0.0,"gamemode":"Survival","health":20.0,"food":20,"food_saturation":5.0}"#,
+        )
+        .unwrap();
+        assert_eq!(data.respawn_point, None);
+        assert_eq!(data.login_times, LoginTimes::default());
+        assert!(!data.vanished);
+        assert_eq!(data.experience, 0);
+    }
+
+    #[test]
+    fn test_new_player_gets_default_gamemode() {
+        assert_eq!(
+            join_gamemode(None, GameMode::Creative, false),
+            GameMode::Creative
+        );
+        assert_eq!(
+            join_gamemode(None, GameMode::Undefined, false),
+            GameMode::Survival
+        );
+    }
+
+    #[test]
+    fn test_returning_player_keeps_gamemode() {
+        let data = data(GameMode::Adventure);
+        assert_eq!(
+            join_gamemode(Some(&data), GameMode::Creative, false),
+            GameMode::Adventure
+        );
+    }
+
+    #[test]
+    fn test_force_gamemode() {
+        let data = data(GameMode::Creative);
+        assert_eq!(
+            join_gamemode(Some(&data), GameMode::Survival, true),
+            GameMode::Survival
+        );
+    }
+
+    #[test]
+    fn test_first_login_is_kept() {
+        let mut times = LoginTimes::default();
+        times.join(100);
+        times.leave(150);
+        times.join(200);
+        assert_eq!(
+            times,
+            LoginTimes {
+                first_login: Some(100),
+                last_login: Some(200),
+                last_seen: Some(150),
+            }
+        );
+
+        // A new session starts without the saved times
+        let mut session = LoginTimes::default();
+        session.join(300);
+        session.restore(times);
+        assert_eq!(
+            session,
+            LoginTimes {
+                first_login: Some(100),
+                last_login: Some(300),
+                last_seen: Some(150),
+            }
+        );
+
+        // Data saved before the times were recorded
+        let mut session = LoginTimes::default();
+        session.join(300);
+        session.restore(LoginTimes::default());
+        assert_eq!(session.first_login, Some(300));
+    }
+}
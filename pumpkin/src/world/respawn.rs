@@ -0,0 +1,224 @@
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_world::{
+    block::{find_block_state, BlockId},
+    dimension::Dimension,
+};
+use serde::{Deserialize, Serialize};
+
+use super::location::Location;
+
+/// Where a Player respawns instead of the world spawn, set by using a bed or with `/spawnpoint`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct RespawnPoint {
+    pub dimension: Dimension,
+    pub position: (i32, i32, i32),
+    pub yaw: f32,
+    /// Points set with `/spawnpoint` don't need a bed or respawn anchor
+    pub forced: bool,
+}
+
+impl RespawnPoint {
+    /// Finds where a Player respawns at this point.
+    ///
+    /// Returns `None` if the bed or respawn anchor is missing or something blocks the Player,
+    /// `get_block` returns `None` for blocks which are not loaded
+    pub fn find_spawn(
+        &self,
+        dimension: Dimension,
+        get_block: impl Fn(WorldPosition) -> Option<BlockId>,
+    ) -> Option<Location> {
+        if self.dimension != dimension {
+            return None;
+        }
+        let (x, y, z) = self.position;
+        let block_at = |y| get_block(WorldPosition(Vector3::new(x, y, z)));
+        let feet_y = if self.forced {
+            y
+        } else {
+            if !is_respawn_block(block_at(y)?) {
+                return None;
+            }
+            // Players stand on top of their bed
+            y + 1
+        };
+        // We don't know which blocks are solid yet, so anything but air is an obstruction
+        let free = |y| block_at(y).is_some_and(|block| block.is_air());
+        if !free(feet_y) || !free(feet_y + 1) {
+            return None;
+        }
+        Some(Location {
+            dimension,
+            position: (x as f64 + 0.5, feet_y as f64, z as f64 + 0.5),
+            yaw: self.yaw,
+            pitch: 0.0,
+        })
+    }
+}
+
+/// Beds and charged respawn anchors
+pub fn is_respawn_block(block: BlockId) -> bool {
+    find_block_state(block).is_some_and(|(name, state)| {
+        name.ends_with("_bed")
+            || (name == "minecraft:respawn_anchor"
+                && state
+                    .properties
+                    .get("charges")
+                    .is_some_and(|charges| charges != "0"))
+    })
+}
+
+/// Where a Player respawns, their respawn point if it is still usable and the world spawn otherwise.
+///
+/// The bool is true if the Player had a respawn point which could not be used
+pub fn respawn_location(
+    point: Option<&RespawnPoint>,
+    world_spawn: Location,
+    get_block: impl Fn(WorldPosition) -> Option<BlockId>,
+) -> (Location, bool) {
+    let Some(point) = point else {
+        return (world_spawn, false);
+    };
+    match point.find_spawn(world_spawn.dimension, get_block) {
+        Some(location) => (location, false),
+        None => (world_spawn, true),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+    use pumpkin_world::{
+        block::{BlockId, BlockState},
+        dimension::Dimension,
+    };
+
+    use crate::world::location::Location;
+
+    use super::{respawn_location, RespawnPoint};
+
+    fn world_spawn() -> Location {
+        Location {
+            dimension: Dimension::OverWorld,
+            position: (10.0, 120.0, 10.0),
+            yaw: 10.0,
+            pitch: 10.0,
+        }
+    }
+
+    fn block(registry_id: &str, properties: Option<&[(&str, &str)]>) -> BlockId {
+        let properties: Option<HashMap<String, String>> = properties.map(|properties| {
+            properties
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        });
+        BlockState::new(registry_id, properties.as_ref())
+            .unwrap()
+            .into()
+    }
+
+    /// A loaded area of air with the given blocks in it
+    fn blocks(placed: &[((i32, i32, i32), BlockId)]) -> impl Fn(WorldPosition) -> Option<BlockId> {
+        let placed: HashMap<_, _> = placed.iter().copied().collect();
+        move |WorldPosition(position)| {
+            (position.y < 200).then(|| {
+                placed
+                    .get(&(position.x, position.y, position.z))
+                    .copied()
+                    .unwrap_or_default()
+            })
+        }
+    }
+
+    fn point(position: (i32, i32, i32), forced: bool) -> RespawnPoint {
+        RespawnPoint {
+            dimension: Dimension::OverWorld,
+            position,
+            yaw: 90.0,
+            forced,
+        }
+    }
+
+    #[test]
+    fn test_respawn_at_bed() {
+        let bed = block("minecraft:red_bed", None);
+        let (location, invalid) = respawn_location(
+            Some(&point((3, 64, -7), false)),
+            world_spawn(),
+            blocks(&[((3, 64, -7), bed)]),
+        );
+        assert!(!invalid);
+        assert_eq!(location.position, (3.5, 65.0, -6.5));
+        assert_eq!(location.yaw, 90.0);
+    }
+
+    #[test]
+    fn test_respawn_at_forced_point() {
+        let (location, invalid) =
+            respawn_location(Some(&point((0, 70, 0), true)), world_spawn(), blocks(&[]));
+        assert!(!invalid);
+        assert_eq!(location.position, (0.5, 70.0, 0.5));
+    }
+
+    #[test]
+    fn test_respawn_anchor_needs_charges() {
+        let empty = block("minecraft:respawn_anchor", Some(&[("charges", "0")]));
+        let charged = block("minecraft:respawn_anchor", Some(&[("charges", "2")]));
+        let point = point((0, 64, 0), false);
+        assert!(point
+            .find_spawn(Dimension::OverWorld, blocks(&[((0, 64, 0), empty)]))
+            .is_none());
+        assert!(point
+            .find_spawn(Dimension::OverWorld, blocks(&[((0, 64, 0), charged)]))
+            .is_some());
+    }
+
+    #[test]
+    fn test_fall_back_to_world_spawn() {
+        let bed = block("minecraft:red_bed", None);
+        let stone = block("minecraft:stone", None);
+
+        // No respawn point at all
+        assert_eq!(
+            respawn_location(None, world_spawn(), blocks(&[])),
+            (world_spawn(), false)
+        );
+        // The bed is gone
+        assert_eq!(
+            respawn_location(Some(&point((3, 64, -7), false)), world_spawn(), blocks(&[])),
+            (world_spawn(), true)
+        );
+        // The bed is obstructed
+        assert_eq!(
+            respawn_location(
+                Some(&point((3, 64, -7), false)),
+                world_spawn(),
+                blocks(&[((3, 64, -7), bed), ((3, 66, -7), stone)])
+            ),
+            (world_spawn(), true)
+        );
+        // A forced point inside of a wall
+        assert_eq!(
+            respawn_location(
+                Some(&point((0, 70, 0), true)),
+                world_spawn(),
+                blocks(&[((0, 70, 0), stone)])
+            ),
+            (world_spawn(), true)
+        );
+        // The chunk is not loaded
+        assert_eq!(
+            respawn_location(Some(&point((0, 250, 0), true)), world_spawn(), blocks(&[])),
+            (world_spawn(), true)
+        );
+        // Another dimension
+        let mut nether = point((0, 70, 0), true);
+        nether.dimension = Dimension::Nether;
+        assert_eq!(
+            respawn_location(Some(&nether), world_spawn(), blocks(&[])),
+            (world_spawn(), true)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+use pumpkin_world::block::{find_block_state, BlockId};
+
+/// Players have to sleep this many ticks before the night can be skipped
+pub const DEEP_SLEEP_TICKS: i32 = 100;
+
+/// How many Players have to sleep to skip the night, like vanilla's `playersSleepingPercentage`
+pub fn sleepers_needed(active_players: usize, percentage: u8) -> usize {
+    (active_players * percentage as usize).div_ceil(100).max(1)
+}
+
+/// Whether enough of the active Players (everyone but spectators) are sleeping to skip the night
+pub fn can_skip_night(sleeping: usize, active_players: usize, percentage: u8) -> bool {
+    percentage <= 100 && sleeping >= sleepers_needed(active_players, percentage)
+}
+
+/// Beds are two blocks, Players always sleep in the head part.
+///
+/// Returns `None` if the block is no bed
+pub fn bed_head(position: WorldPosition, block: BlockId) -> Option<WorldPosition> {
+    let (name, state) = find_block_state(block)?;
+    if !name.ends_with("_bed") {
+        return None;
+    }
+    if state.properties.get("part").map(String::as_str) != Some("foot") {
+        return Some(position);
+    }
+    let offset = match state.properties.get("facing")?.as_str() {
+        "north" => Vector3::new(0, 0, -1),
+        "south" => Vector3::new(0, 0, 1),
+        "west" => Vector3::new(-1, 0, 0),
+        "east" => Vector3::new(1, 0, 0),
+        _ => return None,
+    };
+    Some(WorldPosition(position.0 + offset))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use pumpkin_core::math::{position::WorldPosition, vector3::Vector3};
+    use pumpkin_world::block::{BlockId, BlockState};
+
+    use super::{bed_head, can_skip_night, sleepers_needed};
+
+    #[test]
+    fn test_sleepers_needed() {
+        assert_eq!(sleepers_needed(4, 100), 4);
+        assert_eq!(sleepers_needed(4, 50), 2);
+        assert_eq!(sleepers_needed(3, 50), 2);
+        // At least one Player always has to sleep
+        assert_eq!(sleepers_needed(4, 0), 1);
+        assert_eq!(sleepers_needed(0, 100), 1);
+    }
+
+    #[test]
+    fn test_percentage_threshold_skips_night() {
+        assert!(can_skip_night(1, 1, 100));
+        assert!(can_skip_night(2, 4, 50));
+        assert!(can_skip_night(1, 10, 0));
+        assert!(!can_skip_night(1, 4, 50));
+    }
+
+    #[test]
+    fn test_single_sleeper_does_not_skip_night() {
+        assert!(!can_skip_night(1, 2, 100));
+        assert!(!can_skip_night(1, 3, 100));
+        // Above 100% the night is never skipped
+        assert!(!can_skip_night(1, 1, 101));
+    }
+
+    fn bed(part: &str, facing: &str) -> BlockId {
+        let state = BlockState::new(
+            "minecraft:red_bed",
+            Some(&HashMap::from([
+                ("part".to_string(), part.to_string()),
+                ("facing".to_string(), facing.to_string()),
+                ("occupied".to_string(), "false".to_string()),
+            ])),
+        )
+        .unwrap();
+        state.into()
+    }
+
+    #[test]
+    fn test_bed_head() {
+        let position = WorldPosition(Vector3::new(3, 64, -7));
+        assert_eq!(bed_head(position, bed("head", "north")), Some(position));
+        assert_eq!(
+            bed_head(position, bed("foot", "north")),
+            Some(WorldPosition(Vector3::new(3, 64, -8)))
+        );
+        assert_eq!(
+            bed_head(position, bed("foot", "east")),
+            Some(WorldPosition(Vector3::new(4, 64, -7)))
+        );
+        assert_eq!(bed_head(position, BlockId::default()), None);
+    }
+}
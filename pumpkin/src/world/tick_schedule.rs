@@ -0,0 +1,87 @@
+use std::time::{Duration, Instant};
+
+/// A World which fell this many ticks behind skips them instead of catching up
+pub const MAX_CATCH_UP_TICKS: u32 = 40;
+
+/// When a World ticks, every World can tick at its own rate
+pub struct TickSchedule {
+    interval: Duration,
+    /// The time of the next tick, set once the schedule starts
+    next: Option<Instant>,
+}
+
+impl TickSchedule {
+    pub fn new(tick_rate: f32) -> Self {
+        Self {
+            interval: interval(tick_rate),
+            next: None,
+        }
+    }
+
+    pub fn tick_rate(&self) -> f32 {
+        (1.0 / self.interval.as_secs_f64()) as f32
+    }
+
+    /// Changes the rate, the next tick still happens at the old rate
+    pub fn set_tick_rate(&mut self, tick_rate: f32) {
+        self.interval = interval(tick_rate);
+    }
+
+    /// When the World ticks next, None if the schedule did not start yet
+    pub const fn next_tick(&self) -> Option<Instant> {
+        self.next
+    }
+
+    /// How many ticks the World has to run at `now`, the first call starts the schedule with a tick
+    pub fn due_ticks(&mut self, now: Instant) -> u32 {
+        let next = *self.next.get_or_insert(now);
+        if now < next {
+            return 0;
+        }
+        let behind = (now - next).as_nanos() / self.interval.as_nanos();
+        let ticks = u32::try_from(behind).unwrap_or(u32::MAX).saturating_add(1);
+        if ticks > MAX_CATCH_UP_TICKS {
+            log::warn!("A world is {} ticks behind, skipping them", ticks - 1);
+            self.next = Some(now + self.interval);
+            return 1;
+        }
+        self.next = Some(next + self.interval * ticks);
+        ticks
+    }
+}
+
+fn interval(tick_rate: f32) -> Duration {
+    Duration::from_secs_f64(1.0 / f64::from(tick_rate))
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::{TickSchedule, MAX_CATCH_UP_TICKS};
+
+    #[test]
+    fn test_due_ticks() {
+        let start = Instant::now();
+        let mut schedule = TickSchedule::new(20.0);
+        assert_eq!(schedule.due_ticks(start), 1);
+        assert_eq!(schedule.due_ticks(start + Duration::from_millis(20)), 0);
+        assert_eq!(schedule.due_ticks(start + Duration::from_millis(50)), 1);
+        // A late check runs every missed tick
+        assert_eq!(schedule.due_ticks(start + Duration::from_millis(210)), 3);
+        assert_eq!(
+            schedule.next_tick(),
+            Some(start + Duration::from_millis(250))
+        );
+    }
+
+    #[test]
+    fn test_skips_when_far_behind() {
+        let start = Instant::now();
+        let mut schedule = TickSchedule::new(20.0);
+        schedule.due_ticks(start);
+        let late = start + Duration::from_millis(50) * (MAX_CATCH_UP_TICKS + 5);
+        assert_eq!(schedule.due_ticks(late), 1);
+        assert_eq!(schedule.next_tick(), Some(late + Duration::from_millis(50)));
+    }
+}
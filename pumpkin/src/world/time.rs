@@ -0,0 +1,91 @@
+use pumpkin_protocol::client::play::CUpdateTime;
+
+/// The length of a Minecraft day in ticks
+pub const TICKS_PER_DAY: i64 = 24_000;
+/// How often the time is synced with the clients, in ticks
+pub const TIME_SYNC_INTERVAL: i64 = 20;
+
+/// The time of a World, like vanilla's game time and day time
+#[derive(Default)]
+pub struct WorldTime {
+    /// Ticks since the World was created, this never stops
+    pub world_age: i64,
+    /// Ticks since the first morning, only advances with `doDaylightCycle`
+    pub time_of_day: i64,
+}
+
+impl WorldTime {
+    /// Advances the time by one tick
+    pub fn tick(&mut self, do_daylight_cycle: bool) {
+        self.world_age += 1;
+        if do_daylight_cycle {
+            self.time_of_day += 1;
+        }
+    }
+
+    /// Players can only sleep at night or during a thunderstorm
+    pub fn can_sleep(&self, thundering: bool) -> bool {
+        let time = self.time_of_day.rem_euclid(TICKS_PER_DAY);
+        if thundering {
+            (12_010..23_992).contains(&time)
+        } else {
+            (12_542..23_460).contains(&time)
+        }
+    }
+
+    /// Jumps to the next morning, like vanilla after enough Players slept
+    pub fn skip_to_morning(&mut self) {
+        let time = self.time_of_day + TICKS_PER_DAY;
+        self.time_of_day = time - time.rem_euclid(TICKS_PER_DAY);
+    }
+
+    /// The packet which tells the client the time, the client keeps the time running by itself
+    pub fn packet(&self, do_daylight_cycle: bool) -> CUpdateTime {
+        let time_of_day = if do_daylight_cycle {
+            self.time_of_day
+        } else {
+            // The client stops the cycle for negative times, -0 would still be 0
+            (-self.time_of_day).min(-1)
+        };
+        CUpdateTime::new(self.world_age, time_of_day)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{WorldTime, TICKS_PER_DAY};
+
+    #[test]
+    fn test_tick() {
+        let mut time = WorldTime::default();
+        time.tick(true);
+        time.tick(false);
+        assert_eq!(time.world_age, 2);
+        assert_eq!(time.time_of_day, 1);
+    }
+
+    #[test]
+    fn test_can_sleep() {
+        let mut time = WorldTime::default();
+        assert!(!time.can_sleep(false));
+        time.time_of_day = 12_100;
+        assert!(!time.can_sleep(false));
+        assert!(time.can_sleep(true));
+        time.time_of_day = 18_000 + 5 * TICKS_PER_DAY;
+        assert!(time.can_sleep(false));
+        time.time_of_day = 23_500;
+        assert!(!time.can_sleep(false));
+    }
+
+    #[test]
+    fn test_skip_to_morning() {
+        let mut time = WorldTime {
+            world_age: 50_000,
+            time_of_day: 2 * TICKS_PER_DAY + 18_000,
+        };
+        time.skip_to_morning();
+        assert_eq!(time.time_of_day, 3 * TICKS_PER_DAY);
+        // The world keeps its age
+        assert_eq!(time.world_age, 50_000);
+    }
+}
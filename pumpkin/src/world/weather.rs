@@ -0,0 +1,179 @@
+use std::ops::RangeInclusive;
+
+use pumpkin_protocol::client::play::GameEvent;
+use rand::Rng;
+
+/// How long it stays clear between rain, in ticks
+pub const CLEAR_DURATION: RangeInclusive<i32> = 12_000..=180_000;
+/// How long it rains, in ticks
+pub const RAIN_DURATION: RangeInclusive<i32> = 12_000..=24_000;
+/// How long it thunders, in ticks
+pub const THUNDER_DURATION: RangeInclusive<i32> = 3_600..=15_600;
+
+/// The Weather of a World, this follows vanilla's weather cycle.
+///
+/// Rain and Thunder each have their own timer, when it runs out the state flips.
+/// The rain and thunder levels fade in and out slowly so the client can smoothly change the sky.
+#[derive(Default)]
+pub struct Weather {
+    pub raining: bool,
+    pub thundering: bool,
+    /// Ticks until the weather cycle continues, Set by `/weather clear`
+    pub clear_weather_time: i32,
+    /// Ticks until `raining` flips
+    pub rain_time: i32,
+    /// Ticks until `thundering` flips
+    pub thunder_time: i32,
+    /// From 0.0 to 1.0
+    pub rain_level: f32,
+    /// From 0.0 to 1.0
+    pub thunder_level: f32,
+}
+
+impl Weather {
+    /// Used by `/weather`
+    pub fn set_weather(
+        &mut self,
+        clear_weather_time: i32,
+        weather_time: i32,
+        raining: bool,
+        thundering: bool,
+    ) {
+        self.clear_weather_time = clear_weather_time;
+        self.rain_time = weather_time;
+        self.thunder_time = weather_time;
+        self.raining = raining;
+        self.thundering = thundering;
+    }
+
+    /// The client only renders rain above this level
+    pub fn is_raining(&self) -> bool {
+        self.rain_level > 0.2
+    }
+
+    /// Advances the weather by one tick, returns the game events which should be broadcast
+    pub fn tick(&mut self, do_weather_cycle: bool) -> Vec<(GameEvent, f32)> {
+        if do_weather_cycle {
+            self.advance_cycle();
+        }
+        let was_raining = self.is_raining();
+
+        let old_thunder_level = self.thunder_level;
+        self.thunder_level = Self::fade(self.thunder_level, self.thundering);
+        let old_rain_level = self.rain_level;
+        self.rain_level = Self::fade(self.rain_level, self.raining);
+
+        let mut events = Vec::new();
+        if old_rain_level != self.rain_level {
+            events.push((GameEvent::RainLevelChange, self.rain_level));
+        }
+        if old_thunder_level != self.thunder_level {
+            events.push((GameEvent::ThunderLevelChange, self.thunder_level));
+        }
+        if was_raining != self.is_raining() {
+            if was_raining {
+                events.push((GameEvent::EndRaining, 0.0));
+            } else {
+                events.push((GameEvent::BeginRaining, 0.0));
+            }
+        }
+        events
+    }
+
+    /// The game events a joining player needs to see the current weather
+    pub fn join_events(&self) -> Vec<(GameEvent, f32)> {
+        if self.is_raining() {
+            vec![
+                (GameEvent::BeginRaining, 0.0),
+                (GameEvent::RainLevelChange, self.rain_level),
+                (GameEvent::ThunderLevelChange, self.thunder_level),
+            ]
+        } else {
+            Vec::new()
+        }
+    }
+
+    fn advance_cycle(&mut self) {
+        if self.clear_weather_time > 0 {
+            self.clear_weather_time -= 1;
+            self.thunder_time = if self.thundering { 0 } else { 1 };
+            self.rain_time = if self.raining { 0 } else { 1 };
+            self.thundering = false;
+            self.raining = false;
+            return;
+        }
+        let mut rng = rand::thread_rng();
+        if self.thunder_time > 0 {
+            self.thunder_time -= 1;
+            if self.thunder_time == 0 {
+                self.thundering = !self.thundering;
+            }
+        } else if self.thundering {
+            self.thunder_time = rng.gen_range(THUNDER_DURATION);
+        } else {
+            self.thunder_time = rng.gen_range(CLEAR_DURATION);
+        }
+
+        if self.rain_time > 0 {
+            self.rain_time -= 1;
+            if self.rain_time == 0 {
+                self.raining = !self.raining;
+            }
+        } else if self.raining {
+            self.rain_time = rng.gen_range(RAIN_DURATION);
+        } else {
+            self.rain_time = rng.gen_range(CLEAR_DURATION);
+        }
+    }
+
+    fn fade(level: f32, active: bool) -> f32 {
+        let level = if active { level + 0.01 } else { level - 0.01 };
+        level.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pumpkin_protocol::client::play::GameEvent;
+
+    use super::Weather;
+
+    #[test]
+    fn test_set_weather() {
+        let mut weather = Weather::default();
+        weather.set_weather(0, 6000, true, true);
+        assert!(weather.raining);
+        assert!(weather.thundering);
+        assert_eq!(weather.rain_time, 6000);
+
+        weather.set_weather(6000, 0, false, false);
+        assert!(!weather.raining);
+        assert_eq!(weather.clear_weather_time, 6000);
+    }
+
+    #[test]
+    fn test_rain_events() {
+        let mut weather = Weather::default();
+        weather.set_weather(0, 6000, true, false);
+
+        let events = weather.tick(false);
+        assert_eq!(events, vec![(GameEvent::RainLevelChange, 0.01)]);
+
+        let mut events = Vec::new();
+        for _ in 0..100 {
+            events.extend(weather.tick(false));
+        }
+        assert!(events.contains(&(GameEvent::BeginRaining, 0.0)));
+        assert_eq!(weather.rain_level, 1.0);
+        // The level does not change anymore once it is fully raining
+        assert!(weather.tick(false).is_empty());
+
+        weather.set_weather(6000, 0, false, false);
+        let mut events = Vec::new();
+        for _ in 0..110 {
+            events.extend(weather.tick(true));
+        }
+        assert!(events.contains(&(GameEvent::EndRaining, 0.0)));
+        assert_eq!(weather.rain_level, 0.0);
+    }
+}